@@ -160,4 +160,12 @@ export interface ApiSource {
   city: string;
   state: string;
   portalUrl: string | null;
+  license?: ApiSourceLicense | null;
+}
+
+export interface ApiSourceLicense {
+  type: string;
+  tosUrl: string | null;
+  attributionRequired: boolean;
+  attributionText: string | null;
 }
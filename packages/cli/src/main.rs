@@ -57,6 +57,7 @@ impl Tool {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let multi = crime_map_cli_utils::init_logger();
+    crime_map_generate::init_thread_pool(None)?;
 
     println!("Crime Map Toolchain");
     println!();
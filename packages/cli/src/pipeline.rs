@@ -10,11 +10,8 @@
 
 use std::time::Instant;
 
-use crime_map_cli_utils::{IndicatifProgress, MultiProgress};
-use crime_map_generate::{
-    GenerateArgs, OUTPUT_ANALYTICS_DB, OUTPUT_BOUNDARIES_DB, OUTPUT_BOUNDARIES_PMTILES,
-    OUTPUT_COUNT_DB, OUTPUT_H3_DB, OUTPUT_INCIDENTS_DB, OUTPUT_INCIDENTS_PMTILES, OUTPUT_METADATA,
-};
+use crime_map_cli_utils::{IndicatifProgress, MultiProgress, throttled};
+use crime_map_generate::{GenerateArgs, default_outputs};
 use crime_map_ingest::{EnrichArgs, GeocodeArgs, IngestBoundariesArgs, SyncArgs};
 use dialoguer::{Confirm, Input, MultiSelect, Select};
 
@@ -281,16 +278,17 @@ pub async fn run(multi: &MultiProgress) -> Result<(), Box<dyn std::error::Error>
     // --- Sync ---
     if has_sync {
         current_step += 1;
-        let source_bar = IndicatifProgress::steps_bar(
+        let source_bar = throttled(IndicatifProgress::steps_bar(
             multi,
             &format!("[{current_step}/{total_steps}] Sources"),
             source_ids.len() as u64,
-        );
+        ));
 
         let args = SyncArgs {
             source_ids: source_ids.clone(),
             limit: sync_limit,
             force: sync_force,
+            keep_raw: None,
         };
 
         let result = crime_map_ingest::run_sync(&args, Some(&source_bar)).await;
@@ -314,16 +312,24 @@ pub async fn run(multi: &MultiProgress) -> Result<(), Box<dyn std::error::Error>
     // --- Geocode ---
     if has_geocode {
         current_step += 1;
-        let geocode_bar = IndicatifProgress::batch_bar(
+        let geocode_bar = throttled(IndicatifProgress::batch_bar(
             multi,
             &format!("[{current_step}/{total_steps}] Geocoding"),
-        );
+        ));
 
         let args = GeocodeArgs {
             source_ids: source_ids.clone(),
             batch_size: geocode_batch_size,
             limit: None,
-            nominatim_only: geocode_nominatim_only,
+            since: None,
+            provider_order: if geocode_nominatim_only {
+                vec!["nominatim".to_string()]
+            } else {
+                Vec::new()
+            },
+            match_validation: crime_map_ingest::MatchValidation::Off,
+            dedup_addresses: false,
+            cache_flush_every: crime_map_ingest::DEFAULT_CACHE_FLUSH_EVERY,
         };
 
         match crime_map_ingest::run_geocode(&args, Some(geocode_bar.clone())).await {
@@ -346,10 +352,10 @@ pub async fn run(multi: &MultiProgress) -> Result<(), Box<dyn std::error::Error>
     // --- Enrich ---
     if has_enrich {
         current_step += 1;
-        let enrich_bar = IndicatifProgress::batch_bar(
+        let enrich_bar = throttled(IndicatifProgress::batch_bar(
             multi,
             &format!("[{current_step}/{total_steps}] Enriching"),
-        );
+        ));
 
         let args = EnrichArgs {
             source_ids: source_ids.clone(),
@@ -435,29 +441,18 @@ pub async fn run(multi: &MultiProgress) -> Result<(), Box<dyn std::error::Error>
         log::info!("[{current_step}/{total_steps}] Generating tiles & databases...");
 
         let args = GenerateArgs {
-            limit: None,
             sources: Some(source_ids.join(",")),
-            states: None,
-            keep_intermediate: false,
             force: generate_force,
+            ..GenerateArgs::default()
         };
 
         let dir = crime_map_generate::output_dir();
         std::fs::create_dir_all(&dir)?;
 
-        let all_outputs: &[&str] = &[
-            OUTPUT_INCIDENTS_PMTILES,
-            OUTPUT_INCIDENTS_DB,
-            OUTPUT_COUNT_DB,
-            OUTPUT_H3_DB,
-            OUTPUT_METADATA,
-            OUTPUT_BOUNDARIES_PMTILES,
-            OUTPUT_BOUNDARIES_DB,
-            OUTPUT_ANALYTICS_DB,
-        ];
+        let all_outputs = default_outputs();
 
         let resolved = crime_map_generate::resolve_source_ids(&args)?;
-        match crime_map_generate::run_with_cache(&args, &resolved, &dir, all_outputs, None).await {
+        match crime_map_generate::run_with_cache(&args, &resolved, &dir, &all_outputs, None).await {
             Ok(()) => {
                 log::info!("[{current_step}/{total_steps}] Generation complete");
             }
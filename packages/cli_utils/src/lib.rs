@@ -9,16 +9,28 @@
 //! `log::info!` and friends are suspended while progress bars redraw.
 //!
 //! Any binary that calls [`init_logger()`] at startup gets full progress bar
-//! support for free.
+//! support for free, and JSON log lines when [`LOG_FORMAT_ENV`] is `json`. Wrap bars fed from hot loops in [`throttled`] so they
+//! redraw at a fixed rate instead of on every update.
 
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crime_map_source::progress::ProgressCallback;
+use crime_map_source::progress::{ProgressCallback, ThrottledProgress};
 use indicatif::{ProgressBar, ProgressStyle};
 
 pub use indicatif::MultiProgress;
 
+/// Minimum time between position updates of a [`throttled`] bar, matching
+/// the bars' steady tick.
+pub const RENDER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wraps `progress` in a [`ThrottledProgress`] that forwards position
+/// updates at most once per [`RENDER_INTERVAL`].
+#[must_use]
+pub fn throttled(progress: Arc<dyn ProgressCallback>) -> Arc<dyn ProgressCallback> {
+    ThrottledProgress::wrap(progress, RENDER_INTERVAL)
+}
+
 /// Formats a duration in seconds into a compact human-readable string.
 ///
 /// Examples: `"45s"`, `"12m30s"`, `"2h15m"`, `"1d3h"`.
@@ -58,6 +70,8 @@ fn format_eta(secs: u64) -> String {
 /// instead of `indicatif`'s built-in exponential weighted moving average,
 /// which produces wildly unstable estimates for slow paginated API fetches.
 pub struct IndicatifProgress {
+    /// The [`MultiProgress`] this bar belongs to, used to insert child bars.
+    multi: MultiProgress,
     bar: ProgressBar,
     /// Style to switch to once `set_total()` provides a known length.
     bar_style: ProgressStyle,
@@ -89,6 +103,7 @@ impl IndicatifProgress {
         .progress_chars("##-");
 
         Arc::new(Self {
+            multi: multi.clone(),
             bar,
             bar_style,
             start: Mutex::new(None),
@@ -117,6 +132,7 @@ impl IndicatifProgress {
         let bar_style = bar.style();
 
         Arc::new(Self {
+            multi: multi.clone(),
             bar,
             bar_style,
             start: Mutex::new(Some(Instant::now())),
@@ -144,6 +160,7 @@ impl IndicatifProgress {
         .progress_chars("##-");
 
         Arc::new(Self {
+            multi: multi.clone(),
             bar,
             bar_style,
             start: Mutex::new(None),
@@ -211,21 +228,135 @@ impl ProgressCallback for IndicatifProgress {
     fn finish_and_clear(&self) {
         self.bar.finish_and_clear();
     }
+
+    /// Inserts an indented spinner directly below this bar that becomes a
+    /// full bar on `set_total()`, like [`IndicatifProgress::batch_bar`].
+    fn child(&self, label: &str) -> Arc<dyn ProgressCallback> {
+        let bar = self
+            .multi
+            .insert_after(&self.bar, ProgressBar::new_spinner());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        bar.set_style(
+            ProgressStyle::with_template("    {spinner:.yellow} {msg}")
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        bar.set_message(label.to_string());
+
+        let bar_style = ProgressStyle::with_template(
+            "    {msg} {wide_bar:.yellow/dim} {pos}/{len} {percent}% [{elapsed_precise}]",
+        )
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .progress_chars("##-");
+
+        Arc::new(Self {
+            multi: self.multi.clone(),
+            bar,
+            bar_style,
+            start: Mutex::new(None),
+            base_message: Mutex::new(label.to_string()),
+        })
+    }
+}
+
+/// Environment variable selecting the log line format: `json` writes one
+/// JSON object per record, anything else the default human-readable lines.
+pub const LOG_FORMAT_ENV: &str = "CRIME_MAP_LOG_FORMAT";
+
+/// Logger that either forwards to the pretty-env-logger `inner` or, in
+/// JSON mode, writes each record as a JSON line on stderr. `inner`'s
+/// `RUST_LOG` filter applies either way.
+struct CliLogger<L> {
+    inner: L,
+    json: bool,
+}
+
+impl<L: log::Log> log::Log for CliLogger<L> {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if !self.json {
+            self.inner.log(record);
+            return;
+        }
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // Structured fields first, so they can't shadow the fixed keys. If
+        // visiting them fails partway, emit the line without any fields.
+        let mut line = serde_json::Map::new();
+        if record
+            .key_values()
+            .visit(&mut JsonFields(&mut line))
+            .is_err()
+        {
+            line.clear();
+        }
+        let ts_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+        line.insert("ts_ms".to_string(), ts_ms.into());
+        line.insert("level".to_string(), record.level().as_str().into());
+        line.insert("target".to_string(), record.target().into());
+        line.insert("message".to_string(), record.args().to_string().into());
+        eprintln!("{}", serde_json::Value::Object(line));
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Collects a record's key-value fields into a JSON object, keeping
+/// numbers and booleans typed and formatting everything else as text.
+struct JsonFields<'a>(&'a mut serde_json::Map<String, serde_json::Value>);
+
+impl<'kvs> log::kv::VisitSource<'kvs> for JsonFields<'_> {
+    fn visit_pair(
+        &mut self,
+        key: log::kv::Key<'kvs>,
+        value: log::kv::Value<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        let json = value
+            .to_u64()
+            .map(serde_json::Value::from)
+            .or_else(|| value.to_i64().map(serde_json::Value::from))
+            .or_else(|| {
+                value
+                    .to_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(serde_json::Value::Number)
+            })
+            .or_else(|| value.to_bool().map(serde_json::Value::from))
+            .unwrap_or_else(|| value.to_string().into());
+        self.0.insert(key.as_str().to_string(), json);
+        Ok(())
+    }
 }
 
 /// Initializes the global logger wrapped in `indicatif-log-bridge` so that
 /// `log::info!` and friends are suspended while progress bars redraw.
 ///
+/// With [`LOG_FORMAT_ENV`] set to `json`, records are written as JSON
+/// lines carrying their structured fields (e.g. `source_id`, `records`,
+/// `elapsed_ms`) for log aggregation.
+///
 /// Returns the [`MultiProgress`] that all progress bars must be added to.
 #[must_use]
 pub fn init_logger() -> MultiProgress {
     let multi = MultiProgress::new();
 
     // Build the pretty-env-logger logger manually so we can wrap it.
-    let logger = pretty_env_logger::formatted_builder()
+    let inner = pretty_env_logger::formatted_builder()
         .parse_env("RUST_LOG")
         .build();
-    let level = logger.filter();
+    let level = inner.filter();
+    let logger = CliLogger {
+        inner,
+        json: std::env::var(LOG_FORMAT_ENV).is_ok_and(|v| v.eq_ignore_ascii_case("json")),
+    };
 
     indicatif_log_bridge::LogWrapper::new(multi.clone(), logger)
         .try_init()
@@ -43,6 +43,12 @@ pub enum CrimeSeverity {
 }
 
 impl CrimeSeverity {
+    /// Lowest value on the canonical scale.
+    pub const MIN: u8 = 1;
+
+    /// Highest value on the canonical scale.
+    pub const MAX: u8 = 5;
+
     /// Returns the numeric value of this severity level.
     #[must_use]
     pub const fn value(self) -> u8 {
@@ -64,6 +70,30 @@ impl CrimeSeverity {
             _ => Err(InvalidSeverityError { value }),
         }
     }
+
+    /// Returns a human-readable label for this severity level.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Minimal => "Minimal",
+            Self::Low => "Low",
+            Self::Moderate => "Moderate",
+            Self::High => "High",
+            Self::Critical => "Critical",
+        }
+    }
+
+    /// Returns all levels, lowest first.
+    #[must_use]
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self::Minimal,
+            Self::Low,
+            Self::Moderate,
+            Self::High,
+            Self::Critical,
+        ]
+    }
 }
 
 /// Error returned when attempting to create a [`CrimeSeverity`] from an invalid
@@ -386,5 +416,11 @@ mod tests {
         }
         assert!(CrimeSeverity::from_value(0).is_err());
         assert!(CrimeSeverity::from_value(6).is_err());
+
+        let values: Vec<u8> = CrimeSeverity::all().iter().map(|s| s.value()).collect();
+        assert_eq!(
+            values,
+            (CrimeSeverity::MIN..=CrimeSeverity::MAX).collect::<Vec<_>>()
+        );
     }
 }
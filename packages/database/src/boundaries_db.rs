@@ -3,11 +3,17 @@
 //! Stores census tract, place, county, state, and neighborhood boundaries
 //! with their `GeoJSON` geometry as plain TEXT (no `PostGIS` spatial types).
 //! The boundaries `DuckDB` lives at `data/shared/boundaries.duckdb`.
+//!
+//! [`search`] queries the `boundaries.db` `SQLite` name index that
+//! generation builds from it.
 
 use std::path::Path;
 
 use duckdb::Connection;
 
+use moosicbox_json_utils::database::ToValue as _;
+use switchy_database::{Database, DatabaseValue};
+
 use crate::DbError;
 
 /// Opens (or creates) the boundaries `DuckDB` and ensures schema exists.
@@ -114,6 +120,48 @@ fn create_schema(conn: &Connection) -> Result<(), DbError> {
     Ok(())
 }
 
+/// Checks that `conn` is an intact boundaries database, returning the
+/// total row count across its tables.
+///
+/// Verifies every boundary table exists with its columns and that every
+/// row reads back, like [`crate::geocode_cache::verify`]. Run it on a
+/// database opened read-only, since [`open`] creates missing tables.
+///
+/// # Errors
+///
+/// Returns [`DbError::Integrity`] if a table or column is missing or the
+/// data can't be read.
+pub fn verify(conn: &Connection) -> Result<u64, DbError> {
+    const AREA: &[&str] = &[
+        "geoid",
+        "name",
+        "state_fips",
+        "boundary_geojson",
+        "land_area_sq_mi",
+        "population",
+        "centroid_lon",
+        "centroid_lat",
+    ];
+    let counts = crate::verify_tables(
+        conn,
+        &[
+            ("census_tracts", AREA),
+            ("census_places", AREA),
+            ("census_counties", AREA),
+            (
+                "census_states",
+                &["fips", "name", "abbr", "boundary_geojson", "population"],
+            ),
+            (
+                "neighborhoods",
+                &["id", "source_id", "name", "boundary_geojson"],
+            ),
+            ("tract_neighborhoods", &["geoid", "neighborhood_id"]),
+        ],
+    )?;
+    Ok(counts.iter().sum())
+}
+
 /// Tables in the boundaries schema that support upsert via `INSERT OR
 /// REPLACE`.
 const MERGE_TABLES: &[&str] = &[
@@ -192,3 +240,229 @@ pub fn merge_from(target: &Connection, source_path: &Path) -> Result<u64, DbErro
 
     Ok(total)
 }
+
+// ── Name search over the generated boundaries.db ─────────────────────────
+
+/// A boundary matched by [`search`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundaryMatch {
+    /// Boundary type (`state`, `county`, `place`, `tract`, or
+    /// `neighborhood`).
+    pub boundary_type: String,
+    /// Boundary GEOID or synthetic ID.
+    pub geoid: String,
+    /// Display name.
+    pub name: String,
+    /// Full name including state.
+    pub full_name: Option<String>,
+    /// State abbreviation.
+    pub state_abbr: Option<String>,
+    /// Population (if available).
+    pub population: Option<i64>,
+    /// `true` if `name` starts with the query, `false` if the query only
+    /// appears somewhere inside it.
+    pub exact_prefix: bool,
+}
+
+/// Searches boundary names in the generated `boundaries.db` `SQLite`
+/// database for `query`, case-insensitively for ASCII.
+///
+/// Names starting with `query` come first, found through the
+/// `idx_boundaries_name` index on `(type, name COLLATE NOCASE)`; if fewer
+/// than `limit` of those exist, names merely containing `query` fill the
+/// rest. Both groups are ordered by name. An empty `types` matches every
+/// boundary type, but only a `types` filter lets the prefix search use
+/// the index.
+///
+/// # Errors
+///
+/// Returns [`DbError::Sqlite`] if a query fails.
+pub async fn search(
+    conn: &dyn Database,
+    query: &str,
+    types: &[&str],
+    limit: u32,
+) -> Result<Vec<BoundaryMatch>, DbError> {
+    let query = query.trim();
+    if query.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    // Every name with the prefix sorts in [query, query + U+10FFFF) under
+    // NOCASE, which SQLite answers with a range scan of the index.
+    let upper = format!("{query}\u{10FFFF}");
+    let prefix_range = "name >= $1 COLLATE NOCASE AND name < $2 COLLATE NOCASE";
+
+    let mut params = vec![
+        DatabaseValue::String(query.to_string()),
+        DatabaseValue::String(upper),
+    ];
+    let mut conditions = Vec::new();
+    if !types.is_empty() {
+        let placeholders: Vec<String> = (0..types.len())
+            .map(|i| format!("${}", params.len() + i + 1))
+            .collect();
+        conditions.push(format!("type IN ({})", placeholders.join(", ")));
+        params.extend(
+            types
+                .iter()
+                .map(|t| DatabaseValue::String((*t).to_string())),
+        );
+    }
+
+    let mut prefix_conditions = conditions.clone();
+    prefix_conditions.push(prefix_range.to_string());
+    let mut prefix_params = params.clone();
+    prefix_params.push(DatabaseValue::UInt32(limit));
+    let mut matches = query_matches(conn, &prefix_conditions, &prefix_params, true).await?;
+
+    let remaining = limit - u32::try_from(matches.len()).unwrap_or(limit);
+    if remaining > 0 {
+        let pattern = format!(
+            "%{}%",
+            query
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        conditions.push(format!("name LIKE ${} ESCAPE '\\'", params.len() + 1));
+        conditions.push(format!("NOT ({prefix_range})"));
+        params.push(DatabaseValue::String(pattern));
+        params.push(DatabaseValue::UInt32(remaining));
+        matches.extend(query_matches(conn, &conditions, &params, false).await?);
+    }
+
+    Ok(matches)
+}
+
+/// Runs one [`search`] query. The last of `params` is the `LIMIT`.
+async fn query_matches(
+    conn: &dyn Database,
+    conditions: &[String],
+    params: &[DatabaseValue],
+    exact_prefix: bool,
+) -> Result<Vec<BoundaryMatch>, DbError> {
+    let sql = format!(
+        "SELECT type, geoid, name, full_name, state_abbr, population
+         FROM boundaries
+         WHERE {}
+         ORDER BY name COLLATE NOCASE
+         LIMIT ${}",
+        conditions.join(" AND "),
+        params.len()
+    );
+
+    let rows = conn
+        .query_raw_params(&sql, params)
+        .await
+        .map_err(|e| DbError::Sqlite(format!("Boundary search failed: {e}")))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let population: Option<i32> = row.to_value("population").unwrap_or(None);
+            BoundaryMatch {
+                boundary_type: row.to_value("type").unwrap_or_default(),
+                geoid: row.to_value("geoid").unwrap_or_default(),
+                name: row.to_value("name").unwrap_or_default(),
+                full_name: row.to_value("full_name").unwrap_or(None),
+                state_abbr: row.to_value("state_abbr").unwrap_or(None),
+                population: population.map(i64::from),
+                exact_prefix,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `boundaries.db` laid out like the one generation writes, in a
+    /// fresh file named after `test`.
+    async fn fixture(test: &str) -> Box<dyn Database> {
+        let path = std::env::temp_dir().join(format!(
+            "crime_map_boundaries_{test}_{}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let db = switchy_database_connection::init_sqlite_rusqlite(Some(&path)).unwrap();
+        db.exec_raw(
+            "CREATE TABLE boundaries (
+                type TEXT NOT NULL,
+                geoid TEXT NOT NULL,
+                name TEXT NOT NULL,
+                full_name TEXT,
+                state_abbr TEXT,
+                population INTEGER,
+                PRIMARY KEY (type, geoid)
+            )",
+        )
+        .await
+        .unwrap();
+        db.exec_raw("CREATE INDEX idx_boundaries_name ON boundaries(type, name COLLATE NOCASE)")
+            .await
+            .unwrap();
+        db.exec_raw(
+            "INSERT INTO boundaries VALUES
+                ('state', '17', 'Illinois', 'Illinois', 'IL', 12800000),
+                ('county', '17031', 'Cook', 'Cook County, IL', 'IL', 5200000),
+                ('place', '1714000', 'Chicago', 'Chicago, IL', 'IL', 2700000),
+                ('place', '1714351', 'Chicago Heights', 'Chicago Heights, IL', 'IL', 27000),
+                ('place', '1703012', 'East Chicago', 'East Chicago, IN', 'IN', 26000),
+                ('place', '1750000', 'North_Chicago', NULL, NULL, NULL)",
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    fn names(matches: &[BoundaryMatch]) -> Vec<(&str, bool)> {
+        matches
+            .iter()
+            .map(|m| (m.name.as_str(), m.exact_prefix))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn prefix_matches_rank_above_substring_matches() {
+        let db = fixture("ranking").await;
+
+        let matches = search(db.as_ref(), "chicago", &["place"], 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            names(&matches),
+            [
+                ("Chicago", true),
+                ("Chicago Heights", true),
+                ("East Chicago", false),
+                ("North_Chicago", false),
+            ]
+        );
+        assert_eq!(matches[0].population, Some(2_700_000));
+        assert_eq!(matches[3].full_name, None);
+
+        // The limit is filled by prefix matches first.
+        let matches = search(db.as_ref(), "CHI", &[], 2).await.unwrap();
+        assert_eq!(
+            names(&matches),
+            [("Chicago", true), ("Chicago Heights", true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn filters_types_and_treats_wildcards_literally() {
+        let db = fixture("filters").await;
+
+        let matches = search(db.as_ref(), "c", &["county", "state"], 10)
+            .await
+            .unwrap();
+        assert_eq!(names(&matches), [("Cook", true)]);
+
+        let matches = search(db.as_ref(), "h_c", &[], 10).await.unwrap();
+        assert_eq!(names(&matches), [("North_Chicago", false)]);
+
+        assert!(search(db.as_ref(), "  ", &[], 10).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,491 @@
+//! Query helpers for the generated count `DuckDB` (`counts.duckdb`).
+//!
+//! The `count_summary` table pre-aggregates incidents into spatial cells
+//! keyed by `cell_lng`/`cell_lat` = `FLOOR(coord * precision)`. Both the
+//! generator (via [`cell_sql`]) and query consumers (via
+//! [`bbox_to_cell_range`]) derive cells from here so the two can't drift.
+//!
+//! The `boundary_counts` table rolls the same incidents up to one row per
+//! `(boundary_type, geoid, category, day)`, where `boundary_type` is
+//! `county`, `place`, `tract`, or `neighborhood`, indexed on
+//! `(boundary_type, geoid)` for per-area rankings.
+//!
+//! [`diff`] compares the `count_summary` totals of two count DBs, for
+//! checking that a change to the aggregation didn't drop or duplicate rows.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use duckdb::Connection;
+
+use crate::DbError;
+
+/// Default number of count cells per degree (~111 m cells at the equator).
+pub const DEFAULT_CELL_PRECISION: u32 = 1000;
+
+/// Returns the cell index containing `coord` (degrees) at `precision`
+/// cells per degree.
+///
+/// Matches `CAST(FLOOR(coord * precision) AS INTEGER)` in `DuckDB`, which
+/// uses the same IEEE-754 double arithmetic.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn cell_index(coord: f64, precision: u32) -> i32 {
+    (coord * f64::from(precision)).floor() as i32
+}
+
+/// Returns the SQL expression computing the cell index of `column` at
+/// `precision` cells per degree, for use when building `count_summary`.
+#[must_use]
+pub fn cell_sql(column: &str, precision: u32) -> String {
+    format!("CAST(FLOOR({column} * {precision}) AS INTEGER)")
+}
+
+/// Converts a `[west, south, east, north]` bounding box into inclusive
+/// `((min_cell_lng, max_cell_lng), (min_cell_lat, max_cell_lat))` ranges.
+///
+/// Every cell that intersects the bbox is included, so counts may include
+/// incidents up to one cell outside the bbox edges.
+#[must_use]
+pub fn bbox_to_cell_range(bbox: [f64; 4], precision: u32) -> ((i32, i32), (i32, i32)) {
+    let [west, south, east, north] = bbox;
+    (
+        (cell_index(west, precision), cell_index(east, precision)),
+        (cell_index(south, precision), cell_index(north, precision)),
+    )
+}
+
+/// Optional dimension filters for [`count_in_bbox`]. Empty lists and
+/// `None` values are not applied.
+#[derive(Debug, Default, Clone)]
+pub struct CountFilters {
+    /// Inclusive start day (`YYYY-MM-DD`).
+    pub from_day: Option<String>,
+    /// Inclusive end day (`YYYY-MM-DD`).
+    pub to_day: Option<String>,
+    /// Top-level categories to include.
+    pub categories: Vec<String>,
+    /// Subcategories to include.
+    pub subcategories: Vec<String>,
+    /// Minimum severity.
+    pub severity_min: Option<i32>,
+    /// Arrest tri-state: `0` = no, `1` = yes, `2` = unknown.
+    pub arrest: Option<i32>,
+    /// Domestic tri-state: `0` = no, `1` = yes, `2` = unknown.
+    pub domestic: Option<i32>,
+    /// Source IDs to include.
+    pub source_ids: Vec<String>,
+}
+
+/// Counts incidents in `count_summary` within `bbox`
+/// (`[west, south, east, north]`) matching `filters`.
+///
+/// `precision` must match the value the count DB was generated with.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the query fails.
+pub fn count_in_bbox(
+    conn: &Connection,
+    bbox: [f64; 4],
+    precision: u32,
+    filters: &CountFilters,
+) -> Result<u64, DbError> {
+    let ((min_lng, max_lng), (min_lat, max_lat)) = bbox_to_cell_range(bbox, precision);
+
+    let mut conditions = vec![
+        "cell_lng BETWEEN ? AND ?".to_string(),
+        "cell_lat BETWEEN ? AND ?".to_string(),
+    ];
+    let mut params: Vec<Box<dyn duckdb::ToSql>> = vec![
+        Box::new(min_lng),
+        Box::new(max_lng),
+        Box::new(min_lat),
+        Box::new(max_lat),
+    ];
+
+    if let Some(from) = &filters.from_day {
+        conditions.push("day >= ?".to_string());
+        params.push(Box::new(from.clone()));
+    }
+    if let Some(to) = &filters.to_day {
+        conditions.push("day <= ?".to_string());
+        params.push(Box::new(to.clone()));
+    }
+    if let Some(severity) = filters.severity_min {
+        conditions.push("severity >= ?".to_string());
+        params.push(Box::new(severity));
+    }
+    if let Some(arrest) = filters.arrest {
+        conditions.push("arrest = ?".to_string());
+        params.push(Box::new(arrest));
+    }
+    if let Some(domestic) = filters.domestic {
+        conditions.push("domestic = ?".to_string());
+        params.push(Box::new(domestic));
+    }
+    for (column, values) in [
+        ("category", &filters.categories),
+        ("subcategory", &filters.subcategories),
+        ("source_id", &filters.source_ids),
+    ] {
+        if values.is_empty() {
+            continue;
+        }
+        let placeholders = vec!["?"; values.len()].join(", ");
+        conditions.push(format!("{column} IN ({placeholders})"));
+        for value in values {
+            params.push(Box::new(value.clone()));
+        }
+    }
+
+    let sql = format!(
+        "SELECT CAST(COALESCE(SUM(cnt), 0) AS BIGINT) FROM count_summary WHERE {}",
+        conditions.join(" AND ")
+    );
+    let param_refs: Vec<&dyn duckdb::ToSql> = params.iter().map(AsRef::as_ref).collect();
+    let total: i64 = conn.query_row(&sql, param_refs.as_slice(), |row| row.get(0))?;
+
+    Ok(u64::try_from(total).unwrap_or(0))
+}
+
+/// Old vs new incident totals for one key in a [`CountsDiff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountDelta {
+    /// What was counted: `total`, a category, or a source ID.
+    pub key: String,
+    /// Total in the old count DB.
+    pub old: u64,
+    /// Total in the new count DB.
+    pub new: u64,
+}
+
+impl CountDelta {
+    /// `new - old`.
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub const fn delta(&self) -> i64 {
+        self.new as i64 - self.old as i64
+    }
+
+    /// [`Self::delta`] as a fraction of `old`, or `None` when `old` is `0`
+    /// (the key is new).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn relative(&self) -> Option<f64> {
+        (self.old > 0).then(|| self.delta() as f64 / self.old as f64)
+    }
+}
+
+impl std::fmt::Display for CountDelta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {} ({:+}",
+            self.key,
+            self.old,
+            self.new,
+            self.delta()
+        )?;
+        match self.relative() {
+            Some(relative) => write!(f, ", {:+.2}%)", relative * 100.0),
+            None => write!(f, ", new)"),
+        }
+    }
+}
+
+/// Differences between the `count_summary` totals of two count DBs (see
+/// [`diff`]).
+#[derive(Debug, Clone)]
+pub struct CountsDiff {
+    /// Overall `SUM(cnt)`.
+    pub total: CountDelta,
+    /// Per-category totals that differ, by key.
+    pub categories: Vec<CountDelta>,
+    /// Per-source totals that differ, by key.
+    pub sources: Vec<CountDelta>,
+}
+
+impl CountsDiff {
+    /// Returns `true` if no total differs.
+    #[must_use]
+    pub fn is_identical(&self) -> bool {
+        self.total.old == self.total.new && self.categories.is_empty() && self.sources.is_empty()
+    }
+
+    /// Returns the differing category/source total with the largest
+    /// absolute delta.
+    #[must_use]
+    pub fn largest_absolute(&self) -> Option<&CountDelta> {
+        self.discrepancies()
+            .max_by_key(|d| d.delta().unsigned_abs())
+    }
+
+    /// Returns the differing category/source total with the largest
+    /// relative delta. Keys missing from the old DB rank above all others.
+    #[must_use]
+    pub fn largest_relative(&self) -> Option<&CountDelta> {
+        self.discrepancies().max_by(|a, b| {
+            let a = a.relative().map_or(f64::INFINITY, f64::abs);
+            let b = b.relative().map_or(f64::INFINITY, f64::abs);
+            a.total_cmp(&b)
+        })
+    }
+
+    fn discrepancies(&self) -> impl Iterator<Item = &CountDelta> {
+        self.categories.iter().chain(&self.sources)
+    }
+}
+
+impl std::fmt::Display for CountsDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_identical() {
+            return write!(f, "Count DBs match ({} incidents)", self.total.new);
+        }
+
+        writeln!(f, "Count DBs differ")?;
+        writeln!(f, "  {}", self.total)?;
+        if let Some(largest) = self.largest_absolute() {
+            writeln!(f, "  Largest absolute delta: {largest}")?;
+        }
+        if let Some(largest) = self.largest_relative() {
+            writeln!(f, "  Largest relative delta: {largest}")?;
+        }
+        for (label, deltas) in [("Categories", &self.categories), ("Sources", &self.sources)] {
+            if deltas.is_empty() {
+                continue;
+            }
+            writeln!(f, "  {label}:")?;
+            for delta in deltas {
+                writeln!(f, "    {delta}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares the `count_summary` tables of the count DBs at `old_path` and
+/// `new_path`: overall `SUM(cnt)`, per-category totals, and per-source
+/// totals. Both files are opened read-only.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if either file can't be opened or queried.
+pub fn diff(old_path: &Path, new_path: &Path) -> Result<CountsDiff, DbError> {
+    let old = open_read_only(old_path)?;
+    let new = open_read_only(new_path)?;
+
+    Ok(CountsDiff {
+        total: CountDelta {
+            key: "total".to_string(),
+            old: grouped_totals(&old, None)?.into_values().sum(),
+            new: grouped_totals(&new, None)?.into_values().sum(),
+        },
+        categories: differing(
+            &grouped_totals(&old, Some("category"))?,
+            &grouped_totals(&new, Some("category"))?,
+        ),
+        sources: differing(
+            &grouped_totals(&old, Some("source_id"))?,
+            &grouped_totals(&new, Some("source_id"))?,
+        ),
+    })
+}
+
+fn open_read_only(path: &Path) -> Result<Connection, DbError> {
+    Ok(Connection::open_with_flags(
+        path,
+        duckdb::Config::default().access_mode(duckdb::AccessMode::ReadOnly)?,
+    )?)
+}
+
+/// Returns `SUM(cnt)` per value of `column`, or a single `""` entry for
+/// the overall total when `column` is `None`.
+fn grouped_totals(
+    conn: &Connection,
+    column: Option<&str>,
+) -> Result<BTreeMap<String, u64>, DbError> {
+    let key = column.map_or_else(|| "''".to_string(), |c| format!("COALESCE({c}, '')"));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {key} AS key, CAST(COALESCE(SUM(cnt), 0) AS BIGINT)
+         FROM count_summary GROUP BY 1"
+    ))?;
+    let mut rows = stmt.query([])?;
+
+    let mut totals = BTreeMap::new();
+    while let Some(row) = rows.next()? {
+        let key: String = row.get(0)?;
+        let total: i64 = row.get(1)?;
+        totals.insert(key, u64::try_from(total).unwrap_or(0));
+    }
+    Ok(totals)
+}
+
+/// Returns a [`CountDelta`] for every key whose total differs between
+/// `old` and `new` (missing keys count as `0`).
+fn differing(old: &BTreeMap<String, u64>, new: &BTreeMap<String, u64>) -> Vec<CountDelta> {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .map(|key| CountDelta {
+            key: key.clone(),
+            old: old.get(key).copied().unwrap_or(0),
+            new: new.get(key).copied().unwrap_or(0),
+        })
+        .filter(|d| d.old != d.new)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_db_with_point(lng: f64, lat: f64) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE incidents AS SELECT {lng}::DOUBLE AS longitude, {lat}::DOUBLE AS latitude;
+             CREATE TABLE count_summary AS
+             SELECT {} AS cell_lng, {} AS cell_lat,
+                    'test' AS source_id, 'THEFT' AS subcategory, 'PROPERTY' AS category,
+                    1 AS severity, 2 AS arrest, 2 AS domestic, '2024-01-01' AS day, COUNT(*) AS cnt
+             FROM incidents GROUP BY ALL",
+            cell_sql("longitude", DEFAULT_CELL_PRECISION),
+            cell_sql("latitude", DEFAULT_CELL_PRECISION),
+        ))
+        .unwrap();
+        conn
+    }
+
+    /// A bbox strictly inside cell `(cell_lng, cell_lat)`.
+    fn cell_bbox(cell_lng: i32, cell_lat: i32) -> [f64; 4] {
+        let p = f64::from(DEFAULT_CELL_PRECISION);
+        [
+            (f64::from(cell_lng) + 0.1) / p,
+            (f64::from(cell_lat) + 0.1) / p,
+            (f64::from(cell_lng) + 0.9) / p,
+            (f64::from(cell_lat) + 0.9) / p,
+        ]
+    }
+
+    #[test]
+    fn bbox_to_cell_range_floors_both_edges() {
+        assert_eq!(
+            bbox_to_cell_range([-87.6305, 41.8781, -87.6295, 41.8789], 1000),
+            ((-87631, -87630), (41878, 41878))
+        );
+    }
+
+    #[test]
+    fn boundary_point_is_counted_in_exactly_one_cell() {
+        // Exactly on a cell edge, and a hair to either side of one.
+        for (lng, lat) in [(-87.6, 41.9), (-87.600_000_1, 41.9), (-87.599_999_9, 41.9)] {
+            let conn = count_db_with_point(lng, lat);
+            let cell_lng = cell_index(lng, DEFAULT_CELL_PRECISION);
+            let cell_lat = cell_index(lat, DEFAULT_CELL_PRECISION);
+
+            let mut hits = 0;
+            for d_lng in -1..=1 {
+                for d_lat in -1..=1 {
+                    let bbox = cell_bbox(cell_lng + d_lng, cell_lat + d_lat);
+                    let count = count_in_bbox(
+                        &conn,
+                        bbox,
+                        DEFAULT_CELL_PRECISION,
+                        &CountFilters::default(),
+                    )
+                    .unwrap();
+                    if count > 0 {
+                        assert_eq!((d_lng, d_lat), (0, 0), "({lng}, {lat}) in wrong cell");
+                        hits += count;
+                    }
+                }
+            }
+            assert_eq!(hits, 1, "({lng}, {lat}) counted {hits} times");
+        }
+    }
+
+    #[test]
+    fn diff_reports_changed_totals() {
+        let dir =
+            std::env::temp_dir().join(format!("crime_map_counts_diff_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, rows: &str| {
+            let path = dir.join(name);
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(&format!(
+                "CREATE TABLE count_summary (source_id VARCHAR, category VARCHAR, cnt BIGINT);
+                 INSERT INTO count_summary VALUES {rows}"
+            ))
+            .unwrap();
+            path
+        };
+        let old = write(
+            "old.duckdb",
+            "('a', 'PROPERTY', 10), ('a', 'VIOLENT', 5), ('b', 'PROPERTY', 4)",
+        );
+        let same = write(
+            "same.duckdb",
+            "('a', 'PROPERTY', 6), ('a', 'PROPERTY', 4), ('a', 'VIOLENT', 5), ('b', 'PROPERTY', 4)",
+        );
+        let new = write(
+            "new.duckdb",
+            "('a', 'PROPERTY', 12), ('a', 'VIOLENT', 2), ('c', 'OTHER', 1)",
+        );
+
+        assert!(diff(&old, &same).unwrap().is_identical());
+
+        let changed = diff(&old, &new).unwrap();
+        assert!(!changed.is_identical());
+        assert_eq!((changed.total.old, changed.total.new), (19, 15));
+        assert_eq!(
+            changed
+                .categories
+                .iter()
+                .map(|d| (d.key.as_str(), d.delta()))
+                .collect::<Vec<_>>(),
+            [("OTHER", 1), ("PROPERTY", -2), ("VIOLENT", -3)]
+        );
+        assert_eq!(
+            changed
+                .sources
+                .iter()
+                .map(|d| (d.key.as_str(), d.delta()))
+                .collect::<Vec<_>>(),
+            [("a", -1), ("b", -4), ("c", 1)]
+        );
+        // `b` lost all 4 incidents, more than any other key changed by.
+        assert_eq!(
+            changed.largest_absolute().map(|d| d.key.as_str()),
+            Some("b")
+        );
+        // `c` didn't exist before, so it ranks as the largest relative change.
+        assert_eq!(
+            changed.largest_relative().map(|d| d.key.as_str()),
+            Some("c")
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn filters_are_applied() {
+        let conn = count_db_with_point(-87.63, 41.88);
+        let bbox = [-88.0, 41.0, -87.0, 42.0];
+        let filters = CountFilters {
+            categories: vec!["VIOLENT".to_string()],
+            ..CountFilters::default()
+        };
+        assert_eq!(
+            count_in_bbox(&conn, bbox, DEFAULT_CELL_PRECISION, &filters).unwrap(),
+            0
+        );
+        let filters = CountFilters {
+            from_day: Some("2024-01-01".to_string()),
+            source_ids: vec!["test".to_string()],
+            ..CountFilters::default()
+        };
+        assert_eq!(
+            count_in_bbox(&conn, bbox, DEFAULT_CELL_PRECISION, &filters).unwrap(),
+            1
+        );
+    }
+}
@@ -2,7 +2,14 @@
 //!
 //! Shared across all sources. Caches both successful geocodes
 //! (with coordinates) and failed lookups (null coordinates) so
-//! we don't re-query the same addresses.
+//! we don't re-query the same addresses. Matches rejected by city/state
+//! validation are cached as a third, low-confidence outcome: their
+//! coordinates are kept for review but never used.
+//!
+//! For incremental sync, entries track whether they have been shared:
+//! [`export_unsynced`] writes new entries to a small Parquet delta,
+//! [`mark_synced`] flags them once the delta is stored, and [`import`]
+//! applies another machine's delta.
 
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
@@ -51,11 +58,43 @@ fn create_schema(conn: &Connection) -> Result<(), DbError> {
             matched_address TEXT,
             created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
             PRIMARY KEY (address_key, provider)
+        );
+        ALTER TABLE geocode_cache ADD COLUMN IF NOT EXISTS low_confidence BOOLEAN DEFAULT FALSE;
+        -- Entries that predate delta sync are already in the full shared DB.
+        ALTER TABLE geocode_cache ADD COLUMN IF NOT EXISTS synced BOOLEAN DEFAULT TRUE;
+        ALTER TABLE geocode_cache ALTER COLUMN synced SET DEFAULT FALSE;
+        CREATE TABLE IF NOT EXISTS geocode_cache_sync (
+            name TEXT PRIMARY KEY,
+            value TEXT NOT NULL
         );",
     )?;
     Ok(())
 }
 
+/// Checks that `conn` is an intact geocode cache, returning its entry
+/// count.
+///
+/// Verifies the `geocode_cache` table and the columns lookups read exist
+/// and that every row reads back. Run it on a cache opened read-only
+/// (not with [`open`], which would create a missing table) after pulling
+/// it: a truncated cache that still opened would otherwise just miss
+/// every lookup and send every address back to the geocoding providers.
+///
+/// # Errors
+///
+/// Returns [`DbError::Integrity`] if the table or a column is missing or
+/// the data can't be read.
+pub fn verify(conn: &Connection) -> Result<u64, DbError> {
+    let counts = crate::verify_tables(
+        conn,
+        &[(
+            "geocode_cache",
+            &["address_key", "provider", "lat", "lng", "matched_address"],
+        )],
+    )?;
+    Ok(counts.iter().sum())
+}
+
 /// Result of a geocode cache lookup: `(hits, tried_keys)`.
 pub type CacheLookupResult = (BTreeMap<String, (f64, f64)>, BTreeSet<String>);
 
@@ -63,7 +102,8 @@ pub type CacheLookupResult = (BTreeMap<String, (f64, f64)>, BTreeSet<String>);
 ///
 /// Returns `(hits, tried)` where:
 /// - `hits` maps `address_key` -> (lat, lng) for successful geocodes
-/// - `tried` contains all `address_keys` that have any cache entry (hit or miss)
+/// - `tried` contains all `address_keys` that have any cache entry (hit,
+///   miss, or low-confidence match)
 ///
 /// # Errors
 ///
@@ -82,7 +122,11 @@ pub fn cache_lookup(
     for chunk in address_keys.chunks(1000) {
         let placeholders: String = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
         let sql = format!(
-            "SELECT address_key, lat, lng FROM geocode_cache WHERE address_key IN ({placeholders})"
+            "SELECT address_key,
+                    IF(low_confidence, NULL, lat),
+                    IF(low_confidence, NULL, lng)
+             FROM geocode_cache
+             WHERE address_key IN ({placeholders})"
         );
         let mut stmt = conn.prepare(&sql)?;
 
@@ -131,6 +175,34 @@ pub fn cache_insert(conn: &Connection, entries: &[CacheEntry]) -> Result<(), DbE
     Ok(())
 }
 
+/// Inserts matches rejected by city/state validation into the cache as
+/// low-confidence entries. [`cache_lookup`] reports them as tried but
+/// never as hits.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the insert fails.
+pub fn cache_insert_low_confidence(
+    conn: &Connection,
+    entries: &[CacheEntry],
+) -> Result<(), DbError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare(
+        "INSERT INTO geocode_cache (address_key, provider, lat, lng, matched_address, low_confidence)
+         VALUES (?, ?, ?, ?, ?, TRUE)
+         ON CONFLICT (address_key, provider) DO NOTHING",
+    )?;
+
+    for (key, provider, lat, lng, matched) in entries {
+        stmt.execute(duckdb::params![key, provider, lat, lng, matched.as_deref()])?;
+    }
+
+    Ok(())
+}
+
 /// Retrieves all cached results for a specific provider.
 ///
 /// Returns a list of `(address_key, lat, lng, matched_address)` tuples.
@@ -177,3 +249,284 @@ pub fn get_by_provider(
 
     Ok(results)
 }
+
+/// Which entries [`prune`] should remove. Each policy is independent;
+/// unset policies are skipped.
+#[derive(Debug, Default)]
+pub struct PrunePolicy {
+    /// Drop misses (entries with no coordinates, or low-confidence
+    /// matches) created more than this many days ago, so addresses can be
+    /// retried by improved providers.
+    pub misses_older_than_days: Option<u32>,
+    /// Drop every entry (hits and misses) from these providers.
+    pub providers: Vec<String>,
+    /// Drop entries whose `address_key` is not in this set of keys still
+    /// referenced by any source. Build it with a full scan of the source
+    /// databases; an empty set would drop the entire cache and is rejected.
+    pub live_address_keys: Option<BTreeSet<String>>,
+}
+
+/// Number of entries removed by each [`PrunePolicy`] rule.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PruneStats {
+    /// Entries removed by [`PrunePolicy::misses_older_than_days`].
+    pub stale_misses: u64,
+    /// Entries removed by [`PrunePolicy::providers`].
+    pub provider_entries: u64,
+    /// Entries removed by [`PrunePolicy::live_address_keys`].
+    pub unreferenced: u64,
+}
+
+impl PruneStats {
+    /// Total entries removed.
+    #[must_use]
+    pub const fn total(&self) -> u64 {
+        self.stale_misses + self.provider_entries + self.unreferenced
+    }
+}
+
+/// Removes geocode cache entries according to `policy`.
+///
+/// Rules are applied in order (stale misses, providers, unreferenced) so
+/// each entry is counted under the first rule that removed it.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if a delete fails, or [`DbError::Conversion`] if
+/// `live_address_keys` is provided but empty.
+pub fn prune(conn: &Connection, policy: &PrunePolicy) -> Result<PruneStats, DbError> {
+    if policy
+        .live_address_keys
+        .as_ref()
+        .is_some_and(BTreeSet::is_empty)
+    {
+        return Err(DbError::Conversion {
+            message: "refusing to prune unreferenced entries with an empty live key set"
+                .to_string(),
+        });
+    }
+
+    let mut stats = PruneStats::default();
+
+    if let Some(days) = policy.misses_older_than_days {
+        let removed = conn.execute(
+            "DELETE FROM geocode_cache
+             WHERE (lat IS NULL OR low_confidence)
+               AND created_at < CURRENT_TIMESTAMP - to_days(CAST(? AS INTEGER))",
+            duckdb::params![days],
+        )?;
+        stats.stale_misses = removed as u64;
+    }
+
+    for provider in &policy.providers {
+        let removed = conn.execute(
+            "DELETE FROM geocode_cache WHERE provider = ?",
+            duckdb::params![provider],
+        )?;
+        stats.provider_entries += removed as u64;
+    }
+
+    if let Some(live) = &policy.live_address_keys {
+        conn.execute_batch(
+            "CREATE OR REPLACE TEMP TABLE live_address_keys (address_key TEXT PRIMARY KEY)",
+        )?;
+        {
+            let mut stmt = conn.prepare("INSERT INTO live_address_keys VALUES (?)")?;
+            for key in live {
+                stmt.execute(duckdb::params![key])?;
+            }
+        }
+
+        let removed = conn.execute(
+            "DELETE FROM geocode_cache
+             WHERE address_key NOT IN (SELECT address_key FROM live_address_keys)",
+            [],
+        )?;
+        stats.unreferenced = removed as u64;
+
+        conn.execute_batch("DROP TABLE live_address_keys")?;
+    }
+
+    Ok(stats)
+}
+
+/// `geocode_cache_sync` key holding the name of the newest delta applied.
+const LAST_APPLIED_DELTA: &str = "last_applied_delta";
+
+/// Escapes `path` for use in a single-quoted SQL string literal.
+fn sql_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\'', "''")
+}
+
+/// Writes every entry not yet [`mark_synced`] to a Parquet file at
+/// `path`. Writes nothing when there are none.
+///
+/// Returns the number of entries exported.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the query or `COPY` fails.
+pub fn export_unsynced(conn: &Connection, path: &Path) -> Result<u64, DbError> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM geocode_cache WHERE NOT synced",
+        [],
+        |row| row.get(0),
+    )?;
+    if count == 0 {
+        return Ok(0);
+    }
+
+    conn.execute_batch(&format!(
+        "COPY (
+             SELECT address_key, provider, lat, lng, matched_address, created_at, low_confidence
+             FROM geocode_cache
+             WHERE NOT synced
+         ) TO '{}' (FORMAT PARQUET)",
+        sql_path(path)
+    ))?;
+
+    Ok(count.unsigned_abs())
+}
+
+/// Flags the entries contained in the delta at `path` (written by
+/// [`export_unsynced`]) as synced. Entries inserted after the export stay
+/// unsynced for the next delta.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the update fails.
+pub fn mark_synced(conn: &Connection, path: &Path) -> Result<u64, DbError> {
+    let updated = conn.execute(
+        &format!(
+            "UPDATE geocode_cache SET synced = TRUE
+             FROM read_parquet('{}') AS delta
+             WHERE NOT geocode_cache.synced
+               AND geocode_cache.address_key = delta.address_key
+               AND geocode_cache.provider = delta.provider",
+            sql_path(path)
+        ),
+        [],
+    )?;
+    Ok(updated as u64)
+}
+
+/// Applies a delta written by [`export_unsynced`] (typically on another
+/// machine). Entries already cached are kept; imported entries are
+/// marked synced so they aren't exported again.
+///
+/// Returns the number of new entries.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the file can't be read or the insert fails.
+pub fn import(conn: &Connection, path: &Path) -> Result<u64, DbError> {
+    let inserted = conn.execute(
+        &format!(
+            "INSERT INTO geocode_cache
+                 (address_key, provider, lat, lng, matched_address, created_at, low_confidence, synced)
+             SELECT address_key, provider, lat, lng, matched_address, created_at, low_confidence, TRUE
+             FROM read_parquet('{}')
+             ON CONFLICT (address_key, provider) DO NOTHING",
+            sql_path(path)
+        ),
+        [],
+    )?;
+    Ok(inserted as u64)
+}
+
+/// Returns the name of the newest delta applied with
+/// [`set_last_applied_delta`], or `None` if none has been.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the query fails.
+pub fn last_applied_delta(conn: &Connection) -> Result<Option<String>, DbError> {
+    let mut stmt = conn.prepare("SELECT value FROM geocode_cache_sync WHERE name = ?")?;
+    let mut rows = stmt.query(duckdb::params![LAST_APPLIED_DELTA])?;
+    Ok(match rows.next()? {
+        Some(row) => Some(row.get(0)?),
+        None => None,
+    })
+}
+
+/// Records `name` as the newest delta applied to this cache.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the upsert fails.
+pub fn set_last_applied_delta(conn: &Connection, name: &str) -> Result<(), DbError> {
+    conn.execute(
+        "INSERT INTO geocode_cache_sync (name, value) VALUES (?, ?)
+         ON CONFLICT (name) DO UPDATE SET value = excluded.value",
+        duckdb::params![LAST_APPLIED_DELTA, name],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_counts_entries_and_rejects_a_missing_table() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(matches!(verify(&conn), Err(DbError::Integrity { .. })));
+
+        create_schema(&conn).unwrap();
+        cache_insert(&conn, &[entry("1 MAIN ST"), entry("2 MAIN ST")]).unwrap();
+        assert_eq!(verify(&conn).unwrap(), 2);
+    }
+
+    fn entry(key: &str) -> CacheEntry {
+        (
+            key.to_string(),
+            "census".to_string(),
+            Some(41.88),
+            Some(-87.63),
+            Some(format!("{key}, CHICAGO, IL")),
+        )
+    }
+
+    #[test]
+    fn deltas_round_trip_between_caches() {
+        let dir =
+            std::env::temp_dir().join(format!("crime_map_geocode_delta_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let delta = dir.join("delta.parquet");
+
+        let local = open(&dir.join("local.duckdb")).unwrap();
+        cache_insert(&local, &[entry("1 MAIN ST"), entry("2 MAIN ST")]).unwrap();
+
+        assert_eq!(export_unsynced(&local, &delta).unwrap(), 2);
+        // Inserted after the export, so it must stay unsynced.
+        cache_insert(&local, &[entry("3 MAIN ST")]).unwrap();
+        assert_eq!(mark_synced(&local, &delta).unwrap(), 2);
+
+        let remote = open(&dir.join("remote.duckdb")).unwrap();
+        cache_insert(&remote, &[entry("2 MAIN ST")]).unwrap();
+        assert_eq!(import(&remote, &delta).unwrap(), 1);
+        let (hits, _) = cache_lookup(&remote, &["1 MAIN ST".to_string()]).unwrap();
+        assert!(hits.contains_key("1 MAIN ST"));
+
+        // Only the entry inserted after the first export is left to share.
+        let next = dir.join("next.parquet");
+        assert_eq!(export_unsynced(&local, &next).unwrap(), 1);
+        assert_eq!(
+            export_unsynced(&remote, &dir.join("none.parquet")).unwrap(),
+            1
+        );
+
+        assert_eq!(last_applied_delta(&remote).unwrap(), None);
+        set_last_applied_delta(&remote, "0001.parquet").unwrap();
+        set_last_applied_delta(&remote, "0002.parquet").unwrap();
+        assert_eq!(
+            last_applied_delta(&remote).unwrap().as_deref(),
+            Some("0002.parquet")
+        );
+
+        drop(local);
+        drop(remote);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
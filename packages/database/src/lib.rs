@@ -22,6 +22,7 @@
 //! ```
 
 pub mod boundaries_db;
+pub mod counts;
 pub mod geocode_cache;
 pub mod paths;
 pub mod source_db;
@@ -43,4 +44,62 @@ pub enum DbError {
     /// I/O error.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
+
+    /// `SQLite` error from a generated output database.
+    #[error("SQLite error: {0}")]
+    Sqlite(String),
+
+    /// A database is missing an expected table or column, or its data
+    /// can't be read back.
+    #[error("Integrity check failed: {message}")]
+    Integrity {
+        /// Description of what's wrong.
+        message: String,
+    },
+}
+
+/// Checks that `conn` has each of `tables` with at least its listed
+/// columns, and that every column of every table reads back, returning
+/// the row count of each table in order.
+///
+/// `DuckDB` has no `PRAGMA integrity_check`, but it verifies each block's
+/// checksum as it's read, so a scan touching every column fails on a
+/// truncated or corrupted file instead of silently returning fewer rows.
+fn verify_tables(
+    conn: &duckdb::Connection,
+    tables: &[(&str, &[&str])],
+) -> Result<Vec<u64>, DbError> {
+    let mut counts = Vec::with_capacity(tables.len());
+    for (table, columns) in tables {
+        let mut stmt = conn.prepare(
+            "SELECT column_name FROM duckdb_columns()
+             WHERE schema_name = 'main' AND table_name = ?",
+        )?;
+        let found: Vec<String> = stmt
+            .query_map([table], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        if found.is_empty() {
+            return Err(DbError::Integrity {
+                message: format!("table {table} is missing"),
+            });
+        }
+        if let Some(missing) = columns.iter().find(|c| !found.iter().any(|f| f == *c)) {
+            return Err(DbError::Integrity {
+                message: format!("table {table} is missing column {missing}"),
+            });
+        }
+
+        // `COLUMNS(*)` hashes every column, so none is pruned from the scan.
+        let rows: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*), bit_xor(hash(COLUMNS(*))) FROM {table}"),
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| DbError::Integrity {
+                message: format!("failed to read {table}: {e}"),
+            })?;
+        counts.push(u64::try_from(rows).unwrap_or(0));
+    }
+    Ok(counts)
 }
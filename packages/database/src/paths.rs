@@ -22,9 +22,13 @@ pub fn project_root() -> PathBuf {
 }
 
 /// Returns the `data/` directory path.
+///
+/// Overridable with the `CRIME_MAP_DATA_DIR` environment variable (used by
+/// tests to run the pipeline against fixture data).
 #[must_use]
 pub fn data_dir() -> PathBuf {
-    project_root().join("data")
+    std::env::var_os("CRIME_MAP_DATA_DIR")
+        .map_or_else(|| project_root().join("data"), PathBuf::from)
 }
 
 /// Returns the `data/sources/` directory for per-source `DuckDB` files.
@@ -45,6 +49,14 @@ pub fn source_db_path(source_id: &str) -> PathBuf {
     sources_dir().join(format!("{source_id}.duckdb"))
 }
 
+/// Returns the path of the state file listing the sources that failed the
+/// last sync (see `crime_map_ingest::run_sync_retry`). It isn't a
+/// `DuckDB` file, so R2 sync never transfers it, and it's safe to delete.
+#[must_use]
+pub fn last_sync_failures_path() -> PathBuf {
+    sources_dir().join(".last_sync_failures.json")
+}
+
 /// Returns the path for the boundaries `DuckDB` file.
 #[must_use]
 pub fn boundaries_db_path() -> PathBuf {
@@ -63,6 +75,75 @@ pub fn generated_dir() -> PathBuf {
     data_dir().join("generated")
 }
 
+/// Incidents `PMTiles` (heatmap + points) in a generated output directory.
+pub const INCIDENTS_PMTILES_FILE: &str = "incidents.pmtiles";
+
+/// Sidebar `SQLite` database in a generated output directory.
+pub const INCIDENTS_DB_FILE: &str = "incidents.db";
+
+/// Pre-aggregated count `DuckDB` in a generated output directory.
+pub const COUNTS_DB_FILE: &str = "counts.duckdb";
+
+/// H3 hexbin `DuckDB` in a generated output directory.
+pub const H3_DB_FILE: &str = "h3.duckdb";
+
+/// Analytics `DuckDB` in a generated output directory.
+pub const ANALYTICS_DB_FILE: &str = "analytics.duckdb";
+
+/// Server metadata JSON in a generated output directory.
+pub const METADATA_FILE: &str = "metadata.json";
+
+/// Optional NDJSON export of the analytics `incidents` table in a
+/// generated output directory.
+pub const ANALYTICS_NDJSON_FILE: &str = "analytics_incidents.jsonl";
+
+/// Opt-in tract-level choropleth `DuckDB` in a generated output directory,
+/// derived from [`ANALYTICS_DB_FILE`].
+pub const CHOROPLETH_DB_FILE: &str = "choropleth.duckdb";
+
+/// Optional per-resolution coverage summary of the H3 DB in a generated
+/// output directory.
+pub const H3_STATS_FILE: &str = "h3_stats.json";
+
+/// Freshness heartbeat JSON in a generated output directory.
+pub const HEALTH_FILE: &str = "health.json";
+
+/// Generation manifest in a generated output directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Boundaries `PMTiles` in a generated output directory.
+pub const BOUNDARIES_PMTILES_FILE: &str = "boundaries.pmtiles";
+
+/// Boundaries search `SQLite` database in a generated output directory.
+pub const BOUNDARIES_DB_FILE: &str = "boundaries.db";
+
+/// Files produced per partition/merged output (everything except
+/// boundaries). The generator writes these and R2 sync transfers them, so
+/// a new output only needs adding here.
+pub const GENERATED_FILES: &[&str] = &[
+    INCIDENTS_PMTILES_FILE,
+    INCIDENTS_DB_FILE,
+    COUNTS_DB_FILE,
+    H3_DB_FILE,
+    ANALYTICS_DB_FILE,
+    METADATA_FILE,
+    HEALTH_FILE,
+    MANIFEST_FILE,
+];
+
+/// Opt-in outputs that only exist when requested at generation time. R2
+/// pushes include them only when present locally.
+pub const OPTIONAL_GENERATED_FILES: &[&str] =
+    &[ANALYTICS_NDJSON_FILE, H3_STATS_FILE, CHOROPLETH_DB_FILE];
+
+/// Files produced by boundary generation.
+pub const BOUNDARY_FILES: &[&str] = &[BOUNDARIES_PMTILES_FILE, BOUNDARIES_DB_FILE];
+
+/// Marker file written into a generated output directory whose outputs
+/// were built from a random sample (`--sample`). Such outputs are previews
+/// only; R2 pushes refuse directories containing this file.
+pub const PREVIEW_MARKER_FILE: &str = "PREVIEW";
+
 /// Ensures a directory exists, creating it if necessary.
 ///
 /// # Errors
@@ -3,10 +3,21 @@
 //! Each crime data source gets its own `DuckDB` file at
 //! `data/sources/{source_id}.duckdb`. The file contains an `incidents`
 //! table and a `_meta` table for tracking sync state.
-
-use std::collections::BTreeMap;
-use std::path::Path;
-
+//!
+//! ## Sharded sources
+//!
+//! A very large source can also be split into shard files named
+//! `{source_id}.{shard}.duckdb` (e.g. `chicago_pd.2023.duckdb`), each
+//! with the same schema. [`shard_paths`] enumerates every file backing a
+//! source, and [`ShardedIncidents`] pages through the incidents of all of
+//! them in order, so readers don't need to know how a source is stored.
+//! Enrichment updates every shard in place; sync and geocoding still
+//! write the unsharded file.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crime_map_source::source_def::CoordinatePrecedence;
 use crime_map_source_models::NormalizedIncident;
 use duckdb::Connection;
 
@@ -39,6 +50,47 @@ pub fn open(path: &Path) -> Result<Connection, DbError> {
     Ok(conn)
 }
 
+/// Opens an existing per-source `DuckDB` without creating missing tables.
+///
+/// Returns `None` if the file has no `incidents` table, e.g. a file left
+/// half-initialized by an interrupted sync. Otherwise applies the same
+/// column migrations as [`open`].
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the connection or migration fails.
+pub fn open_existing(path: &Path) -> Result<Option<Connection>, DbError> {
+    let conn = Connection::open(path)?;
+
+    conn.execute_batch(
+        "SET threads = 4;
+         SET memory_limit = '2GB';",
+    )?;
+
+    if !has_incidents_table(&conn)? {
+        return Ok(None);
+    }
+
+    create_schema(&conn)?;
+
+    Ok(Some(conn))
+}
+
+/// Returns whether `conn` has an `incidents` table.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the catalog query fails.
+pub fn has_incidents_table(conn: &Connection) -> Result<bool, DbError> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM duckdb_tables()
+         WHERE schema_name = 'main' AND table_name = 'incidents'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
 /// Opens a source DB by source ID using the default path.
 ///
 /// # Errors
@@ -72,12 +124,22 @@ fn create_schema(conn: &Connection) -> Result<(), DbError> {
             state_fips TEXT,
             county_geoid TEXT,
             neighborhood_id TEXT,
-            enriched BOOLEAN NOT NULL DEFAULT FALSE
+            enriched BOOLEAN NOT NULL DEFAULT FALSE,
+            source_longitude DOUBLE,
+            source_latitude DOUBLE,
+            geocoded_longitude DOUBLE,
+            geocoded_latitude DOUBLE,
+            coord_source TEXT
         );
 
         CREATE TABLE IF NOT EXISTS _meta (
             key TEXT PRIMARY KEY,
             value TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS raw_records (
+            source_incident_id TEXT NOT NULL PRIMARY KEY,
+            raw_json TEXT NOT NULL
         );",
     )?;
 
@@ -92,6 +154,20 @@ fn create_schema(conn: &Connection) -> Result<(), DbError> {
          UPDATE incidents SET enriched = FALSE WHERE enriched IS NULL;",
     )?;
 
+    // Coordinate provenance. Rows synced before these columns existed keep
+    // their source coordinates unless geocoding already replaced them, in
+    // which case the originals are gone and `coord_source` stays NULL.
+    conn.execute_batch(
+        "ALTER TABLE incidents ADD COLUMN IF NOT EXISTS source_longitude DOUBLE;
+         ALTER TABLE incidents ADD COLUMN IF NOT EXISTS source_latitude DOUBLE;
+         ALTER TABLE incidents ADD COLUMN IF NOT EXISTS geocoded_longitude DOUBLE;
+         ALTER TABLE incidents ADD COLUMN IF NOT EXISTS geocoded_latitude DOUBLE;
+         ALTER TABLE incidents ADD COLUMN IF NOT EXISTS coord_source TEXT;
+         UPDATE incidents
+         SET source_longitude = longitude, source_latitude = latitude, coord_source = 'source'
+         WHERE coord_source IS NULL AND has_coordinates = TRUE AND geocoded = FALSE;",
+    )?;
+
     Ok(())
 }
 
@@ -143,7 +219,8 @@ pub fn insert_incidents(
                 source_incident_id, category, parent_category, severity,
                 longitude, latitude, occurred_at, description, block_address,
                 city, state, arrest_made, domestic, location_type,
-                has_coordinates, geocoded
+                has_coordinates, geocoded, source_longitude, source_latitude,
+                coord_source
             ) VALUES ",
         );
 
@@ -151,7 +228,7 @@ pub fn insert_incidents(
             if i > 0 {
                 sql.push_str(", ");
             }
-            sql.push_str("(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)");
+            sql.push_str("(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)");
         }
 
         sql.push_str(
@@ -170,7 +247,10 @@ pub fn insert_incidents(
                 domestic = EXCLUDED.domestic,
                 location_type = EXCLUDED.location_type,
                 has_coordinates = EXCLUDED.has_coordinates,
-                geocoded = EXCLUDED.geocoded",
+                geocoded = EXCLUDED.geocoded,
+                source_longitude = EXCLUDED.source_longitude,
+                source_latitude = EXCLUDED.source_latitude,
+                coord_source = EXCLUDED.coord_source",
         );
 
         let mut stmt = conn.prepare(&sql)?;
@@ -215,10 +295,63 @@ pub fn insert_incidents(
             stmt.raw_bind_parameter(param_idx + 14, has_coordinates)?;
             // geocoded
             stmt.raw_bind_parameter(param_idx + 15, incident.geocoded)?;
+            // source_longitude, source_latitude, coord_source
+            stmt.raw_bind_parameter(
+                param_idx + 16,
+                incident.longitude.filter(|_| has_coordinates),
+            )?;
+            stmt.raw_bind_parameter(
+                param_idx + 17,
+                incident.latitude.filter(|_| has_coordinates),
+            )?;
+            stmt.raw_bind_parameter(param_idx + 18, has_coordinates.then_some("source"))?;
+
+            param_idx += 19;
+        }
+
+        let rows = stmt.raw_execute()?;
+        total_inserted += u64::try_from(rows).unwrap_or(0);
+    }
+
+    Ok(total_inserted)
+}
+
+/// Upserts `(source_incident_id, raw_json)` pairs into the
+/// `raw_records` table, which keeps the source's original record for each
+/// incident for debugging. Only filled when a sync opts in to it.
+///
+/// Returns the number of rows affected.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if any database operation fails.
+pub fn insert_raw_records(conn: &Connection, records: &[(String, String)]) -> Result<u64, DbError> {
+    // Keep the last record of each ID, like `insert_incidents`.
+    let deduped: BTreeMap<&str, &str> = records
+        .iter()
+        .map(|(id, json)| (id.as_str(), json.as_str()))
+        .collect();
+    let deduped: Vec<(&str, &str)> = deduped.into_iter().collect();
 
-            param_idx += 16;
+    let mut total_inserted = 0u64;
+    for chunk in deduped.chunks(CHUNK_SIZE) {
+        let mut sql =
+            String::from("INSERT INTO raw_records (source_incident_id, raw_json) VALUES ");
+        for i in 0..chunk.len() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push_str("(?, ?)");
         }
+        sql.push_str(
+            " ON CONFLICT (source_incident_id) DO UPDATE SET raw_json = EXCLUDED.raw_json",
+        );
 
+        let mut stmt = conn.prepare(&sql)?;
+        for (i, (id, json)) in chunk.iter().enumerate() {
+            stmt.raw_bind_parameter(i * 2 + 1, id)?;
+            stmt.raw_bind_parameter(i * 2 + 2, json)?;
+        }
         let rows = stmt.raw_execute()?;
         total_inserted += u64::try_from(rows).unwrap_or(0);
     }
@@ -258,6 +391,68 @@ pub fn get_max_occurred_at(
     Ok(result.and_then(|s| parse_timestamp(&s)))
 }
 
+/// Rough `[west, south, east, north]` bounding box of the US, including
+/// Alaska, Hawaii, and Puerto Rico. Coordinates outside it are almost
+/// certainly bad geocodes or swapped lat/lng.
+pub const US_BBOX: [f64; 4] = [-180.0, 17.5, -64.5, 71.5];
+
+/// Coordinate health counts for one source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoordinateQuality {
+    /// Total incidents.
+    pub total: u64,
+    /// Incidents with coordinates.
+    pub with_coordinates: u64,
+    /// Incidents whose coordinates came from geocoding rather than the
+    /// source itself.
+    pub geocoded: u64,
+    /// Incidents with spatial attribution applied.
+    pub enriched: u64,
+    /// Incidents with coordinates outside [`US_BBOX`].
+    pub outside_us: u64,
+}
+
+/// Counts coordinate coverage and quality from the incident flags
+/// (`has_coordinates`, `geocoded`, `enriched`) and coordinate ranges.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the query fails.
+pub fn coordinate_quality(conn: &Connection) -> Result<CoordinateQuality, DbError> {
+    let [west, south, east, north] = US_BBOX;
+    let mut stmt = conn.prepare(
+        "SELECT
+            COUNT(*),
+            COUNT(*) FILTER (WHERE has_coordinates),
+            COUNT(*) FILTER (WHERE has_coordinates AND geocoded),
+            COUNT(*) FILTER (WHERE enriched),
+            COUNT(*) FILTER (
+                WHERE has_coordinates
+                  AND NOT (longitude BETWEEN ? AND ? AND latitude BETWEEN ? AND ?)
+            )
+         FROM incidents",
+    )?;
+    let counts: [i64; 5] = stmt.query_row(duckdb::params![west, east, south, north], |row| {
+        Ok([
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get(3)?,
+            row.get(4)?,
+        ])
+    })?;
+
+    let [total, with_coordinates, geocoded, enriched, outside_us] =
+        counts.map(|n| u64::try_from(n).unwrap_or(0));
+    Ok(CoordinateQuality {
+        total,
+        with_coordinates,
+        geocoded,
+        enriched,
+        outside_us,
+    })
+}
+
 /// Parses a `DuckDB` timestamp text representation into a UTC `DateTime`.
 ///
 /// `DuckDB`'s `::TEXT` cast can produce several formats depending on the
@@ -268,7 +463,7 @@ pub fn get_max_occurred_at(
 /// - `2024-01-15 10:30:00.123+00` (both)
 ///
 /// This function tries them in order and returns the first successful parse.
-fn parse_timestamp(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+pub fn parse_timestamp(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
     use chrono::{DateTime, NaiveDateTime, Utc};
 
     // Try parsing as a full DateTime with timezone first
@@ -367,6 +562,60 @@ pub fn update_sync_metadata(conn: &Connection, source_name: &str) -> Result<(),
     Ok(())
 }
 
+/// A source's last sync failure, as recorded by [`set_last_error`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastSyncError {
+    /// Broad cause of the failure (e.g. `rate_limited`).
+    pub kind: String,
+    /// The error message.
+    pub message: String,
+    /// When the sync failed, as RFC 3339.
+    pub failed_at: String,
+}
+
+/// Records a failed sync's error `kind`, `message`, and the current time
+/// in the `_meta` table, replacing any earlier failure.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the metadata update fails.
+pub fn set_last_error(conn: &Connection, kind: &str, message: &str) -> Result<(), DbError> {
+    set_meta(conn, "last_error_kind", kind)?;
+    set_meta(conn, "last_error", message)?;
+    set_meta(conn, "last_error_at", &chrono::Utc::now().to_rfc3339())
+}
+
+/// Clears the failure recorded by [`set_last_error`], after a successful
+/// sync.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the delete fails.
+pub fn clear_last_error(conn: &Connection) -> Result<(), DbError> {
+    conn.execute(
+        "DELETE FROM _meta WHERE key IN ('last_error_kind', 'last_error', 'last_error_at')",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Returns the failure recorded by [`set_last_error`], or `None` if the
+/// last sync succeeded (or none has failed).
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the query fails.
+pub fn get_last_error(conn: &Connection) -> Result<Option<LastSyncError>, DbError> {
+    let Some(message) = get_meta(conn, "last_error")? else {
+        return Ok(None);
+    };
+    Ok(Some(LastSyncError {
+        kind: get_meta(conn, "last_error_kind")?.unwrap_or_default(),
+        message,
+        failed_at: get_meta(conn, "last_error_at")?.unwrap_or_default(),
+    }))
+}
+
 /// Returns whether this source has completed a full (non-limited) sync.
 ///
 /// # Errors
@@ -391,8 +640,11 @@ pub fn set_fully_synced(conn: &Connection, fully_synced: bool) -> Result<(), DbE
 
 /// Updates coordinates for geocoded incidents.
 ///
-/// When `clear_attribution` is true, also clears census GEOIDs for
-/// re-attribution.
+/// The geocoded coordinate is recorded in `geocoded_longitude`/
+/// `geocoded_latitude` alongside any source coordinate, and becomes the
+/// incident's coordinate until [`apply_coordinate_precedence`] says
+/// otherwise. When `clear_attribution` is true, also clears census GEOIDs
+/// for re-attribution.
 ///
 /// # Errors
 ///
@@ -409,6 +661,8 @@ pub fn batch_update_geocoded(
     let sql = if clear_attribution {
         "UPDATE incidents SET
             longitude = ?, latitude = ?,
+            geocoded_longitude = ?, geocoded_latitude = ?,
+            coord_source = 'geocoded',
             geocoded = TRUE,
             census_place_geoid = NULL,
             census_tract_geoid = NULL,
@@ -420,6 +674,8 @@ pub fn batch_update_geocoded(
     } else {
         "UPDATE incidents SET
             longitude = ?, latitude = ?,
+            geocoded_longitude = ?, geocoded_latitude = ?,
+            coord_source = 'geocoded',
             has_coordinates = TRUE,
             geocoded = TRUE
          WHERE source_incident_id = ?"
@@ -429,7 +685,63 @@ pub fn batch_update_geocoded(
     let mut total = 0u64;
 
     for (incident_id, lng, lat) in updates {
-        let rows = stmt.execute(duckdb::params![lng, lat, incident_id])?;
+        let rows = stmt.execute(duckdb::params![lng, lat, lng, lat, incident_id])?;
+        total += u64::try_from(rows).unwrap_or(0);
+    }
+
+    Ok(total)
+}
+
+/// Points every incident that has a coordinate of the kind preferred by
+/// `precedence` at it, falling back to the other kind when it's the only
+/// one, and records the choice in `coord_source`.
+///
+/// Incidents whose coordinate changes lose their spatial attribution and
+/// are marked un-enriched, so the next enrichment re-attributes them.
+/// Incidents with neither column set (geocoded before provenance was
+/// tracked) are left alone.
+///
+/// Returns the number of incidents whose coordinate changed.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the update fails.
+pub fn apply_coordinate_precedence(
+    conn: &Connection,
+    precedence: CoordinatePrecedence,
+) -> Result<u64, DbError> {
+    let (preferred, fallback) = match precedence {
+        CoordinatePrecedence::Geocoded => ("geocoded", "source"),
+        CoordinatePrecedence::Source => ("source", "geocoded"),
+    };
+
+    let mut total = 0u64;
+    for (kind, only_if_missing) in [(preferred, None), (fallback, Some(preferred))] {
+        let missing = only_if_missing.map_or_else(String::new, |other| {
+            format!("AND ({other}_longitude IS NULL OR {other}_latitude IS NULL)")
+        });
+        let rows = conn.execute(
+            &format!(
+                "UPDATE incidents SET
+                    longitude = {kind}_longitude,
+                    latitude = {kind}_latitude,
+                    coord_source = '{kind}',
+                    has_coordinates = TRUE,
+                    census_place_geoid = NULL,
+                    census_tract_geoid = NULL,
+                    state_fips = NULL,
+                    county_geoid = NULL,
+                    neighborhood_id = NULL,
+                    enriched = FALSE
+                 WHERE {kind}_longitude IS NOT NULL
+                   AND {kind}_latitude IS NOT NULL
+                   {missing}
+                   AND (coord_source IS DISTINCT FROM '{kind}'
+                        OR longitude != {kind}_longitude
+                        OR latitude != {kind}_latitude)"
+            ),
+            [],
+        )?;
         total += u64::try_from(rows).unwrap_or(0);
     }
 
@@ -526,23 +838,403 @@ pub fn batch_update_attribution(
 
 /// Discovers all source `DuckDB` files in the sources directory.
 ///
-/// Returns a sorted list of source IDs (derived from filenames).
+/// Returns a sorted list of source IDs (derived from filenames). A sharded
+/// source is listed once.
 #[must_use]
 pub fn discover_source_ids() -> Vec<String> {
-    let dir = crate::paths::sources_dir();
-    let mut ids = Vec::new();
+    let ids: BTreeSet<String> = duckdb_stems(&crate::paths::sources_dir())
+        .map(|(stem, _)| {
+            split_shard_stem(&stem).map_or_else(|| stem.clone(), |(id, _)| id.to_string())
+        })
+        .collect();
+    ids.into_iter().collect()
+}
+
+/// Returns the write-ahead log `DuckDB` keeps next to the database at
+/// `path` (`{path}.wal`) while writes haven't been checkpointed into it.
+#[must_use]
+pub fn wal_path(path: &Path) -> PathBuf {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push(".wal");
+    PathBuf::from(wal)
+}
+
+/// Checkpoints the source `DuckDB` at `path` if it has a pending
+/// write-ahead log, so the main file holds every committed write, and
+/// returns the log's path if it's still there afterwards.
+///
+/// Copying or uploading only the `.duckdb` file of a database with a
+/// pending log silently drops the writes in the log. A log that survives
+/// the checkpoint usually means another process still has the database
+/// open (e.g. a sync in progress).
+///
+/// # Errors
+///
+/// Returns an error if the database can't be opened or checkpointed.
+pub fn checkpoint(path: &Path) -> Result<Option<PathBuf>, DbError> {
+    let wal = wal_path(path);
+    if !wal.exists() {
+        return Ok(None);
+    }
+
+    log::info!(
+        "Checkpointing pending write-ahead log of {}",
+        path.display()
+    );
+    {
+        // Opening replays the log; the schema is left as it is.
+        let conn = Connection::open(path)?;
+        conn.execute_batch("FORCE CHECKPOINT")?;
+    }
+    Ok(wal.exists().then_some(wal))
+}
+
+/// Returns every `DuckDB` file backing `source_id`: the unsharded
+/// `{source_id}.duckdb` first (if it exists), then its
+/// `{source_id}.{shard}.duckdb` shards sorted by shard name. Empty if the
+/// source has no files.
+#[must_use]
+pub fn shard_paths(source_id: &str) -> Vec<PathBuf> {
+    shard_paths_in(&crate::paths::sources_dir(), source_id)
+}
 
-    if let Ok(entries) = std::fs::read_dir(&dir) {
-        for entry in entries.flatten() {
+fn shard_paths_in(dir: &Path, source_id: &str) -> Vec<PathBuf> {
+    let mut shards: Vec<(String, PathBuf)> = duckdb_stems(dir)
+        .filter_map(|(stem, path)| {
+            let (id, shard) = split_shard_stem(&stem)?;
+            (id == source_id).then(|| (shard.to_string(), path))
+        })
+        .collect();
+    shards.sort();
+
+    let unsharded = dir.join(format!("{source_id}.duckdb"));
+    unsharded
+        .exists()
+        .then_some(unsharded)
+        .into_iter()
+        .chain(shards.into_iter().map(|(_, path)| path))
+        .collect()
+}
+
+/// Lists the `(file stem, path)` of every `*.duckdb` file in `dir`.
+fn duckdb_stems(dir: &Path) -> impl Iterator<Item = (String, PathBuf)> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
             let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) == Some("duckdb")
-                && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
-            {
-                ids.push(stem.to_string());
+            if path.extension().and_then(|e| e.to_str()) != Some("duckdb") {
+                return None;
             }
+            let stem = path.file_stem()?.to_str()?.to_string();
+            Some((stem, path))
+        })
+}
+
+/// Splits a shard file stem `{source_id}.{shard}` into the source ID and
+/// shard name. Returns `None` for an unsharded `{source_id}` stem.
+fn split_shard_stem(stem: &str) -> Option<(&str, &str)> {
+    stem.split_once('.')
+        .filter(|(id, shard)| !id.is_empty() && !shard.is_empty())
+}
+
+/// Keyset-paginated reader over the `incidents` of every shard of a
+/// source (see [Sharded sources](self#sharded-sources)), in shard order
+/// and then `rowid` order within each shard.
+pub struct ShardedIncidents {
+    shards: Vec<Connection>,
+    shard: usize,
+    last_rowid: i64,
+}
+
+impl ShardedIncidents {
+    /// Pages through the incidents of `shards`, given in the order of
+    /// [`shard_paths`].
+    #[must_use]
+    pub const fn new(shards: Vec<Connection>) -> Self {
+        Self {
+            shards,
+            shard: 0,
+            // rowids start at 0
+            last_rowid: -1,
+        }
+    }
+
+    /// Reads the next page of up to `limit` incidents matching `filter`
+    /// (a SQL predicate), calling `f` with each row. Column 0 of each row
+    /// is its shard-local `rowid`, followed by `columns` (a SQL select
+    /// list).
+    ///
+    /// A page continues into the next shard when one runs out, so it's
+    /// only shorter than `limit` once every shard has been read.
+    ///
+    /// Returns the number of rows read.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from a query or from `f`.
+    pub fn next_page<E, F>(
+        &mut self,
+        columns: &str,
+        filter: &str,
+        limit: i64,
+        mut f: F,
+    ) -> Result<u64, E>
+    where
+        E: From<duckdb::Error>,
+        F: FnMut(&duckdb::Row<'_>) -> Result<(), E>,
+    {
+        let mut read: i64 = 0;
+        while read < limit
+            && let Some(conn) = self.shards.get(self.shard)
+        {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT rowid, {columns}
+                 FROM incidents
+                 WHERE {filter}
+                   AND rowid > ?
+                 ORDER BY rowid ASC
+                 LIMIT ?",
+            ))?;
+            let wanted = limit - read;
+            let mut rows = stmt.query(duckdb::params![self.last_rowid, wanted])?;
+
+            let mut shard_read: i64 = 0;
+            while let Some(row) = rows.next()? {
+                self.last_rowid = row.get(0)?;
+                f(row)?;
+                shard_read += 1;
+            }
+            read += shard_read;
+
+            if shard_read < wanted {
+                self.shard += 1;
+                self.last_rowid = -1;
+            }
+        }
+
+        Ok(u64::try_from(read).unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_folds_a_pending_wal_into_the_database() {
+        let dir = std::env::temp_dir().join(format!("crime_map_wal_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("src.duckdb");
+
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE t (x INTEGER);
+                 CHECKPOINT;
+                 PRAGMA disable_checkpoint_on_shutdown;
+                 SET checkpoint_threshold = '1GB';
+                 INSERT INTO t VALUES (1), (2)",
+            )
+            .unwrap();
         }
+        assert!(wal_path(&path).exists());
+
+        assert_eq!(checkpoint(&path).unwrap(), None);
+        assert!(!wal_path(&path).exists());
+        let conn = Connection::open(&path).unwrap();
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 2);
+        drop(conn);
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
-    ids.sort();
-    ids
+    #[test]
+    fn sharded_incidents_page_across_shards_in_order() {
+        let dir = std::env::temp_dir().join(format!("crime_map_shards_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, ids) in [
+            ("chicago_pd.2024.duckdb", &["d", "e"][..]),
+            ("chicago_pd.duckdb", &["a"][..]),
+            ("chicago_pd.2023.duckdb", &["b", "c"][..]),
+            ("chicago_pd_old.duckdb", &["x"][..]),
+        ] {
+            let conn = open(&dir.join(file)).unwrap();
+            for id in ids {
+                conn.execute(
+                    "INSERT INTO incidents
+                        (source_incident_id, category, parent_category, severity,
+                         longitude, latitude)
+                     VALUES (?, 'THEFT', 'PROPERTY', 1, -87.6, 41.9)",
+                    [id],
+                )
+                .unwrap();
+            }
+        }
+
+        let paths = shard_paths_in(&dir, "chicago_pd");
+        let names: Vec<_> = paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "chicago_pd.duckdb",
+                "chicago_pd.2023.duckdb",
+                "chicago_pd.2024.duckdb"
+            ]
+        );
+
+        let shards = paths.iter().map(|p| open(p).unwrap()).collect();
+        let mut incidents = ShardedIncidents::new(shards);
+        let mut pages = Vec::new();
+        loop {
+            let mut page = Vec::new();
+            let read = incidents
+                .next_page::<duckdb::Error, _>("source_incident_id", "TRUE", 2, |row| {
+                    page.push(row.get::<_, String>(1)?);
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(read, page.len() as u64);
+            if page.is_empty() {
+                break;
+            }
+            pages.push(page);
+        }
+        assert_eq!(pages, vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn last_error_is_replaced_and_cleared() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        assert_eq!(get_last_error(&conn).unwrap(), None);
+
+        set_last_error(&conn, "server_error", "HTTP 503").unwrap();
+        set_last_error(&conn, "rate_limited", "HTTP 429").unwrap();
+        let last = get_last_error(&conn).unwrap().unwrap();
+        assert_eq!(
+            (last.kind.as_str(), last.message.as_str()),
+            ("rate_limited", "HTTP 429")
+        );
+        assert!(chrono::DateTime::parse_from_rfc3339(&last.failed_at).is_ok());
+
+        clear_last_error(&conn).unwrap();
+        assert_eq!(get_last_error(&conn).unwrap(), None);
+    }
+
+    #[test]
+    fn raw_records_upsert_keeping_the_last_record_per_id() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        let record = |id: &str, json: &str| (id.to_string(), json.to_string());
+
+        insert_raw_records(
+            &conn,
+            &[
+                record("1", r#"{"id":1,"v":"a"}"#),
+                record("2", r#"{"id":2}"#),
+                record("1", r#"{"id":1,"v":"b"}"#),
+            ],
+        )
+        .unwrap();
+        insert_raw_records(&conn, &[record("2", r#"{"id":2,"v":"c"}"#)]).unwrap();
+
+        let mut stmt = conn
+            .prepare("SELECT source_incident_id, raw_json FROM raw_records ORDER BY 1")
+            .unwrap();
+        let rows: Vec<(String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                record("1", r#"{"id":1,"v":"b"}"#),
+                record("2", r#"{"id":2,"v":"c"}"#),
+            ]
+        );
+    }
+
+    #[test]
+    fn coordinate_quality_counts_flags_and_out_of_bounds() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO incidents
+                (source_incident_id, category, parent_category, severity,
+                 longitude, latitude, has_coordinates, geocoded, enriched)
+             VALUES
+                ('1', 'THEFT', 'PROPERTY', 1, -87.6, 41.9, TRUE, FALSE, TRUE),
+                ('2', 'THEFT', 'PROPERTY', 1, -87.6, 41.9, TRUE, TRUE, TRUE),
+                ('3', 'THEFT', 'PROPERTY', 1, 41.9, -87.6, TRUE, FALSE, FALSE),
+                ('4', 'THEFT', 'PROPERTY', 1, 0.0, 0.0, FALSE, TRUE, FALSE)",
+        )
+        .unwrap();
+
+        assert_eq!(
+            coordinate_quality(&conn).unwrap(),
+            CoordinateQuality {
+                total: 4,
+                with_coordinates: 3,
+                geocoded: 1,
+                enriched: 2,
+                outside_us: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn coordinate_precedence_switches_and_keeps_both_coordinates() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO incidents
+                (source_incident_id, category, parent_category, severity,
+                 longitude, latitude, has_coordinates, geocoded, enriched,
+                 source_longitude, source_latitude, coord_source)
+             VALUES
+                ('1', 'THEFT', 'PROPERTY', 1, -87.60, 41.90, TRUE, FALSE, TRUE, -87.60, 41.90, 'source'),
+                ('2', 'THEFT', 'PROPERTY', 1, 0.0, 0.0, FALSE, FALSE, FALSE, NULL, NULL, NULL)",
+        )
+        .unwrap();
+        let updates = [
+            ("1".to_string(), -87.61, 41.91),
+            ("2".to_string(), -87.62, 41.92),
+        ];
+        batch_update_geocoded(&conn, &updates, false).unwrap();
+
+        let coords = |id: &str| -> (f64, f64, String, bool) {
+            conn.query_row(
+                "SELECT longitude, latitude, coord_source, enriched
+                 FROM incidents WHERE source_incident_id = ?",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap()
+        };
+        assert_eq!(coords("1"), (-87.61, 41.91, "geocoded".to_string(), true));
+        assert_eq!(
+            apply_coordinate_precedence(&conn, CoordinatePrecedence::Geocoded).unwrap(),
+            0
+        );
+
+        // Preferring the source restores its coordinate and re-enriches;
+        // incident 2 only has a geocoded coordinate, so it keeps it.
+        assert_eq!(
+            apply_coordinate_precedence(&conn, CoordinatePrecedence::Source).unwrap(),
+            1
+        );
+        assert_eq!(coords("1"), (-87.60, 41.90, "source".to_string(), false));
+        assert_eq!(coords("2"), (-87.62, 41.92, "geocoded".to_string(), false));
+    }
 }
@@ -2,3 +2,5 @@
 //!
 //! Each submodule corresponds to a top-level CLI subcommand. Modules are
 //! added here as commands are implemented beyond their initial stubs.
+
+pub mod probe;
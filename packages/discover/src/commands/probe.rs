@@ -0,0 +1,534 @@
+//! Automatic classification of unknown data source URLs.
+//!
+//! [`probe`] fetches a small sample from a URL, detects which open-data
+//! platform serves it, inspects a sample record for coordinates and dates,
+//! and asks the platform for its total record count. The result is a
+//! partially-filled [`Lead`] for a human to review before saving.
+//!
+//! Platform detection is table-driven: each entry in [`PLATFORMS`] knows
+//! how to recognize its URLs and response shapes, how to build sample and
+//! count requests, and how to pull records and counts out of responses.
+//! Supporting a new platform means adding one entry.
+
+use crime_map_discover_models::{ApiType, CoordinateType, Lead, LeadStatus, Priority};
+use serde_json::Value;
+
+/// Number of records requested when sampling a source.
+const SAMPLE_SIZE: u32 = 5;
+
+/// Result of probing a URL.
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    /// The platform that was detected.
+    pub api_type: ApiType,
+    /// The URL actually fetched for the sample.
+    pub sample_url: String,
+    /// Number of sample records returned.
+    pub sample_size: usize,
+    /// A partially-filled lead (id `0`, status `new`) ready for review.
+    pub lead: Lead,
+}
+
+/// Detection and request rules for one open-data platform.
+struct PlatformProbe {
+    /// The API type reported when this platform matches.
+    api_type: ApiType,
+    /// Case-insensitive URL substrings that identify the platform.
+    url_patterns: &'static [&'static str],
+    /// Builds the sample request URL from the user-provided URL.
+    sample_url: fn(&str) -> String,
+    /// Builds a dedicated count request URL, if the platform has one.
+    count_url: Option<fn(&str) -> String>,
+    /// Returns `true` if a JSON response has this platform's shape.
+    matches_response: fn(&Value) -> bool,
+    /// Extracts sample records (flattened to JSON objects) from a response.
+    extract_records: fn(&Value) -> Vec<Value>,
+    /// Extracts a total record count from a count (or sample) response.
+    extract_count: fn(&Value) -> Option<i64>,
+}
+
+/// Known platforms, checked in order.
+const PLATFORMS: &[PlatformProbe] = &[
+    PlatformProbe {
+        api_type: ApiType::Socrata,
+        url_patterns: &["/resource/", "/api/views/"],
+        sample_url: socrata_sample_url,
+        count_url: Some(socrata_count_url),
+        matches_response: socrata_matches,
+        extract_records: socrata_records,
+        extract_count: socrata_count,
+    },
+    PlatformProbe {
+        api_type: ApiType::Arcgis,
+        url_patterns: &["/featureserver", "/mapserver", "/arcgis/rest/"],
+        sample_url: arcgis_sample_url,
+        count_url: Some(arcgis_count_url),
+        matches_response: arcgis_matches,
+        extract_records: arcgis_records,
+        extract_count: arcgis_count,
+    },
+    PlatformProbe {
+        api_type: ApiType::Ckan,
+        url_patterns: &["/api/3/action/", "datastore_search"],
+        sample_url: ckan_sample_url,
+        count_url: None,
+        matches_response: ckan_matches,
+        extract_records: ckan_records,
+        extract_count: ckan_count,
+    },
+];
+
+/// Probes a URL and returns a partially-classified lead.
+///
+/// The platform is first guessed from the URL; if no pattern matches, the
+/// raw URL is fetched and each platform's response-shape check is tried.
+///
+/// # Errors
+///
+/// Returns an error if the URL cannot be fetched, the response is not
+/// JSON, or no known platform matches.
+pub async fn probe(url: &str) -> Result<ProbeResult, Box<dyn std::error::Error>> {
+    let client = reqwest::Client::builder()
+        .user_agent("crime-map/1.0 (https://github.com/BSteffaniak/crime-map)")
+        .timeout(std::time::Duration::from_secs(60))
+        .build()?;
+
+    let (platform, sample_url, sample) = if let Some(platform) = platform_for_url(url) {
+        let sample_url = (platform.sample_url)(url);
+        let sample = fetch_json(&client, &sample_url).await?;
+        (platform, sample_url, sample)
+    } else {
+        let raw = fetch_json(&client, url).await?;
+        let platform = PLATFORMS
+            .iter()
+            .find(|p| (p.matches_response)(&raw))
+            .ok_or("Could not detect platform from URL or response shape")?;
+        let sample_url = (platform.sample_url)(url);
+        let sample = fetch_json(&client, &sample_url).await?;
+        (platform, sample_url, sample)
+    };
+
+    if !(platform.matches_response)(&sample) {
+        return Err(format!(
+            "Response from {sample_url} does not look like a {} API",
+            platform.api_type
+        )
+        .into());
+    }
+
+    let records = (platform.extract_records)(&sample);
+    let first = records.first();
+
+    let record_count = match platform.count_url {
+        Some(count_url) => match fetch_json(&client, &count_url(url)).await {
+            Ok(body) => (platform.extract_count)(&body),
+            Err(e) => {
+                log::warn!("Count request failed: {e}");
+                None
+            }
+        },
+        None => (platform.extract_count)(&sample),
+    };
+
+    let coordinate_type = first.map_or(CoordinateType::None, detect_coordinate_type);
+    let has_dates = first.map(detect_has_dates);
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let lead = Lead {
+        id: 0,
+        jurisdiction: String::new(),
+        source_name: url_host(url).unwrap_or(url).to_string(),
+        api_type: Some(platform.api_type),
+        url: Some(url.to_string()),
+        status: LeadStatus::New,
+        priority: Priority::Medium,
+        likelihood: None,
+        record_count,
+        has_coordinates: first.map(|_| {
+            matches!(
+                coordinate_type,
+                CoordinateType::PointGeometry
+                    | CoordinateType::LatLngF64
+                    | CoordinateType::LatLngString
+            )
+        }),
+        has_dates,
+        coordinate_type: first.map(|_| coordinate_type),
+        date_format: None,
+        sample_record: first.and_then(|r| serde_json::to_string_pretty(r).ok()),
+        field_notes: first.and_then(Value::as_object).map(|obj| {
+            format!(
+                "Fields: {}",
+                obj.keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }),
+        distance_from_dc_miles: None,
+        notes: Some(format!("Auto-probed from {sample_url}")),
+        discovered_at: now.clone(),
+        updated_at: now,
+        investigated_at: None,
+    };
+
+    Ok(ProbeResult {
+        api_type: platform.api_type,
+        sample_url,
+        sample_size: records.len(),
+        lead,
+    })
+}
+
+/// Returns the first platform whose URL patterns match `url`.
+fn platform_for_url(url: &str) -> Option<&'static PlatformProbe> {
+    let lower = url.to_ascii_lowercase();
+    PLATFORMS
+        .iter()
+        .find(|p| p.url_patterns.iter().any(|pat| lower.contains(pat)))
+}
+
+/// Fetches a URL and parses the body as JSON.
+async fn fetch_json(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    log::info!("Probing {url}");
+    let resp = client.get(url).send().await?.error_for_status()?;
+    Ok(resp.json::<Value>().await?)
+}
+
+/// Returns the host portion of a URL, if present.
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, r)| r);
+    rest.split(['/', '?']).next().filter(|h| !h.is_empty())
+}
+
+/// Strips any query string from a URL.
+fn strip_query(url: &str) -> &str {
+    url.split('?').next().unwrap_or(url)
+}
+
+// ---------------------------------------------------------------------------
+// Socrata
+// ---------------------------------------------------------------------------
+
+/// Normalizes a Socrata URL to its `/resource/` endpoint, mapping a
+/// `/api/views/{id}` metadata URL to `/resource/{id}.json`.
+fn socrata_resource_url(url: &str) -> String {
+    let base = strip_query(url);
+    let lower = base.to_ascii_lowercase();
+    let Some(start) = lower.find("/api/views/") else {
+        return base.to_string();
+    };
+    let id = base[start + "/api/views/".len()..]
+        .split(['/', '.'])
+        .next()
+        .unwrap_or_default();
+    format!("{}/resource/{id}.json", &base[..start])
+}
+
+fn socrata_sample_url(url: &str) -> String {
+    format!("{}?$limit={SAMPLE_SIZE}", socrata_resource_url(url))
+}
+
+fn socrata_count_url(url: &str) -> String {
+    format!("{}?$select=count(*)", socrata_resource_url(url))
+}
+
+fn socrata_matches(body: &Value) -> bool {
+    body.as_array()
+        .is_some_and(|rows| rows.iter().all(Value::is_object))
+}
+
+fn socrata_records(body: &Value) -> Vec<Value> {
+    body.as_array().cloned().unwrap_or_default()
+}
+
+fn socrata_count(body: &Value) -> Option<i64> {
+    let row = body.as_array()?.first()?.as_object()?;
+    let value = row.get("count").or_else(|| row.values().next())?;
+    value
+        .as_i64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+// ---------------------------------------------------------------------------
+// ArcGIS
+// ---------------------------------------------------------------------------
+
+/// Normalizes an `ArcGIS` URL to a layer endpoint (without `/query`),
+/// defaulting to layer `0` when a bare service URL is given.
+fn arcgis_layer_url(url: &str) -> String {
+    let base = strip_query(url).trim_end_matches('/');
+    let base = base.strip_suffix("/query").unwrap_or(base);
+    let lower = base.to_ascii_lowercase();
+    if lower.ends_with("/featureserver") || lower.ends_with("/mapserver") {
+        format!("{base}/0")
+    } else {
+        base.to_string()
+    }
+}
+
+fn arcgis_sample_url(url: &str) -> String {
+    format!(
+        "{}/query?where=1%3D1&outFields=*&resultRecordCount={SAMPLE_SIZE}&f=json",
+        arcgis_layer_url(url)
+    )
+}
+
+fn arcgis_count_url(url: &str) -> String {
+    format!(
+        "{}/query?where=1%3D1&returnCountOnly=true&f=json",
+        arcgis_layer_url(url)
+    )
+}
+
+fn arcgis_matches(body: &Value) -> bool {
+    body.get("features").is_some_and(Value::is_array)
+        || body.get("layers").is_some_and(Value::is_array)
+}
+
+/// Flattens each feature's `attributes` and keeps its `geometry` under a
+/// `geometry` key so coordinate detection sees both.
+fn arcgis_records(body: &Value) -> Vec<Value> {
+    body.get("features")
+        .and_then(Value::as_array)
+        .map(|features| {
+            features
+                .iter()
+                .map(|f| {
+                    let mut obj = f
+                        .get("attributes")
+                        .and_then(Value::as_object)
+                        .cloned()
+                        .unwrap_or_default();
+                    if let Some(geometry) = f.get("geometry") {
+                        obj.insert("geometry".to_string(), geometry.clone());
+                    }
+                    Value::Object(obj)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn arcgis_count(body: &Value) -> Option<i64> {
+    body.get("count").and_then(Value::as_i64)
+}
+
+// ---------------------------------------------------------------------------
+// CKAN
+// ---------------------------------------------------------------------------
+
+fn ckan_sample_url(url: &str) -> String {
+    let sep = if url.contains('?') { '&' } else { '?' };
+    format!("{url}{sep}limit={SAMPLE_SIZE}")
+}
+
+fn ckan_matches(body: &Value) -> bool {
+    body.get("success").is_some_and(Value::is_boolean) && body.get("result").is_some()
+}
+
+fn ckan_records(body: &Value) -> Vec<Value> {
+    body.pointer("/result/records")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn ckan_count(body: &Value) -> Option<i64> {
+    body.pointer("/result/total").and_then(Value::as_i64)
+}
+
+// ---------------------------------------------------------------------------
+// Record inspection
+// ---------------------------------------------------------------------------
+
+/// Guesses how a sample record represents location.
+fn detect_coordinate_type(record: &Value) -> CoordinateType {
+    let Some(obj) = record.as_object() else {
+        return CoordinateType::None;
+    };
+
+    // Point geometries: GeoJSON `{"type": "Point", "coordinates": [...]}`
+    // or ArcGIS `{"x": .., "y": ..}`.
+    let is_point = |v: &Value| {
+        v.get("type").and_then(Value::as_str) == Some("Point")
+            || (v.get("x").is_some_and(Value::is_number)
+                && v.get("y").is_some_and(Value::is_number))
+    };
+    if obj.values().any(is_point) {
+        return CoordinateType::PointGeometry;
+    }
+
+    let find = |needles: &[&str]| {
+        obj.iter().find_map(|(k, v)| {
+            let key = k.to_ascii_lowercase();
+            needles.iter().any(|n| key.contains(n)).then_some(v)
+        })
+    };
+    // Coordinate names are short enough to appear inside other words
+    // (`plate`, `relation`), so they must be whole words of the key.
+    let find_word = |needles: &[&str]| {
+        obj.iter().find_map(|(k, v)| {
+            key_words(k)
+                .iter()
+                .any(|w| needles.contains(&w.as_str()))
+                .then_some(v)
+        })
+    };
+
+    if let (Some(lat), Some(_)) = (
+        find_word(&["latitude", "lat"]),
+        find_word(&["longitude", "lng", "lon"]),
+    ) {
+        if lat.is_number() {
+            return CoordinateType::LatLngF64;
+        }
+        if lat
+            .as_str()
+            .is_some_and(|s| s.trim().parse::<f64>().is_ok())
+        {
+            return CoordinateType::LatLngString;
+        }
+    }
+
+    if find(&["address", "block", "street"]).is_some_and(Value::is_string) {
+        return CoordinateType::AddressOnly;
+    }
+
+    CoordinateType::None
+}
+
+/// Splits a field name into lowercase words at `_`, `-`, spaces, dots,
+/// and `camelCase` humps (`incidentLat` → `incident`, `lat`).
+fn key_words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in key.chars() {
+        if !c.is_ascii_alphanumeric() {
+            words.push(std::mem::take(&mut word));
+            prev_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        word.push(c.to_ascii_lowercase());
+    }
+    words.push(word);
+    words.retain(|w| !w.is_empty());
+    words
+}
+
+/// Returns `true` if a sample record appears to contain a date field.
+fn detect_has_dates(record: &Value) -> bool {
+    let Some(obj) = record.as_object() else {
+        return false;
+    };
+
+    obj.iter().any(|(k, v)| {
+        let key = k.to_ascii_lowercase();
+        let named_like_date = ["date", "time", "occurred", "reported"]
+            .iter()
+            .any(|n| key.contains(n));
+        let iso_like = v.as_str().is_some_and(|s| {
+            s.len() >= 10
+                && s.as_bytes()[..4].iter().all(u8::is_ascii_digit)
+                && s.as_bytes()[4] == b'-'
+        });
+        (named_like_date && !v.is_null()) || iso_like
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn platform(url: &str) -> Option<ApiType> {
+        platform_for_url(url).map(|p| p.api_type)
+    }
+
+    #[test]
+    fn detects_platforms_from_url_patterns() {
+        assert_eq!(
+            platform("https://data.cityofchicago.org/resource/ijzp-q8t2.json"),
+            Some(ApiType::Socrata)
+        );
+        assert_eq!(
+            platform("https://data.example.gov/api/views/abcd-1234"),
+            Some(ApiType::Socrata)
+        );
+        assert_eq!(
+            platform("https://services.arcgis.com/x/ArcGIS/rest/services/Crime/FeatureServer"),
+            Some(ApiType::Arcgis)
+        );
+        assert_eq!(
+            platform("https://data.example.ca/api/3/action/datastore_search?resource_id=1"),
+            Some(ApiType::Ckan)
+        );
+        assert_eq!(platform("https://example.com/crime.csv"), None);
+    }
+
+    #[test]
+    fn builds_sample_urls_for_each_platform() {
+        assert_eq!(
+            socrata_sample_url("https://data.example.gov/api/views/abcd-1234.json?foo=1"),
+            "https://data.example.gov/resource/abcd-1234.json?$limit=5"
+        );
+        assert_eq!(
+            socrata_count_url("https://data.example.gov/resource/abcd-1234.json"),
+            "https://data.example.gov/resource/abcd-1234.json?$select=count(*)"
+        );
+        assert_eq!(
+            arcgis_sample_url("https://x.com/arcgis/rest/services/Crime/MapServer/"),
+            "https://x.com/arcgis/rest/services/Crime/MapServer/0/query?where=1%3D1&outFields=*\
+             &resultRecordCount=5&f=json"
+        );
+        assert_eq!(
+            ckan_sample_url("https://x.ca/api/3/action/datastore_search?resource_id=1"),
+            "https://x.ca/api/3/action/datastore_search?resource_id=1&limit=5"
+        );
+    }
+
+    #[test]
+    fn detects_coordinates_only_from_whole_words() {
+        assert_eq!(
+            detect_coordinate_type(&json!({"geometry": {"x": -87.6, "y": 41.8}})),
+            CoordinateType::PointGeometry
+        );
+        assert_eq!(
+            detect_coordinate_type(&json!({"Latitude": 41.8, "Longitude": -87.6})),
+            CoordinateType::LatLngF64
+        );
+        assert_eq!(
+            detect_coordinate_type(&json!({"incidentLat": "41.8", "incident_lon": "-87.6"})),
+            CoordinateType::LatLngString
+        );
+        assert_eq!(
+            detect_coordinate_type(&json!({
+                "plate": 1.5,
+                "relation": 2.0,
+                "colony": 3.0,
+                "block_address": "100 N STATE ST"
+            })),
+            CoordinateType::AddressOnly
+        );
+        assert_eq!(
+            detect_coordinate_type(&json!({"category": "THEFT"})),
+            CoordinateType::None
+        );
+    }
+
+    #[test]
+    fn detects_dates_by_name_or_iso_value() {
+        assert!(detect_has_dates(&json!({"occurred_on": "03/01/2024"})));
+        assert!(detect_has_dates(&json!({"when": "2024-03-01T14:30:00"})));
+        assert!(!detect_has_dates(&json!({"date": null, "id": "123"})));
+    }
+}
@@ -12,7 +12,8 @@
 use std::path::Path;
 
 use crime_map_discover_models::{
-    ApiPattern, GeocodingCandidate, Lead, LegalInfo, ScrapeTarget, SearchEntry, Source,
+    ApiPattern, CoordinateType, GeocodingCandidate, Lead, LegalInfo, ScrapeTarget, SearchEntry,
+    Source,
 };
 use moosicbox_json_utils::database::ToValue as _;
 use switchy_database::{Database, DatabaseValue};
@@ -494,6 +495,39 @@ pub async fn update_lead(
     Ok(())
 }
 
+/// Records what a probe found about a lead: its record count, whether it
+/// has coordinates and dates, its coordinate type, a sample record, and
+/// notes on its fields, all taken from `probed`.
+///
+/// Always bumps `updated_at` to the current UTC time.
+///
+/// # Errors
+///
+/// Returns [`DbError`] if the update fails.
+pub async fn update_lead_probe(db: &dyn Database, id: i64, probed: &Lead) -> Result<(), DbError> {
+    let now = chrono::Utc::now().to_rfc3339();
+
+    db.exec_raw_params(
+        "UPDATE leads SET record_count = ?, has_coordinates = ?, has_dates = ?,
+             coordinate_type = ?, sample_record = ?, field_notes = ?, updated_at = ?
+         WHERE id = ?",
+        &[
+            opt_i64(probed.record_count),
+            opt_bool(probed.has_coordinates),
+            opt_bool(probed.has_dates),
+            opt_str(probed.coordinate_type.map(CoordinateType::as_str)),
+            opt_str(probed.sample_record.as_deref()),
+            opt_str(probed.field_notes.as_deref()),
+            DatabaseValue::String(now),
+            DatabaseValue::Int64(id),
+        ],
+    )
+    .await
+    .map_err(|e| DbError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
 /// Retrieves all legal information records, ordered by review date descending.
 ///
 /// # Errors
@@ -110,6 +110,21 @@ pub enum Commands {
         source_id: Option<String>,
     },
 
+    /// Fetch a sample from an unknown URL and classify it (platform,
+    /// coordinates, dates, record count).
+    Probe {
+        /// Data source URL (Socrata resource, `ArcGIS` layer, CKAN datastore).
+        url: String,
+
+        /// Jurisdiction name to record if the lead is saved.
+        #[arg(long)]
+        jurisdiction: Option<String>,
+
+        /// Save the probed result as a new lead.
+        #[arg(long)]
+        save: bool,
+    },
+
     /// Suggest next discovery actions.
     Suggest {
         /// Geographic region to focus on.
@@ -1898,6 +1913,66 @@ pub async fn cmd_verify(
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Probe command
+// ---------------------------------------------------------------------------
+
+/// Probes a URL, prints the detected classification, and optionally saves
+/// it as a new lead.
+///
+/// # Errors
+///
+/// Returns an error if the probe or the database insert fails.
+pub async fn cmd_probe(
+    database: &dyn Database,
+    url: &str,
+    jurisdiction: Option<String>,
+    save: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let result = commands::probe::probe(url).await?;
+    let lead = &result.lead;
+
+    println!("=== Probe: {url} ===");
+    println!("Platform:     {}", result.api_type);
+    println!("Sample URL:   {}", result.sample_url);
+    println!("Sample Size:  {}", result.sample_size);
+    println!("Record Count: {}", opt_display(lead.record_count));
+    println!("Has Coords:   {}", bool_str(lead.has_coordinates));
+    println!("Has Dates:    {}", bool_str(lead.has_dates));
+    println!(
+        "Coord Type:   {}",
+        lead.coordinate_type.as_ref().map_or("-", |t| t.as_str())
+    );
+    if let Some(ref fields) = lead.field_notes {
+        println!("{fields}");
+    }
+    if let Some(ref sample) = lead.sample_record {
+        println!();
+        println!("Sample record:");
+        println!("{sample}");
+    }
+
+    if save {
+        let jurisdiction = jurisdiction.unwrap_or_else(|| "Unknown".to_string());
+        let id = db::insert_lead(
+            database,
+            &jurisdiction,
+            &lead.source_name,
+            Some(result.api_type.as_str()),
+            lead.url.as_deref(),
+            lead.priority.as_str(),
+            None,
+            lead.notes.as_deref(),
+        )
+        .await?;
+        db::update_lead_probe(database, id, lead).await?;
+        println!();
+        println!("Saved as lead #{id} (review with `leads investigate {id}`)");
+    }
+
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Suggest command (stub)
 // ---------------------------------------------------------------------------
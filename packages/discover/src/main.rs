@@ -65,6 +65,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Verify { source_id } => {
             crime_map_discover::cmd_verify(database.as_ref(), source_id).await
         }
+        Commands::Probe {
+            url,
+            jurisdiction,
+            save,
+        } => crime_map_discover::cmd_probe(database.as_ref(), &url, jurisdiction, save).await,
         Commands::Suggest { region } => {
             crime_map_discover::cmd_suggest(database.as_ref(), region).await
         }
@@ -0,0 +1,23 @@
+//! Embeds the git commit that built the generator as
+//! `CRIME_MAP_GIT_COMMIT`, or `unknown` when git info isn't available
+//! (e.g. building from a source tarball).
+
+use std::process::Command;
+
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=CRIME_MAP_GIT_COMMIT={commit}");
+
+    // Re-run when HEAD moves (new commit or branch switch).
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../.git/refs/heads");
+}
@@ -0,0 +1,388 @@
+//! Known-bad incidents excluded from every generated output.
+//!
+//! A blocklist lets operators drop individual records that can't be fixed
+//! upstream (test records, coordinates that pass the range filter but are
+//! obviously wrong) without editing the source `DuckDB`s. It is read from
+//! TOML (or JSON when the file ends in `.json`):
+//!
+//! ```toml
+//! [[incidents]]
+//! source = "chicago_pd"
+//! id = "JE123456"
+//! reason = "test record" # optional, for humans
+//! ```
+//!
+//! `id` is the incident's `source_incident_id`. Blocked incidents are
+//! excluded in the SQL of every incident query (see [`Blocklist::apply`]),
+//! so they also don't count towards progress totals. The IDs aren't
+//! inlined into the SQL: `--dedup-sources` can add hundreds of thousands
+//! of them, so each source's are loaded into a temp table on the querying
+//! connection (see [`Blocklist::load_into`]) and anti-joined.
+//!
+//! Sources' declared `export_exclude` filters are applied the same way
+//! (see [`Blocklist::restrict`]).
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::GenerateError;
+use crate::remap::content_hash;
+
+/// A single blocked incident.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockedIncident {
+    /// Source ID the incident belongs to.
+    pub source: String,
+    /// The incident's `source_incident_id`.
+    pub id: String,
+    /// Why the incident is blocked. Not used by generation.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlocklistFile {
+    #[serde(default)]
+    incidents: Vec<BlockedIncident>,
+}
+
+/// A loaded blocklist. The default value blocks nothing.
+#[derive(Debug, Default, Clone)]
+pub struct Blocklist {
+    ids: BTreeMap<String, BTreeSet<String>>,
+    /// Per-source SQL predicates incidents must satisfy to be exported.
+    predicates: BTreeMap<String, String>,
+    hash: Option<String>,
+}
+
+impl Blocklist {
+    /// Loads the blocklist at `path`, or returns an empty blocklist when
+    /// `path` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GenerateError::Io`] if the file can't be read and
+    /// [`GenerateError::InvalidBlocklist`] if it can't be parsed or an
+    /// entry has an empty source or ID.
+    pub fn load(path: Option<&Path>) -> Result<Self, GenerateError> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let bytes = std::fs::read(path)?;
+        let text = std::str::from_utf8(&bytes).map_err(|e| invalid(path, &e))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let file: BlocklistFile = if is_json {
+            serde_json::from_str(text).map_err(|e| invalid(path, &e))?
+        } else {
+            toml::from_str(text).map_err(|e| invalid(path, &e))?
+        };
+
+        if let Some(i) = file
+            .incidents
+            .iter()
+            .position(|b| b.source.trim().is_empty() || b.id.trim().is_empty())
+        {
+            return Err(invalid(
+                path,
+                &format!("entry {} has an empty source or id", i + 1),
+            ));
+        }
+
+        Ok(Self {
+            hash: Some(content_hash(&bytes)),
+            ..Self::from_entries(file.incidents)
+        })
+    }
+
+    /// Builds a blocklist directly from `entries` (no backing file).
+    #[must_use]
+    pub fn from_entries(entries: Vec<BlockedIncident>) -> Self {
+        let mut ids: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for entry in entries {
+            ids.entry(entry.source).or_default().insert(entry.id);
+        }
+        Self {
+            ids,
+            ..Self::default()
+        }
+    }
+
+    /// Keeps only `source_id`'s incidents matching the SQL `predicate`, a
+    /// source's validated `export_exclude` filter. The predicate is folded
+    /// into [`hash`](Self::hash), so changing it triggers regeneration.
+    pub fn restrict(&mut self, source_id: &str, predicate: String) {
+        let hash = content_hash(
+            format!(
+                "{}\n{source_id}\t{predicate}",
+                self.hash.as_deref().unwrap_or_default()
+            )
+            .as_bytes(),
+        );
+        self.hash = Some(hash);
+        self.predicates.insert(source_id.to_string(), predicate);
+    }
+
+    /// Adds `source_id`'s `ids` to the blocklist without changing its
+    /// [`hash`](Self::hash).
+    pub fn extend(&mut self, source_id: &str, ids: impl IntoIterator<Item = String>) {
+        let ids: BTreeSet<String> = ids.into_iter().collect();
+        if !ids.is_empty() {
+            self.ids
+                .entry(source_id.to_string())
+                .or_default()
+                .extend(ids);
+        }
+    }
+
+    /// Returns the SHA-256 of the blocklist file contents, or `None` if no
+    /// file was loaded. Recorded in the manifest so editing the blocklist
+    /// triggers regeneration.
+    #[must_use]
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    /// Returns the number of blocked incidents across all sources.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.values().map(BTreeSet::len).sum()
+    }
+
+    /// Returns `true` if nothing is blocked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns `source_id`'s blocked IDs (empty if none are blocked).
+    #[must_use]
+    pub fn blocked_ids(&self, source_id: &str) -> BTreeSet<String> {
+        self.ids.get(source_id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the name of the temp table [`load_into`](Self::load_into)
+    /// fills with `source_id`'s blocked IDs, or `None` if none of its
+    /// incidents are blocked.
+    #[must_use]
+    pub fn sql_table(&self, source_id: &str) -> Option<String> {
+        self.ids
+            .contains_key(source_id)
+            .then(|| blocked_ids_table(source_id))
+    }
+
+    /// Loads `source_id`'s blocked IDs into its temp table on `conn`, which
+    /// every query using [`apply`](Self::apply)'s filter for `source_id`
+    /// must run on. Does nothing if none of its incidents are blocked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the temp table can't be created or filled.
+    pub fn load_into(
+        &self,
+        conn: &duckdb::Connection,
+        source_id: &str,
+    ) -> Result<(), GenerateError> {
+        match self.ids.get(source_id) {
+            Some(ids) => load_blocked_ids(conn, source_id, ids),
+            None => Ok(()),
+        }
+    }
+
+    /// Extends the SQL predicate `filter` to exclude `source_id`'s blocked
+    /// incidents and apply its [`restrict`](Self::restrict) predicate.
+    /// Returns `filter` unchanged if neither applies.
+    ///
+    /// Blocked incidents are anti-joined against the temp table
+    /// [`load_into`](Self::load_into) creates, so the filter only works on
+    /// connections it was called on.
+    #[must_use]
+    pub fn apply<'a>(&self, filter: &'a str, source_id: &str) -> Cow<'a, str> {
+        let table = self.sql_table(source_id);
+        let predicate = self.predicates.get(source_id);
+        if table.is_none() && predicate.is_none() {
+            return Cow::Borrowed(filter);
+        }
+
+        let mut filter = filter.to_string();
+        if let Some(table) = table {
+            write!(
+                filter,
+                "
+               AND source_incident_id NOT IN (SELECT source_incident_id FROM {table})"
+            )
+            .unwrap();
+        }
+        if let Some(predicate) = predicate {
+            write!(
+                filter,
+                "
+               AND ({predicate})"
+            )
+            .unwrap();
+        }
+        Cow::Owned(filter)
+    }
+}
+
+/// Rows inserted per statement by [`load_blocked_ids`].
+const LOAD_CHUNK: usize = 1000;
+
+/// Returns the quoted name of `source_id`'s blocked-IDs temp table.
+fn blocked_ids_table(source_id: &str) -> String {
+    format!("\"blocked_ids_{}\"", source_id.replace('"', "\"\""))
+}
+
+/// Replaces `source_id`'s blocked-IDs temp table on `conn` with `ids`.
+///
+/// # Errors
+///
+/// Returns an error if the temp table can't be created or filled.
+pub(crate) fn load_blocked_ids(
+    conn: &duckdb::Connection,
+    source_id: &str,
+    ids: &BTreeSet<String>,
+) -> Result<(), GenerateError> {
+    let table = blocked_ids_table(source_id);
+    conn.execute_batch(&format!(
+        "CREATE OR REPLACE TEMP TABLE {table} (source_incident_id VARCHAR PRIMARY KEY)"
+    ))?;
+    let ids: Vec<&String> = ids.iter().collect();
+    for chunk in ids.chunks(LOAD_CHUNK) {
+        let values = vec!["(?)"; chunk.len()].join(", ");
+        conn.execute(
+            &format!("INSERT INTO {table} VALUES {values}"),
+            duckdb::params_from_iter(chunk),
+        )?;
+    }
+    Ok(())
+}
+
+fn invalid(path: &Path, reason: &dyn std::fmt::Display) -> GenerateError {
+    GenerateError::InvalidBlocklist {
+        path: PathBuf::from(path),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocked(source: &str, id: &str) -> BlockedIncident {
+        BlockedIncident {
+            source: source.to_string(),
+            id: id.to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn apply_excludes_only_the_sources_blocked_ids() {
+        let blocklist = Blocklist::from_entries(vec![
+            blocked("chicago_pd", "JE1"),
+            blocked("chicago_pd", "O'Brien"),
+            blocked("la_pd", "LA1"),
+        ]);
+        assert_eq!(blocklist.len(), 3);
+
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE incidents (source_incident_id VARCHAR, has_coordinates BOOLEAN);
+             INSERT INTO incidents VALUES
+                 ('JE1', TRUE), ('O''Brien', TRUE), ('LA1', TRUE), ('JE2', TRUE), ('JE3', FALSE);",
+        )
+        .unwrap();
+        let exported = |source_id: &str| -> Vec<String> {
+            blocklist.load_into(&duck, source_id).unwrap();
+            let filter = blocklist.apply("has_coordinates = TRUE", source_id);
+            let mut stmt = duck
+                .prepare(&format!(
+                    "SELECT source_incident_id FROM incidents WHERE {filter} ORDER BY 1"
+                ))
+                .unwrap();
+            stmt.query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        assert_eq!(exported("chicago_pd"), ["JE2", "LA1"]);
+        assert_eq!(exported("la_pd"), ["JE1", "JE2", "O'Brien"]);
+
+        assert!(matches!(
+            blocklist.apply("has_coordinates = TRUE", "sf_pd"),
+            Cow::Borrowed("has_coordinates = TRUE")
+        ));
+    }
+
+    #[test]
+    fn blocked_ids_load_in_chunks() {
+        let ids: BTreeSet<String> = (0..LOAD_CHUNK * 2 + 1).map(|i| format!("id-{i}")).collect();
+        let blocklist =
+            Blocklist::from_entries(ids.iter().map(|id| blocked("big_pd", id)).collect());
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        blocklist.load_into(&duck, "big_pd").unwrap();
+        // Loading again replaces the table rather than failing on its key.
+        blocklist.load_into(&duck, "big_pd").unwrap();
+
+        let table = blocklist.sql_table("big_pd").unwrap();
+        let count: i64 = duck
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, i64::try_from(ids.len()).unwrap());
+        assert!(blocklist.sql_table("sf_pd").is_none());
+    }
+
+    #[test]
+    fn restrict_appends_the_sources_predicate_and_changes_the_hash() {
+        let mut blocklist = Blocklist::from_entries(vec![blocked("chicago_pd", "JE1")]);
+        blocklist.restrict("chicago_pd", "block_address != 'REDACTED'".to_string());
+        let first = blocklist.hash().unwrap().to_string();
+
+        let filter = blocklist.apply("has_coordinates = TRUE", "chicago_pd");
+        assert!(
+            filter.ends_with(
+                "AND source_incident_id NOT IN (SELECT source_incident_id FROM \"blocked_ids_chicago_pd\")
+               AND (block_address != 'REDACTED')"
+            ),
+            "{filter}"
+        );
+        assert!(matches!(
+            blocklist.apply("has_coordinates = TRUE", "sf_pd"),
+            Cow::Borrowed(_)
+        ));
+
+        blocklist.restrict("chicago_pd", "block_address != 'UNKNOWN'".to_string());
+        assert_ne!(blocklist.hash().unwrap(), first);
+    }
+
+    #[test]
+    fn load_rejects_empty_ids_and_hashes_contents() {
+        let dir = std::env::temp_dir().join(format!("crime_map_blocklist_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("blocklist.toml");
+
+        std::fs::write(&path, "[[incidents]]\nsource = \"x\"\nid = \"\"\n").unwrap();
+        let err = Blocklist::load(Some(&path)).unwrap_err();
+        assert!(matches!(err, GenerateError::InvalidBlocklist { .. }));
+
+        std::fs::write(&path, "[[incidents]]\nsource = \"x\"\nid = \"1\"\n").unwrap();
+        let blocklist = Blocklist::load(Some(&path)).unwrap();
+        assert_eq!(blocklist.len(), 1);
+        assert!(blocklist.hash().is_some());
+
+        assert!(Blocklist::load(None).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
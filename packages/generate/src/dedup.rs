@@ -0,0 +1,529 @@
+//! Cross-source duplicate suppression by source display priority.
+//!
+//! Overlapping jurisdictions (a county and the cities inside it) often
+//! publish the same incident. With `--dedup-sources`, an incident from a
+//! source is suppressed when a source with a strictly higher
+//! [`SourceDefinition::priority`] has an incident that duplicates it.
+//! Sources with equal priority never suppress each other.
+//!
+//! Two incidents are duplicates when they occurred in the same minute
+//! within the same [`DUPLICATE_COORD_DECIMALS`]-decimal coordinate cell
+//! (about 110 m). Suppressed IDs are merged into the run's [`Blocklist`],
+//! so every output excludes them through the same per-source filter.
+//!
+//! [`source_overlaps`] is the diagnostic for deciding which sources need
+//! priorities at all: it reports the source pairs whose coverage overlaps,
+//! by the census tracts stamped on their incidents during enrichment and
+//! by their bounding boxes.
+//!
+//! [`SourceDefinition::priority`]: crime_map_source::source_def::SourceDefinition::priority
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use crime_map_source::registry::all_sources;
+use serde::Serialize;
+
+use crate::GenerateError;
+use crate::blocklist::Blocklist;
+
+/// Coordinate decimals rounded to when matching duplicates (~110 m).
+pub const DUPLICATE_COORD_DECIMALS: u32 = 3;
+
+/// A source compared by [`find_duplicates`].
+#[derive(Debug, Clone)]
+pub struct DedupSource {
+    /// Source ID.
+    pub id: String,
+    /// Display priority; higher wins.
+    pub priority: i32,
+    /// Paths to the source's `DuckDB` shards (see
+    /// [`shard_paths`](crime_map_database::source_db::shard_paths)).
+    pub paths: Vec<PathBuf>,
+    /// SQL predicate selecting the rows that take part (the exportable
+    /// filter, minus blocklisted incidents).
+    pub filter: String,
+    /// The source's blocklisted `source_incident_id`s, which `filter`
+    /// anti-joins against once loaded (see [`Blocklist::load_into`]).
+    pub blocked: BTreeSet<String>,
+}
+
+/// How much two sources' coverage overlaps (see [`source_overlaps`]).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceOverlap {
+    /// First source ID (alphabetically).
+    pub source_a: String,
+    /// Second source ID.
+    pub source_b: String,
+    /// Census tracts with incidents from `source_a`.
+    pub tracts_a: u64,
+    /// Census tracts with incidents from `source_b`.
+    pub tracts_b: u64,
+    /// Census tracts with incidents from both.
+    pub shared_tracts: u64,
+    /// `shared_tracts` as a fraction of the smaller source's tracts: 1.0
+    /// when one source's coverage lies within the other's.
+    pub tract_overlap: f64,
+    /// Area shared by the two sources' incident bounding boxes, as a
+    /// fraction of the smaller box.
+    pub bbox_overlap: f64,
+}
+
+/// A source's incident bounding box, `[min_lng, min_lat, max_lng,
+/// max_lat]`.
+type Bbox = [f64; 4];
+
+/// Returns the fraction of the smaller of `a` and `b` covered by their
+/// intersection (0 for disjoint or zero-area boxes).
+fn bbox_overlap(a: Bbox, b: Bbox) -> f64 {
+    let area =
+        |[min_x, min_y, max_x, max_y]: Bbox| (max_x - min_x).max(0.0) * (max_y - min_y).max(0.0);
+    let smaller = area(a).min(area(b));
+    if smaller <= 0.0 {
+        return 0.0;
+    }
+    area([
+        a[0].max(b[0]),
+        a[1].max(b[1]),
+        a[2].min(b[2]),
+        a[3].min(b[3]),
+    ]) / smaller
+}
+
+/// Returns each of `source_ids`' display priority from the registry.
+/// Sources not in the registry get the default priority `0`.
+#[must_use]
+pub fn source_priorities(source_ids: &[String]) -> BTreeMap<String, i32> {
+    let registry = all_sources();
+    source_ids
+        .iter()
+        .map(|sid| {
+            let priority = registry
+                .iter()
+                .find(|s| s.id() == sid)
+                .map_or(0, |s| s.priority());
+            (sid.clone(), priority)
+        })
+        .collect()
+}
+
+/// Returns the [`DedupSource`]s of `source_ids` that have an `incidents`
+/// table, restricted to `filter` minus `blocklist`.
+///
+/// # Errors
+///
+/// Returns an error if a source database can't be opened.
+fn dedup_sources(
+    source_ids: &[String],
+    priorities: &BTreeMap<String, i32>,
+    filter: &str,
+    blocklist: &Blocklist,
+) -> Result<Vec<DedupSource>, GenerateError> {
+    let mut sources = Vec::new();
+    for sid in source_ids {
+        // Opening applies migrations and skips files without incidents.
+        let mut paths = Vec::new();
+        for path in crime_map_database::source_db::shard_paths(sid) {
+            if crate::open_source_shard(sid, &path)?.is_some() {
+                paths.push(path);
+            }
+        }
+        if paths.is_empty() {
+            continue;
+        }
+        sources.push(DedupSource {
+            id: sid.clone(),
+            priority: priorities.get(sid).copied().unwrap_or(0),
+            paths,
+            filter: blocklist.apply(filter, sid).into_owned(),
+            blocked: blocklist.blocked_ids(sid),
+        });
+    }
+    Ok(sources)
+}
+
+/// Attaches every shard of `sources` to `duck` read-only, loads their
+/// blocked IDs for their filters, and returns the `UNION ALL` of
+/// `select(source, shard alias)` over all of them.
+///
+/// # Errors
+///
+/// Returns an error if a shard can't be attached or the blocked IDs can't
+/// be loaded.
+fn attach_shards(
+    duck: &duckdb::Connection,
+    sources: &[DedupSource],
+    select: impl Fn(&DedupSource, &str) -> String,
+) -> Result<String, GenerateError> {
+    let mut union = String::new();
+    for (i, source) in sources.iter().enumerate() {
+        if !source.blocked.is_empty() {
+            crate::blocklist::load_blocked_ids(duck, &source.id, &source.blocked)?;
+        }
+        for (j, path) in source.paths.iter().enumerate() {
+            let alias = format!("s{i}_{j}");
+            duck.execute_batch(&format!(
+                "ATTACH '{}' AS {alias} (READ_ONLY)",
+                path.to_string_lossy().replace('\'', "''")
+            ))?;
+            if !union.is_empty() {
+                union.push_str(" UNION ALL ");
+            }
+            union.push_str(&select(source, &alias));
+        }
+    }
+    Ok(union)
+}
+
+/// Finds the incidents of `source_ids` (restricted to `filter`) that
+/// duplicate an incident of a higher-priority source, adds them to
+/// `blocklist`, and returns the number suppressed per source. Incidents
+/// already in `blocklist` never suppress others.
+///
+/// # Errors
+///
+/// Returns an error if a source database can't be opened or queried.
+pub fn suppress_duplicates(
+    source_ids: &[String],
+    priorities: &BTreeMap<String, i32>,
+    filter: &str,
+    blocklist: &mut Blocklist,
+) -> Result<BTreeMap<String, u64>, GenerateError> {
+    let sources = dedup_sources(source_ids, priorities, filter, blocklist)?;
+    let duplicates = find_duplicates(&sources)?;
+    let mut counts = BTreeMap::new();
+    for (sid, ids) in duplicates {
+        counts.insert(sid.clone(), ids.len() as u64);
+        blocklist.extend(&sid, ids);
+    }
+    Ok(counts)
+}
+
+/// Returns, per source, the `source_incident_id`s of `sources` that
+/// duplicate an incident of a higher-priority source. Only rows matching
+/// each source's filter are compared.
+///
+/// # Errors
+///
+/// Returns an error if a database can't be attached or queried.
+pub fn find_duplicates(
+    sources: &[DedupSource],
+) -> Result<BTreeMap<String, BTreeSet<String>>, GenerateError> {
+    let mut duplicates: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    let distinct: BTreeSet<i32> = sources.iter().map(|s| s.priority).collect();
+    if distinct.len() < 2 {
+        return Ok(duplicates);
+    }
+
+    let duck = duckdb::Connection::open_in_memory()?;
+    let keys = attach_shards(&duck, sources, |source, alias| {
+        format!(
+            "SELECT '{sid}' AS source_id, {priority} AS priority, source_incident_id,
+                    date_trunc('minute', occurred_at) AS t,
+                    ROUND(longitude, {DUPLICATE_COORD_DECIMALS}) AS lng,
+                    ROUND(latitude, {DUPLICATE_COORD_DECIMALS}) AS lat
+             FROM {alias}.incidents
+             WHERE occurred_at IS NOT NULL AND {filter}",
+            sid = source.id.replace('\'', "''"),
+            priority = source.priority,
+            filter = source.filter,
+        )
+    })?;
+
+    duck.execute_batch(&format!("CREATE TEMP TABLE duplicate_keys AS {keys}"))?;
+
+    let mut stmt = duck.prepare(
+        "SELECT k.source_id, k.source_incident_id
+         FROM duplicate_keys k
+         WHERE EXISTS (
+             SELECT 1 FROM duplicate_keys h
+             WHERE h.priority > k.priority
+               AND h.t = k.t AND h.lng = k.lng AND h.lat = k.lat
+         )",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let sid: String = row.get(0)?;
+        let id: String = row.get(1)?;
+        duplicates.entry(sid).or_default().insert(id);
+    }
+
+    Ok(duplicates)
+}
+
+/// Reports the pairs of `source_ids` (restricted to `filter` minus
+/// `blocklist`) whose coverage overlaps; see [`source_overlaps`].
+///
+/// # Errors
+///
+/// Returns an error if a source database can't be opened or queried.
+pub fn overlap_report(
+    source_ids: &[String],
+    filter: &str,
+    blocklist: &Blocklist,
+) -> Result<Vec<SourceOverlap>, GenerateError> {
+    let sources = dedup_sources(source_ids, &BTreeMap::new(), filter, blocklist)?;
+    source_overlaps(&sources)
+}
+
+/// Returns every pair of `sources` that shares a census tract or whose
+/// bounding boxes intersect, sorted by [`SourceOverlap::tract_overlap`]
+/// and then [`SourceOverlap::bbox_overlap`], largest first.
+///
+/// Tracts are the `census_tract_geoid`s stamped during enrichment, so this
+/// is a grouped set intersection rather than a geometry query. Incidents
+/// without a tract only count toward the bounding box.
+///
+/// # Errors
+///
+/// Returns an error if a database can't be attached or queried.
+pub fn source_overlaps(sources: &[DedupSource]) -> Result<Vec<SourceOverlap>, GenerateError> {
+    let duck = duckdb::Connection::open_in_memory()?;
+    let coverage = attach_shards(&duck, sources, |source, alias| {
+        format!(
+            "SELECT '{sid}' AS source_id, census_tract_geoid AS geoid,
+                    MIN(longitude) AS min_lng, MIN(latitude) AS min_lat,
+                    MAX(longitude) AS max_lng, MAX(latitude) AS max_lat
+             FROM {alias}.incidents
+             WHERE {filter}
+             GROUP BY census_tract_geoid",
+            sid = source.id.replace('\'', "''"),
+            filter = source.filter,
+        )
+    })?;
+    if coverage.is_empty() {
+        return Ok(Vec::new());
+    }
+    duck.execute_batch(&format!("CREATE TEMP TABLE coverage AS {coverage}"))?;
+
+    let mut bboxes: BTreeMap<String, Bbox> = BTreeMap::new();
+    let mut stmt = duck.prepare(
+        "SELECT source_id, MIN(min_lng), MIN(min_lat), MAX(max_lng), MAX(max_lat)
+         FROM coverage
+         GROUP BY source_id",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        bboxes.insert(
+            row.get(0)?,
+            [row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?],
+        );
+    }
+
+    let mut tracts: BTreeMap<String, u64> = BTreeMap::new();
+    let mut stmt = duck.prepare(
+        "SELECT source_id, COUNT(DISTINCT geoid)
+         FROM coverage
+         WHERE geoid IS NOT NULL
+         GROUP BY source_id",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        tracts.insert(
+            row.get(0)?,
+            u64::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+        );
+    }
+
+    let mut shared: BTreeMap<(String, String), u64> = BTreeMap::new();
+    let mut stmt = duck.prepare(
+        "WITH tracts AS (
+             SELECT DISTINCT source_id, geoid FROM coverage WHERE geoid IS NOT NULL
+         )
+         SELECT a.source_id, b.source_id, COUNT(*)
+         FROM tracts a
+         JOIN tracts b ON a.geoid = b.geoid AND a.source_id < b.source_id
+         GROUP BY ALL",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        shared.insert(
+            (row.get(0)?, row.get(1)?),
+            u64::try_from(row.get::<_, i64>(2)?).unwrap_or(0),
+        );
+    }
+
+    let mut overlaps = Vec::new();
+    for (a, &bbox_a) in &bboxes {
+        for (b, &bbox_b) in
+            bboxes.range::<String, _>((std::ops::Bound::Excluded(a), std::ops::Bound::Unbounded))
+        {
+            let shared_tracts = shared.get(&(a.clone(), b.clone())).copied().unwrap_or(0);
+            let bbox_overlap = bbox_overlap(bbox_a, bbox_b);
+            if shared_tracts == 0 && bbox_overlap <= 0.0 {
+                continue;
+            }
+
+            let tracts_a = tracts.get(a).copied().unwrap_or(0);
+            let tracts_b = tracts.get(b).copied().unwrap_or(0);
+            #[allow(clippy::cast_precision_loss)]
+            let tract_overlap = if shared_tracts == 0 {
+                0.0
+            } else {
+                shared_tracts as f64 / tracts_a.min(tracts_b) as f64
+            };
+            overlaps.push(SourceOverlap {
+                source_a: a.clone(),
+                source_b: b.clone(),
+                tracts_a,
+                tracts_b,
+                shared_tracts,
+                tract_overlap,
+                bbox_overlap,
+            });
+        }
+    }
+
+    overlaps.sort_by(|x, y| {
+        y.tract_overlap
+            .total_cmp(&x.tract_overlap)
+            .then(y.bbox_overlap.total_cmp(&x.bbox_overlap))
+            .then_with(|| (&x.source_a, &x.source_b).cmp(&(&y.source_a, &y.source_b)))
+    });
+    Ok(overlaps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_source(path: &std::path::Path, rows: &[(&str, &str, f64, f64)]) {
+        let conn = duckdb::Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE incidents (
+                 source_incident_id VARCHAR, occurred_at TIMESTAMPTZ,
+                 longitude DOUBLE, latitude DOUBLE
+             )",
+        )
+        .unwrap();
+        for (id, at, lng, lat) in rows {
+            conn.execute(
+                "INSERT INTO incidents VALUES (?, ?::TIMESTAMPTZ, ?, ?)",
+                duckdb::params![id, at, lng, lat],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn lower_priority_duplicates_are_found() {
+        let dir = std::env::temp_dir().join(format!("crime_map_dedup_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let city = dir.join("city.duckdb");
+        let county = dir.join("county.duckdb");
+        let _ = std::fs::remove_file(&city);
+        let _ = std::fs::remove_file(&county);
+
+        write_source(
+            &city,
+            &[("c-1", "2024-03-01 14:30:00+00", -87.6301, 41.8801)],
+        );
+        write_source(
+            &county,
+            &[
+                // Same minute, ~10 m away: a duplicate.
+                ("k-1", "2024-03-01 14:30:45+00", -87.6302, 41.8802),
+                // Same place, an hour later: distinct.
+                ("k-2", "2024-03-01 15:30:00+00", -87.6301, 41.8801),
+            ],
+        );
+
+        let source = |id: &str, priority, path: &std::path::Path| DedupSource {
+            id: id.to_string(),
+            priority,
+            paths: vec![path.to_path_buf()],
+            filter: "TRUE".to_string(),
+            blocked: BTreeSet::new(),
+        };
+        let sources = vec![source("city", 10, &city), source("county", 0, &county)];
+        let duplicates = find_duplicates(&sources).unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates["county"], BTreeSet::from(["k-1".to_string()]));
+
+        // Equal priorities never suppress each other.
+        let equal = vec![source("city", 0, &city), source("county", 0, &county)];
+        assert!(find_duplicates(&equal).unwrap().is_empty());
+
+        // Blocked incidents don't suppress their duplicates.
+        let blocklist = Blocklist::from_entries(vec![crate::blocklist::BlockedIncident {
+            source: "city".to_string(),
+            id: "c-1".to_string(),
+            reason: None,
+        }]);
+        let blocked = vec![
+            DedupSource {
+                filter: blocklist.apply("TRUE", "city").into_owned(),
+                blocked: blocklist.blocked_ids("city"),
+                ..source("city", 10, &city)
+            },
+            source("county", 0, &county),
+        ];
+        assert!(find_duplicates(&blocked).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn overlapping_sources_are_reported_by_shared_tracts() {
+        let dir = std::env::temp_dir().join(format!("crime_map_overlap_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let write = |name: &str, rows: &[(&str, f64, f64)]| {
+            let path = dir.join(format!("{name}.duckdb"));
+            let _ = std::fs::remove_file(&path);
+            let conn = duckdb::Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE incidents (
+                     census_tract_geoid VARCHAR, longitude DOUBLE, latitude DOUBLE
+                 )",
+            )
+            .unwrap();
+            for (tract, lng, lat) in rows {
+                conn.execute(
+                    "INSERT INTO incidents VALUES (?, ?, ?)",
+                    duckdb::params![tract, lng, lat],
+                )
+                .unwrap();
+            }
+            DedupSource {
+                id: name.to_string(),
+                priority: 0,
+                paths: vec![path],
+                filter: "TRUE".to_string(),
+                blocked: BTreeSet::new(),
+            }
+        };
+        let sources = vec![
+            write("city", &[("t1", -87.65, 41.85), ("t2", -87.60, 41.90)]),
+            write(
+                "county",
+                &[
+                    ("t1", -87.70, 41.80),
+                    ("t2", -87.60, 41.90),
+                    ("t3", -87.50, 42.00),
+                    ("t4", -87.55, 41.95),
+                ],
+            ),
+            write("elsewhere", &[("t9", -77.05, 38.90), ("t9", -77.00, 38.95)]),
+        ];
+
+        let overlaps = source_overlaps(&sources).unwrap();
+        assert_eq!(overlaps.len(), 1);
+        let overlap = &overlaps[0];
+        assert_eq!(
+            (overlap.source_a.as_str(), overlap.source_b.as_str()),
+            ("city", "county")
+        );
+        assert_eq!(
+            (overlap.tracts_a, overlap.tracts_b, overlap.shared_tracts),
+            (2, 4, 2)
+        );
+        assert!((overlap.tract_overlap - 1.0).abs() < f64::EPSILON);
+        // The city's box lies inside the county's.
+        assert!((overlap.bbox_overlap - 1.0).abs() < 1e-9);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
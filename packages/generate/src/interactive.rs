@@ -6,23 +6,7 @@
 
 use dialoguer::{Confirm, Input, MultiSelect};
 
-use crate::{
-    GenerateArgs, OUTPUT_ANALYTICS_DB, OUTPUT_BOUNDARIES_DB, OUTPUT_BOUNDARIES_PMTILES,
-    OUTPUT_COUNT_DB, OUTPUT_H3_DB, OUTPUT_INCIDENTS_DB, OUTPUT_INCIDENTS_PMTILES, OUTPUT_METADATA,
-    output_dir, resolve_source_ids, run_with_cache,
-};
-
-/// All available output types, paired with their internal constant name.
-const OUTPUT_CHOICES: &[(&str, &str)] = &[
-    ("PMTiles (heatmap + points)", OUTPUT_INCIDENTS_PMTILES),
-    ("Sidebar SQLite", OUTPUT_INCIDENTS_DB),
-    ("Count DuckDB", OUTPUT_COUNT_DB),
-    ("H3 Hexbin DuckDB", OUTPUT_H3_DB),
-    ("Server Metadata", OUTPUT_METADATA),
-    ("Boundaries PMTiles", OUTPUT_BOUNDARIES_PMTILES),
-    ("Boundaries Search DB", OUTPUT_BOUNDARIES_DB),
-    ("Analytics DuckDB", OUTPUT_ANALYTICS_DB),
-];
+use crate::{GenerateArgs, output_catalog, output_dir, resolve_source_ids, run_with_cache};
 
 /// Runs the interactive generation menu.
 ///
@@ -38,7 +22,8 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     std::fs::create_dir_all(&dir)?;
 
     // --- Output type selection (multi-select, all checked by default) ---
-    let output_labels: Vec<&str> = OUTPUT_CHOICES.iter().map(|(label, _)| *label).collect();
+    let catalog = output_catalog();
+    let output_labels: Vec<&str> = catalog.iter().map(|info| info.description).collect();
     let selected_outputs = MultiSelect::new()
         .with_prompt("Outputs to generate (space=toggle, a=all, enter=confirm)")
         .items(&output_labels)
@@ -49,10 +34,7 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    let requested_outputs: Vec<&str> = selected_outputs
-        .iter()
-        .map(|&i| OUTPUT_CHOICES[i].1)
-        .collect();
+    let requested_outputs: Vec<&str> = selected_outputs.iter().map(|&i| catalog[i].name).collect();
 
     // --- Record limit ---
     let limit_str: String = Input::new()
@@ -113,9 +95,9 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     let args = GenerateArgs {
         limit,
         sources,
-        states: None,
         keep_intermediate,
         force,
+        ..GenerateArgs::default()
     };
 
     let source_ids = resolve_source_ids(&args)?;
@@ -0,0 +1,327 @@
+//! Deterministic location jitter for sensitive incidents.
+//!
+//! Incidents such as sexual assaults or domestic violence often happen at
+//! a victim's home, so plotting them at their exact coordinates can
+//! identify the victim. With a [`SensitiveJitter`] configured, every
+//! output that locates a single incident (the incidents `PMTiles`, the
+//! sidebar DB, and `extract` files) shows those incidents displaced by a
+//! bounded offset instead. Every other incident keeps its precise
+//! location.
+//!
+//! The offset is an HMAC of the incident's source and ID under a
+//! [`JitterKey`], not a random number generator, so regenerating from the
+//! same data places each incident at the same spot and tiles stay stable
+//! between runs (an incident can't be located by averaging its positions
+//! across builds). The key is never written to any output: source and
+//! incident IDs are public, so with an unkeyed hash anyone could recompute
+//! an offset and subtract it. Points move between half and all of
+//! [`SensitiveJitter::radius_meters`] in a hashed direction, so none stays
+//! near its true location, though one radius is only a few houses: the
+//! jitter hides which house on the block, not which block.
+//!
+//! Aggregated outputs (counts, H3, choropleth) keep the true coordinates,
+//! since they never show a single incident's location. The analytics DB
+//! and its NDJSON have per-incident rows but no coordinates, only the
+//! tract, place, and neighborhood they fall in.
+//!
+//! [`StackSpread`] uses the same hashed offsets for rendering rather than
+//! privacy: sources that geocode every incident on a block to the block
+//! centroid stack hundreds of points on one coordinate, which tile as a
+//! single dot. Spreading each incident on a coordinate shared with
+//! another incident of its source over a small disc turns the stack into
+//! a visible cluster. Sensitive incidents are only jittered, not also
+//! spread.
+
+use std::collections::BTreeSet;
+
+use hmac::{Hmac, Mac as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+/// Approximate meters per degree of latitude.
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// Largest [`StackSpread::radius_meters`], so a spread stack stays within
+/// its block.
+pub const MAX_STACK_SPREAD_METERS: f64 = 50.0;
+
+/// Environment variable holding the [`JitterKey`] secret.
+pub const JITTER_KEY_ENV: &str = "CRIME_MAP_JITTER_KEY";
+
+/// Shortest accepted [`JitterKey`], in bytes, so it can't be brute-forced
+/// from published points whose true location is known.
+pub const MIN_JITTER_KEY_LEN: usize = 32;
+
+/// Secret that [`SensitiveJitter`] offsets are derived from. Must stay
+/// private and stable between builds: anyone holding it can undo the
+/// jitter, and changing it moves every jittered incident.
+#[derive(Clone)]
+pub struct JitterKey(Vec<u8>);
+
+impl JitterKey {
+    /// Wraps `secret` as a key (see [`validate_jitter_key`]).
+    #[must_use]
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self(secret.into())
+    }
+
+    /// Reads the key from [`JITTER_KEY_ENV`], or `None` if it's unset.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        std::env::var(JITTER_KEY_ENV).ok().map(Self::new)
+    }
+
+    /// Returns a hex digest identifying this key, so the manifest can tell
+    /// when it changed without storing it.
+    #[must_use]
+    pub fn fingerprint(&self) -> String {
+        let digest = self.mac().chain_update(b"fingerprint").finalize();
+        format!("{:x}", digest.into_bytes())
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        Hmac::new_from_slice(&self.0).expect("HMAC accepts keys of any length")
+    }
+}
+
+impl std::fmt::Debug for JitterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("JitterKey(..)")
+    }
+}
+
+/// Which incidents to jitter in the per-incident outputs, and by how much.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensitiveJitter {
+    /// Subcategories (e.g. `SEXUAL_ASSAULT`) whose incidents are jittered.
+    pub categories: BTreeSet<String>,
+    /// Also jitter every incident flagged `domestic`.
+    pub domestic: bool,
+    /// Maximum offset, in meters.
+    pub radius_meters: f64,
+}
+
+impl SensitiveJitter {
+    /// Returns `true` if an incident with subcategory `category` and
+    /// `domestic` flag is sensitive.
+    #[must_use]
+    pub fn applies_to(&self, category: &str, domestic: Option<bool>) -> bool {
+        self.categories.contains(category) || (self.domestic && domestic == Some(true))
+    }
+
+    /// Returns `(lng, lat)` displaced by this incident's deterministic
+    /// offset, seeded by `source_id` and `incident_id` under `key`.
+    #[must_use]
+    pub fn displace(
+        &self,
+        key: &JitterKey,
+        source_id: &str,
+        incident_id: &str,
+        lng: f64,
+        lat: f64,
+    ) -> (f64, f64) {
+        hashed_offset(Some(key), source_id, incident_id, lng, lat, |unit| {
+            self.radius_meters * 0.5f64.mul_add(unit, 0.5)
+        })
+    }
+}
+
+/// How far to spread incidents stacked on an identical coordinate in the
+/// incidents `PMTiles`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StackSpread {
+    /// Maximum offset, in meters.
+    pub radius_meters: f64,
+}
+
+impl StackSpread {
+    /// Returns `(lng, lat)` moved to this incident's deterministic spot in
+    /// a disc of [`Self::radius_meters`], seeded by `source_id` and
+    /// `incident_id`. Spots are spread evenly over the disc, so a stack
+    /// reads as a cluster rather than a ring.
+    ///
+    /// Unkeyed: the spread only applies to incidents that aren't
+    /// sensitive, whose shared coordinate is published anyway.
+    #[must_use]
+    pub fn displace(&self, source_id: &str, incident_id: &str, lng: f64, lat: f64) -> (f64, f64) {
+        hashed_offset(None, source_id, incident_id, lng, lat, |unit| {
+            self.radius_meters * unit.sqrt()
+        })
+    }
+}
+
+/// Returns `(lng, lat)` moved in a direction hashed from `source_id` and
+/// `incident_id`, by `distance` meters of another hashed value in `0..=1`.
+/// With `key`, the hash is an HMAC under it.
+fn hashed_offset(
+    key: Option<&JitterKey>,
+    source_id: &str,
+    incident_id: &str,
+    lng: f64,
+    lat: f64,
+    distance: impl FnOnce(f64) -> f64,
+) -> (f64, f64) {
+    let digest: [u8; 32] = match key {
+        Some(key) => key
+            .mac()
+            .chain_update(source_id)
+            .chain_update([0_u8])
+            .chain_update(incident_id)
+            .finalize()
+            .into_bytes()
+            .into(),
+        None => Sha256::new()
+            .chain_update(source_id)
+            .chain_update([0_u8])
+            .chain_update(incident_id)
+            .finalize()
+            .into(),
+    };
+    let unit = |at: usize| {
+        let bytes = [digest[at], digest[at + 1], digest[at + 2], digest[at + 3]];
+        f64::from(u32::from_le_bytes(bytes)) / f64::from(u32::MAX)
+    };
+
+    let angle = unit(0) * std::f64::consts::TAU;
+    let distance = distance(unit(4));
+    // Longitude degrees shrink toward the poles; the floor keeps the
+    // offset finite for (invalid) polar coordinates.
+    let lng_scale = METERS_PER_DEGREE * lat.to_radians().cos().max(0.01);
+    (
+        lng + distance * angle.cos() / lng_scale,
+        lat + distance * angle.sin() / METERS_PER_DEGREE,
+    )
+}
+
+/// Validates jitter parameters: a positive, finite `radius_meters` and
+/// at least one category or `domestic`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if a parameter is invalid.
+pub fn validate_jitter(jitter: &SensitiveJitter) -> Result<(), String> {
+    if !(jitter.radius_meters.is_finite() && jitter.radius_meters > 0.0) {
+        return Err(format!(
+            "jitter radius ({}) must be a positive number of meters",
+            jitter.radius_meters
+        ));
+    }
+    if jitter.categories.is_empty() && !jitter.domestic {
+        return Err("jitter needs at least one category or domestic".to_string());
+    }
+    Ok(())
+}
+
+/// Validates a jitter key: at least [`MIN_JITTER_KEY_LEN`] bytes.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the key is too short.
+pub fn validate_jitter_key(key: &JitterKey) -> Result<(), String> {
+    if key.0.len() < MIN_JITTER_KEY_LEN {
+        return Err(format!(
+            "{JITTER_KEY_ENV} must be at least {MIN_JITTER_KEY_LEN} bytes (got {})",
+            key.0.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Validates a stack spread: a positive `radius_meters` of at most
+/// [`MAX_STACK_SPREAD_METERS`].
+///
+/// # Errors
+///
+/// Returns a description of the problem if the radius is out of range.
+pub fn validate_stack_spread(spread: StackSpread) -> Result<(), String> {
+    if !(spread.radius_meters > 0.0 && spread.radius_meters <= MAX_STACK_SPREAD_METERS) {
+        return Err(format!(
+            "stack spread ({}) must be more than 0 and at most {MAX_STACK_SPREAD_METERS} meters",
+            spread.radius_meters
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jitter() -> SensitiveJitter {
+        SensitiveJitter {
+            categories: BTreeSet::from(["SEXUAL_ASSAULT".to_string()]),
+            domestic: true,
+            radius_meters: 200.0,
+        }
+    }
+
+    #[test]
+    fn selects_sensitive_categories_and_domestic_incidents() {
+        let jitter = jitter();
+        assert!(jitter.applies_to("SEXUAL_ASSAULT", None));
+        assert!(jitter.applies_to("SIMPLE_ASSAULT", Some(true)));
+        assert!(!jitter.applies_to("SIMPLE_ASSAULT", Some(false)));
+        assert!(!jitter.applies_to("LARCENY_THEFT", None));
+    }
+
+    fn key() -> JitterKey {
+        JitterKey::new("0123456789abcdef0123456789abcdef")
+    }
+
+    #[test]
+    fn displacement_is_deterministic_and_bounded() {
+        let (jitter, key) = (jitter(), key());
+        let (lng, lat) = (-87.63, 41.88);
+        let moved = jitter.displace(&key, "chicago_pd", "123", lng, lat);
+        assert_eq!(moved, jitter.displace(&key, "chicago_pd", "123", lng, lat));
+        assert_ne!(moved, jitter.displace(&key, "chicago_pd", "124", lng, lat));
+
+        for id in 0..100 {
+            let (x, y) = jitter.displace(&key, "chicago_pd", &id.to_string(), lng, lat);
+            let dx = (x - lng) * METERS_PER_DEGREE * lat.to_radians().cos();
+            let dy = (y - lat) * METERS_PER_DEGREE;
+            let meters = dx.hypot(dy);
+            assert!((99.0..=201.0).contains(&meters), "{id} moved {meters}m");
+        }
+    }
+
+    #[test]
+    fn displacement_depends_on_the_key() {
+        let (jitter, key) = (jitter(), key());
+        let other = JitterKey::new("fedcba9876543210fedcba9876543210");
+        let (lng, lat) = (-87.63, 41.88);
+        assert_ne!(
+            jitter.displace(&key, "chicago_pd", "123", lng, lat),
+            jitter.displace(&other, "chicago_pd", "123", lng, lat)
+        );
+        assert_ne!(key.fingerprint(), other.fingerprint());
+        assert_eq!(format!("{key:?}"), "JitterKey(..)");
+
+        assert!(validate_jitter_key(&key).is_ok());
+        assert!(validate_jitter_key(&JitterKey::new("short")).is_err());
+    }
+
+    #[test]
+    fn stack_spread_is_deterministic_and_within_radius() {
+        let spread = StackSpread {
+            radius_meters: 20.0,
+        };
+        let (lng, lat) = (-87.63, 41.88);
+        assert_eq!(
+            spread.displace("chicago_pd", "123", lng, lat),
+            spread.displace("chicago_pd", "123", lng, lat)
+        );
+
+        for id in 0..100 {
+            let (x, y) = spread.displace("chicago_pd", &id.to_string(), lng, lat);
+            let dx = (x - lng) * METERS_PER_DEGREE * lat.to_radians().cos();
+            let dy = (y - lat) * METERS_PER_DEGREE;
+            assert!(dx.hypot(dy) <= 20.01, "{id} moved {}m", dx.hypot(dy));
+        }
+
+        assert!(validate_stack_spread(spread).is_ok());
+        for radius_meters in [0.0, -1.0, 51.0, f64::NAN] {
+            assert!(validate_stack_spread(StackSpread { radius_meters }).is_err());
+        }
+    }
+}
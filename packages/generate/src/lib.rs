@@ -17,26 +17,95 @@
 //! Iterates per-source `DuckDB` files with keyset pagination and streaming
 //! writes to keep memory usage constant regardless of dataset size.
 
+pub mod blocklist;
+pub mod dedup;
 pub mod interactive;
+pub mod jitter;
 pub mod merge;
+pub mod remap;
+pub mod sink;
 pub mod spatial;
+pub mod taxonomy;
+pub mod tiles;
+pub mod verify;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
 use std::io::{BufWriter, Write as _};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use blocklist::Blocklist;
+use crime_map_database::counts::{DEFAULT_CELL_PRECISION, cell_sql};
+use crime_map_database::paths::{self, PREVIEW_MARKER_FILE};
+use crime_map_database::source_db::ShardedIncidents;
+use crime_map_geography_models::neighborhood_geoid;
 use crime_map_source::progress::ProgressCallback;
 use crime_map_source::registry::all_sources;
+use crime_map_source::source_def::SourceDefinition;
+use jitter::{JitterKey, SensitiveJitter, StackSpread};
+use remap::CategoryRemap;
 use serde::{Deserialize, Serialize};
+use sink::{OutputSink, SinkError};
+use taxonomy::Taxonomy;
+use tiles::{TileBackend, TileStats};
 
 /// Number of rows to fetch per database query batch.
 const BATCH_SIZE: i64 = 10_000;
 
+/// Target estimated payload per sidebar `SQLite` transaction. The sidebar
+/// batch size adapts (see [`next_sidebar_batch_size`]) to stay near this.
+const SIDEBAR_TARGET_BATCH_BYTES: usize = 8 * 1024 * 1024;
+
+/// Smallest adaptive sidebar batch size.
+const SIDEBAR_MIN_BATCH_SIZE: i64 = 1_000;
+
+/// Largest adaptive sidebar batch size.
+const SIDEBAR_MAX_BATCH_SIZE: i64 = 50_000;
+
+/// Raw records per sidebar `SQLite` transaction. Smaller than the
+/// incident batches since each holds a whole source record.
+const SIDEBAR_RAW_BATCH_SIZE: usize = 1_000;
+
+/// Per-record time above which a finished output is logged as a possible
+/// performance regression. Generous: the slowest outputs (PMTiles, H3)
+/// normally run well under this.
+const SLOW_OUTPUT_MICROS_PER_RECORD: f64 = 200.0;
+
+/// Outputs that finish faster than this are never flagged as slow.
+const SLOW_OUTPUT_MIN_SECS: f64 = 10.0;
+
 /// Current manifest schema version. Bump this when the manifest format
-/// changes in a backward-incompatible way.
-const MANIFEST_VERSION: u32 = 2;
+/// changes in a backward-incompatible way, or when an output's schema
+/// changes (e.g. a new column), so outputs of an older layout are
+/// regenerated even if the source data is unchanged and partitions of an
+/// older layout aren't merged (see [`merge::run`]).
+const MANIFEST_VERSION: u32 = 3;
+
+/// Layout version of the analytics DB. Bump this when
+/// [`generate_analytics_db`] changes its tables, so an existing DB is
+/// rebuilt in full instead of updated incrementally.
+const ANALYTICS_SCHEMA_VERSION: u32 = 2;
+
+/// Number of hash buckets used for `--sample`; the sample fraction is
+/// resolved to a whole number of buckets.
+const SAMPLE_BUCKETS: u64 = 1_000_000;
+
+/// Web Mercator zoom whose tiles are the cells hashed to `GeoJSONSeq`
+/// shards (see [`geojsonseq_shard`]). At z10 (~40 km tiles) a dense city
+/// spans several cells, so shards stay balanced while each shard still
+/// owns whole tiles at every zoom from here up.
+const SHARD_TILE_ZOOM: u32 = 10;
+
+/// Default [`GenerateArgs::max_pmtiles_features`]: above any single
+/// metro area, below a full national run.
+pub const DEFAULT_MAX_PMTILES_FEATURES: u64 = 20_000_000;
+
+/// Git commit the generator was built from, or `unknown` if git info
+/// wasn't available at build time (set by `build.rs`).
+pub const GIT_COMMIT: &str = env!("CRIME_MAP_GIT_COMMIT");
 
 /// Output name constant for the incidents `PMTiles` file.
 pub const OUTPUT_INCIDENTS_PMTILES: &str = "incidents_pmtiles";
@@ -62,6 +131,411 @@ pub const OUTPUT_BOUNDARIES_DB: &str = "boundaries_db";
 /// Output name constant for the analytics `DuckDB` database.
 pub const OUTPUT_ANALYTICS_DB: &str = "analytics_duckdb";
 
+/// Output name constant for the tract choropleth `DuckDB` database.
+pub const OUTPUT_CHOROPLETH_DB: &str = "choropleth_duckdb";
+
+/// Static description of one output, as listed by [`output_catalog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputInfo {
+    /// Output name (one of the `OUTPUT_*` constants).
+    pub name: &'static str,
+    /// File written into the output directory.
+    pub file: &'static str,
+    /// Whether generation reads the boundaries `DuckDB`.
+    pub needs_boundaries: bool,
+    /// Whether generation scans the source incidents.
+    pub scans_incidents: bool,
+    /// Whether `all` leaves the output out, so it's only generated when
+    /// requested by name.
+    pub opt_in: bool,
+    /// Output whose file this one is derived from instead of the sources.
+    /// Requesting this output brings that one up to date first, and it's
+    /// regenerated whenever that one is.
+    pub derived_from: Option<&'static str>,
+    /// Short human-readable description.
+    pub description: &'static str,
+}
+
+/// Every output the generator can produce, in generation order (derived
+/// outputs after the ones they're derived from).
+const OUTPUT_CATALOG: &[OutputInfo] = &[
+    OutputInfo {
+        name: OUTPUT_INCIDENTS_PMTILES,
+        file: paths::INCIDENTS_PMTILES_FILE,
+        needs_boundaries: false,
+        scans_incidents: true,
+        opt_in: false,
+        derived_from: None,
+        description: "PMTiles (heatmap + points)",
+    },
+    OutputInfo {
+        name: OUTPUT_INCIDENTS_DB,
+        file: paths::INCIDENTS_DB_FILE,
+        needs_boundaries: false,
+        scans_incidents: true,
+        opt_in: false,
+        derived_from: None,
+        description: "Sidebar SQLite",
+    },
+    OutputInfo {
+        name: OUTPUT_COUNT_DB,
+        file: paths::COUNTS_DB_FILE,
+        needs_boundaries: false,
+        scans_incidents: true,
+        opt_in: false,
+        derived_from: None,
+        description: "Count DuckDB",
+    },
+    OutputInfo {
+        name: OUTPUT_H3_DB,
+        file: paths::H3_DB_FILE,
+        needs_boundaries: false,
+        scans_incidents: true,
+        opt_in: false,
+        derived_from: None,
+        description: "H3 Hexbin DuckDB",
+    },
+    OutputInfo {
+        name: OUTPUT_METADATA,
+        file: paths::METADATA_FILE,
+        needs_boundaries: true,
+        scans_incidents: true,
+        opt_in: false,
+        derived_from: None,
+        description: "Server Metadata",
+    },
+    OutputInfo {
+        name: OUTPUT_BOUNDARIES_PMTILES,
+        file: paths::BOUNDARIES_PMTILES_FILE,
+        needs_boundaries: true,
+        scans_incidents: false,
+        opt_in: false,
+        derived_from: None,
+        description: "Boundaries PMTiles",
+    },
+    OutputInfo {
+        name: OUTPUT_BOUNDARIES_DB,
+        file: paths::BOUNDARIES_DB_FILE,
+        needs_boundaries: true,
+        scans_incidents: false,
+        opt_in: false,
+        derived_from: None,
+        description: "Boundaries Search DB",
+    },
+    OutputInfo {
+        name: OUTPUT_ANALYTICS_DB,
+        file: paths::ANALYTICS_DB_FILE,
+        needs_boundaries: true,
+        scans_incidents: true,
+        opt_in: false,
+        derived_from: None,
+        description: "Analytics DuckDB",
+    },
+    OutputInfo {
+        name: OUTPUT_CHOROPLETH_DB,
+        file: paths::CHOROPLETH_DB_FILE,
+        needs_boundaries: false,
+        scans_incidents: false,
+        opt_in: true,
+        derived_from: Some(OUTPUT_ANALYTICS_DB),
+        description: "Tract Choropleth DuckDB",
+    },
+];
+
+/// Returns every output the generator can produce, in generation order.
+/// This is the single source of truth for output names, files, and
+/// dependencies.
+#[must_use]
+pub const fn output_catalog() -> &'static [OutputInfo] {
+    OUTPUT_CATALOG
+}
+
+/// Returns the outputs `all` generates: every output but the
+/// [opt-in](OutputInfo::opt_in) ones, in generation order.
+#[must_use]
+pub fn default_outputs() -> Vec<&'static str> {
+    OUTPUT_CATALOG
+        .iter()
+        .filter(|info| !info.opt_in)
+        .map(|info| info.name)
+        .collect()
+}
+
+/// Looks up an output by name, or `None` if no output has that name.
+#[must_use]
+pub fn output_info(name: &str) -> Option<&'static OutputInfo> {
+    OUTPUT_CATALOG.iter().find(|info| info.name == name)
+}
+
+/// Errors returned by the generation pipeline.
+#[derive(Debug, thiserror::Error)]
+pub enum GenerateError {
+    /// Some sources have records that haven't been spatially enriched.
+    #[error("{}", unenriched_message(.sources))]
+    UnenrichedData {
+        /// `(source_id, un-enriched record count)` for each offending source.
+        sources: Vec<(String, u64)>,
+    },
+
+    /// Some sources have too few enriched incidents attributed to a census
+    /// tract (see [`GenerateArgs::min_tract_coverage`]).
+    #[error("{}", unattributed_message(.sources, *.min_coverage))]
+    UnattributedData {
+        /// Coverage of each source below the threshold.
+        sources: Vec<TractCoverage>,
+        /// The required fraction.
+        min_coverage: f64,
+    },
+
+    /// A tippecanoe-suite program could not be started (not installed or
+    /// not on `PATH`).
+    #[error("{program} not found (is tippecanoe installed?): {source}")]
+    TippecanoeMissing {
+        /// Program that failed to start (`tippecanoe` or `tile-join`).
+        program: String,
+        /// Underlying spawn error.
+        source: std::io::Error,
+    },
+
+    /// A tippecanoe-suite program exited unsuccessfully.
+    #[error("{program} failed for {target}: {status}")]
+    TippecanoeFailed {
+        /// Program that failed (`tippecanoe` or `tile-join`).
+        program: String,
+        /// File being produced.
+        target: String,
+        /// Exit status.
+        status: std::process::ExitStatus,
+    },
+
+    /// An [`OutputSink`] failed to store a finalized output.
+    #[error("Failed to send {file} to the output sink: {source}")]
+    Sink {
+        /// Output file name.
+        file: String,
+        /// Underlying sink error.
+        source: SinkError,
+    },
+
+    /// The boundaries `DuckDB` needed for boundary/metadata outputs
+    /// doesn't exist or has no census tracts, and `--auto-boundaries`
+    /// wasn't set.
+    #[error(
+        "Boundaries database at {} has no census tracts. Ingest boundaries first \
+         (or pass --auto-boundaries): {command}",
+        .path.display()
+    )]
+    BoundariesMissing {
+        /// Expected path of `boundaries.duckdb`.
+        path: PathBuf,
+        /// `cargo ingest` commands that would ingest the boundaries the
+        /// selected sources need.
+        command: String,
+    },
+
+    /// `--auto-boundaries` failed to ingest the missing boundaries.
+    #[error("Boundary ingestion failed: {0}")]
+    BoundaryIngest(String),
+
+    /// Invalid arguments or source selection.
+    #[error("{0}")]
+    InvalidArgs(String),
+
+    /// `DuckDB` error.
+    #[error("DuckDB error: {0}")]
+    Duckdb(#[from] duckdb::Error),
+
+    /// Error opening or reading a per-source or boundaries `DuckDB`.
+    #[error("Database error: {0}")]
+    Database(#[from] crime_map_database::DbError),
+
+    /// `SQLite` error (sidebar/boundaries search databases).
+    #[error("SQLite error: {0}")]
+    Sqlite(String),
+
+    /// JSON serialization error.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The `--blocklist` file couldn't be parsed.
+    #[error("Invalid blocklist {}: {reason}", .path.display())]
+    InvalidBlocklist {
+        /// Path of the blocklist.
+        path: PathBuf,
+        /// Parse or validation failure.
+        reason: String,
+    },
+
+    /// The `--category-remap` config couldn't be parsed.
+    #[error("Invalid category remap {}: {reason}", .path.display())]
+    InvalidRemap {
+        /// Path of the remap config.
+        path: PathBuf,
+        /// Parse or validation failure.
+        reason: String,
+    },
+
+    /// Enriched incidents reference neighborhood IDs that aren't in the
+    /// neighborhoods reference table (stale enrichment or an ID format
+    /// mismatch).
+    #[error("{}", orphan_neighborhoods_message(.orphans))]
+    OrphanNeighborhoods {
+        /// `(neighborhood_id, incident count)` for up to
+        /// [`MAX_REPORTED_ORPHANS`] orphaned IDs, most frequent first.
+        orphans: Vec<(String, u64)>,
+    },
+
+    /// A boundary extract (see [`export_for_boundary`]) matched no
+    /// exportable incident in any source.
+    #[error("No incidents found in {boundary_type} {geoid}")]
+    BoundaryNotFound {
+        /// Boundary type's CLI name.
+        boundary_type: &'static str,
+        /// Requested geoid.
+        geoid: String,
+    },
+
+    /// A GeoJSON boundary extract matched more incidents than fit in one
+    /// `FeatureCollection` (see [`MAX_FEATURE_COLLECTION_FEATURES`]).
+    #[error(
+        "More than {max} incidents match; too many for a GeoJSON FeatureCollection. \
+         Use the csv or parquet format, or a --limit"
+    )]
+    FeatureCollectionTooLarge {
+        /// The feature limit that was exceeded.
+        max: u64,
+    },
+
+    /// The incidents `PMTiles` would tile more features than
+    /// [`GenerateArgs::max_pmtiles_features`] allows.
+    #[error(
+        "{count} incidents would be tiled into the PMTiles, more than the maximum of {max}. \
+         Narrow the run with --sources, --states, --bbox, or --limit, raise \
+         --max-pmtiles-features, or pass --allow-large"
+    )]
+    TooManyFeatures {
+        /// Exportable incidents that would be tiled.
+        count: u64,
+        /// The maximum that was exceeded.
+        max: u64,
+    },
+
+    /// I/O error.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Maximum number of orphaned neighborhood IDs listed in
+/// [`GenerateError::OrphanNeighborhoods`].
+const MAX_REPORTED_ORPHANS: usize = 10;
+
+/// Formats [`GenerateError::OrphanNeighborhoods`].
+fn orphan_neighborhoods_message(orphans: &[(String, u64)]) -> String {
+    let mut msg = String::from(
+        "Incidents reference neighborhood IDs missing from the neighborhoods table \
+         (expected `nbhd-{id}`). Re-run `cargo ingest enrich` against the current \
+         boundaries.\nOrphaned IDs:\n",
+    );
+    for (id, count) in orphans {
+        writeln!(msg, "  - {id}: {count} incident(s)").unwrap();
+    }
+    msg
+}
+
+/// Formats [`GenerateError::UnattributedData`] with a hint listing the
+/// tract ingest and re-enrichment that usually fix it.
+fn unattributed_message(sources: &[TractCoverage], min_coverage: f64) -> String {
+    let mut msg = format!(
+        "Found sources with under {:.0}% of enriched incidents attributed to a census \
+         tract, usually because their state's tract boundaries weren't ingested when \
+         `cargo ingest enrich` ran.\n\
+         Unattributed sources:\n",
+        min_coverage * 100.0
+    );
+    for coverage in sources {
+        write!(
+            msg,
+            "  - {}: {} of {} incident(s) ({:.1}%)",
+            coverage.source_id,
+            coverage.attributed,
+            coverage.enriched,
+            coverage.fraction() * 100.0
+        )
+        .unwrap();
+        if let Some(fips) = &coverage.state_fips {
+            write!(
+                msg,
+                ", {} tracts likely missing",
+                crime_map_geography_models::fips::state_abbr(fips)
+            )
+            .unwrap();
+        }
+        msg.push('\n');
+    }
+
+    let states: std::collections::BTreeSet<&str> = sources
+        .iter()
+        .filter_map(|c| c.state_fips.as_deref())
+        .collect();
+    msg.push_str("Hint: ");
+    if !states.is_empty() {
+        msg.push_str("cargo ingest tracts --states ");
+        msg.push_str(&states.into_iter().collect::<Vec<_>>().join(","));
+        msg.push_str(" && ");
+    }
+    msg.push_str("cargo ingest enrich --force --sources ");
+    msg.push_str(
+        &sources
+            .iter()
+            .map(|c| c.source_id.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    msg
+}
+
+/// Formats [`GenerateError::UnenrichedData`] with a hint listing the
+/// `cargo ingest enrich` invocation that fixes it.
+fn unenriched_message(sources: &[(String, u64)]) -> String {
+    let mut msg = String::from(
+        "Found un-enriched records. Run `cargo ingest enrich` before generation.\n\
+         Un-enriched sources:\n",
+    );
+    for (sid, count) in sources {
+        writeln!(msg, "  - {sid}: {count} record(s)").unwrap();
+    }
+    msg.push_str("Hint: cargo ingest enrich --sources ");
+    msg.push_str(
+        &sources
+            .iter()
+            .map(|(s, _)| s.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    msg
+}
+
+/// Runs a tippecanoe-suite command, mapping a spawn failure to
+/// [`GenerateError::TippecanoeMissing`] and a non-zero exit to
+/// [`GenerateError::TippecanoeFailed`].
+fn run_tippecanoe(cmd: &mut Command, target: &Path) -> Result<(), GenerateError> {
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let status = cmd
+        .status()
+        .map_err(|source| GenerateError::TippecanoeMissing {
+            program: program.clone(),
+            source,
+        })?;
+    if !status.success() {
+        return Err(GenerateError::TippecanoeFailed {
+            program,
+            target: target.display().to_string(),
+            status,
+        });
+    }
+    Ok(())
+}
+
 /// Opens an output `DuckDB` database with a `2GB` memory limit.
 ///
 /// All generated `DuckDB` files (counts, H3, analytics) should use this
@@ -78,17 +552,119 @@ fn open_output_duckdb(path: &Path) -> Result<duckdb::Connection, duckdb::Error>
     Ok(duck)
 }
 
+/// Sidecar files `DuckDB` (`.wal`) and `SQLite` (`-wal`, `-shm`) keep next
+/// to an open database.
+const DB_SIDECAR_SUFFIXES: &[&str] = &[".wal", "-wal", "-shm"];
+
+/// Returns `path` with `suffix` appended to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Returns the `{name}.tmp` path to build the database output at `path`
+/// in, after removing any partial build (and its sidecars) an interrupted
+/// run left there.
+///
+/// Database outputs are built at the temporary path and only moved into
+/// place by [`commit_atomic_output`] once complete, so a crash leaves the
+/// previous good file intact rather than a partial one that
+/// [`output_needs_regen`]'s existence check would accept.
+///
+/// # Errors
+///
+/// Returns an error if a leftover file cannot be removed.
+fn begin_atomic_output(path: &Path) -> Result<PathBuf, std::io::Error> {
+    let tmp_path = with_suffix(path, ".tmp");
+    for stale in std::iter::once(tmp_path.clone()).chain(
+        DB_SIDECAR_SUFFIXES
+            .iter()
+            .map(|suffix| with_suffix(&tmp_path, suffix)),
+    ) {
+        if stale.exists() {
+            std::fs::remove_file(&stale)?;
+        }
+    }
+    Ok(tmp_path)
+}
+
+/// Moves the finished database at `tmp_path` (see [`begin_atomic_output`])
+/// into place at `path`, replacing the previous output. Sidecars move with
+/// it, and the previous output's are removed so they can't be replayed
+/// into the new file.
+///
+/// Every connection to `tmp_path` must be closed first.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be renamed or removed.
+fn commit_atomic_output(tmp_path: &Path, path: &Path) -> Result<(), std::io::Error> {
+    for suffix in DB_SIDECAR_SUFFIXES {
+        let tmp_sidecar = with_suffix(tmp_path, suffix);
+        let sidecar = with_suffix(path, suffix);
+        if tmp_sidecar.exists() {
+            std::fs::rename(&tmp_sidecar, &sidecar)?;
+        } else if sidecar.exists() {
+            std::fs::remove_file(&sidecar)?;
+        }
+    }
+    std::fs::rename(tmp_path, path)
+}
+
 /// Per-source fingerprint capturing the data state at generation time.
 ///
 /// Since source `DuckDB` files are insert-only (`ON CONFLICT DO NOTHING`),
 /// the combination of `record_count`, `last_synced_at`, and
-/// `max_occurred_at` is a reliable change indicator.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// `max_occurred_at` is a reliable change indicator for new data.
+/// `schema_hash` covers data corrected in place (e.g. a re-normalized
+/// category mapping) where the count doesn't change.
+///
+/// The file's mtime and size are recorded alongside as a cheap first-pass
+/// signal: when both still match, the fingerprint is reused without
+/// opening the database. They are not part of the change comparison (see
+/// [`SourceFingerprint::same_data`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct SourceFingerprint {
     source_id: String,
     name: String,
     record_count: i64,
     last_synced_at: Option<String>,
+    /// Hash of the `incidents` columns and the distinct
+    /// `(parent_category, category, severity)` tuples (see
+    /// [`source_schema_hash`]). `None` in manifests written before it was
+    /// recorded, so those regenerate once.
+    #[serde(default)]
+    schema_hash: Option<String>,
+    /// Source `DuckDB` file modification time, in nanoseconds since the
+    /// Unix epoch.
+    #[serde(default)]
+    file_mtime_ns: Option<u64>,
+    /// Source `DuckDB` file size in bytes.
+    #[serde(default)]
+    file_size: Option<u64>,
+}
+
+impl SourceFingerprint {
+    /// Returns `true` if both fingerprints describe the same source data,
+    /// ignoring the file stat fields.
+    fn same_data(&self, other: &Self) -> bool {
+        self.source_id == other.source_id
+            && self.name == other.name
+            && self.record_count == other.record_count
+            && self.last_synced_at == other.last_synced_at
+            && self.schema_hash == other.schema_hash
+    }
+
+    /// Returns `true` if this fingerprint's recorded file stats match
+    /// `stat`. Fingerprints without stats (older manifests) never match.
+    fn stat_matches(&self, stat: (Option<u64>, Option<u64>)) -> bool {
+        matches!(
+            (self.file_mtime_ns, self.file_size, stat),
+            (Some(mtime), Some(size), (Some(cur_mtime), Some(cur_size)))
+                if mtime == cur_mtime && size == cur_size
+        )
+    }
 }
 
 /// Generation manifest stored at `data/generated/manifest.json`.
@@ -103,9 +679,213 @@ struct Manifest {
     sources_filter: Option<Vec<String>>,
     /// The `--limit` value used, or `None` for unlimited.
     limit: Option<u64>,
+    /// The `--bbox` value used, or `None` for no geographic filter.
+    #[serde(default)]
+    bbox: Option<[f64; 4]>,
+    /// Whether `count_summary_monthly` was materialized in the count DB.
+    #[serde(default)]
+    count_monthly: bool,
+    /// Whether `count_summary_rolling` was materialized in the count DB.
+    #[serde(default)]
+    count_rolling: bool,
+    /// Whether `count_timeseries` was materialized in the count DB.
+    #[serde(default)]
+    count_timeseries: bool,
+    /// The `--sample` fraction used, or `None` for all records.
+    #[serde(default)]
+    sample_fraction: Option<f64>,
+    /// SHA-256 of the `--category-remap` config, or `None` if unset.
+    #[serde(default)]
+    category_remap_hash: Option<String>,
+    /// SHA-256 of the `--blocklist` file, or `None` if unset.
+    #[serde(default)]
+    blocklist_hash: Option<String>,
+    /// Display priority of each source when `--dedup-sources` was set, or
+    /// `None` if duplicates were kept.
+    #[serde(default)]
+    dedup_priorities: Option<BTreeMap<String, i32>>,
+    /// Whether the count and H3 aggregations were grouped by `dow`/`hour`.
+    #[serde(default)]
+    time_dimensions: bool,
+    /// Whether the analytics incidents were also exported as NDJSON.
+    #[serde(default)]
+    analytics_ndjson: bool,
+    /// [`ANALYTICS_SCHEMA_VERSION`] of the last analytics DB build.
+    #[serde(default)]
+    analytics_schema_version: u32,
+    /// [`file_stat`] of the boundaries `DuckDB` the analytics reference
+    /// tables were last copied from.
+    #[serde(default)]
+    analytics_boundaries_stat: (Option<u64>, Option<u64>),
+    /// Latest `occurred_at` (`YYYY-MM-DD HH:MM:SS`, UTC) of each source in
+    /// the analytics DB, the high-water mark incremental updates refresh
+    /// from (see [`analytics_refresh_windows`]).
+    #[serde(default)]
+    analytics_high_water: BTreeMap<String, String>,
+    /// Whether the H3 coverage stats were also written as JSON.
+    #[serde(default)]
+    h3_stats: bool,
+    /// Whether the count DB kept its raw `incidents` table.
+    #[serde(default)]
+    keep_incidents_table: bool,
+    /// Minimum incidents per H3 cell by resolution (see
+    /// [`GenerateArgs::h3_min_cell_count`]).
+    #[serde(default)]
+    h3_min_cell_count: BTreeMap<u8, u32>,
+    /// Boundary simplification zooms by layer (see
+    /// [`GenerateArgs::simplify_boundaries`]), or `None` for raw geometry.
+    #[serde(default)]
+    simplify_boundaries: Option<BTreeMap<String, u8>>,
+    /// Timezone the count and H3 DBs bucketed each source's days in (see
+    /// [`source_timezones`]); sources without one used UTC.
+    #[serde(default)]
+    source_timezones: BTreeMap<String, String>,
+    /// The incidents `PMTiles` clustering used, or `None` for none.
+    #[serde(default)]
+    pmtiles_cluster: Option<PmtilesCluster>,
+    /// The tiler that built the incidents `PMTiles`.
+    #[serde(default)]
+    tile_backend: TileBackend,
+    /// The sensitive-incident jitter applied to the incidents `PMTiles`
+    /// and sidebar DB, or `None` for exact locations.
+    #[serde(default)]
+    sensitive_jitter: Option<SensitiveJitter>,
+    /// [`JitterKey::fingerprint`] of the key `sensitive_jitter` used; the
+    /// key itself is never stored.
+    #[serde(default)]
+    jitter_key_fingerprint: Option<String>,
+    /// The spread applied to stacked points in the incidents `PMTiles`,
+    /// or `None` for none.
+    #[serde(default)]
+    stack_spread: Option<StackSpread>,
+    /// Whether the sidebar DB includes the sources' raw record JSON.
+    #[serde(default)]
+    raw_json: bool,
+    /// The boundary layers in the boundary outputs (see
+    /// [`boundary_types_filter`]), or `None` for all of them.
+    #[serde(default)]
+    boundary_types: Option<Vec<String>>,
     /// Map of output name to ISO 8601 timestamp of last successful
     /// generation.
     outputs: BTreeMap<String, String>,
+    /// Map of output name to the [`build_id`] of the code that last
+    /// generated it.
+    #[serde(default)]
+    builds: BTreeMap<String, String>,
+    /// Map of output name to how long its last generation took, in
+    /// seconds.
+    #[serde(default)]
+    durations_secs: BTreeMap<String, f64>,
+    /// Map of `PMTiles` output name to its [`TileStats`], for outputs last
+    /// generated with [`GenerateArgs::tile_stats`].
+    #[serde(default)]
+    tile_stats: BTreeMap<String, TileStats>,
+}
+
+impl Default for Manifest {
+    /// An empty manifest of the current [`MANIFEST_VERSION`], as if no
+    /// output had been generated yet.
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            source_fingerprints: Vec::new(),
+            sources_filter: None,
+            limit: None,
+            bbox: None,
+            count_monthly: false,
+            count_rolling: false,
+            count_timeseries: false,
+            sample_fraction: None,
+            category_remap_hash: None,
+            blocklist_hash: None,
+            dedup_priorities: None,
+            time_dimensions: false,
+            analytics_ndjson: false,
+            analytics_schema_version: 0,
+            analytics_boundaries_stat: (None, None),
+            analytics_high_water: BTreeMap::new(),
+            h3_stats: false,
+            keep_incidents_table: false,
+            h3_min_cell_count: BTreeMap::new(),
+            simplify_boundaries: None,
+            source_timezones: BTreeMap::new(),
+            pmtiles_cluster: None,
+            tile_backend: TileBackend::default(),
+            sensitive_jitter: None,
+            jitter_key_fingerprint: None,
+            stack_spread: None,
+            raw_json: false,
+            boundary_types: None,
+            outputs: BTreeMap::new(),
+            builds: BTreeMap::new(),
+            durations_secs: BTreeMap::new(),
+            tile_stats: BTreeMap::new(),
+        }
+    }
+}
+
+/// Returns the identifier of the code producing outputs:
+/// `{crate version}+{git commit}` (e.g. `0.1.0+abc123def456`).
+#[must_use]
+pub fn build_id() -> String {
+    format!("{}+{GIT_COMMIT}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Returns the `build` object stamped into `metadata.json`.
+fn build_info() -> serde_json::Value {
+    serde_json::json!({
+        "id": build_id(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "commit": GIT_COMMIT,
+    })
+}
+
+/// Environment variable sizing the global `rayon` pool when `--threads`
+/// isn't given (see [`init_thread_pool`]).
+pub const THREADS_ENV: &str = "CRIME_MAP_THREADS";
+
+/// Sizes the global `rayon` thread pool used by the parallel sections of
+/// generation (e.g. H3 cell boundaries), returning its size.
+///
+/// `threads` (`--threads`) takes precedence over [`THREADS_ENV`]; with
+/// neither, `rayon` starts one thread per core. Call it once at startup,
+/// before any parallel work.
+///
+/// The H3 cell boundaries run on this pool (unless
+/// [`GenerateArgs::h3_threads`] asks for a dedicated one), and the
+/// boundary layer export starts as many workers as it has threads. It is
+/// separate from `DuckDB`'s own threads (4 per output or source
+/// connection), so peak CPU use is roughly this pool plus `DuckDB`'s
+/// threads. Outputs are generated one at a time, so it doesn't multiply
+/// further.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::InvalidArgs`] if [`THREADS_ENV`] isn't a
+/// positive integer, or an error if the pool was already initialized.
+pub fn init_thread_pool(threads: Option<std::num::NonZeroUsize>) -> Result<usize, GenerateError> {
+    let threads =
+        match threads {
+            Some(threads) => Some(threads),
+            None => match std::env::var(THREADS_ENV) {
+                Ok(value) => Some(value.trim().parse::<std::num::NonZeroUsize>().map_err(
+                    |_| {
+                        GenerateError::InvalidArgs(format!(
+                            "{THREADS_ENV} must be a positive number of threads, got {value:?}"
+                        ))
+                    },
+                )?),
+                Err(_) => None,
+            },
+        };
+
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.map_or(0, std::num::NonZeroUsize::get))
+        .build_global()
+        .map_err(std::io::Error::other)?;
+    let size = rayon::current_num_threads();
+    log::debug!("Using {size} rayon thread(s)");
+    Ok(size)
 }
 
 /// Returns the workspace root directory.
@@ -127,6 +907,7 @@ pub fn output_dir() -> PathBuf {
 }
 
 /// Shared arguments for all generate subcommands.
+#[derive(Debug, Clone)]
 pub struct GenerateArgs {
     /// Maximum number of records to export (useful for testing).
     pub limit: Option<u64>,
@@ -144,8 +925,424 @@ pub struct GenerateArgs {
     /// deleting it.
     pub keep_intermediate: bool,
 
+    /// Split the intermediate incidents `GeoJSONSeq` into this many files
+    /// by a spatial hash (see [`geojsonseq_shard`]) for national-scale
+    /// inputs. `None` or `Some(1)` writes a single file.
+    pub geojsonseq_shards: Option<u32>,
+
     /// Force regeneration even if source data hasn't changed.
     pub force: bool,
+
+    /// Simplify boundary polygons with Douglas-Peucker before handing them
+    /// to tippecanoe, keyed by layer: each layer's tolerance is one tile
+    /// pixel at the given zoom (see [`parse_simplify_boundaries`]). Layers
+    /// without an entry, and every layer when `None`, keep their raw
+    /// geometry.
+    pub simplify_boundaries: Option<BTreeMap<String, u8>>,
+
+    /// Geographic bounding box `[min_lng, min_lat, max_lng, max_lat]`.
+    /// Only incidents inside it are exported. Intersected with the
+    /// `--sources`/`--states` filters.
+    pub bbox: Option<[f64; 4]>,
+
+    /// Remove orphaned files left by interrupted runs (see
+    /// [`repair_output_dir`]) before generating.
+    pub repair: bool,
+
+    /// Also materialize `count_summary_monthly` (bucketed by `YYYY-MM`)
+    /// in the count `DuckDB` for coarse time-slider queries.
+    pub count_monthly: bool,
+
+    /// Also materialize `count_summary_rolling` (trailing 30/90/365-day
+    /// counts per cell, see [`create_count_rolling`]) in the count
+    /// `DuckDB` for "recent activity" queries.
+    pub count_rolling: bool,
+
+    /// Also materialize `count_timeseries` (monthly counts per source and
+    /// category, see [`create_count_timeseries`]) in the count `DuckDB`
+    /// for trend charts.
+    pub count_timeseries: bool,
+
+    /// Export only a pseudo-random fraction (`0 < f <= 1`) of each
+    /// source's incidents, selected by a hash of the row ID. Composes with
+    /// `limit`. Sampled outputs are previews: the output directory is
+    /// tagged with [`PREVIEW_MARKER_FILE`] and R2 pushes refuse it.
+    pub sample_fraction: Option<f64>,
+
+    /// Path to a TOML/JSON category remap config (see [`remap`]) applied
+    /// to each incident's categories before it is written to any output.
+    pub category_remap: Option<PathBuf>,
+
+    /// Path to a TOML/JSON blocklist of known-bad incidents (see
+    /// [`blocklist`]) excluded from every output.
+    pub blocklist: Option<PathBuf>,
+
+    /// Drop incidents that duplicate an incident of a higher-priority
+    /// overlapping source (see [`dedup`]). Per-source partitions ignore
+    /// it, since a single source has no cross-source duplicates.
+    pub dedup_sources: bool,
+
+    /// Also group `count_summary` and `h3_counts` by day of week (`dow`,
+    /// `0` = Sunday) and hour of day (`hour`, `0`-`23`). Multiplies the
+    /// row counts of both tables.
+    pub time_dimensions: bool,
+
+    /// Also export the analytics `incidents` table as NDJSON
+    /// ([`paths::ANALYTICS_NDJSON_FILE`]) for consumers without `DuckDB`.
+    pub analytics_ndjson: bool,
+
+    /// Also write the per-resolution H3 coverage stats (always logged)
+    /// to [`paths::H3_STATS_FILE`].
+    pub h3_stats: bool,
+
+    /// Require at least this fraction (`0`-`1`) of each source's
+    /// enriched incidents to have a census tract (see
+    /// [`TractCoverage`]). Catches sources enriched before their state's
+    /// boundaries were ingested, which pass the enrichment check but are
+    /// missing from every boundary-based output. `None` skips the check.
+    pub min_tract_coverage: Option<f64>,
+
+    /// Log sources below `min_tract_coverage` as warnings instead of
+    /// failing the run.
+    pub warn_tract_coverage: bool,
+
+    /// Keep the raw `incidents` staging table in the count `DuckDB`
+    /// instead of dropping it after aggregation, so other outputs can be
+    /// re-derived from it with `h3_from`. Roughly doubles the file size.
+    pub keep_incidents_table: bool,
+
+    /// Build the H3 `DuckDB` from the `incidents` table of this `DuckDB`
+    /// (a count database generated with `keep_incidents_table`) instead
+    /// of scanning the source databases. The incidents are used as they
+    /// were filtered and remapped when that database was generated; only
+    /// `limit` still applies. Always regenerates the H3 output.
+    pub h3_from: Option<PathBuf>,
+
+    /// Minimum incidents (summed over every other dimension) an H3 cell
+    /// needs at each resolution to be kept in `h3_counts`; sparser cells
+    /// are suppressed, for decluttering and k-anonymity. Resolutions
+    /// without an entry keep every cell.
+    pub h3_min_cell_count: BTreeMap<u8, u32>,
+
+    /// Checkpoint the manifest's fingerprints and config before generating
+    /// so outputs completed before an interruption are kept by the next
+    /// run, which resumes from the first un-recorded output. Without this,
+    /// an interrupted first run regenerates every output.
+    pub resume: bool,
+
+    /// Cluster nearby incident points into single features below a zoom
+    /// in the incidents `PMTiles` (see [`PmtilesCluster`]). `None` keeps
+    /// every zoom as individual points.
+    pub pmtiles_cluster: Option<PmtilesCluster>,
+
+    /// Tiler for the incidents `PMTiles` (see [`tiles`]). The boundaries
+    /// `PMTiles` always use tippecanoe.
+    pub tile_backend: TileBackend,
+
+    /// Displace sensitive incidents in the incidents `PMTiles`, sidebar
+    /// DB, and extracts by a deterministic offset for victim privacy (see
+    /// [`jitter`]). `None` plots every incident at its exact location.
+    pub sensitive_jitter: Option<SensitiveJitter>,
+
+    /// Secret the [`Self::sensitive_jitter`] offsets are keyed with,
+    /// required with it (see [`JitterKey::from_env`]). Never written to
+    /// any output.
+    pub jitter_key: Option<JitterKey>,
+
+    /// Spread incidents that share an exact coordinate with another
+    /// incident of their source (e.g. block-centroid geocodes) over a
+    /// small deterministic disc in the incidents `PMTiles`, so stacks
+    /// render as clusters (see [`jitter::StackSpread`]). `None` leaves
+    /// them stacked.
+    pub stack_spread: Option<StackSpread>,
+
+    /// Copy the raw source JSON that `ingest --keep-raw` kept for each
+    /// incident into a `raw_records` table of the sidebar DB, for
+    /// debugging normalization. Makes the DB much larger and exposes
+    /// every source field, so it must never be set for public builds.
+    pub raw_json: bool,
+
+    /// How the sidebar DB's rows are written (see [`SidebarBackend`]).
+    /// Doesn't affect the output.
+    pub sidebar_backend: SidebarBackend,
+
+    /// Refuse to tile the incidents `PMTiles` when more incidents than this
+    /// are exportable, guarding against accidental national-scale runs
+    /// that take hours and fill the disk. `None` allows any count.
+    /// Doesn't affect the output.
+    pub max_pmtiles_features: Option<u64>,
+
+    /// Threads computing H3 cell boundaries for `h3.duckdb`, in a pool of
+    /// their own. `None` runs them on the global pool (see
+    /// [`init_thread_pool`]). Doesn't affect the output.
+    pub h3_threads: Option<std::num::NonZeroUsize>,
+
+    /// Update the existing sidebar DB in place when only a few sources'
+    /// data changed since the last run, replacing just their rows instead
+    /// of rebuilding it. Falls back to a full rebuild whenever the rest of
+    /// the configuration or any schema changed.
+    pub only_changed_sources: bool,
+
+    /// Update the existing analytics DB in place when only source data
+    /// changed, replacing each changed source's rows from the month of
+    /// its previous latest incident onward instead of rebuilding it.
+    /// Falls back to a full rebuild whenever the rest of the
+    /// configuration, any schema, or the reference tables changed.
+    pub incremental_analytics: bool,
+
+    /// When an output needs `boundaries.duckdb` and it has no census
+    /// tracts, ingest the boundaries of the selected states first (see
+    /// [`boundary_states`]) instead of failing. Downloads from `TIGERweb`.
+    pub auto_boundaries: bool,
+
+    /// Read the per-layer feature counts and per-zoom drops of each
+    /// `PMTiles` output generated (see [`tiles::tile_stats`]), log them,
+    /// and record them in the manifest's `tile_stats`.
+    pub tile_stats: bool,
+
+    /// Boundary layers (`states`, `counties`, `places`, `tracts`,
+    /// `neighborhoods`) included in the boundaries `PMTiles` and search
+    /// DB, or `None` for all of them. A city map can leave out `tracts` to
+    /// shrink the `PMTiles` considerably.
+    pub boundary_types: Option<Vec<String>>,
+}
+
+impl Default for GenerateArgs {
+    /// Every source, every incident, and every optional table or check
+    /// off, with the [`DEFAULT_MAX_PMTILES_FEATURES`] guard on.
+    fn default() -> Self {
+        Self {
+            limit: None,
+            sources: None,
+            states: None,
+            keep_intermediate: false,
+            geojsonseq_shards: None,
+            force: false,
+            simplify_boundaries: None,
+            bbox: None,
+            repair: false,
+            count_monthly: false,
+            count_rolling: false,
+            count_timeseries: false,
+            sample_fraction: None,
+            category_remap: None,
+            blocklist: None,
+            dedup_sources: false,
+            time_dimensions: false,
+            analytics_ndjson: false,
+            h3_stats: false,
+            min_tract_coverage: None,
+            warn_tract_coverage: false,
+            keep_incidents_table: false,
+            h3_from: None,
+            h3_min_cell_count: BTreeMap::new(),
+            resume: false,
+            pmtiles_cluster: None,
+            tile_backend: TileBackend::default(),
+            sensitive_jitter: None,
+            jitter_key: None,
+            stack_spread: None,
+            raw_json: false,
+            sidebar_backend: SidebarBackend::default(),
+            max_pmtiles_features: Some(DEFAULT_MAX_PMTILES_FEATURES),
+            h3_threads: None,
+            only_changed_sources: false,
+            incremental_analytics: false,
+            auto_boundaries: false,
+            tile_stats: false,
+            boundary_types: None,
+        }
+    }
+}
+
+/// Low-zoom point clustering for the incidents `PMTiles`, passed to
+/// tippecanoe as `--cluster-distance` and `--cluster-maxzoom`.
+///
+/// At zooms up to `max_zoom`, points within `distance` pixels of each
+/// other are merged into one feature carrying a `point_count` property
+/// (plus `clustered` and `sqrt_point_count`); above it, every incident is
+/// an individual point again. Clients must read `point_count` at low zoom:
+/// a cluster's other properties come from one arbitrary member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PmtilesCluster {
+    /// Cluster radius in screen pixels (`1`-`255`).
+    pub distance: u32,
+    /// Highest zoom level that is clustered (`0`-`13`).
+    pub max_zoom: u32,
+}
+
+/// Validates clustering parameters: `distance` within `1..=255` (the
+/// tippecanoe limit) and `max_zoom` below the maximum tile zoom of `14`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if a parameter is out of range.
+pub fn validate_pmtiles_cluster(cluster: &PmtilesCluster) -> Result<(), String> {
+    if !(1..=255).contains(&cluster.distance) {
+        return Err(format!(
+            "cluster distance ({}) must be within 1-255 pixels",
+            cluster.distance
+        ));
+    }
+    if cluster.max_zoom >= 14 {
+        return Err(format!(
+            "cluster max zoom ({}) must be below 14",
+            cluster.max_zoom
+        ));
+    }
+    Ok(())
+}
+
+/// Parses a `--bbox` value of the form `min_lng,min_lat,max_lng,max_lat`.
+///
+/// # Errors
+///
+/// Returns an error if the value doesn't have four numeric components or
+/// fails [`validate_bbox`].
+pub fn parse_bbox(s: &str) -> Result<[f64; 4], String> {
+    let parts: Vec<f64> = s
+        .split(',')
+        .map(|p| {
+            p.trim()
+                .parse::<f64>()
+                .map_err(|e| format!("Invalid bbox component '{p}': {e}"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let bbox: [f64; 4] = parts
+        .try_into()
+        .map_err(|_| format!("Expected 4 comma-separated values in bbox, got '{s}'"))?;
+    validate_bbox(&bbox)?;
+    Ok(bbox)
+}
+
+/// Validates a bounding box: all values finite, within WGS84 range, and
+/// `min < max` on both axes.
+///
+/// # Errors
+///
+/// Returns a description of the first problem found.
+pub fn validate_bbox(bbox: &[f64; 4]) -> Result<(), String> {
+    let [min_lng, min_lat, max_lng, max_lat] = *bbox;
+    if bbox.iter().any(|v| !v.is_finite()) {
+        return Err("bbox values must be finite".to_string());
+    }
+    if !(-180.0..=180.0).contains(&min_lng) || !(-180.0..=180.0).contains(&max_lng) {
+        return Err("bbox longitudes must be within [-180, 180]".to_string());
+    }
+    if !(-90.0..=90.0).contains(&min_lat) || !(-90.0..=90.0).contains(&max_lat) {
+        return Err("bbox latitudes must be within [-90, 90]".to_string());
+    }
+    if min_lng >= max_lng {
+        return Err(format!(
+            "bbox min_lng ({min_lng}) must be < max_lng ({max_lng})"
+        ));
+    }
+    if min_lat >= max_lat {
+        return Err(format!(
+            "bbox min_lat ({min_lat}) must be < max_lat ({max_lat})"
+        ));
+    }
+    Ok(())
+}
+
+/// Parses a `--sample` value: a fraction in `(0, 1]`.
+///
+/// # Errors
+///
+/// Returns an error if the value isn't a number or fails
+/// [`validate_sample_fraction`].
+pub fn parse_sample_fraction(s: &str) -> Result<f64, String> {
+    let fraction = s
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| format!("Invalid sample fraction '{s}': {e}"))?;
+    validate_sample_fraction(fraction)?;
+    Ok(fraction)
+}
+
+/// Validates a sample fraction: finite and within `(0, 1]`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the fraction is out of range.
+pub fn validate_sample_fraction(fraction: f64) -> Result<(), String> {
+    if !fraction.is_finite() || fraction <= 0.0 || fraction > 1.0 {
+        return Err(format!(
+            "sample fraction ({fraction}) must be within (0, 1]"
+        ));
+    }
+    Ok(())
+}
+
+/// Parses a `--min-tract-coverage` fraction (see
+/// [`validate_min_tract_coverage`]).
+///
+/// # Errors
+///
+/// Returns a description of the problem if `s` isn't a number in `[0, 1]`.
+pub fn parse_min_tract_coverage(s: &str) -> Result<f64, String> {
+    let fraction = s
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| format!("Invalid tract coverage '{s}': {e}"))?;
+    validate_min_tract_coverage(fraction)?;
+    Ok(fraction)
+}
+
+/// Validates a minimum tract coverage: finite and within `[0, 1]`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the fraction is out of range.
+pub fn validate_min_tract_coverage(fraction: f64) -> Result<(), String> {
+    if !fraction.is_finite() || !(0.0..=1.0).contains(&fraction) {
+        return Err(format!(
+            "minimum tract coverage ({fraction}) must be within [0, 1]"
+        ));
+    }
+    Ok(())
+}
+
+/// Parses a `--h3-min-cell-count` value: either a single count applied to
+/// every H3 resolution (`3`) or comma-separated `resolution=count` pairs
+/// (`8=3,9=5`).
+///
+/// # Errors
+///
+/// Returns a description of the problem if a resolution isn't generated
+/// or a count isn't a positive integer.
+pub fn parse_h3_min_cell_count(s: &str) -> Result<BTreeMap<u8, u32>, String> {
+    let parse_count = |count: &str| match count.trim().parse::<u32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!(
+            "Invalid H3 minimum cell count '{count}': must be a positive integer"
+        )),
+    };
+
+    if !s.contains('=') {
+        let count = parse_count(s)?;
+        return Ok(H3_RESOLUTIONS.iter().map(|&r| (r, count)).collect());
+    }
+
+    s.split(',')
+        .map(|pair| {
+            let (resolution, count) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected 'resolution=count', got '{pair}'"))?;
+            let resolution = resolution
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|r| H3_RESOLUTIONS.contains(r))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid H3 resolution '{resolution}': expected one of {H3_RESOLUTIONS:?}"
+                    )
+                })?;
+            Ok((resolution, parse_count(count)?))
+        })
+        .collect()
 }
 
 /// Runs the generation pipeline with manifest-based caching.
@@ -153,67 +1350,191 @@ pub struct GenerateArgs {
 /// Compares current source fingerprints against the stored manifest to
 /// determine which `requested_outputs` actually need regeneration. Skips
 /// outputs that are already up-to-date unless `--force` is specified.
+/// Outputs [derived](OutputInfo::derived_from) from another bring it up to
+/// date too.
 ///
 /// # Errors
 ///
-/// Returns an error if the database query, file I/O, or any generation
-/// step fails.
+/// Returns [`GenerateError::UnenrichedData`] if any selected source has
+/// un-enriched records, [`GenerateError::UnattributedData`] if one is
+/// below `args.min_tract_coverage`, [`GenerateError::BoundariesMissing`] if a boundary
+/// or metadata output is requested without census tracts in
+/// `boundaries.duckdb` (unless `args.auto_boundaries` ingests them),
+/// [`GenerateError::TippecanoeMissing`]/[`GenerateError::TippecanoeFailed`]
+/// for `PMTiles` failures, and a database or I/O variant if any other
+/// generation step fails.
 ///
 /// # Panics
 ///
 /// Panics if a spatial-index-dependent output is requested but the index
 /// failed to load (this should never happen in practice since load errors
 /// are propagated before the output runs).
-#[allow(clippy::too_many_lines, clippy::future_not_send)]
+#[allow(clippy::future_not_send)]
 pub async fn run_with_cache(
     args: &GenerateArgs,
     source_ids: &[String],
     dir: &Path,
     requested_outputs: &[&str],
     progress: Option<Arc<dyn ProgressCallback>>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    log::info!("Querying source fingerprints...");
-    let fingerprints = query_fingerprints(source_ids)?;
+) -> Result<(), GenerateError> {
+    run_with_sink(args, source_ids, dir, requested_outputs, progress, None).await
+}
 
-    // Count the actual exportable records (must match the export WHERE clause)
-    let total_records = count_exportable_records(source_ids)?;
-    log::info!(
-        "Found {} sources, {total_records} exportable records",
-        fingerprints.len()
-    );
+/// Like [`run_with_cache`], additionally handing each finalized database
+/// and JSON output to `sink` (if set) and deleting the local copy. See
+/// [`sink`] for why and what is excluded.
+///
+/// # Errors
+///
+/// Same as [`run_with_cache`], plus [`GenerateError::Sink`] if the sink
+/// fails, and [`GenerateError::InvalidArgs`] if a sink is combined with
+/// `--sample`, whose preview outputs must not leave the machine.
+///
+/// # Panics
+///
+/// Same as [`run_with_cache`].
+#[allow(clippy::too_many_lines, clippy::future_not_send)]
+pub async fn run_with_sink(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    dir: &Path,
+    requested_outputs: &[&str],
+    progress: Option<Arc<dyn ProgressCallback>>,
+    sink: Option<&dyn OutputSink>,
+) -> Result<(), GenerateError> {
+    if sink.is_some() && args.sample_fraction.is_some() {
+        return Err(GenerateError::InvalidArgs(
+            "Sampled (preview) outputs can't be sent to an output sink".to_string(),
+        ));
+    }
+    if let Some(bbox) = &args.bbox {
+        validate_bbox(bbox).map_err(GenerateError::InvalidArgs)?;
+    }
+    if let Some(fraction) = args.sample_fraction {
+        validate_sample_fraction(fraction).map_err(GenerateError::InvalidArgs)?;
+    }
+    if let Some(min_coverage) = args.min_tract_coverage {
+        validate_min_tract_coverage(min_coverage).map_err(GenerateError::InvalidArgs)?;
+    }
+    if let Some(cluster) = &args.pmtiles_cluster {
+        validate_pmtiles_cluster(cluster).map_err(GenerateError::InvalidArgs)?;
+        if args.tile_backend == TileBackend::Native {
+            return Err(GenerateError::InvalidArgs(
+                "Point clustering requires the tippecanoe tile backend".to_string(),
+            ));
+        }
+    }
+    keyed_jitter(args)?;
+    if let Some(spread) = args.stack_spread {
+        jitter::validate_stack_spread(spread).map_err(GenerateError::InvalidArgs)?;
+    }
+    if let Some(types) = &args.boundary_types {
+        validate_boundary_types(types).map_err(GenerateError::InvalidArgs)?;
+    }
+    if args.geojsonseq_shards == Some(0) {
+        return Err(GenerateError::InvalidArgs(
+            "GeoJSONSeq shard count must be at least 1".to_string(),
+        ));
+    }
+    if let Some(from) = &args.h3_from
+        && !from.is_file()
+    {
+        return Err(GenerateError::InvalidArgs(format!(
+            "H3 input database {} does not exist",
+            from.display()
+        )));
+    }
+    let remap = CategoryRemap::load(args.category_remap.as_deref())?;
+    let mut blocklist = load_blocklist(args, source_ids)?;
+    if !blocklist.is_empty() {
+        log::info!("Blocklist excludes {} incident(s)", blocklist.len());
+    }
 
-    // Validate that all records have been spatially enriched
-    if total_records > 0 {
-        validate_enrichment(source_ids)?;
+    let mut manifest = load_manifest(dir);
+
+    if args.repair {
+        let removed = repair_output_dir(dir)?;
+        if let Some(m) = manifest.as_mut() {
+            invalidate_repaired_outputs(m, dir, &removed);
+        }
     }
 
-    // Each output processes all records, so total work = outputs_needing_regen * total_records.
-    // But we use a single progress bar showing records for the current output being generated.
+    log::info!("Querying source fingerprints...");
+    let fingerprints = query_fingerprints(source_ids, manifest.as_ref())?;
+    let dedup_priorities = args
+        .dedup_sources
+        .then(|| dedup::source_priorities(source_ids));
+
+    // `progress` tracks outputs completed; each output gets a child bar
+    // tracking the records it has processed.
     let progress = progress.unwrap_or_else(crime_map_source::progress::null_progress);
 
-    let mut manifest = load_manifest(dir);
-    let sources_filter = sorted_sources_filter(args);
+    let mut requested = requested_outputs.to_vec();
+    for &name in requested_outputs {
+        if let Some(parent) = output_info(name).and_then(|info| info.derived_from)
+            && !requested.contains(&parent)
+        {
+            requested.push(parent);
+        }
+    }
 
     // Determine what needs regeneration
-    let needs: BTreeMap<&str, bool> = requested_outputs
-        .iter()
-        .map(|&name| {
-            let path = output_file_path(dir, name);
-            let needed = output_needs_regen(
-                manifest.as_ref(),
-                &fingerprints,
-                name,
-                &path,
-                sources_filter.as_deref(),
-                args.limit,
-                args.force,
-            );
-            (name, needed)
-        })
-        .collect();
+    let mut needs = outputs_needing_regen(
+        args,
+        manifest.as_ref(),
+        &fingerprints,
+        &remap,
+        &blocklist,
+        dedup_priorities.as_ref(),
+        dir,
+        &requested,
+    );
+    for info in output_catalog() {
+        if let Some(parent) = info.derived_from
+            && needs.get(parent) == Some(&true)
+            && let Some(needed) = needs.get_mut(info.name)
+        {
+            *needed = true;
+        }
+    }
+
+    // Decided before `--resume` drops the outputs being rebuilt from the
+    // manifest, which would make every output look unrecorded.
+    let sidebar_changed = if needs.get(OUTPUT_INCIDENTS_DB) == Some(&true) {
+        sidebar_changed_sources(
+            args,
+            manifest.as_ref(),
+            &fingerprints,
+            &remap,
+            &blocklist,
+            dedup_priorities.as_ref(),
+            dir,
+        )
+    } else {
+        None
+    };
+    let analytics_windows = if needs.get(OUTPUT_ANALYTICS_DB) == Some(&true) {
+        analytics_refresh_windows(
+            args,
+            manifest.as_ref(),
+            &fingerprints,
+            &remap,
+            &blocklist,
+            dedup_priorities.as_ref(),
+            dir,
+        )
+    } else {
+        None
+    };
 
     if needs.values().all(|&v| !v) {
         log::info!("All requested outputs are up-to-date, nothing to regenerate");
+        // Refresh the recorded file stats so the next run can take the
+        // fast path (e.g. manifests written before stats were recorded).
+        if let Some(manifest) = manifest.as_mut() {
+            manifest.source_fingerprints = fingerprints;
+            save_manifest(dir, manifest)?;
+        }
         return Ok(());
     }
 
@@ -225,142 +1546,473 @@ pub async fn run_with_cache(
         }
     }
 
-    // Ensure we have a manifest to update
-    let manifest = manifest.get_or_insert_with(|| Manifest {
-        version: MANIFEST_VERSION,
-        source_fingerprints: Vec::new(),
-        sources_filter: None,
-        limit: None,
-        outputs: BTreeMap::new(),
-    });
+    // Only now open the source databases: an up-to-date run returns above
+    // without touching them when every file stat matches the manifest.
+    //
+    // Count the actual exportable records (must match the export WHERE clause)
+    let (total_records, blocked_records) = count_exportable_records(args, source_ids, &blocklist)?;
+    log::info!(
+        "Found {} sources, {total_records} exportable records ({blocked_records} excluded by blocklist)",
+        fingerprints.len()
+    );
+
+    if remap.has_severity_overrides() {
+        let overridden = count_severity_overrides(args, source_ids, &remap, &blocklist)?;
+        log::info!("Severity overrides change the severity of {overridden} incident(s)");
+    }
+
+    if let Some(fallback) = remap.taxonomy().fallback() {
+        let unmapped = count_unmapped_subcategories(args, source_ids, &remap, &blocklist)?;
+        log::info!(
+            "Taxonomy fallback groups {} incident(s) in {} unmapped subcategories under {fallback}",
+            unmapped.values().sum::<u64>(),
+            unmapped.len()
+        );
+        let mut by_count: Vec<_> = unmapped.into_iter().collect();
+        by_count.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        for (subcategory, count) in by_count {
+            log::info!("  unmapped subcategory {subcategory}: {count} incident(s)");
+        }
+    }
+
+    // Validate that all records have been spatially enriched
+    if total_records > 0 {
+        validate_enrichment(args, source_ids, &blocklist)?;
+        if let Some(min_coverage) = args.min_tract_coverage {
+            validate_tract_coverage(args, source_ids, &blocklist, min_coverage)?;
+        }
+    }
+
+    if let Some(priorities) = &dedup_priorities {
+        log::info!("Finding cross-source duplicates...");
+        let suppressed = dedup::suppress_duplicates(
+            source_ids,
+            priorities,
+            &exportable_filter(args),
+            &mut blocklist,
+        )?;
+        for (sid, count) in &suppressed {
+            log::info!(
+                "Suppressed {count} incident(s) from '{sid}' duplicating higher-priority sources"
+            );
+        }
+        log::info!(
+            "Suppressed {} duplicate incident(s) in total",
+            suppressed.values().sum::<u64>()
+        );
+    }
+
+    // Ensure we have a manifest to update
+    let manifest = manifest.get_or_insert_with(Manifest::default);
+
+    // Drop the outputs about to be rebuilt and record the new config now,
+    // so after an interruption only the unfinished outputs are stale.
+    if args.resume {
+        for (&name, _) in needs.iter().filter(|&(_, &needed)| needed) {
+            manifest.outputs.remove(name);
+            manifest.builds.remove(name);
+            manifest.durations_secs.remove(name);
+            manifest.tile_stats.remove(name);
+        }
+        stamp_manifest(
+            manifest,
+            args,
+            &fingerprints,
+            &remap,
+            &blocklist,
+            dedup_priorities.as_ref(),
+        );
+        save_manifest(dir, manifest)?;
+    }
 
     // Build spatial index if any output that uses it is needed
     // NOTE: Spatial enrichment now happens at ingest time (`cargo ingest enrich`).
     // The spatial index is no longer loaded here for per-incident lookups.
     // It is still needed for boundary generation (PMTiles, DB).
 
-    // Open boundaries DuckDB for boundary outputs
-    let needs_boundaries = needs.get(OUTPUT_BOUNDARIES_PMTILES) == Some(&true)
-        || needs.get(OUTPUT_BOUNDARIES_DB) == Some(&true)
-        || needs.get(OUTPUT_METADATA) == Some(&true);
+    // Open boundaries DuckDB for the outputs that read it
+    let needs_boundaries = needs.iter().any(|(&name, &needed)| {
+        needed && output_info(name).is_some_and(|info| info.needs_boundaries)
+    });
 
     let boundaries_conn = if needs_boundaries {
+        ensure_boundaries(args, source_ids).await?;
         Some(crime_map_database::boundaries_db::open_default()?)
     } else {
         None
     };
 
+    // Tag the directory before writing anything so even a partial sampled
+    // run can't be pushed as production artifacts.
+    if args.sample_fraction.is_some() {
+        std::fs::write(dir.join(PREVIEW_MARKER_FILE), "")?;
+    }
+
     // Run each output that needs it
+    #[allow(clippy::cast_possible_truncation)]
+    let outputs_to_run = needs.values().filter(|&&needed| needed).count() as u64;
+    progress.set_message("Generating outputs".to_string());
+    progress.set_total(outputs_to_run);
+    progress.set_position(0);
+
     if needs.get(OUTPUT_INCIDENTS_PMTILES) == Some(&true) {
-        progress.set_message("Generating PMTiles...".to_string());
-        progress.set_total(total_records);
-        progress.set_position(0);
-        generate_pmtiles(args, source_ids, dir, &progress)?;
-        record_output(manifest, OUTPUT_INCIDENTS_PMTILES);
+        check_pmtiles_feature_count(args, total_records)?;
+        let run = start_output(
+            &progress,
+            OUTPUT_INCIDENTS_PMTILES,
+            "Generating PMTiles...",
+            total_records,
+        );
+        generate_pmtiles(args, source_ids, &remap, &blocklist, dir, &run.child)?;
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_INCIDENTS_PMTILES, elapsed);
+        record_tile_stats(manifest, args, dir, OUTPUT_INCIDENTS_PMTILES);
         save_manifest(dir, manifest)?;
     }
 
     if needs.get(OUTPUT_INCIDENTS_DB) == Some(&true) {
-        progress.set_message("Generating sidebar DB...".to_string());
-        progress.set_total(total_records);
-        progress.set_position(0);
-        generate_sidebar_db(args, source_ids, dir, &progress).await?;
-        record_output(manifest, OUTPUT_INCIDENTS_DB);
+        let run = start_output(
+            &progress,
+            OUTPUT_INCIDENTS_DB,
+            "Generating sidebar DB...",
+            total_records,
+        );
+        generate_sidebar_db(
+            args,
+            source_ids,
+            &remap,
+            &blocklist,
+            sidebar_changed.as_deref(),
+            dir,
+            &run.child,
+        )
+        .await?;
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_INCIDENTS_DB, elapsed);
         save_manifest(dir, manifest)?;
+        sink_output(sink, args, dir, OUTPUT_INCIDENTS_DB).await?;
     }
 
     if needs.get(OUTPUT_COUNT_DB) == Some(&true) {
-        progress.set_message("Generating count DB...".to_string());
-        progress.set_total(total_records);
-        progress.set_position(0);
-        generate_count_db(args, source_ids, dir, &progress)?;
-        record_output(manifest, OUTPUT_COUNT_DB);
+        let run = start_output(
+            &progress,
+            OUTPUT_COUNT_DB,
+            "Generating count DB...",
+            total_records,
+        );
+        generate_count_db(args, source_ids, &remap, &blocklist, dir, &run.child)?;
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_COUNT_DB, elapsed);
         save_manifest(dir, manifest)?;
+        sink_output(sink, args, dir, OUTPUT_COUNT_DB).await?;
     }
 
     if needs.get(OUTPUT_H3_DB) == Some(&true) {
-        progress.set_message("Generating H3 hexbin DB...".to_string());
-        progress.set_total(total_records);
-        progress.set_position(0);
-        generate_h3_db(args, source_ids, dir, &progress)?;
-        record_output(manifest, OUTPUT_H3_DB);
+        let run = start_output(
+            &progress,
+            OUTPUT_H3_DB,
+            "Generating H3 hexbin DB...",
+            total_records,
+        );
+        generate_h3_db(args, source_ids, &remap, &blocklist, dir, &run.child)?;
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_H3_DB, elapsed);
         save_manifest(dir, manifest)?;
+        sink_output(sink, args, dir, OUTPUT_H3_DB).await?;
     }
 
     if needs.get(OUTPUT_METADATA) == Some(&true) {
-        progress.set_message("Generating server metadata...".to_string());
-        progress.set_total(0);
-        progress.set_position(0);
+        let run = start_output(
+            &progress,
+            OUTPUT_METADATA,
+            "Generating server metadata...",
+            0,
+        );
         generate_metadata(
+            args,
             source_ids,
+            &blocklist,
             boundaries_conn
                 .as_ref()
                 .expect("boundaries connection required"),
             dir,
         )?;
-        record_output(manifest, OUTPUT_METADATA);
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_METADATA, elapsed);
         save_manifest(dir, manifest)?;
+        sink_output(sink, args, dir, OUTPUT_METADATA).await?;
     }
 
     if needs.get(OUTPUT_BOUNDARIES_PMTILES) == Some(&true) {
-        progress.set_message("Generating boundaries PMTiles...".to_string());
-        progress.set_total(0);
-        progress.set_position(0);
+        let run = start_output(
+            &progress,
+            OUTPUT_BOUNDARIES_PMTILES,
+            "Generating boundaries PMTiles...",
+            0,
+        );
         generate_boundaries_pmtiles(
             boundaries_conn
                 .as_ref()
                 .expect("boundaries connection required"),
             dir,
-            &progress,
+            args.simplify_boundaries.as_ref(),
+            args.boundary_types.as_deref(),
+            &run.child,
         )?;
-        record_output(manifest, OUTPUT_BOUNDARIES_PMTILES);
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_BOUNDARIES_PMTILES, elapsed);
+        record_tile_stats(manifest, args, dir, OUTPUT_BOUNDARIES_PMTILES);
         save_manifest(dir, manifest)?;
     }
 
     if needs.get(OUTPUT_BOUNDARIES_DB) == Some(&true) {
-        progress.set_message("Generating boundaries search DB...".to_string());
-        progress.set_total(0);
-        progress.set_position(0);
+        let run = start_output(
+            &progress,
+            OUTPUT_BOUNDARIES_DB,
+            "Generating boundaries search DB...",
+            0,
+        );
         generate_boundaries_db(
             boundaries_conn
                 .as_ref()
                 .expect("boundaries connection required"),
             dir,
+            args.boundary_types.as_deref(),
         )
         .await?;
-        record_output(manifest, OUTPUT_BOUNDARIES_DB);
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_BOUNDARIES_DB, elapsed);
         save_manifest(dir, manifest)?;
+        sink_output(sink, args, dir, OUTPUT_BOUNDARIES_DB).await?;
     }
 
     if needs.get(OUTPUT_ANALYTICS_DB) == Some(&true) {
-        progress.set_message("Generating analytics DB...".to_string());
-        progress.set_total(total_records);
-        progress.set_position(0);
-        generate_analytics_db(
+        let run = start_output(
+            &progress,
+            OUTPUT_ANALYTICS_DB,
+            "Generating analytics DB...",
+            total_records,
+        );
+        let boundaries_stat = file_stat(&crime_map_database::paths::boundaries_db_path());
+        manifest.analytics_high_water = generate_analytics_db(
             args,
             source_ids,
+            &remap,
+            &blocklist,
+            analytics_windows.as_ref(),
             boundaries_conn
                 .as_ref()
                 .expect("boundaries connection required"),
             dir,
-            &progress,
+            &run.child,
         )?;
-        record_output(manifest, OUTPUT_ANALYTICS_DB);
+        manifest.analytics_schema_version = ANALYTICS_SCHEMA_VERSION;
+        manifest.analytics_boundaries_stat = boundaries_stat;
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_ANALYTICS_DB, elapsed);
+        save_manifest(dir, manifest)?;
+        // The choropleth is derived from the local file, so it's sent on
+        // after that.
+        if needs.get(OUTPUT_CHOROPLETH_DB) != Some(&true) {
+            sink_output(sink, args, dir, OUTPUT_ANALYTICS_DB).await?;
+        }
+    }
+
+    if needs.get(OUTPUT_CHOROPLETH_DB) == Some(&true) {
+        let run = start_output(
+            &progress,
+            OUTPUT_CHOROPLETH_DB,
+            "Generating tract choropleth DB...",
+            total_records,
+        );
+        generate_choropleth_db(dir)?;
+        let elapsed = finish_output(&progress, &run);
+        record_output(manifest, OUTPUT_CHOROPLETH_DB, elapsed);
         save_manifest(dir, manifest)?;
+        if needs.get(OUTPUT_ANALYTICS_DB) == Some(&true) {
+            sink_output(sink, args, dir, OUTPUT_ANALYTICS_DB).await?;
+        }
+        sink_output(sink, args, dir, OUTPUT_CHOROPLETH_DB).await?;
     }
 
     // Update manifest with current fingerprints and config
-    manifest.source_fingerprints.clone_from(&fingerprints);
-    manifest.sources_filter.clone_from(&sources_filter);
-    manifest.limit = args.limit;
-    manifest.version = MANIFEST_VERSION;
+    stamp_manifest(
+        manifest,
+        args,
+        &fingerprints,
+        &remap,
+        &blocklist,
+        dedup_priorities.as_ref(),
+    );
     save_manifest(dir, manifest)?;
 
+    let marker = dir.join(PREVIEW_MARKER_FILE);
+    if args.sample_fraction.is_none() && marker.exists() {
+        std::fs::remove_file(&marker)?;
+    }
+
     cleanup_intermediate(args, dir);
 
     Ok(())
 }
 
+// ============================================================
+// Per-source partitions
+// ============================================================
+
+/// Outputs produced for a single-source partition.
+///
+/// Boundary outputs are excluded because they don't depend on incident
+/// data and are generated once for the whole merged set. The opt-in
+/// choropleth is derived from the merged analytics DB instead (see
+/// [`merge::run`]).
+pub const PARTITION_OUTPUTS: &[&str] = &[
+    OUTPUT_INCIDENTS_PMTILES,
+    OUTPUT_INCIDENTS_DB,
+    OUTPUT_COUNT_DB,
+    OUTPUT_H3_DB,
+    OUTPUT_METADATA,
+    OUTPUT_ANALYTICS_DB,
+];
+
+/// Returns the output directory for a single-source partition:
+/// `data/generated/partitions/{source_id}/`.
+#[must_use]
+pub fn partition_dir(source_id: &str) -> PathBuf {
+    output_dir().join("partitions").join(source_id)
+}
+
+/// Generates the full set of [`PARTITION_OUTPUTS`] containing only
+/// `source_id`'s data into [`partition_dir`].
+///
+/// The partition has its own `manifest.json` scoped to that one source, so
+/// re-running only rebuilds the partition when that source changed. The
+/// outputs use exactly the same generators (and therefore the same
+/// schemas) as a full run, so [`merge::run`] can combine any set of
+/// partitions by straight concatenation/aggregation.
+///
+/// `args.sources`, `args.states`, `args.dedup_sources`, and
+/// `args.h3_from` are ignored, since a partition always scans exactly one
+/// source, and so is `args.h3_min_cell_count`, which [`merge::run`]
+/// applies to the summed counts instead. All other settings are honored.
+///
+/// # Errors
+///
+/// Returns an error if the source has no `DuckDB` file or any generation
+/// step fails.
+#[allow(clippy::future_not_send)]
+pub async fn generate_partition(
+    args: &GenerateArgs,
+    source_id: &str,
+    progress: Option<Arc<dyn ProgressCallback>>,
+) -> Result<PathBuf, GenerateError> {
+    let partition_args = partition_args(args, source_id);
+
+    let source_ids = resolve_source_ids(&partition_args)?;
+    let dir = partition_dir(source_id);
+    std::fs::create_dir_all(&dir)?;
+
+    log::info!(
+        "Generating partition for source '{source_id}' into {}",
+        dir.display()
+    );
+    run_with_cache(
+        &partition_args,
+        &source_ids,
+        &dir,
+        PARTITION_OUTPUTS,
+        progress,
+    )
+    .await?;
+
+    Ok(dir)
+}
+
+/// Which partitions a [`generate_partitions`] run skipped and which it
+/// (re)generated.
+#[derive(Debug, Default, Clone)]
+pub struct PartitionRunReport {
+    /// Source IDs whose partition was already complete.
+    pub skipped: Vec<String>,
+    /// Source IDs whose partition was generated or resumed.
+    pub generated: Vec<String>,
+}
+
+/// Generates a partition (see [`generate_partition`]) for each of
+/// `source_ids` in order.
+///
+/// With `args.resume`, partitions whose [`PARTITION_OUTPUTS`] are all
+/// recorded as up-to-date in their manifest are skipped without scanning
+/// the source, and an interrupted partition resumes from its first
+/// un-recorded output. Re-running the same command after an interruption
+/// therefore only does the remaining work.
+///
+/// # Errors
+///
+/// Returns an error if any partition fails to generate. Partitions
+/// finished before the failure keep their outputs.
+#[allow(clippy::future_not_send)]
+pub async fn generate_partitions(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    progress: Option<Arc<dyn ProgressCallback>>,
+) -> Result<PartitionRunReport, GenerateError> {
+    let mut report = PartitionRunReport::default();
+
+    for source_id in source_ids {
+        if args.resume && !args.force && partition_is_complete(args, source_id)? {
+            log::info!("Partition '{source_id}' is complete, skipping");
+            report.skipped.push(source_id.clone());
+            continue;
+        }
+
+        generate_partition(args, source_id, progress.clone()).await?;
+        report.generated.push(source_id.clone());
+    }
+
+    Ok(report)
+}
+
+/// Returns whether every [`PARTITION_OUTPUTS`] entry of `source_id`'s
+/// partition is up-to-date under `args`.
+fn partition_is_complete(args: &GenerateArgs, source_id: &str) -> Result<bool, GenerateError> {
+    let dir = partition_dir(source_id);
+    let Some(manifest) = load_manifest(&dir) else {
+        return Ok(false);
+    };
+
+    let partition_args = partition_args(args, source_id);
+    let source_ids = resolve_source_ids(&partition_args)?;
+    let fingerprints = query_fingerprints(&source_ids, Some(&manifest))?;
+    let remap = CategoryRemap::load(args.category_remap.as_deref())?;
+    let blocklist = load_blocklist(args, &source_ids)?;
+
+    Ok(outputs_needing_regen(
+        &partition_args,
+        Some(&manifest),
+        &fingerprints,
+        &remap,
+        &blocklist,
+        None,
+        &dir,
+        PARTITION_OUTPUTS,
+    )
+    .values()
+    .all(|&needed| !needed))
+}
+
+/// Returns `args` scoped to the single source `source_id`.
+fn partition_args(args: &GenerateArgs, source_id: &str) -> GenerateArgs {
+    GenerateArgs {
+        sources: Some(source_id.to_string()),
+        states: None,
+        dedup_sources: false,
+        h3_from: None,
+        h3_min_cell_count: BTreeMap::new(),
+        ..args.clone()
+    }
+}
+
 // ============================================================
 // Manifest / caching infrastructure
 // ============================================================
@@ -368,6 +2020,11 @@ pub async fn run_with_cache(
 /// Queries per-source `DuckDB` `_meta` tables for fingerprints used to
 /// detect data changes.
 ///
+/// If the mtime and size of a source's files, including any `.wal`
+/// sidecar (see [`shards_stat`]), match the fingerprint recorded in
+/// `manifest`, that fingerprint is reused without opening the database.
+/// Otherwise the `_meta` tables are queried.
+///
 /// Returns one [`SourceFingerprint`] per source, ordered by source ID.
 ///
 /// # Errors
@@ -375,24 +2032,53 @@ pub async fn run_with_cache(
 /// Returns an error if any source database cannot be opened or queried.
 fn query_fingerprints(
     source_ids: &[String],
-) -> Result<Vec<SourceFingerprint>, Box<dyn std::error::Error>> {
+    manifest: Option<&Manifest>,
+) -> Result<Vec<SourceFingerprint>, GenerateError> {
     let mut fingerprints = Vec::with_capacity(source_ids.len());
+    let mut reused = 0_usize;
 
     for sid in source_ids {
-        let path = crime_map_database::paths::source_db_path(sid);
-        if !path.exists() {
+        let paths = crime_map_database::source_db::shard_paths(sid);
+        if paths.is_empty() {
             log::warn!(
                 "Source DuckDB not found: {} — skipping fingerprint",
-                path.display()
+                crime_map_database::paths::source_db_path(sid).display()
             );
             continue;
         }
 
-        let conn = crime_map_database::source_db::open_by_id(sid)?;
-        let name =
-            crime_map_database::source_db::get_meta(&conn, "source_name")?.unwrap_or_default();
-        let record_count = crime_map_database::source_db::get_record_count(&conn)?;
-        let last_synced_at = crime_map_database::source_db::get_meta(&conn, "last_synced_at")?;
+        let previous =
+            manifest.and_then(|m| m.source_fingerprints.iter().find(|fp| fp.source_id == *sid));
+        if let Some(previous) = previous.filter(|fp| fp.stat_matches(shards_stat(&paths))) {
+            fingerprints.push(previous.clone());
+            reused += 1;
+            continue;
+        }
+
+        let (name, record_count, last_synced_at, schema_hash) = {
+            let shards = open_source_shards(sid)?;
+            let Some(primary) = shards.first() else {
+                continue;
+            };
+            let mut record_count = 0;
+            let mut schema_hashes = Vec::with_capacity(shards.len());
+            for conn in &shards {
+                record_count += crime_map_database::source_db::get_record_count(conn)?;
+                schema_hashes.push(source_schema_hash(conn)?);
+            }
+            (
+                crime_map_database::source_db::get_meta(primary, "source_name")?
+                    .unwrap_or_default(),
+                record_count,
+                crime_map_database::source_db::get_meta(primary, "last_synced_at")?,
+                // Unchanged for an unsharded source.
+                schema_hashes.join(","),
+            )
+        };
+
+        // Stat after the connections are closed so any checkpoint performed
+        // on open/close is reflected in the recorded mtime and size.
+        let (file_mtime_ns, file_size) = shards_stat(&paths);
 
         #[allow(clippy::cast_possible_wrap)]
         fingerprints.push(SourceFingerprint {
@@ -400,40 +2086,234 @@ fn query_fingerprints(
             name,
             record_count: record_count as i64,
             last_synced_at,
+            schema_hash: Some(schema_hash),
+            file_mtime_ns,
+            file_size,
         });
     }
 
+    if reused > 0 {
+        log::info!(
+            "Reused {reused}/{} fingerprints from unchanged source files",
+            fingerprints.len()
+        );
+    }
+
     Ok(fingerprints)
 }
 
+/// Hashes how a source's incidents were normalized: the `incidents`
+/// column names and every distinct `(parent_category, category,
+/// severity)` tuple, in a stable order.
+///
+/// Fixing a category or severity mapping and re-ingesting in place changes
+/// the tuples (and so the hash) even when the record count doesn't.
+///
+/// # Errors
+///
+/// Returns an error if the catalog or incidents query fails.
+fn source_schema_hash(conn: &duckdb::Connection) -> Result<String, GenerateError> {
+    use sha2::Digest as _;
+
+    let mut hasher = sha2::Sha256::new();
+
+    let mut stmt = conn.prepare(
+        "SELECT column_name FROM duckdb_columns()
+         WHERE schema_name = 'main' AND table_name = 'incidents'
+         ORDER BY column_index",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let column: String = row.get(0)?;
+        hasher.update(column.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.update(b"\n");
+
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT parent_category, category, CAST(severity AS VARCHAR)
+         FROM incidents
+         ORDER BY ALL",
+    )?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        for i in 0..3 {
+            let value: Option<String> = row.get(i)?;
+            hasher.update(value.as_deref().unwrap_or("\0").as_bytes());
+            hasher.update(b"\t");
+        }
+        hasher.update(b"\n");
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Returns `(mtime_ns, size)` for a file, with `None` for anything the
+/// filesystem can't report.
+fn file_stat(path: &Path) -> (Option<u64>, Option<u64>) {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return (None, None);
+    };
+    let mtime_ns = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .and_then(|d| u64::try_from(d.as_nanos()).ok());
+    (mtime_ns, Some(meta.len()))
+}
+
+/// Combined [`file_stat`] of all of a source's shard files and their
+/// `.wal` sidecars: the latest modification time and the total size, so
+/// changing, adding, or removing any shard, or writing to one without
+/// checkpointing, changes it. Equals [`file_stat`] for an unsharded source
+/// without a WAL.
+fn shards_stat(paths: &[PathBuf]) -> (Option<u64>, Option<u64>) {
+    let stats: Vec<_> = paths
+        .iter()
+        .flat_map(|path| {
+            let wal = crime_map_database::source_db::wal_path(path);
+            std::iter::once(file_stat(path)).chain(wal.exists().then(|| file_stat(&wal)))
+        })
+        .collect();
+    let mtime_ns = stats
+        .iter()
+        .map(|&(mtime, _)| mtime)
+        .collect::<Option<Vec<_>>>()
+        .and_then(|mtimes| mtimes.into_iter().max());
+    let size = stats.iter().map(|&(_, size)| size).sum();
+    (mtime_ns, size)
+}
+
 /// Counts incidents with coordinates across all source `DuckDB` files.
 ///
 /// Uses the same `has_coordinates = TRUE` + coordinate range filter as
 /// the progress bar total matches the real feature count.
 ///
+/// Returns `(exportable, blocked)`: the exportable count excludes
+/// `blocklist`, and `blocked` is how many otherwise-exportable incidents
+/// it removed.
+///
 /// # Errors
 ///
 /// Returns an error if any source database cannot be opened or queried.
-fn count_exportable_records(source_ids: &[String]) -> Result<u64, Box<dyn std::error::Error>> {
+fn count_exportable_records(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    blocklist: &Blocklist,
+) -> Result<(u64, u64), GenerateError> {
+    let filter = exportable_filter(args);
     let mut total: u64 = 0;
+    let mut blocked: u64 = 0;
 
     for sid in source_ids {
-        let path = crime_map_database::paths::source_db_path(sid);
-        if !path.exists() {
-            continue;
+        let blocked_pred = blocklist.sql_table(sid).map_or_else(
+            || "FALSE".to_string(),
+            |table| format!("source_incident_id IN (SELECT source_incident_id FROM {table})"),
+        );
+        for conn in open_filtered_shards(sid, blocklist)? {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT
+                    COUNT(*) FILTER (WHERE NOT ({blocked_pred})),
+                    COUNT(*) FILTER (WHERE {blocked_pred})
+                 FROM incidents WHERE {filter}"
+            ))?;
+            let (count, blocked_count): (i64, i64) =
+                stmt.query_row([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            #[allow(clippy::cast_sign_loss)]
+            {
+                total += count as u64;
+                blocked += blocked_count as u64;
+            }
         }
+    }
 
-        let conn = crime_map_database::source_db::open_by_id(sid)?;
-        let mut stmt =
-            conn.prepare("SELECT COUNT(*) FROM incidents WHERE has_coordinates = TRUE AND longitude BETWEEN -180 AND 180 AND latitude BETWEEN -90 AND 90")?;
-        let count: i64 = stmt.query_row([], |row| row.get(0))?;
-        #[allow(clippy::cast_sign_loss)]
-        {
-            total += count as u64;
+    Ok((total, blocked))
+}
+
+/// Counts the exportable incidents the taxonomy fallback regroups, by
+/// subcategory: those whose subcategory, after `remap`'s rules, isn't a
+/// leaf of the taxonomy. Lists the subcategories to add to the taxonomy.
+///
+/// # Errors
+///
+/// Returns an error if any source database cannot be opened or queried.
+fn count_unmapped_subcategories(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+) -> Result<BTreeMap<String, u64>, GenerateError> {
+    let filter = exportable_filter(args);
+    let mut unmapped: BTreeMap<String, u64> = BTreeMap::new();
+
+    for sid in source_ids {
+        let filter = blocklist.apply(&filter, sid);
+        for conn in open_filtered_shards(sid, blocklist)? {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT category, parent_category, COUNT(*)
+                 FROM incidents WHERE {filter}
+                 GROUP BY ALL"
+            ))?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let mut category = row.get::<_, Option<String>>(0)?.unwrap_or_default();
+                let mut parent = row.get::<_, Option<String>>(1)?.unwrap_or_default();
+                let count: i64 = row.get(2)?;
+
+                remap.apply(sid, &mut category, &mut parent);
+                if remap.taxonomy().fallback_for(&category).is_some() {
+                    *unmapped.entry(category).or_default() += u64::try_from(count).unwrap_or(0);
+                }
+            }
         }
     }
 
-    Ok(total)
+    Ok(unmapped)
+}
+
+/// Counts the exportable incidents whose severity `remap`'s severity
+/// overrides change, grouping each source by category and severity so no
+/// incident is read individually.
+///
+/// # Errors
+///
+/// Returns an error if any source database cannot be opened or queried.
+fn count_severity_overrides(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+) -> Result<u64, GenerateError> {
+    let filter = exportable_filter(args);
+    let mut overridden: u64 = 0;
+
+    for sid in source_ids {
+        let filter = blocklist.apply(&filter, sid);
+        for conn in open_filtered_shards(sid, blocklist)? {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT category, parent_category, severity, COUNT(*)
+                 FROM incidents WHERE {filter}
+                 GROUP BY ALL"
+            ))?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let mut category = row.get::<_, Option<String>>(0)?.unwrap_or_default();
+                let mut parent = row.get::<_, Option<String>>(1)?.unwrap_or_default();
+                let severity = i32::from(row.get::<_, Option<i16>>(2)?.unwrap_or(1));
+                let count: i64 = row.get(3)?;
+
+                remap.apply(sid, &mut category, &mut parent);
+                if remap
+                    .severity_override(&category, &parent)
+                    .is_some_and(|s| s != remap.normalize_severity(sid, severity))
+                {
+                    overridden += u64::try_from(count).unwrap_or(0);
+                }
+            }
+        }
+    }
+
+    Ok(overridden)
 }
 
 /// Validates that all exportable records in the given sources have been
@@ -444,27 +2324,28 @@ fn count_exportable_records(source_ids: &[String]) -> Result<u64, Box<dyn std::e
 ///
 /// # Errors
 ///
-/// Returns an error if any source has un-enriched records or if
-/// database queries fail.
-fn validate_enrichment(source_ids: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+/// Returns [`GenerateError::UnenrichedData`] listing every source with
+/// un-enriched records, or a database error if queries fail.
+fn validate_enrichment(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    blocklist: &Blocklist,
+) -> Result<(), GenerateError> {
+    let filter = exportable_filter(args);
     let mut unenriched: Vec<(String, u64)> = Vec::new();
 
     for sid in source_ids {
-        let path = crime_map_database::paths::source_db_path(sid);
-        if !path.exists() {
-            continue;
+        let filter = blocklist.apply(&filter, sid);
+        let mut count: i64 = 0;
+        for conn in open_filtered_shards(sid, blocklist)? {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT COUNT(*) FROM incidents
+                 WHERE enriched = FALSE
+                   AND {filter}"
+            ))?;
+            count += stmt.query_row([], |row| row.get::<_, i64>(0))?;
         }
 
-        let conn = crime_map_database::source_db::open_by_id(sid)?;
-        let mut stmt = conn.prepare(
-            "SELECT COUNT(*) FROM incidents
-             WHERE has_coordinates = TRUE
-               AND enriched = FALSE
-               AND longitude BETWEEN -180 AND 180
-               AND latitude BETWEEN -90 AND 90",
-        )?;
-        let count: i64 = stmt.query_row([], |row| row.get(0))?;
-
         #[allow(clippy::cast_sign_loss)]
         if count > 0 {
             unenriched.push((sid.clone(), count as u64));
@@ -475,31 +2356,142 @@ fn validate_enrichment(source_ids: &[String]) -> Result<(), Box<dyn std::error::
         return Ok(());
     }
 
-    let mut msg = String::from(
-        "Found un-enriched records. Run `cargo ingest enrich` before generation.\n\
-         Un-enriched sources:\n",
-    );
-    for (sid, count) in &unenriched {
-        use std::fmt::Write;
-        writeln!(msg, "  - {sid}: {count} record(s)").unwrap();
+    Err(GenerateError::UnenrichedData {
+        sources: unenriched,
+    })
+}
+
+/// Census tract attribution of one source's exportable enriched
+/// incidents.
+///
+/// Enrichment marks every incident it visits as enriched, even when no
+/// tract polygon contains it, so a source enriched before its state's
+/// tracts were ingested is fully "enriched" yet has no tract GEOIDs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TractCoverage {
+    /// Source ID.
+    pub source_id: String,
+    /// Exportable incidents marked enriched.
+    pub enriched: u64,
+    /// How many of them have a `census_tract_geoid`.
+    pub attributed: u64,
+    /// FIPS code of the source's configured state, whose tract boundaries
+    /// are likely missing, if known.
+    pub state_fips: Option<String>,
+}
+
+impl TractCoverage {
+    /// Fraction of enriched incidents attributed to a tract (`1.0` when
+    /// the source has none).
+    #[must_use]
+    pub fn fraction(&self) -> f64 {
+        if self.enriched == 0 {
+            return 1.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let fraction = self.attributed as f64 / self.enriched as f64;
+        fraction
     }
-    msg.push_str("Hint: cargo ingest enrich --sources ");
-    msg.push_str(
-        &unenriched
-            .iter()
-            .map(|(s, _)| s.as_str())
-            .collect::<Vec<_>>()
-            .join(","),
-    );
+}
+
+/// Counts the enriched incidents in `conn` matching `filter`, and those of
+/// them attributed to a census tract.
+///
+/// # Errors
+///
+/// Returns a `DuckDB` error if the query fails.
+fn tract_coverage_counts(
+    conn: &duckdb::Connection,
+    filter: &str,
+) -> Result<(u64, u64), duckdb::Error> {
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*), COUNT(census_tract_geoid)
+             FROM incidents
+             WHERE enriched = TRUE
+               AND {filter}"
+        ),
+        [],
+        |row| {
+            let enriched: i64 = row.get(0)?;
+            let attributed: i64 = row.get(1)?;
+            Ok((
+                u64::try_from(enriched).unwrap_or(0),
+                u64::try_from(attributed).unwrap_or(0),
+            ))
+        },
+    )
+}
 
-    Err(msg.into())
+/// Checks that at least `min_coverage` of each source's exportable
+/// enriched incidents are attributed to a census tract (see
+/// [`TractCoverage`]).
+///
+/// With `args.warn_tract_coverage`, sources below the threshold are only
+/// logged.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::UnattributedData`] listing every source below
+/// the threshold, or a database error if queries fail.
+fn validate_tract_coverage(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    blocklist: &Blocklist,
+    min_coverage: f64,
+) -> Result<(), GenerateError> {
+    let filter = exportable_filter(args);
+    let registry = all_sources();
+    let mut below: Vec<TractCoverage> = Vec::new();
+
+    for sid in source_ids {
+        let shards = open_filtered_shards(sid, blocklist)?;
+        if shards.is_empty() {
+            continue;
+        }
+        let filter = blocklist.apply(&filter, sid);
+        let (mut enriched, mut attributed) = (0, 0);
+        for conn in &shards {
+            let (shard_enriched, shard_attributed) = tract_coverage_counts(conn, &filter)?;
+            enriched += shard_enriched;
+            attributed += shard_attributed;
+        }
+        let coverage = TractCoverage {
+            source_id: sid.clone(),
+            enriched,
+            attributed,
+            state_fips: registry
+                .iter()
+                .find(|s| s.id() == sid.as_str())
+                .and_then(|s| crime_map_geography_models::fips::abbr_to_fips(&s.state))
+                .map(str::to_string),
+        };
+        log::debug!("Source '{sid}': {attributed} of {enriched} enriched incidents have a tract");
+        if coverage.fraction() < min_coverage {
+            below.push(coverage);
+        }
+    }
+
+    if below.is_empty() {
+        return Ok(());
+    }
+
+    let err = GenerateError::UnattributedData {
+        sources: below,
+        min_coverage,
+    };
+    if args.warn_tract_coverage {
+        log::warn!("{err}");
+        return Ok(());
+    }
+    Err(err)
 }
 
 /// Loads the generation manifest from `dir/manifest.json`.
 ///
 /// Returns `None` if the file does not exist or cannot be parsed.
 fn load_manifest(dir: &Path) -> Option<Manifest> {
-    let path = dir.join("manifest.json");
+    let path = dir.join(paths::MANIFEST_FILE);
     let Ok(contents) = std::fs::read_to_string(&path) else {
         log::info!("No existing manifest found");
         return None;
@@ -524,8 +2516,8 @@ fn load_manifest(dir: &Path) -> Option<Manifest> {
 /// # Errors
 ///
 /// Returns an error if the file cannot be written.
-fn save_manifest(dir: &Path, manifest: &Manifest) -> Result<(), Box<dyn std::error::Error>> {
-    let path = dir.join("manifest.json");
+fn save_manifest(dir: &Path, manifest: &Manifest) -> Result<(), GenerateError> {
+    let path = dir.join(paths::MANIFEST_FILE);
     let tmp_path = dir.join("manifest.json.tmp");
     let contents = serde_json::to_string_pretty(manifest)?;
     std::fs::write(&tmp_path, contents)?;
@@ -534,30 +2526,169 @@ fn save_manifest(dir: &Path, manifest: &Manifest) -> Result<(), Box<dyn std::err
     Ok(())
 }
 
-/// Records a successful output generation in the manifest.
-fn record_output(manifest: &mut Manifest, output_name: &str) {
-    manifest
-        .outputs
-        .insert(output_name.to_string(), chrono::Utc::now().to_rfc3339());
+/// An output being generated: its child progress bar plus what's needed
+/// to time it.
+struct OutputRun {
+    name: &'static str,
+    records: u64,
+    started: Instant,
+    child: Arc<dyn ProgressCallback>,
 }
 
-/// Returns the file path for a given output name.
-#[must_use]
-fn output_file_path(dir: &Path, output_name: &str) -> PathBuf {
-    match output_name {
-        OUTPUT_INCIDENTS_PMTILES => dir.join("incidents.pmtiles"),
-        OUTPUT_INCIDENTS_DB => dir.join("incidents.db"),
-        OUTPUT_COUNT_DB => dir.join("counts.duckdb"),
-        OUTPUT_H3_DB => dir.join("h3.duckdb"),
-        OUTPUT_METADATA => dir.join("metadata.json"),
-        OUTPUT_BOUNDARIES_PMTILES => dir.join("boundaries.pmtiles"),
-        OUTPUT_BOUNDARIES_DB => dir.join("boundaries.db"),
-        OUTPUT_ANALYTICS_DB => dir.join("analytics.duckdb"),
-        _ => dir.join(output_name),
+/// Starts timing `name` and creates its child progress bar under the
+/// overall `progress`, sized to `records` units of work.
+fn start_output(
+    progress: &Arc<dyn ProgressCallback>,
+    name: &'static str,
+    label: &str,
+    records: u64,
+) -> OutputRun {
+    let child = progress.child(label);
+    child.set_total(records);
+    OutputRun {
+        name,
+        records,
+        started: Instant::now(),
+        child,
     }
 }
 
-/// Normalizes the `--sources` flag into a sorted list for manifest comparison.
+/// Clears an output's child progress bar, advances the overall `progress`
+/// by one output, and logs how long it took, warning if it was slow for
+/// its record count (see [`is_slow_output`]). Returns the elapsed time.
+fn finish_output(progress: &Arc<dyn ProgressCallback>, run: &OutputRun) -> Duration {
+    run.child.finish_and_clear();
+    progress.inc(1);
+
+    let elapsed = run.started.elapsed();
+    log::info!(
+        event = "output_generated",
+        output = run.name,
+        records = run.records,
+        elapsed_ms = elapsed.as_millis();
+        "Output {} completed in {:.1}s",
+        run.name,
+        elapsed.as_secs_f64()
+    );
+    if is_slow_output(elapsed, run.records) {
+        #[allow(clippy::cast_precision_loss)]
+        let micros_per_record = elapsed.as_secs_f64() * 1e6 / run.records as f64;
+        log::warn!(
+            "Output {} took {micros_per_record:.0}µs per record over {} records \
+             (threshold {SLOW_OUTPUT_MICROS_PER_RECORD}µs); possible performance regression",
+            run.name,
+            run.records
+        );
+    }
+    elapsed
+}
+
+/// Returns whether an output that took `elapsed` over `records` records is
+/// slow enough to flag: above [`SLOW_OUTPUT_MICROS_PER_RECORD`] and longer
+/// than [`SLOW_OUTPUT_MIN_SECS`] (so tiny runs dominated by fixed costs
+/// aren't flagged). Outputs without records are never flagged.
+fn is_slow_output(elapsed: Duration, records: u64) -> bool {
+    if records == 0 || elapsed.as_secs_f64() < SLOW_OUTPUT_MIN_SECS {
+        return false;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let micros_per_record = elapsed.as_secs_f64() * 1e6 / records as f64;
+    micros_per_record > SLOW_OUTPUT_MICROS_PER_RECORD
+}
+
+/// Records a successful output generation (timestamp, [`build_id`], and
+/// how long it took) in the manifest.
+fn record_output(manifest: &mut Manifest, output_name: &str, elapsed: Duration) {
+    manifest
+        .outputs
+        .insert(output_name.to_string(), chrono::Utc::now().to_rfc3339());
+    manifest.builds.insert(output_name.to_string(), build_id());
+    manifest
+        .durations_secs
+        .insert(output_name.to_string(), elapsed.as_secs_f64());
+}
+
+/// Replaces the manifest's [`TileStats`] of the just-generated `PMTiles`
+/// output `output_name` with those read from its archive, logging them,
+/// or drops them without [`GenerateArgs::tile_stats`]. Unreadable stats
+/// are logged and skipped rather than failing the run.
+fn record_tile_stats(manifest: &mut Manifest, args: &GenerateArgs, dir: &Path, output_name: &str) {
+    manifest.tile_stats.remove(output_name);
+    if !args.tile_stats {
+        return;
+    }
+
+    let path = output_file_path(dir, output_name);
+    match tiles::tile_stats(&path) {
+        Ok(stats) => {
+            stats.log(&path.file_name().unwrap_or_default().to_string_lossy());
+            manifest.tile_stats.insert(output_name.to_string(), stats);
+        }
+        Err(e) => log::warn!("Couldn't read tile stats of {}: {e}", path.display()),
+    }
+}
+
+/// Returns the files written for `output_name` that may be handed to an
+/// [`OutputSink`]. Tippecanoe outputs are kept locally and return none.
+fn sinkable_files(output_name: &str, args: &GenerateArgs) -> Vec<&'static str> {
+    match output_name {
+        OUTPUT_INCIDENTS_DB => vec![paths::INCIDENTS_DB_FILE],
+        OUTPUT_COUNT_DB => vec![paths::COUNTS_DB_FILE],
+        OUTPUT_H3_DB if args.h3_stats => vec![paths::H3_DB_FILE, paths::H3_STATS_FILE],
+        OUTPUT_H3_DB => vec![paths::H3_DB_FILE],
+        OUTPUT_METADATA => vec![paths::METADATA_FILE, paths::HEALTH_FILE],
+        OUTPUT_BOUNDARIES_DB => vec![paths::BOUNDARIES_DB_FILE],
+        OUTPUT_ANALYTICS_DB if args.analytics_ndjson => {
+            vec![paths::ANALYTICS_DB_FILE, paths::ANALYTICS_NDJSON_FILE]
+        }
+        OUTPUT_ANALYTICS_DB => vec![paths::ANALYTICS_DB_FILE],
+        OUTPUT_CHOROPLETH_DB => vec![paths::CHOROPLETH_DB_FILE],
+        _ => Vec::new(),
+    }
+}
+
+/// Hands the finalized files of `output_name` to `sink`, deleting each
+/// local copy once stored. Does nothing without a sink.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::Sink`] if the sink fails, or an I/O error if a
+/// stored file can't be removed.
+async fn sink_output(
+    sink: Option<&dyn OutputSink>,
+    args: &GenerateArgs,
+    dir: &Path,
+    output_name: &str,
+) -> Result<(), GenerateError> {
+    let Some(sink) = sink else {
+        return Ok(());
+    };
+
+    for file in sinkable_files(output_name, args) {
+        let path = dir.join(file);
+        if !path.exists() {
+            continue;
+        }
+        sink.put(file, &path)
+            .await
+            .map_err(|source| GenerateError::Sink {
+                file: file.to_string(),
+                source,
+            })?;
+        std::fs::remove_file(&path)?;
+        log::info!("Sent {file} to the output sink and removed the local copy");
+    }
+
+    Ok(())
+}
+
+/// Returns the file path for a given output name.
+#[must_use]
+fn output_file_path(dir: &Path, output_name: &str) -> PathBuf {
+    output_info(output_name).map_or_else(|| dir.join(output_name), |info| dir.join(info.file))
+}
+
+/// Normalizes the `--sources` flag into a sorted list for manifest comparison.
 fn sorted_sources_filter(args: &GenerateArgs) -> Option<Vec<String>> {
     args.sources.as_ref().map(|s| {
         let mut v: Vec<String> = s.split(',').map(|x| x.trim().to_string()).collect();
@@ -566,21 +2697,345 @@ fn sorted_sources_filter(args: &GenerateArgs) -> Option<Vec<String>> {
     })
 }
 
+/// Most changed sources `--only-changed-sources` patches into an existing
+/// sidebar DB; with more, a full rebuild is about as cheap.
+const INCREMENTAL_SIDEBAR_MAX_SOURCES: usize = 3;
+
+/// How a source's data differs from the manifest's, as returned by
+/// [`changed_sources`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceChange {
+    /// The source wasn't in the manifest.
+    Added,
+    /// The source is no longer selected or has no data.
+    Removed,
+    /// The source was re-synced with the same schema.
+    Updated,
+}
+
+/// Returns the sources whose data changed since `manifest`, if they are
+/// the sole reason `output` is stale, or `None` if it must be rebuilt.
+///
+/// Updating `output` in place is only possible when every other manifest
+/// check passes, no `--limit` or `--dedup-sources` makes one source's rows
+/// depend on the others, and no changed source's schema hash differs (see
+/// [`source_schema_hash`]).
+#[allow(clippy::too_many_arguments)]
+fn changed_sources(
+    args: &GenerateArgs,
+    manifest: Option<&Manifest>,
+    fingerprints: &[SourceFingerprint],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    dedup_priorities: Option<&BTreeMap<String, i32>>,
+    dir: &Path,
+    output: &str,
+) -> Option<Vec<(String, SourceChange)>> {
+    if args.limit.is_some() || dedup_priorities.is_some() {
+        return None;
+    }
+
+    // Re-run the regeneration checks as if the source data were unchanged;
+    // anything still stale (config, missing file, --force) needs a rebuild.
+    let previous = manifest?;
+    let mut unchanged_data = previous.clone();
+    unchanged_data.source_fingerprints = fingerprints.to_vec();
+    let still_stale = outputs_needing_regen(
+        args,
+        Some(&unchanged_data),
+        fingerprints,
+        remap,
+        blocklist,
+        dedup_priorities,
+        dir,
+        &[output],
+    );
+    if still_stale.get(output) != Some(&false) {
+        return None;
+    }
+
+    let by_id = |fps: &'_ [SourceFingerprint]| -> BTreeMap<String, SourceFingerprint> {
+        fps.iter()
+            .map(|fp| (fp.source_id.clone(), fp.clone()))
+            .collect()
+    };
+    let old = by_id(&previous.source_fingerprints);
+    let new = by_id(fingerprints);
+
+    let mut changed = Vec::new();
+    let ids: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for id in ids {
+        let change = match (old.get(id), new.get(id)) {
+            (Some(a), Some(b)) if a.same_data(b) => continue,
+            (Some(a), Some(b)) if a.schema_hash.is_none() || a.schema_hash != b.schema_hash => {
+                let file = output_info(output).map_or(output, |info| info.file);
+                log::info!("{id}: schema changed, rebuilding {file} in full");
+                return None;
+            }
+            (Some(_), Some(_)) => SourceChange::Updated,
+            (None, _) => SourceChange::Added,
+            (Some(_), None) => SourceChange::Removed,
+        };
+        changed.push((id.clone(), change));
+    }
+
+    Some(changed)
+}
+
+/// Returns the sources whose rows can be replaced in the existing sidebar
+/// DB under `args.only_changed_sources`, or `None` if it must be rebuilt:
+/// the [`changed_sources`] of the sidebar DB, if there are at most
+/// [`INCREMENTAL_SIDEBAR_MAX_SOURCES`] of them.
+fn sidebar_changed_sources(
+    args: &GenerateArgs,
+    manifest: Option<&Manifest>,
+    fingerprints: &[SourceFingerprint],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    dedup_priorities: Option<&BTreeMap<String, i32>>,
+    dir: &Path,
+) -> Option<Vec<String>> {
+    if !args.only_changed_sources {
+        return None;
+    }
+
+    let changed: Vec<String> = changed_sources(
+        args,
+        manifest,
+        fingerprints,
+        remap,
+        blocklist,
+        dedup_priorities,
+        dir,
+        OUTPUT_INCIDENTS_DB,
+    )?
+    .into_iter()
+    .map(|(id, _)| id)
+    .collect();
+
+    if changed.len() > INCREMENTAL_SIDEBAR_MAX_SOURCES {
+        log::info!(
+            "{} sources changed (more than {INCREMENTAL_SIDEBAR_MAX_SOURCES}), \
+             rebuilding the sidebar DB in full",
+            changed.len()
+        );
+        return None;
+    }
+
+    Some(changed)
+}
+
+/// Returns the rows to replace in the existing analytics DB under
+/// `args.incremental_analytics`, or `None` if it must be rebuilt.
+///
+/// Each of the analytics DB's [`changed_sources`] maps to the start
+/// (`YYYY-MM-01 00:00:00`) of the month holding its previous high-water
+/// mark, from which its rows are replaced, or to `None` to replace all of
+/// them (added, removed, or previously empty sources). Sources are
+/// insert-only and mostly append recent incidents, so this usually
+/// re-inserts a few weeks of rows instead of every year of them;
+/// [`refresh_analytics_db`] still falls back to a full rebuild if older
+/// rows changed.
+///
+/// Besides the conditions of [`changed_sources`], the incremental path
+/// needs the same [`ANALYTICS_SCHEMA_VERSION`] and an unchanged
+/// boundaries `DuckDB` (the reference tables).
+fn analytics_refresh_windows(
+    args: &GenerateArgs,
+    manifest: Option<&Manifest>,
+    fingerprints: &[SourceFingerprint],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    dedup_priorities: Option<&BTreeMap<String, i32>>,
+    dir: &Path,
+) -> Option<BTreeMap<String, Option<String>>> {
+    if !args.incremental_analytics {
+        return None;
+    }
+
+    let changed = changed_sources(
+        args,
+        manifest,
+        fingerprints,
+        remap,
+        blocklist,
+        dedup_priorities,
+        dir,
+        OUTPUT_ANALYTICS_DB,
+    )?;
+
+    let previous = manifest?;
+    if previous.analytics_schema_version != ANALYTICS_SCHEMA_VERSION {
+        log::info!("Analytics DB layout changed, rebuilding it in full");
+        return None;
+    }
+    let boundaries_stat = file_stat(&crime_map_database::paths::boundaries_db_path());
+    if boundaries_stat.0.is_none() || previous.analytics_boundaries_stat != boundaries_stat {
+        log::info!("Boundaries changed, rebuilding the analytics DB in full");
+        return None;
+    }
+
+    Some(
+        changed
+            .into_iter()
+            .map(|(id, change)| {
+                let since = match change {
+                    SourceChange::Updated => previous
+                        .analytics_high_water
+                        .get(&id)
+                        .and_then(|ts| month_start(ts)),
+                    SourceChange::Added | SourceChange::Removed => None,
+                };
+                (id, since)
+            })
+            .collect(),
+    )
+}
+
+/// Returns the start of the month holding `timestamp`, as
+/// `YYYY-MM-01 00:00:00`, or `None` if it doesn't start with a date.
+fn month_start(timestamp: &str) -> Option<String> {
+    let date = chrono::NaiveDate::parse_from_str(timestamp.get(..10)?, "%Y-%m-%d").ok()?;
+    Some(format!("{} 00:00:00", date.format("%Y-%m-01")))
+}
+
+/// What [`output_needs_regen`] compares against the manifest: the
+/// current arguments and fingerprints, plus the values derived from them
+/// that the manifest records.
+struct RegenInputs<'a> {
+    args: &'a GenerateArgs,
+    fingerprints: &'a [SourceFingerprint],
+    /// See [`sorted_sources_filter`].
+    sources_filter: Option<Vec<String>>,
+    category_remap_hash: Option<&'a str>,
+    blocklist_hash: Option<&'a str>,
+    dedup_priorities: Option<&'a BTreeMap<String, i32>>,
+    /// See [`boundary_types_filter`].
+    boundary_types: Option<Vec<String>>,
+    /// See [`source_timezones`].
+    source_timezones: BTreeMap<String, String>,
+    /// See [`JitterKey::fingerprint`].
+    jitter_key_fingerprint: Option<String>,
+}
+
+/// Determines which of `outputs` in `dir` need regeneration under `args`
+/// (see [`output_needs_regen`]).
+fn outputs_needing_regen<'a>(
+    args: &GenerateArgs,
+    manifest: Option<&Manifest>,
+    fingerprints: &[SourceFingerprint],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    dedup_priorities: Option<&BTreeMap<String, i32>>,
+    dir: &Path,
+    outputs: &[&'a str],
+) -> BTreeMap<&'a str, bool> {
+    let current = RegenInputs {
+        args,
+        fingerprints,
+        sources_filter: sorted_sources_filter(args),
+        category_remap_hash: remap.hash(),
+        blocklist_hash: blocklist.hash(),
+        dedup_priorities,
+        boundary_types: boundary_types_filter(args),
+        source_timezones: source_timezones(fingerprints),
+        jitter_key_fingerprint: jitter_key_fingerprint(args),
+    };
+
+    outputs
+        .iter()
+        .map(|&name| {
+            let path = output_file_path(dir, name);
+            let needed = output_needs_regen(
+                manifest,
+                &current,
+                name,
+                &path,
+                args.force || (name == OUTPUT_H3_DB && args.h3_from.is_some()),
+            );
+            (name, needed)
+        })
+        .collect()
+}
+
+/// Records the current source fingerprints and generation config in
+/// `manifest`.
+fn stamp_manifest(
+    manifest: &mut Manifest,
+    args: &GenerateArgs,
+    fingerprints: &[SourceFingerprint],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    dedup_priorities: Option<&BTreeMap<String, i32>>,
+) {
+    manifest.source_fingerprints = fingerprints.to_vec();
+    manifest.source_timezones = source_timezones(fingerprints);
+    manifest.sources_filter = sorted_sources_filter(args);
+    manifest.limit = args.limit;
+    manifest.bbox = args.bbox;
+    manifest.count_monthly = args.count_monthly;
+    manifest.count_rolling = args.count_rolling;
+    manifest.count_timeseries = args.count_timeseries;
+    manifest.sample_fraction = args.sample_fraction;
+    manifest.category_remap_hash = remap.hash().map(str::to_string);
+    manifest.blocklist_hash = blocklist.hash().map(str::to_string);
+    manifest.dedup_priorities = dedup_priorities.cloned();
+    manifest.time_dimensions = args.time_dimensions;
+    manifest.analytics_ndjson = args.analytics_ndjson;
+    manifest.h3_stats = args.h3_stats;
+    manifest.keep_incidents_table = args.keep_incidents_table;
+    manifest
+        .h3_min_cell_count
+        .clone_from(&args.h3_min_cell_count);
+    manifest.pmtiles_cluster = args.pmtiles_cluster;
+    manifest.tile_backend = args.tile_backend;
+    manifest.sensitive_jitter.clone_from(&args.sensitive_jitter);
+    manifest.jitter_key_fingerprint = jitter_key_fingerprint(args);
+    manifest.stack_spread = args.stack_spread;
+    manifest.raw_json = args.raw_json;
+    manifest.boundary_types = boundary_types_filter(args);
+    manifest
+        .simplify_boundaries
+        .clone_from(&args.simplify_boundaries);
+    manifest.version = MANIFEST_VERSION;
+}
+
+/// Returns the [`JitterKey::fingerprint`] of `args.jitter_key` if
+/// `args.sensitive_jitter` uses it.
+fn jitter_key_fingerprint(args: &GenerateArgs) -> Option<String> {
+    args.sensitive_jitter
+        .as_ref()
+        .and(args.jitter_key.as_ref())
+        .map(JitterKey::fingerprint)
+}
+
 /// Determines whether a specific output needs regeneration.
 ///
 /// Returns `true` if any of: `force` is set, no manifest exists, manifest
 /// version mismatch, source fingerprints changed, CLI config changed
-/// (`--sources` or `--limit`), output not recorded in manifest, or output
-/// file missing from disk.
+/// (`--sources`, `--limit`, `--bbox`, `--sample`, the `--category-remap`
+/// or `--blocklist` contents, or `--dedup-sources` and the source
+/// priorities; `--count-monthly`/`--count-rolling` for the count DB,
+/// `--time-dimensions` and the sources' timezones for the count and H3
+/// DBs, `--keep-incidents-table` for the count DB, `--analytics-ndjson`
+/// for the analytics DB,
+/// `--h3-stats` and `--h3-min-cell-count` for the H3 DB, `--raw-json` for
+/// the sidebar DB, `--boundary-types` for the boundary outputs,
+/// `--simplify-boundaries` for the boundaries `PMTiles`, the
+/// sensitive-incident jitter and its key for the incidents `PMTiles` and
+/// sidebar DB, and the clustering parameters, tile backend, and stack
+/// spread for the incidents `PMTiles` only), output not recorded in
+/// manifest, a [derived](OutputInfo::derived_from) output generated before
+/// the output it's derived from, or output file missing from disk.
 fn output_needs_regen(
     manifest: Option<&Manifest>,
-    current_fingerprints: &[SourceFingerprint],
+    current: &RegenInputs,
     output_name: &str,
     output_path: &Path,
-    sources_filter: Option<&[String]>,
-    limit: Option<u64>,
     force: bool,
 ) -> bool {
+    let args = current.args;
+
     if force {
         return true;
     }
@@ -593,15 +3048,97 @@ fn output_needs_regen(
         return true;
     }
 
-    if m.source_fingerprints != current_fingerprints {
+    if m.source_fingerprints.len() != current.fingerprints.len()
+        || m.source_fingerprints
+            .iter()
+            .zip(current.fingerprints)
+            .any(|(a, b)| !a.same_data(b))
+    {
+        return true;
+    }
+
+    if m.sources_filter != current.sources_filter {
+        return true;
+    }
+
+    if m.limit != args.limit {
+        return true;
+    }
+
+    if m.bbox != args.bbox {
+        return true;
+    }
+
+    if m.sample_fraction != args.sample_fraction {
+        return true;
+    }
+
+    if m.category_remap_hash.as_deref() != current.category_remap_hash {
+        return true;
+    }
+
+    if m.blocklist_hash.as_deref() != current.blocklist_hash {
+        return true;
+    }
+
+    if m.dedup_priorities.as_ref() != current.dedup_priorities {
+        return true;
+    }
+
+    if output_name == OUTPUT_COUNT_DB
+        && (m.count_monthly != args.count_monthly
+            || m.count_rolling != args.count_rolling
+            || m.count_timeseries != args.count_timeseries
+            || m.keep_incidents_table != args.keep_incidents_table)
+    {
+        return true;
+    }
+
+    if (output_name == OUTPUT_COUNT_DB || output_name == OUTPUT_H3_DB)
+        && (m.time_dimensions != args.time_dimensions
+            || m.source_timezones != current.source_timezones)
+    {
+        return true;
+    }
+
+    if output_name == OUTPUT_ANALYTICS_DB && m.analytics_ndjson != args.analytics_ndjson {
+        return true;
+    }
+
+    if output_name == OUTPUT_H3_DB
+        && (m.h3_stats != args.h3_stats || m.h3_min_cell_count != args.h3_min_cell_count)
+    {
+        return true;
+    }
+
+    if output_name == OUTPUT_INCIDENTS_DB && m.raw_json != args.raw_json {
+        return true;
+    }
+
+    if (output_name == OUTPUT_BOUNDARIES_PMTILES || output_name == OUTPUT_BOUNDARIES_DB)
+        && m.boundary_types != current.boundary_types
+    {
+        return true;
+    }
+
+    // The search DB keeps the raw geometry, so only the tiles care.
+    if output_name == OUTPUT_BOUNDARIES_PMTILES && m.simplify_boundaries != args.simplify_boundaries
+    {
         return true;
     }
 
-    if m.sources_filter.as_deref() != sources_filter {
+    if (output_name == OUTPUT_INCIDENTS_PMTILES || output_name == OUTPUT_INCIDENTS_DB)
+        && (m.sensitive_jitter != args.sensitive_jitter
+            || m.jitter_key_fingerprint != current.jitter_key_fingerprint)
+    {
         return true;
     }
 
-    if m.limit != limit {
+    if output_name == OUTPUT_INCIDENTS_PMTILES
+        && (m.pmtiles_cluster != args.pmtiles_cluster
+            || m.tile_backend != args.tile_backend
+            || m.stack_spread != args.stack_spread)
+    {
         return true;
     }
 
@@ -609,6 +3146,17 @@ fn output_needs_regen(
         return true;
     }
 
+    if let Some(parent) = output_info(output_name).and_then(|info| info.derived_from) {
+        let generated = |name: &str| {
+            m.outputs
+                .get(name)
+                .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+        };
+        if generated(parent) > generated(output_name) {
+            return true;
+        }
+    }
+
     if !output_path.exists() {
         return true;
     }
@@ -626,25 +3174,19 @@ fn output_needs_regen(
 ///
 /// If both are provided, their results are unioned (deduplicated).
 ///
-/// Returns an empty `Vec` if neither flag was provided (meaning: export
-/// all sources that have `DuckDB` files on disk).
+/// If neither flag was provided, resolves every enabled source (see
+/// [`crime_map_source::reconcile`]) that has a `DuckDB` file on disk.
+/// Sources without a file, or left out by `CRIME_MAP_SOURCES`, are skipped
+/// with a warning, as are files of sources missing from the registry.
 ///
 /// # Errors
 ///
 /// Returns an error if a provided source ID does not match any configured
-/// source.
-pub fn resolve_source_ids(args: &GenerateArgs) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    if args.sources.is_none() && args.states.is_none() {
-        // No filter: discover all source DuckDB files on disk
-        let ids = crime_map_database::source_db::discover_source_ids();
-        if ids.is_empty() {
-            return Err("No source DuckDB files found in data/sources/".into());
-        }
-        log::info!("Discovered {} source DuckDB files", ids.len());
-        return Ok(ids);
-    }
+/// source, or if no source resolves to a `DuckDB` file.
+pub fn resolve_source_ids(args: &GenerateArgs) -> Result<Vec<String>, GenerateError> {
+    use crime_map_source::reconcile::{SourceStatus, reconcile_sources};
 
-    let registry = all_sources();
+    let filtered = args.sources.is_some() || args.states.is_some();
     let mut short_ids: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
 
     // Collect source IDs from --sources
@@ -664,51 +3206,189 @@ pub fn resolve_source_ids(args: &GenerateArgs) -> Result<Vec<String>, Box<dyn st
             .map(|f| crime_map_geography_models::fips::state_abbr(f).to_string())
             .collect();
 
-        for source in &registry {
+        for source in &all_sources() {
             if abbrs.iter().any(|a| a.eq_ignore_ascii_case(&source.state)) {
                 short_ids.insert(source.id().to_string());
             }
         }
     }
 
-    if short_ids.is_empty() {
-        return Err("No sources matched the provided --sources / --states filters".into());
+    if filtered && short_ids.is_empty() {
+        return Err(GenerateError::InvalidArgs(
+            "No sources matched the provided --sources / --states filters".to_string(),
+        ));
     }
 
-    // Validate each short ID exists in the registry
-    let mut result = Vec::with_capacity(short_ids.len());
-    for short_id in &short_ids {
-        if registry.iter().any(|s| s.id() == short_id.as_str()) {
-            let path = crime_map_database::paths::source_db_path(short_id);
-            if path.exists() {
-                result.push(short_id.clone());
-                log::info!("Resolved source '{short_id}' -> {}", path.display());
-            } else {
-                log::warn!(
-                    "Source '{short_id}' is in the registry but has no DuckDB file — skipping",
-                );
-            }
+    let requested: Vec<String> = short_ids.into_iter().collect();
+    let synced = crime_map_database::source_db::discover_source_ids()
+        .into_iter()
+        .collect();
+    let reconciliation = reconcile_sources(&requested, &synced);
+
+    let unknown = reconciliation
+        .with_status(SourceStatus::Unknown)
+        .filter(|s| s.requested)
+        .filter_map(|s| s.problem())
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        return Err(GenerateError::InvalidArgs(unknown.join("; ")));
+    }
+    // Files of sources missing from the registry are always worth a
+    // warning; unsynced sources only if they were asked for.
+    for problem in reconciliation.problems(&[SourceStatus::Unknown]) {
+        log::warn!("Skipping {problem}");
+    }
+    for problem in reconciliation.problems(&[SourceStatus::NotSynced]) {
+        if filtered {
+            log::warn!("Skipping {problem}");
         } else {
-            return Err(format!("Unknown source ID: {short_id}").into());
+            log::debug!("Skipping {problem}");
+        }
+    }
+    for problem in reconciliation.problems(&[SourceStatus::Disabled]) {
+        log::debug!("Skipping {problem}");
+    }
+
+    let result = reconciliation.ready_ids();
+    for short_id in &result {
+        let paths = crime_map_database::source_db::shard_paths(short_id);
+        if let Some(path) = paths.first() {
+            log::info!("Resolved source '{short_id}' -> {}", path.display());
+            if paths.len() > 1 {
+                log::info!("  ...and {} more shard(s)", paths.len() - 1);
+            }
         }
     }
 
     if result.is_empty() {
-        return Err("None of the requested sources have DuckDB files on disk".into());
+        return Err(GenerateError::InvalidArgs(if filtered {
+            "None of the requested sources have DuckDB files on disk".to_string()
+        } else {
+            "No source DuckDB files found in data/sources/".to_string()
+        }));
     }
 
     Ok(result)
 }
 
-/// Deletes the intermediate `.geojsonseq` file unless `--keep-intermediate`
-/// was specified.
-fn cleanup_intermediate(args: &GenerateArgs, dir: &Path) {
-    let path = dir.join("incidents.geojsonseq");
-    if args.keep_intermediate {
-        log::info!("Keeping intermediate file: {}", path.display());
-        return;
+/// Preflight for outputs that read `boundaries.duckdb`: succeeds if it
+/// has census tracts. Otherwise, with `args.auto_boundaries`, ingests the
+/// boundaries of [`boundary_states`] (states already present are
+/// skipped, so this is idempotent); without it, fails with the commands
+/// to run.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::BoundariesMissing`] if there are no tracts
+/// and `args.auto_boundaries` isn't set, or
+/// [`GenerateError::BoundaryIngest`] if checking or ingesting fails.
+#[allow(clippy::future_not_send)]
+async fn ensure_boundaries(
+    args: &GenerateArgs,
+    source_ids: &[String],
+) -> Result<(), GenerateError> {
+    let path = crime_map_database::paths::boundaries_db_path();
+    // Checking creates an empty database, so only check an existing one.
+    let tracts = if path.exists() {
+        crime_map_ingest::boundary_tract_count()
+            .map_err(|e| GenerateError::BoundaryIngest(e.to_string()))?
+    } else {
+        0
+    };
+    if tracts > 0 {
+        return Ok(());
+    }
+
+    let state_fips = boundary_states(args, source_ids);
+    if !args.auto_boundaries {
+        return Err(GenerateError::BoundariesMissing {
+            path,
+            command: ingest_boundaries_command(&state_fips),
+        });
+    }
+
+    if state_fips.is_empty() {
+        log::info!(
+            "No census tracts in {}; ingesting boundaries for all states...",
+            path.display()
+        );
+    } else {
+        log::info!(
+            "No census tracts in {}; ingesting boundaries for states {}...",
+            path.display(),
+            state_fips.join(",")
+        );
+    }
+    let result = crime_map_ingest::run_ingest_boundaries(&crime_map_ingest::IngestBoundariesArgs {
+        state_fips,
+        force: false,
+    })
+    .await
+    .map_err(|e| GenerateError::BoundaryIngest(e.to_string()))?;
+    log::info!(
+        "Ingested boundaries: tracts={}, places={}, counties={}, states={}, neighborhoods={}",
+        result.tracts,
+        result.places,
+        result.counties,
+        result.states,
+        result.neighborhoods
+    );
+    Ok(())
+}
+
+/// Returns the state FIPS codes whose boundaries `source_ids` need: the
+/// `--states` filter if given, else the states of the selected sources.
+/// Empty means all states (a source's state is unknown).
+#[must_use]
+pub fn boundary_states(args: &GenerateArgs, source_ids: &[String]) -> Vec<String> {
+    if let Some(states) = &args.states {
+        return states
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+    }
+
+    let registry = all_sources();
+    let mut fips = std::collections::BTreeSet::new();
+    for sid in source_ids {
+        let state = registry
+            .iter()
+            .find(|s| s.id() == sid.as_str())
+            .and_then(|s| crime_map_geography_models::fips::abbr_to_fips(&s.state));
+        let Some(state) = state else {
+            return Vec::new();
+        };
+        fips.insert(state.to_string());
     }
-    if path.exists() {
+    fips.into_iter().collect()
+}
+
+/// Returns the `cargo ingest` commands that ingest every boundary type
+/// for `state_fips` (all states if empty).
+fn ingest_boundaries_command(state_fips: &[String]) -> String {
+    let states = if state_fips.is_empty() {
+        String::new()
+    } else {
+        format!(" --states {}", state_fips.join(","))
+    };
+    format!(
+        "cargo ingest states && cargo ingest counties{states} && cargo ingest tracts{states} \
+         && cargo ingest places{states} && cargo ingest neighborhoods"
+    )
+}
+
+/// Deletes the intermediate incidents `.geojsonseq` file(s) unless
+/// `--keep-intermediate` was specified.
+fn cleanup_intermediate(args: &GenerateArgs, dir: &Path) {
+    for path in incidents_intermediates(dir) {
+        if args.keep_intermediate {
+            log::info!("Keeping intermediate file: {}", path.display());
+            continue;
+        }
         match std::fs::remove_file(&path) {
             Ok(()) => log::info!("Cleaned up intermediate file: {}", path.display()),
             Err(e) => log::warn!("Failed to remove intermediate file {}: {e}", path.display()),
@@ -716,18 +3396,203 @@ fn cleanup_intermediate(args: &GenerateArgs, dir: &Path) {
     }
 }
 
-// ============================================================
-// Per-source DuckDB row iteration helpers
-// ============================================================
+/// Returns whether `name` is an incidents `GeoJSONSeq` intermediate:
+/// `incidents.geojsonseq` or a shard `incidents-NNN.geojsonseq`.
+fn is_incidents_intermediate(name: &str) -> bool {
+    name == "incidents.geojsonseq"
+        || name
+            .strip_prefix("incidents-")
+            .and_then(|rest| rest.strip_suffix(".geojsonseq"))
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
 
-/// A decoded incident row from a source `DuckDB` file.
-#[allow(dead_code)]
-struct IncidentRow {
-    source_incident_id: String,
-    source_id: String,
-    source_name: String,
-    category: String,
-    parent_category: String,
+/// Lists the incidents `GeoJSONSeq` intermediates present in `dir`,
+/// sharded or not.
+fn incidents_intermediates(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .filter(|e| is_incidents_intermediate(&e.file_name().to_string_lossy()))
+        .map(|e| e.path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// File suffixes that only exist while a write is in progress. Any such
+/// file found before a run starts was left behind by an interrupted one.
+const ORPHAN_SUFFIXES: &[&str] = &[".wal", ".tmp", ".tmp-wal", ".tmp-shm", ".part"];
+
+/// Intermediate `.geojsonseq` files and the output each one feeds.
+/// Incidents shards are matched by [`is_incidents_intermediate`].
+const INTERMEDIATE_OUTPUTS: &[(&str, &str)] = &[
+    ("incidents.geojsonseq", paths::INCIDENTS_PMTILES_FILE),
+    ("states.geojsonseq", paths::BOUNDARIES_PMTILES_FILE),
+    ("counties.geojsonseq", paths::BOUNDARIES_PMTILES_FILE),
+    ("places.geojsonseq", paths::BOUNDARIES_PMTILES_FILE),
+    ("tracts.geojsonseq", paths::BOUNDARIES_PMTILES_FILE),
+    ("neighborhoods.geojsonseq", paths::BOUNDARIES_PMTILES_FILE),
+];
+
+/// Removes files left behind in `dir` by interrupted generation runs.
+///
+/// Deletes:
+/// - `DuckDB` `.wal` files, `.tmp` files from atomic writes (with their
+///   `SQLite` `-wal`/`-shm` sidecars), and `.part` downloads — none of
+///   these survive a completed run.
+/// - Intermediate `.geojsonseq` files whose output is missing or older
+///   than the intermediate (i.e. tippecanoe never finished). Intermediates
+///   kept via `--keep-intermediate` are older than their output and are
+///   left alone.
+///
+/// Must not be called while another generation run is using `dir`.
+/// Returns the paths that were removed.
+///
+/// # Errors
+///
+/// Returns an error if `dir` cannot be read or an orphan cannot be removed.
+pub fn repair_output_dir(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
+    let mut removed = Vec::new();
+    if !dir.exists() {
+        return Ok(removed);
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        let intermediate_output = if is_incidents_intermediate(&name) {
+            Some(paths::INCIDENTS_PMTILES_FILE)
+        } else {
+            INTERMEDIATE_OUTPUTS
+                .iter()
+                .find(|(i, _)| *i == name)
+                .map(|&(_, output)| output)
+        };
+
+        let orphaned = if ORPHAN_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+            true
+        } else if let Some(output) = intermediate_output {
+            let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+            match (modified(&path), modified(&dir.join(output))) {
+                (Some(intermediate), Some(built)) => built < intermediate,
+                _ => true,
+            }
+        } else {
+            false
+        };
+
+        if orphaned {
+            std::fs::remove_file(&path)?;
+            log::info!("Removed orphaned file: {}", path.display());
+            removed.push(path);
+        }
+    }
+
+    if removed.is_empty() {
+        log::info!("No orphaned files found in {}", dir.display());
+    } else {
+        log::info!("Removed {} orphaned file(s)", removed.len());
+    }
+
+    Ok(removed)
+}
+
+/// Drops manifest entries for outputs whose `.wal` was removed by
+/// [`repair_output_dir`]: without its WAL the database may be missing
+/// committed data, so it must be regenerated.
+fn invalidate_repaired_outputs(manifest: &mut Manifest, dir: &Path, removed: &[PathBuf]) {
+    manifest.outputs.retain(|name, _| {
+        let wal = crime_map_database::source_db::wal_path(&output_file_path(dir, name));
+        let keep = !removed.contains(&wal);
+        if !keep {
+            log::info!("{name}: WAL was removed, forcing regeneration");
+        }
+        keep
+    });
+}
+
+// ============================================================
+// Per-source DuckDB row iteration helpers
+// ============================================================
+
+/// Loads `args.blocklist` and adds the `export_exclude` filters declared
+/// by each of `source_ids` (see [`Blocklist::restrict`]).
+///
+/// # Errors
+///
+/// Returns an error if the blocklist file can't be loaded.
+fn load_blocklist(args: &GenerateArgs, source_ids: &[String]) -> Result<Blocklist, GenerateError> {
+    let mut blocklist = Blocklist::load(args.blocklist.as_deref())?;
+    for def in all_sources() {
+        if source_ids.iter().any(|sid| sid == def.id())
+            && let Some(predicate) = def.export_predicate()
+        {
+            log::debug!("{}: applying export_exclude filter: {predicate}", def.id());
+            blocklist.restrict(def.id(), predicate);
+        }
+    }
+    Ok(blocklist)
+}
+
+/// Returns the SQL `WHERE` predicate selecting exportable incidents:
+/// valid coordinates, intersected with `args.bbox` if set, and thinned to
+/// `args.sample_fraction` if set.
+///
+/// Sampling keeps rows whose `hash(rowid)` falls in the lowest fraction of
+/// [`SAMPLE_BUCKETS`] buckets, so the same rows are chosen for every output
+/// and the sample is spread evenly over each source's time range rather
+/// than front-loaded like `--limit`.
+///
+/// Every incident-selecting query (exports, counts, metadata) uses this,
+/// narrowed per source by [`Blocklist::apply`], so progress totals and
+/// outputs always agree.
+fn exportable_filter(args: &GenerateArgs) -> String {
+    let mut filter = String::from(
+        "has_coordinates = TRUE
+               AND longitude BETWEEN -180 AND 180
+               AND latitude BETWEEN -90 AND 90",
+    );
+    if let Some([min_lng, min_lat, max_lng, max_lat]) = args.bbox {
+        write!(
+            filter,
+            "
+               AND longitude BETWEEN {min_lng} AND {max_lng}
+               AND latitude BETWEEN {min_lat} AND {max_lat}"
+        )
+        .unwrap();
+    }
+    if let Some(fraction) = args.sample_fraction {
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let kept = (fraction * SAMPLE_BUCKETS as f64).round() as u64;
+        write!(
+            filter,
+            "
+               AND hash(rowid) % {SAMPLE_BUCKETS} < {kept}"
+        )
+        .unwrap();
+    }
+    filter
+}
+
+/// A decoded incident row from a source `DuckDB` file.
+#[allow(dead_code)]
+struct IncidentRow {
+    source_incident_id: String,
+    source_id: String,
+    source_name: String,
+    category: String,
+    parent_category: String,
     severity: i32,
     longitude: f64,
     latitude: f64,
@@ -745,11 +3610,236 @@ struct IncidentRow {
     state_fips: Option<String>,
     county_geoid: Option<String>,
     neighborhood_id: Option<String>,
+    /// Which coordinate the incident uses (`source` or `geocoded`), when
+    /// known. Only read where it's exported.
+    coord_source: Option<String>,
+}
+
+impl IncidentRow {
+    /// Converts `occurred_at` to local wall-clock time in `timezone` (see
+    /// [`localize_occurred_at`]). No-op when `timezone` is `None`.
+    fn localize(&mut self, timezone: Option<chrono_tz::Tz>) {
+        if let Some(tz) = timezone
+            && let Some(local) = self
+                .occurred_at
+                .as_deref()
+                .and_then(|ts| localize_occurred_at(ts, tz))
+        {
+            self.occurred_at = Some(local);
+        }
+    }
+
+    /// Rough in-memory size of this row once bound as insert parameters:
+    /// the string payloads plus a fixed allowance for the numeric fields
+    /// and per-value overhead.
+    fn estimated_size(&self) -> usize {
+        const FIXED_OVERHEAD: usize = 21 * std::mem::size_of::<u64>();
+
+        let optional = [
+            &self.occurred_at,
+            &self.description,
+            &self.block_address,
+            &self.location_type,
+            &self.census_tract_geoid,
+            &self.census_place_geoid,
+            &self.state_fips,
+            &self.county_geoid,
+            &self.neighborhood_id,
+            &self.coord_source,
+        ];
+        FIXED_OVERHEAD
+            + self.source_incident_id.len()
+            + self.source_id.len()
+            + self.source_name.len()
+            + self.category.len()
+            + self.parent_category.len()
+            + self.city.len()
+            + self.state.len()
+            + optional
+                .iter()
+                .filter_map(|s| s.as_ref())
+                .map(String::len)
+                .sum::<usize>()
+    }
+
+    /// Rewrites this row's categories with the first matching rule in
+    /// `remap`, then sets its canonical severity (see [`remap`] for the
+    /// precedence of severity overrides).
+    fn apply_remap(&mut self, remap: &CategoryRemap) {
+        remap.apply(
+            &self.source_id,
+            &mut self.category,
+            &mut self.parent_category,
+        );
+        self.severity = remap.severity(
+            &self.source_id,
+            &self.category,
+            &self.parent_category,
+            self.severity,
+        );
+    }
+}
+
+/// Returns the sidebar batch size to use after a full batch of `current`
+/// rows whose estimated size was `batch_bytes`.
+///
+/// Halves when the batch exceeded [`SIDEBAR_TARGET_BATCH_BYTES`] and
+/// doubles when doubling would still fit, clamped to
+/// [`SIDEBAR_MIN_BATCH_SIZE`]..=[`SIDEBAR_MAX_BATCH_SIZE`]. The gap between
+/// the two thresholds keeps the size from oscillating.
+fn next_sidebar_batch_size(current: i64, batch_bytes: usize) -> i64 {
+    let next = if batch_bytes > SIDEBAR_TARGET_BATCH_BYTES {
+        current / 2
+    } else if batch_bytes.saturating_mul(2) <= SIDEBAR_TARGET_BATCH_BYTES {
+        current.saturating_mul(2)
+    } else {
+        current
+    };
+    next.clamp(SIDEBAR_MIN_BATCH_SIZE, SIDEBAR_MAX_BATCH_SIZE)
+}
+
+/// Converts a stored `occurred_at` instant to `YYYY-MM-DD HH:MM:SS` local
+/// wall-clock time in `tz`, so day (and hour) buckets match when the
+/// incident happened locally.
+///
+/// Date-only timestamps (stored at exactly midnight UTC, see
+/// [`time_dimensions`]) are returned unchanged: shifting them would move
+/// the incident onto the previous day in every zone west of UTC. Returns
+/// `None` if `occurred_at` can't be parsed.
+fn localize_occurred_at(occurred_at: &str, tz: chrono_tz::Tz) -> Option<String> {
+    use chrono::Timelike as _;
+
+    let utc = crime_map_database::source_db::parse_timestamp(occurred_at)?;
+    if utc.num_seconds_from_midnight() == 0 {
+        return None;
+    }
+    Some(
+        utc.with_timezone(&tz)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+    )
+}
+
+/// Returns the configured timezone for `source_id` (see
+/// [`crime_map_source::source_def::SourceDefinition::timezone`]).
+///
+/// Sources without one, or with a name that isn't a valid IANA timezone,
+/// keep bucketing by the stored (UTC) `occurred_at`, so late-night
+/// incidents may land on the wrong day. That is logged as a warning once
+/// per source per process.
+fn source_timezone(source_id: &str) -> Option<chrono_tz::Tz> {
+    static WARNED: std::sync::Mutex<std::collections::BTreeSet<String>> =
+        std::sync::Mutex::new(std::collections::BTreeSet::new());
+
+    let def = all_sources().into_iter().find(|s| s.id() == source_id);
+    let tz = def.as_ref().and_then(SourceDefinition::timezone);
+    if tz.is_none()
+        && WARNED
+            .lock()
+            .is_ok_and(|mut warned| warned.insert(source_id.to_string()))
+    {
+        match def.as_ref().and_then(|d| d.timezone.as_deref()) {
+            Some(invalid) => log::warn!(
+                "{source_id}: invalid timezone {invalid:?}; day buckets use stored occurred_at"
+            ),
+            None => log::warn!(
+                "{source_id}: no timezone configured; day buckets use stored occurred_at"
+            ),
+        }
+    }
+    tz
+}
+
+/// Returns the IANA timezone name of each fingerprinted source that has a
+/// valid one (see [`source_timezone`]), keyed by source ID.
+fn source_timezones(fingerprints: &[SourceFingerprint]) -> BTreeMap<String, String> {
+    let sources = all_sources();
+    fingerprints
+        .iter()
+        .filter_map(|fp| {
+            let tz = sources
+                .iter()
+                .find(|s| s.id() == fp.source_id)?
+                .timezone()?;
+            Some((fp.source_id.clone(), tz.name().to_string()))
+        })
+        .collect()
+}
+
+/// Extracts `(dow, hour)` from an `occurred_at` timestamp string.
+///
+/// `dow` is `0` (Sunday) through `6`. Sources that only report a date are
+/// stored at exactly midnight, so a missing or `00:00:00` time yields a
+/// `None` hour rather than a bogus `0`.
+fn time_dimensions(occurred_at: Option<&str>) -> (Option<i32>, Option<i32>) {
+    use chrono::Datelike as _;
+
+    let Some(ts) = occurred_at else {
+        return (None, None);
+    };
+
+    #[allow(clippy::cast_possible_wrap)]
+    let dow = ts
+        .get(..10)
+        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .map(|d| d.weekday().num_days_from_sunday() as i32);
+
+    let hour = ts
+        .get(11..19)
+        .filter(|time| *time != "00:00:00")
+        .and_then(|time| time.get(..2))
+        .and_then(|h| h.parse::<i32>().ok())
+        .filter(|h| (0..24).contains(h));
+
+    (dow, hour)
+}
+
+/// Columns of a source's `incidents` table read into an [`IncidentRow`]
+/// by [`incident_row`].
+const INCIDENT_COLUMNS: &str = "source_incident_id, category, parent_category, severity,
+     longitude, latitude, occurred_at::TEXT as occurred_at_text,
+     description, block_address,
+     city, state, arrest_made, domestic, location_type,
+     census_tract_geoid, census_place_geoid, state_fips,
+     county_geoid, neighborhood_id, coord_source";
+
+/// Builds an [`IncidentRow`] from a page row of [`ShardedIncidents`]
+/// selecting [`INCIDENT_COLUMNS`] (which follow the `rowid` in column 0).
+fn incident_row(
+    row: &duckdb::Row<'_>,
+    source_id: &str,
+    source_name: &str,
+) -> Result<IncidentRow, duckdb::Error> {
+    Ok(IncidentRow {
+        source_incident_id: row.get(1)?,
+        source_id: source_id.to_string(),
+        source_name: source_name.to_string(),
+        category: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        parent_category: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+        severity: row.get::<_, Option<i16>>(4)?.unwrap_or(1).into(),
+        longitude: row.get(5)?,
+        latitude: row.get(6)?,
+        occurred_at: row.get(7)?,
+        description: row.get(8)?,
+        block_address: row.get(9)?,
+        city: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
+        state: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+        arrest_made: row.get(12)?,
+        domestic: row.get(13)?,
+        location_type: row.get(14)?,
+        census_tract_geoid: row.get(15)?,
+        census_place_geoid: row.get(16)?,
+        state_fips: row.get(17)?,
+        county_geoid: row.get(18)?,
+        neighborhood_id: row.get(19)?,
+        coord_source: row.get(20)?,
+    })
 }
 
-/// Iterates over incidents from a single source `DuckDB` with keyset
-/// pagination. Calls `callback` for each row. Respects `limit` and
-/// `remaining` count.
+/// Iterates over the incidents matching `filter` minus `blocklist` from
+/// every shard of a source `DuckDB` with keyset pagination (see
+/// [`ShardedIncidents`]). Calls `callback` for each row. Respects `limit`
+/// and `remaining` count.
 ///
 /// Returns the number of rows processed.
 ///
@@ -759,14 +3849,17 @@ struct IncidentRow {
 fn iterate_source_incidents<F>(
     source_id: &str,
     source_name: &str,
+    filter: &str,
+    blocklist: &Blocklist,
+    remap: &CategoryRemap,
     limit: &mut Option<u64>,
     callback: &mut F,
-) -> Result<u64, Box<dyn std::error::Error>>
+) -> Result<u64, GenerateError>
 where
-    F: FnMut(&IncidentRow) -> Result<(), Box<dyn std::error::Error>>,
+    F: FnMut(&IncidentRow) -> Result<(), GenerateError>,
 {
-    let conn = crime_map_database::source_db::open_by_id(source_id)?;
-    let mut last_rowid: i64 = 0;
+    let mut incidents = ShardedIncidents::new(open_filtered_shards(source_id, blocklist)?);
+    let filter = blocklist.apply(filter, source_id);
     let mut count: u64 = 0;
 
     loop {
@@ -776,62 +3869,21 @@ where
 
         #[allow(clippy::cast_sign_loss)]
         let batch_limit = match *limit {
-            Some(r) => i64::try_from(r.min(BATCH_SIZE as u64))?,
+            Some(r) => i64::try_from(r.min(BATCH_SIZE as u64)).unwrap_or(BATCH_SIZE),
             None => BATCH_SIZE,
         };
 
-        let mut stmt = conn.prepare(
-            "SELECT rowid,
-                    source_incident_id, category, parent_category, severity,
-                    longitude, latitude, occurred_at::TEXT as occurred_at_text,
-                    description, block_address,
-                    city, state, arrest_made, domestic, location_type,
-                    census_tract_geoid, census_place_geoid, state_fips,
-                    county_geoid, neighborhood_id
-             FROM incidents
-             WHERE has_coordinates = TRUE
-               AND longitude BETWEEN -180 AND 180
-               AND latitude BETWEEN -90 AND 90
-               AND rowid > ?
-             ORDER BY rowid ASC
-             LIMIT ?",
+        let batch_len = incidents.next_page::<GenerateError, _>(
+            INCIDENT_COLUMNS,
+            &filter,
+            batch_limit,
+            |row| {
+                let mut incident = incident_row(row, source_id, source_name)?;
+                incident.apply_remap(remap);
+                callback(&incident)
+            },
         )?;
 
-        let mut rows = stmt.query(duckdb::params![last_rowid, batch_limit])?;
-
-        let mut batch_len: u64 = 0;
-        while let Some(row) = rows.next()? {
-            let rowid: i64 = row.get(0)?;
-            last_rowid = rowid;
-
-            let incident = IncidentRow {
-                source_incident_id: row.get(1)?,
-                source_id: source_id.to_string(),
-                source_name: source_name.to_string(),
-                category: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
-                parent_category: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
-                severity: row.get::<_, Option<i16>>(4)?.unwrap_or(1).into(),
-                longitude: row.get(5)?,
-                latitude: row.get(6)?,
-                occurred_at: row.get(7)?,
-                description: row.get(8)?,
-                block_address: row.get(9)?,
-                city: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
-                state: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
-                arrest_made: row.get(12)?,
-                domestic: row.get(13)?,
-                location_type: row.get(14)?,
-                census_tract_geoid: row.get(15)?,
-                census_place_geoid: row.get(16)?,
-                state_fips: row.get(17)?,
-                county_geoid: row.get(18)?,
-                neighborhood_id: row.get(19)?,
-            };
-
-            callback(&incident)?;
-            batch_len += 1;
-        }
-
         if batch_len == 0 {
             break;
         }
@@ -851,12 +3903,77 @@ where
     Ok(count)
 }
 
-/// Resolves the human-readable source name for a source ID.
+/// Opens `source_id`'s primary `DuckDB` (its first file in
+/// [`shard_paths`](crime_map_database::source_db::shard_paths)) for
+/// reading metadata such as its `_meta` table.
+///
+/// Returns `None` if the source has no file, or (with a warning) if it has
+/// no `incidents` table because a sync was interrupted before its first
+/// insert, so one half-initialized source doesn't abort the whole run.
+///
+/// # Errors
+///
+/// Returns an error if an existing file can't be opened or migrated.
+fn open_source_db(source_id: &str) -> Result<Option<duckdb::Connection>, GenerateError> {
+    let paths = crime_map_database::source_db::shard_paths(source_id);
+    match paths.first() {
+        Some(path) => open_source_shard(source_id, path),
+        None => Ok(None),
+    }
+}
+
+/// Opens every shard of `source_id`'s `DuckDB` for reading incidents, in
+/// shard order. Shards without an `incidents` table are skipped with a
+/// warning, as in [`open_source_db`].
+///
+/// # Errors
+///
+/// Returns an error if an existing file can't be opened or migrated.
+fn open_source_shards(source_id: &str) -> Result<Vec<duckdb::Connection>, GenerateError> {
+    crime_map_database::source_db::shard_paths(source_id)
+        .iter()
+        .filter_map(|path| open_source_shard(source_id, path).transpose())
+        .collect()
+}
+
+/// Opens every shard of `source_id` like [`open_source_shards`], with
+/// `blocklist`'s IDs for the source loaded into each so the filter from
+/// [`Blocklist::apply`] can run on it.
+///
+/// # Errors
+///
+/// Returns an error if a shard can't be opened or the IDs can't be loaded.
+fn open_filtered_shards(
+    source_id: &str,
+    blocklist: &Blocklist,
+) -> Result<Vec<duckdb::Connection>, GenerateError> {
+    let shards = open_source_shards(source_id)?;
+    for conn in &shards {
+        blocklist.load_into(conn, source_id)?;
+    }
+    Ok(shards)
+}
+
+fn open_source_shard(
+    source_id: &str,
+    path: &Path,
+) -> Result<Option<duckdb::Connection>, GenerateError> {
+    let conn = crime_map_database::source_db::open_existing(path)?;
+    if conn.is_none() {
+        log::warn!(
+            "Source '{source_id}' has no incidents table in {}, skipping — run sync first",
+            path.display()
+        );
+    }
+    Ok(conn)
+}
+
+/// Resolves a human-readable source name
 ///
 /// Reads from the `_meta` table in the source's `DuckDB` file, or falls
 /// back to the TOML registry name.
 fn resolve_source_name(source_id: &str) -> String {
-    if let Ok(conn) = crime_map_database::source_db::open_by_id(source_id)
+    if let Ok(Some(conn)) = open_source_db(source_id)
         && let Ok(Some(name)) = crime_map_database::source_db::get_meta(&conn, "source_name")
     {
         return name;
@@ -874,75 +3991,199 @@ fn resolve_source_name(source_id: &str) -> String {
 // PMTiles generation
 // ============================================================
 
-/// Exports incidents as `GeoJSONSeq` and generates `PMTiles` via tippecanoe.
+/// Exports incidents as `GeoJSONSeq` and tiles them into `PMTiles` with
+/// the configured [`TileBackend`].
 fn generate_pmtiles(
     args: &GenerateArgs,
     source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
     dir: &Path,
     progress: &Arc<dyn ProgressCallback>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let geojsonseq_path = dir.join("incidents.geojsonseq");
+) -> Result<(), GenerateError> {
+    // Drop intermediates kept from earlier runs, which may have used a
+    // different shard count.
+    for stale in incidents_intermediates(dir) {
+        std::fs::remove_file(&stale)?;
+    }
+
+    let shards = args.geojsonseq_shards.unwrap_or(1).max(1);
+    let geojsonseq_paths = geojsonseq_shard_paths(dir, shards);
 
     log::info!("Exporting incidents to GeoJSONSeq...");
-    export_geojsonseq(&geojsonseq_path, args.limit, source_ids, progress)?;
-
-    // Skip tippecanoe if no features were exported (empty GeoJSONSeq).
-    // tippecanoe crashes with "Did not read any valid geometries" on empty input.
-    let file_size = std::fs::metadata(&geojsonseq_path)
-        .map(|m| m.len())
-        .unwrap_or(0);
-    if file_size == 0 {
+    let shard_counts = export_geojsonseq(
+        &geojsonseq_paths,
+        args.limit,
+        &exportable_filter(args),
+        source_ids,
+        remap,
+        blocklist,
+        keyed_jitter(args)?,
+        args.stack_spread,
+        progress,
+    )?;
+
+    // Skip tiling if no features were exported (empty GeoJSONSeq).
+    // tippecanoe crashes with "Did not read any valid geometries" on empty
+    // input, so empty shards are dropped as well.
+    let mut inputs = Vec::new();
+    for (path, &count) in geojsonseq_paths.iter().zip(&shard_counts) {
+        if count == 0 {
+            std::fs::remove_file(path).ok();
+        } else {
+            inputs.push(path);
+        }
+    }
+    if inputs.is_empty() {
         log::warn!("No incident features to tile; skipping PMTiles generation");
-        std::fs::remove_file(&geojsonseq_path).ok();
         return Ok(());
     }
 
-    log::info!("Running tippecanoe to generate PMTiles...");
+    let output_path = dir.join(paths::INCIDENTS_PMTILES_FILE);
+    let inputs: Vec<&Path> = inputs.into_iter().map(PathBuf::as_path).collect();
+    args.tile_backend
+        .generator()
+        .generate(&inputs, &output_path, args.pmtiles_cluster.as_ref())?;
 
-    let output_path = dir.join("incidents.pmtiles");
-
-    let mut cmd = Command::new("tippecanoe");
-    cmd.args([
-        "-o",
-        &*output_path.to_string_lossy(),
-        "--force",
-        "--no-feature-limit",
-        "--no-tile-size-limit",
-        "--minimum-zoom=0",
-        "--maximum-zoom=14",
-        "--drop-densest-as-needed",
-        "--extend-zooms-if-still-dropping",
-        "--layer=incidents",
-        &*geojsonseq_path.to_string_lossy(),
-    ]);
+    log::info!("PMTiles generated: {}", output_path.display());
+    Ok(())
+}
 
-    if std::env::var("CI").is_ok() {
-        cmd.arg("--quiet");
+/// Checks that tiling `exportable` incidents (capped by `--limit`) stays
+/// within [`GenerateArgs::max_pmtiles_features`].
+///
+/// # Errors
+///
+/// Returns [`GenerateError::TooManyFeatures`] if it doesn't.
+fn check_pmtiles_feature_count(args: &GenerateArgs, exportable: u64) -> Result<(), GenerateError> {
+    let count = args.limit.map_or(exportable, |limit| exportable.min(limit));
+    match args.max_pmtiles_features {
+        Some(max) if count > max => Err(GenerateError::TooManyFeatures { count, max }),
+        _ => Ok(()),
     }
+}
+
+/// Returns [`GenerateArgs::sensitive_jitter`] with its
+/// [`GenerateArgs::jitter_key`] after validating both, or `None` if
+/// nothing is jittered.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::InvalidArgs`] if the jitter is invalid or its
+/// key is missing or too short.
+fn keyed_jitter(
+    args: &GenerateArgs,
+) -> Result<Option<(&SensitiveJitter, &JitterKey)>, GenerateError> {
+    let Some(jitter) = &args.sensitive_jitter else {
+        return Ok(None);
+    };
+    jitter::validate_jitter(jitter).map_err(GenerateError::InvalidArgs)?;
+    let key = args.jitter_key.as_ref().ok_or_else(|| {
+        GenerateError::InvalidArgs(format!(
+            "Jittering sensitive incidents needs a secret key in {}",
+            jitter::JITTER_KEY_ENV
+        ))
+    })?;
+    jitter::validate_jitter_key(key).map_err(GenerateError::InvalidArgs)?;
+    Ok(Some((jitter, key)))
+}
 
-    let status = cmd.status()?;
+/// Returns `incident`'s location displaced by `jitter` if it's a
+/// sensitive incident, or `None` if it keeps its exact location.
+fn jittered_location(
+    incident: &IncidentRow,
+    jitter: Option<(&SensitiveJitter, &JitterKey)>,
+) -> Option<(f64, f64)> {
+    let (jitter, key) = jitter?;
+    jitter
+        .applies_to(&incident.category, incident.domestic)
+        .then(|| {
+            jitter.displace(
+                key,
+                &incident.source_id,
+                &incident.source_incident_id,
+                incident.longitude,
+                incident.latitude,
+            )
+        })
+}
 
-    if !status.success() {
-        return Err("tippecanoe failed".into());
+/// Returns the incidents `GeoJSONSeq` path(s) for `shards` shards:
+/// `incidents.geojsonseq` for one, else `incidents-000.geojsonseq` etc.
+fn geojsonseq_shard_paths(dir: &Path, shards: u32) -> Vec<PathBuf> {
+    if shards <= 1 {
+        return vec![dir.join("incidents.geojsonseq")];
     }
+    (0..shards)
+        .map(|i| dir.join(format!("incidents-{i:03}.geojsonseq")))
+        .collect()
+}
 
-    log::info!("PMTiles generated: {}", output_path.display());
-    Ok(())
+/// Returns the shard (`0..shards`) an incident at `(lng, lat)` is written
+/// to.
+///
+/// Points are bucketed by their [`SHARD_TILE_ZOOM`] Web Mercator tile and
+/// the tile coordinates are hashed, so shards are deterministic across
+/// runs, each covers interleaved regions spread over the whole map, and
+/// every tile at that zoom or deeper falls entirely in one shard.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn geojsonseq_shard(lng: f64, lat: f64, shards: u32) -> u32 {
+    if shards <= 1 {
+        return 0;
+    }
+
+    let n = f64::from(1_u32 << SHARD_TILE_ZOOM);
+    let lat = lat.clamp(-85.051_128, 85.051_128).to_radians();
+    let x = ((lng + 180.0) / 360.0 * n).floor().clamp(0.0, n - 1.0);
+    let y = ((1.0 - lat.tan().asinh() / std::f64::consts::PI) / 2.0 * n)
+        .floor()
+        .clamp(0.0, n - 1.0);
+
+    let tile = ((x as u64) << 32) | y as u64;
+
+    // splitmix64 finalizer: a stable, well-mixed hash of the tile.
+    let mut h = tile.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^= h >> 31;
+
+    (h % u64::from(shards)) as u32
 }
 
 /// Exports all incidents from source `DuckDB` files as newline-delimited
 /// `GeoJSON`, iterating per-source with keyset pagination and streaming
 /// writes to keep memory constant.
+///
+/// With more than one path, each feature goes to the shard chosen by
+/// [`geojsonseq_shard`]. Returns the number of features written to each
+/// path; progress and totals cover all shards.
+///
+/// Sensitive incidents are displaced by `jitter`. With `spread`, the
+/// other incidents on a coordinate [`stacked_coordinates`] reports for
+/// their source are spread around it.
+#[allow(clippy::too_many_arguments)]
 fn export_geojsonseq(
-    output_path: &Path,
+    output_paths: &[PathBuf],
     limit: Option<u64>,
+    filter: &str,
     source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    jitter: Option<(&SensitiveJitter, &JitterKey)>,
+    spread: Option<StackSpread>,
     progress: &Arc<dyn ProgressCallback>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let file = std::fs::File::create(output_path)?;
-    let mut writer = BufWriter::new(file);
+) -> Result<Vec<u64>, GenerateError> {
+    let mut writers = output_paths
+        .iter()
+        .map(|path| Ok(BufWriter::new(std::fs::File::create(path)?)))
+        .collect::<Result<Vec<_>, std::io::Error>>()?;
+    #[allow(clippy::cast_possible_truncation)]
+    let shards = writers.len() as u32;
+    let mut shard_counts = vec![0_u64; writers.len()];
     let mut total_count: u64 = 0;
     let mut remaining = limit;
+    let mut jittered: u64 = 0;
+    let mut spread_count: u64 = 0;
 
     for sid in source_ids {
         if remaining == Some(0) {
@@ -950,149 +4191,903 @@ fn export_geojsonseq(
         }
 
         let source_name = resolve_source_name(sid);
-        let source_count =
-            iterate_source_incidents(sid, &source_name, &mut remaining, &mut |incident| {
-                // Read pre-computed spatial attribution from source DuckDB
-                let tract_geoid = incident.census_tract_geoid.clone();
-                let state_fips = incident.state_fips.clone();
-                let county_geoid = incident.county_geoid.clone();
-                let place_geoid = incident.census_place_geoid.clone();
-                let neighborhood_id = incident.neighborhood_id.clone();
-
-                let feature = serde_json::json!({
-                    "type": "Feature",
-                    "geometry": {
-                        "type": "Point",
-                        "coordinates": [incident.longitude, incident.latitude]
-                    },
-                    "properties": {
-                        "sid": incident.source_incident_id,
-                        "src": incident.source_id,
-                        "src_name": incident.source_name,
-                        "subcategory": incident.category,
-                        "category": incident.parent_category,
-                        "severity": incident.severity,
-                        "city": incident.city,
-                        "state": incident.state,
-                        "arrest": incident.arrest_made,
-                        "date": incident.occurred_at,
-                        "desc": incident.description,
-                        "addr": incident.block_address,
-                        "state_fips": state_fips,
-                        "county_geoid": county_geoid,
-                        "place_geoid": place_geoid,
-                        "tract_geoid": tract_geoid,
-                        "neighborhood_id": neighborhood_id,
-                    }
-                });
-
-                serde_json::to_writer(&mut writer, &feature)?;
+        let stacked = if spread.is_some() {
+            stacked_coordinates(sid, filter, blocklist)?
+        } else {
+            BTreeSet::new()
+        };
+        let source_count = iterate_source_incidents(
+            sid,
+            &source_name,
+            filter,
+            blocklist,
+            remap,
+            &mut remaining,
+            &mut |incident| {
+                let mut feature = incident_feature(incident);
+                let (mut lng, mut lat) = (incident.longitude, incident.latitude);
+                if let Some(moved) = jittered_location(incident, jitter) {
+                    (lng, lat) = moved;
+                    feature["geometry"]["coordinates"] = serde_json::json!([lng, lat]);
+                    jittered += 1;
+                } else if let Some(spread) = spread
+                    && stacked.contains(&(lng.to_bits(), lat.to_bits()))
+                {
+                    (lng, lat) = spread.displace(
+                        &incident.source_id,
+                        &incident.source_incident_id,
+                        lng,
+                        lat,
+                    );
+                    feature["geometry"]["coordinates"] = serde_json::json!([lng, lat]);
+                    spread_count += 1;
+                }
+                let shard = geojsonseq_shard(lng, lat, shards) as usize;
+                let writer = &mut writers[shard];
+                serde_json::to_writer(&mut *writer, &feature)?;
                 writer.write_all(b"\n")?;
+                shard_counts[shard] += 1;
                 Ok(())
-            })?;
+            },
+        )?;
 
         total_count += source_count;
         progress.inc(source_count);
         log::info!("Exported {source_count} features from source '{sid}' (total: {total_count})");
     }
 
-    writer.flush()?;
-    log::info!(
-        "Exported {total_count} features to {}",
-        output_path.display()
-    );
-    Ok(())
+    for writer in &mut writers {
+        writer.flush()?;
+    }
+    if let Some((jitter, _)) = jitter {
+        log::info!(
+            "Jittered {jittered} sensitive incidents by up to {}m",
+            jitter.radius_meters
+        );
+    }
+    if let Some(spread) = spread {
+        log::info!(
+            "Spread {spread_count} stacked incidents by up to {}m",
+            spread.radius_meters
+        );
+    }
+    for (path, count) in output_paths.iter().zip(&shard_counts) {
+        log::info!("Exported {count} features to {}", path.display());
+    }
+    if output_paths.len() > 1 {
+        log::info!(
+            "Exported {total_count} features across {} shards",
+            output_paths.len()
+        );
+    }
+    Ok(shard_counts)
 }
 
-// ============================================================
-// Sidebar SQLite generation
-// ============================================================
-
-/// Generates a `SQLite` database for server-side sidebar queries.
-///
-/// Creates `incidents.db` with:
-/// - A flat `incidents` table containing all incident data
-/// - An R-tree spatial index for efficient count queries
-/// - A date index for efficient paginated feature queries
-///
-/// The R-tree enables fast `COUNT(*)` within a bounding box.
-/// Feature queries walk the date index and check bbox inline,
-/// relying on `LIMIT` to short-circuit early.
+/// Returns the coordinates (as [`f64::to_bits`] pairs) that more than one
+/// of `sid`'s incidents matching `filter` minus `blocklist` share, for
+/// [`StackSpread`]. Counted per source shard, so a coordinate used once
+/// in each of two shards isn't reported.
 ///
 /// # Errors
 ///
-/// Returns an error if the source `DuckDB` export, `SQLite` creation, or
-/// index population fails.
-#[allow(clippy::too_many_lines, clippy::future_not_send)]
-async fn generate_sidebar_db(
-    args: &GenerateArgs,
-    source_ids: &[String],
-    dir: &Path,
-    progress: &Arc<dyn ProgressCallback>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    use switchy_database::DatabaseValue;
-
-    let db_path = dir.join("incidents.db");
-
-    // Remove any existing file so we start fresh
-    if db_path.exists() {
-        std::fs::remove_file(&db_path)?;
+/// Returns an error if a source shard can't be opened or queried.
+fn stacked_coordinates(
+    sid: &str,
+    filter: &str,
+    blocklist: &Blocklist,
+) -> Result<BTreeSet<(u64, u64)>, GenerateError> {
+    let filter = blocklist.apply(filter, sid);
+    let mut stacked = BTreeSet::new();
+    for conn in open_filtered_shards(sid, blocklist)? {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT longitude, latitude
+             FROM incidents
+             WHERE {filter}
+             GROUP BY longitude, latitude
+             HAVING COUNT(*) > 1"
+        ))?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, f64>(0)?, row.get::<_, f64>(1)?)))?;
+        for row in rows {
+            let (lng, lat) = row?;
+            stacked.insert((lng.to_bits(), lat.to_bits()));
+        }
     }
+    if !stacked.is_empty() {
+        log::info!("{sid}: {} stacked coordinates to spread", stacked.len());
+    }
+    Ok(stacked)
+}
 
-    log::info!("Creating sidebar SQLite database...");
-
-    let sqlite = switchy_database_connection::init_sqlite_rusqlite(Some(&db_path))
-        .map_err(|e| format!("Failed to open sidebar SQLite: {e}"))?;
-
-    // WAL mode + generous busy timeout to avoid "database is locked" errors
-    // when the connection pool uses multiple connections.
-    sqlite.exec_raw("PRAGMA journal_mode=WAL").await?;
-    sqlite.exec_raw("PRAGMA busy_timeout=5000").await?;
+/// Returns `incident` as a GeoJSON point feature, with the pre-computed
+/// spatial attribution read from the source `DuckDB` as properties.
+fn incident_feature(incident: &IncidentRow) -> serde_json::Value {
+    serde_json::json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "Point",
+            "coordinates": [incident.longitude, incident.latitude]
+        },
+        "properties": {
+            "sid": incident.source_incident_id,
+            "src": incident.source_id,
+            "src_name": incident.source_name,
+            "subcategory": incident.category,
+            "category": incident.parent_category,
+            "severity": incident.severity,
+            "city": incident.city,
+            "state": incident.state,
+            "arrest": incident.arrest_made,
+            "domestic": incident.domestic,
+            "date": incident.occurred_at,
+            "desc": incident.description,
+            "addr": incident.block_address,
+            "state_fips": incident.state_fips,
+            "county_geoid": incident.county_geoid,
+            "place_geoid": incident.census_place_geoid,
+            "tract_geoid": incident.census_tract_geoid,
+            "neighborhood_id": incident.neighborhood_id,
+            "coord_src": incident.coord_source,
+        }
+    })
+}
 
-    // Create schema
-    sqlite
-        .exec_raw(
-            "CREATE TABLE incidents (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                source_id TEXT NOT NULL,
-                source_name TEXT NOT NULL,
-                source_incident_id TEXT,
-                subcategory TEXT NOT NULL,
-                category TEXT NOT NULL,
-                severity INTEGER NOT NULL,
-                longitude REAL NOT NULL,
-                latitude REAL NOT NULL,
-                occurred_at TEXT,
-                description TEXT,
-                block_address TEXT,
-                city TEXT,
-                state TEXT,
-                arrest_made INTEGER,
-                location_type TEXT,
-                state_fips TEXT,
-                county_geoid TEXT,
-                place_geoid TEXT,
-                tract_geoid TEXT,
-                neighborhood_id TEXT
-            )",
-        )
+/// Writes `features` to `writer` as a GeoJSON `FeatureCollection`, one
+/// feature per line.
+///
+/// Unlike the `GeoJSONSeq` written for tiling, a `FeatureCollection` is
+/// one JSON document that has to be buffered to build and parsed whole to
+/// read, so it's only for small result sets (at most
+/// [`MAX_FEATURE_COLLECTION_FEATURES`]). Build the features with
+/// [`incident_feature`] so they carry the same properties as the tiles.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+fn write_feature_collection<W: Write>(
+    features: &[serde_json::Value],
+    mut writer: W,
+) -> Result<(), GenerateError> {
+    writer.write_all(br#"{"type":"FeatureCollection","features":["#)?;
+    for (i, feature) in features.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(b"\n")?;
+        serde_json::to_writer(&mut writer, feature)?;
+    }
+    writer.write_all(b"\n]}\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+// ============================================================
+// Boundary extracts
+// ============================================================
+
+/// Most incidents a GeoJSON [`export_for_boundary`] extract buffers into
+/// its `FeatureCollection` before failing with
+/// [`GenerateError::FeatureCollectionTooLarge`].
+pub const MAX_FEATURE_COLLECTION_FEATURES: u64 = 250_000;
+
+/// Boundary level of a [`export_for_boundary`] extract, identifying the
+/// geoid column enrichment stamps on every incident.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundaryType {
+    /// US state, by 2-digit FIPS code.
+    State,
+    /// County, by 5-digit GEOID.
+    County,
+    /// Census place, by 7-digit GEOID.
+    Place,
+    /// Census tract, by 11-digit GEOID.
+    Tract,
+    /// Neighborhood, by `nbhd-{id}` string ID.
+    Neighborhood,
+}
+
+impl BoundaryType {
+    /// Returns the boundary type's CLI name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::State => "state",
+            Self::County => "county",
+            Self::Place => "place",
+            Self::Tract => "tract",
+            Self::Neighborhood => "neighborhood",
+        }
+    }
+
+    /// Returns the source `DuckDB` `incidents` column holding this level's
+    /// geoid.
+    #[must_use]
+    pub const fn column(self) -> &'static str {
+        match self {
+            Self::State => "state_fips",
+            Self::County => "county_geoid",
+            Self::Place => "census_place_geoid",
+            Self::Tract => "census_tract_geoid",
+            Self::Neighborhood => "neighborhood_id",
+        }
+    }
+
+    /// Returns whether `geoid` has this level's shape: the right number
+    /// of digits for census levels, or `nbhd-{id}` for neighborhoods (see
+    /// [`neighborhood_geoid`]).
+    #[must_use]
+    pub fn accepts(self, geoid: &str) -> bool {
+        let digits = match self {
+            Self::State => 2,
+            Self::County => 5,
+            Self::Place => 7,
+            Self::Tract => 11,
+            Self::Neighborhood => {
+                return geoid
+                    .strip_prefix("nbhd-")
+                    .is_some_and(|id| id.parse::<i32>().is_ok());
+            }
+        };
+        geoid.len() == digits && geoid.bytes().all(|b| b.is_ascii_digit())
+    }
+}
+
+/// Parses a boundary type (`state`, `county`, `place`, `tract`, or
+/// `neighborhood`).
+///
+/// # Errors
+///
+/// Returns an error naming the valid types if `s` isn't one of them.
+pub fn parse_boundary_type(s: &str) -> Result<BoundaryType, String> {
+    match s {
+        "state" => Ok(BoundaryType::State),
+        "county" => Ok(BoundaryType::County),
+        "place" => Ok(BoundaryType::Place),
+        "tract" => Ok(BoundaryType::Tract),
+        "neighborhood" => Ok(BoundaryType::Neighborhood),
+        _ => Err(format!(
+            "unknown boundary type '{s}' (expected 'state', 'county', 'place', \
+             'tract', or 'neighborhood')"
+        )),
+    }
+}
+
+/// File format of a [`export_for_boundary`] extract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractFormat {
+    /// A GeoJSON `FeatureCollection` of point features, with the same
+    /// properties as the tiled incidents.
+    GeoJson,
+    /// CSV with a header row.
+    Csv,
+    /// Parquet.
+    Parquet,
+}
+
+impl ExtractFormat {
+    /// Returns the format's CLI name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::GeoJson => "geojson",
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+/// Parses an extract format (`geojson`, `csv`, or `parquet`).
+///
+/// # Errors
+///
+/// Returns an error naming the valid formats if `s` isn't one of them.
+pub fn parse_extract_format(s: &str) -> Result<ExtractFormat, String> {
+    match s {
+        "geojson" => Ok(ExtractFormat::GeoJson),
+        "csv" => Ok(ExtractFormat::Csv),
+        "parquet" => Ok(ExtractFormat::Parquet),
+        _ => Err(format!(
+            "unknown extract format '{s}' (expected 'geojson', 'csv', or 'parquet')"
+        )),
+    }
+}
+
+/// Reports the pairs of `source_ids` whose coverage overlaps, over their
+/// exportable incidents minus `--blocklist` (see
+/// [`dedup::source_overlaps`]). This is the check for whether a pair needs
+/// `--dedup-sources` priorities.
+///
+/// # Errors
+///
+/// Returns an error if the blocklist can't be loaded or a source can't be
+/// read.
+pub fn source_overlap_report(
+    args: &GenerateArgs,
+    source_ids: &[String],
+) -> Result<Vec<dedup::SourceOverlap>, GenerateError> {
+    let blocklist = load_blocklist(args, source_ids)?;
+    dedup::overlap_report(source_ids, &exportable_filter(args), &blocklist)
+}
+
+/// Exports the incidents of `source_ids` whose `boundary_type` geoid is
+/// `geoid` to `output` as `format`, returning how many were written.
+///
+/// Filters on the geoid column stamped during enrichment (see
+/// [`BoundaryType::column`]) rather than testing polygons, alongside the
+/// usual `--bbox`, `--sample`, `--limit`, `--category-remap`, and
+/// `--blocklist` handling. Sensitive incidents are jittered as in the
+/// incidents `PMTiles` (see [`jitter`]). The file is written next to
+/// `output` and only moved into place once complete.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::InvalidArgs`] if `geoid` isn't shaped like a
+/// `boundary_type` geoid or the jitter is invalid (see [`keyed_jitter`]),
+/// [`GenerateError::BoundaryNotFound`] if no
+/// source has an exportable incident in the boundary, or an error if a
+/// source can't be read or the file can't be written.
+#[allow(clippy::too_many_lines)]
+pub fn export_for_boundary(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    boundary_type: BoundaryType,
+    geoid: &str,
+    format: ExtractFormat,
+    output: &Path,
+) -> Result<u64, GenerateError> {
+    if !boundary_type.accepts(geoid) {
+        return Err(GenerateError::InvalidArgs(format!(
+            "'{geoid}' is not a valid {} geoid",
+            boundary_type.as_str()
+        )));
+    }
+
+    let jitter = keyed_jitter(args)?;
+    let remap = CategoryRemap::load(args.category_remap.as_deref())?;
+    let blocklist = load_blocklist(args, source_ids)?;
+    let filter = format!(
+        "{}
+               AND {} = '{}'",
+        exportable_filter(args),
+        boundary_type.column(),
+        geoid.replace('\'', "''"),
+    );
+
+    let tmp_path = with_suffix(output, ".tmp");
+    if tmp_path.exists() {
+        std::fs::remove_file(&tmp_path)?;
+    }
+
+    log::info!(
+        "Extracting incidents in {} {geoid} to {}...",
+        boundary_type.as_str(),
+        output.display()
+    );
+
+    let mut remaining = args.limit;
+    let mut total: u64 = 0;
+    match format {
+        ExtractFormat::GeoJson => {
+            let mut features = Vec::new();
+            for sid in source_ids {
+                if remaining == Some(0) {
+                    break;
+                }
+                total += iterate_source_incidents(
+                    sid,
+                    &resolve_source_name(sid),
+                    &filter,
+                    &blocklist,
+                    &remap,
+                    &mut remaining,
+                    &mut |incident| {
+                        if features.len() as u64 >= MAX_FEATURE_COLLECTION_FEATURES {
+                            return Err(GenerateError::FeatureCollectionTooLarge {
+                                max: MAX_FEATURE_COLLECTION_FEATURES,
+                            });
+                        }
+                        let mut feature = incident_feature(incident);
+                        if let Some((lng, lat)) = jittered_location(incident, jitter) {
+                            feature["geometry"]["coordinates"] = serde_json::json!([lng, lat]);
+                        }
+                        features.push(feature);
+                        Ok(())
+                    },
+                )?;
+            }
+            if total > 0 {
+                let writer = BufWriter::new(std::fs::File::create(&tmp_path)?);
+                write_feature_collection(&features, writer)?;
+            }
+        }
+        ExtractFormat::Csv | ExtractFormat::Parquet => {
+            let duck = duckdb::Connection::open_in_memory()?;
+            duck.execute_batch(
+                "CREATE TABLE extract (
+                    source_id VARCHAR,
+                    source_name VARCHAR,
+                    source_incident_id VARCHAR,
+                    category VARCHAR,
+                    subcategory VARCHAR,
+                    severity INTEGER,
+                    longitude DOUBLE,
+                    latitude DOUBLE,
+                    occurred_at VARCHAR,
+                    description VARCHAR,
+                    block_address VARCHAR,
+                    city VARCHAR,
+                    state VARCHAR,
+                    arrest_made BOOLEAN,
+                    domestic BOOLEAN,
+                    location_type VARCHAR,
+                    state_fips VARCHAR,
+                    county_geoid VARCHAR,
+                    place_geoid VARCHAR,
+                    tract_geoid VARCHAR,
+                    neighborhood_id VARCHAR
+                )",
+            )?;
+            duck.execute_batch("BEGIN TRANSACTION")?;
+            let mut insert_stmt = duck.prepare(
+                "INSERT INTO extract VALUES
+                    (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            for sid in source_ids {
+                if remaining == Some(0) {
+                    break;
+                }
+                total += iterate_source_incidents(
+                    sid,
+                    &resolve_source_name(sid),
+                    &filter,
+                    &blocklist,
+                    &remap,
+                    &mut remaining,
+                    &mut |incident| {
+                        let (lng, lat) = jittered_location(incident, jitter)
+                            .unwrap_or((incident.longitude, incident.latitude));
+                        insert_stmt.execute(duckdb::params![
+                            incident.source_id,
+                            incident.source_name,
+                            incident.source_incident_id,
+                            incident.parent_category,
+                            incident.category,
+                            incident.severity,
+                            lng,
+                            lat,
+                            incident.occurred_at,
+                            incident.description,
+                            incident.block_address,
+                            incident.city,
+                            incident.state,
+                            incident.arrest_made,
+                            incident.domestic,
+                            incident.location_type,
+                            incident.state_fips,
+                            incident.county_geoid,
+                            incident.census_place_geoid,
+                            incident.census_tract_geoid,
+                            incident.neighborhood_id,
+                        ])?;
+                        Ok(())
+                    },
+                )?;
+            }
+            duck.execute_batch("COMMIT")?;
+            drop(insert_stmt);
+
+            if total > 0 {
+                let options = if format == ExtractFormat::Csv {
+                    "FORMAT CSV, HEADER"
+                } else {
+                    "FORMAT PARQUET"
+                };
+                duck.execute_batch(&format!(
+                    "COPY extract TO '{}' ({options})",
+                    tmp_path.to_string_lossy().replace('\'', "''"),
+                ))?;
+            }
+        }
+    }
+
+    if total == 0 {
+        if tmp_path.exists() {
+            std::fs::remove_file(&tmp_path)?;
+        }
+        return Err(GenerateError::BoundaryNotFound {
+            boundary_type: boundary_type.as_str(),
+            geoid: geoid.to_string(),
+        });
+    }
+
+    std::fs::rename(&tmp_path, output)?;
+    log::info!("Extracted {total} incidents to {}", output.display());
+    Ok(total)
+}
+
+// ============================================================
+// Sidebar SQLite generation
+// ============================================================
+
+/// How [`generate_sidebar_db`] fills the sidebar DB. Both produce the
+/// same schema, R-tree, indexes, and rows; only the row IDs may differ.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SidebarBackend {
+    /// Decode each batch of incidents in Rust and insert it row by row.
+    #[default]
+    Rows,
+    /// Run one `INSERT … SELECT` per source shard in `DuckDB`, writing
+    /// through its `sqlite` extension, so rows never pass through Rust.
+    /// Faster, but the extension is downloaded on first use.
+    Duckdb,
+}
+
+impl SidebarBackend {
+    /// Returns the backend's CLI name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Rows => "rows",
+            Self::Duckdb => "duckdb",
+        }
+    }
+}
+
+/// Parses a `--sidebar-backend` value (`rows` or `duckdb`).
+///
+/// # Errors
+///
+/// Returns an error naming the valid backends if `s` isn't one of them.
+pub fn parse_sidebar_backend(s: &str) -> Result<SidebarBackend, String> {
+    match s {
+        "rows" => Ok(SidebarBackend::Rows),
+        "duckdb" => Ok(SidebarBackend::Duckdb),
+        _ => Err(format!(
+            "unknown sidebar backend '{s}' (expected 'rows' or 'duckdb')"
+        )),
+    }
+}
+
+/// Generates a `SQLite` database for server-side sidebar queries.
+///
+/// Creates `incidents.db` with:
+/// - A flat `incidents` table containing all incident data
+/// - An R-tree spatial index for efficient count queries
+/// - A date index for efficient paginated feature queries
+///
+/// The R-tree enables fast `COUNT(*)` within a bounding box.
+/// Feature queries walk the date index and check bbox inline,
+/// relying on `LIMIT` to short-circuit early.
+///
+/// With `args.raw_json`, a `raw_records` table also holds the raw source
+/// JSON kept at ingest for each inserted incident (see
+/// [`GenerateArgs::raw_json`]). Sensitive incidents are jittered as in
+/// the incidents `PMTiles` (see [`jitter_sidebar_rows`]).
+///
+/// With `changed_sources` (see [`sidebar_changed_sources`]), a copy of the
+/// existing DB is updated instead: each listed source's rows and R-tree
+/// entries are deleted, the ones still in `source_ids` are re-inserted,
+/// and the copy replaces the original.
+///
+/// # Errors
+///
+/// Returns an error if the source `DuckDB` export, `SQLite` creation, or
+/// index population fails.
+#[allow(clippy::too_many_lines, clippy::future_not_send)]
+async fn generate_sidebar_db(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    changed_sources: Option<&[String]>,
+    dir: &Path,
+    progress: &Arc<dyn ProgressCallback>,
+) -> Result<(), GenerateError> {
+    use switchy_database::DatabaseValue;
+
+    let output_path = dir.join(paths::INCIDENTS_DB_FILE);
+    let db_path = begin_atomic_output(&output_path)?;
+
+    if let Some(changed) = changed_sources {
+        log::info!(
+            "Updating sidebar SQLite database for {} changed source(s): {}",
+            changed.len(),
+            changed.join(", ")
+        );
+        std::fs::copy(&output_path, &db_path)?;
+    } else {
+        log::info!("Creating sidebar SQLite database...");
+    }
+
+    let sqlite = open_sidebar_sqlite(&db_path).await?;
+
+    if let Some(changed) = changed_sources {
+        // The R-tree has no source column; its entries share the row IDs.
+        for sid in changed {
+            let params = [DatabaseValue::String(sid.clone())];
+            sqlite
+                .exec_raw_params(
+                    "DELETE FROM incidents_rtree
+                     WHERE id IN (SELECT id FROM incidents WHERE source_id = $1)",
+                    &params,
+                )
+                .await
+                .map_err(|e| GenerateError::Sqlite(format!("Failed to delete R-tree rows: {e}")))?;
+            sqlite
+                .exec_raw_params("DELETE FROM incidents WHERE source_id = $1", &params)
+                .await
+                .map_err(|e| GenerateError::Sqlite(format!("Failed to delete incidents: {e}")))?;
+            if args.raw_json {
+                sqlite
+                    .exec_raw_params("DELETE FROM raw_records WHERE source_id = $1", &params)
+                    .await
+                    .map_err(|e| {
+                        GenerateError::Sqlite(format!("Failed to delete raw records: {e}"))
+                    })?;
+            }
+        }
+    } else {
+        create_sidebar_schema(&*sqlite, args.raw_json).await?;
+    }
+
+    // Populate from per-source DuckDB files
+    let insert_ids: Vec<&String> = source_ids
+        .iter()
+        .filter(|sid| changed_sources.is_none_or(|changed| changed.contains(sid)))
+        .collect();
+    let (sqlite, total_count) = match args.sidebar_backend {
+        SidebarBackend::Rows => {
+            let count =
+                insert_sidebar_rows(&*sqlite, args, &insert_ids, remap, blocklist, progress)
+                    .await?;
+            (sqlite, count)
+        }
+        SidebarBackend::Duckdb => {
+            // DuckDB's sqlite extension links its own SQLite, whose file
+            // locks can't see this pool's connections, so close them while
+            // it writes.
+            drop(sqlite);
+            let count = insert_sidebar_rows_duckdb(
+                args,
+                &insert_ids,
+                remap,
+                blocklist,
+                &db_path,
+                progress,
+            )?;
+            (open_sidebar_sqlite(&db_path).await?, count)
+        }
+    };
+
+    if let Some((jitter, key)) = keyed_jitter(args)? {
+        let moved = jitter_sidebar_rows(&*sqlite, jitter, key, &insert_ids).await?;
+        log::info!(
+            "Jittered {moved} sensitive incidents in the sidebar DB by up to {}m",
+            jitter.radius_meters
+        );
+    }
+
+    if let Some(changed) = changed_sources {
+        // Indexes are maintained by the inserts; only the R-tree needs the
+        // re-inserted rows added.
+        for sid in changed {
+            sqlite
+                .exec_raw_params(
+                    "INSERT INTO incidents_rtree (id, min_lng, max_lng, min_lat, max_lat)
+                     SELECT id, longitude, longitude, latitude, latitude FROM incidents
+                     WHERE source_id = $1",
+                    &[DatabaseValue::String(sid.clone())],
+                )
+                .await
+                .map_err(|e| GenerateError::Sqlite(format!("Failed to populate R-tree: {e}")))?;
+        }
+    } else {
+        // Populate R-tree from incidents table
+        log::info!("Populating R-tree spatial index...");
+        sqlite
+            .exec_raw(
+                "INSERT INTO incidents_rtree (id, min_lng, max_lng, min_lat, max_lat)
+                 SELECT id, longitude, longitude, latitude, latitude FROM incidents",
+            )
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to populate R-tree: {e}")))?;
+
+        // Create date index for feature queries
+        log::info!("Creating indexes...");
+        sqlite
+            .exec_raw("CREATE INDEX idx_incidents_occurred_at ON incidents(occurred_at DESC)")
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
+        sqlite
+            .exec_raw("CREATE INDEX idx_incidents_source_id ON incidents(source_id)")
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
+        sqlite
+            .exec_raw("CREATE INDEX idx_incidents_state_fips ON incidents(state_fips)")
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
+        sqlite
+            .exec_raw("CREATE INDEX idx_incidents_county_geoid ON incidents(county_geoid)")
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
+        sqlite
+            .exec_raw("CREATE INDEX idx_incidents_place_geoid ON incidents(place_geoid)")
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
+        sqlite
+            .exec_raw("CREATE INDEX idx_incidents_tract_geoid ON incidents(tract_geoid)")
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
+        sqlite
+            .exec_raw("CREATE INDEX idx_incidents_neighborhood_id ON incidents(neighborhood_id)")
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
+    }
+
+    sqlite
+        .exec_raw("ANALYZE")
         .await
-        .map_err(|e| format!("Failed to create incidents table: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to run ANALYZE: {e}")))?;
 
+    // Checkpoint the WAL to ensure all data is flushed to the main .db file.
+    // Without this, data lives only in the -wal sidecar file, which is not
+    // uploaded by CI artifact steps — causing "no such table" errors during merge.
     sqlite
-        .exec_raw(
-            "CREATE VIRTUAL TABLE incidents_rtree USING rtree(
-                id, min_lng, max_lng, min_lat, max_lat
-            )",
-        )
+        .exec_raw("PRAGMA wal_checkpoint(TRUNCATE)")
         .await
-        .map_err(|e| format!("Failed to create incidents_rtree: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to checkpoint WAL: {e}")))?;
+
+    drop(sqlite);
+    commit_atomic_output(&db_path, &output_path)?;
+
+    log::info!(
+        "Sidebar SQLite database generated: {} ({total_count} rows)",
+        output_path.display()
+    );
+    Ok(())
+}
+
+/// Opens the sidebar `SQLite` database at `path` in WAL mode.
+#[allow(clippy::future_not_send)]
+async fn open_sidebar_sqlite(
+    path: &Path,
+) -> Result<Box<dyn switchy_database::Database>, GenerateError> {
+    let sqlite = switchy_database_connection::init_sqlite_rusqlite(Some(path))
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to open sidebar SQLite: {e}")))?;
+
+    // WAL mode + generous busy timeout to avoid "database is locked" errors
+    // when the connection pool uses multiple connections.
+    sqlite
+        .exec_raw("PRAGMA journal_mode=WAL")
+        .await
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to set journal_mode: {e}")))?;
+    sqlite
+        .exec_raw("PRAGMA busy_timeout=5000")
+        .await
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to set busy_timeout: {e}")))?;
+    Ok(sqlite)
+}
+
+/// Moves the sensitive incidents of `source_ids` in the sidebar DB to
+/// their jittered locations (see [`jitter`]), returning how many moved.
+///
+/// Runs once either [`SidebarBackend`] has inserted the rows and before
+/// the R-tree is filled from them, so both backends jitter alike. Matches
+/// on the remapped `subcategory`, as [`SensitiveJitter::applies_to`] does
+/// for the tiles.
+///
+/// # Errors
+///
+/// Returns an error if the rows can't be read or updated.
+#[allow(clippy::future_not_send)]
+async fn jitter_sidebar_rows(
+    sqlite: &dyn switchy_database::Database,
+    jitter: &SensitiveJitter,
+    key: &JitterKey,
+    source_ids: &[&String],
+) -> Result<u64, GenerateError> {
+    use switchy_database::DatabaseValue;
+
+    let mut sensitive = Vec::new();
+    if !jitter.categories.is_empty() {
+        let categories: Vec<String> = jitter
+            .categories
+            .iter()
+            .map(|c| format!("'{}'", c.replace('\'', "''")))
+            .collect();
+        sensitive.push(format!("subcategory IN ({})", categories.join(", ")));
+    }
+    if jitter.domestic {
+        sensitive.push("domestic = 1".to_string());
+    }
+    let query = format!(
+        "SELECT id, source_incident_id, longitude, latitude FROM incidents
+         WHERE source_id = $1 AND id > $2 AND ({})
+         ORDER BY id
+         LIMIT {BATCH_SIZE}",
+        sensitive.join(" OR ")
+    );
+
+    let mut total: u64 = 0;
+    for &sid in source_ids {
+        let mut last_id: i64 = 0;
+        loop {
+            let rows = sqlite
+                .query_raw_params(
+                    &query,
+                    &[
+                        DatabaseValue::String(sid.clone()),
+                        DatabaseValue::Int64(last_id),
+                    ],
+                )
+                .await
+                .map_err(|e| {
+                    GenerateError::Sqlite(format!("Failed to query sensitive incidents: {e}"))
+                })?;
+            if rows.is_empty() {
+                break;
+            }
+
+            let tx = sqlite
+                .begin_transaction()
+                .await
+                .map_err(|e| GenerateError::Sqlite(format!("Failed to begin transaction: {e}")))?;
+            for row in &rows {
+                let value_err =
+                    |e| GenerateError::Sqlite(format!("Failed to read sensitive incident: {e}"));
+                let id: i64 = row.to_value("id").map_err(value_err)?;
+                let incident_id: Option<String> =
+                    row.to_value("source_incident_id").map_err(value_err)?;
+                let (lng, lat) = jitter.displace(
+                    key,
+                    sid,
+                    &incident_id.unwrap_or_default(),
+                    row.to_value("longitude").map_err(value_err)?,
+                    row.to_value("latitude").map_err(value_err)?,
+                );
+                tx.exec_raw_params(
+                    "UPDATE incidents SET longitude = $1, latitude = $2 WHERE id = $3",
+                    &[
+                        DatabaseValue::Real64(lng),
+                        DatabaseValue::Real64(lat),
+                        DatabaseValue::Int64(id),
+                    ],
+                )
+                .await
+                .map_err(|e| GenerateError::Sqlite(format!("Failed to jitter incident: {e}")))?;
+                last_id = id;
+            }
+            tx.commit()
+                .await
+                .map_err(|e| GenerateError::Sqlite(format!("Failed to commit transaction: {e}")))?;
+
+            #[allow(clippy::cast_possible_truncation)]
+            let moved = rows.len() as u64;
+            total += moved;
+        }
+    }
+
+    Ok(total)
+}
+
+/// Inserts the exportable incidents of `source_ids` into the sidebar DB,
+/// decoding each batch in Rust and inserting it row by row, and returns
+/// the number inserted ([`SidebarBackend::Rows`]).
+#[allow(clippy::too_many_lines, clippy::future_not_send)]
+async fn insert_sidebar_rows(
+    sqlite: &dyn switchy_database::Database,
+    args: &GenerateArgs,
+    source_ids: &[&String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    progress: &Arc<dyn ProgressCallback>,
+) -> Result<u64, GenerateError> {
+    use switchy_database::DatabaseValue;
 
-    // Populate from per-source DuckDB files
     let mut total_count: u64 = 0;
+    let filter = exportable_filter(args);
     let mut remaining = args.limit;
 
-    for sid in source_ids {
+    for &sid in source_ids {
         if remaining == Some(0) {
             break;
         }
@@ -1101,9 +5096,18 @@ async fn generate_sidebar_db(
 
         let source_count = {
             // We need to batch-insert into SQLite. Collect into a Vec per batch.
-            let conn = crime_map_database::source_db::open_by_id(sid)?;
-            let mut last_rowid: i64 = 0;
+            let shards = open_filtered_shards(sid, blocklist)?;
+            if shards.is_empty() {
+                continue;
+            }
+            let mut incidents = ShardedIncidents::new(shards);
+            let filter = blocklist.apply(&filter, sid);
             let mut source_total: u64 = 0;
+            // Incidents inserted, to pick their raw records afterwards.
+            let mut inserted_ids = BTreeSet::new();
+            // Adapted per batch to keep roughly constant bytes per
+            // transaction; reset per source since row widths differ.
+            let mut batch_size = BATCH_SIZE;
 
             loop {
                 if remaining == Some(0) {
@@ -1112,61 +5116,25 @@ async fn generate_sidebar_db(
 
                 #[allow(clippy::cast_sign_loss)]
                 let batch_limit = match remaining {
-                    Some(r) => i64::try_from(r.min(BATCH_SIZE as u64))?,
-                    None => BATCH_SIZE,
+                    Some(r) => i64::try_from(r.min(batch_size as u64)).unwrap_or(batch_size),
+                    None => batch_size,
                 };
 
                 // Collect batch from DuckDB in a separate scope so non-Send
                 // DuckDB types are dropped before any .await points.
                 let batch: Vec<IncidentRow> = {
-                    let mut stmt = conn.prepare(
-                        "SELECT rowid,
-                                source_incident_id, category, parent_category, severity,
-                                longitude, latitude, occurred_at::TEXT as occurred_at_text,
-                                description, block_address,
-                                city, state, arrest_made, domestic, location_type,
-                                census_tract_geoid, census_place_geoid, state_fips,
-                                county_geoid, neighborhood_id
-                         FROM incidents
-                         WHERE has_coordinates = TRUE
-                   AND longitude BETWEEN -180 AND 180
-                   AND latitude BETWEEN -90 AND 90
-                   AND rowid > ?
-                         ORDER BY rowid ASC
-                         LIMIT ?",
-                    )?;
-
-                    let mut rows = stmt.query(duckdb::params![last_rowid, batch_limit])?;
-
                     let mut batch: Vec<IncidentRow> = Vec::new();
-                    while let Some(row) = rows.next()? {
-                        let rowid: i64 = row.get(0)?;
-                        last_rowid = rowid;
-
-                        batch.push(IncidentRow {
-                            source_incident_id: row.get(1)?,
-                            source_id: sid.clone(),
-                            source_name: source_name.clone(),
-                            category: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
-                            parent_category: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
-                            severity: row.get::<_, Option<i16>>(4)?.unwrap_or(1).into(),
-                            longitude: row.get(5)?,
-                            latitude: row.get(6)?,
-                            occurred_at: row.get(7)?,
-                            description: row.get(8)?,
-                            block_address: row.get(9)?,
-                            city: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
-                            state: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
-                            arrest_made: row.get(12)?,
-                            domestic: row.get(13)?,
-                            location_type: row.get(14)?,
-                            census_tract_geoid: row.get(15)?,
-                            census_place_geoid: row.get(16)?,
-                            state_fips: row.get(17)?,
-                            county_geoid: row.get(18)?,
-                            neighborhood_id: row.get(19)?,
-                        });
-                    }
+                    incidents.next_page::<GenerateError, _>(
+                        INCIDENT_COLUMNS,
+                        &filter,
+                        batch_limit,
+                        |row| {
+                            let mut incident = incident_row(row, sid, &source_name)?;
+                            incident.apply_remap(remap);
+                            batch.push(incident);
+                            Ok(())
+                        },
+                    )?;
                     batch
                 };
 
@@ -1176,14 +5144,14 @@ async fn generate_sidebar_db(
 
                 #[allow(clippy::cast_possible_truncation)]
                 let batch_len = batch.len() as u64;
+                let batch_bytes: usize = batch.iter().map(IncidentRow::estimated_size).sum();
 
                 // Insert batch into SQLite within a transaction.
                 // begin_transaction() pins all operations to one pooled
                 // connection, avoiding "database is locked" errors.
-                let tx = sqlite
-                    .begin_transaction()
-                    .await
-                    .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+                let tx = sqlite.begin_transaction().await.map_err(|e| {
+                    GenerateError::Sqlite(format!("Failed to begin transaction: {e}"))
+                })?;
 
                 for incident in &batch {
                     let tract_geoid = incident.census_tract_geoid.clone();
@@ -1193,15 +5161,16 @@ async fn generate_sidebar_db(
                     let neighborhood_id = incident.neighborhood_id.clone();
 
                     let arrest_int = incident.arrest_made.map(i32::from);
+                    let domestic_int = incident.domestic.map(i32::from);
 
                     tx
                         .exec_raw_params(
                             "INSERT INTO incidents (source_id, source_name, source_incident_id,
                                 subcategory, category,
                                 severity, longitude, latitude, occurred_at, description,
-                                block_address, city, state, arrest_made, location_type,
+                                block_address, city, state, arrest_made, domestic, location_type,
                                 state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id)
-                             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)",
+                             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)",
                             &[
                                 DatabaseValue::String(incident.source_id.clone()),
                                 DatabaseValue::String(incident.source_name.clone()),
@@ -1217,6 +5186,7 @@ async fn generate_sidebar_db(
                                 DatabaseValue::String(incident.city.clone()),
                                 DatabaseValue::String(incident.state.clone()),
                                 arrest_int.map_or(DatabaseValue::Null, DatabaseValue::Int32),
+                                domestic_int.map_or(DatabaseValue::Null, DatabaseValue::Int32),
                                 incident.location_type.as_ref().map_or(DatabaseValue::Null, |s| DatabaseValue::String(s.clone())),
                                 state_fips.map_or(DatabaseValue::Null, DatabaseValue::String),
                                 county_geoid.map_or(DatabaseValue::Null, DatabaseValue::String),
@@ -1226,12 +5196,20 @@ async fn generate_sidebar_db(
                             ],
                         )
                         .await
-                        .map_err(|e| format!("Failed to insert incident: {e}"))?;
+                        .map_err(|e| GenerateError::Sqlite(format!("Failed to insert incident: {e}")))?;
                 }
 
-                tx.commit()
-                    .await
-                    .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+                tx.commit().await.map_err(|e| {
+                    GenerateError::Sqlite(format!("Failed to commit transaction: {e}"))
+                })?;
+
+                if args.raw_json {
+                    inserted_ids.extend(
+                        batch
+                            .into_iter()
+                            .map(|incident| incident.source_incident_id),
+                    );
+                }
 
                 source_total += batch_len;
                 if let Some(ref mut r) = remaining {
@@ -1240,11 +5218,55 @@ async fn generate_sidebar_db(
 
                 progress.inc(batch_len);
 
+                // A short batch means the source is exhausted. The keyset
+                // (`rowid > last_rowid`) doesn't depend on the batch size,
+                // so resizing between batches can't skip or repeat rows.
                 #[allow(clippy::cast_sign_loss)]
                 let batch_limit_u64 = batch_limit as u64;
                 if batch_len < batch_limit_u64 {
                     break;
                 }
+
+                let next = next_sidebar_batch_size(batch_size, batch_bytes);
+                if next != batch_size {
+                    log::info!(
+                        "{sid}: sidebar batch size {batch_size} -> {next} \
+                         (last batch ~{} KB)",
+                        batch_bytes / 1024
+                    );
+                    batch_size = next;
+                }
+            }
+
+            if args.raw_json {
+                let raw = source_raw_records(sid, &inserted_ids)?;
+                for chunk in raw.chunks(SIDEBAR_RAW_BATCH_SIZE) {
+                    let tx = sqlite.begin_transaction().await.map_err(|e| {
+                        GenerateError::Sqlite(format!("Failed to begin transaction: {e}"))
+                    })?;
+                    for (incident_id, raw_json) in chunk {
+                        tx.exec_raw_params(
+                            "INSERT INTO raw_records (source_id, source_incident_id, raw_json)
+                             VALUES ($1, $2, $3)",
+                            &[
+                                DatabaseValue::String(sid.clone()),
+                                DatabaseValue::String(incident_id.clone()),
+                                DatabaseValue::String(raw_json.clone()),
+                            ],
+                        )
+                        .await
+                        .map_err(|e| {
+                            GenerateError::Sqlite(format!("Failed to insert raw record: {e}"))
+                        })?;
+                    }
+                    tx.commit().await.map_err(|e| {
+                        GenerateError::Sqlite(format!("Failed to commit transaction: {e}"))
+                    })?;
+                }
+                log::info!(
+                    "Inserted {} raw records from source '{sid}' into sidebar DB",
+                    raw.len()
+                );
             }
 
             source_total
@@ -1254,63 +5276,257 @@ async fn generate_sidebar_db(
         log::info!("Inserted {source_count} rows from source '{sid}' into sidebar DB...");
     }
 
-    // Populate R-tree from incidents table
-    log::info!("Populating R-tree spatial index...");
+    Ok(total_count)
+}
+
+/// Inserts the exportable incidents of `source_ids` into the sidebar DB at
+/// `db_path`, whose schema must already exist, with one `DuckDB`
+/// `INSERT … SELECT` per source shard ([`SidebarBackend::Duckdb`]).
+/// Returns the number inserted.
+///
+/// `remap` only depends on an incident's source, categories, and
+/// severity, so it's applied by joining a table of each shard's distinct
+/// combinations, remapped in Rust, rather than per row.
+///
+/// # Errors
+///
+/// Returns an error if the `sqlite` extension can't be loaded or a
+/// source database can't be attached or queried.
+#[allow(clippy::too_many_lines)]
+fn insert_sidebar_rows_duckdb(
+    args: &GenerateArgs,
+    source_ids: &[&String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    db_path: &Path,
+    progress: &Arc<dyn ProgressCallback>,
+) -> Result<u64, GenerateError> {
+    let duck = duckdb::Connection::open_in_memory()?;
+    duck.execute_batch("INSTALL sqlite; LOAD sqlite;")
+        .map_err(|e| {
+            GenerateError::Sqlite(format!(
+                "Failed to load the DuckDB sqlite extension (use --sidebar-backend rows \
+                 offline): {e}"
+            ))
+        })?;
+    duck.execute_batch(&format!(
+        "ATTACH '{}' AS sidebar (TYPE SQLITE);
+         CREATE TEMP TABLE sidebar_remap (
+             category VARCHAR,
+             parent_category VARCHAR,
+             severity SMALLINT,
+             to_category VARCHAR,
+             to_parent_category VARCHAR,
+             to_severity INTEGER
+         );",
+        db_path.to_string_lossy()
+    ))?;
+
+    let filter = exportable_filter(args);
+    let mut remaining = args.limit;
+    let mut total_count: u64 = 0;
+
+    for &sid in source_ids {
+        if remaining == Some(0) {
+            break;
+        }
+        let source_name = resolve_source_name(sid);
+        let filter = blocklist.apply(&filter, sid);
+        blocklist.load_into(&duck, sid)?;
+        let mut source_count: u64 = 0;
+
+        for path in crime_map_database::source_db::shard_paths(sid) {
+            if remaining == Some(0) {
+                break;
+            }
+            // Logs and skips shards without an incidents table.
+            if open_source_shard(sid, &path)?.is_none() {
+                continue;
+            }
+            duck.execute_batch(&format!(
+                "ATTACH '{}' AS shard (READ_ONLY)",
+                path.to_string_lossy()
+            ))?;
+
+            duck.execute_batch("DELETE FROM sidebar_remap")?;
+            let combos: Vec<(Option<String>, Option<String>, Option<i16>)> = {
+                let mut stmt = duck.prepare(
+                    "SELECT DISTINCT category, parent_category, severity FROM shard.incidents",
+                )?;
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                    .collect::<Result<_, _>>()?
+            };
+            {
+                let mut insert =
+                    duck.prepare("INSERT INTO sidebar_remap VALUES (?, ?, ?, ?, ?, ?)")?;
+                for (category, parent, severity) in combos {
+                    // Same defaults as `incident_row`.
+                    let mut to_category = category.clone().unwrap_or_default();
+                    let mut to_parent = parent.clone().unwrap_or_default();
+                    remap.apply(sid, &mut to_category, &mut to_parent);
+                    let to_severity =
+                        remap.severity(sid, &to_category, &to_parent, severity.unwrap_or(1).into());
+                    insert.execute(duckdb::params![
+                        category,
+                        parent,
+                        severity,
+                        to_category,
+                        to_parent,
+                        to_severity
+                    ])?;
+                }
+            }
+
+            let limit = remaining.map_or_else(String::new, |r| format!("LIMIT {r}"));
+            let inserted = duck.execute(
+                &format!(
+                    "INSERT INTO sidebar.incidents (
+                         source_id, source_name, source_incident_id,
+                         subcategory, category, severity,
+                         longitude, latitude, occurred_at,
+                         description, block_address, city, state,
+                         arrest_made, domestic, location_type,
+                         state_fips, county_geoid, place_geoid,
+                         tract_geoid, neighborhood_id
+                     )
+                     SELECT
+                         ?, ?, i.source_incident_id,
+                         m.to_category, m.to_parent_category, m.to_severity,
+                         i.longitude, i.latitude, i.occurred_at::TEXT,
+                         i.description, i.block_address,
+                         COALESCE(i.city, ''), COALESCE(i.state, ''),
+                         i.arrest_made::INTEGER, i.domestic::INTEGER, i.location_type,
+                         i.state_fips, i.county_geoid, i.census_place_geoid,
+                         i.census_tract_geoid, i.neighborhood_id
+                     FROM (
+                         SELECT *, rowid AS source_rowid FROM shard.incidents
+                         WHERE {filter}
+                         ORDER BY rowid
+                         {limit}
+                     ) i
+                     JOIN sidebar_remap m
+                       ON m.category IS NOT DISTINCT FROM i.category
+                      AND m.parent_category IS NOT DISTINCT FROM i.parent_category
+                      AND m.severity IS NOT DISTINCT FROM i.severity
+                     ORDER BY i.source_rowid"
+                ),
+                duckdb::params![sid, source_name],
+            )?;
+            #[allow(clippy::cast_possible_truncation)]
+            let inserted = inserted as u64;
+
+            if args.raw_json {
+                duck.execute(
+                    "INSERT INTO sidebar.raw_records (source_id, source_incident_id, raw_json)
+                     SELECT ?, r.source_incident_id, r.raw_json
+                     FROM shard.raw_records r
+                     WHERE r.source_incident_id IN (
+                         SELECT source_incident_id FROM sidebar.incidents WHERE source_id = ?
+                     )
+                     AND r.source_incident_id NOT IN (
+                         SELECT source_incident_id FROM sidebar.raw_records WHERE source_id = ?
+                     )",
+                    duckdb::params![sid, sid, sid],
+                )?;
+            }
+
+            duck.execute_batch("DETACH shard")?;
+            source_count += inserted;
+            if let Some(ref mut r) = remaining {
+                *r = r.saturating_sub(inserted);
+            }
+            progress.inc(inserted);
+        }
+
+        total_count += source_count;
+        log::info!("Inserted {source_count} rows from source '{sid}' into sidebar DB...");
+    }
+
+    duck.execute_batch("DETACH sidebar")?;
+    Ok(total_count)
+}
+
+/// Schema of the sidebar DB's optional `raw_records` table (see
+/// [`GenerateArgs::raw_json`]).
+pub(crate) const RAW_RECORDS_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS raw_records (
+    source_id TEXT NOT NULL,
+    source_incident_id TEXT NOT NULL,
+    raw_json TEXT NOT NULL,
+    PRIMARY KEY (source_id, source_incident_id)
+)";
+
+/// Returns the raw record JSON that `ingest --keep-raw` kept for
+/// `source_id`'s incidents in `ids`, as `(source_incident_id, raw_json)`.
+fn source_raw_records(
+    source_id: &str,
+    ids: &BTreeSet<String>,
+) -> Result<Vec<(String, String)>, GenerateError> {
+    let mut records = Vec::new();
+    for shard in open_source_shards(source_id)? {
+        let mut stmt = shard.prepare("SELECT source_incident_id, raw_json FROM raw_records")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        for row in rows {
+            let (id, json): (String, String) = row?;
+            if ids.contains(&id) {
+                records.push((id, json));
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Creates the sidebar DB's `incidents` table and its R-tree, plus the
+/// `raw_records` table if `raw_json` is set.
+async fn create_sidebar_schema(
+    sqlite: &dyn switchy_database::Database,
+    raw_json: bool,
+) -> Result<(), GenerateError> {
     sqlite
         .exec_raw(
-            "INSERT INTO incidents_rtree (id, min_lng, max_lng, min_lat, max_lat)
-             SELECT id, longitude, longitude, latitude, latitude FROM incidents",
+            "CREATE TABLE incidents (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_id TEXT NOT NULL,
+                source_name TEXT NOT NULL,
+                source_incident_id TEXT,
+                subcategory TEXT NOT NULL,
+                category TEXT NOT NULL,
+                severity INTEGER NOT NULL,
+                longitude REAL NOT NULL,
+                latitude REAL NOT NULL,
+                occurred_at TEXT,
+                description TEXT,
+                block_address TEXT,
+                city TEXT,
+                state TEXT,
+                arrest_made INTEGER,
+                domestic INTEGER,
+                location_type TEXT,
+                state_fips TEXT,
+                county_geoid TEXT,
+                place_geoid TEXT,
+                tract_geoid TEXT,
+                neighborhood_id TEXT
+            )",
         )
         .await
-        .map_err(|e| format!("Failed to populate R-tree: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create incidents table: {e}")))?;
 
-    // Create date index for feature queries
-    log::info!("Creating indexes...");
-    sqlite
-        .exec_raw("CREATE INDEX idx_incidents_occurred_at ON incidents(occurred_at DESC)")
-        .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
     sqlite
-        .exec_raw("CREATE INDEX idx_incidents_source_id ON incidents(source_id)")
-        .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
-    sqlite
-        .exec_raw("CREATE INDEX idx_incidents_state_fips ON incidents(state_fips)")
-        .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
-    sqlite
-        .exec_raw("CREATE INDEX idx_incidents_county_geoid ON incidents(county_geoid)")
-        .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
-    sqlite
-        .exec_raw("CREATE INDEX idx_incidents_place_geoid ON incidents(place_geoid)")
-        .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
-    sqlite
-        .exec_raw("CREATE INDEX idx_incidents_tract_geoid ON incidents(tract_geoid)")
-        .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
-    sqlite
-        .exec_raw("CREATE INDEX idx_incidents_neighborhood_id ON incidents(neighborhood_id)")
-        .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
-    sqlite
-        .exec_raw("ANALYZE")
+        .exec_raw(
+            "CREATE VIRTUAL TABLE incidents_rtree USING rtree(
+                id, min_lng, max_lng, min_lat, max_lat
+            )",
+        )
         .await
-        .map_err(|e| format!("Failed to run ANALYZE: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create incidents_rtree: {e}")))?;
 
-    // Checkpoint the WAL to ensure all data is flushed to the main .db file.
-    // Without this, data lives only in the -wal sidecar file, which is not
-    // uploaded by CI artifact steps — causing "no such table" errors during merge.
-    sqlite
-        .exec_raw("PRAGMA wal_checkpoint(TRUNCATE)")
-        .await
-        .map_err(|e| format!("Failed to checkpoint WAL: {e}"))?;
+    if raw_json {
+        sqlite
+            .exec_raw(RAW_RECORDS_SCHEMA)
+            .await
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to create raw_records: {e}")))?;
+    }
 
-    log::info!(
-        "Sidebar SQLite database generated: {} ({total_count} rows)",
-        db_path.display()
-    );
     Ok(())
 }
 
@@ -1324,10 +5540,25 @@ async fn generate_sidebar_db(
 /// Creates `counts.duckdb` with:
 /// - A raw `incidents` table populated from source `DuckDB` files
 /// - A `count_summary` table aggregated by spatial cell, subcategory, severity,
-///   arrest status, and day
+///   arrest status, domestic status, and day
+/// - If `args.count_monthly` is set, a `count_summary_monthly` table with the
+///   same columns but bucketed by `month` (`YYYY-MM`) instead of `day`
+/// - If `args.count_rolling` is set, a `count_summary_rolling` table of
+///   trailing-window counts (see [`create_count_rolling`])
+/// - If `args.count_timeseries` is set, a `count_timeseries` table of
+///   monthly counts per source and category (see
+///   [`create_count_timeseries`])
+/// - If `args.time_dimensions` is set, `dow` and `hour` grouping columns in
+///   `count_summary` (see [`time_dimensions`])
+/// - A `boundary_counts` table rolled up from `count_summary` (see
+///   [`create_boundary_counts`])
+/// - With a [taxonomy](taxonomy), one rollup column per level on
+///   `count_summary` and `count_summary_monthly` (see
+///   [`add_taxonomy_columns`])
 ///
 /// At runtime, count queries become a simple `SUM(cnt)` over the summary table
 /// filtered by cell coordinates, completing in under 10ms for any bounding box.
+/// Cell math lives in [`crime_map_database::counts`], shared with consumers.
 ///
 /// # Errors
 ///
@@ -1336,58 +5567,31 @@ async fn generate_sidebar_db(
 fn generate_count_db(
     args: &GenerateArgs,
     source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
     dir: &Path,
     progress: &Arc<dyn ProgressCallback>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let db_path = dir.join("counts.duckdb");
-
-    // Remove any existing file so we start fresh
-    if db_path.exists() {
-        std::fs::remove_file(&db_path)?;
-    }
-    // DuckDB may also create a .wal file
-    let wal_path = dir.join("counts.duckdb.wal");
-    if wal_path.exists() {
-        std::fs::remove_file(&wal_path)?;
-    }
+) -> Result<(), GenerateError> {
+    let output_path = dir.join(paths::COUNTS_DB_FILE);
+    let db_path = begin_atomic_output(&output_path)?;
 
     log::info!("Creating DuckDB count database...");
 
-    {
-        let duck = open_output_duckdb(&db_path)?;
+    create_incidents_staging(&open_output_duckdb(&db_path)?)?;
 
-        // Create raw incidents table for aggregation
-        duck.execute_batch(
-            "CREATE TABLE incidents (
-                source_id VARCHAR NOT NULL,
-                subcategory VARCHAR NOT NULL,
-                severity INTEGER NOT NULL,
-                longitude DOUBLE NOT NULL,
-                latitude DOUBLE NOT NULL,
-                occurred_at VARCHAR,
-                arrest_made INTEGER,
-                category VARCHAR NOT NULL,
-                state_fips VARCHAR,
-                county_geoid VARCHAR,
-                place_geoid VARCHAR,
-                tract_geoid VARCHAR,
-                neighborhood_id VARCHAR
-            )",
-        )?;
-    }
-
-    let total_count = populate_duckdb_incidents(args, source_ids, &db_path, progress)?;
+    let total_count =
+        populate_duckdb_incidents(args, source_ids, remap, blocklist, &db_path, progress)?;
 
     // Reopen for aggregation
     let duck = open_output_duckdb(&db_path)?;
 
     // Create pre-aggregated count summary table
     log::info!("Creating count_summary aggregation table...");
-    duck.execute_batch(
+    duck.execute_batch(&format!(
         "CREATE TABLE count_summary AS
          SELECT
-             CAST(FLOOR(longitude * 1000) AS INTEGER) AS cell_lng,
-             CAST(FLOOR(latitude * 1000) AS INTEGER) AS cell_lat,
+             {cell_lng} AS cell_lng,
+             {cell_lat} AS cell_lat,
              source_id,
              subcategory,
              category,
@@ -1395,7 +5599,10 @@ fn generate_count_db(
              CASE WHEN arrest_made = 1 THEN 1
                   WHEN arrest_made = 0 THEN 0
                   ELSE 2 END AS arrest,
-             SUBSTRING(occurred_at, 1, 10) AS day,
+             CASE WHEN domestic = 1 THEN 1
+                  WHEN domestic = 0 THEN 0
+                  ELSE 2 END AS domestic,
+             SUBSTRING(occurred_at, 1, 10) AS day,{time_columns}
              state_fips,
              county_geoid,
              place_geoid,
@@ -1407,28 +5614,264 @@ fn generate_count_db(
          FROM incidents
          GROUP BY ALL
          ORDER BY cell_lng, cell_lat",
-    )?;
+        cell_lng = cell_sql("longitude", DEFAULT_CELL_PRECISION),
+        cell_lat = cell_sql("latitude", DEFAULT_CELL_PRECISION),
+        time_columns = if args.time_dimensions {
+            "\n             dow,\n             hour,"
+        } else {
+            ""
+        },
+    ))?;
+
+    // Drop the raw incidents table to save space, unless it's kept so
+    // other outputs can be re-derived from this database
+    if args.keep_incidents_table {
+        log::info!("Keeping raw incidents table in count DB");
+    } else {
+        duck.execute_batch("DROP TABLE incidents")?;
+    }
+
+    // Roll the daily summary up to months. Every column other than `day`
+    // and the additive measures is a grouping key, so re-aggregating the
+    // summary gives the same result as a second pass over the raw rows.
+    // `dow`/`hour` aren't selected, so they're rolled up too.
+    if args.count_monthly {
+        log::info!("Creating count_summary_monthly aggregation table...");
+        duck.execute_batch(
+            "CREATE TABLE count_summary_monthly AS
+             SELECT
+                 cell_lng,
+                 cell_lat,
+                 source_id,
+                 subcategory,
+                 category,
+                 severity,
+                 arrest,
+                 domestic,
+                 SUBSTRING(day, 1, 7) AS month,
+                 state_fips,
+                 county_geoid,
+                 place_geoid,
+                 tract_geoid,
+                 neighborhood_id,
+                 SUM(cnt) AS cnt,
+                 SUM(sum_lng) AS sum_lng,
+                 SUM(sum_lat) AS sum_lat
+             FROM count_summary
+             GROUP BY ALL
+             ORDER BY cell_lng, cell_lat",
+        )?;
+    }
+
+    // Before the indexes, which block adding columns
+    if args.count_monthly {
+        add_taxonomy_columns(
+            &duck,
+            remap.taxonomy(),
+            &["count_summary", "count_summary_monthly"],
+        )?;
+    } else {
+        add_taxonomy_columns(&duck, remap.taxonomy(), &["count_summary"])?;
+    }
+
+    if args.count_rolling {
+        create_count_rolling(&duck)?;
+    }
+
+    if args.count_timeseries {
+        create_count_timeseries(&duck)?;
+    }
 
-    // Drop the raw incidents table to save space
-    duck.execute_batch("DROP TABLE incidents")?;
+    create_boundary_counts(&duck)?;
 
     // Create indexes on the summary table for fast filtering
     log::info!("Creating count_summary indexes...");
     duck.execute_batch(
         "CREATE INDEX idx_count_summary_cells ON count_summary (cell_lng, cell_lat)",
     )?;
+    if args.count_monthly {
+        duck.execute_batch(
+            "CREATE INDEX idx_count_summary_monthly_cells
+             ON count_summary_monthly (cell_lng, cell_lat)",
+        )?;
+    }
 
     // Reclaim disk space freed by DROP TABLE incidents above.
     log::info!("Running VACUUM on counts DuckDB...");
     duck.execute_batch("VACUUM")?;
 
+    drop(duck);
+    commit_atomic_output(&db_path, &output_path)?;
+
     log::info!(
         "DuckDB count database generated: {} ({total_count} rows aggregated)",
-        db_path.display()
+        output_path.display()
     );
     Ok(())
 }
 
+/// Trailing windows, in days, materialized in `count_summary_rolling`.
+const ROLLING_WINDOW_DAYS: &[u32] = &[30, 90, 365];
+
+/// Creates the `count_summary_rolling` table from `count_summary` in
+/// `duck`.
+///
+/// Grain: one row per `(cell, source_id, category, subcategory,
+/// window_days)` with the incident count `cnt` over the `window_days` days
+/// ending at `as_of`, the latest `day` in `count_summary` (inclusive), for
+/// each of [`ROLLING_WINDOW_DAYS`]. Rows with an empty window are omitted.
+///
+/// The windows are relative to the newest data at generation time, not to
+/// the current date: they go stale as time passes without a regeneration,
+/// so they suit "recent activity" widgets rather than exact historical
+/// queries (use `count_summary` for those).
+///
+/// Rebuilt from the merged `count_summary` by [`merge::run`], since
+/// partitions can have different latest days.
+///
+/// # Errors
+///
+/// Returns an error if the aggregation or index creation fails.
+fn create_count_rolling(duck: &duckdb::Connection) -> Result<(), duckdb::Error> {
+    log::info!("Creating count_summary_rolling aggregation table...");
+
+    let windows = ROLLING_WINDOW_DAYS
+        .iter()
+        .map(|days| format!("({days})"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    duck.execute_batch(&format!(
+        "CREATE TABLE count_summary_rolling AS
+         WITH bounds AS (
+             SELECT MAX(day) AS as_of FROM count_summary WHERE day IS NOT NULL
+         )
+         SELECT
+             s.cell_lng,
+             s.cell_lat,
+             s.source_id,
+             s.category,
+             s.subcategory,
+             w.window_days,
+             b.as_of,
+             SUM(s.cnt)::BIGINT AS cnt
+         FROM count_summary s
+         CROSS JOIN bounds b
+         CROSS JOIN (VALUES {windows}) AS w(window_days)
+         WHERE s.day IS NOT NULL
+           AND s.day <= b.as_of
+           AND CAST(s.day AS DATE) > CAST(b.as_of AS DATE) - w.window_days
+         GROUP BY ALL
+         ORDER BY s.cell_lng, s.cell_lat;
+         CREATE INDEX idx_count_summary_rolling_cells
+             ON count_summary_rolling (window_days, cell_lng, cell_lat);"
+    ))
+}
+
+/// Creates the `count_timeseries` table from `count_summary` in `duck`.
+///
+/// Grain: one row per `(source_id, category, month)` with the incident
+/// count `cnt`, where `month` is `YYYY-MM`. Incidents without a date are
+/// left out. A few rows per source and month, so trend charts ("is crime
+/// going up?") query it at interactive speed without touching the cells.
+///
+/// It spans every month in the exported data: generation applies no date
+/// window, only the same source, bbox, sample, and blocklist filters as
+/// `count_summary`.
+///
+/// Rebuilt from the merged `count_summary` by [`merge::run`].
+///
+/// # Errors
+///
+/// Returns an error if the aggregation fails.
+fn create_count_timeseries(duck: &duckdb::Connection) -> Result<(), duckdb::Error> {
+    log::info!("Creating count_timeseries aggregation table...");
+    duck.execute_batch(
+        "CREATE TABLE count_timeseries AS
+         SELECT
+             source_id,
+             category,
+             SUBSTRING(day, 1, 7) AS month,
+             SUM(cnt)::BIGINT AS cnt
+         FROM count_summary
+         WHERE day IS NOT NULL
+         GROUP BY ALL
+         ORDER BY source_id, category, month",
+    )
+}
+
+/// Boundary levels rolled up into `boundary_counts`, as
+/// `(boundary_type, count_summary column)`.
+const BOUNDARY_COUNT_LEVELS: &[(&str, &str)] = &[
+    ("county", "county_geoid"),
+    ("place", "place_geoid"),
+    ("tract", "tract_geoid"),
+    ("neighborhood", "neighborhood_id"),
+];
+
+/// Creates the `boundary_counts` table from `count_summary` in `duck`.
+///
+/// Grain: one row per `(boundary_type, geoid, category, day)` with the
+/// incident count `cnt`, where `boundary_type` is one of
+/// [`BOUNDARY_COUNT_LEVELS`] and `geoid` is the county/place/tract GEOID or
+/// neighborhood ID. Incidents without attribution at a level are left out
+/// of that level. Indexed on `(boundary_type, geoid)` so per-area rankings
+/// don't scan incident rows.
+///
+/// Rebuilt from the merged `count_summary` by [`merge::run`], since the same
+/// boundary can appear in several partitions.
+///
+/// # Errors
+///
+/// Returns an error if the aggregation or index creation fails.
+fn create_boundary_counts(duck: &duckdb::Connection) -> Result<(), duckdb::Error> {
+    log::info!("Creating boundary_counts aggregation table...");
+
+    let levels = BOUNDARY_COUNT_LEVELS
+        .iter()
+        .map(|(boundary_type, column)| {
+            format!(
+                "SELECT '{boundary_type}' AS boundary_type, {column} AS geoid, category, day,
+                        SUM(cnt) AS cnt
+                 FROM count_summary
+                 WHERE {column} IS NOT NULL
+                 GROUP BY ALL"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+
+    duck.execute_batch(&format!(
+        "CREATE TABLE boundary_counts AS {levels} ORDER BY boundary_type, geoid, day;
+         CREATE INDEX idx_boundary_counts_geoid ON boundary_counts (boundary_type, geoid);"
+    ))
+}
+
+/// Creates the raw `incidents` table filled by
+/// [`populate_duckdb_incidents`] for aggregation.
+fn create_incidents_staging(duck: &duckdb::Connection) -> Result<(), duckdb::Error> {
+    duck.execute_batch(
+        "CREATE TABLE incidents (
+            source_id VARCHAR NOT NULL,
+            subcategory VARCHAR NOT NULL,
+            severity INTEGER NOT NULL,
+            longitude DOUBLE NOT NULL,
+            latitude DOUBLE NOT NULL,
+            occurred_at VARCHAR,
+            arrest_made INTEGER,
+            domestic INTEGER,
+            dow INTEGER,
+            hour INTEGER,
+            category VARCHAR NOT NULL,
+            state_fips VARCHAR,
+            county_geoid VARCHAR,
+            place_geoid VARCHAR,
+            tract_geoid VARCHAR,
+            neighborhood_id VARCHAR
+        )",
+    )
+}
+
 /// Populates the `DuckDB` incidents table from source `DuckDB` files.
 ///
 /// Iterates each source, reads incidents, computes boundary GEOIDs via
@@ -1443,10 +5886,13 @@ fn generate_count_db(
 fn populate_duckdb_incidents(
     args: &GenerateArgs,
     source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
     duck_path: &Path,
     progress: &Arc<dyn ProgressCallback>,
-) -> Result<u64, Box<dyn std::error::Error>> {
+) -> Result<u64, GenerateError> {
     let mut total_count: u64 = 0;
+    let filter = exportable_filter(args);
     let mut remaining = args.limit;
 
     for sid in source_ids {
@@ -1455,10 +5901,15 @@ fn populate_duckdb_incidents(
         }
 
         let source_name = resolve_source_name(sid);
+        let timezone = source_timezone(sid);
 
         // Iterate source DuckDB and insert into output DuckDB in batches
-        let conn = crime_map_database::source_db::open_by_id(sid)?;
-        let mut last_rowid: i64 = 0;
+        let shards = open_filtered_shards(sid, blocklist)?;
+        if shards.is_empty() {
+            continue;
+        }
+        let mut incidents = ShardedIncidents::new(shards);
+        let filter = blocklist.apply(&filter, sid);
         let mut source_total: u64 = 0;
 
         loop {
@@ -1468,59 +5919,24 @@ fn populate_duckdb_incidents(
 
             #[allow(clippy::cast_sign_loss)]
             let batch_limit = match remaining {
-                Some(r) => i64::try_from(r.min(BATCH_SIZE as u64))?,
+                Some(r) => i64::try_from(r.min(BATCH_SIZE as u64)).unwrap_or(BATCH_SIZE),
                 None => BATCH_SIZE,
             };
 
-            let mut stmt = conn.prepare(
-                "SELECT rowid,
-                        source_incident_id, category, parent_category, severity,
-                        longitude, latitude, occurred_at::TEXT as occurred_at_text,
-                        description, block_address,
-                        city, state, arrest_made, domestic, location_type,
-                        census_tract_geoid, census_place_geoid, state_fips,
-                        county_geoid, neighborhood_id
-                 FROM incidents
-                 WHERE has_coordinates = TRUE
-                   AND longitude BETWEEN -180 AND 180
-                   AND latitude BETWEEN -90 AND 90
-                   AND rowid > ?
-                 ORDER BY rowid ASC
-                 LIMIT ?",
-            )?;
-
-            let mut rows = stmt.query(duckdb::params![last_rowid, batch_limit])?;
-
             // Collect batch in memory
             let mut batch: Vec<IncidentRow> = Vec::new();
-            while let Some(row) = rows.next()? {
-                let rowid: i64 = row.get(0)?;
-                last_rowid = rowid;
-
-                batch.push(IncidentRow {
-                    source_incident_id: row.get(1)?,
-                    source_id: sid.clone(),
-                    source_name: source_name.clone(),
-                    category: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
-                    parent_category: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
-                    severity: row.get::<_, Option<i16>>(4)?.unwrap_or(1).into(),
-                    longitude: row.get(5)?,
-                    latitude: row.get(6)?,
-                    occurred_at: row.get(7)?,
-                    description: row.get(8)?,
-                    block_address: row.get(9)?,
-                    city: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
-                    state: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
-                    arrest_made: row.get(12)?,
-                    domestic: row.get(13)?,
-                    location_type: row.get(14)?,
-                    census_tract_geoid: row.get(15)?,
-                    census_place_geoid: row.get(16)?,
-                    state_fips: row.get(17)?,
-                    county_geoid: row.get(18)?,
-                    neighborhood_id: row.get(19)?,
-                });
-            }
+            incidents.next_page::<GenerateError, _>(
+                INCIDENT_COLUMNS,
+                &filter,
+                batch_limit,
+                |row| {
+                    let mut incident = incident_row(row, sid, &source_name)?;
+                    incident.apply_remap(remap);
+                    incident.localize(timezone);
+                    batch.push(incident);
+                    Ok(())
+                },
+            )?;
 
             if batch.is_empty() {
                 break;
@@ -1536,9 +5952,9 @@ fn populate_duckdb_incidents(
 
                 let mut insert_stmt = duck.prepare(
                     "INSERT INTO incidents (source_id, subcategory, severity, longitude, latitude,
-                        occurred_at, arrest_made, category,
+                        occurred_at, arrest_made, domestic, dow, hour, category,
                         state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id)
-                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
                 )?;
 
                 for incident in &batch {
@@ -1549,6 +5965,8 @@ fn populate_duckdb_incidents(
                     let neighborhood_id = incident.neighborhood_id.clone();
 
                     let arrest_int: Option<i32> = incident.arrest_made.map(i32::from);
+                    let domestic_int: Option<i32> = incident.domestic.map(i32::from);
+                    let (dow, hour) = time_dimensions(incident.occurred_at.as_deref());
 
                     insert_stmt.execute(duckdb::params![
                         incident.source_id,
@@ -1558,6 +5976,9 @@ fn populate_duckdb_incidents(
                         incident.latitude,
                         incident.occurred_at,
                         arrest_int,
+                        domestic_int,
+                        dow,
+                        hour,
                         incident.parent_category,
                         state_fips,
                         county_geoid,
@@ -1608,10 +6029,14 @@ const H3_BATCH_SIZE: i64 = 50_000;
 /// Generates a `DuckDB` database with pre-aggregated H3 hexbin counts.
 ///
 /// Creates `h3.duckdb` with an `h3_counts` table indexed by H3 cell,
-/// resolution, category, severity, arrest status, and day. Uses a staging
+/// resolution, category, severity, arrest and domestic status, and day
+/// (plus `dow`/`hour` if `args.time_dimensions` is set). Uses a staging
 /// table approach for performance: incidents are bulk-inserted with
 /// pre-computed H3 cell indices as extra columns, then a single SQL
-/// aggregation produces the final table.
+/// aggregation produces the final table. Incidents come from the source
+/// databases, or from an existing raw `incidents` table when
+/// `args.h3_from` is set. Cells below `args.h3_min_cell_count` are then
+/// suppressed (see [`suppress_sparse_h3_cells`]).
 ///
 /// # Errors
 ///
@@ -1621,20 +6046,19 @@ const H3_BATCH_SIZE: i64 = 50_000;
 fn generate_h3_db(
     args: &GenerateArgs,
     source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
     dir: &Path,
     progress: &Arc<dyn ProgressCallback>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    use h3o::{LatLng, Resolution};
-
-    let db_path = dir.join("h3.duckdb");
-
-    // Remove any existing file so we start fresh
-    if db_path.exists() {
-        std::fs::remove_file(&db_path)?;
-    }
-    let wal_path = dir.join("h3.duckdb.wal");
-    if wal_path.exists() {
-        std::fs::remove_file(&wal_path)?;
+) -> Result<(), GenerateError> {
+    use h3o::Resolution;
+
+    let output_path = dir.join(paths::H3_DB_FILE);
+    let db_path = begin_atomic_output(&output_path)?;
+    // Stats of a previous run would be stale (or unwanted) after this run
+    let stats_path = dir.join(paths::H3_STATS_FILE);
+    if stats_path.exists() {
+        std::fs::remove_file(&stats_path)?;
     }
 
     log::info!("Creating H3 hexbin DuckDB database...");
@@ -1656,7 +6080,10 @@ fn generate_h3_db(
                 subcategory VARCHAR NOT NULL,
                 severity TINYINT NOT NULL,
                 arrest TINYINT NOT NULL,
+                domestic TINYINT NOT NULL,
                 day VARCHAR NOT NULL,
+                dow TINYINT,
+                hour TINYINT,
                 lng DOUBLE NOT NULL,
                 lat DOUBLE NOT NULL,
                 h3_r4 BIGINT NOT NULL,
@@ -1674,8 +6101,232 @@ fn generate_h3_db(
         )?;
     }
 
-    // Populate staging table from per-source DuckDB files
+    let total_count = match &args.h3_from {
+        Some(from) => load_h3_staging_from_db(args, from, &db_path, &resolutions, progress)?,
+        None => load_h3_staging_from_sources(
+            args,
+            source_ids,
+            remap,
+            blocklist,
+            &db_path,
+            &resolutions,
+            progress,
+        )?,
+    };
+
+    // Aggregate staging table into final h3_counts using UNION ALL
+    // across the 6 resolution columns.
+    let duck = open_output_duckdb(&db_path)?;
+
+    log::info!("Aggregating H3 counts from staging table...");
+    let time_columns = if args.time_dimensions {
+        ", dow, hour"
+    } else {
+        ""
+    };
+    duck.execute_batch(&format!(
+        "CREATE TABLE h3_counts AS
+         WITH unpivoted AS (
+             SELECT h3_r4 AS h3_index, 4 AS resolution, source_id, category, subcategory, severity, arrest, domestic, day{time_columns}, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
+             UNION ALL
+             SELECT h3_r5, 5, source_id, category, subcategory, severity, arrest, domestic, day{time_columns}, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
+             UNION ALL
+             SELECT h3_r6, 6, source_id, category, subcategory, severity, arrest, domestic, day{time_columns}, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
+             UNION ALL
+             SELECT h3_r7, 7, source_id, category, subcategory, severity, arrest, domestic, day{time_columns}, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
+             UNION ALL
+             SELECT h3_r8, 8, source_id, category, subcategory, severity, arrest, domestic, day{time_columns}, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
+             UNION ALL
+             SELECT h3_r9, 9, source_id, category, subcategory, severity, arrest, domestic, day{time_columns}, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
+         )
+         SELECT
+             CAST(h3_index AS UBIGINT) AS h3_index,
+             CAST(resolution AS TINYINT) AS resolution,
+             source_id,
+             category,
+             subcategory,
+             CAST(severity AS TINYINT) AS severity,
+             CAST(arrest AS TINYINT) AS arrest,
+             CAST(domestic AS TINYINT) AS domestic,
+             day{time_columns},
+             state_fips,
+             county_geoid,
+             place_geoid,
+             tract_geoid,
+             neighborhood_id,
+             CAST(COUNT(*) AS INTEGER) AS cnt,
+             SUM(lng) AS sum_lng,
+             SUM(lat) AS sum_lat
+         FROM unpivoted
+         GROUP BY h3_index, resolution, source_id, category, subcategory, severity, arrest, domestic, day{time_columns}, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id
+         ORDER BY resolution, h3_index",
+    ))?;
+
+    // Drop staging table to reclaim space
+    duck.execute_batch("DROP TABLE h3_staging")?;
+
+    // Before the boundaries below, so only surviving cells get vertices.
+    let suppressed = suppress_sparse_h3_cells(&duck, &args.h3_min_cell_count)?;
+
+    // Create indexes for fast viewport queries
+    log::info!("Creating H3 indexes...");
+    duck.execute_batch("CREATE INDEX idx_h3_counts_res_cell ON h3_counts (resolution, h3_index)")?;
+
+    let mut stats = h3_stats(&duck)?;
+    for s in &mut stats {
+        if let Some(&(cells, incidents)) = suppressed.get(&s.resolution) {
+            s.suppressed_cells = cells;
+            s.suppressed_incidents = incidents;
+        }
+    }
+    log_h3_stats(&stats);
+
+    // Pre-compute hex boundary vertices for every distinct H3 cell.
+    log::info!("Pre-computing H3 boundary vertices...");
+    duck.execute_batch(
+        "CREATE TABLE h3_boundaries (
+             h3_index UBIGINT PRIMARY KEY,
+             v0_lng DOUBLE NOT NULL, v0_lat DOUBLE NOT NULL,
+             v1_lng DOUBLE NOT NULL, v1_lat DOUBLE NOT NULL,
+             v2_lng DOUBLE NOT NULL, v2_lat DOUBLE NOT NULL,
+             v3_lng DOUBLE NOT NULL, v3_lat DOUBLE NOT NULL,
+             v4_lng DOUBLE NOT NULL, v4_lat DOUBLE NOT NULL,
+             v5_lng DOUBLE NOT NULL, v5_lat DOUBLE NOT NULL,
+             exact_boundary VARCHAR
+         )",
+    )?;
+
+    {
+        let mut distinct_stmt =
+            duck.prepare("SELECT DISTINCT CAST(h3_index AS BIGINT) FROM h3_counts")?;
+        let cells = distinct_stmt
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Vertices are computed in parallel, a chunk at a time so only one
+        // chunk of rows is held in memory, then inserted serially. They run
+        // on the global pool unless `h3_threads` asks for a dedicated one.
+        let pool = args
+            .h3_threads
+            .map(|threads| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads.get())
+                    .build()
+                    .map_err(std::io::Error::other)
+            })
+            .transpose()?;
+        log::info!(
+            "Computing vertices of {} H3 cells ({} threads)...",
+            cells.len(),
+            pool.as_ref().map_or_else(
+                rayon::current_num_threads,
+                rayon::ThreadPool::current_num_threads
+            )
+        );
+
+        let mut boundary_stmt = duck.prepare(
+            "INSERT INTO h3_boundaries VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )?;
+
+        let mut boundary_count: u64 = 0;
+        let mut pentagon_count: u64 = 0;
+        let mut irregular_count: u64 = 0;
+        for chunk in cells.chunks(H3_BOUNDARY_CHUNK_SIZE) {
+            let boundaries = match &pool {
+                Some(pool) => pool.install(|| h3_cell_boundaries(chunk)),
+                None => h3_cell_boundaries(chunk),
+            };
+            for (h3_raw, boundary) in boundaries {
+                if boundary.is_pentagon {
+                    pentagon_count += 1;
+                }
+                if boundary.exact.is_some() {
+                    irregular_count += 1;
+                }
+
+                let [
+                    (v0_lng, v0_lat),
+                    (v1_lng, v1_lat),
+                    (v2_lng, v2_lat),
+                    (v3_lng, v3_lat),
+                    (v4_lng, v4_lat),
+                    (v5_lng, v5_lat),
+                ] = boundary.padded;
+
+                boundary_stmt.execute(duckdb::params![
+                    h3_raw,
+                    v0_lng,
+                    v0_lat,
+                    v1_lng,
+                    v1_lat,
+                    v2_lng,
+                    v2_lat,
+                    v3_lng,
+                    v3_lat,
+                    v4_lng,
+                    v4_lat,
+                    v5_lng,
+                    v5_lat,
+                    boundary.exact,
+                ])?;
+
+                boundary_count += 1;
+            }
+        }
+
+        log::info!("Pre-computed boundaries for {boundary_count} distinct H3 cells");
+        if pentagon_count > 0 {
+            log::warn!(
+                "{pentagon_count} H3 cell(s) are pentagons; their v0..v5 columns are padded \
+                 with a repeated vertex (use exact_boundary for the true shape)"
+            );
+        }
+        if irregular_count > pentagon_count {
+            log::info!(
+                "{} H3 cell(s) have distortion vertices (not exactly 6); \
+                 full rings stored in exact_boundary",
+                irregular_count - pentagon_count
+            );
+        }
+    }
+
+    // Reclaim disk space freed by DROP TABLE h3_staging above.
+    log::info!("Running VACUUM on H3 DuckDB...");
+    duck.execute_batch("VACUUM")?;
+
+    drop(duck);
+    commit_atomic_output(&db_path, &output_path)?;
+
+    if args.h3_stats {
+        write_h3_stats(&stats, dir)?;
+    }
+
+    log::info!(
+        "H3 DuckDB database generated: {} ({total_count} incidents indexed)",
+        output_path.display()
+    );
+    Ok(())
+}
+
+/// Populates the H3 staging table from the per-source `DuckDB` files.
+///
+/// Returns the total number of incidents read.
+///
+/// # Errors
+///
+/// Returns an error if any source or output database operation fails.
+#[allow(clippy::too_many_lines)]
+fn load_h3_staging_from_sources(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    db_path: &Path,
+    resolutions: &[h3o::Resolution],
+    progress: &Arc<dyn ProgressCallback>,
+) -> Result<u64, GenerateError> {
     let mut total_count: u64 = 0;
+    let filter = exportable_filter(args);
     let mut remaining = args.limit;
 
     for sid in source_ids {
@@ -1684,9 +6335,14 @@ fn generate_h3_db(
         }
 
         let source_name = resolve_source_name(sid);
+        let timezone = source_timezone(sid);
 
-        let conn = crime_map_database::source_db::open_by_id(sid)?;
-        let mut last_rowid: i64 = 0;
+        let shards = open_filtered_shards(sid, blocklist)?;
+        if shards.is_empty() {
+            continue;
+        }
+        let mut incidents = ShardedIncidents::new(shards);
+        let filter = blocklist.apply(&filter, sid);
         let mut source_total: u64 = 0;
 
         loop {
@@ -1696,278 +6352,512 @@ fn generate_h3_db(
 
             #[allow(clippy::cast_sign_loss)]
             let batch_limit = match remaining {
-                Some(r) => i64::try_from(r.min(H3_BATCH_SIZE as u64))?,
+                Some(r) => i64::try_from(r.min(H3_BATCH_SIZE as u64)).unwrap_or(H3_BATCH_SIZE),
                 None => H3_BATCH_SIZE,
             };
 
-            let mut stmt = conn.prepare(
-                "SELECT rowid,
-                        source_incident_id, category, parent_category, severity,
-                        longitude, latitude, occurred_at::TEXT as occurred_at_text,
-                        description, block_address,
-                        city, state, arrest_made, domestic, location_type,
-                        census_tract_geoid, census_place_geoid, state_fips,
-                        county_geoid, neighborhood_id
-                 FROM incidents
-                 WHERE has_coordinates = TRUE
-                   AND longitude BETWEEN -180 AND 180
-                   AND latitude BETWEEN -90 AND 90
-                   AND rowid > ?
-                 ORDER BY rowid ASC
-                 LIMIT ?",
+            let mut batch: Vec<IncidentRow> = Vec::new();
+            incidents.next_page::<GenerateError, _>(
+                INCIDENT_COLUMNS,
+                &filter,
+                batch_limit,
+                |row| {
+                    let mut incident = incident_row(row, sid, &source_name)?;
+                    incident.apply_remap(remap);
+                    incident.localize(timezone);
+                    batch.push(incident);
+                    Ok(())
+                },
             )?;
 
-            let mut rows = stmt.query(duckdb::params![last_rowid, batch_limit])?;
+            if batch.is_empty() {
+                break;
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            let batch_len = batch.len() as u64;
+
+            insert_h3_staging(db_path, resolutions, &batch)?;
+
+            source_total += batch_len;
+            if let Some(ref mut r) = remaining {
+                *r = r.saturating_sub(batch_len);
+            }
+
+            progress.inc(batch_len);
+
+            #[allow(clippy::cast_sign_loss)]
+            let batch_limit_u64 = batch_limit as u64;
+            if batch_len < batch_limit_u64 {
+                break;
+            }
+        }
+
+        total_count += source_total;
+        log::info!("Loaded {source_total} incidents from source '{sid}' into H3 staging table...");
+    }
+
+    Ok(total_count)
+}
+
+/// Columns of the raw `incidents` table (see [`create_incidents_staging`])
+/// that [`load_h3_staging_from_db`] reads.
+const H3_INPUT_COLUMNS: &[&str] = &[
+    "source_id",
+    "subcategory",
+    "category",
+    "severity",
+    "longitude",
+    "latitude",
+    "occurred_at",
+    "arrest_made",
+    "domestic",
+    "state_fips",
+    "county_geoid",
+    "place_geoid",
+    "tract_geoid",
+    "neighborhood_id",
+];
+
+/// Populates the H3 staging table from the raw `incidents` table of an
+/// existing `DuckDB` (see [`GenerateArgs::h3_from`]) instead of the
+/// source databases.
+///
+/// The incidents were filtered, remapped, and localized when that
+/// database was generated, so only `args.limit` is applied here.
+///
+/// Returns the total number of incidents read.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::InvalidArgs`] if `from` has no compatible
+/// `incidents` table, or an error if a database operation fails.
+#[allow(clippy::too_many_lines)]
+fn load_h3_staging_from_db(
+    args: &GenerateArgs,
+    from: &Path,
+    db_path: &Path,
+    resolutions: &[h3o::Resolution],
+    progress: &Arc<dyn ProgressCallback>,
+) -> Result<u64, GenerateError> {
+    log::info!(
+        "Reading incidents for H3 staging from {}...",
+        from.display()
+    );
+
+    let conn = duckdb::Connection::open_with_flags(
+        from,
+        duckdb::Config::default().access_mode(duckdb::AccessMode::ReadOnly)?,
+    )?;
+    let columns: i64 = if crime_map_database::source_db::has_incidents_table(&conn)? {
+        conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM duckdb_columns()
+                 WHERE schema_name = 'main' AND table_name = 'incidents'
+                   AND column_name IN ({})",
+                H3_INPUT_COLUMNS
+                    .iter()
+                    .map(|c| format!("'{c}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            [],
+            |row| row.get(0),
+        )?
+    } else {
+        0
+    };
+    if usize::try_from(columns).ok() != Some(H3_INPUT_COLUMNS.len()) {
+        return Err(GenerateError::InvalidArgs(format!(
+            "{} has no raw incidents table; generate the count DB with \
+             --keep-incidents-table first",
+            from.display()
+        )));
+    }
+
+    let mut total_count: u64 = 0;
+    let mut remaining = args.limit;
+    let mut last_rowid: i64 = -1;
+
+    loop {
+        if remaining == Some(0) {
+            break;
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        let batch_limit = match remaining {
+            Some(r) => i64::try_from(r.min(H3_BATCH_SIZE as u64)).unwrap_or(H3_BATCH_SIZE),
+            None => H3_BATCH_SIZE,
+        };
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT rowid, {}
+             FROM incidents
+             WHERE rowid > ?
+             ORDER BY rowid ASC
+             LIMIT ?",
+            H3_INPUT_COLUMNS.join(", ")
+        ))?;
+        let mut rows = stmt.query(duckdb::params![last_rowid, batch_limit])?;
+
+        let mut batch: Vec<IncidentRow> = Vec::new();
+        while let Some(row) = rows.next()? {
+            last_rowid = row.get(0)?;
+            batch.push(IncidentRow {
+                source_incident_id: String::new(),
+                source_id: row.get(1)?,
+                source_name: String::new(),
+                category: row.get(2)?,
+                parent_category: row.get(3)?,
+                severity: row.get(4)?,
+                longitude: row.get(5)?,
+                latitude: row.get(6)?,
+                occurred_at: row.get(7)?,
+                description: None,
+                block_address: None,
+                city: String::new(),
+                state: String::new(),
+                arrest_made: row.get::<_, Option<i32>>(8)?.map(|v| v != 0),
+                domestic: row.get::<_, Option<i32>>(9)?.map(|v| v != 0),
+                location_type: None,
+                state_fips: row.get(10)?,
+                county_geoid: row.get(11)?,
+                census_place_geoid: row.get(12)?,
+                census_tract_geoid: row.get(13)?,
+                neighborhood_id: row.get(14)?,
+                coord_source: None,
+            });
+        }
 
-            let mut batch: Vec<IncidentRow> = Vec::new();
-            while let Some(row) = rows.next()? {
-                let rowid: i64 = row.get(0)?;
-                last_rowid = rowid;
-
-                batch.push(IncidentRow {
-                    source_incident_id: row.get(1)?,
-                    source_id: sid.clone(),
-                    source_name: source_name.clone(),
-                    category: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
-                    parent_category: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
-                    severity: row.get::<_, Option<i16>>(4)?.unwrap_or(1).into(),
-                    longitude: row.get(5)?,
-                    latitude: row.get(6)?,
-                    occurred_at: row.get(7)?,
-                    description: row.get(8)?,
-                    block_address: row.get(9)?,
-                    city: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
-                    state: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
-                    arrest_made: row.get(12)?,
-                    domestic: row.get(13)?,
-                    location_type: row.get(14)?,
-                    census_tract_geoid: row.get(15)?,
-                    census_place_geoid: row.get(16)?,
-                    state_fips: row.get(17)?,
-                    county_geoid: row.get(18)?,
-                    neighborhood_id: row.get(19)?,
-                });
-            }
+        if batch.is_empty() {
+            break;
+        }
 
-            if batch.is_empty() {
-                break;
-            }
+        #[allow(clippy::cast_possible_truncation)]
+        let batch_len = batch.len() as u64;
+        insert_h3_staging(db_path, resolutions, &batch)?;
 
-            #[allow(clippy::cast_possible_truncation)]
-            let batch_len = batch.len() as u64;
+        total_count += batch_len;
+        if let Some(ref mut r) = remaining {
+            *r = r.saturating_sub(batch_len);
+        }
 
-            // Insert into H3 staging in the output DuckDB
-            {
-                let duck = open_output_duckdb(&db_path)?;
-                duck.execute_batch("BEGIN TRANSACTION")?;
+        progress.inc(batch_len);
 
-                let mut insert_stmt = duck.prepare(
-                    "INSERT INTO h3_staging (source_id, category, subcategory, severity, arrest, day, lng, lat,
-                        h3_r4, h3_r5, h3_r6, h3_r7, h3_r8, h3_r9,
-                        state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id)
-                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                )?;
+        #[allow(clippy::cast_sign_loss)]
+        let batch_limit_u64 = batch_limit as u64;
+        if batch_len < batch_limit_u64 {
+            break;
+        }
+    }
 
-                for incident in &batch {
-                    let arrest_int: i32 = match incident.arrest_made {
-                        Some(true) => 1,
-                        Some(false) => 0,
-                        None => 2,
-                    };
-
-                    let day = incident
-                        .occurred_at
-                        .as_deref()
-                        .and_then(|s| s.get(..10))
-                        .unwrap_or("");
-
-                    // Boundary GEOIDs
-                    let tract_geoid = incident.census_tract_geoid.clone();
-                    let state_fips = incident.state_fips.clone();
-                    let county_geoid = incident.county_geoid.clone();
-                    let place_geoid = incident.census_place_geoid.clone();
-                    let neighborhood_id = incident.neighborhood_id.clone();
+    log::info!("Loaded {total_count} incidents into H3 staging table");
+    Ok(total_count)
+}
 
-                    let Ok(coord) = LatLng::new(incident.latitude, incident.longitude) else {
-                        continue;
-                    };
+/// Inserts `batch` into the `h3_staging` table of the `DuckDB` at
+/// `db_path`, computing each incident's cell at every resolution.
+/// Incidents with invalid coordinates are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the output database operation fails.
+fn insert_h3_staging(
+    db_path: &Path,
+    resolutions: &[h3o::Resolution],
+    batch: &[IncidentRow],
+) -> Result<(), GenerateError> {
+    use h3o::LatLng;
+
+    let duck = open_output_duckdb(db_path)?;
+    duck.execute_batch("BEGIN TRANSACTION")?;
+
+    let mut insert_stmt = duck.prepare(
+        "INSERT INTO h3_staging (source_id, category, subcategory, severity, arrest, domestic, day, dow, hour, lng, lat,
+            h3_r4, h3_r5, h3_r6, h3_r7, h3_r8, h3_r9,
+            state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )?;
 
-                    // Compute all 6 H3 cell indices (nanoseconds each)
-                    let h3_cells: Vec<i64> = resolutions
-                        .iter()
-                        .map(|&res| {
-                            #[allow(clippy::cast_possible_wrap)]
-                            let idx = u64::from(coord.to_cell(res)) as i64;
-                            idx
-                        })
-                        .collect();
+    for incident in batch {
+        let arrest_int: i32 = match incident.arrest_made {
+            Some(true) => 1,
+            Some(false) => 0,
+            None => 2,
+        };
+        let domestic_int: i32 = match incident.domestic {
+            Some(true) => 1,
+            Some(false) => 0,
+            None => 2,
+        };
 
-                    insert_stmt.execute(duckdb::params![
-                        incident.source_id,
-                        incident.parent_category,
-                        incident.category,
-                        incident.severity,
-                        arrest_int,
-                        day,
-                        incident.longitude,
-                        incident.latitude,
-                        h3_cells[0],
-                        h3_cells[1],
-                        h3_cells[2],
-                        h3_cells[3],
-                        h3_cells[4],
-                        h3_cells[5],
-                        state_fips,
-                        county_geoid,
-                        place_geoid,
-                        tract_geoid,
-                        neighborhood_id,
-                    ])?;
-                }
+        let day = incident
+            .occurred_at
+            .as_deref()
+            .and_then(|s| s.get(..10))
+            .unwrap_or("");
+        let (dow, hour) = time_dimensions(incident.occurred_at.as_deref());
+
+        // Boundary GEOIDs
+        let tract_geoid = incident.census_tract_geoid.clone();
+        let state_fips = incident.state_fips.clone();
+        let county_geoid = incident.county_geoid.clone();
+        let place_geoid = incident.census_place_geoid.clone();
+        let neighborhood_id = incident.neighborhood_id.clone();
+
+        let Ok(coord) = LatLng::new(incident.latitude, incident.longitude) else {
+            continue;
+        };
 
-                duck.execute_batch("COMMIT")?;
-            }
+        // Compute all 6 H3 cell indices (nanoseconds each)
+        let h3_cells: Vec<i64> = resolutions
+            .iter()
+            .map(|&res| {
+                #[allow(clippy::cast_possible_wrap)]
+                let idx = u64::from(coord.to_cell(res)) as i64;
+                idx
+            })
+            .collect();
 
-            source_total += batch_len;
-            if let Some(ref mut r) = remaining {
-                *r = r.saturating_sub(batch_len);
-            }
+        insert_stmt.execute(duckdb::params![
+            incident.source_id,
+            incident.parent_category,
+            incident.category,
+            incident.severity,
+            arrest_int,
+            domestic_int,
+            day,
+            dow,
+            hour,
+            incident.longitude,
+            incident.latitude,
+            h3_cells[0],
+            h3_cells[1],
+            h3_cells[2],
+            h3_cells[3],
+            h3_cells[4],
+            h3_cells[5],
+            state_fips,
+            county_geoid,
+            place_geoid,
+            tract_geoid,
+            neighborhood_id,
+        ])?;
+    }
 
-            progress.inc(batch_len);
+    duck.execute_batch("COMMIT")?;
 
-            #[allow(clippy::cast_sign_loss)]
-            let batch_limit_u64 = batch_limit as u64;
-            if batch_len < batch_limit_u64 {
-                break;
-            }
-        }
+    Ok(())
+}
 
-        total_count += source_total;
-        log::info!("Loaded {source_total} incidents from source '{sid}' into H3 staging table...");
+/// Deletes the cells of `h3_counts` with fewer incidents, summed across
+/// every other dimension, than `min_cell_count` requires at their
+/// resolution (see [`GenerateArgs::h3_min_cell_count`]).
+///
+/// Returns the `(cells, incidents)` suppressed at each thresholded
+/// resolution.
+///
+/// # Errors
+///
+/// Returns an error if the `DuckDB` queries fail.
+pub(crate) fn suppress_sparse_h3_cells(
+    duck: &duckdb::Connection,
+    min_cell_count: &BTreeMap<u8, u32>,
+) -> Result<BTreeMap<u8, (u64, u64)>, GenerateError> {
+    let mut suppressed = BTreeMap::new();
+    for (&resolution, &min) in min_cell_count {
+        duck.execute_batch(&format!(
+            "CREATE OR REPLACE TEMP TABLE sparse_h3_cells AS
+             SELECT h3_index, SUM(cnt)::BIGINT AS n
+             FROM h3_counts
+             WHERE resolution = {resolution}
+             GROUP BY h3_index
+             HAVING SUM(cnt) < {min};
+             DELETE FROM h3_counts
+             WHERE resolution = {resolution}
+               AND h3_index IN (SELECT h3_index FROM sparse_h3_cells)"
+        ))?;
+        let (cells, incidents): (i64, i64) = duck.query_row(
+            "SELECT COUNT(*)::BIGINT, COALESCE(SUM(n), 0)::BIGINT FROM sparse_h3_cells",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        log::info!(
+            "H3 r{resolution}: suppressed {cells} cell(s) with fewer than {min} incidents \
+             ({incidents} incidents)"
+        );
+        suppressed.insert(
+            resolution,
+            (
+                u64::try_from(cells).unwrap_or_default(),
+                u64::try_from(incidents).unwrap_or_default(),
+            ),
+        );
     }
+    duck.execute_batch("DROP TABLE IF EXISTS sparse_h3_cells")?;
+    Ok(suppressed)
+}
 
-    // Aggregate staging table into final h3_counts using UNION ALL
-    // across the 6 resolution columns.
-    let duck = open_output_duckdb(&db_path)?;
+/// Coverage of one resolution in the final `h3_counts` table, with
+/// counts summed per cell across every other dimension.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct H3ResolutionStats {
+    /// H3 resolution.
+    pub resolution: u8,
+    /// Number of distinct cells with at least one incident.
+    pub cells: u64,
+    /// Incidents indexed at this resolution.
+    pub total: u64,
+    /// Incidents in the densest cell.
+    pub max_per_cell: u64,
+    /// Median incidents per (non-empty) cell.
+    pub median_per_cell: u64,
+    /// Hex index of the densest cell (ties broken by lowest index).
+    pub densest_cell: String,
+    /// Cells dropped for falling below the resolution's minimum count
+    /// (see [`GenerateArgs::h3_min_cell_count`]). Not included in the
+    /// other fields.
+    pub suppressed_cells: u64,
+    /// Incidents in the suppressed cells.
+    pub suppressed_incidents: u64,
+}
 
-    log::info!("Aggregating H3 counts from staging table...");
-    duck.execute_batch(
-        "CREATE TABLE h3_counts AS
-         WITH unpivoted AS (
-             SELECT h3_r4 AS h3_index, 4 AS resolution, source_id, category, subcategory, severity, arrest, day, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
-             UNION ALL
-             SELECT h3_r5, 5, source_id, category, subcategory, severity, arrest, day, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
-             UNION ALL
-             SELECT h3_r6, 6, source_id, category, subcategory, severity, arrest, day, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
-             UNION ALL
-             SELECT h3_r7, 7, source_id, category, subcategory, severity, arrest, day, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
-             UNION ALL
-             SELECT h3_r8, 8, source_id, category, subcategory, severity, arrest, day, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
-             UNION ALL
-             SELECT h3_r9, 9, source_id, category, subcategory, severity, arrest, day, lng, lat, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id FROM h3_staging
+/// Computes [`H3ResolutionStats`] for each resolution in `h3_counts`,
+/// ordered by resolution.
+///
+/// # Errors
+///
+/// Returns an error if the `DuckDB` query fails.
+pub(crate) fn h3_stats(duck: &duckdb::Connection) -> Result<Vec<H3ResolutionStats>, GenerateError> {
+    let mut stmt = duck.prepare(
+        "WITH cells AS (
+             SELECT resolution, h3_index, SUM(cnt)::BIGINT AS n
+             FROM h3_counts
+             GROUP BY resolution, h3_index
+         ),
+         ranked AS (
+             SELECT *, row_number() OVER (
+                 PARTITION BY resolution ORDER BY n DESC, h3_index
+             ) AS rank
+             FROM cells
          )
          SELECT
-             CAST(h3_index AS UBIGINT) AS h3_index,
-             CAST(resolution AS TINYINT) AS resolution,
-             source_id,
-             category,
-             subcategory,
-             CAST(severity AS TINYINT) AS severity,
-             CAST(arrest AS TINYINT) AS arrest,
-             day,
-             state_fips,
-             county_geoid,
-             place_geoid,
-             tract_geoid,
-             neighborhood_id,
-             CAST(COUNT(*) AS INTEGER) AS cnt,
-             SUM(lng) AS sum_lng,
-             SUM(lat) AS sum_lat
-         FROM unpivoted
-         GROUP BY h3_index, resolution, source_id, category, subcategory, severity, arrest, day, state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id
-         ORDER BY resolution, h3_index",
+             resolution::INTEGER,
+             COUNT(*)::BIGINT,
+             SUM(n)::BIGINT,
+             MAX(n)::BIGINT,
+             quantile_disc(n, 0.5)::BIGINT,
+             MAX(h3_index) FILTER (WHERE rank = 1)::UBIGINT
+         FROM ranked
+         GROUP BY resolution
+         ORDER BY resolution",
     )?;
 
-    // Drop staging table to reclaim space
-    duck.execute_batch("DROP TABLE h3_staging")?;
-
-    // Create indexes for fast viewport queries
-    log::info!("Creating H3 indexes...");
-    duck.execute_batch("CREATE INDEX idx_h3_counts_res_cell ON h3_counts (resolution, h3_index)")?;
+    let rows = stmt.query_map([], |row| {
+        let resolution: i32 = row.get(0)?;
+        let cells: i64 = row.get(1)?;
+        let total: i64 = row.get(2)?;
+        let max_per_cell: i64 = row.get(3)?;
+        let median_per_cell: i64 = row.get(4)?;
+        let densest: u64 = row.get(5)?;
+        Ok(H3ResolutionStats {
+            resolution: u8::try_from(resolution).unwrap_or_default(),
+            cells: u64::try_from(cells).unwrap_or_default(),
+            total: u64::try_from(total).unwrap_or_default(),
+            max_per_cell: u64::try_from(max_per_cell).unwrap_or_default(),
+            median_per_cell: u64::try_from(median_per_cell).unwrap_or_default(),
+            densest_cell: format!("{densest:x}"),
+            suppressed_cells: 0,
+            suppressed_incidents: 0,
+        })
+    })?;
 
-    // Pre-compute hex boundary vertices for every distinct H3 cell.
-    log::info!("Pre-computing H3 boundary vertices...");
-    duck.execute_batch(
-        "CREATE TABLE h3_boundaries (
-             h3_index UBIGINT PRIMARY KEY,
-             v0_lng DOUBLE NOT NULL, v0_lat DOUBLE NOT NULL,
-             v1_lng DOUBLE NOT NULL, v1_lat DOUBLE NOT NULL,
-             v2_lng DOUBLE NOT NULL, v2_lat DOUBLE NOT NULL,
-             v3_lng DOUBLE NOT NULL, v3_lat DOUBLE NOT NULL,
-             v4_lng DOUBLE NOT NULL, v4_lat DOUBLE NOT NULL,
-             v5_lng DOUBLE NOT NULL, v5_lat DOUBLE NOT NULL
-         )",
-    )?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
 
-    {
-        let mut boundary_stmt = duck
-            .prepare("INSERT INTO h3_boundaries VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")?;
+/// Logs one line per resolution of `stats`.
+fn log_h3_stats(stats: &[H3ResolutionStats]) {
+    for s in stats {
+        log::info!(
+            "H3 r{}: {} cells, {} incidents, max {} / median {} per cell (densest {})",
+            s.resolution,
+            s.cells,
+            s.total,
+            s.max_per_cell,
+            s.median_per_cell,
+            s.densest_cell,
+        );
+    }
+}
 
-        let mut distinct_stmt =
-            duck.prepare("SELECT DISTINCT CAST(h3_index AS BIGINT) FROM h3_counts")?;
-        let mut rows = distinct_stmt.query([])?;
+/// Writes `stats` as a JSON array to [`paths::H3_STATS_FILE`] in `dir`.
+///
+/// # Errors
+///
+/// Returns an error if serialization or the write/rename fails.
+pub(crate) fn write_h3_stats(stats: &[H3ResolutionStats], dir: &Path) -> Result<(), GenerateError> {
+    let path = dir.join(paths::H3_STATS_FILE);
+    let tmp_path = dir.join(format!("{}.tmp", paths::H3_STATS_FILE));
+    std::fs::write(&tmp_path, serde_json::to_string_pretty(stats)?)?;
+    std::fs::rename(&tmp_path, &path)?;
 
-        let mut boundary_count: u64 = 0;
-        while let Some(row) = rows.next()? {
-            let h3_raw: i64 = row.get(0)?;
-            #[allow(clippy::cast_sign_loss)]
-            let h3_u64 = h3_raw as u64;
-            let Some(cell) = h3o::CellIndex::try_from(h3_u64).ok() else {
-                continue;
-            };
+    log::info!("H3 stats written: {}", path.display());
+    Ok(())
+}
 
-            let boundary = cell.boundary();
-            let verts: Vec<_> = boundary.iter().collect();
+/// Number of H3 cells whose boundaries are computed per parallel batch
+/// before being inserted into `h3_boundaries`.
+const H3_BOUNDARY_CHUNK_SIZE: usize = 100_000;
+
+/// Pre-computed boundary of one H3 cell as stored in `h3_boundaries`.
+#[derive(Debug, PartialEq)]
+struct H3CellBoundary {
+    /// First six `(lng, lat)` vertices. Cells with fewer than 6 vertices
+    /// (pentagons) are padded by repeating the last vertex, which renders
+    /// as a degenerate hexagon.
+    padded: [(f64, f64); 6],
+    /// Full vertex ring as JSON `[[lng, lat], ...]`, populated only when
+    /// the cell does not have exactly 6 vertices (pentagons, or cells with
+    /// icosahedron-edge distortion vertices at Class III resolutions).
+    exact: Option<String>,
+    /// Whether the cell is one of the 12 pentagons at its resolution.
+    is_pentagon: bool,
+}
 
-            // Hexagons have 6 vertices; pentagons have 5 (extremely rare).
-            // Pad with the last vertex if fewer than 6.
-            let v = |i: usize| -> (f64, f64) {
-                if i < verts.len() {
-                    (verts[i].lng(), verts[i].lat())
-                } else {
-                    let last = &verts[verts.len() - 1];
-                    (last.lng(), last.lat())
-                }
-            };
+/// Computes the `h3_boundaries` row values for a cell.
+fn h3_cell_boundary(cell: h3o::CellIndex) -> H3CellBoundary {
+    let verts: Vec<(f64, f64)> = cell.boundary().iter().map(|v| (v.lng(), v.lat())).collect();
 
-            let (v0_lng, v0_lat) = v(0);
-            let (v1_lng, v1_lat) = v(1);
-            let (v2_lng, v2_lat) = v(2);
-            let (v3_lng, v3_lat) = v(3);
-            let (v4_lng, v4_lat) = v(4);
-            let (v5_lng, v5_lat) = v(5);
-
-            #[allow(clippy::cast_possible_wrap)]
-            boundary_stmt.execute(duckdb::params![
-                h3_raw, v0_lng, v0_lat, v1_lng, v1_lat, v2_lng, v2_lat, v3_lng, v3_lat, v4_lng,
-                v4_lat, v5_lng, v5_lat,
-            ])?;
+    let last = verts.last().copied().unwrap_or((0.0, 0.0));
+    let padded = std::array::from_fn(|i| verts.get(i).copied().unwrap_or(last));
 
-            boundary_count += 1;
-        }
+    let exact = (verts.len() != 6).then(|| {
+        let ring: Vec<[f64; 2]> = verts.iter().map(|&(lng, lat)| [lng, lat]).collect();
+        serde_json::to_string(&ring).unwrap_or_default()
+    });
 
-        log::info!("Pre-computed boundaries for {boundary_count} distinct H3 cells");
+    H3CellBoundary {
+        padded,
+        exact,
+        is_pentagon: cell.is_pentagon(),
     }
+}
 
-    // Reclaim disk space freed by DROP TABLE h3_staging above.
-    log::info!("Running VACUUM on H3 DuckDB...");
-    duck.execute_batch("VACUUM")?;
+/// Computes [`h3_cell_boundary`] for each raw `h3_counts` index in `cells`
+/// in parallel on the current `rayon` pool, keeping the input order.
+/// Indexes that aren't valid cells are skipped.
+fn h3_cell_boundaries(cells: &[i64]) -> Vec<(i64, H3CellBoundary)> {
+    use rayon::prelude::*;
 
-    log::info!(
-        "H3 DuckDB database generated: {} ({total_count} incidents indexed)",
-        db_path.display()
-    );
-    Ok(())
+    cells
+        .par_iter()
+        .filter_map(|&h3_raw| {
+            #[allow(clippy::cast_sign_loss)]
+            let cell = h3o::CellIndex::try_from(h3_raw as u64).ok()?;
+            Some((h3_raw, h3_cell_boundary(cell)))
+        })
+        .collect()
 }
 
 // ============================================================
@@ -1979,19 +6869,24 @@ fn generate_h3_db(
 /// This includes:
 /// - `cities`: distinct `(city, state)` pairs from the dataset
 /// - `minDate` / `maxDate`: the earliest and latest `occurred_at` timestamps
-/// - `sources`: source metadata from the TOML registry
+/// - `sources`: source metadata from the TOML registry, plus each source's
+///   latest `occurred_at` (`maxDate`)
 ///
 /// The server loads this file at boot to populate the AI agent context
-/// without needing a live database connection.
+/// without needing a live database connection. A `health.json` freshness
+/// heartbeat is derived from it alongside (see [`build_health`]).
 ///
 /// # Errors
 ///
 /// Returns an error if the database query or file write fails.
 fn generate_metadata(
+    args: &GenerateArgs,
     source_ids: &[String],
+    blocklist: &Blocklist,
     boundaries_conn: &duckdb::Connection,
     dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), GenerateError> {
+    let filter = exportable_filter(args);
     log::info!("Querying available cities...");
 
     let mut all_cities: std::collections::BTreeSet<(String, String)> =
@@ -2000,107 +6895,384 @@ fn generate_metadata(
     let mut max_date: Option<String> = None;
 
     let registry = all_sources();
-    let mut sources: Vec<serde_json::Value> = Vec::new();
+    // Keyed by id so `sources` comes out sorted regardless of the order of
+    // `source_ids`, keeping the file byte-identical for identical data.
+    let mut sources: BTreeMap<&str, serde_json::Value> = BTreeMap::new();
 
     for sid in source_ids {
-        let path = crime_map_database::paths::source_db_path(sid);
-        if !path.exists() {
+        let shards = open_filtered_shards(sid, blocklist)?;
+        let Some(primary) = shards.first() else {
             continue;
+        };
+        let filter = blocklist.apply(&filter, sid);
+
+        let (mut source_min_date, mut source_max_date) = (None, None);
+        let mut record_count: u64 = 0;
+        for conn in &shards {
+            // Collect distinct cities
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT city, state FROM incidents
+                 WHERE city IS NOT NULL AND city != ''",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let city: String = row.get::<_, Option<String>>(0)?.unwrap_or_default();
+                let state: String = row.get::<_, Option<String>>(1)?.unwrap_or_default();
+                if !city.is_empty() {
+                    all_cities.insert((city, state));
+                }
+            }
+
+            // Collect date range
+            let (src_min, src_max) = source_date_range(conn, &filter)?;
+            merge_date_range(&mut source_min_date, &mut source_max_date, src_min, src_max);
+
+            record_count += crime_map_database::source_db::get_record_count(conn)?;
         }
+        merge_date_range(
+            &mut min_date,
+            &mut max_date,
+            source_min_date,
+            source_max_date.clone(),
+        );
+
+        // Build source metadata from registry + _meta
+        let source_name =
+            crime_map_database::source_db::get_meta(primary, "source_name")?.unwrap_or_default();
 
-        let conn = crime_map_database::source_db::open_by_id(sid)?;
+        // Find registry entry for additional metadata
+        let def = registry.iter().find(|s| s.id() == sid.as_str());
 
-        // Collect distinct cities
-        let mut stmt = conn.prepare(
-            "SELECT DISTINCT city, state FROM incidents
-             WHERE city IS NOT NULL AND city != ''",
-        )?;
-        let mut rows = stmt.query([])?;
-        while let Some(row) = rows.next()? {
-            let city: String = row.get::<_, Option<String>>(0)?.unwrap_or_default();
-            let state: String = row.get::<_, Option<String>>(1)?.unwrap_or_default();
-            if !city.is_empty() {
-                all_cities.insert((city, state));
-            }
+        let portal_url = def.and_then(crime_map_source::source_def::SourceDefinition::portal_url);
+        let city = def.map_or(String::new(), |d| d.city.clone());
+        let state = def.map_or(String::new(), |d| d.state.clone());
+        let license = def.map(source_license_json);
+
+        sources.insert(
+            sid,
+            serde_json::json!({
+                "id": sid,
+                "name": source_name,
+                "recordCount": record_count,
+                "city": city,
+                "state": state,
+                "portalUrl": portal_url,
+                "maxDate": source_max_date,
+                "license": license,
+            }),
+        );
+    }
+    let sources: Vec<serde_json::Value> = sources.into_values().collect();
+
+    let cities: Vec<serde_json::Value> = all_cities
+        .iter()
+        .map(|(city, state)| serde_json::json!([city, state]))
+        .collect();
+
+    log::info!("Found {} distinct cities", cities.len());
+
+    // Also query boundary summary counts from the boundaries DuckDB
+    // for the /api/sources endpoint context
+    let _ = boundaries_conn; // used for boundary outputs, not needed for metadata beyond sources
+
+    let metadata = serde_json::json!({
+        "cities": cities,
+        "minDate": min_date,
+        "maxDate": max_date,
+        "sources": sources,
+        "build": build_info(),
+    });
+
+    let path = dir.join(paths::METADATA_FILE);
+    let tmp_path = dir.join("metadata.json.tmp");
+    let contents = serde_json::to_string_pretty(&metadata)?;
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, &path)?;
+
+    log::info!("Server metadata generated: {}", path.display());
+
+    write_health(dir, &metadata)?;
+    Ok(())
+}
+
+/// Builds a source's `license` block for `metadata.json` from its registry
+/// definition, so clients can render the credit line the source requires.
+///
+/// Warns when the source sets `attribution_required` but has no
+/// `attribution_text`, since clients would have nothing to display.
+fn source_license_json(def: &SourceDefinition) -> serde_json::Value {
+    let license = def.license();
+    let attribution_text = license
+        .attribution_text
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty());
+    if license.attribution_required && attribution_text.is_none() {
+        log::warn!(
+            "Source '{}' requires attribution but has no attribution_text",
+            def.id()
+        );
+    }
+    serde_json::json!({
+        "type": license.license_type,
+        "tosUrl": license.tos_url,
+        "attributionRequired": license.attribution_required,
+        "attributionText": attribution_text,
+    })
+}
+
+/// Builds the `health.json` freshness heartbeat from a `metadata.json`
+/// value, so full runs and [`merge::run`] derive it the same way.
+///
+/// Contains the generation timestamp, manifest version, overall date
+/// range, total and per-source record counts, and `dataAgeDays`: whole
+/// days from the latest `occurred_at` to `today`, overall and per source.
+/// A monitor can alert when the age grows past a threshold, which usually
+/// means a sync is broken.
+fn build_health(metadata: &serde_json::Value, today: chrono::NaiveDate) -> serde_json::Value {
+    let sources: Vec<serde_json::Value> = metadata["sources"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|source| {
+            let max_date = source["maxDate"].as_str();
+            serde_json::json!({
+                "id": source["id"],
+                "recordCount": source["recordCount"],
+                "maxDate": max_date,
+                "dataAgeDays": data_age_days(max_date, today),
+            })
+        })
+        .collect();
+
+    let total_records: u64 = sources
+        .iter()
+        .filter_map(|s| s["recordCount"].as_u64())
+        .sum();
+    let max_date = metadata["maxDate"].as_str();
+
+    serde_json::json!({
+        "generatedAt": chrono::Utc::now().to_rfc3339(),
+        "manifestVersion": MANIFEST_VERSION,
+        "minDate": metadata["minDate"],
+        "maxDate": max_date,
+        "dataAgeDays": data_age_days(max_date, today),
+        "totalRecords": total_records,
+        "sources": sources,
+    })
+}
+
+/// Returns the earliest and latest `occurred_at` (as `DuckDB` timestamp
+/// text) across the exportable incidents of `source_ids`, honouring the
+/// same coordinate, bbox, sampling, and blocklist filters as the outputs.
+///
+/// Each source is a single `MIN`/`MAX` aggregate, not a row scan in Rust.
+/// Sources without a local database are skipped; either bound is `None`
+/// when no exportable incident has an `occurred_at`.
+///
+/// # Errors
+///
+/// Returns an error if the blocklist can't be loaded or a source database
+/// can't be queried.
+pub fn dataset_date_range(
+    args: &GenerateArgs,
+    source_ids: &[String],
+) -> Result<(Option<String>, Option<String>), GenerateError> {
+    let filter = exportable_filter(args);
+    let blocklist = load_blocklist(args, source_ids)?;
+
+    let (mut min_date, mut max_date) = (None, None);
+    for sid in source_ids {
+        let filter = blocklist.apply(&filter, sid);
+        for conn in open_filtered_shards(sid, &blocklist)? {
+            let (src_min, src_max) = source_date_range(&conn, &filter)?;
+            merge_date_range(&mut min_date, &mut max_date, src_min, src_max);
         }
+    }
+
+    Ok((min_date, max_date))
+}
+
+/// Returns the earliest and latest `occurred_at` of the incidents in one
+/// source database matching `filter`.
+fn source_date_range(
+    conn: &duckdb::Connection,
+    filter: &str,
+) -> Result<(Option<String>, Option<String>), GenerateError> {
+    Ok(conn.query_row(
+        &format!(
+            "SELECT MIN(occurred_at)::TEXT as min_d, MAX(occurred_at)::TEXT as max_d
+              FROM incidents WHERE {filter}
+                AND occurred_at IS NOT NULL"
+        ),
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?)
+}
+
+/// Widens the running `min`/`max` date range to include `src_min` and
+/// `src_max`. The timestamps share one text format, so they compare
+/// lexically.
+fn merge_date_range(
+    min: &mut Option<String>,
+    max: &mut Option<String>,
+    src_min: Option<String>,
+    src_max: Option<String>,
+) {
+    if let Some(d) = src_min
+        && min.as_ref().is_none_or(|cur| d < *cur)
+    {
+        *min = Some(d);
+    }
+    if let Some(d) = src_max
+        && max.as_ref().is_none_or(|cur| d > *cur)
+    {
+        *max = Some(d);
+    }
+}
 
-        // Collect date range
-        let mut stmt = conn.prepare(
-            "SELECT MIN(occurred_at)::TEXT as min_d, MAX(occurred_at)::TEXT as max_d
-              FROM incidents WHERE has_coordinates = TRUE
-                AND longitude BETWEEN -180 AND 180
-                AND latitude BETWEEN -90 AND 90
-                AND occurred_at IS NOT NULL",
-        )?;
-        let mut rows = stmt.query([])?;
-        if let Some(row) = rows.next()? {
-            let src_min: Option<String> = row.get(0)?;
-            let src_max: Option<String> = row.get(1)?;
-
-            if let Some(d) = src_min {
-                min_date = Some(match min_date {
-                    Some(ref cur) if cur.as_str() <= d.as_str() => cur.clone(),
-                    _ => d,
-                });
-            }
-            if let Some(d) = src_max {
-                max_date = Some(match max_date {
-                    Some(ref cur) if cur.as_str() >= d.as_str() => cur.clone(),
-                    _ => d,
-                });
-            }
-        }
+/// Returns the number of whole days from the date part of `max_date`
+/// (a `DuckDB` timestamp rendered as text) to `today`.
+fn data_age_days(max_date: Option<&str>, today: chrono::NaiveDate) -> Option<i64> {
+    let date = chrono::NaiveDate::parse_from_str(max_date?.get(..10)?, "%Y-%m-%d").ok()?;
+    Some((today - date).num_days())
+}
 
-        // Build source metadata from registry + _meta
-        let source_name =
-            crime_map_database::source_db::get_meta(&conn, "source_name")?.unwrap_or_default();
-        let record_count = crime_map_database::source_db::get_record_count(&conn)?;
+/// Writes `health.json` (see [`build_health`]) into `dir`.
+///
+/// # Errors
+///
+/// Returns an error if serialization or the file write fails.
+fn write_health(dir: &Path, metadata: &serde_json::Value) -> Result<(), GenerateError> {
+    let health = build_health(metadata, chrono::Utc::now().date_naive());
 
-        // Find registry entry for additional metadata
-        let def = registry.iter().find(|s| s.id() == sid.as_str());
+    let path = dir.join(paths::HEALTH_FILE);
+    let tmp_path = dir.join("health.json.tmp");
+    std::fs::write(&tmp_path, serde_json::to_string_pretty(&health)?)?;
+    std::fs::rename(&tmp_path, &path)?;
 
-        let portal_url = def.and_then(crime_map_source::source_def::SourceDefinition::portal_url);
-        let city = def.map_or(String::new(), |d| d.city.clone());
-        let state = def.map_or(String::new(), |d| d.state.clone());
+    log::info!("Health heartbeat generated: {}", path.display());
+    Ok(())
+}
 
-        sources.push(serde_json::json!({
-            "id": sid,
-            "name": source_name,
-            "recordCount": record_count,
-            "city": city,
-            "state": state,
-            "portalUrl": portal_url,
-        }));
-    }
+// ============================================================
+// Tract choropleth DuckDB generation
+// ============================================================
 
-    let cities: Vec<serde_json::Value> = all_cities
-        .iter()
-        .map(|(city, state)| serde_json::json!([city, state]))
-        .collect();
+/// Residents per unit of `tract_counts.rate` (incidents per 1,000
+/// residents).
+pub const CHOROPLETH_RATE_PER: u32 = 1000;
 
-    log::info!("Found {} distinct cities", cities.len());
+/// Generates a `DuckDB` database of incident counts per census tract, for
+/// choropleth fills joined against the `tracts` layer of
+/// `boundaries.pmtiles` by GEOID.
+///
+/// Derived from the analytics DB in `dir`, whose `incidents` rows carry
+/// each incident's tract and whose `census_tracts` table holds the tract
+/// populations, so the sources are never re-read. Creates
+/// `choropleth.duckdb` with:
+/// - `tract_category_counts`: one row per `(tract_geoid, category)` with
+///   the incident count `cnt`
+/// - `tract_counts`: one row per tract with the `total` count, the tract
+///   `population`, and `rate` (see [`create_tract_counts`])
+/// - `choropleth_meta`: a single row with the `grain` (`census_tract`),
+///   the `min_date`/`max_date` (inclusive `YYYY-MM-DD`, UTC) covered, the
+///   `rate_per` unit, and the number of `untracted` incidents without a
+///   tract GEOID, which aren't counted
+///
+/// Counts cover every incident in the analytics DB, across all dates.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::InvalidArgs`] if `dir` has no analytics DB,
+/// or an error if it can't be read or the output `DuckDB` can't be
+/// written.
+pub(crate) fn generate_choropleth_db(dir: &Path) -> Result<(), GenerateError> {
+    let analytics_path = dir.join(paths::ANALYTICS_DB_FILE);
+    if !analytics_path.exists() {
+        return Err(GenerateError::InvalidArgs(format!(
+            "The choropleth DB is derived from {}, which is missing",
+            analytics_path.display()
+        )));
+    }
+    let output_path = dir.join(paths::CHOROPLETH_DB_FILE);
+    let db_path = begin_atomic_output(&output_path)?;
 
-    // Also query boundary summary counts from the boundaries DuckDB
-    // for the /api/sources endpoint context
-    let _ = boundaries_conn; // used for boundary outputs, not needed for metadata beyond sources
+    log::info!("Creating tract choropleth DuckDB database from the analytics DB...");
 
-    let metadata = serde_json::json!({
-        "cities": cities,
-        "minDate": min_date,
-        "maxDate": max_date,
-        "sources": sources,
-    });
+    let duck = open_output_duckdb(&db_path)?;
+    duck.execute_batch(&format!(
+        "ATTACH '{}' AS analytics (READ_ONLY);
+         CREATE TABLE tract_category_counts AS
+         SELECT census_tract_geoid AS tract_geoid, category, COUNT(*)::BIGINT AS cnt
+         FROM analytics.incidents
+         WHERE census_tract_geoid IS NOT NULL
+         GROUP BY ALL
+         ORDER BY tract_geoid, category;
+         CREATE TABLE choropleth_meta AS
+         SELECT
+             'census_tract' AS grain,
+             strftime(MIN(occurred_at), '%Y-%m-%d') AS min_date,
+             strftime(MAX(occurred_at), '%Y-%m-%d') AS max_date,
+             {CHOROPLETH_RATE_PER} AS rate_per,
+             COUNT(*) FILTER (WHERE census_tract_geoid IS NULL)::BIGINT AS untracted
+         FROM analytics.incidents;
+         CREATE TEMP TABLE tract_population AS
+         SELECT geoid, population::BIGINT AS population FROM analytics.census_tracts;",
+        analytics_path.to_string_lossy().replace('\'', "''"),
+    ))?;
+    let total_count: i64 =
+        duck.query_row("SELECT COUNT(*) FROM analytics.incidents", [], |row| {
+            row.get(0)
+        })?;
+    duck.execute_batch("DETACH analytics")?;
+
+    create_tract_counts(&duck)?;
+    duck.execute_batch("DROP TABLE tract_population")?;
+
+    let tracts: i64 = duck.query_row("SELECT COUNT(*) FROM tract_counts", [], |row| row.get(0))?;
+
+    log::info!("Running VACUUM on choropleth DuckDB...");
+    duck.execute_batch("VACUUM")?;
 
-    let path = dir.join("metadata.json");
-    let tmp_path = dir.join("metadata.json.tmp");
-    let contents = serde_json::to_string_pretty(&metadata)?;
-    std::fs::write(&tmp_path, contents)?;
-    std::fs::rename(&tmp_path, &path)?;
+    drop(duck);
+    commit_atomic_output(&db_path, &output_path)?;
 
-    log::info!("Server metadata generated: {}", path.display());
+    log::info!(
+        "Tract choropleth DuckDB generated: {} ({total_count} incidents across {tracts} tracts)",
+        output_path.display()
+    );
     Ok(())
 }
 
+/// Creates `tract_counts` from `tract_category_counts` and a
+/// `tract_population (geoid, population)` table in `duck`.
+///
+/// `rate` is incidents per [`CHOROPLETH_RATE_PER`] residents, or `NULL`
+/// when the tract's population is unknown or zero.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+fn create_tract_counts(duck: &duckdb::Connection) -> Result<(), duckdb::Error> {
+    duck.execute_batch(&format!(
+        "CREATE TABLE tract_counts AS
+         SELECT
+             c.tract_geoid,
+             SUM(c.cnt)::BIGINT AS total,
+             p.population,
+             CASE WHEN p.population > 0
+                  THEN SUM(c.cnt) * {CHOROPLETH_RATE_PER}.0 / p.population
+             END AS rate
+         FROM tract_category_counts c
+         LEFT JOIN tract_population p ON p.geoid = c.tract_geoid
+         GROUP BY c.tract_geoid, p.population
+         ORDER BY c.tract_geoid;
+         CREATE INDEX idx_tract_category_counts_geoid ON tract_category_counts (tract_geoid);"
+    ))
+}
+
 // ============================================================
 // Analytics DuckDB generation
 // ============================================================
@@ -2114,30 +7286,44 @@ fn generate_metadata(
 /// - `neighborhoods` / `tract_neighborhoods` tables: neighborhood mapping
 /// - `census_places` table: place metadata for `search_locations` tool
 /// - `crime_categories` table: distinct category/subcategory/severity from data
+/// - With a [taxonomy](taxonomy), one rollup column per level on `incidents`
+///   (see [`add_taxonomy_columns`])
 ///
 /// This replaces all runtime `PostGIS` queries from the AI analytics tools.
 ///
+/// With `windows` (see [`analytics_refresh_windows`]), the existing DB is
+/// updated instead when possible (see [`refresh_analytics_db`]).
+///
+/// Returns the new high-water mark of each source (see
+/// [`analytics_high_water`]).
+///
 /// # Errors
 ///
 /// Returns an error if the source `DuckDB` export or output `DuckDB`
 /// creation fails.
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
 fn generate_analytics_db(
     args: &GenerateArgs,
     source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    windows: Option<&BTreeMap<String, Option<String>>>,
     boundaries_conn: &duckdb::Connection,
     dir: &Path,
     progress: &Arc<dyn ProgressCallback>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let db_path = dir.join("analytics.duckdb");
-
-    // Remove existing files
-    if db_path.exists() {
-        std::fs::remove_file(&db_path)?;
+) -> Result<BTreeMap<String, String>, GenerateError> {
+    let output_path = dir.join(paths::ANALYTICS_DB_FILE);
+    if let Some(windows) = windows
+        && refresh_analytics_db(args, source_ids, remap, blocklist, windows, dir, progress)?
+    {
+        return analytics_high_water(&output_path);
     }
-    let wal_path = dir.join("analytics.duckdb.wal");
-    if wal_path.exists() {
-        std::fs::remove_file(&wal_path)?;
+
+    let db_path = begin_atomic_output(&output_path)?;
+    // A previous export would be stale (or unwanted) after this run
+    let ndjson_path = dir.join(paths::ANALYTICS_NDJSON_FILE);
+    if ndjson_path.exists() {
+        std::fs::remove_file(&ndjson_path)?;
     }
 
     log::info!("Creating analytics DuckDB database...");
@@ -2155,6 +7341,7 @@ fn generate_analytics_db(
                 subcategory VARCHAR NOT NULL,
                 severity INTEGER NOT NULL,
                 arrest_made BOOLEAN,
+                domestic BOOLEAN,
                 parent_category_id INTEGER,
                 category_id INTEGER,
                 source_id VARCHAR NOT NULL,
@@ -2174,133 +7361,16 @@ fn generate_analytics_db(
             break;
         }
 
-        let source_name = resolve_source_name(sid);
-
-        let conn = crime_map_database::source_db::open_by_id(sid)?;
-        let mut last_rowid: i64 = 0;
-        let mut source_total: u64 = 0;
-
-        loop {
-            if remaining == Some(0) {
-                break;
-            }
-
-            #[allow(clippy::cast_sign_loss)]
-            let batch_limit = match remaining {
-                Some(r) => i64::try_from(r.min(BATCH_SIZE as u64))?,
-                None => BATCH_SIZE,
-            };
-
-            let mut stmt = conn.prepare(
-                "SELECT rowid,
-                        source_incident_id, category, parent_category, severity,
-                        longitude, latitude, occurred_at::TEXT as occurred_at_text,
-                        description, block_address,
-                        city, state, arrest_made, domestic, location_type,
-                        census_tract_geoid, census_place_geoid, state_fips,
-                        county_geoid, neighborhood_id
-                 FROM incidents
-                 WHERE has_coordinates = TRUE
-                   AND longitude BETWEEN -180 AND 180
-                   AND latitude BETWEEN -90 AND 90
-                   AND rowid > ?
-                 ORDER BY rowid ASC
-                 LIMIT ?",
-            )?;
-
-            let mut rows = stmt.query(duckdb::params![last_rowid, batch_limit])?;
-
-            let mut batch: Vec<IncidentRow> = Vec::new();
-            while let Some(row) = rows.next()? {
-                let rowid: i64 = row.get(0)?;
-                last_rowid = rowid;
-
-                batch.push(IncidentRow {
-                    source_incident_id: row.get(1)?,
-                    source_id: sid.clone(),
-                    source_name: source_name.clone(),
-                    category: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
-                    parent_category: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
-                    severity: row.get::<_, Option<i16>>(4)?.unwrap_or(1).into(),
-                    longitude: row.get(5)?,
-                    latitude: row.get(6)?,
-                    occurred_at: row.get(7)?,
-                    description: row.get(8)?,
-                    block_address: row.get(9)?,
-                    city: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
-                    state: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
-                    arrest_made: row.get(12)?,
-                    domestic: row.get(13)?,
-                    location_type: row.get(14)?,
-                    census_tract_geoid: row.get(15)?,
-                    census_place_geoid: row.get(16)?,
-                    state_fips: row.get(17)?,
-                    county_geoid: row.get(18)?,
-                    neighborhood_id: row.get(19)?,
-                });
-            }
-
-            if batch.is_empty() {
-                break;
-            }
-
-            #[allow(clippy::cast_possible_truncation)]
-            let batch_len = batch.len() as u64;
-
-            {
-                let duck = open_output_duckdb(&db_path)?;
-                duck.execute_batch("BEGIN TRANSACTION")?;
-
-                let mut insert_stmt = duck.prepare(
-                    "INSERT INTO incidents (occurred_at, city, state, category, subcategory,
-                        severity, arrest_made, parent_category_id, category_id, source_id,
-                        census_tract_geoid, census_place_geoid, neighborhood_id)
-                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                )?;
-
-                for incident in &batch {
-                    // Read pre-computed spatial attribution
-                    let tract_geoid = incident.census_tract_geoid.clone();
-                    let place_geoid = incident.census_place_geoid.clone();
-                    let neighborhood_id = incident.neighborhood_id.clone();
-
-                    let parent_category_id: Option<i32> = None;
-                    let category_id: Option<i32> = None;
-
-                    insert_stmt.execute(duckdb::params![
-                        incident.occurred_at,
-                        incident.city,
-                        incident.state,
-                        incident.parent_category,
-                        incident.category,
-                        incident.severity,
-                        incident.arrest_made,
-                        parent_category_id,
-                        category_id,
-                        incident.source_id,
-                        tract_geoid,
-                        place_geoid,
-                        neighborhood_id,
-                    ])?;
-                }
-
-                duck.execute_batch("COMMIT")?;
-            }
-
-            source_total += batch_len;
-            if let Some(ref mut r) = remaining {
-                *r = r.saturating_sub(batch_len);
-            }
-
-            progress.inc(batch_len);
-
-            #[allow(clippy::cast_sign_loss)]
-            let batch_limit_u64 = batch_limit as u64;
-            if batch_len < batch_limit_u64 {
-                break;
-            }
-        }
-
+        let source_total = insert_analytics_incidents(
+            args,
+            sid,
+            None,
+            remap,
+            blocklist,
+            &db_path,
+            &mut remaining,
+            progress,
+        )?;
         total_count += source_total;
         log::info!("Inserted {source_total} rows from source '{sid}' into analytics DB...");
     }
@@ -2308,6 +7378,9 @@ fn generate_analytics_db(
     // Now populate reference tables from the boundaries DuckDB
     let duck = open_output_duckdb(&db_path)?;
 
+    // Before the indexes, which block adding columns
+    add_taxonomy_columns(&duck, remap.taxonomy(), &["incidents"])?;
+
     // Create indexes on the incidents table
     log::info!("Creating analytics indexes...");
     duck.execute_batch(
@@ -2386,13 +7459,14 @@ fn generate_analytics_db(
         while let Some(row) = src_rows.next()? {
             let id: i32 = row.get(0)?;
             let name: String = row.get::<_, Option<String>>(1)?.unwrap_or_default();
-            let nbhd_id = format!("nbhd-{id}");
-            dst_stmt.execute(duckdb::params![nbhd_id, name])?;
+            dst_stmt.execute(duckdb::params![neighborhood_geoid(id), name])?;
             count += 1;
         }
         log::info!("Inserted {count} neighborhoods");
     }
 
+    validate_neighborhood_ids(&duck)?;
+
     // ── Tract-neighborhood mapping table ──
     log::info!("Populating tract_neighborhoods reference table...");
     duck.execute_batch(
@@ -2414,8 +7488,7 @@ fn generate_analytics_db(
         while let Some(row) = src_rows.next()? {
             let geoid: String = row.get(0)?;
             let nbhd_id: i32 = row.get(1)?;
-            let nbhd_id_str = format!("nbhd-{nbhd_id}");
-            dst_stmt.execute(duckdb::params![geoid, nbhd_id_str])?;
+            dst_stmt.execute(duckdb::params![geoid, neighborhood_geoid(nbhd_id)])?;
             count += 1;
         }
         log::info!("Inserted {count} tract-neighborhood mappings");
@@ -2465,20 +7538,304 @@ fn generate_analytics_db(
     }
 
     // ── Crime categories reference table (derived from data) ──
+    populate_crime_categories(&duck, remap.taxonomy())?;
+
+    // Compact the file before upload/serving.
+    if args.analytics_ndjson {
+        export_analytics_ndjson(&duck, dir)?;
+    }
+
+    log::info!("Running VACUUM on analytics DuckDB...");
+    duck.execute_batch("VACUUM")?;
+
+    drop(duck);
+    commit_atomic_output(&db_path, &output_path)?;
+
+    log::info!(
+        "Analytics DuckDB database generated: {} ({total_count} incident rows + reference tables)",
+        output_path.display()
+    );
+    analytics_high_water(&output_path)
+}
+
+/// Inserts `sid`'s exportable incidents into the analytics DB at
+/// `db_path`, only those from `since` (`YYYY-MM-DD HH:MM:SS`, UTC) onward
+/// if given, returning how many were inserted. `remaining` is the
+/// `--limit` budget left, decremented as rows are inserted.
+///
+/// # Errors
+///
+/// Returns an error if the source or output `DuckDB` can't be read or
+/// written.
+#[allow(clippy::too_many_arguments)]
+fn insert_analytics_incidents(
+    args: &GenerateArgs,
+    sid: &str,
+    since: Option<&str>,
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    db_path: &Path,
+    remaining: &mut Option<u64>,
+    progress: &Arc<dyn ProgressCallback>,
+) -> Result<u64, GenerateError> {
+    let source_name = resolve_source_name(sid);
+
+    let shards = open_filtered_shards(sid, blocklist)?;
+    if shards.is_empty() {
+        return Ok(0);
+    }
+    let mut incidents = ShardedIncidents::new(shards);
+    let mut filter = blocklist.apply(&exportable_filter(args), sid).into_owned();
+    if let Some(since) = since {
+        write!(
+            filter,
+            "
+               AND occurred_at >= '{since}+00'::TIMESTAMPTZ"
+        )
+        .unwrap();
+    }
+    let mut source_total: u64 = 0;
+
+    loop {
+        if *remaining == Some(0) {
+            break;
+        }
+
+        #[allow(clippy::cast_sign_loss)]
+        let batch_limit = match *remaining {
+            Some(r) => i64::try_from(r.min(BATCH_SIZE as u64)).unwrap_or(BATCH_SIZE),
+            None => BATCH_SIZE,
+        };
+
+        let mut batch: Vec<IncidentRow> = Vec::new();
+        incidents.next_page::<GenerateError, _>(INCIDENT_COLUMNS, &filter, batch_limit, |row| {
+            let mut incident = incident_row(row, sid, &source_name)?;
+            incident.apply_remap(remap);
+            batch.push(incident);
+            Ok(())
+        })?;
+
+        if batch.is_empty() {
+            break;
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let batch_len = batch.len() as u64;
+
+        {
+            let duck = open_output_duckdb(db_path)?;
+            duck.execute_batch("BEGIN TRANSACTION")?;
+
+            let mut insert_stmt = duck.prepare(
+                "INSERT INTO incidents (occurred_at, city, state, category, subcategory,
+                    severity, arrest_made, domestic, parent_category_id, category_id, source_id,
+                    census_tract_geoid, census_place_geoid, neighborhood_id)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+
+            for incident in &batch {
+                // Read pre-computed spatial attribution
+                let tract_geoid = incident.census_tract_geoid.clone();
+                let place_geoid = incident.census_place_geoid.clone();
+                let neighborhood_id = incident.neighborhood_id.clone();
+
+                let parent_category_id: Option<i32> = None;
+                let category_id: Option<i32> = None;
+
+                insert_stmt.execute(duckdb::params![
+                    incident.occurred_at,
+                    incident.city,
+                    incident.state,
+                    incident.parent_category,
+                    incident.category,
+                    incident.severity,
+                    incident.arrest_made,
+                    incident.domestic,
+                    parent_category_id,
+                    category_id,
+                    incident.source_id,
+                    tract_geoid,
+                    place_geoid,
+                    neighborhood_id,
+                ])?;
+            }
+
+            duck.execute_batch("COMMIT")?;
+        }
+
+        source_total += batch_len;
+        if let Some(r) = remaining.as_mut() {
+            *r = r.saturating_sub(batch_len);
+        }
+
+        progress.inc(batch_len);
+
+        #[allow(clippy::cast_sign_loss)]
+        let batch_limit_u64 = batch_limit as u64;
+        if batch_len < batch_limit_u64 {
+            break;
+        }
+    }
+
+    Ok(source_total)
+}
+
+/// Updates the existing analytics DB in place: each source in `windows`
+/// (see [`analytics_refresh_windows`]) has its rows from the window start
+/// onward, or all of them, deleted and re-inserted from the source, and
+/// the derived taxonomy columns and `crime_categories` table are rebuilt.
+/// The reference tables are kept as they are.
+///
+/// Returns `false`, leaving the existing DB untouched, if a refreshed
+/// source's row count no longer matches its exportable records afterwards
+/// (rows before its window were added or changed), in which case the DB
+/// must be rebuilt in full.
+///
+/// # Errors
+///
+/// Returns an error if the existing DB can't be copied or updated, or a
+/// source can't be read.
+fn refresh_analytics_db(
+    args: &GenerateArgs,
+    source_ids: &[String],
+    remap: &CategoryRemap,
+    blocklist: &Blocklist,
+    windows: &BTreeMap<String, Option<String>>,
+    dir: &Path,
+    progress: &Arc<dyn ProgressCallback>,
+) -> Result<bool, GenerateError> {
+    let output_path = dir.join(paths::ANALYTICS_DB_FILE);
+    let db_path = begin_atomic_output(&output_path)?;
+    log::info!(
+        "Updating analytics DuckDB database for {} changed source(s)...",
+        windows.len()
+    );
+    std::fs::copy(&output_path, &db_path)?;
+
+    {
+        let duck = open_output_duckdb(&db_path)?;
+        for (sid, since) in windows {
+            if let Some(since) = since {
+                log::info!("{sid}: replacing analytics rows from {since}");
+                duck.execute(
+                    "DELETE FROM incidents
+                     WHERE source_id = ? AND occurred_at >= CAST(? AS TIMESTAMP)",
+                    duckdb::params![sid, since],
+                )?;
+            } else {
+                log::info!("{sid}: replacing all analytics rows");
+                duck.execute("DELETE FROM incidents WHERE source_id = ?", [sid])?;
+            }
+        }
+    }
+
+    let refreshed: Vec<&String> = windows
+        .keys()
+        .filter(|sid| source_ids.contains(sid))
+        .collect();
+    for sid in &refreshed {
+        let inserted = insert_analytics_incidents(
+            args,
+            sid,
+            windows[*sid].as_deref(),
+            remap,
+            blocklist,
+            &db_path,
+            &mut None,
+            progress,
+        )?;
+        log::info!("Inserted {inserted} rows from source '{sid}' into analytics DB...");
+    }
+
+    let duck = open_output_duckdb(&db_path)?;
+    for sid in refreshed {
+        let (expected, _) = count_exportable_records(args, std::slice::from_ref(sid), blocklist)?;
+        let actual: i64 = duck.query_row(
+            "SELECT COUNT(*) FROM incidents WHERE source_id = ?",
+            [sid],
+            |row| row.get(0),
+        )?;
+        if u64::try_from(actual).ok() != Some(expected) {
+            log::info!(
+                "{sid}: {actual} analytics rows but {expected} exportable records \
+                 (older rows changed), rebuilding the analytics DB in full"
+            );
+            return Ok(false);
+        }
+    }
+
+    add_taxonomy_columns(&duck, remap.taxonomy(), &["incidents"])?;
+    populate_crime_categories(&duck, remap.taxonomy())?;
+    if args.analytics_ndjson {
+        export_analytics_ndjson(&duck, dir)?;
+    }
+    duck.execute_batch("CHECKPOINT")?;
+
+    drop(duck);
+    commit_atomic_output(&db_path, &output_path)?;
+    log::info!(
+        "Analytics DuckDB database updated: {}",
+        output_path.display()
+    );
+    Ok(true)
+}
+
+/// Returns the latest `occurred_at` of each source in the analytics DB at
+/// `path`, as `YYYY-MM-DD HH:MM:SS` (UTC).
+///
+/// # Errors
+///
+/// Returns an error if the database can't be opened or queried.
+fn analytics_high_water(path: &Path) -> Result<BTreeMap<String, String>, GenerateError> {
+    let duck = open_output_duckdb(path)?;
+    let mut stmt = duck.prepare(
+        "SELECT source_id, strftime(MAX(occurred_at), '%Y-%m-%d %H:%M:%S')
+         FROM incidents
+         WHERE occurred_at IS NOT NULL
+         GROUP BY source_id",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Rebuilds the `crime_categories` reference table from the distinct
+/// `(subcategory, category, severity)` tuples in the analytics incidents.
+///
+/// `level` is `category` or `subcategory`. With a non-empty `taxonomy`,
+/// every rollup node used by an incident gets a row too, with its level
+/// name as `level` and `parent_id` linking it to the node a level up, and
+/// each subcategory row links to its nodes through one `{level}_id` column
+/// per level. Needs the `category_taxonomy` table created by
+/// [`add_taxonomy_columns`].
+///
+/// # Errors
+///
+/// Returns an error if the table can't be created or populated.
+fn populate_crime_categories(
+    duck: &duckdb::Connection,
+    taxonomy: &Taxonomy,
+) -> Result<(), GenerateError> {
     log::info!("Populating crime_categories reference table...");
-    duck.execute_batch(
-        "CREATE TABLE crime_categories (
+    // Added up front: the primary key index blocks adding columns later
+    let level_ids: String = taxonomy
+        .levels()
+        .iter()
+        .map(|level| format!(",\n            {level}_id INTEGER"))
+        .collect();
+    duck.execute_batch(&format!(
+        "CREATE OR REPLACE TABLE crime_categories (
             id INTEGER PRIMARY KEY,
             name VARCHAR NOT NULL,
             parent_id INTEGER,
-            severity INTEGER
+            severity INTEGER,
+            level VARCHAR NOT NULL{level_ids}
         )",
-    )?;
+    ))?;
 
     // Build categories from the distinct (subcategory, parent_category, severity)
     // tuples in the incidents table
     duck.execute_batch(
-        "INSERT INTO crime_categories (id, name, parent_id, severity)
+        "INSERT INTO crime_categories (id, name, parent_id, severity, level)
          WITH parents AS (
              SELECT DISTINCT category AS name
              FROM incidents
@@ -2500,19 +7857,193 @@ fn generate_analytics_db(
              FROM children c
              JOIN numbered_parents np ON np.name = c.parent_name
          )
-         SELECT id, name, NULL AS parent_id, NULL AS severity FROM numbered_parents
+         SELECT id, name, NULL AS parent_id, NULL AS severity, 'category' AS level
+         FROM numbered_parents
          UNION ALL
-         SELECT id, name, parent_id, severity FROM numbered_children",
+         SELECT id, name, parent_id, severity, 'subcategory' AS level
+         FROM numbered_children",
     )?;
 
-    // Compact the file before upload/serving.
-    log::info!("Running VACUUM on analytics DuckDB...");
-    duck.execute_batch("VACUUM")?;
+    let mut parent_level: Option<&String> = None;
+    for level in taxonomy.levels() {
+        let (parent_column, parent_join) = parent_level.map_or_else(
+            || (String::new(), "NULL".to_string()),
+            |parent| {
+                (
+                    format!(", {parent}"),
+                    format!(
+                        "(SELECT p.id FROM crime_categories p
+                          WHERE p.level = '{parent}' AND p.name = nodes.{parent})"
+                    ),
+                )
+            },
+        );
+        duck.execute_batch(&format!(
+            "INSERT INTO crime_categories (id, name, parent_id, severity, level)
+             SELECT
+                 (SELECT COALESCE(MAX(id), 0) FROM crime_categories)
+                     + ROW_NUMBER() OVER (ORDER BY {level}),
+                 {level},
+                 {parent_join},
+                 NULL,
+                 '{level}'
+             FROM (
+                 SELECT DISTINCT {level}{parent_column}
+                 FROM category_taxonomy
+                 WHERE subcategory IN (SELECT subcategory FROM incidents)
+             ) nodes;
+             UPDATE crime_categories
+             SET {level}_id = links.id
+             FROM (
+                 SELECT t.subcategory, n.id
+                 FROM category_taxonomy t
+                 JOIN crime_categories n ON n.level = '{level}' AND n.name = t.{level}
+             ) links
+             WHERE crime_categories.level = 'subcategory'
+               AND crime_categories.name = links.subcategory;"
+        ))?;
+        parent_level = Some(level);
+    }
 
-    log::info!(
-        "Analytics DuckDB database generated: {} ({total_count} incident rows + reference tables)",
-        db_path.display()
-    );
+    Ok(())
+}
+
+/// Adds one `VARCHAR` column per `taxonomy` level to each of `tables` in
+/// `duck` and fills it from the table's `subcategory` (`NULL` for
+/// subcategories outside the taxonomy). Existing level columns are
+/// refilled. Does nothing without a taxonomy.
+///
+/// The taxonomy is first loaded into a temporary `category_taxonomy`
+/// table (`subcategory` plus one column per level), which
+/// [`populate_crime_categories`] reads too. Columns can't be added to a
+/// table once it has an index, so this must run before the tables are
+/// indexed.
+///
+/// # Errors
+///
+/// Returns an error if a table can't be altered or updated.
+fn add_taxonomy_columns(
+    duck: &duckdb::Connection,
+    taxonomy: &Taxonomy,
+    tables: &[&str],
+) -> Result<(), duckdb::Error> {
+    if taxonomy.is_empty() {
+        return Ok(());
+    }
+
+    let levels = taxonomy.levels();
+    let columns: String = levels
+        .iter()
+        .map(|level| format!(", {level} VARCHAR NOT NULL"))
+        .collect();
+    duck.execute_batch(&format!(
+        "CREATE OR REPLACE TEMP TABLE category_taxonomy (
+            subcategory VARCHAR PRIMARY KEY{columns}
+        )"
+    ))?;
+    {
+        let placeholders = vec!["?"; levels.len() + 1].join(", ");
+        let mut stmt = duck.prepare(&format!(
+            "INSERT INTO category_taxonomy VALUES ({placeholders})"
+        ))?;
+        for (subcategory, rollup) in taxonomy.rollups() {
+            stmt.execute(duckdb::params_from_iter(
+                std::iter::once(subcategory).chain(rollup),
+            ))?;
+        }
+    }
+
+    let assignments = levels
+        .iter()
+        .map(|level| format!("{level} = t.{level}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    for table in tables {
+        log::info!("Adding taxonomy rollup columns to {table}...");
+        for level in levels {
+            let exists: bool = duck.query_row(
+                "SELECT COUNT(*) > 0 FROM duckdb_columns()
+                 WHERE table_name = ? AND column_name = ?",
+                [*table, level.as_str()],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                duck.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {level} VARCHAR"))?;
+            }
+        }
+        duck.execute_batch(&format!(
+            "UPDATE {table} SET {assignments}
+             FROM category_taxonomy t
+             WHERE {table}.subcategory = t.subcategory"
+        ))?;
+    }
+    Ok(())
+}
+
+/// Checks that every `neighborhood_id` on the analytics `incidents` table
+/// in `duck` exists in its `neighborhoods` reference table.
+///
+/// Both sides are keyed by [`neighborhood_geoid`]; an orphan means the
+/// incidents were enriched against different boundaries or with a different
+/// ID format, which would otherwise make neighborhood joins silently empty.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::OrphanNeighborhoods`] listing the most frequent
+/// orphaned IDs, or a `DuckDB` error if the query fails.
+fn validate_neighborhood_ids(duck: &duckdb::Connection) -> Result<(), GenerateError> {
+    let mut stmt = duck.prepare(
+        "SELECT i.neighborhood_id, COUNT(*) AS cnt
+         FROM incidents i
+         LEFT JOIN neighborhoods n ON n.id = i.neighborhood_id
+         WHERE i.neighborhood_id IS NOT NULL AND n.id IS NULL
+         GROUP BY i.neighborhood_id
+         ORDER BY cnt DESC, i.neighborhood_id
+         LIMIT ?",
+    )?;
+    #[allow(clippy::cast_possible_wrap)]
+    let mut rows = stmt.query([MAX_REPORTED_ORPHANS as i64])?;
+
+    let mut orphans = Vec::new();
+    while let Some(row) = rows.next()? {
+        let id: String = row.get(0)?;
+        let count: i64 = row.get(1)?;
+        orphans.push((id, u64::try_from(count).unwrap_or(0)));
+    }
+
+    if orphans.is_empty() {
+        Ok(())
+    } else {
+        Err(GenerateError::OrphanNeighborhoods { orphans })
+    }
+}
+
+/// Exports the analytics `incidents` table in `duck` to
+/// [`paths::ANALYTICS_NDJSON_FILE`] in `dir`, one JSON object per line.
+///
+/// `occurred_at` is written as ISO 8601 UTC (`YYYY-MM-DDTHH:MM:SSZ`) and
+/// missing values as JSON `null`. The always-`NULL` legacy ID columns are
+/// omitted.
+///
+/// # Errors
+///
+/// Returns an error if the `COPY` or the final rename fails.
+fn export_analytics_ndjson(duck: &duckdb::Connection, dir: &Path) -> Result<(), GenerateError> {
+    let path = dir.join(paths::ANALYTICS_NDJSON_FILE);
+    let tmp_path = dir.join(format!("{}.tmp", paths::ANALYTICS_NDJSON_FILE));
+
+    log::info!("Exporting analytics incidents to {}...", path.display());
+    duck.execute_batch(&format!(
+        "COPY (
+             SELECT * EXCLUDE (parent_category_id, category_id)
+                 REPLACE (strftime(occurred_at, '%Y-%m-%dT%H:%M:%SZ') AS occurred_at)
+             FROM incidents
+         ) TO '{}' (FORMAT JSON)",
+        tmp_path.to_string_lossy().replace('\'', "''"),
+    ))?;
+    std::fs::rename(&tmp_path, &path)?;
+
+    log::info!("Analytics NDJSON exported: {}", path.display());
     Ok(())
 }
 
@@ -2523,8 +8054,9 @@ fn generate_analytics_db(
 /// Generates a `SQLite` database for boundary name lookups at runtime.
 ///
 /// Creates `boundaries.db` with a single `boundaries` table containing
-/// name/geoid metadata for all boundary types (states, counties, places,
-/// tracts, neighborhoods). Used by `GET /api/boundaries/search` to
+/// name/geoid metadata for the boundary types selected by
+/// `boundary_types` (all of states, counties, places, tracts, and
+/// neighborhoods if `None`). Used by `GET /api/boundaries/search` to
 /// support type-ahead boundary filtering without a live database.
 ///
 /// # Errors
@@ -2534,23 +8066,29 @@ fn generate_analytics_db(
 async fn generate_boundaries_db(
     boundaries_conn: &duckdb::Connection,
     dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+    boundary_types: Option<&[String]>,
+) -> Result<(), GenerateError> {
     use switchy_database::DatabaseValue;
 
-    let db_path = dir.join("boundaries.db");
+    let layers = selected_boundary_layers(boundary_types);
+    let includes = |layer: &str| layers.iter().any(|&(name, _)| name == layer);
 
-    // Remove existing file
-    if db_path.exists() {
-        std::fs::remove_file(&db_path)?;
-    }
+    let output_path = dir.join(paths::BOUNDARIES_DB_FILE);
+    let db_path = begin_atomic_output(&output_path)?;
 
     log::info!("Creating boundaries search SQLite database...");
     let sqlite = switchy_database_connection::init_sqlite_rusqlite(Some(&db_path))
-        .map_err(|e| format!("Failed to open boundaries SQLite: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to open boundaries SQLite: {e}")))?;
 
     // WAL mode + generous busy timeout to avoid "database is locked" errors.
-    sqlite.exec_raw("PRAGMA journal_mode=WAL").await?;
-    sqlite.exec_raw("PRAGMA busy_timeout=5000").await?;
+    sqlite
+        .exec_raw("PRAGMA journal_mode=WAL")
+        .await
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to set journal_mode: {e}")))?;
+    sqlite
+        .exec_raw("PRAGMA busy_timeout=5000")
+        .await
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to set busy_timeout: {e}")))?;
 
     sqlite
         .exec_raw(
@@ -2565,10 +8103,10 @@ async fn generate_boundaries_db(
             )",
         )
         .await
-        .map_err(|e| format!("Failed to create boundaries table: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create boundaries table: {e}")))?;
 
     // States
-    {
+    if includes("states") {
         let mut src_stmt = boundaries_conn
             .prepare("SELECT fips, name, abbr, population FROM census_states ORDER BY fips")?;
         let mut src_rows = src_stmt.query([])?;
@@ -2576,7 +8114,7 @@ async fn generate_boundaries_db(
         let tx = sqlite
             .begin_transaction()
             .await
-            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to begin transaction: {e}")))?;
 
         let mut count = 0u64;
         while let Some(row) = src_rows.next()? {
@@ -2596,17 +8134,17 @@ async fn generate_boundaries_db(
                 ],
             )
             .await
-            .map_err(|e| format!("Failed to insert state boundary: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to insert state boundary: {e}")))?;
             count += 1;
         }
         tx.commit()
             .await
-            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to commit transaction: {e}")))?;
         log::info!("Inserted {count} state boundaries");
     }
 
     // Counties
-    {
+    if includes("counties") {
         let mut src_stmt = boundaries_conn.prepare(
             "SELECT geoid, name, full_name, state_abbr, population
              FROM census_counties ORDER BY geoid",
@@ -2616,7 +8154,7 @@ async fn generate_boundaries_db(
         let tx = sqlite
             .begin_transaction()
             .await
-            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to begin transaction: {e}")))?;
 
         let mut count = 0u64;
         while let Some(row) = src_rows.next()? {
@@ -2637,17 +8175,17 @@ async fn generate_boundaries_db(
                 ],
             )
             .await
-            .map_err(|e| format!("Failed to insert county boundary: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to insert county boundary: {e}")))?;
             count += 1;
         }
         tx.commit()
             .await
-            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to commit transaction: {e}")))?;
         log::info!("Inserted {count} county boundaries");
     }
 
     // Places
-    {
+    if includes("places") {
         let mut src_stmt = boundaries_conn.prepare(
             "SELECT geoid, name, full_name, state_abbr, population
              FROM census_places ORDER BY geoid",
@@ -2657,7 +8195,7 @@ async fn generate_boundaries_db(
         let tx = sqlite
             .begin_transaction()
             .await
-            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to begin transaction: {e}")))?;
 
         let mut count = 0u64;
         while let Some(row) = src_rows.next()? {
@@ -2678,17 +8216,17 @@ async fn generate_boundaries_db(
                 ],
             )
             .await
-            .map_err(|e| format!("Failed to insert place boundary: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to insert place boundary: {e}")))?;
             count += 1;
         }
         tx.commit()
             .await
-            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to commit transaction: {e}")))?;
         log::info!("Inserted {count} place boundaries");
     }
 
     // Tracts
-    {
+    if includes("tracts") {
         let mut src_stmt = boundaries_conn.prepare(
             "SELECT geoid, name, state_abbr, county_name, population
              FROM census_tracts ORDER BY geoid",
@@ -2698,7 +8236,7 @@ async fn generate_boundaries_db(
         let tx = sqlite
             .begin_transaction()
             .await
-            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to begin transaction: {e}")))?;
 
         let mut count = 0u64;
         while let Some(row) = src_rows.next()? {
@@ -2725,17 +8263,17 @@ async fn generate_boundaries_db(
                 ],
             )
             .await
-            .map_err(|e| format!("Failed to insert tract boundary: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to insert tract boundary: {e}")))?;
             count += 1;
         }
         tx.commit()
             .await
-            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to commit transaction: {e}")))?;
         log::info!("Inserted {count} tract boundaries");
     }
 
     // Neighborhoods
-    {
+    if includes("neighborhoods") {
         let mut src_stmt = boundaries_conn
             .prepare("SELECT id, name, city, state FROM neighborhoods ORDER BY id")?;
         let mut src_rows = src_stmt.query([])?;
@@ -2743,7 +8281,7 @@ async fn generate_boundaries_db(
         let tx = sqlite
             .begin_transaction()
             .await
-            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to begin transaction: {e}")))?;
 
         let mut count = 0u64;
         while let Some(row) = src_rows.next()? {
@@ -2751,7 +8289,7 @@ async fn generate_boundaries_db(
             let name: String = row.get::<_, Option<String>>(1)?.unwrap_or_default();
             let city: String = row.get::<_, Option<String>>(2)?.unwrap_or_default();
             let state: String = row.get::<_, Option<String>>(3)?.unwrap_or_default();
-            let geoid = format!("nbhd-{id}");
+            let geoid = neighborhood_geoid(id);
             let full_name = format!("{name}, {city}, {state}");
             tx.exec_raw_params(
                 "INSERT INTO boundaries (type, geoid, name, full_name, state_abbr, population)
@@ -2764,12 +8302,14 @@ async fn generate_boundaries_db(
                 ],
             )
             .await
-            .map_err(|e| format!("Failed to insert neighborhood boundary: {e}"))?;
+            .map_err(|e| {
+                GenerateError::Sqlite(format!("Failed to insert neighborhood boundary: {e}"))
+            })?;
             count += 1;
         }
         tx.commit()
             .await
-            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+            .map_err(|e| GenerateError::Sqlite(format!("Failed to commit transaction: {e}")))?;
         log::info!("Inserted {count} neighborhood boundaries");
     }
 
@@ -2777,21 +8317,24 @@ async fn generate_boundaries_db(
     sqlite
         .exec_raw("CREATE INDEX idx_boundaries_name ON boundaries(type, name COLLATE NOCASE)")
         .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
     sqlite
         .exec_raw("ANALYZE")
         .await
-        .map_err(|e| format!("Failed to run ANALYZE: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to run ANALYZE: {e}")))?;
 
     // Checkpoint the WAL to ensure all data is in the main .db file
     sqlite
         .exec_raw("PRAGMA wal_checkpoint(TRUNCATE)")
         .await
-        .map_err(|e| format!("Failed to checkpoint WAL: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to checkpoint WAL: {e}")))?;
+
+    drop(sqlite);
+    commit_atomic_output(&db_path, &output_path)?;
 
     log::info!(
         "Boundaries search database generated: {}",
-        db_path.display()
+        output_path.display()
     );
     Ok(())
 }
@@ -2809,12 +8352,187 @@ const BOUNDARY_LAYERS: &[(&str, &str)] = &[
     ("neighborhoods", "neighborhoods.geojsonseq"),
 ];
 
+/// Returns the [`BOUNDARY_LAYERS`] selected by `boundary_types` (see
+/// [`GenerateArgs::boundary_types`]), in their standard order.
+fn selected_boundary_layers(
+    boundary_types: Option<&[String]>,
+) -> Vec<(&'static str, &'static str)> {
+    BOUNDARY_LAYERS
+        .iter()
+        .copied()
+        .filter(|(layer, _)| boundary_types.is_none_or(|types| types.iter().any(|t| t == layer)))
+        .collect()
+}
+
+/// Returns the `--boundary-types` selection as recorded in the manifest:
+/// the selected layers in their standard order, or `None` if every layer
+/// is included, so listing them in another order or spelling out all of
+/// them doesn't force a rebuild.
+fn boundary_types_filter(args: &GenerateArgs) -> Option<Vec<String>> {
+    let layers = selected_boundary_layers(args.boundary_types.as_deref());
+    (layers.len() < BOUNDARY_LAYERS.len()).then(|| {
+        layers
+            .iter()
+            .map(|(layer, _)| (*layer).to_string())
+            .collect()
+    })
+}
+
+/// Validates a `--boundary-types` selection: at least one type, each a
+/// layer in [`BOUNDARY_LAYERS`].
+///
+/// # Errors
+///
+/// Returns a description naming the valid types if the selection is empty
+/// or names an unknown type.
+pub fn validate_boundary_types(types: &[String]) -> Result<(), String> {
+    let known: Vec<&str> = BOUNDARY_LAYERS.iter().map(|(layer, _)| *layer).collect();
+    if types.is_empty() {
+        return Err(format!(
+            "boundary types can't be empty (expected some of: {})",
+            known.join(", ")
+        ));
+    }
+    if let Some(unknown) = types.iter().find(|t| !known.contains(&t.as_str())) {
+        return Err(format!(
+            "unknown boundary type '{unknown}' (expected some of: {})",
+            known.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Maximum zoom of the boundaries `PMTiles`.
+const BOUNDARIES_MAX_ZOOM: u8 = 14;
+
+/// Default `--simplify-boundaries` zooms: the highest zoom level at which
+/// each boundary layer is expected to be viewed with full fidelity, used
+/// to derive its simplification tolerance.
+///
+/// Layers that are only meaningful at low zoom (states) can be simplified
+/// aggressively, while small polygons (tracts, places) need a finer
+/// tolerance. Layers not listed here (neighborhoods: hand-drawn and already
+/// coarse) are written as-is.
+const DEFAULT_BOUNDARY_SIMPLIFY_ZOOMS: &[(&str, u8)] = &[
+    ("states", 8),
+    ("counties", 10),
+    ("places", 12),
+    ("tracts", 12),
+];
+
+/// Parses a `--simplify-boundaries` value.
+///
+/// An empty value selects [`DEFAULT_BOUNDARY_SIMPLIFY_ZOOMS`]; otherwise
+/// comma-separated `layer=zoom` pairs (`states=6,tracts=13`) replace the
+/// defaults, so layers left out are not simplified.
+///
+/// # Errors
+///
+/// Returns a description of the problem if a layer isn't one of the
+/// boundary layers or a zoom is above the boundaries `PMTiles` max zoom.
+pub fn parse_simplify_boundaries(s: &str) -> Result<BTreeMap<String, u8>, String> {
+    if s.trim().is_empty() {
+        return Ok(DEFAULT_BOUNDARY_SIMPLIFY_ZOOMS
+            .iter()
+            .map(|&(layer, zoom)| (layer.to_string(), zoom))
+            .collect());
+    }
+
+    s.split(',')
+        .map(|pair| {
+            let (layer, zoom) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected 'layer=zoom', got '{pair}'"))?;
+            let layer = layer.trim();
+            if !BOUNDARY_LAYERS.iter().any(|(name, _)| *name == layer) {
+                let names: Vec<&str> = BOUNDARY_LAYERS.iter().map(|(name, _)| *name).collect();
+                return Err(format!(
+                    "Unknown boundary layer '{layer}': expected one of {names:?}"
+                ));
+            }
+            let zoom = zoom
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|z| *z <= BOUNDARIES_MAX_ZOOM)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid simplification zoom '{zoom}': must be an integer from 0 to {BOUNDARIES_MAX_ZOOM}"
+                    )
+                })?;
+            Ok((layer.to_string(), zoom))
+        })
+        .collect()
+}
+
+/// Returns the Douglas-Peucker tolerance (in degrees) for a layer
+/// simplified for viewing up to `zoom`.
+///
+/// The tolerance is the width of one 256px tile pixel at that zoom, so
+/// removed detail is sub-pixel there.
+fn boundary_simplify_tolerance(zoom: u8) -> f64 {
+    360.0 / (256.0 * f64::from(1u32 << zoom))
+}
+
+/// Simplifies a `GeoJSON` polygon/multipolygon geometry with
+/// Douglas-Peucker at the given tolerance.
+///
+/// Each polygon is simplified independently, so shared borders between
+/// neighbouring features can diverge by up to `tolerance`. Because the
+/// tolerance is sub-pixel at the layer's simplification zoom, the
+/// resulting slivers are invisible at the zooms the layer is designed for;
+/// small gaps when over-zooming past that point are accepted.
+///
+/// Rings that would collapse below a valid ring (4 coordinates) are kept
+/// at full resolution for exteriors and dropped for holes. Non-polygonal
+/// or unparseable geometries are returned unchanged.
+fn simplify_boundary_geometry(geometry: serde_json::Value, tolerance: f64) -> serde_json::Value {
+    use geo::Simplify as _;
+
+    let Ok(parsed) = geojson::Geometry::from_json_value(geometry.clone()) else {
+        return geometry;
+    };
+    let Ok(geom) = geo::Geometry::<f64>::try_from(parsed) else {
+        return geometry;
+    };
+
+    let simplify_polygon = |polygon: &geo::Polygon<f64>| -> geo::Polygon<f64> {
+        let exterior = polygon.exterior().simplify(tolerance);
+        if exterior.0.len() < 4 {
+            return polygon.clone();
+        }
+        let interiors = polygon
+            .interiors()
+            .iter()
+            .map(|ring| ring.simplify(tolerance))
+            .filter(|ring| ring.0.len() >= 4)
+            .collect();
+        geo::Polygon::new(exterior, interiors)
+    };
+
+    let simplified = match &geom {
+        geo::Geometry::Polygon(p) => geo::Geometry::Polygon(simplify_polygon(p)),
+        geo::Geometry::MultiPolygon(mp) => geo::Geometry::MultiPolygon(geo::MultiPolygon(
+            mp.0.iter().map(simplify_polygon).collect(),
+        )),
+        _ => return geometry,
+    };
+
+    serde_json::to_value(geojson::Geometry::new(geojson::Value::from(&simplified)))
+        .unwrap_or(geometry)
+}
+
 /// Generates `boundaries.pmtiles` containing administrative boundary
 /// polygons from the boundaries `DuckDB`.
 ///
-/// Exports 5 `GeoJSONSeq` files (states, counties, places, tracts,
-/// neighborhoods), then runs tippecanoe with multiple named layers
-/// to produce a single `PMTiles` archive.
+/// Exports a `GeoJSONSeq` file per layer selected by `boundary_types`
+/// (all of states, counties, places, tracts, and neighborhoods if `None`)
+/// in parallel (see [`export_boundary_layers`]), then runs tippecanoe
+/// with multiple named layers to produce a single `PMTiles` archive.
+///
+/// Layers with a zoom in `simplify` (see
+/// [`GenerateArgs::simplify_boundaries`]) are pre-simplified to shrink the
+/// intermediate files and reduce tippecanoe's work.
 ///
 /// # Errors
 ///
@@ -2822,19 +8540,16 @@ const BOUNDARY_LAYERS: &[(&str, &str)] = &[
 fn generate_boundaries_pmtiles(
     boundaries_conn: &duckdb::Connection,
     dir: &Path,
+    simplify: Option<&BTreeMap<String, u8>>,
+    boundary_types: Option<&[String]>,
     progress: &Arc<dyn ProgressCallback>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    log::info!("Exporting boundary layers to GeoJSONSeq...");
-
-    export_boundary_layer(boundaries_conn, dir, "states", progress)?;
-    export_boundary_layer(boundaries_conn, dir, "counties", progress)?;
-    export_boundary_layer(boundaries_conn, dir, "places", progress)?;
-    export_boundary_layer(boundaries_conn, dir, "tracts", progress)?;
-    export_boundary_layer(boundaries_conn, dir, "neighborhoods", progress)?;
+) -> Result<(), GenerateError> {
+    let layers = selected_boundary_layers(boundary_types);
+    export_boundary_layers(boundaries_conn, dir, &layers, simplify, progress)?;
 
     log::info!("Running tippecanoe to generate boundaries PMTiles...");
 
-    let output_path = dir.join("boundaries.pmtiles");
+    let output_path = dir.join(paths::BOUNDARIES_PMTILES_FILE);
     let mut cmd = Command::new("tippecanoe");
     cmd.args([
         "-o",
@@ -2843,7 +8558,7 @@ fn generate_boundaries_pmtiles(
         "--no-feature-limit",
         "--no-tile-size-limit",
         "--minimum-zoom=0",
-        "--maximum-zoom=14",
+        &format!("--maximum-zoom={BOUNDARIES_MAX_ZOOM}"),
         "--coalesce-densest-as-needed",
         "--detect-shared-borders",
     ]);
@@ -2854,7 +8569,7 @@ fn generate_boundaries_pmtiles(
 
     // Add each layer as a named-layer with its GeoJSONSeq file
     let mut has_layers = false;
-    for &(layer_name, filename) in BOUNDARY_LAYERS {
+    for &(layer_name, filename) in &layers {
         let layer_path = dir.join(filename);
         let non_empty = layer_path.exists()
             && std::fs::metadata(&layer_path)
@@ -2874,20 +8589,17 @@ fn generate_boundaries_pmtiles(
     if !has_layers {
         log::warn!("No boundary features to tile; skipping boundaries PMTiles generation");
         // Clean up empty layer files
-        for &(_, filename) in BOUNDARY_LAYERS {
+        for &(_, filename) in &layers {
             let path = dir.join(filename);
             std::fs::remove_file(&path).ok();
         }
         return Ok(());
     }
 
-    let status = cmd.status()?;
-    if !status.success() {
-        return Err("tippecanoe failed for boundaries".into());
-    }
+    run_tippecanoe(&mut cmd, &output_path)?;
 
     // Clean up intermediate GeoJSONSeq files
-    for &(_, filename) in BOUNDARY_LAYERS {
+    for &(_, filename) in &layers {
         let path = dir.join(filename);
         if path.exists()
             && let Err(e) = std::fs::remove_file(&path)
@@ -2900,17 +8612,85 @@ fn generate_boundaries_pmtiles(
     Ok(())
 }
 
+/// Exports every layer in `layers` (name and file, as in
+/// [`BOUNDARY_LAYERS`]) with [`export_boundary_layer`], overlapping their
+/// reads and serialization.
+///
+/// Layers are handed out to as many worker threads as the global `rayon`
+/// pool has (see [`init_thread_pool`]), at most one per layer. `DuckDB` connections can't be shared across threads, so
+/// each worker gets its own connection cloned from `boundaries_conn`.
+/// Clones share the one open database instance, and with it the buffer
+/// pool and `memory_limit`, so the parallelism doesn't multiply `DuckDB`'s
+/// memory the way opening the file once per worker would.
+///
+/// # Errors
+///
+/// Returns the first error from any layer; the other workers still finish
+/// their current layer before this returns.
+fn export_boundary_layers(
+    boundaries_conn: &duckdb::Connection,
+    dir: &Path,
+    layers: &[(&str, &str)],
+    simplify: Option<&BTreeMap<String, u8>>,
+    progress: &Arc<dyn ProgressCallback>,
+) -> Result<(), GenerateError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let workers = rayon::current_num_threads().min(layers.len());
+    log::info!("Exporting boundary layers to GeoJSONSeq ({workers} threads)...");
+
+    let conns = (0..workers)
+        .map(|_| boundaries_conn.try_clone())
+        .collect::<Result<Vec<_>, _>>()?;
+    let next_layer = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = conns
+            .into_iter()
+            .map(|conn| {
+                let next_layer = &next_layer;
+                scope.spawn(move || -> Result<(), GenerateError> {
+                    while let Some(&(layer, _)) =
+                        layers.get(next_layer.fetch_add(1, Ordering::Relaxed))
+                    {
+                        let tolerance = simplify
+                            .and_then(|zooms| zooms.get(layer))
+                            .map(|&zoom| boundary_simplify_tolerance(zoom));
+                        if let Err(e) =
+                            export_boundary_layer(&conn, dir, layer, tolerance, progress)
+                        {
+                            // Stop the other workers from starting new layers.
+                            next_layer.store(layers.len(), Ordering::Relaxed);
+                            return Err(e);
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+
+        handles.into_iter().try_for_each(|handle| {
+            handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    })
+}
+
 /// Exports a single boundary layer from the boundaries `DuckDB` as
 /// `GeoJSONSeq`.
 ///
 /// Each feature is a polygon/multipolygon with name/identifier properties.
+/// If `simplify_tolerance` is set, geometries are simplified with
+/// [`simplify_boundary_geometry`] before being written.
 #[allow(clippy::too_many_lines)]
 fn export_boundary_layer(
     boundaries_conn: &duckdb::Connection,
     dir: &Path,
     layer: &str,
+    simplify_tolerance: Option<f64>,
     progress: &Arc<dyn ProgressCallback>,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), GenerateError> {
     let filename = format!("{layer}.geojsonseq");
     let output_path = dir.join(&filename);
     let file = std::fs::File::create(&output_path)?;
@@ -2956,7 +8736,11 @@ fn export_boundary_layer(
              WHERE boundary_geojson IS NOT NULL
              ORDER BY id"
         }
-        _ => return Err(format!("Unknown boundary layer: {layer}").into()),
+        _ => {
+            return Err(GenerateError::InvalidArgs(format!(
+                "Unknown boundary layer: {layer}"
+            )));
+        }
     };
 
     // Check row count first to avoid DuckDB type-inference edge cases on
@@ -2967,7 +8751,11 @@ fn export_boundary_layer(
         "places" => "SELECT COUNT(*) FROM census_places WHERE boundary_geojson IS NOT NULL",
         "tracts" => "SELECT COUNT(*) FROM census_tracts WHERE boundary_geojson IS NOT NULL",
         "neighborhoods" => "SELECT COUNT(*) FROM neighborhoods WHERE boundary_geojson IS NOT NULL",
-        _ => return Err(format!("Unknown boundary layer: {layer}").into()),
+        _ => {
+            return Err(GenerateError::InvalidArgs(format!(
+                "Unknown boundary layer: {layer}"
+            )));
+        }
     };
     let total: u64 = boundaries_conn.query_row(count_query, [], |row| row.get(0))?;
 
@@ -2996,7 +8784,10 @@ fn export_boundary_layer(
             continue;
         }
 
-        let geometry: serde_json::Value = serde_json::from_str(&geojson_str)?;
+        let mut geometry: serde_json::Value = serde_json::from_str(&geojson_str)?;
+        if let Some(tolerance) = simplify_tolerance {
+            geometry = simplify_boundary_geometry(geometry, tolerance);
+        }
 
         let properties = match layer {
             "states" => {
@@ -3064,12 +8855,12 @@ fn export_boundary_layer(
                 })
             }
             "neighborhoods" => {
-                let id: i64 = row.get(0)?;
+                let id: i32 = row.get(0)?;
                 let name: String = row.get::<_, Option<String>>(1)?.unwrap_or_default();
                 let city: String = row.get::<_, Option<String>>(2)?.unwrap_or_default();
                 let state: String = row.get::<_, Option<String>>(3)?.unwrap_or_default();
                 serde_json::json!({
-                    "nbhd_id": format!("nbhd-{id}"),
+                    "nbhd_id": neighborhood_geoid(id),
                     "name": name,
                     "city": city,
                     "state": state,
@@ -3091,6 +8882,771 @@ fn export_boundary_layer(
 
     writer.flush()?;
     progress.inc(count);
-    log::info!("Exported {count} {layer} boundary features to {filename}");
+    if let Some(tolerance) = simplify_tolerance {
+        log::info!(
+            "Exported {count} {layer} boundary features to {filename} \
+             (simplified, tolerance {tolerance:.6}°)"
+        );
+    } else {
+        log::info!("Exported {count} {layer} boundary features to {filename}");
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_h3_boundaries_match_serial_computation() {
+        let mut cells: Vec<i64> = h3o::CellIndex::base_cells()
+            .flat_map(|cell| cell.children(h3o::Resolution::Two))
+            .map(|cell| i64::try_from(u64::from(cell)).unwrap())
+            .collect();
+        // Invalid indexes are skipped, as in the serial loop.
+        cells.push(0);
+
+        let serial: Vec<(i64, H3CellBoundary)> = cells
+            .iter()
+            .filter_map(|&raw| {
+                let cell = h3o::CellIndex::try_from(u64::try_from(raw).ok()?).ok()?;
+                Some((raw, h3_cell_boundary(cell)))
+            })
+            .collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let parallel = pool.install(|| h3_cell_boundaries(&cells));
+
+        assert_eq!(parallel.len(), cells.len() - 1);
+        assert!(parallel.iter().any(|(_, b)| b.is_pentagon));
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    fn source_license_carries_registry_attribution() {
+        let registry = all_sources();
+        let def = registry
+            .iter()
+            .find(|def| def.license().attribution_required)
+            .expect("at least one source requires attribution");
+        let license = source_license_json(def);
+        assert_eq!(license["type"], def.license().license_type.as_str());
+        assert_eq!(license["attributionRequired"], true);
+        assert!(
+            license["attributionText"]
+                .as_str()
+                .is_some_and(|text| !text.is_empty())
+        );
+    }
+
+    #[test]
+    fn slow_outputs_are_flagged_per_record() {
+        // 1M records in 60s is 60µs/record: fine.
+        assert!(!is_slow_output(Duration::from_secs(60), 1_000_000));
+        // 100k records in 60s is 600µs/record: slow.
+        assert!(is_slow_output(Duration::from_secs(60), 100_000));
+        // Short runs and record-less outputs are never flagged.
+        assert!(!is_slow_output(Duration::from_secs(5), 10));
+        assert!(!is_slow_output(Duration::from_secs(600), 0));
+    }
+
+    #[test]
+    fn sidebar_batch_size_adapts_within_bounds() {
+        // Huge rows: halve, but never below the floor.
+        assert_eq!(
+            next_sidebar_batch_size(BATCH_SIZE, SIDEBAR_TARGET_BATCH_BYTES * 3),
+            BATCH_SIZE / 2
+        );
+        assert_eq!(
+            next_sidebar_batch_size(SIDEBAR_MIN_BATCH_SIZE, usize::MAX),
+            SIDEBAR_MIN_BATCH_SIZE
+        );
+
+        // Small rows: double, but never above the ceiling.
+        assert_eq!(next_sidebar_batch_size(BATCH_SIZE, 1024), BATCH_SIZE * 2);
+        assert_eq!(
+            next_sidebar_batch_size(SIDEBAR_MAX_BATCH_SIZE, 0),
+            SIDEBAR_MAX_BATCH_SIZE
+        );
+
+        // Between half and full target: keep.
+        assert_eq!(
+            next_sidebar_batch_size(BATCH_SIZE, SIDEBAR_TARGET_BATCH_BYTES * 3 / 4),
+            BATCH_SIZE
+        );
+    }
+
+    #[test]
+    fn tract_rate_is_null_without_population() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE tract_category_counts (tract_geoid VARCHAR, category VARCHAR, cnt BIGINT);
+             INSERT INTO tract_category_counts VALUES
+                 ('A', 'PROPERTY', 3), ('A', 'VIOLENT', 1), ('B', 'PROPERTY', 2), ('C', 'VIOLENT', 1);
+             CREATE TABLE tract_population (geoid VARCHAR, population BIGINT);
+             INSERT INTO tract_population VALUES ('A', 2000), ('B', 0);",
+        )
+        .unwrap();
+
+        create_tract_counts(&duck).unwrap();
+
+        let mut stmt = duck
+            .prepare("SELECT tract_geoid, total, rate FROM tract_counts ORDER BY tract_geoid")
+            .unwrap();
+        let rows: Vec<(String, i64, Option<f64>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("A".to_string(), 4, Some(2.0)),
+                ("B".to_string(), 2, None),
+                ("C".to_string(), 1, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn output_catalog_covers_every_output() {
+        let names: Vec<&str> = output_catalog().iter().map(|info| info.name).collect();
+        for name in [
+            OUTPUT_INCIDENTS_PMTILES,
+            OUTPUT_INCIDENTS_DB,
+            OUTPUT_COUNT_DB,
+            OUTPUT_H3_DB,
+            OUTPUT_METADATA,
+            OUTPUT_BOUNDARIES_PMTILES,
+            OUTPUT_BOUNDARIES_DB,
+            OUTPUT_ANALYTICS_DB,
+            OUTPUT_CHOROPLETH_DB,
+        ] {
+            assert_eq!(
+                names.iter().filter(|&&n| n == name).count(),
+                1,
+                "{name} must appear exactly once"
+            );
+        }
+        assert_eq!(names.len(), 9, "catalog has an output without a constant");
+
+        // The R2 sync lists must know every file the catalog produces.
+        for info in output_catalog() {
+            assert!(
+                paths::GENERATED_FILES.contains(&info.file)
+                    || paths::BOUNDARY_FILES.contains(&info.file)
+                    || paths::OPTIONAL_GENERATED_FILES.contains(&info.file),
+                "{} is not synced to R2",
+                info.file
+            );
+        }
+
+        // Opt-in outputs stay out of `all`, and only sync when present.
+        let defaults = default_outputs();
+        for info in output_catalog() {
+            assert_eq!(defaults.contains(&info.name), !info.opt_in, "{}", info.name);
+            if info.opt_in {
+                assert!(
+                    paths::OPTIONAL_GENERATED_FILES.contains(&info.file),
+                    "{} is opt-in but always synced",
+                    info.file
+                );
+            }
+        }
+        assert!(!defaults.contains(&OUTPUT_CHOROPLETH_DB));
+
+        // Partitions hold every incident-derived output.
+        let incident_outputs: Vec<&str> = output_catalog()
+            .iter()
+            .filter(|info| info.scans_incidents)
+            .map(|info| info.name)
+            .collect();
+        assert_eq!(incident_outputs, PARTITION_OUTPUTS);
+    }
+
+    #[test]
+    fn h3_stats_sum_each_cell_across_dimensions() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE h3_counts (h3_index UBIGINT, resolution TINYINT, category VARCHAR, cnt INTEGER);
+             INSERT INTO h3_counts VALUES
+                 (10, 4, 'THEFT', 3), (10, 4, 'ASSAULT', 2),
+                 (11, 4, 'THEFT', 1),
+                 (20, 5, 'THEFT', 1), (21, 5, 'THEFT', 2), (22, 5, 'THEFT', 2);",
+        )
+        .unwrap();
+
+        let stats = h3_stats(&duck).unwrap();
+        assert_eq!(
+            stats,
+            vec![
+                H3ResolutionStats {
+                    resolution: 4,
+                    cells: 2,
+                    total: 6,
+                    max_per_cell: 5,
+                    median_per_cell: 1,
+                    densest_cell: "a".to_string(),
+                    suppressed_cells: 0,
+                    suppressed_incidents: 0,
+                },
+                H3ResolutionStats {
+                    resolution: 5,
+                    cells: 3,
+                    total: 5,
+                    max_per_cell: 2,
+                    median_per_cell: 2,
+                    densest_cell: "15".to_string(),
+                    suppressed_cells: 0,
+                    suppressed_incidents: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn atomic_output_replaces_only_on_commit() {
+        let dir = std::env::temp_dir().join(format!("crime_map_atomic_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("counts.duckdb");
+        std::fs::write(&path, "previous").unwrap();
+        std::fs::write(dir.join("counts.duckdb.wal"), "previous wal").unwrap();
+        // Left behind by an interrupted build.
+        std::fs::write(dir.join("counts.duckdb.tmp"), "partial").unwrap();
+        std::fs::write(dir.join("counts.duckdb.tmp.wal"), "partial wal").unwrap();
+
+        let tmp_path = begin_atomic_output(&path).unwrap();
+        assert!(!tmp_path.exists());
+        assert!(!dir.join("counts.duckdb.tmp.wal").exists());
+
+        // Until committed, the previous output stays in place.
+        std::fs::write(&tmp_path, "new").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "previous");
+
+        commit_atomic_output(&tmp_path, &path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert!(!tmp_path.exists());
+        assert!(!dir.join("counts.duckdb.wal").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn ingest_boundaries_command_scopes_states() {
+        let command = ingest_boundaries_command(&["11".to_string(), "24".to_string()]);
+        assert!(command.contains("cargo ingest tracts --states 11,24"));
+        assert!(command.contains("cargo ingest states &&"));
+        assert!(!ingest_boundaries_command(&[]).contains("--states"));
+    }
+
+    #[test]
+    fn month_start_truncates_high_water_marks() {
+        assert_eq!(
+            month_start("2024-04-11 18:20:00").as_deref(),
+            Some("2024-04-01 00:00:00")
+        );
+        assert_eq!(
+            month_start("2024-12-31").as_deref(),
+            Some("2024-12-01 00:00:00")
+        );
+        assert_eq!(month_start("04/11/2024"), None);
+    }
+
+    #[test]
+    fn source_schema_hash_tracks_normalization_not_row_count() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE incidents (parent_category VARCHAR, category VARCHAR, severity INTEGER);
+             INSERT INTO incidents VALUES ('PROPERTY', 'THEFT', 2), ('VIOLENT', 'ASSAULT', 4)",
+        )
+        .unwrap();
+        let before = source_schema_hash(&duck).unwrap();
+
+        // More rows with already-seen tuples don't change the hash.
+        duck.execute_batch("INSERT INTO incidents VALUES ('PROPERTY', 'THEFT', 2)")
+            .unwrap();
+        assert_eq!(source_schema_hash(&duck).unwrap(), before);
+
+        // Re-normalizing a category in place does.
+        duck.execute_batch("UPDATE incidents SET category = 'LARCENY' WHERE category = 'THEFT'")
+            .unwrap();
+        assert_ne!(source_schema_hash(&duck).unwrap(), before);
+    }
+
+    #[test]
+    fn count_timeseries_sums_each_source_category_and_month() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE count_summary (
+                 cell_lng INTEGER, cell_lat INTEGER, source_id VARCHAR, category VARCHAR,
+                 subcategory VARCHAR, day VARCHAR, cnt BIGINT
+             );
+             INSERT INTO count_summary VALUES
+                 (1, 1, 's', 'PROPERTY', 'THEFT', '2024-06-30', 2),
+                 (2, 2, 's', 'PROPERTY', 'BURGLARY', '2024-06-01', 3),
+                 (1, 1, 's', 'PROPERTY', 'THEFT', '2024-05-31', 4),
+                 (1, 1, 't', 'PROPERTY', 'THEFT', '2024-06-15', 5),
+                 (2, 2, 's', 'VIOLENT', 'ASSAULT', NULL, 7)",
+        )
+        .unwrap();
+        create_count_timeseries(&duck).unwrap();
+
+        let series: Vec<(String, String, String, i64)> = duck
+            .prepare("SELECT source_id, category, month, cnt FROM count_timeseries")
+            .unwrap()
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let row = |source: &str, month: &str, cnt| {
+            (
+                source.to_string(),
+                "PROPERTY".to_string(),
+                month.to_string(),
+                cnt,
+            )
+        };
+        assert_eq!(
+            series,
+            vec![
+                row("s", "2024-05", 4),
+                row("s", "2024-06", 5),
+                row("t", "2024-06", 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn count_rolling_windows_end_at_latest_day() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE count_summary (
+                 cell_lng INTEGER, cell_lat INTEGER, source_id VARCHAR, category VARCHAR,
+                 subcategory VARCHAR, day VARCHAR, cnt BIGINT
+             );
+             INSERT INTO count_summary VALUES
+                 (1, 1, 's', 'PROPERTY', 'THEFT', '2024-06-30', 2),
+                 (1, 1, 's', 'PROPERTY', 'THEFT', '2024-06-01', 3),
+                 (1, 1, 's', 'PROPERTY', 'THEFT', '2024-05-31', 4),
+                 (1, 1, 's', 'PROPERTY', 'THEFT', '2023-07-02', 5),
+                 (1, 1, 's', 'PROPERTY', 'THEFT', '2023-07-01', 6),
+                 (2, 2, 's', 'VIOLENT', 'ASSAULT', NULL, 7)",
+        )
+        .unwrap();
+        create_count_rolling(&duck).unwrap();
+
+        let windows: Vec<(i32, String, i64)> = duck
+            .prepare(
+                "SELECT window_days, as_of, cnt FROM count_summary_rolling
+                 ORDER BY window_days",
+            )
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let as_of = "2024-06-30".to_string();
+        // 30 days ending 2024-06-30 start on 2024-06-01; 365 days (over a
+        // leap day) start on 2023-07-02.
+        assert_eq!(
+            windows,
+            vec![
+                (30, as_of.clone(), 5),
+                (90, as_of.clone(), 9),
+                (365, as_of, 14),
+            ]
+        );
+    }
+
+    #[test]
+    fn date_range_merges_across_sources_and_ignores_invalid_coordinates() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE incidents (
+                 has_coordinates BOOLEAN, longitude DOUBLE, latitude DOUBLE,
+                 occurred_at TIMESTAMP
+             );
+             INSERT INTO incidents VALUES
+                 (TRUE, -87.6, 41.9, '2024-03-01 12:00:00'),
+                 (TRUE, -87.6, 41.9, '2024-05-01 08:30:00'),
+                 (TRUE, -87.6, 41.9, NULL),
+                 (FALSE, 0.0, 0.0, '2019-01-01 00:00:00')",
+        )
+        .unwrap();
+        let filter = "has_coordinates = TRUE
+               AND longitude BETWEEN -180 AND 180
+               AND latitude BETWEEN -90 AND 90";
+        let (src_min, src_max) = source_date_range(&duck, filter).unwrap();
+        assert_eq!(src_min.as_deref(), Some("2024-03-01 12:00:00"));
+        assert_eq!(src_max.as_deref(), Some("2024-05-01 08:30:00"));
+
+        let (mut min, mut max) = (None, None);
+        merge_date_range(&mut min, &mut max, src_min, src_max);
+        merge_date_range(
+            &mut min,
+            &mut max,
+            Some("2023-12-31 23:00:00".to_string()),
+            Some("2024-04-01 00:00:00".to_string()),
+        );
+        merge_date_range(&mut min, &mut max, None, None);
+        assert_eq!(min.as_deref(), Some("2023-12-31 23:00:00"));
+        assert_eq!(max.as_deref(), Some("2024-05-01 08:30:00"));
+    }
+
+    #[test]
+    fn tract_coverage_counts_only_enriched_incidents() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE incidents (enriched BOOLEAN, census_tract_geoid VARCHAR);
+             INSERT INTO incidents VALUES
+                 (TRUE, '17031839100'), (TRUE, NULL), (TRUE, NULL), (FALSE, NULL)",
+        )
+        .unwrap();
+        assert_eq!(tract_coverage_counts(&duck, "TRUE").unwrap(), (3, 1));
+
+        let coverage = TractCoverage {
+            source_id: "chicago_pd".to_string(),
+            enriched: 3,
+            attributed: 1,
+            state_fips: Some("17".to_string()),
+        };
+        assert!((coverage.fraction() - 1.0 / 3.0).abs() < 1e-9);
+        let msg = unattributed_message(&[coverage], 0.9);
+        assert!(msg.contains("IL tracts likely missing"));
+        assert!(msg.contains(
+            "cargo ingest tracts --states 17 && cargo ingest enrich --force --sources chicago_pd"
+        ));
+
+        let empty = TractCoverage {
+            source_id: "empty".to_string(),
+            enriched: 0,
+            attributed: 0,
+            state_fips: None,
+        };
+        assert!((empty.fraction() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn validate_neighborhood_ids_reports_orphans() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(&format!(
+            "CREATE TABLE neighborhoods (id VARCHAR PRIMARY KEY, name VARCHAR);
+             INSERT INTO neighborhoods VALUES ('{}', 'Loop');
+             CREATE TABLE incidents (neighborhood_id VARCHAR);
+             INSERT INTO incidents VALUES ('{}'), (NULL)",
+            neighborhood_geoid(1),
+            neighborhood_geoid(1),
+        ))
+        .unwrap();
+        validate_neighborhood_ids(&duck).unwrap();
+
+        // A raw integer ID instead of `nbhd-{id}` is an orphan.
+        duck.execute_batch("INSERT INTO incidents VALUES ('1'), ('1')")
+            .unwrap();
+        let err = validate_neighborhood_ids(&duck).unwrap_err();
+        assert!(matches!(
+            err,
+            GenerateError::OrphanNeighborhoods { ref orphans } if orphans == &[("1".to_string(), 2)]
+        ));
+    }
+
+    #[test]
+    fn time_dimensions_treats_midnight_as_unknown_hour() {
+        // 2024-03-10 was a Sunday.
+        assert_eq!(
+            time_dimensions(Some("2024-03-10 14:30:00+00")),
+            (Some(0), Some(14))
+        );
+        assert_eq!(
+            time_dimensions(Some("2024-03-16 00:00:00+00")),
+            (Some(6), None)
+        );
+        assert_eq!(time_dimensions(Some("2024-03-11")), (Some(1), None));
+        assert_eq!(time_dimensions(None), (None, None));
+    }
+
+    #[test]
+    fn localize_occurred_at_shifts_day_but_keeps_date_only() {
+        let chicago = chrono_tz::America::Chicago;
+        // 03:30 UTC on Mar 10 is still the evening of Mar 9 in Chicago.
+        assert_eq!(
+            localize_occurred_at("2024-03-10 03:30:00+00", chicago).as_deref(),
+            Some("2024-03-09 21:30:00")
+        );
+        assert_eq!(
+            localize_occurred_at("2024-07-04 18:00:00.250+00", chicago).as_deref(),
+            Some("2024-07-04 13:00:00")
+        );
+        assert_eq!(
+            localize_occurred_at("2024-03-16 00:00:00+00", chicago),
+            None
+        );
+    }
+
+    #[test]
+    fn build_health_reports_data_age_per_source() {
+        let metadata = serde_json::json!({
+            "minDate": "2020-01-01 00:00:00",
+            "maxDate": "2024-03-10 23:59:00",
+            "sources": [
+                { "id": "fresh", "recordCount": 10, "maxDate": "2024-03-10 23:59:00" },
+                { "id": "stale", "recordCount": 5, "maxDate": "2024-01-01 08:00:00+00" },
+                { "id": "empty", "recordCount": 0, "maxDate": null },
+            ],
+        });
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 12).unwrap();
+
+        let health = build_health(&metadata, today);
+
+        assert_eq!(health["dataAgeDays"], 2);
+        assert_eq!(health["totalRecords"], 15);
+        assert_eq!(health["manifestVersion"], MANIFEST_VERSION);
+        assert_eq!(health["sources"][0]["dataAgeDays"], 2);
+        assert_eq!(health["sources"][1]["dataAgeDays"], 71);
+        assert!(health["sources"][2]["dataAgeDays"].is_null());
+    }
+
+    #[test]
+    fn h3_cell_boundary_flags_pentagon_and_keeps_exact_ring() {
+        // Base cell 4 is one of the 12 resolution-0 pentagons.
+        let cell = h3o::CellIndex::try_from(0x0800_9fff_ffff_ffff_u64).unwrap();
+        let boundary = h3_cell_boundary(cell);
+
+        assert!(boundary.is_pentagon);
+        let exact: Vec<[f64; 2]> =
+            serde_json::from_str(boundary.exact.as_deref().unwrap()).unwrap();
+        assert_eq!(exact.len(), 5);
+        // The sixth padded vertex repeats the fifth.
+        assert_eq!(boundary.padded[5], boundary.padded[4]);
+    }
+
+    #[test]
+    fn h3_cell_boundary_hexagon_has_no_exact_ring() {
+        let cell = h3o::LatLng::new(41.88, -87.63)
+            .unwrap()
+            .to_cell(h3o::Resolution::Eight);
+        let boundary = h3_cell_boundary(cell);
+
+        assert!(!boundary.is_pentagon);
+        assert!(boundary.exact.is_none());
+    }
+
+    #[test]
+    fn geojsonseq_shards_are_stable_tile_aligned_and_balanced() {
+        assert_eq!(geojsonseq_shard(-87.63, 41.88, 1), 0);
+
+        // Points in the same z10 tile share a shard.
+        let shard = geojsonseq_shard(-87.63, 41.88, 8);
+        assert_eq!(geojsonseq_shard(-87.631, 41.881, 8), shard);
+        assert_eq!(geojsonseq_shard(-87.63, 41.88, 8), shard);
+
+        // A continental grid spreads roughly evenly over the shards.
+        let mut counts = [0_u32; 8];
+        for i in 0..200 {
+            for j in 0..100 {
+                let lng = -125.0 + f64::from(i) * 0.3;
+                let lat = 25.0 + f64::from(j) * 0.25;
+                counts[geojsonseq_shard(lng, lat, 8) as usize] += 1;
+            }
+        }
+        let expected = 200 * 100 / 8;
+        for count in counts {
+            assert!(
+                count > expected * 8 / 10 && count < expected * 12 / 10,
+                "unbalanced shards: {counts:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn incidents_intermediate_names() {
+        assert!(is_incidents_intermediate("incidents.geojsonseq"));
+        assert!(is_incidents_intermediate("incidents-007.geojsonseq"));
+        assert!(!is_incidents_intermediate("incidents-.geojsonseq"));
+        assert!(!is_incidents_intermediate("incidents-abc.geojsonseq"));
+        assert!(!is_incidents_intermediate("states.geojsonseq"));
+
+        let dir = Path::new("out");
+        assert_eq!(
+            geojsonseq_shard_paths(dir, 1),
+            vec![dir.join("incidents.geojsonseq")]
+        );
+        let sharded = geojsonseq_shard_paths(dir, 3);
+        assert_eq!(sharded.len(), 3);
+        assert_eq!(sharded[2], dir.join("incidents-002.geojsonseq"));
+    }
+
+    #[test]
+    fn sparse_h3_cells_are_suppressed_per_resolution() {
+        let duck = duckdb::Connection::open_in_memory().unwrap();
+        duck.execute_batch(
+            "CREATE TABLE h3_counts (h3_index UBIGINT, resolution TINYINT, category VARCHAR, cnt INTEGER);
+             INSERT INTO h3_counts VALUES
+                 (10, 4, 'THEFT', 1), (10, 4, 'ASSAULT', 1),
+                 (11, 4, 'THEFT', 1),
+                 (20, 5, 'THEFT', 1), (21, 5, 'THEFT', 3);",
+        )
+        .unwrap();
+
+        let thresholds = parse_h3_min_cell_count("4=2").unwrap();
+        let suppressed = suppress_sparse_h3_cells(&duck, &thresholds).unwrap();
+        assert_eq!(suppressed, BTreeMap::from([(4, (1, 1))]));
+
+        let remaining: Vec<(u64, i32)> = duck
+            .prepare("SELECT DISTINCT h3_index, resolution::INTEGER FROM h3_counts ORDER BY 1")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, vec![(10, 4), (20, 5), (21, 5)]);
+
+        assert_eq!(
+            parse_h3_min_cell_count("3").unwrap().len(),
+            H3_RESOLUTIONS.len()
+        );
+        assert!(parse_h3_min_cell_count("12=3").is_err());
+        assert!(parse_h3_min_cell_count("9=0").is_err());
+    }
+
+    #[test]
+    fn boundary_simplification_drops_sub_tolerance_vertices() {
+        let square = serde_json::json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [0.5, 0.000_001], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]],
+        });
+        let simplified = simplify_boundary_geometry(square, 0.01);
+        assert_eq!(simplified["type"], "Polygon");
+        assert_eq!(
+            simplified["coordinates"],
+            serde_json::json!([[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]])
+        );
+
+        // Each part of a multipolygon is simplified on its own.
+        let multi = serde_json::json!({
+            "type": "MultiPolygon",
+            "coordinates": [
+                [[[0.0, 0.0], [0.5, 0.000_001], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]],
+                [[[2.0, 0.0], [3.0, 0.0], [3.0, 0.5], [3.000_001, 1.0], [2.0, 1.0], [2.0, 0.0]]],
+            ],
+        });
+        let simplified = simplify_boundary_geometry(multi, 0.01);
+        let parts = simplified["coordinates"].as_array().unwrap();
+        assert_eq!(parts.len(), 2);
+        assert!(
+            parts
+                .iter()
+                .all(|part| part[0].as_array().unwrap().len() == 5)
+        );
+    }
+
+    #[test]
+    fn boundary_simplification_keeps_collapsing_exteriors_and_drops_holes() {
+        let sliver = serde_json::json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 0.001], [0.0, 0.0]]],
+        });
+        assert_eq!(simplify_boundary_geometry(sliver.clone(), 0.01), sliver);
+
+        let with_hole = serde_json::json!({
+            "type": "Polygon",
+            "coordinates": [
+                [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]],
+                [[5.0, 5.0], [6.0, 5.0], [6.0, 5.001], [5.0, 5.0]],
+            ],
+        });
+        let simplified = simplify_boundary_geometry(with_hole, 0.01);
+        assert_eq!(simplified["coordinates"].as_array().unwrap().len(), 1);
+
+        let point = serde_json::json!({ "type": "Point", "coordinates": [1.0, 2.0] });
+        assert_eq!(simplify_boundary_geometry(point.clone(), 0.01), point);
+        let garbage = serde_json::json!({ "type": "Polygon" });
+        assert_eq!(simplify_boundary_geometry(garbage.clone(), 0.01), garbage);
+    }
+
+    #[test]
+    fn simplify_boundaries_accepts_defaults_or_layer_zooms() {
+        let defaults = parse_simplify_boundaries("").unwrap();
+        assert_eq!(defaults.len(), DEFAULT_BOUNDARY_SIMPLIFY_ZOOMS.len());
+        assert_eq!(defaults.get("states"), Some(&8));
+
+        assert_eq!(
+            parse_simplify_boundaries("states=6, tracts=13").unwrap(),
+            BTreeMap::from([("states".to_string(), 6), ("tracts".to_string(), 13)])
+        );
+        assert!(parse_simplify_boundaries("zips=8").is_err());
+        assert!(parse_simplify_boundaries("states=15").is_err());
+        assert!(parse_simplify_boundaries("states").is_err());
+
+        assert!((boundary_simplify_tolerance(0) - 360.0 / 256.0).abs() < f64::EPSILON);
+        assert!(
+            (boundary_simplify_tolerance(8) * 256.0 - boundary_simplify_tolerance(0)).abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn boundary_type_accepts_only_its_geoid_shape() {
+        assert!(BoundaryType::State.accepts("17"));
+        assert!(BoundaryType::County.accepts("17031"));
+        assert!(BoundaryType::Place.accepts("1714000"));
+        assert!(BoundaryType::Tract.accepts("17031839100"));
+        assert!(BoundaryType::Neighborhood.accepts(&neighborhood_geoid(12)));
+
+        assert!(!BoundaryType::Tract.accepts("17031"));
+        assert!(!BoundaryType::County.accepts("1703x"));
+        assert!(!BoundaryType::Neighborhood.accepts("12"));
+        assert!(!BoundaryType::State.accepts("17' OR 1=1 --"));
+
+        assert_eq!(
+            parse_boundary_type("tract").map(BoundaryType::column),
+            Ok("census_tract_geoid")
+        );
+        assert!(parse_boundary_type("zip").is_err());
+        assert_eq!(parse_extract_format("parquet"), Ok(ExtractFormat::Parquet));
+    }
+
+    #[test]
+    fn boundary_types_select_layers_in_standard_order() {
+        let types = vec!["neighborhoods".to_string(), "states".to_string()];
+        assert!(validate_boundary_types(&types).is_ok());
+        assert_eq!(
+            selected_boundary_layers(Some(types.as_slice()))
+                .iter()
+                .map(|(layer, _)| *layer)
+                .collect::<Vec<_>>(),
+            vec!["states", "neighborhoods"]
+        );
+        assert_eq!(selected_boundary_layers(None).len(), BOUNDARY_LAYERS.len());
+
+        assert!(validate_boundary_types(&[]).is_err());
+        assert!(
+            validate_boundary_types(&["tract".to_string()])
+                .unwrap_err()
+                .starts_with("unknown boundary type 'tract'")
+        );
+    }
+
+    #[test]
+    fn feature_collection_is_valid_geojson() {
+        let features = [
+            serde_json::json!({"type": "Feature", "properties": {"sid": "1"}}),
+            serde_json::json!({"type": "Feature", "properties": {"sid": "2"}}),
+        ];
+        for features in [&features[..], &[]] {
+            let mut out = Vec::new();
+            write_feature_collection(features, &mut out).unwrap();
+            let collection: serde_json::Value = serde_json::from_slice(&out).unwrap();
+            assert_eq!(collection["type"], "FeatureCollection");
+            assert_eq!(
+                collection["features"].as_array().unwrap().as_slice(),
+                features
+            );
+        }
+    }
+}
@@ -8,18 +8,39 @@
 //! `crime_map_generate`. When invoked without a subcommand, launches the
 //! interactive menu.
 
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 use clap::{Args, Parser, Subcommand};
+use crime_map_generate::jitter::{JitterKey, SensitiveJitter, StackSpread};
+use crime_map_generate::sink::{OutputSink, R2Sink};
+use crime_map_generate::tiles::{TileBackend, parse_tile_backend};
+use crime_map_generate::verify::verify_outputs;
 use crime_map_generate::{
-    GenerateArgs, OUTPUT_ANALYTICS_DB, OUTPUT_BOUNDARIES_DB, OUTPUT_BOUNDARIES_PMTILES,
-    OUTPUT_COUNT_DB, OUTPUT_H3_DB, OUTPUT_INCIDENTS_DB, OUTPUT_INCIDENTS_PMTILES, OUTPUT_METADATA,
-    output_dir, resolve_source_ids, run_with_cache,
+    BoundaryType, DEFAULT_MAX_PMTILES_FEATURES, ExtractFormat, GenerateArgs, OUTPUT_BOUNDARIES_DB,
+    OUTPUT_BOUNDARIES_PMTILES, OUTPUT_CHOROPLETH_DB, OUTPUT_COUNT_DB, OUTPUT_H3_DB,
+    OUTPUT_INCIDENTS_DB, OUTPUT_INCIDENTS_PMTILES, PmtilesCluster, SidebarBackend, default_outputs,
+    export_for_boundary, output_catalog, output_dir, output_info, parse_bbox, parse_boundary_type,
+    parse_extract_format, parse_h3_min_cell_count, parse_min_tract_coverage, parse_sample_fraction,
+    parse_sidebar_backend, parse_simplify_boundaries, resolve_source_ids, run_with_sink,
+    source_overlap_report,
 };
 
 #[derive(Parser)]
 #[command(name = "crime_map_generate", about = "Tile generation tool", version)]
 struct Cli {
+    /// List every output with its file and dependencies, then exit.
+    #[arg(long)]
+    list_outputs: bool,
+
+    /// Size of the thread pool for parallel work such as H3 cell
+    /// boundaries and the boundary layer export (default:
+    /// `CRIME_MAP_THREADS`, else one per core).
+    /// Separate from `DuckDB`'s own threads.
+    #[arg(long, global = true)]
+    threads: Option<NonZeroUsize>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -60,6 +81,209 @@ struct CliGenerateArgs {
     /// Useful for partition jobs where boundaries are generated separately.
     #[arg(long)]
     skip_boundaries: bool,
+
+    /// Simplify boundary polygons with Douglas-Peucker before tiling.
+    /// Without a value, each layer is simplified to one tile pixel at a
+    /// default zoom (states 8, counties 10, places and tracts 12); pass
+    /// "layer=zoom" pairs (e.g. "states=6,tracts=13") to choose them, in
+    /// which case unlisted layers keep their raw geometry. Off by default.
+    #[arg(long, num_args = 0..=1, default_missing_value = "", value_parser = parse_simplify_boundaries)]
+    simplify_boundaries: Option<BTreeMap<String, u8>>,
+
+    /// Comma-separated boundary types (`states`, `counties`, `places`,
+    /// `tracts`, `neighborhoods`) to include in the boundaries `PMTiles`
+    /// and search DB. Defaults to all of them.
+    #[arg(long, value_delimiter = ',')]
+    boundary_types: Option<Vec<String>>,
+
+    /// Only export incidents inside this bounding box, given as
+    /// `min_lng,min_lat,max_lng,max_lat` (e.g. "-77.6,38.7,-76.4,39.5").
+    /// Intersected with `--sources`/`--states`.
+    #[arg(long, value_parser = parse_bbox, allow_hyphen_values = true)]
+    bbox: Option<[f64; 4]>,
+
+    /// Delete orphaned `.wal`/`.tmp`/`.part` files and unfinished
+    /// intermediates left in the output directory by an interrupted run
+    /// before generating.
+    #[arg(long)]
+    repair: bool,
+
+    /// Also build a `count_summary_monthly` table (bucketed by `YYYY-MM`)
+    /// in the count database for zoomed-out time-slider queries.
+    #[arg(long)]
+    count_monthly: bool,
+
+    /// Also build a `count_summary_rolling` table of trailing
+    /// 30/90/365-day counts per cell, ending at the latest incident day.
+    /// The windows go stale until the next regeneration.
+    #[arg(long)]
+    count_rolling: bool,
+
+    /// Also build a `count_timeseries` table of monthly counts per source
+    /// and category, for trend charts. Covers every month in the data.
+    #[arg(long)]
+    count_timeseries: bool,
+
+    /// Export only this fraction (0-1] of each source's incidents, chosen
+    /// pseudo-randomly by row. Composes with `--limit`. For preview outputs
+    /// only: the output directory is marked and R2 pushes refuse it.
+    #[arg(long, value_parser = parse_sample_fraction)]
+    sample: Option<f64>,
+
+    /// TOML/JSON file of per-source category remap rules applied to
+    /// incidents before they are written to any output.
+    #[arg(long)]
+    category_remap: Option<PathBuf>,
+
+    /// TOML/JSON blocklist of `(source, id)` incidents to exclude from
+    /// every output.
+    #[arg(long)]
+    blocklist: Option<PathBuf>,
+
+    /// Drop incidents that duplicate an incident of a higher-priority
+    /// overlapping source (by the sources' `priority`).
+    #[arg(long)]
+    dedup_sources: bool,
+
+    /// Also group the count and H3 aggregations by day of week (`dow`) and
+    /// hour of day (`hour`). Multiplies their row counts.
+    #[arg(long)]
+    time_dimensions: bool,
+
+    /// Also export the analytics `incidents` table as NDJSON
+    /// (`analytics_incidents.jsonl`) for consumers without `DuckDB`.
+    #[arg(long)]
+    analytics_ndjson: bool,
+
+    /// Also write the per-resolution H3 coverage stats to
+    /// `h3_stats.json` (they are always logged).
+    #[arg(long)]
+    h3_stats: bool,
+
+    /// Fail when under this fraction (0-1) of a source's enriched
+    /// incidents have a census tract, which usually means its state's
+    /// tracts weren't ingested before enrichment.
+    #[arg(long, value_parser = parse_min_tract_coverage)]
+    min_tract_coverage: Option<f64>,
+
+    /// Only warn about sources below `--min-tract-coverage`.
+    #[arg(long, requires = "min_tract_coverage")]
+    warn_tract_coverage: bool,
+
+    /// Keep the raw incidents table in `counts.duckdb` so `--h3-from` can
+    /// rebuild the H3 DB from it. Roughly doubles the file size.
+    #[arg(long)]
+    keep_incidents_table: bool,
+
+    /// Build the H3 DB from the raw incidents table of this DuckDB (a
+    /// `counts.duckdb` generated with `--keep-incidents-table`) instead
+    /// of re-scanning the source databases.
+    #[arg(long)]
+    h3_from: Option<PathBuf>,
+
+    /// Drop H3 cells with fewer incidents than this from `h3_counts`,
+    /// either for every resolution ("3") or per resolution ("8=3,9=5").
+    /// The number of suppressed cells is logged.
+    #[arg(long, value_parser = parse_h3_min_cell_count)]
+    h3_min_cell_count: Option<BTreeMap<u8, u32>>,
+
+    /// Threads computing H3 cell boundaries, in a dedicated pool
+    /// (default: they run on the `--threads` pool).
+    #[arg(long)]
+    h3_threads: Option<NonZeroUsize>,
+
+    /// Record progress after each output so a re-run after an
+    /// interruption resumes from the first unfinished output.
+    #[arg(long)]
+    resume: bool,
+
+    /// Split the intermediate incidents GeoJSONSeq into this many files
+    /// by spatial hash, all tiled together. For national-scale inputs.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    geojsonseq_shards: Option<u32>,
+
+    /// Cluster incident points within this many pixels (1-255) into
+    /// features with a `point_count` property at low zooms of the PMTiles.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=255))]
+    cluster_distance: Option<u32>,
+
+    /// Highest zoom clustered by `--cluster-distance`; individual points
+    /// are shown above it.
+    #[arg(
+        long,
+        default_value_t = 10,
+        requires = "cluster_distance",
+        value_parser = clap::value_parser!(u32).range(0..14)
+    )]
+    cluster_max_zoom: u32,
+
+    /// Tiler for the incidents PMTiles: `tippecanoe` (default) or the
+    /// experimental in-process `native` backend, which needs no external
+    /// binary but stops at zoom 12, thins dense tiles naively, and can't
+    /// cluster.
+    #[arg(long, default_value = "tippecanoe", value_parser = parse_tile_backend)]
+    tile_backend: TileBackend,
+
+    #[command(flatten)]
+    jitter: JitterArgs,
+
+    /// Copy the raw source JSON kept by `ingest --keep-raw` into a
+    /// `raw_records` table of the sidebar DB, for debugging. Much larger
+    /// output that exposes every source field; never use for public builds.
+    #[arg(long)]
+    raw_json: bool,
+
+    /// How to write the sidebar DB's rows: `rows` inserts them from Rust;
+    /// `duckdb` streams each source straight from `DuckDB` through its
+    /// `sqlite` extension (faster, but needs network access on first use
+    /// to download the extension). Both produce the same DB.
+    #[arg(long, default_value = "rows", value_parser = parse_sidebar_backend)]
+    sidebar_backend: SidebarBackend,
+
+    /// Refuse to tile the incidents PMTiles when more incidents than this
+    /// are exportable (a guard against accidental national-scale runs).
+    #[arg(long, default_value_t = DEFAULT_MAX_PMTILES_FEATURES)]
+    max_pmtiles_features: u64,
+
+    /// Tile the incidents PMTiles however many incidents are exportable,
+    /// ignoring `--max-pmtiles-features`.
+    #[arg(long)]
+    allow_large: bool,
+
+    /// Patch the existing sidebar DB when at most a few sources changed,
+    /// replacing only their rows instead of rebuilding it. Any other
+    /// config or schema change still triggers a full rebuild.
+    #[arg(long)]
+    only_changed_sources: bool,
+
+    /// Update the existing analytics DB by re-inserting only changed
+    /// sources' rows from the month of their previous latest incident.
+    /// Any other config, schema, or boundaries change still triggers a
+    /// full rebuild.
+    #[arg(long)]
+    incremental_analytics: bool,
+
+    /// If boundaries.duckdb has no census tracts, ingest the boundaries
+    /// of the selected states (from `--states` or the sources) before
+    /// generating instead of failing. Downloads from `TIGERweb`.
+    #[arg(long)]
+    auto_boundaries: bool,
+
+    /// Log each PMTiles output's per-layer feature counts and per-zoom
+    /// feature drops, and record them in manifest.json.
+    #[arg(long)]
+    tile_stats: bool,
+
+    /// Upload each database/JSON output to this R2 key prefix (e.g.
+    /// "generated/merged") as soon as it's finalized, then delete the
+    /// local copy. PMTiles are still written locally.
+    #[arg(long)]
+    upload_prefix: Option<String>,
+
+    /// Smoke-check every output left in the output directory after
+    /// generating (see the `verify` subcommand) and fail if any is broken.
+    #[arg(long)]
+    verify: bool,
 }
 
 impl From<&CliGenerateArgs> for GenerateArgs {
@@ -69,11 +293,101 @@ impl From<&CliGenerateArgs> for GenerateArgs {
             sources: cli.sources.clone(),
             states: cli.states.clone(),
             keep_intermediate: cli.keep_intermediate,
+            geojsonseq_shards: cli.geojsonseq_shards,
             force: cli.force,
+            simplify_boundaries: cli.simplify_boundaries.clone(),
+            bbox: cli.bbox,
+            repair: cli.repair,
+            count_monthly: cli.count_monthly,
+            count_rolling: cli.count_rolling,
+            count_timeseries: cli.count_timeseries,
+            sample_fraction: cli.sample,
+            category_remap: cli.category_remap.clone(),
+            blocklist: cli.blocklist.clone(),
+            dedup_sources: cli.dedup_sources,
+            time_dimensions: cli.time_dimensions,
+            analytics_ndjson: cli.analytics_ndjson,
+            h3_stats: cli.h3_stats,
+            min_tract_coverage: cli.min_tract_coverage,
+            warn_tract_coverage: cli.warn_tract_coverage,
+            keep_incidents_table: cli.keep_incidents_table,
+            h3_from: cli.h3_from.clone(),
+            h3_min_cell_count: cli.h3_min_cell_count.clone().unwrap_or_default(),
+            resume: cli.resume,
+            pmtiles_cluster: cli.cluster_distance.map(|distance| PmtilesCluster {
+                distance,
+                max_zoom: cli.cluster_max_zoom,
+            }),
+            tile_backend: cli.tile_backend,
+            sensitive_jitter: cli.jitter.sensitive_jitter(),
+            jitter_key: JitterKey::from_env(),
+            stack_spread: cli.jitter.stack_spread(),
+            raw_json: cli.raw_json,
+            sidebar_backend: cli.sidebar_backend,
+            max_pmtiles_features: (!cli.allow_large).then_some(cli.max_pmtiles_features),
+            h3_threads: cli.h3_threads,
+            only_changed_sources: cli.only_changed_sources,
+            incremental_analytics: cli.incremental_analytics,
+            auto_boundaries: cli.auto_boundaries,
+            tile_stats: cli.tile_stats,
+            boundary_types: cli.boundary_types.clone(),
         }
     }
 }
 
+/// CLI arguments for jittering sensitive incidents and spreading stacked
+/// points, shared by every command built from the generate options so
+/// partitioned builds and extracts place points the same way. The jitter
+/// key is read from `CRIME_MAP_JITTER_KEY` rather than a flag, so it never
+/// shows up in shell history or process lists.
+#[derive(Args)]
+struct JitterArgs {
+    /// Comma-separated subcategories (e.g. "SEXUAL_ASSAULT") whose
+    /// incidents are displaced by a deterministic offset in the PMTiles,
+    /// sidebar DB, and extracts, for victim privacy. Requires a secret of
+    /// at least 32 bytes in CRIME_MAP_JITTER_KEY. Aggregated outputs keep
+    /// exact locations.
+    #[arg(long, value_delimiter = ',')]
+    jitter_categories: Vec<String>,
+
+    /// Also jitter every incident flagged as domestic.
+    #[arg(long)]
+    jitter_domestic: bool,
+
+    /// Maximum jitter offset in meters; points move between half and all
+    /// of it.
+    #[arg(long, default_value_t = 150.0)]
+    jitter_meters: f64,
+
+    /// Spread incidents stacked on the exact same coordinate within a
+    /// source (e.g. block-centroid geocodes) over a deterministic disc of
+    /// this many meters (at most 50) in the PMTiles, so they render as a
+    /// cluster instead of one dot.
+    #[arg(long)]
+    spread_stacked_meters: Option<f64>,
+}
+
+impl JitterArgs {
+    /// Returns the configured jitter, or `None` if nothing is sensitive.
+    fn sensitive_jitter(&self) -> Option<SensitiveJitter> {
+        (!self.jitter_categories.is_empty() || self.jitter_domestic).then(|| SensitiveJitter {
+            categories: self
+                .jitter_categories
+                .iter()
+                .map(|c| c.trim().to_string())
+                .collect(),
+            domestic: self.jitter_domestic,
+            radius_meters: self.jitter_meters,
+        })
+    }
+
+    /// Returns the configured stack spread, if any.
+    fn stack_spread(&self) -> Option<StackSpread> {
+        self.spread_stacked_meters
+            .map(|radius_meters| StackSpread { radius_meters })
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Generate `PMTiles` from `DuckDB` source data (heatmap + individual points)
@@ -96,6 +410,13 @@ enum Commands {
         #[command(flatten)]
         args: CliGenerateArgs,
     },
+    /// Generate `DuckDB` tract choropleth database with per-tract counts and
+    /// rates (opt-in, not part of `all`), derived from the analytics DB,
+    /// which is brought up to date first
+    ChoroplethDb {
+        #[command(flatten)]
+        args: CliGenerateArgs,
+    },
     /// Generate administrative boundary `PMTiles` (states, counties, places, tracts, neighborhoods)
     Boundaries {
         #[command(flatten)]
@@ -106,6 +427,21 @@ enum Commands {
         #[command(flatten)]
         args: CliGenerateArgs,
     },
+    /// Generate all non-boundary outputs for each source into
+    /// `data/generated/partitions/{source}/` (for scatter-gather pipelines)
+    Partition {
+        /// Comma-separated source IDs to generate partitions for.
+        #[arg(long)]
+        source: String,
+
+        /// Generation options, as for `all`. Options that select sources,
+        /// the output location, or combine sources (`--sources`,
+        /// `--states`, `--output-dir`, `--skip-boundaries`,
+        /// `--upload-prefix`, `--verify`, `--dedup-sources`, `--h3-from`)
+        /// are rejected, and `--h3-min-cell-count` belongs on `merge`.
+        #[command(flatten)]
+        args: CliGenerateArgs,
+    },
     /// Merge partitioned artifacts from multiple directories into unified output files
     Merge {
         /// Comma-separated list of partition directories to merge.
@@ -122,6 +458,65 @@ enum Commands {
         /// in the workspace root.
         #[arg(long)]
         output_dir: Option<PathBuf>,
+
+        /// Drop merged H3 cells with fewer incidents than this, summed
+        /// across every partition, either for every resolution ("3") or
+        /// per resolution ("8=3,9=5").
+        #[arg(long, value_parser = parse_h3_min_cell_count)]
+        h3_min_cell_count: Option<BTreeMap<u8, u32>>,
+
+        /// Also derive the opt-in tract choropleth DB from the merged
+        /// analytics DB.
+        #[arg(long)]
+        choropleth: bool,
+    },
+    /// Export the incidents inside one boundary (by the geoid stamped
+    /// during enrichment) to a GeoJSON, CSV, or Parquet file
+    Extract {
+        /// Boundary type: `state`, `county`, `place`, `tract`, or
+        /// `neighborhood`.
+        #[arg(long, value_parser = parse_boundary_type)]
+        boundary_type: BoundaryType,
+
+        /// Geoid of the boundary (e.g. "17031839100" for a tract, or
+        /// "nbhd-12" for a neighborhood).
+        #[arg(long)]
+        geoid: String,
+
+        /// Output format: `geojson` (default), `csv`, or `parquet`.
+        #[arg(long, default_value = "geojson", value_parser = parse_extract_format)]
+        format: ExtractFormat,
+
+        /// Output file. Defaults to `{type}_{geoid}.{format}` in the output
+        /// directory.
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        args: CliGenerateArgs,
+    },
+    /// Print, as JSON, the source pairs whose coverage overlaps (shared
+    /// census tracts and bounding box area), largest overlap first
+    Overlap {
+        #[command(flatten)]
+        args: CliGenerateArgs,
+    },
+    /// Open each output in a directory, run a minimal query on it, and
+    /// exit non-zero if any is corrupt or empty (e.g. before an R2 push)
+    Verify {
+        /// Directory of generated outputs. Defaults to `data/generated/`
+        /// in the workspace root.
+        #[arg(long)]
+        output_dir: Option<PathBuf>,
+    },
+    /// Compare the totals of two count databases (`counts.duckdb`) and
+    /// exit non-zero if they differ
+    DiffCounts {
+        /// Baseline count database.
+        old: PathBuf,
+
+        /// Count database to check against the baseline.
+        new: PathBuf,
     },
 }
 
@@ -130,6 +525,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     pretty_env_logger::init();
     let cli = Cli::parse();
 
+    if cli.list_outputs {
+        print_output_catalog();
+        return Ok(());
+    }
+
+    crime_map_generate::init_thread_pool(cli.threads)?;
+
     let Some(command) = cli.command else {
         return crime_map_generate::interactive::run().await;
     };
@@ -139,6 +541,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             partition_dirs,
             boundaries_dir,
             output_dir: out_dir,
+            h3_min_cell_count,
+            choropleth,
         } => {
             let dirs: Vec<PathBuf> = partition_dirs
                 .split(',')
@@ -146,7 +550,97 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .collect();
             let out = out_dir.unwrap_or_else(output_dir);
             std::fs::create_dir_all(&out)?;
-            crime_map_generate::merge::run(&dirs, boundaries_dir.as_deref(), &out).await?;
+            crime_map_generate::merge::run(
+                &dirs,
+                boundaries_dir.as_deref(),
+                &out,
+                &h3_min_cell_count.unwrap_or_default(),
+                choropleth,
+            )
+            .await?;
+        }
+        Commands::Extract {
+            boundary_type,
+            geoid,
+            format,
+            output,
+            args: cli_args,
+        } => {
+            let output = match output {
+                Some(path) => path,
+                None => {
+                    let dir = cli_args.output_dir.clone().unwrap_or_else(output_dir);
+                    std::fs::create_dir_all(&dir)?;
+                    dir.join(format!(
+                        "{}_{geoid}.{}",
+                        boundary_type.as_str(),
+                        format.as_str()
+                    ))
+                }
+            };
+            let args = GenerateArgs::from(&cli_args);
+            let source_ids = resolve_source_ids(&args)?;
+            export_for_boundary(&args, &source_ids, boundary_type, &geoid, format, &output)?;
+        }
+        Commands::Overlap { args: cli_args } => {
+            let args = GenerateArgs::from(&cli_args);
+            let source_ids = resolve_source_ids(&args)?;
+            let overlaps = source_overlap_report(&args, &source_ids)?;
+            println!("{}", serde_json::to_string_pretty(&overlaps)?);
+        }
+        Commands::Verify { output_dir: dir } => {
+            verify(&dir.unwrap_or_else(output_dir)).await?;
+        }
+        Commands::DiffCounts { old, new } => {
+            let diff = crime_map_database::counts::diff(&old, &new)?;
+            println!("{diff}");
+            if !diff.is_identical() {
+                std::process::exit(1);
+            }
+        }
+        Commands::Partition {
+            source,
+            args: cli_args,
+        } => {
+            let unsupported: Vec<&str> = [
+                ("--sources", cli_args.sources.is_some()),
+                ("--states", cli_args.states.is_some()),
+                ("--output-dir", cli_args.output_dir.is_some()),
+                ("--skip-boundaries", cli_args.skip_boundaries),
+                ("--upload-prefix", cli_args.upload_prefix.is_some()),
+                ("--verify", cli_args.verify),
+                ("--dedup-sources", cli_args.dedup_sources),
+                ("--h3-from", cli_args.h3_from.is_some()),
+                (
+                    "--h3-min-cell-count (pass it to merge instead)",
+                    cli_args.h3_min_cell_count.is_some(),
+                ),
+            ]
+            .into_iter()
+            .filter_map(|(flag, set)| set.then_some(flag))
+            .collect();
+            if !unsupported.is_empty() {
+                return Err(format!("partition doesn't support {}", unsupported.join(", ")).into());
+            }
+
+            let source_ids: Vec<String> = source
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            let args = GenerateArgs::from(&cli_args);
+            let report = crime_map_generate::generate_partitions(&args, &source_ids, None).await?;
+            log::info!(
+                "Partitions done: {} generated, {} skipped (complete)",
+                report.generated.len(),
+                report.skipped.len()
+            );
+            if !report.generated.is_empty() {
+                log::info!("  Generated: {}", report.generated.join(", "));
+            }
+            if !report.skipped.is_empty() {
+                log::info!("  Skipped: {}", report.skipped.join(", "));
+            }
         }
         cmd => {
             run_generate_command(cmd).await?;
@@ -156,28 +650,49 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Prints [`output_catalog`] as a table.
+fn print_output_catalog() {
+    println!(
+        "{:<20} {:<22} {:<10} {:<9} DESCRIPTION",
+        "OUTPUT", "FILE", "BOUNDARIES", "INCIDENTS"
+    );
+    for info in output_catalog() {
+        println!(
+            "{:<20} {:<22} {:<10} {:<9} {}",
+            info.name,
+            info.file,
+            if info.needs_boundaries { "yes" } else { "no" },
+            if info.scans_incidents { "yes" } else { "no" },
+            if info.opt_in {
+                format!("{} (opt-in)", info.description)
+            } else {
+                info.description.to_string()
+            },
+        );
+    }
+}
+
 #[allow(clippy::future_not_send)]
 async fn run_generate_command(command: Commands) -> Result<(), Box<dyn std::error::Error>> {
+    let all_outputs = default_outputs();
     let (cli_args, base_outputs): (&CliGenerateArgs, &[&str]) = match &command {
         Commands::Pmtiles { args } => (args, &[OUTPUT_INCIDENTS_PMTILES]),
         Commands::Sidebar { args } => (args, &[OUTPUT_INCIDENTS_DB]),
         Commands::CountDb { args } => (args, &[OUTPUT_COUNT_DB]),
         Commands::H3Db { args } => (args, &[OUTPUT_H3_DB]),
+        Commands::ChoroplethDb { args } => (args, &[OUTPUT_CHOROPLETH_DB]),
         Commands::Boundaries { args } => (args, &[OUTPUT_BOUNDARIES_PMTILES, OUTPUT_BOUNDARIES_DB]),
-        Commands::All { args } => (
-            args,
-            &[
-                OUTPUT_INCIDENTS_PMTILES,
-                OUTPUT_INCIDENTS_DB,
-                OUTPUT_COUNT_DB,
-                OUTPUT_H3_DB,
-                OUTPUT_METADATA,
-                OUTPUT_BOUNDARIES_PMTILES,
-                OUTPUT_BOUNDARIES_DB,
-                OUTPUT_ANALYTICS_DB,
-            ][..],
-        ),
-        Commands::Merge { .. } => unreachable!("Merge handled separately"),
+        Commands::All { args } => (args, &all_outputs[..]),
+        Commands::Merge { .. }
+        | Commands::Partition { .. }
+        | Commands::Extract { .. }
+        | Commands::Overlap { .. }
+        | Commands::Verify { .. }
+        | Commands::DiffCounts { .. } => {
+            unreachable!(
+                "Merge, Partition, Extract, Overlap, Verify, and DiffCounts handled separately"
+            )
+        }
     };
 
     // Filter out boundary outputs if --skip-boundaries is set
@@ -199,9 +714,11 @@ async fn run_generate_command(command: Commands) -> Result<(), Box<dyn std::erro
     // Boundary-only outputs don't need per-source DuckDB files — they read
     // exclusively from boundaries.duckdb. Skip source resolution so the
     // `boundaries` subcommand works without any files in data/sources/.
-    let needs_sources = outputs
-        .iter()
-        .any(|&o| o != OUTPUT_BOUNDARIES_PMTILES && o != OUTPUT_BOUNDARIES_DB);
+    // Derived outputs bring the output they're derived from up to date,
+    // which may scan them.
+    let needs_sources = outputs.iter().any(|&o| {
+        output_info(o).is_some_and(|info| info.scans_incidents || info.derived_from.is_some())
+    });
 
     let source_ids = if needs_sources {
         resolve_source_ids(&args)?
@@ -209,7 +726,51 @@ async fn run_generate_command(command: Commands) -> Result<(), Box<dyn std::erro
         Vec::new()
     };
 
-    run_with_cache(&args, &source_ids, &dir, &outputs, None).await?;
+    let sink = cli_args
+        .upload_prefix
+        .as_deref()
+        .map(|prefix| crime_map_r2::R2Client::from_env().map(|client| R2Sink::new(client, prefix)))
+        .transpose()?;
+
+    run_with_sink(
+        &args,
+        &source_ids,
+        &dir,
+        &outputs,
+        None,
+        sink.as_ref().map(|s| s as &dyn OutputSink),
+    )
+    .await?;
+
+    if cli_args.verify {
+        verify(&dir).await?;
+    }
 
     Ok(())
 }
+
+/// Runs [`verify_outputs`] on `dir`, failing with the broken files.
+#[allow(clippy::future_not_send)]
+async fn verify(dir: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let report = verify_outputs(dir).await;
+    let failed: Vec<String> = report
+        .iter()
+        .filter_map(|health| {
+            health
+                .problem
+                .as_ref()
+                .map(|problem| format!("{}: {problem}", health.file))
+        })
+        .collect();
+    if failed.is_empty() {
+        log::info!("All {} outputs in {} verified", report.len(), dir.display());
+        Ok(())
+    } else {
+        Err(format!(
+            "{} output(s) failed verification:\n  {}",
+            failed.len(),
+            failed.join("\n  ")
+        )
+        .into())
+    }
+}
@@ -2,8 +2,9 @@
 //!
 //! Each partition directory is expected to contain a subset of the standard
 //! generation outputs (produced by `cargo generate all --sources=X
-//! --output-dir=<partition>`). This module combines them into a single set
-//! of artifacts suitable for serving.
+//! --output-dir=<partition>`, or `cargo generate partition --source=X` which
+//! writes to `data/generated/partitions/X/`). This module combines them into
+//! a single set of artifacts suitable for serving.
 //!
 //! ## Merge strategies
 //!
@@ -11,27 +12,49 @@
 //! |----------|----------|
 //! | `incidents.pmtiles` | `tile-join` from the tippecanoe suite |
 //! | `incidents.db` | SQLite `ATTACH` + `INSERT` with auto-assigned IDs, R-tree rebuild |
-//! | `counts.duckdb` | DuckDB `ATTACH` + `INSERT INTO ... SELECT` (UNION ALL) |
-//! | `h3.duckdb` | DuckDB `ATTACH` + `INSERT INTO ... SELECT`, deduplicate `h3_boundaries` |
+//! | `counts.duckdb` | DuckDB `ATTACH` + `INSERT INTO ... SELECT SUM(cnt) ... GROUP BY`, `boundary_counts` rebuilt from the merged summary |
+//! | `h3.duckdb` | DuckDB `ATTACH` + `INSERT INTO ... SELECT SUM(cnt) ... GROUP BY`, sparse cells suppressed, deduplicate `h3_boundaries` |
 //! | `analytics.duckdb` | DuckDB `ATTACH` + UNION ALL incidents, copy reference tables from first partition |
+//! | `analytics_incidents.jsonl` | Re-exported from the merged `analytics.duckdb` if any partition has it |
+//! | `choropleth.duckdb` | Opt-in: derived from the merged `analytics.duckdb` |
 //! | `metadata.json` | JSON merge: union cities, union sources, MIN/MAX dates |
+//! | `health.json` | Rebuilt from the merged `metadata.json` |
 //! | `boundaries.pmtiles` | Copy from `--boundaries-dir` |
 //! | `boundaries.db` | Copy from `--boundaries-dir` |
+//! | `PREVIEW` | Written if any partition has it (sampled outputs) |
+//!
+//! Partitions must all have the current output layout: a partition whose
+//! `manifest.json` has an older version than this build's is refused, since
+//! its tables may lack columns the merge selects. Regenerate it first.
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use crime_map_database::paths::{self, PREVIEW_MARKER_FILE};
+
+use crate::GenerateError;
+
 /// Runs the full merge pipeline.
 ///
+/// `h3_min_cell_count` is applied to the merged H3 counts (see
+/// [`crate::GenerateArgs::h3_min_cell_count`]): partitions don't apply it,
+/// since a cell's count is only final once every source is summed. With
+/// `choropleth`, the opt-in choropleth DB is derived from the merged
+/// analytics DB.
+///
 /// # Errors
 ///
-/// Returns an error if any merge step fails (missing files, subprocess
-/// errors, database errors, etc.).
+/// Returns an error if a partition was generated with an older output
+/// layout (see [`check_partition_versions`]) or any merge step fails
+/// (missing files, subprocess errors, database errors, etc.).
 pub async fn run(
     partition_dirs: &[PathBuf],
     boundaries_dir: Option<&Path>,
     output_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+    h3_min_cell_count: &BTreeMap<u8, u32>,
+    choropleth: bool,
+) -> Result<(), GenerateError> {
     log::info!(
         "Merging {} partitions into {}",
         partition_dirs.len(),
@@ -40,23 +63,45 @@ pub async fn run(
 
     // Validate that at least one partition exists
     if partition_dirs.is_empty() {
-        return Err("No partition directories provided".into());
+        return Err(GenerateError::InvalidArgs(
+            "No partition directories provided".to_string(),
+        ));
     }
 
     for dir in partition_dirs {
         if !dir.exists() {
-            return Err(format!("Partition directory does not exist: {}", dir.display()).into());
+            return Err(GenerateError::InvalidArgs(format!(
+                "Partition directory does not exist: {}",
+                dir.display()
+            )));
         }
     }
 
+    check_partition_versions(partition_dirs)?;
+
     // Merge each artifact type
     merge_pmtiles(partition_dirs, output_dir)?;
     merge_sidebar_db(partition_dirs, output_dir).await?;
     merge_count_db(partition_dirs, output_dir)?;
-    merge_h3_db(partition_dirs, output_dir)?;
+    merge_h3_db(partition_dirs, output_dir, h3_min_cell_count)?;
     merge_analytics_db(partition_dirs, output_dir)?;
+    if choropleth {
+        crate::generate_choropleth_db(output_dir)?;
+    }
     merge_metadata(partition_dirs, output_dir)?;
 
+    // A merge containing any sampled partition is itself only a preview
+    let marker = output_dir.join(PREVIEW_MARKER_FILE);
+    if partition_dirs
+        .iter()
+        .any(|dir| dir.join(PREVIEW_MARKER_FILE).exists())
+    {
+        log::warn!("Some partitions were generated with --sample; merged outputs are preview-only");
+        std::fs::write(&marker, "")?;
+    } else if marker.exists() {
+        std::fs::remove_file(&marker)?;
+    }
+
     // Copy boundary artifacts if provided
     if let Some(bdir) = boundaries_dir {
         copy_boundary_artifacts(bdir, output_dir)?;
@@ -68,18 +113,51 @@ pub async fn run(
     Ok(())
 }
 
+/// Fails if any partition's `manifest.json` records a manifest version
+/// other than [`crate::MANIFEST_VERSION`], naming the partitions to
+/// regenerate. Partitions without a readable manifest are merged with a
+/// warning, since their layout can't be checked.
+///
+/// # Errors
+///
+/// Returns [`GenerateError::InvalidArgs`] listing the stale partitions.
+fn check_partition_versions(partition_dirs: &[PathBuf]) -> Result<(), GenerateError> {
+    let mut stale = Vec::new();
+    for dir in partition_dirs {
+        let version = std::fs::read_to_string(dir.join(paths::MANIFEST_FILE))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+            .and_then(|manifest| manifest["version"].as_u64());
+        match version {
+            Some(version) if version == u64::from(crate::MANIFEST_VERSION) => {}
+            Some(version) => stale.push(format!("{} (version {version})", dir.display())),
+            None => log::warn!(
+                "Partition {} has no readable manifest; merging it without a layout check",
+                dir.display()
+            ),
+        }
+    }
+
+    if stale.is_empty() {
+        return Ok(());
+    }
+    Err(GenerateError::InvalidArgs(format!(
+        "Partitions generated with an older output layout (expected manifest version {}): {}; \
+         regenerate them with `cargo generate partition --force` before merging",
+        crate::MANIFEST_VERSION,
+        stale.join(", ")
+    )))
+}
+
 // ============================================================
 // PMTiles merge via tile-join
 // ============================================================
 
 /// Merges `incidents.pmtiles` from all partitions using `tile-join`.
-fn merge_pmtiles(
-    partition_dirs: &[PathBuf],
-    output_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+fn merge_pmtiles(partition_dirs: &[PathBuf], output_dir: &Path) -> Result<(), GenerateError> {
     let inputs: Vec<PathBuf> = partition_dirs
         .iter()
-        .map(|d| d.join("incidents.pmtiles"))
+        .map(|d| d.join(paths::INCIDENTS_PMTILES_FILE))
         .filter(|p| p.exists())
         .collect();
 
@@ -88,7 +166,7 @@ fn merge_pmtiles(
         return Ok(());
     }
 
-    let output_path = output_dir.join("incidents.pmtiles");
+    let output_path = output_dir.join(paths::INCIDENTS_PMTILES_FILE);
     log::info!("Merging {} PMTiles files via tile-join...", inputs.len());
 
     let mut cmd = Command::new("tile-join");
@@ -105,13 +183,7 @@ fn merge_pmtiles(
         cmd.arg(input);
     }
 
-    let status = cmd
-        .status()
-        .map_err(|e| format!("Failed to run tile-join (is tippecanoe installed?): {e}"))?;
-
-    if !status.success() {
-        return Err(format!("tile-join exited with status {status}").into());
-    }
+    crate::run_tippecanoe(&mut cmd, &output_path)?;
 
     log::info!("PMTiles merge complete: {}", output_path.display());
     Ok(())
@@ -126,15 +198,16 @@ fn merge_pmtiles(
 /// Uses `ATTACH DATABASE` to read from each partition and inserts rows
 /// with auto-assigned IDs (the partition-local IDs are discarded). The
 /// R-tree spatial index and all secondary indexes are rebuilt after all
-/// data is inserted.
+/// data is inserted. Partitions generated with `--raw-json` also have
+/// their `raw_records` copied.
 #[allow(clippy::too_many_lines)]
 async fn merge_sidebar_db(
     partition_dirs: &[PathBuf],
     output_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), GenerateError> {
     let inputs: Vec<PathBuf> = partition_dirs
         .iter()
-        .map(|d| d.join("incidents.db"))
+        .map(|d| d.join(paths::INCIDENTS_DB_FILE))
         .filter(|p| p.exists())
         .collect();
 
@@ -143,18 +216,24 @@ async fn merge_sidebar_db(
         return Ok(());
     }
 
-    let output_path = output_dir.join("incidents.db");
+    let output_path = output_dir.join(paths::INCIDENTS_DB_FILE);
     if output_path.exists() {
         std::fs::remove_file(&output_path)?;
     }
 
     log::info!("Merging {} incidents.db files...", inputs.len());
     let sqlite = switchy_database_connection::init_sqlite_rusqlite(Some(&output_path))
-        .map_err(|e| format!("Failed to open merged SQLite: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to open merged SQLite: {e}")))?;
 
     // WAL mode + generous busy timeout to avoid "database is locked" errors.
-    sqlite.exec_raw("PRAGMA journal_mode=WAL").await?;
-    sqlite.exec_raw("PRAGMA busy_timeout=5000").await?;
+    sqlite
+        .exec_raw("PRAGMA journal_mode=WAL")
+        .await
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to set journal_mode: {e}")))?;
+    sqlite
+        .exec_raw("PRAGMA busy_timeout=5000")
+        .await
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to set busy_timeout: {e}")))?;
 
     // Create the schema (matches generate_sidebar_db in lib.rs)
     sqlite
@@ -175,6 +254,7 @@ async fn merge_sidebar_db(
                 city TEXT,
                 state TEXT,
                 arrest_made INTEGER,
+                domestic INTEGER,
                 location_type TEXT,
                 state_fips TEXT,
                 county_geoid TEXT,
@@ -184,7 +264,7 @@ async fn merge_sidebar_db(
             )",
         )
         .await
-        .map_err(|e| format!("Failed to create incidents table: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create incidents table: {e}")))?;
 
     // Import from each partition.
     //
@@ -209,7 +289,7 @@ async fn merge_sidebar_db(
                      subcategory, category, severity,
                      longitude, latitude, occurred_at,
                      description, block_address, city, state,
-                     arrest_made, location_type,
+                     arrest_made, domestic, location_type,
                      state_fips, county_geoid, place_geoid,
                      tract_geoid, neighborhood_id
                  )
@@ -218,7 +298,7 @@ async fn merge_sidebar_db(
                      subcategory, category, severity,
                      longitude, latitude, occurred_at,
                      description, block_address, city, state,
-                     arrest_made, location_type,
+                     arrest_made, domestic, location_type,
                      state_fips, county_geoid, place_geoid,
                      tract_geoid, neighborhood_id
                  FROM {alias}.incidents;
@@ -245,11 +325,39 @@ async fn merge_sidebar_db(
                     );
                     continue;
                 }
-                return Err(format!("Failed to merge partition {}: {e}", input.display()).into());
+                return Err(GenerateError::Sqlite(format!(
+                    "Failed to merge partition {}: {e}",
+                    input.display()
+                )));
             }
         }
     }
 
+    for (i, input) in inputs.iter().enumerate() {
+        if !has_raw_records(input).await? {
+            continue;
+        }
+        let alias = format!("r{i}");
+        let path_str = input.to_string_lossy();
+        sqlite
+            .exec_raw(&format!(
+                "{schema};
+                 ATTACH DATABASE '{path_str}' AS {alias};
+                 INSERT OR IGNORE INTO raw_records (source_id, source_incident_id, raw_json)
+                 SELECT source_id, source_incident_id, raw_json FROM {alias}.raw_records;
+                 DETACH {alias};",
+                schema = crate::RAW_RECORDS_SCHEMA
+            ))
+            .await
+            .map_err(|e| {
+                GenerateError::Sqlite(format!(
+                    "Failed to merge raw records of {}: {e}",
+                    input.display()
+                ))
+            })?;
+        log::info!("  Partition {}: merged raw records", i + 1);
+    }
+
     // Build R-tree spatial index
     log::info!("Building R-tree spatial index...");
     sqlite
@@ -259,49 +367,49 @@ async fn merge_sidebar_db(
             )",
         )
         .await
-        .map_err(|e| format!("Failed to create R-tree: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create R-tree: {e}")))?;
     sqlite
         .exec_raw(
             "INSERT INTO incidents_rtree (id, min_lng, max_lng, min_lat, max_lat)
              SELECT id, longitude, longitude, latitude, latitude FROM incidents",
         )
         .await
-        .map_err(|e| format!("Failed to populate R-tree: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to populate R-tree: {e}")))?;
 
     // Build secondary indexes
     log::info!("Building secondary indexes...");
     sqlite
         .exec_raw("CREATE INDEX idx_incidents_occurred_at ON incidents(occurred_at DESC)")
         .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
     sqlite
         .exec_raw("CREATE INDEX idx_incidents_source_id ON incidents(source_id)")
         .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
     sqlite
         .exec_raw("CREATE INDEX idx_incidents_state_fips ON incidents(state_fips)")
         .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
     sqlite
         .exec_raw("CREATE INDEX idx_incidents_county_geoid ON incidents(county_geoid)")
         .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
     sqlite
         .exec_raw("CREATE INDEX idx_incidents_place_geoid ON incidents(place_geoid)")
         .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
     sqlite
         .exec_raw("CREATE INDEX idx_incidents_tract_geoid ON incidents(tract_geoid)")
         .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
     sqlite
         .exec_raw("CREATE INDEX idx_incidents_neighborhood_id ON incidents(neighborhood_id)")
         .await
-        .map_err(|e| format!("Failed to create index: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to create index: {e}")))?;
     sqlite
         .exec_raw("ANALYZE")
         .await
-        .map_err(|e| format!("Failed to run ANALYZE: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to run ANALYZE: {e}")))?;
 
     // Checkpoint the WAL to ensure all data is in the main .db file.
     // Without this, uploaded/transferred .db files may be missing data
@@ -309,28 +417,49 @@ async fn merge_sidebar_db(
     sqlite
         .exec_raw("PRAGMA wal_checkpoint(TRUNCATE)")
         .await
-        .map_err(|e| format!("Failed to checkpoint WAL: {e}"))?;
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to checkpoint WAL: {e}")))?;
 
     log::info!("Sidebar merge complete: {}", output_path.display());
     Ok(())
 }
 
+/// Returns `true` if the partition sidebar DB at `path` has a
+/// `raw_records` table.
+async fn has_raw_records(path: &Path) -> Result<bool, GenerateError> {
+    let db = switchy_database_connection::init_sqlite_rusqlite(Some(path))
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to open {}: {e}", path.display())))?;
+    let rows = db
+        .query_raw_params(
+            "SELECT COUNT(*) AS n FROM sqlite_master WHERE type = 'table' AND name = 'raw_records'",
+            &[],
+        )
+        .await
+        .map_err(|e| GenerateError::Sqlite(format!("Failed to query {}: {e}", path.display())))?;
+    let count: i64 = rows
+        .first()
+        .and_then(|row| row.to_value("n").ok())
+        .unwrap_or_default();
+    Ok(count > 0)
+}
+
 // ============================================================
 // DuckDB counts.duckdb merge
 // ============================================================
 
 /// Merges `counts.duckdb` from all partitions.
 ///
-/// Each partition's `count_summary` table is already pre-aggregated by
-/// `source_id`, so a simple `UNION ALL` produces correct results without
-/// re-aggregation.
-fn merge_count_db(
-    partition_dirs: &[PathBuf],
-    output_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+/// `count_summary` is merged with [`merge_summed_table`].
+/// `count_summary_monthly` is merged the same way, but only if every
+/// partition has it (a partial merge would undercount).
+/// `boundary_counts` is rebuilt from the merged `count_summary` because a
+/// boundary can span partitions, and `count_summary_rolling` (if every
+/// partition has it) because its windows end at the latest day overall.
+/// `count_timeseries` (if every partition has it) is rebuilt too, since
+/// it's cheaper than summing it separately.
+fn merge_count_db(partition_dirs: &[PathBuf], output_dir: &Path) -> Result<(), GenerateError> {
     let inputs: Vec<PathBuf> = partition_dirs
         .iter()
-        .map(|d| d.join("counts.duckdb"))
+        .map(|d| d.join(paths::COUNTS_DB_FILE))
         .filter(|p| p.exists())
         .collect();
 
@@ -339,7 +468,7 @@ fn merge_count_db(
         return Ok(());
     }
 
-    let output_path = output_dir.join("counts.duckdb");
+    let output_path = output_dir.join(paths::COUNTS_DB_FILE);
     // Remove existing file + WAL
     for ext in &["", ".wal"] {
         let p = output_dir.join(format!("counts.duckdb{ext}"));
@@ -351,21 +480,80 @@ fn merge_count_db(
     log::info!("Merging {} counts.duckdb files...", inputs.len());
     let duck = crate::open_output_duckdb(&output_path)?;
 
-    // Attach all partitions and build UNION ALL
-    let mut union_parts = Vec::with_capacity(inputs.len());
+    // Attach all partitions
     for (i, input) in inputs.iter().enumerate() {
         let alias = format!("p{i}");
         let path_str = input.to_string_lossy();
         duck.execute_batch(&format!("ATTACH '{path_str}' AS {alias} (READ_ONLY)"))?;
-        union_parts.push(format!("SELECT * FROM {alias}.count_summary"));
     }
 
-    let union_query = union_parts.join(" UNION ALL ");
-    duck.execute_batch(&format!("CREATE TABLE count_summary AS {union_query}"))?;
+    merge_summed_table(&duck, "count_summary", inputs.len(), "cell_lng, cell_lat")?;
 
     // Create index
     duck.execute_batch("CREATE INDEX idx_count_summary_cell ON count_summary(cell_lng, cell_lat)")?;
 
+    crate::create_boundary_counts(&duck)?;
+
+    let monthly_count: i64 = duck.query_row(
+        "SELECT COUNT(*) FROM duckdb_tables()
+         WHERE database_name LIKE 'p%' AND table_name = 'count_summary_monthly'",
+        [],
+        |row| row.get(0),
+    )?;
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let monthly_count = monthly_count as usize;
+    if monthly_count == inputs.len() {
+        merge_summed_table(
+            &duck,
+            "count_summary_monthly",
+            inputs.len(),
+            "cell_lng, cell_lat",
+        )?;
+        duck.execute_batch(
+            "CREATE INDEX idx_count_summary_monthly_cell
+             ON count_summary_monthly(cell_lng, cell_lat)",
+        )?;
+    } else if monthly_count > 0 {
+        log::warn!(
+            "Only {monthly_count}/{} partitions have count_summary_monthly; skipping it",
+            inputs.len()
+        );
+    }
+
+    let rolling_count: i64 = duck.query_row(
+        "SELECT COUNT(*) FROM duckdb_tables()
+         WHERE database_name LIKE 'p%' AND table_name = 'count_summary_rolling'",
+        [],
+        |row| row.get(0),
+    )?;
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let rolling_count = rolling_count as usize;
+    if rolling_count == inputs.len() {
+        crate::create_count_rolling(&duck)?;
+    } else if rolling_count > 0 {
+        log::warn!(
+            "Only {rolling_count}/{} partitions have count_summary_rolling; skipping it",
+            inputs.len()
+        );
+    }
+
+    let timeseries_count: i64 = duck.query_row(
+        "SELECT COUNT(*) FROM duckdb_tables()
+         WHERE database_name LIKE 'p%' AND table_name = 'count_timeseries'",
+        [],
+        |row| row.get(0),
+    )?;
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let timeseries_count = timeseries_count as usize;
+    if timeseries_count == inputs.len() {
+        crate::create_count_timeseries(&duck)?;
+    } else if timeseries_count > 0 {
+        log::warn!(
+            "Only {timeseries_count}/{} partitions have count_timeseries; skipping it",
+            inputs.len()
+        );
+    }
+
     // Detach all
     for i in 0..inputs.len() {
         duck.execute_batch(&format!("DETACH p{i}"))?;
@@ -384,16 +572,19 @@ fn merge_count_db(
 
 /// Merges `h3.duckdb` from all partitions.
 ///
-/// `h3_counts` rows are unioned directly. `h3_boundaries` rows are
-/// deduplicated by `(h3_index, resolution)` since hex cells near state
-/// borders may appear in multiple partitions.
+/// `h3_counts` is merged with [`merge_summed_table`], then cells below
+/// `h3_min_cell_count` are suppressed from the summed counts.
+/// `h3_boundaries` rows are deduplicated by `h3_index` since hex cells
+/// near state borders may appear in multiple partitions, and rows of
+/// suppressed cells are dropped.
 fn merge_h3_db(
     partition_dirs: &[PathBuf],
     output_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+    h3_min_cell_count: &BTreeMap<u8, u32>,
+) -> Result<(), GenerateError> {
     let inputs: Vec<PathBuf> = partition_dirs
         .iter()
-        .map(|d| d.join("h3.duckdb"))
+        .map(|d| d.join(paths::H3_DB_FILE))
         .filter(|p| p.exists())
         .collect();
 
@@ -402,14 +593,18 @@ fn merge_h3_db(
         return Ok(());
     }
 
-    let output_path = output_dir.join("h3.duckdb");
-    // Remove existing file + WAL
+    let output_path = output_dir.join(paths::H3_DB_FILE);
+    // Remove existing file + WAL, and any stats of a previous merge
     for ext in &["", ".wal"] {
         let p = output_dir.join(format!("h3.duckdb{ext}"));
         if p.exists() {
             std::fs::remove_file(&p)?;
         }
     }
+    let stats_path = output_dir.join(paths::H3_STATS_FILE);
+    if stats_path.exists() {
+        std::fs::remove_file(&stats_path)?;
+    }
 
     log::info!("Merging {} h3.duckdb files...", inputs.len());
     let duck = crate::open_output_duckdb(&output_path)?;
@@ -421,15 +616,8 @@ fn merge_h3_db(
         duck.execute_batch(&format!("ATTACH '{path_str}' AS {alias} (READ_ONLY)"))?;
     }
 
-    // UNION ALL h3_counts
-    let counts_union: Vec<String> = (0..inputs.len())
-        .map(|i| format!("SELECT * FROM p{i}.h3_counts"))
-        .collect();
-
-    duck.execute_batch(&format!(
-        "CREATE TABLE h3_counts AS {}",
-        counts_union.join(" UNION ALL ")
-    ))?;
+    merge_summed_table(&duck, "h3_counts", inputs.len(), "resolution, h3_index")?;
+    let suppressed = crate::suppress_sparse_h3_cells(&duck, h3_min_cell_count)?;
 
     // Create index on h3_counts
     duck.execute_batch("CREATE INDEX idx_h3_counts ON h3_counts(resolution, h3_index)")?;
@@ -443,6 +631,7 @@ fn merge_h3_db(
         "CREATE TABLE h3_boundaries AS
          SELECT DISTINCT ON (h3_index) *
          FROM ({})
+         WHERE h3_index IN (SELECT h3_index FROM h3_counts)
          ORDER BY h3_index",
         boundaries_union.join(" UNION ALL ")
     ))?;
@@ -452,6 +641,21 @@ fn merge_h3_db(
         duck.execute_batch(&format!("DETACH p{i}"))?;
     }
 
+    let mut stats = crate::h3_stats(&duck)?;
+    for s in &mut stats {
+        if let Some(&(cells, incidents)) = suppressed.get(&s.resolution) {
+            s.suppressed_cells = cells;
+            s.suppressed_incidents = incidents;
+        }
+    }
+    crate::log_h3_stats(&stats);
+    if partition_dirs
+        .iter()
+        .any(|d| d.join(paths::H3_STATS_FILE).exists())
+    {
+        crate::write_h3_stats(&stats, output_dir)?;
+    }
+
     // Compact the merged file before upload.
     duck.execute_batch("VACUUM")?;
 
@@ -459,6 +663,66 @@ fn merge_h3_db(
     Ok(())
 }
 
+// ============================================================
+// Pre-aggregated count tables
+// ============================================================
+
+/// Columns of a pre-aggregated count table that are summed when merging
+/// partitions. Every other column is part of the group key.
+const SUMMED_COLUMNS: &[&str] = &["cnt", "sum_lng", "sum_lat"];
+
+/// Creates `table` in `duck` from the same-named table of each attached
+/// partition `p0`..`p{partitions - 1}`, summing [`SUMMED_COLUMNS`] per
+/// group key and ordering rows by `order_by`.
+///
+/// Rows for the same key in several partitions are combined rather than
+/// duplicated. The aggregation is a single `INSERT INTO ... SELECT ...
+/// GROUP BY` over the attached tables, so `DuckDB` streams it (spilling to
+/// disk past its memory limit) instead of the partitions being loaded into
+/// the Rust heap. The merged table keeps the column types of `p0`'s.
+///
+/// # Errors
+///
+/// Returns an error if `p0` has no `table` or any query fails.
+fn merge_summed_table(
+    duck: &duckdb::Connection,
+    table: &str,
+    partitions: usize,
+    order_by: &str,
+) -> Result<(), GenerateError> {
+    let mut stmt = duck.prepare(
+        "SELECT column_name FROM duckdb_columns()
+         WHERE database_name = 'p0' AND table_name = ?
+         ORDER BY column_index",
+    )?;
+    let columns = stmt
+        .query_map([table], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let (sums, keys): (Vec<String>, Vec<String>) = columns
+        .into_iter()
+        .partition(|column| SUMMED_COLUMNS.contains(&column.as_str()));
+    let keys = keys.join(", ");
+    let sums = sums
+        .iter()
+        .map(|column| format!("SUM({column}) AS {column}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let union = (0..partitions)
+        .map(|i| format!("SELECT * FROM p{i}.{table}"))
+        .collect::<Vec<_>>()
+        .join(" UNION ALL BY NAME ");
+
+    duck.execute_batch(&format!(
+        "CREATE TABLE {table} AS SELECT * FROM p0.{table} LIMIT 0;
+         INSERT INTO {table} BY NAME
+         SELECT {keys}, {sums}
+         FROM ({union})
+         GROUP BY {keys}
+         ORDER BY {order_by};"
+    ))?;
+    Ok(())
+}
+
 // ============================================================
 // DuckDB analytics.duckdb merge
 // ============================================================
@@ -483,13 +747,10 @@ const ANALYTICS_REFERENCE_TABLES: &[&str] = &[
 /// `neighborhoods`, etc.) are identical across partitions and are copied
 /// from the first partition.
 #[allow(clippy::too_many_lines)]
-fn merge_analytics_db(
-    partition_dirs: &[PathBuf],
-    output_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+fn merge_analytics_db(partition_dirs: &[PathBuf], output_dir: &Path) -> Result<(), GenerateError> {
     let inputs: Vec<PathBuf> = partition_dirs
         .iter()
-        .map(|d| d.join("analytics.duckdb"))
+        .map(|d| d.join(paths::ANALYTICS_DB_FILE))
         .filter(|p| p.exists())
         .collect();
 
@@ -498,14 +759,18 @@ fn merge_analytics_db(
         return Ok(());
     }
 
-    let output_path = output_dir.join("analytics.duckdb");
-    // Remove existing file + WAL
+    let output_path = output_dir.join(paths::ANALYTICS_DB_FILE);
+    // Remove existing file + WAL, and any NDJSON export of a previous merge
     for ext in &["", ".wal"] {
         let p = output_dir.join(format!("analytics.duckdb{ext}"));
         if p.exists() {
             std::fs::remove_file(&p)?;
         }
     }
+    let ndjson_path = output_dir.join(paths::ANALYTICS_NDJSON_FILE);
+    if ndjson_path.exists() {
+        std::fs::remove_file(&ndjson_path)?;
+    }
 
     log::info!("Merging {} analytics.duckdb files...", inputs.len());
     let duck = crate::open_output_duckdb(&output_path)?;
@@ -551,6 +816,13 @@ fn merge_analytics_db(
         duck.execute_batch(&format!("DETACH p{i}"))?;
     }
 
+    if partition_dirs
+        .iter()
+        .any(|d| d.join(paths::ANALYTICS_NDJSON_FILE).exists())
+    {
+        crate::export_analytics_ndjson(&duck, output_dir)?;
+    }
+
     // Compact the merged file before upload.
     duck.execute_batch("VACUUM")?;
 
@@ -566,14 +838,12 @@ fn merge_analytics_db(
 ///
 /// Unions city arrays (deduplicated and sorted), unions source arrays
 /// (deduplicated by `id`), takes the MIN of all `minDate` values and the
-/// MAX of all `maxDate` values.
-fn merge_metadata(
-    partition_dirs: &[PathBuf],
-    output_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
+/// MAX of all `maxDate` values. Also rebuilds `health.json` from the
+/// merged result.
+fn merge_metadata(partition_dirs: &[PathBuf], output_dir: &Path) -> Result<(), GenerateError> {
     let inputs: Vec<PathBuf> = partition_dirs
         .iter()
-        .map(|d| d.join("metadata.json"))
+        .map(|d| d.join(paths::METADATA_FILE))
         .filter(|p| p.exists())
         .collect();
 
@@ -584,7 +854,8 @@ fn merge_metadata(
 
     log::info!("Merging {} metadata.json files...", inputs.len());
 
-    let mut all_cities: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+    let mut all_cities: std::collections::BTreeSet<(String, String)> =
+        std::collections::BTreeSet::new();
     let mut all_sources: std::collections::BTreeMap<String, serde_json::Value> =
         std::collections::BTreeMap::new();
     let mut min_date: Option<String> = None;
@@ -597,7 +868,9 @@ fn merge_metadata(
         // Collect cities
         if let Some(cities) = meta.get("cities").and_then(|c| c.as_array()) {
             for city in cities {
-                all_cities.insert(city.to_string());
+                if let Ok(pair) = serde_json::from_value(city.clone()) {
+                    all_cities.insert(pair);
+                }
             }
         }
 
@@ -627,10 +900,10 @@ fn merge_metadata(
         }
     }
 
-    // Reconstruct city arrays from deduplicated set
+    // Same `(city, state)` order as a single run's `generate_metadata`
     let cities: Vec<serde_json::Value> = all_cities
-        .into_iter()
-        .filter_map(|s| serde_json::from_str(&s).ok())
+        .iter()
+        .map(|(city, state)| serde_json::json!([city, state]))
         .collect();
 
     // Collect sources sorted by id
@@ -641,14 +914,17 @@ fn merge_metadata(
         "minDate": min_date,
         "maxDate": max_date,
         "sources": sources,
+        "build": crate::build_info(),
     });
 
-    let path = output_dir.join("metadata.json");
+    let path = output_dir.join(paths::METADATA_FILE);
     let tmp = output_dir.join("metadata.json.tmp");
     std::fs::write(&tmp, serde_json::to_string_pretty(&merged)?)?;
     std::fs::rename(&tmp, &path)?;
 
     log::info!("Metadata merge complete: {}", path.display());
+
+    crate::write_health(output_dir, &merged)?;
     Ok(())
 }
 
@@ -659,11 +935,8 @@ fn merge_metadata(
 /// Copies pre-generated boundary artifacts (`boundaries.pmtiles` and
 /// `boundaries.db`) from the boundaries directory into the output
 /// directory.
-fn copy_boundary_artifacts(
-    boundaries_dir: &Path,
-    output_dir: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
-    for filename in &["boundaries.pmtiles", "boundaries.db"] {
+fn copy_boundary_artifacts(boundaries_dir: &Path, output_dir: &Path) -> Result<(), GenerateError> {
+    for filename in paths::BOUNDARY_FILES {
         let src = boundaries_dir.join(filename);
         if src.exists() {
             let dst = output_dir.join(filename);
@@ -675,3 +948,129 @@ fn copy_boundary_artifacts(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_partitions_with_an_older_manifest_version() {
+        let root = std::env::temp_dir().join(format!("crime_map_merge_v_{}", std::process::id()));
+        let partitions = [root.join("current"), root.join("stale"), root.join("bare")];
+        for dir in &partitions {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        let manifest = |version: u32| format!(r#"{{"version": {version}}}"#);
+        std::fs::write(
+            partitions[0].join(paths::MANIFEST_FILE),
+            manifest(crate::MANIFEST_VERSION),
+        )
+        .unwrap();
+        std::fs::write(partitions[1].join(paths::MANIFEST_FILE), manifest(2)).unwrap();
+
+        assert!(check_partition_versions(&[partitions[0].clone(), partitions[2].clone()]).is_ok());
+        let err = check_partition_versions(&partitions)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("stale (version 2)"), "{err}");
+        assert!(!err.contains("current"), "{err}");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn count_merge_sums_rows_shared_across_partitions() {
+        let root = std::env::temp_dir().join(format!("crime_map_merge_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let partitions = [root.join("a"), root.join("b")];
+        let rows = [
+            "(1.0, 2.0, 'src', 'THEFT', '2024-01-01', '17', '17031', NULL, NULL, NULL, 2, 2.0, 4.0),
+             (1.0, 3.0, 'src', 'THEFT', '2024-01-01', '17', '17031', NULL, NULL, NULL, 1, 1.0, 3.0)",
+            "(1.0, 2.0, 'src', 'THEFT', '2024-01-01', '17', '17031', NULL, NULL, NULL, 3, 3.0, 6.0)",
+        ];
+        for (dir, rows) in partitions.iter().zip(rows) {
+            std::fs::create_dir_all(dir).unwrap();
+            let duck = duckdb::Connection::open(dir.join(paths::COUNTS_DB_FILE)).unwrap();
+            duck.execute_batch(&format!(
+                "CREATE TABLE count_summary (
+                    cell_lng DOUBLE, cell_lat DOUBLE, source_id VARCHAR, category VARCHAR,
+                    day VARCHAR, state_fips VARCHAR, county_geoid VARCHAR,
+                    place_geoid VARCHAR, tract_geoid VARCHAR, neighborhood_id VARCHAR,
+                    cnt BIGINT, sum_lng DOUBLE, sum_lat DOUBLE
+                 );
+                 INSERT INTO count_summary VALUES {rows};"
+            ))
+            .unwrap();
+        }
+
+        let output = root.join("merged");
+        std::fs::create_dir_all(&output).unwrap();
+        merge_count_db(&partitions, &output).unwrap();
+
+        let duck = duckdb::Connection::open(output.join(paths::COUNTS_DB_FILE)).unwrap();
+        let summary: Vec<(f64, i64, f64)> = duck
+            .prepare("SELECT cell_lat, cnt, sum_lat FROM count_summary ORDER BY cell_lat")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(summary, vec![(2.0, 5, 10.0), (3.0, 1, 3.0)]);
+        let county_total: i64 = duck
+            .query_row(
+                "SELECT SUM(cnt)::BIGINT FROM boundary_counts WHERE boundary_type = 'county'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(county_total, 6);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn h3_merge_suppresses_cells_sparse_after_summing() {
+        let root = std::env::temp_dir().join(format!("crime_map_merge_h3_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let partitions = [root.join("a"), root.join("b")];
+        // Cell 1 has one incident in each partition; cell 2 only one.
+        let rows = [
+            "(9, 1, 'a', 1, 1.0, 1.0), (9, 2, 'a', 1, 2.0, 2.0)",
+            "(9, 1, 'b', 1, 1.0, 1.0)",
+        ];
+        for (dir, rows) in partitions.iter().zip(rows) {
+            std::fs::create_dir_all(dir).unwrap();
+            let duck = duckdb::Connection::open(dir.join(paths::H3_DB_FILE)).unwrap();
+            duck.execute_batch(&format!(
+                "CREATE TABLE h3_counts (
+                    resolution UTINYINT, h3_index UBIGINT, source_id VARCHAR,
+                    cnt INTEGER, sum_lng DOUBLE, sum_lat DOUBLE
+                 );
+                 INSERT INTO h3_counts VALUES {rows};
+                 CREATE TABLE h3_boundaries AS
+                 SELECT DISTINCT h3_index, 0.0 AS v0_lng FROM h3_counts;"
+            ))
+            .unwrap();
+        }
+
+        let output = root.join("merged");
+        std::fs::create_dir_all(&output).unwrap();
+        merge_h3_db(&partitions, &output, &BTreeMap::from([(9, 2)])).unwrap();
+
+        let duck = duckdb::Connection::open(output.join(paths::H3_DB_FILE)).unwrap();
+        let cells = |table: &str| -> Vec<u64> {
+            duck.prepare(&format!(
+                "SELECT DISTINCT h3_index FROM {table} ORDER BY h3_index"
+            ))
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+        };
+        assert_eq!(cells("h3_counts"), vec![1]);
+        assert_eq!(cells("h3_boundaries"), vec![1]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,595 @@
+//! Per-source category remapping applied at generation time.
+//!
+//! A remap config lets operators correct or regroup a source's categories
+//! in the generated outputs without re-ingesting it. The config is a list
+//! of rules, read from TOML (or JSON when the file ends in `.json`):
+//!
+//! ```toml
+//! [[rules]]
+//! source = "chicago_pd"
+//! category = "OTHER"
+//! to_category = "VANDALISM"
+//! to_parent_category = "PROPERTY"
+//! ```
+//!
+//! `category` and `parent_category` use the source `DuckDB` column names:
+//! `category` is the subcategory (e.g. `LARCENY_THEFT`) and
+//! `parent_category` the top-level category (e.g. `PROPERTY`). Every
+//! matcher that is set must match; an unset matcher matches anything. The
+//! first matching rule wins.
+//!
+//! The same file can map a source's severities onto the canonical
+//! [`CrimeSeverity`] scale (1–5) when the source uses a different range:
+//!
+//! ```toml
+//! [[severity_scales]]
+//! source = "some_pd"
+//! min = 0
+//! max = 10
+//! ```
+//!
+//! Values are rescaled linearly from `min..=max` to 1–5. Every emitted
+//! severity is then validated against the canonical range; out-of-range
+//! values are clamped and logged once per source and value.
+//!
+//! Severities can also be overridden by category for every source, so the
+//! same kind of incident has the same severity across the unified map:
+//!
+//! ```toml
+//! [[severity_overrides]]
+//! parent_category = "VIOLENT"
+//! category = "ROBBERY"
+//! severity = 4
+//! ```
+//!
+//! Matchers work as in `rules`, and the first matching override wins.
+//! Precedence, per incident:
+//!
+//! 1. `rules` rewrite the categories.
+//! 2. An override matching the *rewritten* categories sets the severity,
+//!    replacing the source's value (and its severity scale).
+//! 3. Otherwise the source severity is kept, rescaled and clamped as
+//!    above.
+//!
+//! A `[taxonomy]` table adds rollup levels above the subcategory; see
+//! [`crate::taxonomy`]. Its `fallback` regroups subcategories outside the
+//! taxonomy after the `rules` (step 1), so overrides see the fallback
+//! category.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crime_map_crime_models::CrimeSeverity;
+use serde::Deserialize;
+
+use crate::GenerateError;
+use crate::taxonomy::{Taxonomy, TaxonomyFile};
+
+/// A single remap rule.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemapRule {
+    /// Source ID to match, or `None` for every source.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Subcategory to match.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Top-level category to match.
+    #[serde(default)]
+    pub parent_category: Option<String>,
+    /// Replacement subcategory.
+    #[serde(default)]
+    pub to_category: Option<String>,
+    /// Replacement top-level category.
+    #[serde(default)]
+    pub to_parent_category: Option<String>,
+}
+
+impl RemapRule {
+    fn matches(&self, source_id: &str, category: &str, parent_category: &str) -> bool {
+        self.source.as_deref().is_none_or(|s| s == source_id)
+            && self.category.as_deref().is_none_or(|c| c == category)
+            && self
+                .parent_category
+                .as_deref()
+                .is_none_or(|p| p == parent_category)
+    }
+}
+
+/// The severity range a source uses, mapped onto the canonical scale.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeverityScale {
+    /// Source ID the scale applies to.
+    pub source: String,
+    /// Source value that maps to [`CrimeSeverity::MIN`].
+    pub min: i32,
+    /// Source value that maps to [`CrimeSeverity::MAX`].
+    pub max: i32,
+}
+
+impl SeverityScale {
+    /// Linearly rescales `value` from `min..=max` onto the canonical
+    /// scale, rounding to the nearest level. Values outside `min..=max`
+    /// land outside the canonical range.
+    fn rescale(&self, value: i32) -> i32 {
+        let span = f64::from(CrimeSeverity::MAX - CrimeSeverity::MIN);
+        let t = f64::from(value - self.min) / f64::from(self.max - self.min);
+        #[allow(clippy::cast_possible_truncation)]
+        let level = (t * span).round() as i32;
+        i32::from(CrimeSeverity::MIN) + level
+    }
+}
+
+/// A canonical severity for every incident in a category, regardless of
+/// source.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SeverityOverride {
+    /// Subcategory to match.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Top-level category to match.
+    #[serde(default)]
+    pub parent_category: Option<String>,
+    /// Canonical severity ([`CrimeSeverity::MIN`]..=[`CrimeSeverity::MAX`]).
+    pub severity: i32,
+}
+
+impl SeverityOverride {
+    fn matches(&self, category: &str, parent_category: &str) -> bool {
+        self.category.as_deref().is_none_or(|c| c == category)
+            && self
+                .parent_category
+                .as_deref()
+                .is_none_or(|p| p == parent_category)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RemapFile {
+    #[serde(default)]
+    rules: Vec<RemapRule>,
+    #[serde(default)]
+    severity_scales: Vec<SeverityScale>,
+    #[serde(default)]
+    severity_overrides: Vec<SeverityOverride>,
+    #[serde(default)]
+    taxonomy: Option<TaxonomyFile>,
+}
+
+/// A loaded category remap table. The default value remaps nothing and
+/// only validates severities.
+#[derive(Debug, Default, Clone)]
+pub struct CategoryRemap {
+    rules: Vec<RemapRule>,
+    severity_scales: BTreeMap<String, SeverityScale>,
+    severity_overrides: Vec<SeverityOverride>,
+    taxonomy: Taxonomy,
+    /// `(source_id, severity)` pairs already logged as out of range.
+    out_of_range: Arc<Mutex<BTreeSet<(String, i32)>>>,
+    hash: Option<String>,
+}
+
+impl CategoryRemap {
+    /// Loads the remap config at `path`, or returns an empty remap when
+    /// `path` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GenerateError::Io`] if the file can't be read and
+    /// [`GenerateError::InvalidRemap`] if it can't be parsed, a rule
+    /// doesn't rewrite anything, a severity scale is empty or repeated, a
+    /// severity override matches everything or is off the canonical
+    /// scale, or the taxonomy isn't a valid tree (see [`Taxonomy::new`]).
+    pub fn load(path: Option<&Path>) -> Result<Self, GenerateError> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+
+        let bytes = std::fs::read(path)?;
+        let text = std::str::from_utf8(&bytes).map_err(|e| invalid(path, &e))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let file: RemapFile = if is_json {
+            serde_json::from_str(text).map_err(|e| invalid(path, &e))?
+        } else {
+            toml::from_str(text).map_err(|e| invalid(path, &e))?
+        };
+
+        if let Some(i) = file
+            .rules
+            .iter()
+            .position(|r| r.to_category.is_none() && r.to_parent_category.is_none())
+        {
+            return Err(invalid(
+                path,
+                &format!(
+                    "rule {} sets neither to_category nor to_parent_category",
+                    i + 1
+                ),
+            ));
+        }
+
+        let mut severity_scales = BTreeMap::new();
+        for scale in file.severity_scales {
+            if scale.min >= scale.max {
+                return Err(invalid(
+                    path,
+                    &format!(
+                        "severity scale for {} has min {} >= max {}",
+                        scale.source, scale.min, scale.max
+                    ),
+                ));
+            }
+            if severity_scales.contains_key(&scale.source) {
+                return Err(invalid(
+                    path,
+                    &format!("severity scale for {} is defined twice", scale.source),
+                ));
+            }
+            severity_scales.insert(scale.source.clone(), scale);
+        }
+
+        let canonical = i32::from(CrimeSeverity::MIN)..=i32::from(CrimeSeverity::MAX);
+        for (i, over) in file.severity_overrides.iter().enumerate() {
+            if over.category.is_none() && over.parent_category.is_none() {
+                return Err(invalid(
+                    path,
+                    &format!(
+                        "severity override {} sets neither category nor parent_category",
+                        i + 1
+                    ),
+                ));
+            }
+            if !canonical.contains(&over.severity) {
+                return Err(invalid(
+                    path,
+                    &format!(
+                        "severity override {} has severity {}, outside {}-{}",
+                        i + 1,
+                        over.severity,
+                        canonical.start(),
+                        canonical.end()
+                    ),
+                ));
+            }
+        }
+
+        let taxonomy = file
+            .taxonomy
+            .map(Taxonomy::from_file)
+            .transpose()
+            .map_err(|e| invalid(path, &e))?
+            .unwrap_or_default();
+
+        Ok(Self {
+            rules: file.rules,
+            severity_scales,
+            severity_overrides: file.severity_overrides,
+            taxonomy,
+            out_of_range: Arc::default(),
+            hash: Some(content_hash(&bytes)),
+        })
+    }
+
+    /// Builds a remap directly from `rules` (no backing file).
+    #[must_use]
+    pub fn from_rules(rules: Vec<RemapRule>) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    /// Adds severity scales to this remap, replacing any existing scale
+    /// for the same source.
+    #[must_use]
+    pub fn with_severity_scales(mut self, scales: Vec<SeverityScale>) -> Self {
+        for scale in scales {
+            self.severity_scales.insert(scale.source.clone(), scale);
+        }
+        self
+    }
+
+    /// Adds severity overrides to this remap, after any existing ones.
+    #[must_use]
+    pub fn with_severity_overrides(mut self, overrides: Vec<SeverityOverride>) -> Self {
+        self.severity_overrides.extend(overrides);
+        self
+    }
+
+    /// Replaces this remap's taxonomy.
+    #[must_use]
+    pub fn with_taxonomy(mut self, taxonomy: Taxonomy) -> Self {
+        self.taxonomy = taxonomy;
+        self
+    }
+
+    /// Returns the category taxonomy, which is empty if none is
+    /// configured.
+    #[must_use]
+    pub const fn taxonomy(&self) -> &Taxonomy {
+        &self.taxonomy
+    }
+
+    /// Returns `true` if any severity overrides are configured.
+    #[must_use]
+    pub fn has_severity_overrides(&self) -> bool {
+        !self.severity_overrides.is_empty()
+    }
+
+    /// Returns the SHA-256 of the config file contents, or `None` if no
+    /// config was loaded. Recorded in the manifest so editing the config
+    /// triggers regeneration.
+    #[must_use]
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    /// Rewrites `category`/`parent_category` in place using the first rule
+    /// matching `source_id` and the current values, then moves a
+    /// subcategory outside the taxonomy under its fallback category, if
+    /// one is configured.
+    pub fn apply(&self, source_id: &str, category: &mut String, parent_category: &mut String) {
+        if let Some(rule) = self
+            .rules
+            .iter()
+            .find(|r| r.matches(source_id, category, parent_category))
+        {
+            if let Some(to) = &rule.to_category {
+                category.clone_from(to);
+            }
+            if let Some(to) = &rule.to_parent_category {
+                parent_category.clone_from(to);
+            }
+        }
+
+        if let Some(fallback) = self.taxonomy.fallback_for(category) {
+            fallback.clone_into(parent_category);
+        }
+    }
+
+    /// Returns the canonical severity of an incident with the given
+    /// (already remapped) categories and source `severity`: the first
+    /// matching severity override, or else [`Self::normalize_severity`]
+    /// (see the module docs for the precedence).
+    pub fn severity(
+        &self,
+        source_id: &str,
+        category: &str,
+        parent_category: &str,
+        severity: i32,
+    ) -> i32 {
+        self.severity_override(category, parent_category)
+            .unwrap_or_else(|| self.normalize_severity(source_id, severity))
+    }
+
+    /// Returns the severity of the first severity override matching
+    /// `category` and `parent_category`, if any.
+    #[must_use]
+    pub fn severity_override(&self, category: &str, parent_category: &str) -> Option<i32> {
+        self.severity_overrides
+            .iter()
+            .find(|o| o.matches(category, parent_category))
+            .map(|o| o.severity)
+    }
+
+    /// Maps `severity` onto the canonical scale using `source_id`'s
+    /// severity scale, if any, and clamps the result into
+    /// [`CrimeSeverity::MIN`]..=[`CrimeSeverity::MAX`].
+    ///
+    /// The first time a given out-of-range value is seen for a source it
+    /// is logged as a warning.
+    pub fn normalize_severity(&self, source_id: &str, severity: i32) -> i32 {
+        let mapped = self
+            .severity_scales
+            .get(source_id)
+            .map_or(severity, |scale| scale.rescale(severity));
+
+        let min = i32::from(CrimeSeverity::MIN);
+        let max = i32::from(CrimeSeverity::MAX);
+        if (min..=max).contains(&mapped) {
+            return mapped;
+        }
+
+        let first = self
+            .out_of_range
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert((source_id.to_string(), severity));
+        if first {
+            log::warn!(
+                "{source_id}: severity {severity} maps to {mapped}, outside the canonical \
+                 {min}-{max} scale; clamping"
+            );
+        }
+        mapped.clamp(min, max)
+    }
+}
+
+fn invalid(path: &Path, reason: &dyn std::fmt::Display) -> GenerateError {
+    GenerateError::InvalidRemap {
+        path: PathBuf::from(path),
+        reason: reason.to_string(),
+    }
+}
+
+/// Returns the hex SHA-256 of `bytes`.
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    use sha2::Digest as _;
+
+    format!("{:x}", sha2::Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        source: Option<&str>,
+        category: Option<&str>,
+        to_category: Option<&str>,
+        to_parent_category: Option<&str>,
+    ) -> RemapRule {
+        RemapRule {
+            source: source.map(str::to_string),
+            category: category.map(str::to_string),
+            parent_category: None,
+            to_category: to_category.map(str::to_string),
+            to_parent_category: to_parent_category.map(str::to_string),
+        }
+    }
+
+    fn remapped(remap: &CategoryRemap, source_id: &str, category: &str) -> (String, String) {
+        let mut category = category.to_string();
+        let mut parent = "OTHER".to_string();
+        remap.apply(source_id, &mut category, &mut parent);
+        (category, parent)
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_respects_source() {
+        let remap = CategoryRemap::from_rules(vec![
+            rule(
+                Some("chicago_pd"),
+                Some("MISC"),
+                Some("VANDALISM"),
+                Some("PROPERTY"),
+            ),
+            rule(None, Some("MISC"), None, Some("PUBLIC_ORDER")),
+        ]);
+
+        assert_eq!(
+            remapped(&remap, "chicago_pd", "MISC"),
+            ("VANDALISM".to_string(), "PROPERTY".to_string())
+        );
+        assert_eq!(
+            remapped(&remap, "la_pd", "MISC"),
+            ("MISC".to_string(), "PUBLIC_ORDER".to_string())
+        );
+        assert_eq!(
+            remapped(&remap, "la_pd", "THEFT"),
+            ("THEFT".to_string(), "OTHER".to_string())
+        );
+    }
+
+    #[test]
+    fn severity_scales_rescale_and_clamp() {
+        let remap = CategoryRemap::default().with_severity_scales(vec![SeverityScale {
+            source: "ten_point".to_string(),
+            min: 0,
+            max: 10,
+        }]);
+
+        assert_eq!(remap.normalize_severity("ten_point", 0), 1);
+        assert_eq!(remap.normalize_severity("ten_point", 5), 3);
+        assert_eq!(remap.normalize_severity("ten_point", 10), 5);
+        assert_eq!(remap.normalize_severity("ten_point", 14), 5);
+
+        // Sources without a scale are already canonical; only clamped.
+        assert_eq!(remap.normalize_severity("other", 4), 4);
+        assert_eq!(remap.normalize_severity("other", 0), 1);
+        assert_eq!(remap.normalize_severity("other", 9), 5);
+    }
+
+    #[test]
+    fn severity_overrides_take_precedence_over_source_severity() {
+        let remap = CategoryRemap::from_rules(vec![rule(
+            None,
+            Some("HOLDUP"),
+            Some("ROBBERY"),
+            Some("VIOLENT"),
+        )])
+        .with_severity_scales(vec![SeverityScale {
+            source: "ten_point".to_string(),
+            min: 0,
+            max: 10,
+        }])
+        .with_severity_overrides(vec![SeverityOverride {
+            category: Some("ROBBERY".to_string()),
+            parent_category: Some("VIOLENT".to_string()),
+            severity: 4,
+        }]);
+
+        // Matched after the category rules, and over the severity scale.
+        let (category, parent) = remapped(&remap, "ten_point", "HOLDUP");
+        assert_eq!(remap.severity("ten_point", &category, &parent, 10), 4);
+        assert_eq!(remap.severity("other", "ROBBERY", "VIOLENT", 2), 4);
+
+        // Unmatched categories keep their (normalized) source severity.
+        assert_eq!(remap.severity("ten_point", "THEFT", "PROPERTY", 10), 5);
+        assert_eq!(remap.severity("other", "ROBBERY", "PROPERTY", 2), 2);
+    }
+
+    #[test]
+    fn load_rejects_rules_without_replacement() {
+        let dir = std::env::temp_dir().join(format!("crime_map_remap_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("remap.toml");
+        std::fs::write(&path, "[[rules]]\nsource = \"x\"\n").unwrap();
+
+        let err = CategoryRemap::load(Some(&path)).unwrap_err();
+        assert!(matches!(err, GenerateError::InvalidRemap { .. }));
+
+        std::fs::write(
+            &path,
+            "[[rules]]\ncategory = \"MISC\"\nto_category = \"OTHER\"\n",
+        )
+        .unwrap();
+        let remap = CategoryRemap::load(Some(&path)).unwrap();
+        assert_eq!(remap.hash().map(str::len), Some(64));
+
+        std::fs::write(
+            &path,
+            "[[severity_scales]]\nsource = \"x\"\nmin = 3\nmax = 3\n",
+        )
+        .unwrap();
+        let err = CategoryRemap::load(Some(&path)).unwrap_err();
+        assert!(matches!(err, GenerateError::InvalidRemap { .. }));
+
+        std::fs::write(
+            &path,
+            "[taxonomy]\nlevels = [\"ucr_part\"]\nfallback = \"UNCLASSIFIED\"\n\n\
+             [taxonomy.parents]\nROBBERY = \"PART_I\"\n",
+        )
+        .unwrap();
+        let remap = CategoryRemap::load(Some(&path)).unwrap();
+        assert_eq!(
+            remap.taxonomy().rollup("ROBBERY"),
+            Some(["PART_I".to_string()].as_slice())
+        );
+        assert_eq!(
+            remapped(&remap, "chicago_pd", "ROBBERY"),
+            ("ROBBERY".to_string(), "OTHER".to_string())
+        );
+        assert_eq!(
+            remapped(&remap, "chicago_pd", "ARSON"),
+            ("ARSON".to_string(), "UNCLASSIFIED".to_string())
+        );
+
+        std::fs::write(
+            &path,
+            "[taxonomy]\nlevels = [\"ucr_part\"]\n\n[taxonomy.parents]\nA = \"B\"\nB = \"A\"\n",
+        )
+        .unwrap();
+        let err = CategoryRemap::load(Some(&path)).unwrap_err();
+        assert!(matches!(err, GenerateError::InvalidRemap { .. }));
+
+        for invalid_override in ["severity = 4", "category = \"ROBBERY\"\nseverity = 9"] {
+            std::fs::write(
+                &path,
+                format!("[[severity_overrides]]\n{invalid_override}\n"),
+            )
+            .unwrap();
+            let err = CategoryRemap::load(Some(&path)).unwrap_err();
+            assert!(matches!(err, GenerateError::InvalidRemap { .. }));
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,70 @@
+//! Destinations that finalized outputs are handed to during generation
+//! (see [`crate::run_with_sink`]).
+//!
+//! On runners with small disks, staging every output locally before a
+//! separate `r2` push is wasteful. With a sink, each database and JSON
+//! output is uploaded as soon as it's closed and the local copy deleted,
+//! so at most one such output is on disk at a time. Tippecanoe outputs
+//! (`PMTiles`) still need local staging and are never sent to a sink.
+//!
+//! Because sunk outputs no longer exist locally, the manifest can't treat
+//! them as up-to-date and the next run regenerates them.
+
+use std::path::Path;
+
+use crime_map_r2::R2Client;
+
+/// Error returned by an [`OutputSink`].
+pub type SinkError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Receives generated output files once they are finalized.
+#[async_trait::async_trait]
+pub trait OutputSink: Send + Sync {
+    /// Stores the output `file_name` (relative to the output directory),
+    /// whose finalized contents are at `path`. The caller deletes `path`
+    /// after this returns successfully.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file couldn't be stored.
+    async fn put(&self, file_name: &str, path: &Path) -> Result<(), SinkError>;
+}
+
+/// [`OutputSink`] uploading each output to R2 under a key prefix
+/// (e.g. `generated/merged`), tagged with the generator's
+/// [`crate::build_id`].
+pub struct R2Sink {
+    client: R2Client,
+    prefix: String,
+}
+
+impl R2Sink {
+    /// Creates a sink uploading to `{prefix}/{file_name}` via `client`.
+    #[must_use]
+    pub fn new(client: R2Client, prefix: &str) -> Self {
+        Self {
+            client,
+            prefix: prefix.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Returns the key prefix outputs are uploaded under.
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
+#[async_trait::async_trait]
+impl OutputSink for R2Sink {
+    async fn put(&self, file_name: &str, path: &Path) -> Result<(), SinkError> {
+        let key = format!("{}/{file_name}", self.prefix);
+        let build = crate::build_id();
+        let stats = self
+            .client
+            .upload_with_build(&key, path, Some(&build))
+            .await?;
+        log::info!("Uploaded {file_name} to R2 {key} ({stats})");
+        Ok(())
+    }
+}
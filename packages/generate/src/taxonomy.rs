@@ -0,0 +1,330 @@
+//! Multi-level category rollups defined in the category remap config.
+//!
+//! Incidents carry a two-level category (`category` → `subcategory`).
+//! A taxonomy adds more levels above the subcategory, such as UCR
+//! Part I/II, so clients can filter and group at any of them. It lives in
+//! the `[taxonomy]` table of the `--category-remap` file:
+//!
+//! ```toml
+//! [taxonomy]
+//! levels = ["ucr_part", "ucr_group"]
+//!
+//! [taxonomy.parents]
+//! ROBBERY = "VIOLENT_PART_I"
+//! AGGRAVATED_ASSAULT = "VIOLENT_PART_I"
+//! VIOLENT_PART_I = "PART_I"
+//! ```
+//!
+//! `levels` names the rollup columns from the root down. `parents` links
+//! each subcategory (a leaf, after the remap `rules` are applied) and
+//! each rollup node to its parent. Nodes without a parent are roots. The
+//! links must form a tree in which every leaf is exactly `levels` steps
+//! below a root, so each leaf has one value per level.
+//!
+//! The analytics `incidents` and count `count_summary` tables get one
+//! `VARCHAR` column per level (`NULL` for subcategories outside the
+//! taxonomy), and the analytics `crime_categories` table gets a row per
+//! rollup node plus a `{level}_id` link on each subcategory.
+//!
+//! Sources can emit subcategories the taxonomy doesn't list, which would
+//! otherwise keep their own top-level category and clutter category
+//! filters with one-off entries. With a `fallback`, every such
+//! subcategory is grouped under that top-level category instead, keeping
+//! its subcategory:
+//!
+//! ```toml
+//! [taxonomy]
+//! levels = ["ucr_part", "ucr_group"]
+//! fallback = "OTHER"
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Deserialize;
+
+/// Columns of the analytics and count tables the rollup levels are
+/// added to, which a level can't be named after.
+const RESERVED_COLUMNS: &[&str] = &[
+    "arrest",
+    "arrest_made",
+    "category",
+    "category_id",
+    "cell_lat",
+    "cell_lng",
+    "census_place_geoid",
+    "census_tract_geoid",
+    "city",
+    "cnt",
+    "county_geoid",
+    "day",
+    "domestic",
+    "dow",
+    "hour",
+    "id",
+    "level",
+    "month",
+    "name",
+    "neighborhood_id",
+    "occurred_at",
+    "parent_category_id",
+    "parent_id",
+    "place_geoid",
+    "severity",
+    "source_id",
+    "state",
+    "state_fips",
+    "subcategory",
+    "sum_lat",
+    "sum_lng",
+    "tract_geoid",
+];
+
+/// The `[taxonomy]` table of a remap config, before validation.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TaxonomyFile {
+    levels: Vec<String>,
+    parents: BTreeMap<String, String>,
+    #[serde(default)]
+    fallback: Option<String>,
+}
+
+/// A validated category taxonomy. The default value has no levels.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Taxonomy {
+    levels: Vec<String>,
+    /// Each leaf's ancestors, one per level from the root down.
+    rollups: BTreeMap<String, Vec<String>>,
+    /// Top-level category of subcategories that aren't leaves.
+    fallback: Option<String>,
+}
+
+impl Taxonomy {
+    /// Builds a taxonomy from its level names (root first) and
+    /// child-to-parent links.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if there are no levels, a
+    /// level isn't a usable column name or is repeated, the links contain
+    /// a cycle, or a leaf isn't exactly `levels.len()` steps below a root.
+    pub fn new(levels: Vec<String>, parents: &BTreeMap<String, String>) -> Result<Self, String> {
+        if levels.is_empty() {
+            return Err("taxonomy needs at least one level".to_string());
+        }
+        let mut seen = BTreeSet::new();
+        for level in &levels {
+            let is_identifier = level
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+                && level
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !is_identifier {
+                return Err(format!(
+                    "taxonomy level '{level}' must be a lowercase identifier"
+                ));
+            }
+            // `crime_categories` links each level through a `{level}_id`
+            // column.
+            let id_column = format!("{level}_id");
+            if RESERVED_COLUMNS.contains(&level.as_str())
+                || RESERVED_COLUMNS.contains(&id_column.as_str())
+                || levels.contains(&id_column)
+            {
+                return Err(format!(
+                    "taxonomy level '{level}' clashes with an existing column"
+                ));
+            }
+            if !seen.insert(level) {
+                return Err(format!("taxonomy level '{level}' is listed twice"));
+            }
+        }
+        if parents.is_empty() {
+            return Err("taxonomy has no parents".to_string());
+        }
+
+        let inner: BTreeSet<&String> = parents.values().collect();
+        let mut rollups = BTreeMap::new();
+        for leaf in parents.keys().filter(|node| !inner.contains(node)) {
+            let mut path = Vec::new();
+            let mut node = leaf;
+            while let Some(parent) = parents.get(node) {
+                if path.contains(parent) {
+                    return Err(format!("taxonomy has a cycle through '{parent}'"));
+                }
+                path.push(parent.clone());
+                node = parent;
+            }
+            if path.len() != levels.len() {
+                return Err(format!(
+                    "'{leaf}' is {} level(s) below its root '{node}', expected {}",
+                    path.len(),
+                    levels.len()
+                ));
+            }
+            path.reverse();
+            rollups.insert(leaf.clone(), path);
+        }
+
+        // A cycle without a way in has no leaves, so the walks above never
+        // reach it.
+        if let Some(stranded) = parents.keys().find(|node| {
+            !rollups.contains_key(*node) && !rollups.values().any(|path| path.contains(*node))
+        }) {
+            return Err(format!("taxonomy has a cycle through '{stranded}'"));
+        }
+
+        Ok(Self {
+            levels,
+            rollups,
+            fallback: None,
+        })
+    }
+
+    /// Groups every subcategory that isn't a leaf under the top-level
+    /// category `fallback` (see the [module docs](self)).
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if `fallback` is empty.
+    pub fn with_fallback(mut self, fallback: String) -> Result<Self, String> {
+        if fallback.trim().is_empty() {
+            return Err("taxonomy fallback can't be empty".to_string());
+        }
+        self.fallback = Some(fallback);
+        Ok(self)
+    }
+
+    /// Validates the `[taxonomy]` table of a remap config.
+    pub(crate) fn from_file(file: TaxonomyFile) -> Result<Self, String> {
+        let taxonomy = Self::new(file.levels, &file.parents)?;
+        match file.fallback {
+            Some(fallback) => taxonomy.with_fallback(fallback),
+            None => Ok(taxonomy),
+        }
+    }
+
+    /// Returns `true` if no taxonomy is configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Returns the rollup level names, from the root down.
+    #[must_use]
+    pub fn levels(&self) -> &[String] {
+        &self.levels
+    }
+
+    /// Returns every leaf (subcategory) with its ancestors, one per level
+    /// from the root down.
+    #[must_use]
+    pub const fn rollups(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.rollups
+    }
+
+    /// Returns `subcategory`'s ancestors, one per level from the root
+    /// down, or `None` if it isn't a leaf of the taxonomy.
+    #[must_use]
+    pub fn rollup(&self, subcategory: &str) -> Option<&[String]> {
+        self.rollups.get(subcategory).map(Vec::as_slice)
+    }
+
+    /// Returns the fallback top-level category, if one is configured.
+    #[must_use]
+    pub fn fallback(&self) -> Option<&str> {
+        self.fallback.as_deref()
+    }
+
+    /// Returns the fallback top-level category if `subcategory` isn't a
+    /// leaf of the taxonomy, or `None` if it is or there's no fallback.
+    #[must_use]
+    pub fn fallback_for(&self, subcategory: &str) -> Option<&str> {
+        self.fallback
+            .as_deref()
+            .filter(|_| !self.rollups.contains_key(subcategory))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(child, parent)| ((*child).to_string(), (*parent).to_string()))
+            .collect()
+    }
+
+    fn levels() -> Vec<String> {
+        vec!["ucr_part".to_string(), "ucr_group".to_string()]
+    }
+
+    #[test]
+    fn resolves_each_leaf_to_one_value_per_level() {
+        let taxonomy = Taxonomy::new(
+            levels(),
+            &links(&[
+                ("ROBBERY", "VIOLENT_PART_I"),
+                ("HOMICIDE", "VIOLENT_PART_I"),
+                ("VIOLENT_PART_I", "PART_I"),
+                ("VANDALISM", "PROPERTY_PART_II"),
+                ("PROPERTY_PART_II", "PART_II"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            taxonomy.rollup("ROBBERY"),
+            Some(["PART_I".to_string(), "VIOLENT_PART_I".to_string()].as_slice())
+        );
+        assert_eq!(
+            taxonomy.rollup("VANDALISM"),
+            Some(["PART_II".to_string(), "PROPERTY_PART_II".to_string()].as_slice())
+        );
+        assert_eq!(taxonomy.rollup("VIOLENT_PART_I"), None);
+        assert_eq!(taxonomy.rollups().len(), 3);
+        assert_eq!(taxonomy.fallback_for("ARSON"), None);
+
+        let taxonomy = taxonomy.with_fallback("OTHER".to_string()).unwrap();
+        assert_eq!(taxonomy.fallback_for("ARSON"), Some("OTHER"));
+        assert_eq!(taxonomy.fallback_for("ROBBERY"), None);
+        assert!(taxonomy.with_fallback(" ".to_string()).is_err());
+    }
+
+    #[test]
+    fn rejects_cycles_uneven_depths_and_bad_levels() {
+        let cycle = links(&[("A", "B"), ("B", "C"), ("C", "B")]);
+        assert!(
+            Taxonomy::new(levels(), &cycle)
+                .unwrap_err()
+                .contains("cycle")
+        );
+
+        let closed_loop = links(&[("A", "B"), ("B", "ROOT"), ("X", "Y"), ("Y", "X")]);
+        assert!(
+            Taxonomy::new(levels(), &closed_loop)
+                .unwrap_err()
+                .contains("cycle")
+        );
+
+        let uneven = links(&[("A", "B"), ("B", "ROOT"), ("C", "ROOT")]);
+        assert_eq!(
+            Taxonomy::new(levels(), &uneven).unwrap_err(),
+            "'C' is 1 level(s) below its root 'ROOT', expected 2"
+        );
+
+        let tree = links(&[("A", "ROOT")]);
+        for bad in [
+            vec![],
+            vec!["category".to_string()],
+            vec!["Part".to_string()],
+            vec!["part".to_string(), "part".to_string()],
+        ] {
+            assert!(Taxonomy::new(bad, &tree).is_err());
+        }
+    }
+}
@@ -0,0 +1,938 @@
+//! Pluggable backends that tile the incident points into `PMTiles`.
+//!
+//! [`TileBackend::Tippecanoe`] (the default) shells out to tippecanoe.
+//! [`TileBackend::Native`] is an experimental in-process backend for
+//! environments that can't install tippecanoe: it encodes Mapbox Vector
+//! Tiles and writes a `PMTiles` v3 archive in pure Rust, with no external
+//! binary.
+//!
+//! The native backend trades tippecanoe's tiling quality for having no
+//! dependencies. Compared to tippecanoe it:
+//!
+//! - stops at zoom [`NATIVE_MAX_ZOOM`] instead of 14 (clients overzoom);
+//! - thins dense tiles by keeping an evenly spaced subset of at most
+//!   [`NATIVE_MAX_POINTS_PER_TILE`] points below the maximum zoom, rather
+//!   than tippecanoe's density-aware dropping and zoom extension;
+//! - doesn't support point clustering (`--cluster-distance`);
+//! - writes uncompressed tiles and directories, so archives are larger;
+//! - holds every point (and one zoom level of encoded tiles) in memory,
+//!   so it's only suited to small and medium datasets.
+//!
+//! Boundary `PMTiles` and `tile-join` merges always use tippecanoe.
+//!
+//! [`tile_stats`] reads back how many features an archive holds and how
+//! many tippecanoe dropped at each zoom (the native backend records only
+//! the former), from the metadata written alongside the tiles.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead as _, BufReader, BufWriter, Write as _};
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GenerateError, PmtilesCluster};
+
+/// Layer name of the incident points in the `PMTiles`.
+pub const INCIDENTS_LAYER: &str = "incidents";
+
+/// Highest zoom level written by the native backend.
+pub const NATIVE_MAX_ZOOM: u8 = 12;
+
+/// Most points the native backend keeps in one tile below
+/// [`NATIVE_MAX_ZOOM`]. Every point is kept at the maximum zoom.
+pub const NATIVE_MAX_POINTS_PER_TILE: usize = 2_000;
+
+/// Which tiler builds the incidents `PMTiles`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileBackend {
+    /// The external tippecanoe binary.
+    #[default]
+    Tippecanoe,
+    /// The experimental pure-Rust tiler (see the module docs for how it
+    /// falls short of tippecanoe).
+    Native,
+}
+
+impl TileBackend {
+    /// Returns the backend's CLI name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Tippecanoe => "tippecanoe",
+            Self::Native => "native",
+        }
+    }
+
+    /// Returns the [`TileGenerator`] implementing this backend.
+    #[must_use]
+    pub fn generator(self) -> Box<dyn TileGenerator> {
+        match self {
+            Self::Tippecanoe => Box::new(TippecanoeGenerator),
+            Self::Native => Box::new(NativeGenerator::default()),
+        }
+    }
+}
+
+/// Parses a `--tile-backend` value (`tippecanoe` or `native`).
+///
+/// # Errors
+///
+/// Returns an error naming the valid backends if `s` isn't one of them.
+pub fn parse_tile_backend(s: &str) -> Result<TileBackend, String> {
+    match s {
+        "tippecanoe" => Ok(TileBackend::Tippecanoe),
+        "native" => Ok(TileBackend::Native),
+        _ => Err(format!(
+            "unknown tile backend '{s}' (expected 'tippecanoe' or 'native')"
+        )),
+    }
+}
+
+/// Tiles `GeoJSONSeq` point features into a `PMTiles` archive.
+pub trait TileGenerator {
+    /// Tiles the features in `inputs` into a single [`INCIDENTS_LAYER`]
+    /// layer written to `output`, clustering low zooms if `cluster` is
+    /// set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs can't be read, the archive can't be
+    /// written, or the backend doesn't support `cluster`.
+    fn generate(
+        &self,
+        inputs: &[&Path],
+        output: &Path,
+        cluster: Option<&PmtilesCluster>,
+    ) -> Result<(), GenerateError>;
+}
+
+// ============================================================
+// tippecanoe
+// ============================================================
+
+/// Tiles with the external tippecanoe binary.
+pub struct TippecanoeGenerator;
+
+impl TileGenerator for TippecanoeGenerator {
+    fn generate(
+        &self,
+        inputs: &[&Path],
+        output: &Path,
+        cluster: Option<&PmtilesCluster>,
+    ) -> Result<(), GenerateError> {
+        log::info!("Running tippecanoe to generate PMTiles...");
+
+        let mut cmd = Command::new("tippecanoe");
+        cmd.args([
+            "-o",
+            &*output.to_string_lossy(),
+            "--force",
+            "--no-feature-limit",
+            "--no-tile-size-limit",
+            "--minimum-zoom=0",
+            "--maximum-zoom=14",
+            "--drop-densest-as-needed",
+            "--extend-zooms-if-still-dropping",
+        ]);
+        cmd.arg(format!("--layer={INCIDENTS_LAYER}"));
+        if let Some(cluster) = cluster {
+            log::info!(
+                "Clustering points within {}px up to zoom {}",
+                cluster.distance,
+                cluster.max_zoom
+            );
+            cmd.arg(format!("--cluster-distance={}", cluster.distance));
+            cmd.arg(format!("--cluster-maxzoom={}", cluster.max_zoom));
+        }
+        cmd.args(inputs);
+
+        if std::env::var("CI").is_ok() {
+            cmd.arg("--quiet");
+        }
+
+        crate::run_tippecanoe(&mut cmd, output)
+    }
+}
+
+// ============================================================
+// Native
+// ============================================================
+
+/// Experimental pure-Rust tiler (see the module docs).
+pub struct NativeGenerator {
+    /// Highest zoom level written.
+    pub max_zoom: u8,
+    /// Most points kept per tile below `max_zoom`.
+    pub max_points_per_tile: usize,
+}
+
+impl Default for NativeGenerator {
+    fn default() -> Self {
+        Self {
+            max_zoom: NATIVE_MAX_ZOOM,
+            max_points_per_tile: NATIVE_MAX_POINTS_PER_TILE,
+        }
+    }
+}
+
+/// Vector tile extent (coordinate units per tile side).
+const EXTENT: u32 = 4096;
+
+/// A property value as stored in a vector tile.
+#[derive(Debug, Clone)]
+enum PropValue {
+    String(String),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+}
+
+impl PropValue {
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::String(s) => Some(Self::String(s.clone())),
+            serde_json::Value::Bool(b) => Some(Self::Bool(*b)),
+            serde_json::Value::Number(n) => n
+                .as_i64()
+                .map(Self::Int)
+                .or_else(|| n.as_f64().map(Self::Double)),
+            serde_json::Value::Null
+            | serde_json::Value::Array(_)
+            | serde_json::Value::Object(_) => None,
+        }
+    }
+
+    /// `TileJSON` field type for the `vector_layers` metadata.
+    const fn field_type(&self) -> &'static str {
+        match self {
+            Self::String(_) => "String",
+            Self::Int(_) | Self::Double(_) => "Number",
+            Self::Bool(_) => "Boolean",
+        }
+    }
+
+    /// Encodes this value as a vector tile `Value` message.
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        match self {
+            Self::String(s) => put_bytes(&mut buf, 1, s.as_bytes()),
+            Self::Double(d) => {
+                put_key(&mut buf, 3, WIRE_FIXED64);
+                buf.extend_from_slice(&d.to_le_bytes());
+            }
+            Self::Int(i) => {
+                put_key(&mut buf, 6, WIRE_VARINT);
+                put_varint(&mut buf, zigzag64(*i));
+            }
+            Self::Bool(b) => {
+                put_key(&mut buf, 7, WIRE_VARINT);
+                put_varint(&mut buf, u64::from(*b));
+            }
+        }
+        buf
+    }
+}
+
+/// An incident point read from the `GeoJSONSeq` input.
+struct NativePoint {
+    lng: f64,
+    lat: f64,
+    properties: Vec<(String, PropValue)>,
+}
+
+impl TileGenerator for NativeGenerator {
+    fn generate(
+        &self,
+        inputs: &[&Path],
+        output: &Path,
+        cluster: Option<&PmtilesCluster>,
+    ) -> Result<(), GenerateError> {
+        if cluster.is_some() {
+            return Err(GenerateError::InvalidArgs(
+                "the native tile backend doesn't support point clustering".to_string(),
+            ));
+        }
+
+        log::info!(
+            "Tiling incidents in-process (experimental native backend, zoom 0-{})...",
+            self.max_zoom
+        );
+
+        let points = read_points(inputs)?;
+        let mut fields = BTreeMap::new();
+        for point in &points {
+            for (key, value) in &point.properties {
+                fields
+                    .entry(key.clone())
+                    .or_insert_with(|| value.field_type());
+            }
+        }
+
+        let tmp_path = output.with_extension("pmtiles.tmp");
+        let data_path = output.with_extension("pmtiles.data.tmp");
+        let mut data = BufWriter::new(std::fs::File::create(&data_path)?);
+        let mut entries = Vec::new();
+        let mut offset = 0_u64;
+
+        for z in 0..=self.max_zoom {
+            // Tile IDs increase with zoom, and the map is ordered within
+            // one, so tiles are written in tile ID order.
+            for (tile_id, bytes) in self.tiles_at_zoom(&points, z) {
+                data.write_all(&bytes)?;
+                #[allow(clippy::cast_possible_truncation)]
+                let length = bytes.len() as u32;
+                entries.push(DirEntry {
+                    tile_id,
+                    offset,
+                    length,
+                    run_length: 1,
+                });
+                offset += u64::from(length);
+            }
+            log::debug!("  zoom {z}: {} tiles total", entries.len());
+        }
+        data.flush()?;
+        drop(data);
+
+        let metadata = serde_json::json!({
+            "name": INCIDENTS_LAYER,
+            "format": "pbf",
+            "generator": format!("crime_map_generate native {}", crate::build_id()),
+            "vector_layers": [{
+                "id": INCIDENTS_LAYER,
+                "fields": fields,
+                "minzoom": 0,
+                "maxzoom": self.max_zoom,
+            }],
+            "tilestats": {
+                "layerCount": 1,
+                "layers": [{
+                    "layer": INCIDENTS_LAYER,
+                    "count": points.len(),
+                    "geometry": "Point",
+                }],
+            },
+        });
+
+        let header = ArchiveInfo {
+            max_zoom: self.max_zoom,
+            bounds: bounds(&points),
+        };
+        write_pmtiles(&tmp_path, &data_path, &entries, &metadata, &header)?;
+        std::fs::remove_file(&data_path)?;
+        std::fs::rename(&tmp_path, output)?;
+
+        log::info!(
+            "Native tiler wrote {} tiles for {} points",
+            entries.len(),
+            points.len()
+        );
+        Ok(())
+    }
+}
+
+impl NativeGenerator {
+    /// Encodes every non-empty tile at zoom `z`, keyed by tile ID.
+    fn tiles_at_zoom(&self, points: &[NativePoint], z: u8) -> BTreeMap<u64, Vec<u8>> {
+        let mut buckets: BTreeMap<(u32, u32), Vec<(usize, i32, i32)>> = BTreeMap::new();
+        for (i, point) in points.iter().enumerate() {
+            let (x, y, px, py) = tile_position(point.lng, point.lat, z);
+            buckets.entry((x, y)).or_default().push((i, px, py));
+        }
+
+        buckets
+            .into_iter()
+            .map(|((x, y), members)| {
+                let kept: Vec<_> = if z < self.max_zoom && members.len() > self.max_points_per_tile
+                {
+                    let n = members.len();
+                    (0..self.max_points_per_tile)
+                        .map(|k| members[k * n / self.max_points_per_tile])
+                        .collect()
+                } else {
+                    members
+                };
+
+                let mut layer = LayerBuilder::default();
+                for (i, px, py) in kept {
+                    layer.add_point(px, py, &points[i].properties);
+                }
+                (zxy_to_tile_id(z, x, y), layer.into_tile())
+            })
+            .collect()
+    }
+}
+
+/// Reads the point features from `GeoJSONSeq` files, skipping anything
+/// that isn't a valid point.
+fn read_points(inputs: &[&Path]) -> Result<Vec<NativePoint>, GenerateError> {
+    let mut points = Vec::new();
+    let mut skipped = 0_u64;
+
+    for input in inputs {
+        let reader = BufReader::new(std::fs::File::open(input)?);
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let feature: serde_json::Value = serde_json::from_str(&line)?;
+            let geometry = &feature["geometry"];
+            let coords = &geometry["coordinates"];
+            let (true, Some(lng), Some(lat)) = (
+                geometry["type"] == "Point",
+                coords[0].as_f64(),
+                coords[1].as_f64(),
+            ) else {
+                skipped += 1;
+                continue;
+            };
+
+            let properties = feature["properties"]
+                .as_object()
+                .map(|props| {
+                    props
+                        .iter()
+                        .filter_map(|(k, v)| PropValue::from_json(v).map(|v| (k.clone(), v)))
+                        .collect()
+                })
+                .unwrap_or_default();
+            points.push(NativePoint {
+                lng,
+                lat,
+                properties,
+            });
+        }
+    }
+
+    if skipped > 0 {
+        log::warn!("Native tiler skipped {skipped} non-point features");
+    }
+    Ok(points)
+}
+
+/// Returns `[min_lng, min_lat, max_lng, max_lat]` of `points`, or the
+/// whole world if there are none.
+fn bounds(points: &[NativePoint]) -> [f64; 4] {
+    if points.is_empty() {
+        return [-180.0, -85.051_128, 180.0, 85.051_128];
+    }
+    points.iter().fold(
+        [f64::MAX, f64::MAX, f64::MIN, f64::MIN],
+        |[w, s, e, n], p| [w.min(p.lng), s.min(p.lat), e.max(p.lng), n.max(p.lat)],
+    )
+}
+
+/// Returns the Web Mercator tile `(x, y)` containing `(lng, lat)` at zoom
+/// `z`, and the point's position within it in tile extent units.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn tile_position(lng: f64, lat: f64, z: u8) -> (u32, u32, i32, i32) {
+    let n = f64::from(1_u32 << z);
+    let lat = lat.clamp(-85.051_128, 85.051_128).to_radians();
+    let wx = ((lng + 180.0) / 360.0 * n).clamp(0.0, n - f64::EPSILON);
+    let wy =
+        ((1.0 - lat.tan().asinh() / std::f64::consts::PI) / 2.0 * n).clamp(0.0, n - f64::EPSILON);
+    let (tx, ty) = (wx.floor(), wy.floor());
+    let extent = f64::from(EXTENT);
+    let px = ((wx - tx) * extent).floor().min(extent - 1.0);
+    let py = ((wy - ty) * extent).floor().min(extent - 1.0);
+    (tx as u32, ty as u32, px as i32, py as i32)
+}
+
+/// Converts a tile address to its `PMTiles` tile ID: the count of tiles
+/// at lower zooms plus the tile's position along a Hilbert curve.
+fn zxy_to_tile_id(z: u8, x: u32, y: u32) -> u64 {
+    let base = ((1_u64 << (2 * u32::from(z))) - 1) / 3;
+    let (mut x, mut y) = (u64::from(x), u64::from(y));
+    let mut d = 0_u64;
+    let mut s = (1_u64 << z) >> 1;
+    while s > 0 {
+        let rx = u64::from(x & s != 0);
+        let ry = u64::from(y & s != 0);
+        d += s * s * ((3 * rx) ^ ry);
+        if ry == 0 {
+            if rx == 1 {
+                x = s.wrapping_sub(1).wrapping_sub(x);
+                y = s.wrapping_sub(1).wrapping_sub(y);
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s >>= 1;
+    }
+    base + d
+}
+
+// ============================================================
+// Vector tile encoding
+// ============================================================
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        #[allow(clippy::cast_possible_truncation)]
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    buf.push(value as u8);
+}
+
+fn put_key(buf: &mut Vec<u8>, field: u32, wire: u8) {
+    put_varint(buf, u64::from((field << 3) | u32::from(wire)));
+}
+
+fn put_bytes(buf: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    put_key(buf, field, WIRE_LEN);
+    put_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn put_packed(buf: &mut Vec<u8>, field: u32, values: &[u32]) {
+    let mut packed = Vec::new();
+    for &v in values {
+        put_varint(&mut packed, u64::from(v));
+    }
+    put_bytes(buf, field, &packed);
+}
+
+#[allow(clippy::cast_sign_loss)]
+const fn zigzag32(v: i32) -> u32 {
+    ((v << 1) ^ (v >> 31)) as u32
+}
+
+#[allow(clippy::cast_sign_loss)]
+const fn zigzag64(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+/// Accumulates the features, keys, and values of one vector tile layer.
+#[derive(Default)]
+struct LayerBuilder {
+    features: Vec<u8>,
+    keys: Vec<String>,
+    key_index: BTreeMap<String, u32>,
+    values: Vec<Vec<u8>>,
+    value_index: BTreeMap<Vec<u8>, u32>,
+}
+
+impl LayerBuilder {
+    #[allow(clippy::cast_possible_truncation)]
+    fn add_point(&mut self, px: i32, py: i32, properties: &[(String, PropValue)]) {
+        let mut tags = Vec::with_capacity(properties.len() * 2);
+        for (key, value) in properties {
+            let key_id = *self.key_index.entry(key.clone()).or_insert_with(|| {
+                self.keys.push(key.clone());
+                (self.keys.len() - 1) as u32
+            });
+            let encoded = value.encode();
+            let value_id = match self.value_index.get(&encoded) {
+                Some(&id) => id,
+                None => {
+                    let id = self.values.len() as u32;
+                    self.values.push(encoded.clone());
+                    self.value_index.insert(encoded, id);
+                    id
+                }
+            };
+            tags.push(key_id);
+            tags.push(value_id);
+        }
+
+        let mut feature = Vec::new();
+        if !tags.is_empty() {
+            put_packed(&mut feature, 2, &tags);
+        }
+        // GeomType POINT.
+        put_key(&mut feature, 3, WIRE_VARINT);
+        put_varint(&mut feature, 1);
+        // MoveTo with a count of one, then the zigzag-encoded position.
+        put_packed(&mut feature, 4, &[(1 << 3) | 1, zigzag32(px), zigzag32(py)]);
+
+        put_bytes(&mut self.features, 2, &feature);
+    }
+
+    /// Finishes the layer and wraps it in a tile message.
+    fn into_tile(self) -> Vec<u8> {
+        let mut layer = Vec::new();
+        put_key(&mut layer, 15, WIRE_VARINT);
+        put_varint(&mut layer, 2);
+        put_bytes(&mut layer, 1, INCIDENTS_LAYER.as_bytes());
+        layer.extend_from_slice(&self.features);
+        for key in &self.keys {
+            put_bytes(&mut layer, 3, key.as_bytes());
+        }
+        for value in &self.values {
+            put_bytes(&mut layer, 4, value);
+        }
+        put_key(&mut layer, 5, WIRE_VARINT);
+        put_varint(&mut layer, u64::from(EXTENT));
+
+        let mut tile = Vec::new();
+        put_bytes(&mut tile, 3, &layer);
+        tile
+    }
+}
+
+// ============================================================
+// PMTiles v3 writing
+// ============================================================
+
+/// Length of the fixed `PMTiles` v3 header.
+const HEADER_LEN: usize = 127;
+
+/// The header and root directory must fit in the first 16 KiB.
+const ROOT_LIMIT: usize = 16_384;
+
+/// A `PMTiles` directory entry. `run_length == 0` points at a leaf
+/// directory instead of tile data.
+#[derive(Debug, Clone, Copy)]
+struct DirEntry {
+    tile_id: u64,
+    offset: u64,
+    length: u32,
+    run_length: u32,
+}
+
+/// Header fields that aren't layout offsets.
+struct ArchiveInfo {
+    max_zoom: u8,
+    bounds: [f64; 4],
+}
+
+/// Serializes a directory (uncompressed).
+fn serialize_directory(entries: &[DirEntry]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    put_varint(&mut buf, entries.len() as u64);
+
+    let mut last_id = 0;
+    for e in entries {
+        put_varint(&mut buf, e.tile_id - last_id);
+        last_id = e.tile_id;
+    }
+    for e in entries {
+        put_varint(&mut buf, u64::from(e.run_length));
+    }
+    for e in entries {
+        put_varint(&mut buf, u64::from(e.length));
+    }
+    for (i, e) in entries.iter().enumerate() {
+        let contiguous = i > 0 && {
+            let prev = entries[i - 1];
+            e.offset == prev.offset + u64::from(prev.length)
+        };
+        put_varint(&mut buf, if contiguous { 0 } else { e.offset + 1 });
+    }
+    buf
+}
+
+/// Builds the root directory, splitting `entries` into leaf directories
+/// when they don't fit in the root. Returns `(root, leaves)`.
+fn build_directories(entries: &[DirEntry]) -> (Vec<u8>, Vec<u8>) {
+    let root = serialize_directory(entries);
+    if HEADER_LEN + root.len() <= ROOT_LIMIT {
+        return (root, Vec::new());
+    }
+
+    let mut leaf_size = 4_096;
+    loop {
+        let mut leaves = Vec::new();
+        let mut root_entries = Vec::new();
+        for chunk in entries.chunks(leaf_size) {
+            let leaf = serialize_directory(chunk);
+            #[allow(clippy::cast_possible_truncation)]
+            root_entries.push(DirEntry {
+                tile_id: chunk[0].tile_id,
+                offset: leaves.len() as u64,
+                length: leaf.len() as u32,
+                run_length: 0,
+            });
+            leaves.extend_from_slice(&leaf);
+        }
+        let root = serialize_directory(&root_entries);
+        if HEADER_LEN + root.len() <= ROOT_LIMIT {
+            return (root, leaves);
+        }
+        leaf_size *= 2;
+    }
+}
+
+/// Writes a `PMTiles` v3 archive to `path` from the tile data already
+/// written (in tile ID order) to `data_path`.
+fn write_pmtiles(
+    path: &Path,
+    data_path: &Path,
+    entries: &[DirEntry],
+    metadata: &serde_json::Value,
+    info: &ArchiveInfo,
+) -> Result<(), GenerateError> {
+    let (root, leaves) = build_directories(entries);
+    let metadata = serde_json::to_vec(metadata)?;
+    let data_len = std::fs::metadata(data_path)?.len();
+
+    let root_offset = HEADER_LEN as u64;
+    let metadata_offset = root_offset + root.len() as u64;
+    let leaves_offset = metadata_offset + metadata.len() as u64;
+    let data_offset = leaves_offset + leaves.len() as u64;
+    let tiles = entries.len() as u64;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let e7 = |deg: f64| (deg * 10_000_000.0).round() as i32;
+    let [west, south, east, north] = info.bounds;
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(b"PMTiles");
+    header.push(3);
+    for value in [
+        root_offset,
+        root.len() as u64,
+        metadata_offset,
+        metadata.len() as u64,
+        leaves_offset,
+        leaves.len() as u64,
+        data_offset,
+        data_len,
+        tiles,
+        tiles,
+        tiles,
+    ] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    // Clustered; no internal or tile compression; MVT tiles.
+    header.extend_from_slice(&[1, 1, 1, 1, 0, info.max_zoom]);
+    for value in [e7(west), e7(south), e7(east), e7(north)] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+    header.push(0);
+    header.extend_from_slice(&e7(f64::midpoint(west, east)).to_le_bytes());
+    header.extend_from_slice(&e7(f64::midpoint(south, north)).to_le_bytes());
+    debug_assert_eq!(header.len(), HEADER_LEN);
+
+    let mut out = BufWriter::new(std::fs::File::create(path)?);
+    out.write_all(&header)?;
+    out.write_all(&root)?;
+    out.write_all(&metadata)?;
+    out.write_all(&leaves)?;
+    std::io::copy(&mut std::fs::File::open(data_path)?, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+// ============================================================
+// Tile statistics
+// ============================================================
+
+/// Feature counts and thinning of a `PMTiles` archive, read from the
+/// `tilestats` and `strategies` metadata tippecanoe writes into it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileStats {
+    /// Features in each layer, by layer name.
+    pub layers: BTreeMap<String, u64>,
+    /// How each zoom's tiles were thinned to fit, by zoom. Zooms that
+    /// kept every feature are left out.
+    pub zooms: BTreeMap<u8, ZoomStats>,
+}
+
+/// How tippecanoe thinned the tiles of one zoom level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoomStats {
+    /// Features dropped or coalesced across all layers.
+    pub dropped: u64,
+    /// Size in bytes of the largest tile that had to be shrunk, if any.
+    pub max_tile_bytes: Option<u64>,
+}
+
+impl TileStats {
+    /// Extracts the statistics from a `PMTiles` metadata object. Missing
+    /// keys (e.g. in archives from older tippecanoe versions) are treated
+    /// as empty.
+    #[must_use]
+    pub fn from_metadata(metadata: &serde_json::Value) -> Self {
+        let layers = metadata["tilestats"]["layers"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|layer| {
+                Some((
+                    layer["layer"].as_str()?.to_string(),
+                    layer["count"].as_u64()?,
+                ))
+            })
+            .collect();
+
+        let zooms = metadata["strategies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(zoom, strategy)| {
+                let strategy = strategy.as_object()?;
+                let dropped = strategy
+                    .iter()
+                    .filter(|(key, _)| key.starts_with("dropped_") || key.starts_with("coalesced_"))
+                    .filter_map(|(_, count)| count.as_u64())
+                    .sum();
+                let max_tile_bytes = strategy
+                    .get("tile_size_desired")
+                    .and_then(serde_json::Value::as_u64);
+                if dropped == 0 && max_tile_bytes.is_none() {
+                    return None;
+                }
+                Some((
+                    u8::try_from(zoom).ok()?,
+                    ZoomStats {
+                        dropped,
+                        max_tile_bytes,
+                    },
+                ))
+            })
+            .collect();
+
+        Self { layers, zooms }
+    }
+
+    /// Returns the features across all layers.
+    #[must_use]
+    pub fn features(&self) -> u64 {
+        self.layers.values().sum()
+    }
+
+    /// Logs one line per layer and per thinned zoom, prefixed with `file`.
+    pub fn log(&self, file: &str) {
+        for (layer, count) in &self.layers {
+            log::info!("{file}: layer {layer} has {count} features");
+        }
+        let features = self.features();
+        for (zoom, stats) in &self.zooms {
+            #[allow(clippy::cast_precision_loss)]
+            let percent = if features == 0 {
+                0.0
+            } else {
+                stats.dropped as f64 * 100.0 / features as f64
+            };
+            let largest = stats
+                .max_tile_bytes
+                .map(|bytes| format!(", largest tile {bytes} bytes"))
+                .unwrap_or_default();
+            log::info!(
+                "{file}: zoom {zoom} dropped {} features ({percent:.1}%){largest}",
+                stats.dropped
+            );
+        }
+    }
+}
+
+/// Reads the [`TileStats`] of the `PMTiles` archive at `path`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the archive or its metadata
+/// can't be read.
+pub fn tile_stats(path: &Path) -> Result<TileStats, String> {
+    crate::verify::pmtiles_metadata(path).map(|metadata| TileStats::from_metadata(&metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_ids_follow_the_pmtiles_hilbert_order() {
+        assert_eq!(zxy_to_tile_id(0, 0, 0), 0);
+        assert_eq!(zxy_to_tile_id(1, 0, 0), 1);
+        assert_eq!(zxy_to_tile_id(1, 0, 1), 2);
+        assert_eq!(zxy_to_tile_id(1, 1, 1), 3);
+        assert_eq!(zxy_to_tile_id(1, 1, 0), 4);
+        assert_eq!(zxy_to_tile_id(2, 0, 0), 5);
+    }
+
+    #[test]
+    fn native_backend_writes_a_pmtiles_archive() {
+        let dir =
+            std::env::temp_dir().join(format!("crime_map_native_tiles_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("incidents.geojsonseq");
+        let feature = r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[-87.63,41.88]},"properties":{"sid":"a-1","severity":2,"arrest":null}}"#;
+        std::fs::write(&input, format!("{feature}\n{feature}\n")).unwrap();
+        let output = dir.join("incidents.pmtiles");
+
+        let generator = NativeGenerator {
+            max_zoom: 4,
+            max_points_per_tile: 1,
+        };
+        generator.generate(&[&input], &output, None).unwrap();
+
+        let mut file = std::fs::File::open(&output).unwrap();
+        let header = crate::verify::read_pmtiles_header(&mut file).unwrap();
+        let header_u64 = |at| crate::verify::header_u64(&header, at);
+        // Both points share a tile at every zoom: one tile per zoom 0-4.
+        assert_eq!(header_u64(80), 5);
+        // Header, directory, metadata, and tile data account for every byte.
+        assert_eq!(
+            header_u64(56) + header_u64(64),
+            file.metadata().unwrap().len()
+        );
+        assert_eq!(header[101], 4);
+        assert_eq!(
+            tile_stats(&output).unwrap().layers,
+            BTreeMap::from([(INCIDENTS_LAYER.to_string(), 2)])
+        );
+
+        assert!(
+            generator
+                .generate(
+                    &[&input],
+                    &output,
+                    Some(&PmtilesCluster {
+                        distance: 10,
+                        max_zoom: 3,
+                    }),
+                )
+                .is_err()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn tile_stats_sum_the_thinning_of_each_zoom() {
+        let metadata = serde_json::json!({
+            "tilestats": {"layerCount": 1, "layers": [{"layer": "incidents", "count": 1000}]},
+            "strategies": [
+                {},
+                {"dropped_by_rate": 300, "detail_reduced": 2},
+                {"dropped_as_needed": 50, "coalesced_as_needed": 10, "tile_size_desired": 612_345},
+            ],
+        });
+        let stats = TileStats::from_metadata(&metadata);
+
+        assert_eq!(stats.features(), 1000);
+        assert_eq!(
+            stats.zooms,
+            BTreeMap::from([
+                (
+                    1,
+                    ZoomStats {
+                        dropped: 300,
+                        max_tile_bytes: None,
+                    }
+                ),
+                (
+                    2,
+                    ZoomStats {
+                        dropped: 60,
+                        max_tile_bytes: Some(612_345),
+                    }
+                ),
+            ])
+        );
+        assert_eq!(
+            TileStats::from_metadata(&serde_json::json!({})),
+            TileStats::default()
+        );
+    }
+}
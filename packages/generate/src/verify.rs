@@ -0,0 +1,298 @@
+//! Smoke checks that each generated output is a valid, queryable file.
+//!
+//! The manifest only checks that an output exists, so a truncated
+//! `PMTiles` archive or a database that no longer opens would pass it and
+//! be uploaded. [`verify_outputs`] instead opens every output present in a
+//! directory the way its consumer would and runs a minimal query:
+//!
+//! | File | Check |
+//! |------|-------|
+//! | `*.pmtiles` | v3 header, tile data within the file, at least one tile, metadata naming the expected layers |
+//! | `incidents.db`, `boundaries.db` | opens as `SQLite`, each table has rows |
+//! | `*.duckdb` | opens read-only, each summary table has rows |
+//! | `metadata.json`, `health.json` | parses, with the keys the server reads |
+//!
+//! Outputs that weren't generated are skipped, so the same check works
+//! for full, partition, merged, and boundary-only directories.
+
+use std::io::{Read as _, Seek as _, SeekFrom};
+use std::path::Path;
+
+use crime_map_database::paths;
+use switchy_database::Database as _;
+
+use crate::tiles::INCIDENTS_LAYER;
+
+/// Length of a `PMTiles` v3 header.
+const PMTILES_HEADER_LEN: usize = 127;
+
+/// Outcome of smoke-checking one output file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputHealth {
+    /// File name within the output directory.
+    pub file: &'static str,
+    /// What's wrong with the file, or `None` if it passed.
+    pub problem: Option<String>,
+}
+
+impl OutputHealth {
+    /// Returns `true` if the file passed its checks.
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        self.problem.is_none()
+    }
+}
+
+/// What to check in one kind of output file.
+enum Check {
+    /// `PMTiles` archive that must contain these vector layers (or any
+    /// layer, if empty).
+    Pmtiles(&'static [&'static str]),
+    /// `SQLite` database whose tables must each have rows.
+    Sqlite(&'static [&'static str]),
+    /// `DuckDB` database whose tables must each have rows.
+    Duckdb(&'static [&'static str]),
+    /// JSON object with these top-level keys.
+    Json(&'static [&'static str]),
+}
+
+/// The checks run by [`verify_outputs`], by file name.
+const CHECKS: &[(&str, Check)] = &[
+    (
+        paths::INCIDENTS_PMTILES_FILE,
+        Check::Pmtiles(&[INCIDENTS_LAYER]),
+    ),
+    // Layers without features are left out by tippecanoe, so any is fine.
+    (paths::BOUNDARIES_PMTILES_FILE, Check::Pmtiles(&[])),
+    (
+        paths::INCIDENTS_DB_FILE,
+        Check::Sqlite(&["incidents", "incidents_rtree"]),
+    ),
+    (paths::BOUNDARIES_DB_FILE, Check::Sqlite(&["boundaries"])),
+    (paths::COUNTS_DB_FILE, Check::Duckdb(&["count_summary"])),
+    (
+        paths::H3_DB_FILE,
+        Check::Duckdb(&["h3_counts", "h3_boundaries"]),
+    ),
+    (paths::ANALYTICS_DB_FILE, Check::Duckdb(&["incidents"])),
+    (paths::CHOROPLETH_DB_FILE, Check::Duckdb(&["tract_counts"])),
+    (
+        paths::METADATA_FILE,
+        Check::Json(&["cities", "minDate", "maxDate", "sources", "build"]),
+    ),
+    (
+        paths::HEALTH_FILE,
+        Check::Json(&["generatedAt", "maxDate", "sources"]),
+    ),
+];
+
+/// Opens every output present in `dir` and runs a minimal smoke check on
+/// it (see the [module docs](self)), returning one [`OutputHealth`] per
+/// file checked. Missing outputs are skipped.
+#[allow(clippy::future_not_send)]
+pub async fn verify_outputs(dir: &Path) -> Vec<OutputHealth> {
+    let mut report = Vec::new();
+    for (file, check) in CHECKS {
+        let path = dir.join(file);
+        if !path.exists() {
+            continue;
+        }
+
+        let result = match check {
+            Check::Pmtiles(layers) => check_pmtiles(&path, layers),
+            Check::Sqlite(tables) => check_sqlite(&path, tables).await,
+            Check::Duckdb(tables) => check_duckdb(&path, tables),
+            Check::Json(keys) => check_json(&path, keys),
+        };
+        if let Err(problem) = &result {
+            log::error!("{file} failed verification: {problem}");
+        } else {
+            log::info!("{file} verified");
+        }
+        report.push(OutputHealth {
+            file,
+            problem: result.err(),
+        });
+    }
+    report
+}
+
+/// Checks that `path` is a complete `PMTiles` v3 archive with at least one
+/// tile whose metadata lists every layer in `layers` (or any layer, if
+/// `layers` is empty).
+fn check_pmtiles(path: &Path, layers: &[&str]) -> Result<(), String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let file_len = file.metadata().map_err(|e| e.to_string())?.len();
+
+    let header = read_pmtiles_header(&mut file)?;
+    let data_end = header_u64(&header, 56).saturating_add(header_u64(&header, 64));
+    if data_end > file_len {
+        return Err(format!(
+            "truncated: tile data ends at byte {data_end} of {file_len}"
+        ));
+    }
+    if header_u64(&header, 72) == 0 {
+        return Err("contains no tiles".to_string());
+    }
+
+    let metadata = read_pmtiles_metadata(&mut file, &header)?;
+    let found: Vec<&str> = metadata["vector_layers"]
+        .as_array()
+        .map(|layers| layers.iter().filter_map(|l| l["id"].as_str()).collect())
+        .unwrap_or_default();
+    if found.is_empty() {
+        return Err("metadata lists no vector layers".to_string());
+    }
+    if let Some(missing) = layers.iter().find(|layer| !found.contains(layer)) {
+        return Err(format!("missing layer '{missing}' (found {found:?})"));
+    }
+    Ok(())
+}
+
+/// Reads the JSON metadata of the `PMTiles` v3 archive at `path`.
+///
+/// # Errors
+///
+/// Returns a description of the problem if the file isn't a `PMTiles` v3
+/// archive or its metadata can't be read.
+pub(crate) fn pmtiles_metadata(path: &Path) -> Result<serde_json::Value, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let header = read_pmtiles_header(&mut file)?;
+    read_pmtiles_metadata(&mut file, &header)
+}
+
+/// Reads and checks the magic of a `PMTiles` v3 header.
+pub(crate) fn read_pmtiles_header(
+    file: &mut std::fs::File,
+) -> Result<[u8; PMTILES_HEADER_LEN], String> {
+    let mut header = [0_u8; PMTILES_HEADER_LEN];
+    file.read_exact(&mut header)
+        .map_err(|e| format!("truncated header: {e}"))?;
+    if &header[..7] != b"PMTiles" || header[7] != 3 {
+        return Err("not a PMTiles v3 archive".to_string());
+    }
+    Ok(header)
+}
+
+/// Returns the little-endian `u64` at byte `at` of a `PMTiles` header.
+pub(crate) fn header_u64(header: &[u8; PMTILES_HEADER_LEN], at: usize) -> u64 {
+    let mut bytes = [0_u8; 8];
+    bytes.copy_from_slice(&header[at..at + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Reads and decompresses the JSON metadata `header` points to.
+fn read_pmtiles_metadata(
+    file: &mut std::fs::File,
+    header: &[u8; PMTILES_HEADER_LEN],
+) -> Result<serde_json::Value, String> {
+    let (metadata_offset, metadata_len) = (header_u64(header, 24), header_u64(header, 32));
+    let mut raw = Vec::new();
+    file.seek(SeekFrom::Start(metadata_offset))
+        .and_then(|_| file.by_ref().take(metadata_len).read_to_end(&mut raw))
+        .map_err(|e| format!("unreadable metadata: {e}"))?;
+    let metadata = match header[97] {
+        // Unknown or none
+        0 | 1 => raw,
+        // gzip (tippecanoe's default)
+        2 => {
+            let mut json = Vec::new();
+            flate2::read::GzDecoder::new(raw.as_slice())
+                .read_to_end(&mut json)
+                .map_err(|e| format!("corrupt gzip metadata: {e}"))?;
+            json
+        }
+        other => return Err(format!("unsupported metadata compression {other}")),
+    };
+    serde_json::from_slice(&metadata).map_err(|e| format!("invalid metadata JSON: {e}"))
+}
+
+/// Checks that `path` opens as `SQLite` and each of `tables` has rows.
+#[allow(clippy::future_not_send)]
+async fn check_sqlite(path: &Path, tables: &[&str]) -> Result<(), String> {
+    let db = switchy_database_connection::init_sqlite_rusqlite(Some(path))
+        .map_err(|e| format!("failed to open: {e}"))?;
+    for table in tables {
+        let rows = db
+            .query_raw_params(&format!("SELECT COUNT(*) AS n FROM {table}"), &[])
+            .await
+            .map_err(|e| format!("failed to query {table}: {e}"))?;
+        let count: i64 = rows
+            .first()
+            .and_then(|row| row.to_value("n").ok())
+            .unwrap_or_default();
+        if count == 0 {
+            return Err(format!("table {table} is empty"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `path` opens read-only as `DuckDB` and each of `tables` has
+/// rows.
+fn check_duckdb(path: &Path, tables: &[&str]) -> Result<(), String> {
+    let conn = duckdb::Config::default()
+        .access_mode(duckdb::AccessMode::ReadOnly)
+        .and_then(|config| duckdb::Connection::open_with_flags(path, config))
+        .map_err(|e| format!("failed to open: {e}"))?;
+    for table in tables {
+        let count: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .map_err(|e| format!("failed to query {table}: {e}"))?;
+        if count == 0 {
+            return Err(format!("table {table} is empty"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `path` parses as a JSON object with every key in `keys`.
+fn check_json(path: &Path, keys: &[&str]) -> Result<(), String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let json: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|e| format!("invalid JSON: {e}"))?;
+    let Some(object) = json.as_object() else {
+        return Err("not a JSON object".to_string());
+    };
+    if let Some(missing) = keys.iter().find(|key| !object.contains_key(**key)) {
+        return Err(format!("missing key '{missing}'"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pmtiles_check_rejects_truncated_and_foreign_files() {
+        let dir = std::env::temp_dir().join(format!("crime_map_verify_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let not_pmtiles = dir.join("garbage.pmtiles");
+        std::fs::write(&not_pmtiles, [0_u8; PMTILES_HEADER_LEN]).unwrap();
+        assert_eq!(
+            check_pmtiles(&not_pmtiles, &[]),
+            Err("not a PMTiles v3 archive".to_string())
+        );
+
+        let short = dir.join("short.pmtiles");
+        std::fs::write(&short, b"PMTiles\x03").unwrap();
+        assert!(
+            check_pmtiles(&short, &[])
+                .unwrap_err()
+                .starts_with("truncated header")
+        );
+
+        let json = dir.join("metadata.json");
+        std::fs::write(&json, r#"{"cities": [], "sources": []}"#).unwrap();
+        assert_eq!(
+            check_json(&json, &["cities", "minDate"]),
+            Err("missing key 'minDate'".to_string())
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
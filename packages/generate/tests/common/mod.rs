@@ -0,0 +1,216 @@
+//! Fixture data directory shared by the generation integration tests.
+//!
+//! `CRIME_MAP_DATA_DIR` is process-wide, so each test binary writes the
+//! fixture once, under its own temporary directory (see [`fixture`]), and
+//! its tests take turns using it. Tests that change the fixture data live
+//! in their own binary.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crime_map_database::paths;
+use crime_map_generate::GenerateArgs;
+use switchy_database::Database as _;
+
+/// `(id, category, parent_category, lng, lat, occurred_at)`.
+pub type FixtureIncident = (
+    &'static str,
+    &'static str,
+    &'static str,
+    f64,
+    f64,
+    &'static str,
+);
+
+/// `(source_id, incidents)` for each fixture source.
+pub const FIXTURE_SOURCES: &[(&str, &[FixtureIncident])] = &[
+    (
+        "fixture_a",
+        &[
+            (
+                "a-1",
+                "THEFT",
+                "PROPERTY",
+                -87.63,
+                41.88,
+                "2024-03-01 14:30:00+00",
+            ),
+            (
+                "a-2",
+                "ASSAULT",
+                "VIOLENT",
+                -87.62,
+                41.89,
+                "2024-03-02 09:15:00+00",
+            ),
+            (
+                "a-3",
+                "THEFT",
+                "PROPERTY",
+                -87.64,
+                41.87,
+                "2024-03-03 22:00:00+00",
+            ),
+        ],
+    ),
+    (
+        "fixture_b",
+        &[
+            (
+                "b-1",
+                "BURGLARY",
+                "PROPERTY",
+                -87.63,
+                41.88,
+                "2024-04-10 03:45:00+00",
+            ),
+            (
+                "b-2",
+                "ROBBERY",
+                "VIOLENT",
+                -87.61,
+                41.90,
+                "2024-04-11 18:20:00+00",
+            ),
+        ],
+    ),
+];
+
+pub const FIXTURE_INCIDENTS: i64 = 5;
+
+/// The fixture data directory of this test binary.
+pub struct Fixture {
+    /// Temporary directory holding `data/` and the tests' output
+    /// directories.
+    pub root: PathBuf,
+    /// IDs of the fixture sources, including one without an `incidents`
+    /// table.
+    pub source_ids: Vec<String>,
+}
+
+impl Fixture {
+    /// Returns an empty output directory for the test `name`.
+    pub fn output_dir(&self, name: &str) -> PathBuf {
+        let dir = self.root.join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
+
+static FIXTURE: OnceLock<Fixture> = OnceLock::new();
+
+/// Held while a test uses the fixture: generation opens the fixture's
+/// `DuckDB` files read-write, so two runs must not overlap.
+static FIXTURE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Writes the fixture data directory on first use and returns it, along
+/// with a guard to hold for the rest of the test.
+pub async fn fixture() -> (tokio::sync::MutexGuard<'static, ()>, &'static Fixture) {
+    let guard = FIXTURE_LOCK.lock().await;
+    let fixture = FIXTURE.get_or_init(|| {
+        let root = std::env::temp_dir().join(format!(
+            "crime_map_{}_{}",
+            env!("CARGO_CRATE_NAME"),
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+
+        // SAFETY: tests only read the environment after getting the
+        // fixture, and `OnceLock` makes the others wait until it's set.
+        unsafe { std::env::set_var("CRIME_MAP_DATA_DIR", root.join("data")) };
+
+        let source_ids = write_sources();
+        write_boundaries();
+        Fixture { root, source_ids }
+    });
+    (guard, fixture)
+}
+
+pub fn args() -> GenerateArgs {
+    GenerateArgs::default()
+}
+
+pub fn write_sources() -> Vec<String> {
+    let mut ids = Vec::new();
+    for (source_id, incidents) in FIXTURE_SOURCES {
+        let conn = crime_map_database::source_db::open_by_id(source_id).unwrap();
+        for (id, category, parent, lng, lat, occurred_at) in *incidents {
+            conn.execute(
+                "INSERT INTO incidents (
+                    source_incident_id, category, parent_category, severity,
+                    longitude, latitude, occurred_at, city, state,
+                    census_place_geoid, census_tract_geoid, state_fips,
+                    county_geoid, neighborhood_id, enriched
+                 ) VALUES (
+                    ?, ?, ?, 2, ?, ?, ?::TIMESTAMPTZ, 'Chicago', 'IL',
+                    '1714000', '17031839100', '17', '17031', 'nbhd-1', TRUE
+                 )",
+                duckdb::params![id, category, parent, lng, lat, occurred_at],
+            )
+            .unwrap();
+        }
+        crime_map_database::source_db::set_meta(&conn, "source_name", source_id).unwrap();
+        ids.push((*source_id).to_string());
+    }
+
+    // A source whose sync was interrupted before its first insert: the
+    // file exists but has no `incidents` table, so it must be skipped.
+    let empty = "fixture_empty";
+    let path = paths::source_db_path(empty);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    drop(duckdb::Connection::open(&path).unwrap());
+    ids.push(empty.to_string());
+
+    ids
+}
+
+pub fn write_boundaries() {
+    let conn = crime_map_database::boundaries_db::open_default().unwrap();
+    let square = r#"{"type":"Polygon","coordinates":[[[-87.7,41.8],[-87.6,41.8],[-87.6,41.95],[-87.7,41.95],[-87.7,41.8]]]}"#;
+    conn.execute(
+        "INSERT INTO census_states (fips, name, abbr, boundary_geojson, population)
+         VALUES ('17', 'Illinois', 'IL', ?, 12800000)",
+        [square],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO census_counties
+            (geoid, name, full_name, state_fips, county_fips, state_abbr, boundary_geojson, population)
+         VALUES ('17031', 'Cook', 'Cook County, IL', '17', '031', 'IL', ?, 5200000)",
+        [square],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO census_places
+            (geoid, name, full_name, state_fips, state_abbr, boundary_geojson, population)
+         VALUES ('1714000', 'Chicago', 'Chicago, IL', '17', 'IL', ?, 2700000)",
+        [square],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO census_tracts
+            (geoid, name, state_fips, county_fips, state_abbr, county_name, boundary_geojson,
+             land_area_sq_mi, population)
+         VALUES ('17031839100', 'Tract 8391', '17', '031', 'IL', 'Cook', ?, 0.5, 4000)",
+        [square],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO neighborhoods (id, source_id, city, state, name, boundary_geojson)
+         VALUES (1, 'fixture_a', 'Chicago', 'IL', 'Loop', ?)",
+        [square],
+    )
+    .unwrap();
+    conn.execute_batch("INSERT INTO tract_neighborhoods VALUES ('17031839100', 1)")
+        .unwrap();
+}
+
+pub async fn sqlite_count(path: &Path, table: &str) -> i64 {
+    let db = switchy_database_connection::init_sqlite_rusqlite(Some(path)).unwrap();
+    let rows = db
+        .query_raw_params(&format!("SELECT COUNT(*) AS n FROM {table}"), &[])
+        .await
+        .unwrap();
+    rows[0].to_value("n").unwrap()
+}
@@ -0,0 +1,89 @@
+//! End-to-end test of incremental regeneration after one source is
+//! re-synced.
+//!
+//! Kept apart from `run_with_cache.rs` because it adds an incident to the
+//! fixture data, which the tests there count on staying unchanged.
+
+mod common;
+
+use std::path::Path;
+
+use crime_map_database::paths;
+use crime_map_generate::{GenerateArgs, OUTPUT_ANALYTICS_DB, OUTPUT_INCIDENTS_DB, run_with_cache};
+
+use common::{FIXTURE_INCIDENTS, args, fixture, sqlite_count};
+
+fn duckdb_count(path: &Path, sql: &str) -> i64 {
+    let conn = duckdb::Connection::open(path).unwrap();
+    conn.query_row(sql, [], |row| row.get(0)).unwrap()
+}
+
+#[tokio::test]
+async fn resync_patches_only_the_changed_source() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("incremental");
+
+    let incremental = GenerateArgs {
+        only_changed_sources: true,
+        incremental_analytics: true,
+        ..args()
+    };
+    run_with_cache(
+        &incremental,
+        &fixture.source_ids,
+        &out,
+        &[OUTPUT_INCIDENTS_DB, OUTPUT_ANALYTICS_DB],
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Re-syncing one source patches only its rows into the sidebar DB and
+    // its latest month into the analytics DB.
+    let conn = crime_map_database::source_db::open_by_id("fixture_b").unwrap();
+    conn.execute(
+        "INSERT INTO incidents (
+            source_incident_id, category, parent_category, severity,
+            longitude, latitude, occurred_at, city, state, enriched
+         ) VALUES (
+            'b-3', 'THEFT', 'PROPERTY', 2, -87.60, 41.91,
+            '2024-04-12 12:00:00+00'::TIMESTAMPTZ, 'Chicago', 'IL', TRUE
+         )",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+    run_with_cache(
+        &incremental,
+        &fixture.source_ids,
+        &out,
+        &[OUTPUT_INCIDENTS_DB, OUTPUT_ANALYTICS_DB],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let analytics = out.join(paths::ANALYTICS_DB_FILE);
+    assert_eq!(
+        duckdb_count(
+            &analytics,
+            "SELECT COUNT(*) FROM incidents WHERE source_id = 'fixture_b'"
+        ),
+        3
+    );
+    assert_eq!(
+        duckdb_count(&analytics, "SELECT COUNT(*) FROM incidents"),
+        FIXTURE_INCIDENTS + 1
+    );
+    let sidebar = out.join(paths::INCIDENTS_DB_FILE);
+    assert_eq!(
+        sqlite_count(&sidebar, "incidents").await,
+        FIXTURE_INCIDENTS + 1
+    );
+    assert_eq!(
+        sqlite_count(&sidebar, "incidents_rtree").await,
+        FIXTURE_INCIDENTS + 1
+    );
+
+    std::fs::remove_dir_all(&fixture.root).unwrap();
+}
@@ -0,0 +1,405 @@
+//! End-to-end tests of [`run_with_cache`] over a tiny fixture dataset.
+//!
+//! The fixture (see [`common::fixture`]) has two source `DuckDB`s, a
+//! source file without an `incidents` table, and a minimal boundaries
+//! `DuckDB`. Each test generates into its own output directory, only
+//! outputs that don't need tippecanoe.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crime_map_database::paths;
+use crime_map_generate::{
+    BoundaryType, ExtractFormat, GenerateArgs, GenerateError, OUTPUT_ANALYTICS_DB,
+    OUTPUT_BOUNDARIES_DB, OUTPUT_CHOROPLETH_DB, OUTPUT_COUNT_DB, OUTPUT_H3_DB, OUTPUT_INCIDENTS_DB,
+    OUTPUT_INCIDENTS_PMTILES, OUTPUT_METADATA, export_for_boundary, run_with_cache, run_with_sink,
+    sink::{OutputSink, SinkError},
+    verify::{OutputHealth, verify_outputs},
+};
+
+use common::{FIXTURE_INCIDENTS, args, fixture, sqlite_count};
+
+/// Outputs generated by the test (everything except the `PMTiles`).
+const OUTPUTS: &[&str] = &[
+    OUTPUT_INCIDENTS_DB,
+    OUTPUT_COUNT_DB,
+    OUTPUT_H3_DB,
+    OUTPUT_METADATA,
+    OUTPUT_BOUNDARIES_DB,
+    OUTPUT_ANALYTICS_DB,
+    OUTPUT_CHOROPLETH_DB,
+];
+
+/// Files written for [`OUTPUTS`].
+const OUTPUT_FILES: &[&str] = &[
+    paths::INCIDENTS_DB_FILE,
+    paths::COUNTS_DB_FILE,
+    paths::H3_DB_FILE,
+    paths::METADATA_FILE,
+    paths::HEALTH_FILE,
+    paths::BOUNDARIES_DB_FILE,
+    paths::ANALYTICS_DB_FILE,
+    paths::CHOROPLETH_DB_FILE,
+];
+
+/// Sink that copies outputs into a directory.
+struct DirSink(PathBuf);
+
+#[async_trait::async_trait]
+impl OutputSink for DirSink {
+    async fn put(&self, file_name: &str, path: &Path) -> Result<(), SinkError> {
+        std::fs::create_dir_all(&self.0)?;
+        std::fs::copy(path, self.0.join(file_name))?;
+        Ok(())
+    }
+}
+
+fn duckdb_count(path: &Path, sql: &str) -> i64 {
+    let conn = duckdb::Connection::open(path).unwrap();
+    conn.query_row(sql, [], |row| row.get(0)).unwrap()
+}
+
+fn output_mtimes(dir: &Path) -> BTreeMap<&'static str, SystemTime> {
+    OUTPUT_FILES
+        .iter()
+        .map(|&file| {
+            let modified = std::fs::metadata(dir.join(file))
+                .and_then(|m| m.modified())
+                .unwrap_or_else(|e| panic!("{file} missing: {e}"));
+            (file, modified)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn generates_every_output() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("every_output");
+
+    run_with_cache(&args(), &fixture.source_ids, &out, OUTPUTS, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        sqlite_count(&out.join(paths::INCIDENTS_DB_FILE), "incidents").await,
+        FIXTURE_INCIDENTS
+    );
+    assert_eq!(
+        sqlite_count(&out.join(paths::BOUNDARIES_DB_FILE), "boundaries").await,
+        5
+    );
+    assert_eq!(
+        duckdb_count(
+            &out.join(paths::COUNTS_DB_FILE),
+            "SELECT SUM(cnt)::BIGINT FROM count_summary"
+        ),
+        FIXTURE_INCIDENTS
+    );
+    for resolution in 4..=9 {
+        assert_eq!(
+            duckdb_count(
+                &out.join(paths::H3_DB_FILE),
+                &format!("SELECT SUM(cnt)::BIGINT FROM h3_counts WHERE resolution = {resolution}"),
+            ),
+            FIXTURE_INCIDENTS,
+            "h3 resolution {resolution}"
+        );
+    }
+    assert_eq!(
+        duckdb_count(
+            &out.join(paths::ANALYTICS_DB_FILE),
+            "SELECT COUNT(*) FROM incidents WHERE neighborhood_id = 'nbhd-1'"
+        ),
+        FIXTURE_INCIDENTS
+    );
+    let choropleth = out.join(paths::CHOROPLETH_DB_FILE);
+    assert_eq!(
+        duckdb_count(
+            &choropleth,
+            "SELECT total FROM tract_counts WHERE tract_geoid = '17031839100'"
+        ),
+        FIXTURE_INCIDENTS
+    );
+    // 5 incidents over a population of 4000, per 1000 residents.
+    assert_eq!(
+        duckdb_count(
+            &choropleth,
+            "SELECT (rate * 100)::BIGINT FROM tract_counts WHERE tract_geoid = '17031839100'"
+        ),
+        125
+    );
+
+    let metadata: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join(paths::METADATA_FILE)).unwrap())
+            .unwrap();
+    assert_eq!(metadata["sources"].as_array().map(Vec::len), Some(2));
+    assert_eq!(
+        metadata["minDate"].as_str().map(|d| &d[..10]),
+        Some("2024-03-01")
+    );
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join(paths::MANIFEST_FILE)).unwrap())
+            .unwrap();
+    for output in OUTPUTS {
+        assert!(
+            manifest["durations_secs"][output].is_f64(),
+            "{output} duration not recorded"
+        );
+    }
+
+    let report = verify_outputs(&out).await;
+    assert_eq!(report.len(), OUTPUT_FILES.len(), "{report:?}");
+    assert!(report.iter().all(OutputHealth::is_ok), "{report:?}");
+
+    std::fs::remove_dir_all(&out).unwrap();
+}
+
+#[tokio::test]
+async fn skips_outputs_that_are_up_to_date() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("up_to_date");
+
+    run_with_cache(&args(), &fixture.source_ids, &out, OUTPUTS, None)
+        .await
+        .unwrap();
+
+    // Nothing changed, so the second run must not rewrite any output.
+    let before = output_mtimes(&out);
+    run_with_cache(&args(), &fixture.source_ids, &out, OUTPUTS, None)
+        .await
+        .unwrap();
+    assert_eq!(output_mtimes(&out), before);
+
+    std::fs::remove_dir_all(&out).unwrap();
+}
+
+#[tokio::test]
+async fn metadata_does_not_depend_on_source_order() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("metadata_order");
+
+    run_with_cache(&args(), &fixture.source_ids, &out, &[OUTPUT_METADATA], None)
+        .await
+        .unwrap();
+    let metadata_bytes = std::fs::read(out.join(paths::METADATA_FILE)).unwrap();
+
+    // Regenerating from the same data with the sources listed in a
+    // different order must produce a byte-identical metadata.json.
+    let reversed: Vec<String> = fixture.source_ids.iter().rev().cloned().collect();
+    let forced = GenerateArgs {
+        force: true,
+        ..args()
+    };
+    run_with_cache(&forced, &reversed, &out, &[OUTPUT_METADATA], None)
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read(out.join(paths::METADATA_FILE)).unwrap(),
+        metadata_bytes
+    );
+
+    std::fs::remove_dir_all(&out).unwrap();
+}
+
+#[tokio::test]
+async fn feature_guard_refuses_too_many_pmtiles_features() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("feature_guard");
+
+    let capped = GenerateArgs {
+        max_pmtiles_features: Some(1),
+        ..args()
+    };
+    assert!(matches!(
+        run_with_cache(
+            &capped,
+            &fixture.source_ids,
+            &out,
+            &[OUTPUT_INCIDENTS_PMTILES],
+            None
+        )
+        .await,
+        Err(GenerateError::TooManyFeatures { max: 1, .. })
+    ));
+
+    std::fs::remove_dir_all(&out).unwrap();
+}
+
+#[tokio::test]
+async fn sink_receives_finalized_outputs() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("sink");
+    let sunk = fixture.output_dir("sunk");
+
+    // With a sink, finalized outputs move out of the output directory.
+    run_with_sink(
+        &args(),
+        &fixture.source_ids,
+        &out,
+        &[OUTPUT_INCIDENTS_DB, OUTPUT_METADATA],
+        None,
+        Some(&DirSink(sunk.clone())),
+    )
+    .await
+    .unwrap();
+    for file in [
+        paths::INCIDENTS_DB_FILE,
+        paths::METADATA_FILE,
+        paths::HEALTH_FILE,
+    ] {
+        assert!(sunk.join(file).exists(), "{file} not sent to sink");
+        assert!(!out.join(file).exists(), "{file} left in output dir");
+    }
+
+    std::fs::remove_dir_all(&out).unwrap();
+    std::fs::remove_dir_all(&sunk).unwrap();
+}
+
+#[tokio::test]
+async fn h3_db_rebuilds_from_kept_count_incidents() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("h3_from_counts");
+
+    // A count DB that keeps its raw incidents rebuilds the H3 DB alone.
+    let keep = GenerateArgs {
+        keep_incidents_table: true,
+        ..args()
+    };
+    run_with_cache(&keep, &fixture.source_ids, &out, &[OUTPUT_COUNT_DB], None)
+        .await
+        .unwrap();
+    let counts = out.join("kept_counts.duckdb");
+    std::fs::rename(out.join(paths::COUNTS_DB_FILE), &counts).unwrap();
+    assert_eq!(
+        duckdb_count(&counts, "SELECT COUNT(*) FROM incidents"),
+        FIXTURE_INCIDENTS
+    );
+
+    let from_counts = GenerateArgs {
+        h3_from: Some(counts),
+        ..args()
+    };
+    run_with_cache(
+        &from_counts,
+        &fixture.source_ids,
+        &out,
+        &[OUTPUT_H3_DB],
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        duckdb_count(
+            &out.join(paths::H3_DB_FILE),
+            "SELECT SUM(cnt)::BIGINT FROM h3_counts WHERE resolution = 9"
+        ),
+        FIXTURE_INCIDENTS
+    );
+
+    std::fs::remove_dir_all(&out).unwrap();
+}
+
+#[tokio::test]
+async fn boundary_extract_keeps_only_its_incidents() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("extract");
+
+    let extract = out.join("tract.parquet");
+    let extracted = export_for_boundary(
+        &args(),
+        &fixture.source_ids,
+        BoundaryType::Tract,
+        "17031839100",
+        ExtractFormat::Parquet,
+        &extract,
+    )
+    .unwrap();
+    assert_eq!(extracted, u64::try_from(FIXTURE_INCIDENTS).unwrap());
+    assert_eq!(
+        duckdb_count(
+            &out.join("unused.duckdb"),
+            &format!(
+                "SELECT COUNT(*) FROM read_parquet('{}')",
+                extract.to_string_lossy()
+            )
+        ),
+        FIXTURE_INCIDENTS
+    );
+    assert!(matches!(
+        export_for_boundary(
+            &args(),
+            &fixture.source_ids,
+            BoundaryType::Tract,
+            "17031000000",
+            ExtractFormat::Csv,
+            &out.join("missing.csv"),
+        ),
+        Err(GenerateError::BoundaryNotFound { .. })
+    ));
+
+    std::fs::remove_dir_all(&out).unwrap();
+}
+
+#[tokio::test]
+async fn taxonomy_adds_a_column_per_rollup_level() {
+    let (_guard, fixture) = fixture().await;
+    let out = fixture.output_dir("taxonomy");
+
+    // A taxonomy in the remap config adds a column per rollup level and
+    // links the subcategories to their nodes in crime_categories.
+    let remap = out.join("remap.toml");
+    std::fs::write(
+        &remap,
+        "[taxonomy]\nlevels = [\"ucr_part\"]\n\n[taxonomy.parents]\n\
+         THEFT = \"PART_I\"\nROBBERY = \"PART_I\"\nASSAULT = \"PART_II\"\n",
+    )
+    .unwrap();
+    run_with_cache(
+        &GenerateArgs {
+            category_remap: Some(remap),
+            ..args()
+        },
+        &fixture.source_ids,
+        &out,
+        &[OUTPUT_COUNT_DB, OUTPUT_ANALYTICS_DB],
+        None,
+    )
+    .await
+    .unwrap();
+    let analytics = out.join(paths::ANALYTICS_DB_FILE);
+    assert_eq!(
+        duckdb_count(
+            &analytics,
+            "SELECT COUNT(*) FROM incidents WHERE ucr_part = 'PART_I'"
+        ),
+        3
+    );
+    assert_eq!(
+        duckdb_count(
+            &analytics,
+            "SELECT COUNT(*) FROM incidents WHERE ucr_part IS NULL"
+        ),
+        1
+    );
+    assert_eq!(
+        duckdb_count(
+            &analytics,
+            "SELECT COUNT(*) FROM crime_categories c
+             JOIN crime_categories p ON p.id = c.ucr_part_id
+             WHERE c.name = 'ROBBERY' AND p.name = 'PART_I' AND p.level = 'ucr_part'"
+        ),
+        1
+    );
+    assert_eq!(
+        duckdb_count(
+            &out.join(paths::COUNTS_DB_FILE),
+            "SELECT SUM(cnt)::BIGINT FROM count_summary WHERE ucr_part = 'PART_II'"
+        ),
+        1
+    );
+
+    std::fs::remove_dir_all(&out).unwrap();
+}
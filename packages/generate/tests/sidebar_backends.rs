@@ -0,0 +1,90 @@
+//! Checks that both [`SidebarBackend`]s build the same sidebar DB: the
+//! same schema, R-tree, indexes, and rows (only the row IDs may differ).
+//!
+//! The duckdb backend needs `DuckDB`'s sqlite extension, which it
+//! downloads on first use, so the test only runs when
+//! [`DUCKDB_SQLITE_ENV`] is set. CI preinstalls the extension and sets it;
+//! locally, run it with `CRIME_MAP_TEST_DUCKDB_SQLITE=1 cargo test` where
+//! the download is available.
+
+mod common;
+
+use std::path::Path;
+
+use crime_map_database::paths;
+use crime_map_generate::{GenerateArgs, OUTPUT_INCIDENTS_DB, SidebarBackend, run_with_cache};
+use switchy_database::Database as _;
+
+use common::{FIXTURE_INCIDENTS, args, fixture, sqlite_count};
+
+/// Set (to anything) to run the test that needs `DuckDB`'s sqlite extension.
+const DUCKDB_SQLITE_ENV: &str = "CRIME_MAP_TEST_DUCKDB_SQLITE";
+
+/// Returns the `r` column of each row of `sql`, run against the `SQLite`
+/// database at `path`.
+async fn sqlite_strings(path: &Path, sql: &str) -> Vec<String> {
+    let db = switchy_database_connection::init_sqlite_rusqlite(Some(path)).unwrap();
+    db.query_raw_params(sql, &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.to_value("r").unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn duckdb_backend_matches_rows_backend() {
+    if std::env::var_os(DUCKDB_SQLITE_ENV).is_none() {
+        eprintln!("skipping: set {DUCKDB_SQLITE_ENV} to run the duckdb sidebar backend");
+        return;
+    }
+
+    let (_guard, fixture) = fixture().await;
+    let rows_dir = fixture.output_dir("sidebar_rows");
+    let duckdb_dir = fixture.output_dir("sidebar_duckdb");
+    for (dir, sidebar_backend) in [
+        (&rows_dir, SidebarBackend::Rows),
+        (&duckdb_dir, SidebarBackend::Duckdb),
+    ] {
+        run_with_cache(
+            &GenerateArgs {
+                sidebar_backend,
+                ..args()
+            },
+            &fixture.source_ids,
+            dir,
+            &[OUTPUT_INCIDENTS_DB],
+            None,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("{sidebar_backend:?} backend failed: {e}"));
+    }
+
+    let schema = "SELECT type || ' ' || name || ': ' || COALESCE(sql, '') AS r
+                  FROM sqlite_master ORDER BY type, name";
+    let rows = "SELECT json_array(
+                    source_id, source_name, source_incident_id, subcategory, category,
+                    severity, longitude, latitude, occurred_at, description,
+                    block_address, city, state, arrest_made, domestic, location_type,
+                    state_fips, county_geoid, place_geoid, tract_geoid, neighborhood_id
+                ) AS r
+                FROM incidents ORDER BY source_id, source_incident_id";
+    let rtree = "SELECT COUNT(*) || '' AS r FROM incidents_rtree";
+    for sql in [schema, rows, rtree] {
+        assert_eq!(
+            sqlite_strings(&duckdb_dir.join(paths::INCIDENTS_DB_FILE), sql).await,
+            sqlite_strings(&rows_dir.join(paths::INCIDENTS_DB_FILE), sql).await,
+            "{sql}"
+        );
+    }
+    assert_eq!(
+        sqlite_count(
+            &duckdb_dir.join(paths::INCIDENTS_DB_FILE),
+            "incidents_rtree"
+        )
+        .await,
+        FIXTURE_INCIDENTS
+    );
+
+    std::fs::remove_dir_all(&fixture.root).unwrap();
+}
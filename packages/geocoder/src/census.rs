@@ -6,6 +6,21 @@
 //! - Single: `GET /geocoder/locations/address`
 //! - Batch: `POST /geocoder/locations/addressbatch` (up to 10,000 rows)
 //!
+//! Every request names a benchmark, the release of the address ranges
+//! (street network) matched against, and a vintage, the release of the
+//! geographies paired with it. Only some pairs are published; the ones
+//! accepted here are listed in [`BENCHMARK_VINTAGES`]:
+//!
+//! | Benchmark | Vintages |
+//! |-----------|----------|
+//! | `Public_AR_Current` (default) | `Current_Current` (default), `ACS2024_Current`, `Census2020_Current` |
+//! | `Public_AR_ACS2024` | `Current_ACS2024`, `ACS2024_ACS2024`, `Census2020_ACS2024` |
+//! | `Public_AR_Census2020` | `Census2020_Census2020`, `Census2010_Census2020` |
+//!
+//! `Public_AR_Current` is refreshed as new streets are added, so it matches
+//! addresses in newly built areas that year-specific benchmarks miss. Pin
+//! a year-specific benchmark only to reproduce older results.
+//!
 //! See <https://geocoding.geo.census.gov/geocoder/Geocoding_Services_API.html>
 
 use std::fmt::Write as _;
@@ -19,15 +34,65 @@ use crate::{
 /// Maximum number of addresses per batch request (Census Bureau limit).
 pub const MAX_BATCH_SIZE: usize = 10_000;
 
+/// Benchmark used when a service config doesn't name one.
+pub const DEFAULT_BENCHMARK: &str = "Public_AR_Current";
+
+/// Vintage used when a service config doesn't name one.
+pub const DEFAULT_VINTAGE: &str = "Current_Current";
+
+/// Public benchmarks and the vintages published for each (see the
+/// [module docs](self)).
+pub const BENCHMARK_VINTAGES: &[(&str, &[&str])] = &[
+    (
+        "Public_AR_Current",
+        &["Current_Current", "ACS2024_Current", "Census2020_Current"],
+    ),
+    (
+        "Public_AR_ACS2024",
+        &["Current_ACS2024", "ACS2024_ACS2024", "Census2020_ACS2024"],
+    ),
+    (
+        "Public_AR_Census2020",
+        &["Census2020_Census2020", "Census2010_Census2020"],
+    ),
+];
+
+/// Checks that `vintage` is published for `benchmark` (see
+/// [`BENCHMARK_VINTAGES`]).
+///
+/// # Errors
+///
+/// Returns a description listing the valid choices if the benchmark is
+/// unknown or the vintage isn't published for it.
+pub fn validate_benchmark_vintage(benchmark: &str, vintage: &str) -> Result<(), String> {
+    let Some((_, vintages)) = BENCHMARK_VINTAGES.iter().find(|(b, _)| *b == benchmark) else {
+        let benchmarks: Vec<&str> = BENCHMARK_VINTAGES.iter().map(|(b, _)| *b).collect();
+        return Err(format!(
+            "Unknown Census benchmark '{benchmark}' (expected one of: {})",
+            benchmarks.join(", ")
+        ));
+    };
+    if !vintages.contains(&vintage) {
+        return Err(format!(
+            "Census vintage '{vintage}' isn't published for benchmark '{benchmark}' \
+             (expected one of: {})",
+            vintages.join(", ")
+        ));
+    }
+    Ok(())
+}
+
 /// Geocodes a single address using the Census Bureau structured endpoint.
 ///
 /// # Errors
 ///
 /// Returns [`GeocodeError`] if the HTTP request or response parsing fails.
+#[allow(clippy::too_many_arguments)]
 pub async fn geocode_single(
     client: &reqwest::Client,
     base_url: &str,
     benchmark: &str,
+    vintage: &str,
     street: &str,
     city: &str,
     state: &str,
@@ -39,6 +104,7 @@ pub async fn geocode_single(
          &city={city}\
          &state={state}\
          &benchmark={benchmark}\
+         &vintage={vintage}\
          &format=json",
         street = urlencoding(street),
         city = urlencoding(city),
@@ -67,6 +133,7 @@ pub async fn geocode_batch(
     client: &reqwest::Client,
     base_url: &str,
     benchmark: &str,
+    vintage: &str,
     addresses: &[AddressInput],
 ) -> Result<BatchResult, GeocodeError> {
     if addresses.is_empty() {
@@ -94,6 +161,7 @@ pub async fn geocode_batch(
 
     let form = multipart::Form::new()
         .text("benchmark", benchmark.to_string())
+        .text("vintage", vintage.to_string())
         .part(
             "addressFile",
             multipart::Part::text(csv_content)
@@ -315,6 +383,22 @@ mod tests {
         assert_eq!(parse_coord_pair(""), None);
     }
 
+    #[test]
+    fn validates_benchmark_vintage_pairs() {
+        assert!(validate_benchmark_vintage(DEFAULT_BENCHMARK, DEFAULT_VINTAGE).is_ok());
+        assert!(validate_benchmark_vintage("Public_AR_ACS2024", "ACS2024_ACS2024").is_ok());
+        assert!(
+            validate_benchmark_vintage("Public_AR_Current", "ACS2024_ACS2024")
+                .unwrap_err()
+                .contains("Current_Current")
+        );
+        assert!(
+            validate_benchmark_vintage("Public_AR_2010", "Current_Current")
+                .unwrap_err()
+                .starts_with("Unknown Census benchmark")
+        );
+    }
+
     #[test]
     fn parses_csv_with_quotes() {
         let fields = parse_csv_line(r#""hello","world","foo,bar""#);
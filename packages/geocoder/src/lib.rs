@@ -19,7 +19,8 @@
 //! is running) are skipped automatically.
 //!
 //! Also provides address cleaning utilities for normalizing block-level
-//! addresses from crime data sources.
+//! addresses from crime data sources, and [`validation`] of matches
+//! against the expected city/state.
 
 pub mod address;
 pub mod census;
@@ -27,6 +28,7 @@ pub mod nominatim;
 pub mod pelias;
 pub mod service_registry;
 pub mod tantivy_index;
+pub mod validation;
 
 use thiserror::Error;
 
@@ -87,7 +89,11 @@ pub struct AddressInput {
 pub enum GeocodeError {
     /// HTTP request failed.
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(reqwest::Error),
+
+    /// HTTP request timed out (connect or overall request timeout).
+    #[error("HTTP timeout: {0}")]
+    Timeout(reqwest::Error),
 
     /// Response parsing failed.
     #[error("Parse error: {message}")]
@@ -101,6 +107,31 @@ pub enum GeocodeError {
     RateLimited,
 }
 
+impl GeocodeError {
+    /// Returns `true` if the error is transient (timeout, connection
+    /// failure, or rate limit) and the request may succeed if retried.
+    ///
+    /// Callers should not cache a transient failure as a known miss.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Timeout(_) | Self::RateLimited => true,
+            Self::Http(e) => e.is_connect(),
+            Self::Parse { .. } => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for GeocodeError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout(e)
+        } else {
+            Self::Http(e)
+        }
+    }
+}
+
 /// Result of a batch geocoding operation.
 #[derive(Debug, Clone)]
 pub struct BatchResult {
@@ -2,7 +2,7 @@
 //!
 //! Each geocoding provider is defined in a TOML file under `services/`.
 //! The registry embeds these at compile time and exposes them via
-//! [`all_services`] and [`enabled_services`].
+//! [`all_services`], [`enabled_services`], and [`ordered_services`].
 
 use serde::Deserialize;
 
@@ -30,8 +30,15 @@ pub enum ProviderConfig {
     Census {
         /// API base URL (e.g., `"https://geocoding.geo.census.gov/geocoder"`).
         base_url: String,
-        /// Benchmark name (e.g., `"Public_AR_Current"`).
+        /// Benchmark name (e.g., `"Public_AR_Current"`). Defaults to
+        /// [`crate::census::DEFAULT_BENCHMARK`].
+        #[serde(default = "default_census_benchmark")]
         benchmark: String,
+        /// Vintage name, which must be published for `benchmark` (see
+        /// [`crate::census::BENCHMARK_VINTAGES`]). Defaults to
+        /// [`crate::census::DEFAULT_VINTAGE`].
+        #[serde(default = "default_census_vintage")]
+        vintage: String,
         /// Maximum addresses per batch request.
         max_batch_size: usize,
     },
@@ -64,6 +71,14 @@ const fn default_concurrent() -> usize {
     10
 }
 
+fn default_census_benchmark() -> String {
+    crate::census::DEFAULT_BENCHMARK.to_string()
+}
+
+fn default_census_vintage() -> String {
+    crate::census::DEFAULT_VINTAGE.to_string()
+}
+
 impl GeocodingService {
     /// Returns the provider's base URL regardless of variant.
     ///
@@ -118,6 +133,39 @@ pub fn enabled_services() -> Vec<GeocodingService> {
     services
 }
 
+/// Returns the enabled services named in `order`, in that order, or every
+/// enabled service in priority order when `order` is empty.
+///
+/// Lets a single run reorder (and implicitly filter) the pipeline without
+/// editing the TOML registry.
+///
+/// # Errors
+///
+/// Returns an error if `order` names a service that isn't enabled or names
+/// one more than once.
+pub fn ordered_services(order: &[String]) -> Result<Vec<GeocodingService>, String> {
+    let enabled = enabled_services();
+    if order.is_empty() {
+        return Ok(enabled);
+    }
+
+    let mut services: Vec<GeocodingService> = Vec::with_capacity(order.len());
+    for id in order {
+        if services.iter().any(|s| s.id == *id) {
+            return Err(format!("Geocoding provider '{id}' listed more than once"));
+        }
+        let Some(service) = enabled.iter().find(|s| s.id == *id) else {
+            let available: Vec<&str> = enabled.iter().map(|s| s.id.as_str()).collect();
+            return Err(format!(
+                "Unknown or disabled geocoding provider '{id}' (enabled: {})",
+                available.join(", ")
+            ));
+        };
+        services.push(service.clone());
+    }
+    Ok(services)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,6 +202,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn census_services_use_published_benchmark_vintages() {
+        for svc in &all_services() {
+            if let ProviderConfig::Census {
+                benchmark, vintage, ..
+            } = &svc.provider
+            {
+                crate::census::validate_benchmark_vintage(benchmark, vintage)
+                    .unwrap_or_else(|e| panic!("Service {}: {e}", svc.id));
+            }
+        }
+    }
+
     #[test]
     fn enabled_services_sorted_by_priority() {
         let services = enabled_services();
@@ -168,4 +229,33 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn ordered_services_follows_requested_order() {
+        let enabled = enabled_services();
+        assert_eq!(
+            ordered_services(&[]).unwrap().len(),
+            enabled.len(),
+            "empty order should keep every enabled service"
+        );
+
+        let reversed: Vec<String> = enabled.iter().rev().map(|s| s.id.clone()).collect();
+        let ids: Vec<String> = ordered_services(&reversed)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, reversed);
+
+        let first = vec![enabled[0].id.clone()];
+        assert_eq!(ordered_services(&first).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn ordered_services_rejects_unknown_and_duplicates() {
+        assert!(ordered_services(&["no_such_provider".to_string()]).is_err());
+
+        let id = enabled_services()[0].id.clone();
+        assert!(ordered_services(&[id.clone(), id]).is_err());
+    }
 }
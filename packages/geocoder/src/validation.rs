@@ -0,0 +1,158 @@
+//! Cross-validation of geocoded results against the incident's expected
+//! city and state.
+//!
+//! Geocoders sometimes return a plausible-but-wrong location, most often
+//! an ambiguous street name matched in a different metro. Every provider
+//! reports the address it matched (e.g. `"100 N STATE ST, CHICAGO, IL,
+//! 60602"` from Census or `"…, Chicago, Cook County, Illinois, …"` from
+//! Nominatim), so a match whose locality disagrees with the requested
+//! city/state can be rejected as low confidence instead of accepted.
+
+use crime_map_geography_models::fips;
+
+/// How strictly a geocoded result's matched address must agree with the
+/// requested city and state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchValidation {
+    /// Accept every match.
+    #[default]
+    Off,
+    /// Reject matches in a different state.
+    State,
+    /// Reject matches in a different state or city.
+    City,
+}
+
+impl TryFrom<&str> for MatchValidation {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "off" => Ok(Self::Off),
+            "state" => Ok(Self::State),
+            "city" => Ok(Self::City),
+            _ => Err(format!(
+                "unknown match validation: {value} (expected off, state, or city)"
+            )),
+        }
+    }
+}
+
+impl std::str::FromStr for MatchValidation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s)
+    }
+}
+
+impl MatchValidation {
+    /// Returns `true` if `matched_address` is consistent with the
+    /// expected `city` and two-letter `state` at this strictness.
+    ///
+    /// Matches can only be checked against what the provider reports: a
+    /// result without a matched address, or an expected city/state that
+    /// is empty, is accepted. The first component of the matched address
+    /// is the street and is never treated as the locality, so `"CHICAGO
+    /// AVE, EVANSTON, IL"` does not pass for Chicago.
+    #[must_use]
+    pub fn accepts(self, matched_address: Option<&str>, city: &str, state: &str) -> bool {
+        if self == Self::Off {
+            return true;
+        }
+        let Some(matched) = matched_address else {
+            return true;
+        };
+
+        let components: Vec<String> = matched.split(',').skip(1).map(normalize).collect();
+
+        let state = state.trim().to_uppercase();
+        if !state.is_empty() {
+            let name = fips::abbr_to_fips(&state).map(|f| normalize(fips::state_name(f)));
+            let in_state = components.iter().any(|c| {
+                names_state(c, &state) || name.as_deref().is_some_and(|n| names_state(c, n))
+            });
+            if !in_state {
+                return false;
+            }
+        }
+
+        let city = normalize(city);
+        if self == Self::City && !city.is_empty() {
+            return components.iter().any(|c| *c == city);
+        }
+
+        true
+    }
+}
+
+/// Upper-cases `s`, drops periods, and collapses whitespace, so `"St.
+/// Louis"` and `"ST LOUIS"` compare equal.
+fn normalize(s: &str) -> String {
+    s.replace('.', "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase()
+}
+
+/// Returns `true` if address component `component` is the state `name`,
+/// or starts with it followed by a ZIP code (e.g. `"IL 60602"`).
+fn names_state(component: &str, name: &str) -> bool {
+    component
+        .strip_prefix(name)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn off_accepts_anything() {
+        assert!(MatchValidation::Off.accepts(Some("1 MAIN ST, GARY, IN"), "Chicago", "IL"));
+    }
+
+    #[test]
+    fn state_checks_abbreviation_and_full_name() {
+        let v = MatchValidation::State;
+        assert!(v.accepts(Some("100 N STATE ST, CHICAGO, IL, 60602"), "Chicago", "IL"));
+        assert!(v.accepts(
+            Some("100, North State Street, Loop, Chicago, Cook County, Illinois, 60602, United States"),
+            "Chicago",
+            "IL"
+        ));
+        assert!(v.accepts(
+            Some("100 N State St, Springfield, IL 62701"),
+            "Chicago",
+            "IL"
+        ));
+        assert!(!v.accepts(Some("100 N STATE ST, GARY, IN, 46402"), "Chicago", "IL"));
+    }
+
+    #[test]
+    fn city_rejects_other_metros_but_not_street_names() {
+        let v = MatchValidation::City;
+        assert!(v.accepts(Some("100 N STATE ST, CHICAGO, IL, 60602"), "chicago", "il"));
+        assert!(v.accepts(Some("1 Market St, St. Louis, MO"), "ST LOUIS", "MO"));
+        assert!(!v.accepts(
+            Some("100 N STATE ST, SPRINGFIELD, IL, 62701"),
+            "Chicago",
+            "IL"
+        ));
+        assert!(!v.accepts(Some("1 CHICAGO AVE, EVANSTON, IL, 60202"), "Chicago", "IL"));
+        assert!(!v.accepts(Some("1 MAIN ST, CHICAGO HEIGHTS, IL"), "Chicago", "IL"));
+    }
+
+    #[test]
+    fn unverifiable_matches_are_accepted() {
+        assert!(MatchValidation::City.accepts(None, "Chicago", "IL"));
+        assert!(MatchValidation::City.accepts(Some("1 MAIN ST, GARY, IN"), "", ""));
+    }
+
+    #[test]
+    fn parses_strictness() {
+        assert_eq!("city".parse(), Ok(MatchValidation::City));
+        assert!("strict".parse::<MatchValidation>().is_err());
+    }
+}
@@ -42,6 +42,7 @@ pub mod query;
 pub mod schema;
 pub mod synonyms;
 pub mod verify;
+pub mod version;
 
 use std::path::{Path, PathBuf};
 use std::time::Instant;
@@ -118,6 +119,24 @@ pub enum GeocoderIndexError {
     #[error("Index directory not found: {0}")]
     IndexNotFound(String),
 
+    /// Index was built by an incompatible version of this crate.
+    #[error(
+        "Geocoder index at {path} was built with format version {found} \
+         (by {built_by}), but this build expects version {}. \
+         Rebuild it with `{}`",
+        version::INDEX_FORMAT_VERSION,
+        version::REBUILD_COMMAND
+    )]
+    IncompatibleIndex {
+        /// Index directory.
+        path: String,
+        /// Format version found in the index, or a description of the
+        /// mismatch when there is no marker.
+        found: String,
+        /// What built the index.
+        built_by: String,
+    },
+
     /// Async task join error.
     #[error("Task join error: {0}")]
     Join(#[from] tokio::task::JoinError),
@@ -145,7 +164,9 @@ impl GeocoderIndex {
     /// # Errors
     ///
     /// Returns an error if the directory does not exist or does not
-    /// contain a valid Tantivy index.
+    /// contain a valid Tantivy index, or
+    /// [`GeocoderIndexError::IncompatibleIndex`] if the index was built
+    /// by an incompatible version (see [`version`]).
     pub fn open(index_dir: impl AsRef<Path>) -> Result<Self, GeocoderIndexError> {
         let index_dir = index_dir.as_ref();
         if !index_dir.exists() {
@@ -156,7 +177,16 @@ impl GeocoderIndex {
 
         log::info!("Opening geocoder index at {}", index_dir.display());
 
+        version::check(index_dir)?;
+
         let index = Index::open_in_dir(index_dir)?;
+        if index.schema() != schema::build_schema() {
+            return Err(GeocoderIndexError::IncompatibleIndex {
+                path: index_dir.display().to_string(),
+                found: "unknown (schema mismatch)".to_string(),
+                built_by: "an older or newer build".to_string(),
+            });
+        }
         schema::register_tokenizers(&index);
 
         let reader = index
@@ -326,6 +356,7 @@ pub struct BuildConfig<'a> {
 /// 2. Parses `OpenAddresses` CSV files (from directory or `.tar.zst` archive)
 /// 3. Parses the OSM PBF file for address nodes
 /// 4. Commits and optimizes the index
+/// 5. Writes the [`version::VERSION_FILE`] marker
 ///
 /// # Errors
 ///
@@ -464,6 +495,8 @@ fn build_index_sync(
     log::info!("Optimizing index (merging segments)...");
     writer.wait_merging_threads()?;
 
+    version::write(index_dir)?;
+
     let elapsed = start.elapsed();
 
     // Calculate index size
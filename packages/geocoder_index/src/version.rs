@@ -0,0 +1,140 @@
+//! Index format versioning.
+//!
+//! Every index directory carries a small [`VERSION_FILE`] written at
+//! build time. It records [`INDEX_FORMAT_VERSION`], which is bumped
+//! whenever the schema, normalization, or tokenization changes in a way
+//! that makes existing indexes return wrong results. Because the marker
+//! lives inside the index directory, it also travels inside the
+//! `.tar.zst` archive synced through R2, so pulling an index built by a
+//! newer (or older) binary is caught on open instead of silently
+//! misbehaving.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::GeocoderIndexError;
+
+/// Format version of indexes built by this crate.
+///
+/// Bump this whenever a change requires existing indexes to be rebuilt.
+pub const INDEX_FORMAT_VERSION: u32 = 1;
+
+/// Name of the version marker file inside the index directory.
+pub const VERSION_FILE: &str = "crime_map_index.toml";
+
+/// Command that rebuilds the index, shown in incompatibility errors.
+pub const REBUILD_COMMAND: &str = "cargo ingest geocoder-build";
+
+/// Contents of the [`VERSION_FILE`] marker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexVersion {
+    /// Format version the index was built with.
+    pub format_version: u32,
+    /// Version of the crate that built the index, for diagnostics only.
+    pub built_by: String,
+}
+
+impl IndexVersion {
+    /// Returns the marker for indexes built by this binary.
+    #[must_use]
+    pub fn current() -> Self {
+        Self {
+            format_version: INDEX_FORMAT_VERSION,
+            built_by: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Writes the current version marker into `index_dir`.
+///
+/// # Errors
+///
+/// Returns an error if the marker cannot be serialized or written.
+pub fn write(index_dir: &Path) -> Result<(), GeocoderIndexError> {
+    let contents = toml::to_string(&IndexVersion::current()).map_err(|e| {
+        GeocoderIndexError::Other(format!("Failed to serialize {VERSION_FILE}: {e}"))
+    })?;
+    std::fs::write(index_dir.join(VERSION_FILE), contents)?;
+    Ok(())
+}
+
+/// Reads the version marker from `index_dir`.
+///
+/// Returns `None` for indexes built before versioning was introduced.
+///
+/// # Errors
+///
+/// Returns an error if the marker exists but cannot be read or parsed.
+pub fn read(index_dir: &Path) -> Result<Option<IndexVersion>, GeocoderIndexError> {
+    let path = index_dir.join(VERSION_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(&path)?;
+    let version = toml::from_str(&contents)
+        .map_err(|e| GeocoderIndexError::Other(format!("Invalid {}: {e}", path.display())))?;
+    Ok(Some(version))
+}
+
+/// Checks that the index in `index_dir` was built with a compatible
+/// format version.
+///
+/// Indexes without a marker predate versioning; they are accepted here
+/// and left to the schema check in [`crate::GeocoderIndex::open`].
+///
+/// # Errors
+///
+/// Returns [`GeocoderIndexError::IncompatibleIndex`] if the marker
+/// records a different format version, or an error if it can't be read.
+pub fn check(index_dir: &Path) -> Result<(), GeocoderIndexError> {
+    let Some(version) = read(index_dir)? else {
+        log::warn!(
+            "Geocoder index at {} has no {VERSION_FILE}; rebuild it with `{REBUILD_COMMAND}` \
+             to add one",
+            index_dir.display()
+        );
+        return Ok(());
+    };
+
+    if version.format_version != INDEX_FORMAT_VERSION {
+        return Err(GeocoderIndexError::IncompatibleIndex {
+            path: index_dir.display().to_string(),
+            found: version.format_version.to_string(),
+            built_by: version.built_by,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_other_format_versions() {
+        let tmp = std::env::temp_dir().join("geocoder_index_version_test");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+
+        // No marker: a pre-versioning index is let through.
+        check(&tmp).unwrap();
+
+        write(&tmp).unwrap();
+        assert_eq!(read(&tmp).unwrap(), Some(IndexVersion::current()));
+        check(&tmp).unwrap();
+
+        let newer = IndexVersion {
+            format_version: INDEX_FORMAT_VERSION + 1,
+            built_by: "9.9.9".to_string(),
+        };
+        std::fs::write(tmp.join(VERSION_FILE), toml::to_string(&newer).unwrap()).unwrap();
+        let err = check(&tmp).unwrap_err();
+        assert!(matches!(err, GeocoderIndexError::IncompatibleIndex { .. }));
+        assert!(err.to_string().contains(REBUILD_COMMAND));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
@@ -12,6 +12,16 @@ pub mod fips;
 
 use serde::{Deserialize, Serialize};
 
+/// Returns the canonical string ID (`nbhd-{id}`) for the neighborhood with
+/// integer ID `id` in the boundaries database.
+///
+/// Enrichment, the analytics reference tables, and boundary search all key
+/// neighborhoods by this string, so every conversion must go through here.
+#[must_use]
+pub fn neighborhood_geoid(id: i32) -> String {
+    format!("nbhd-{id}")
+}
+
 /// A US state row as stored in the database.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -9,7 +9,7 @@ use std::time::Instant;
 
 use dialoguer::{Confirm, Input, Select};
 
-use crime_map_cli_utils::{IndicatifProgress, MultiProgress};
+use crime_map_cli_utils::{IndicatifProgress, MultiProgress, throttled};
 
 /// Top-level actions available in the ingest interactive menu.
 enum IngestAction {
@@ -108,12 +108,17 @@ async fn sync_sources(multi: &MultiProgress) -> Result<(), Box<dyn std::error::E
         .interact()?;
 
     let num_sources = source_ids.len();
-    let source_bar = IndicatifProgress::steps_bar(multi, "Sources", num_sources as u64);
+    let source_bar = throttled(IndicatifProgress::steps_bar(
+        multi,
+        "Sources",
+        num_sources as u64,
+    ));
 
     let args = crate::SyncArgs {
         source_ids,
         limit,
         force,
+        keep_raw: None,
     };
 
     let result = crate::run_sync(&args, Some(&source_bar)).await;
@@ -161,13 +166,21 @@ async fn geocode_interactive(multi: &MultiProgress) -> Result<(), Box<dyn std::e
         .interact()?;
 
     let start = Instant::now();
-    let geocode_bar = IndicatifProgress::batch_bar(multi, "Geocoding");
+    let geocode_bar = throttled(IndicatifProgress::batch_bar(multi, "Geocoding"));
 
     let args = crate::GeocodeArgs {
         source_ids,
         batch_size,
         limit,
-        nominatim_only,
+        since: None,
+        provider_order: if nominatim_only {
+            vec!["nominatim".to_string()]
+        } else {
+            Vec::new()
+        },
+        match_validation: crate::MatchValidation::Off,
+        dedup_addresses: false,
+        cache_flush_every: crate::DEFAULT_CACHE_FLUSH_EVERY,
     };
 
     let result = crate::run_geocode(&args, Some(geocode_bar.clone())).await?;
@@ -197,7 +210,7 @@ fn enrich_interactive(multi: &MultiProgress) -> Result<(), Box<dyn std::error::E
         .interact()?;
 
     let start = Instant::now();
-    let enrich_bar = IndicatifProgress::batch_bar(multi, "Enriching");
+    let enrich_bar = throttled(IndicatifProgress::batch_bar(multi, "Enriching"));
 
     let args = crate::EnrichArgs { source_ids, force };
 
@@ -361,9 +374,7 @@ async fn ingest_neighborhoods() -> Result<(), Box<dyn std::error::Error>> {
         sources_to_ingest.len()
     );
 
-    let client = reqwest::Client::builder()
-        .user_agent("crime-map/1.0")
-        .build()?;
+    let client = crate::build_http_client()?;
 
     let start = Instant::now();
     let mut total = 0u64;
@@ -7,12 +7,16 @@
 
 pub mod interactive;
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Instant;
 
+use crime_map_crime_models::CrimeSubcategory;
 use crime_map_database::{geocode_cache, source_db};
+use crime_map_geocoder::service_registry::{GeocodingService, ordered_services};
 use crime_map_source::FetchOptions;
 use crime_map_source::progress::ProgressCallback;
+use crime_map_source::reconcile::{SourceStatus, reconcile_sources};
 use crime_map_source::source_def::SourceDefinition;
 use duckdb::Connection;
 
@@ -24,6 +28,25 @@ use duckdb::Connection;
 /// by the `ON CONFLICT DO NOTHING` clause.
 pub const INCREMENTAL_BUFFER_DAYS: i64 = 7;
 
+/// User agent sent with all HTTP requests made by the ingest pipeline.
+pub const USER_AGENT: &str = "crime-map/1.0 (https://github.com/BSteffaniak/crime-map)";
+
+/// Default TCP connect timeout in seconds. Override with
+/// `CRIME_MAP_HTTP_CONNECT_TIMEOUT_SECS`.
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 30;
+
+/// Default overall request timeout in seconds. Override with
+/// `CRIME_MAP_HTTP_TIMEOUT_SECS`.
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 300;
+
+/// How long idle pooled connections are kept alive, in seconds.
+const POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+/// Maximum idle pooled connections kept per host.
+const POOL_MAX_IDLE_PER_HOST: usize = 16;
+
+pub use crime_map_geocoder::validation::MatchValidation;
+
 /// A cached geocoding result: `(address_key, provider, lat, lng, matched_address)`.
 pub type CacheEntry = geocode_cache::CacheEntry;
 
@@ -63,6 +86,10 @@ pub struct SyncArgs {
     pub limit: Option<u64>,
     /// Force a full sync, ignoring any previously synced data.
     pub force: bool,
+    /// Fraction of records (`0 < f <= 1`) whose raw source JSON is kept in
+    /// the source `DuckDB` for debugging (see [`sync_source`]). `None`
+    /// keeps none.
+    pub keep_raw: Option<f64>,
 }
 
 /// Arguments for [`run_geocode`].
@@ -73,8 +100,29 @@ pub struct GeocodeArgs {
     pub batch_size: u64,
     /// Maximum total incidents to geocode across all sources.
     pub limit: Option<u64>,
-    /// Skip Census Bureau batch geocoder and only use Nominatim.
-    pub nominatim_only: bool,
+    /// Only geocode incidents that occurred on or after this date, so a
+    /// recent sync's records get coordinates before a large historical
+    /// backlog. `None` selects every eligible incident.
+    pub since: Option<chrono::NaiveDate>,
+    /// Geocoding provider IDs to use, in order (e.g. `["pelias",
+    /// "census"]`). Providers not listed are skipped. Empty means every
+    /// enabled provider in registry priority order; `["nominatim"]` is the
+    /// old Nominatim-only mode.
+    pub provider_order: Vec<String>,
+    /// How strictly each match must agree with the incident's city/state.
+    /// Rejected matches are cached as low confidence and the address is
+    /// left for the next provider.
+    pub match_validation: MatchValidation,
+    /// Remember every address resolved during the run (see
+    /// [`AddressMemo`]) so one seen again in a later batch or source isn't
+    /// looked up or sent to a provider again. Costs memory proportional to
+    /// the number of distinct addresses.
+    pub dedup_addresses: bool,
+    /// Write buffered geocode results to the cache whenever this many are
+    /// pending, bounding memory on large runs (see
+    /// [`DEFAULT_CACHE_FLUSH_EVERY`]). Results are also flushed after each
+    /// provider and Census batch regardless.
+    pub cache_flush_every: usize,
 }
 
 /// Arguments for [`run_enrich`].
@@ -91,6 +139,163 @@ pub struct SyncResult {
     pub succeeded: u64,
     /// Source IDs that failed to sync.
     pub failed: Vec<String>,
+    /// Why each source in [`Self::failed`] failed.
+    pub failures: Vec<SyncFailure>,
+}
+
+/// Broad cause of a source sync failure, for deciding whether a retry is
+/// likely to help.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncFailureKind {
+    /// The upstream API answered HTTP 429.
+    RateLimited,
+    /// The upstream API answered with a 5xx status.
+    ServerError,
+    /// The upstream API rejected the request with a 4xx status (other
+    /// than 429), which a retry usually won't fix.
+    ClientError,
+    /// The request timed out or couldn't connect.
+    Network,
+    /// The source's `DuckDB` file couldn't be opened or written.
+    Database,
+    /// Anything else (parse or normalization failures).
+    Other,
+}
+
+impl SyncFailureKind {
+    /// Returns the kind's `snake_case` name, as serialized.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::ServerError => "server_error",
+            Self::ClientError => "client_error",
+            Self::Network => "network",
+            Self::Database => "database",
+            Self::Other => "other",
+        }
+    }
+
+    /// Classifies a sync error by its innermost recognized cause.
+    #[must_use]
+    pub fn of(err: &(dyn std::error::Error + 'static)) -> Self {
+        let mut current = Some(err);
+        while let Some(err) = current {
+            if let Some(crime_map_source::SourceError::Normalization { message }) =
+                err.downcast_ref::<crime_map_source::SourceError>()
+            {
+                return Self::from_http_message(message);
+            }
+            if let Some(e) = err.downcast_ref::<reqwest::Error>() {
+                return match e.status() {
+                    Some(status) if status == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                        Self::RateLimited
+                    }
+                    Some(status) if status.is_server_error() => Self::ServerError,
+                    Some(status) if status.is_client_error() => Self::ClientError,
+                    _ if e.is_timeout() || e.is_connect() => Self::Network,
+                    _ => Self::Other,
+                };
+            }
+            if err.is::<duckdb::Error>() {
+                return Self::Database;
+            }
+            current = err.source();
+        }
+        Self::Other
+    }
+
+    /// Classifies the `HTTP {status}` messages the fetchers report for
+    /// non-success responses.
+    fn from_http_message(message: &str) -> Self {
+        if message.starts_with("HTTP 429") {
+            Self::RateLimited
+        } else if message.starts_with("HTTP 5") {
+            Self::ServerError
+        } else if message.starts_with("HTTP 4") {
+            Self::ClientError
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// A source that failed to sync, as recorded in
+/// [`paths::last_sync_failures_path`](crime_map_database::paths::last_sync_failures_path).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFailure {
+    /// Source ID.
+    pub source_id: String,
+    /// Broad cause of the failure.
+    pub kind: SyncFailureKind,
+    /// The last error message.
+    pub error: String,
+    /// When the source last failed.
+    pub failed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SyncFailure {
+    fn new(source_id: &str, err: &(dyn std::error::Error + 'static)) -> Self {
+        Self {
+            source_id: source_id.to_string(),
+            kind: SyncFailureKind::of(err),
+            error: err.to_string(),
+            failed_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// A source's fetcher failed partway through a sync. Keeps the
+/// [`SourceError`](crime_map_source::SourceError) as its source so
+/// [`SyncFailureKind::of`] can classify it.
+#[derive(Debug)]
+struct FetchError {
+    source_name: String,
+    error: crime_map_source::SourceError,
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Fetch error for {}: {}", self.source_name, self.error)
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Result of a [`dry_run_normalize`] call.
+#[derive(Debug, Default)]
+pub struct NormalizeReport {
+    /// Raw records returned by the fetcher.
+    pub total_raw: u64,
+    /// Records that survived normalization.
+    pub normalized: u64,
+    /// Normalized records whose crime type fell through to
+    /// [`CrimeSubcategory::Unknown`].
+    pub unknown: u64,
+    /// Number of normalized records per mapped subcategory (including
+    /// [`CrimeSubcategory::Unknown`]).
+    pub subcategories: BTreeMap<CrimeSubcategory, u64>,
+}
+
+impl NormalizeReport {
+    /// Fraction of normalized records that mapped to a known subcategory,
+    /// or `0.0` if nothing normalized.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mapped_ratio(&self) -> f64 {
+        if self.normalized == 0 {
+            return 0.0;
+        }
+        (self.normalized - self.unknown) as f64 / self.normalized as f64
+    }
 }
 
 /// Result of a [`run_geocode`] call.
@@ -117,6 +322,119 @@ pub struct EnrichResult {
     pub sources_processed: u64,
 }
 
+/// Coordinate health for one source, as returned by [`quality_report`].
+///
+/// Ratios are `0.0` when their denominator is zero. Geocoded and enriched
+/// ratios are over incidents with coordinates, since nothing else can be
+/// either.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceQuality {
+    /// Source ID.
+    pub source_id: String,
+    /// Total incidents.
+    pub total: u64,
+    /// Incidents with coordinates.
+    pub with_coordinates: u64,
+    /// Incidents whose coordinates came from geocoding.
+    pub geocoded: u64,
+    /// Incidents whose coordinates were provided by the source.
+    pub source_provided: u64,
+    /// Incidents with spatial attribution applied.
+    pub enriched: u64,
+    /// Incidents with coordinates outside [`source_db::US_BBOX`].
+    pub outside_us: u64,
+    /// Fraction of incidents with coordinates.
+    pub coordinates_ratio: f64,
+    /// Fraction of coordinates that came from geocoding; the rest were
+    /// provided by the source.
+    pub geocoded_ratio: f64,
+    /// Fraction of coordinates with spatial attribution applied.
+    pub enriched_ratio: f64,
+    /// Broad cause of the last sync failure, if the last sync failed.
+    pub last_error_kind: Option<String>,
+    /// The last sync failure's error message.
+    pub last_error: Option<String>,
+    /// When the last sync failed, as RFC 3339.
+    pub last_failed_at: Option<String>,
+}
+
+impl SourceQuality {
+    #[allow(clippy::cast_precision_loss)]
+    fn new(
+        source_id: String,
+        counts: source_db::CoordinateQuality,
+        last_error: Option<source_db::LastSyncError>,
+    ) -> Self {
+        let ratio = |n: u64, of: u64| if of == 0 { 0.0 } else { n as f64 / of as f64 };
+        Self {
+            source_id,
+            coordinates_ratio: ratio(counts.with_coordinates, counts.total),
+            geocoded_ratio: ratio(counts.geocoded, counts.with_coordinates),
+            enriched_ratio: ratio(counts.enriched, counts.with_coordinates),
+            total: counts.total,
+            with_coordinates: counts.with_coordinates,
+            geocoded: counts.geocoded,
+            source_provided: counts.with_coordinates - counts.geocoded,
+            enriched: counts.enriched,
+            outside_us: counts.outside_us,
+            last_error_kind: last_error.as_ref().map(|e| e.kind.clone()),
+            last_failed_at: last_error.as_ref().map(|e| e.failed_at.clone()),
+            last_error: last_error.map(|e| e.message),
+        }
+    }
+}
+
+// ── HTTP client ──────────────────────────────────────────────────
+
+/// Builds the shared [`reqwest::Client`] used for geocoding and boundary
+/// ingestion.
+///
+/// Sets the crate [`USER_AGENT`], connect and request timeouts, and
+/// connection pool limits so a single hung upstream cannot stall a whole
+/// batch. Timeouts default to 30s (connect) and 300s (request, generous
+/// enough for 10k-address Census batches) and can be overridden with the
+/// `CRIME_MAP_HTTP_CONNECT_TIMEOUT_SECS` and `CRIME_MAP_HTTP_TIMEOUT_SECS`
+/// environment variables.
+///
+/// Timed-out requests surface as
+/// [`crime_map_geocoder::GeocodeError::Timeout`] from the geocoder
+/// providers, which callers treat as transient.
+///
+/// # Errors
+///
+/// Returns an error if the client cannot be built.
+pub fn build_http_client() -> Result<reqwest::Client, reqwest::Error> {
+    let connect_timeout = env_secs(
+        "CRIME_MAP_HTTP_CONNECT_TIMEOUT_SECS",
+        DEFAULT_CONNECT_TIMEOUT_SECS,
+    );
+    let request_timeout = env_secs("CRIME_MAP_HTTP_TIMEOUT_SECS", DEFAULT_REQUEST_TIMEOUT_SECS);
+
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(std::time::Duration::from_secs(connect_timeout))
+        .timeout(std::time::Duration::from_secs(request_timeout))
+        .pool_idle_timeout(std::time::Duration::from_secs(POOL_IDLE_TIMEOUT_SECS))
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .build()
+}
+
+/// Reads a positive number of seconds from an environment variable,
+/// falling back to `default` if unset or invalid.
+fn env_secs(var: &str, default: u64) -> u64 {
+    match std::env::var(var) {
+        Ok(value) => match value.trim().parse::<u64>() {
+            Ok(secs) if secs > 0 => secs,
+            _ => {
+                log::warn!("Invalid {var}='{value}', using default {default}s");
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
 // ── High-level orchestration functions ───────────────────────────
 
 /// Syncs data from the specified sources (or all enabled sources if
@@ -126,6 +444,11 @@ pub struct EnrichResult {
 /// collects results. Returns a [`SyncResult`] with the list of any
 /// sources that failed so the caller can decide how to handle them.
 ///
+/// The failures are also recorded in the
+/// [`last_sync_failures_path`](crime_map_database::paths::last_sync_failures_path)
+/// state file for [`run_sync_retry`]: every source synced here is
+/// replaced in it by its new failure, or cleared if it succeeded.
+///
 /// # Errors
 ///
 /// Only returns `Err` for fatal/unrecoverable errors (none currently).
@@ -147,6 +470,7 @@ pub async fn run_sync(args: &SyncArgs, progress: Option<&Arc<dyn ProgressCallbac
     let mut result = SyncResult {
         succeeded: 0,
         failed: Vec::new(),
+        failures: Vec::new(),
     };
 
     for (i, src) in sources.iter().enumerate() {
@@ -161,11 +485,12 @@ pub async fn run_sync(args: &SyncArgs, progress: Option<&Arc<dyn ProgressCallbac
 
         match source_db::open_by_id(src.id()) {
             Ok(conn) => {
-                let mut sync_succeeded = false;
+                let mut failure = None;
                 for attempt in 1..=3u32 {
-                    match sync_source(&conn, src, args.limit, args.force, None).await {
+                    match sync_source(&conn, src, args.limit, args.force, args.keep_raw, None).await
+                    {
                         Ok(()) => {
-                            sync_succeeded = true;
+                            failure = None;
                             break;
                         }
                         Err(e) => {
@@ -179,18 +504,24 @@ pub async fn run_sync(args: &SyncArgs, progress: Option<&Arc<dyn ProgressCallbac
                             } else {
                                 log::error!("Failed to sync {} after 3 attempts: {e}", src.id());
                             }
+                            failure = Some(SyncFailure::new(src.id(), e.as_ref()));
                         }
                     }
                 }
-                if sync_succeeded {
-                    result.succeeded += 1;
-                } else {
+                if let Some(failure) = failure {
                     result.failed.push(src.id().to_string());
+                    result.failures.push(failure);
+                } else {
+                    result.succeeded += 1;
                 }
             }
             Err(e) => {
                 log::error!("Failed to open DB for {}: {e}", src.id());
                 result.failed.push(src.id().to_string());
+                result.failures.push(SyncFailure {
+                    kind: SyncFailureKind::Database,
+                    ..SyncFailure::new(src.id(), &e)
+                });
             }
         }
 
@@ -199,9 +530,105 @@ pub async fn run_sync(args: &SyncArgs, progress: Option<&Arc<dyn ProgressCallbac
         }
     }
 
+    let synced: Vec<&str> = sources.iter().map(SourceDefinition::id).collect();
+    if let Err(e) = record_sync_failures(&synced, &result.failures) {
+        log::warn!("Failed to record sync failures: {e}");
+    }
+
     result
 }
 
+/// Re-syncs only the sources that failed their last sync, as recorded by
+/// [`run_sync`] (narrowed to `args.source_ids`, if any). Sources that
+/// succeed are cleared from the record; a missing or unreadable record
+/// means there's nothing to retry.
+#[allow(clippy::future_not_send)]
+pub async fn run_sync_retry(
+    args: &SyncArgs,
+    progress: Option<&Arc<dyn ProgressCallback>>,
+) -> SyncResult {
+    let failures: Vec<SyncFailure> = load_sync_failures()
+        .into_iter()
+        .filter(|f| args.source_ids.is_empty() || args.source_ids.contains(&f.source_id))
+        .collect();
+    if failures.is_empty() {
+        log::info!("No failed sources to retry");
+        return SyncResult {
+            succeeded: 0,
+            failed: Vec::new(),
+            failures: Vec::new(),
+        };
+    }
+
+    for failure in &failures {
+        log::info!(
+            "Retrying {} (failed {} with {:?}: {})",
+            failure.source_id,
+            failure.failed_at.format("%Y-%m-%d %H:%M UTC"),
+            failure.kind,
+            failure.error
+        );
+    }
+    let retry = SyncArgs {
+        source_ids: failures.into_iter().map(|f| f.source_id).collect(),
+        limit: args.limit,
+        force: args.force,
+        keep_raw: args.keep_raw,
+    };
+    run_sync(&retry, progress).await
+}
+
+/// Returns the failures recorded by the last [`run_sync`] of each source
+/// that's still failing, sorted by source ID. Empty if there's no record
+/// or it can't be read.
+#[must_use]
+pub fn load_sync_failures() -> Vec<SyncFailure> {
+    let path = crime_map_database::paths::last_sync_failures_path();
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            log::warn!("Ignoring unreadable {}: {e}", path.display());
+            return Vec::new();
+        }
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid {}: {e}", path.display());
+        Vec::new()
+    })
+}
+
+/// Replaces the recorded failures of every source in `synced` with
+/// `failures`, keeping other sources' records. Removes the state file
+/// once nothing is failing.
+///
+/// # Errors
+///
+/// Returns an error if the state file can't be written or removed.
+fn record_sync_failures(synced: &[&str], failures: &[SyncFailure]) -> std::io::Result<()> {
+    let path = crime_map_database::paths::last_sync_failures_path();
+    let mut by_source: BTreeMap<String, SyncFailure> = load_sync_failures()
+        .into_iter()
+        .filter(|f| !synced.contains(&f.source_id.as_str()))
+        .map(|f| (f.source_id.clone(), f))
+        .collect();
+    by_source.extend(failures.iter().map(|f| (f.source_id.clone(), f.clone())));
+
+    if by_source.is_empty() {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+
+    crime_map_database::paths::ensure_dir(&crime_map_database::paths::sources_dir())?;
+    let records: Vec<&SyncFailure> = by_source.values().collect();
+    let json = serde_json::to_string_pretty(&records).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, &path)
+}
+
 /// Runs the two-phase geocode pipeline: first geocodes incidents missing
 /// coordinates, then re-geocodes sources with imprecise block-centroid
 /// coordinates.
@@ -225,7 +652,14 @@ pub async fn run_geocode(
             .collect()
     };
 
+    let services = ordered_services(&args.provider_order)?;
+    if !args.provider_order.is_empty() {
+        let ids: Vec<&str> = services.iter().map(|s| s.id.as_str()).collect();
+        log::info!("Geocoding provider order: {}", ids.join(" → "));
+    }
+
     let cache_conn = geocode_cache::open_default()?;
+    let mut memo = args.dedup_addresses.then(AddressMemo::default);
 
     let mut missing_geocoded = 0u64;
 
@@ -237,7 +671,11 @@ pub async fn run_geocode(
             &cache_conn,
             args.batch_size,
             args.limit,
-            args.nominatim_only,
+            args.since,
+            &services,
+            args.match_validation,
+            args.cache_flush_every,
+            memo.as_mut(),
             progress.clone(),
         )
         .await?;
@@ -267,7 +705,11 @@ pub async fn run_geocode(
                     &cache_conn,
                     args.batch_size,
                     remaining_limit,
-                    args.nominatim_only,
+                    args.since,
+                    &services,
+                    args.match_validation,
+                    args.cache_flush_every,
+                    memo.as_mut(),
                     progress.clone(),
                 )
                 .await?;
@@ -276,6 +718,14 @@ pub async fn run_geocode(
         }
     }
 
+    if let Some(memo) = &memo {
+        log::info!(
+            "Address memo: {} distinct addresses, {} repeat lookups skipped",
+            memo.len(),
+            memo.hits()
+        );
+    }
+
     Ok(GeocodeResult {
         missing_geocoded,
         re_geocoded,
@@ -291,7 +741,8 @@ const ENRICH_BATCH_SIZE: i64 = 50_000;
 /// `args.force` is `true`), performs point-in-polygon lookups against
 /// the boundaries `SpatialIndex`, and writes the results
 /// (`census_tract_geoid`, `census_place_geoid`, `state_fips`,
-/// `county_geoid`, `neighborhood_id`) back to the source `DuckDB`.
+/// `county_geoid`, `neighborhood_id`) back to each of the source's `DuckDB`
+/// files (see [`source_db::shard_paths`]).
 ///
 /// Records that fall outside all known census tracts / places are still
 /// marked `enriched = TRUE` with `NULL` geo fields so they are not
@@ -329,118 +780,149 @@ pub fn run_enrich(
     let geo_index = SpatialIndex::load(&boundaries_conn)?;
     drop(boundaries_conn);
 
+    let precedences: BTreeMap<String, _> = all_sources()
+        .iter()
+        .map(|s| (s.id().to_string(), s.coordinate_precedence()))
+        .collect();
+
     let mut total_enriched = 0u64;
     let mut sources_processed = 0u64;
 
     for sid in &target_ids {
-        let source_conn = match source_db::open_by_id(sid) {
-            Ok(c) => c,
-            Err(e) => {
-                log::warn!("Skipping source '{sid}': {e}");
-                continue;
-            }
-        };
+        let precedence = precedences.get(sid).copied().unwrap_or_default();
+        let mut processed = false;
+
+        for path in source_db::shard_paths(sid) {
+            let label = path
+                .file_stem()
+                .map_or_else(|| sid.clone(), |s| s.to_string_lossy().into_owned());
+            let source_conn = match source_db::open_existing(&path) {
+                Ok(Some(c)) => c,
+                Ok(None) => {
+                    log::warn!("Skipping {}: no incidents table", path.display());
+                    continue;
+                }
+                Err(e) => {
+                    log::warn!("Skipping {}: {e}", path.display());
+                    continue;
+                }
+            };
 
-        let filter = if args.force {
-            "WHERE has_coordinates = TRUE \
-                AND longitude BETWEEN -180 AND 180 \
-                AND latitude BETWEEN -90 AND 90"
-        } else {
-            "WHERE has_coordinates = TRUE \
-                AND enriched = FALSE \
-                AND longitude BETWEEN -180 AND 180 \
-                AND latitude BETWEEN -90 AND 90"
-        };
+            // Settle which coordinate each incident uses before attributing,
+            // so incidents whose coordinate switched are re-enriched below.
+            let switched = source_db::apply_coordinate_precedence(&source_conn, precedence)?;
+            if switched > 0 {
+                log::info!(
+                    "{label}: switched {switched} record(s) to {} coordinates",
+                    precedence.as_str()
+                );
+            }
 
-        // Count eligible rows for progress
-        let count_sql = format!("SELECT COUNT(*) FROM incidents {filter}");
-        let mut count_stmt = source_conn.prepare(&count_sql)?;
-        let eligible: i64 = count_stmt.query_row([], |row| row.get(0))?;
+            let filter = if args.force {
+                "WHERE has_coordinates = TRUE \
+                    AND longitude BETWEEN -180 AND 180 \
+                    AND latitude BETWEEN -90 AND 90"
+            } else {
+                "WHERE has_coordinates = TRUE \
+                    AND enriched = FALSE \
+                    AND longitude BETWEEN -180 AND 180 \
+                    AND latitude BETWEEN -90 AND 90"
+            };
 
-        if eligible == 0 {
-            log::info!("{sid}: no un-enriched records, skipping");
-            continue;
-        }
+            // Count eligible rows for progress
+            let count_sql = format!("SELECT COUNT(*) FROM incidents {filter}");
+            let mut count_stmt = source_conn.prepare(&count_sql)?;
+            let eligible: i64 = count_stmt.query_row([], |row| row.get(0))?;
 
-        #[allow(clippy::cast_sign_loss)]
-        {
-            log::info!("{sid}: enriching {eligible} record(s)");
-            if let Some(ref p) = progress {
-                p.set_total(eligible as u64);
+            if eligible == 0 {
+                log::info!("{label}: no un-enriched records, skipping");
+                continue;
             }
-        }
 
-        // Keyset pagination using source_incident_id ordering
-        let query_sql = format!(
-            "SELECT source_incident_id, longitude, latitude \
-             FROM incidents {filter} \
-                AND source_incident_id > ? \
-             ORDER BY source_incident_id ASC \
-             LIMIT ?"
-        );
+            #[allow(clippy::cast_sign_loss)]
+            {
+                log::info!("{label}: enriching {eligible} record(s)");
+                if let Some(ref p) = progress {
+                    p.set_total(eligible as u64);
+                }
+            }
 
-        let mut last_id = String::new();
-        let mut source_enriched = 0u64;
+            // Keyset pagination using source_incident_id ordering
+            let query_sql = format!(
+                "SELECT source_incident_id, longitude, latitude \
+                 FROM incidents {filter} \
+                    AND source_incident_id > ? \
+                 ORDER BY source_incident_id ASC \
+                 LIMIT ?"
+            );
 
-        loop {
-            let mut stmt = source_conn.prepare(&query_sql)?;
-            let mut rows = stmt.query(duckdb::params![&last_id, ENRICH_BATCH_SIZE])?;
+            let mut last_id = String::new();
+            let mut source_enriched = 0u64;
+
+            loop {
+                let mut stmt = source_conn.prepare(&query_sql)?;
+                let mut rows = stmt.query(duckdb::params![&last_id, ENRICH_BATCH_SIZE])?;
+
+                let mut batch: Vec<source_db::AttributionUpdate> = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let incident_id: String = row.get(0)?;
+                    let lng: f64 = row.get(1)?;
+                    let lat: f64 = row.get(2)?;
+
+                    let tract_geoid = geo_index.lookup_tract(lng, lat).map(str::to_owned);
+                    let place_geoid = geo_index.lookup_place(lng, lat).map(str::to_owned);
+                    let state_fips = tract_geoid
+                        .as_deref()
+                        .and_then(SpatialIndex::derive_state_fips)
+                        .map(str::to_owned);
+                    let county_geoid = tract_geoid
+                        .as_deref()
+                        .and_then(SpatialIndex::derive_county_geoid)
+                        .map(str::to_owned);
+                    let neighborhood_id = tract_geoid
+                        .as_deref()
+                        .and_then(|g| geo_index.lookup_neighborhood(g))
+                        .map(str::to_owned);
+
+                    last_id.clone_from(&incident_id);
+
+                    batch.push(source_db::AttributionUpdate {
+                        source_incident_id: incident_id,
+                        census_tract_geoid: tract_geoid,
+                        census_place_geoid: place_geoid,
+                        state_fips,
+                        county_geoid,
+                        neighborhood_id,
+                    });
+                }
 
-            let mut batch: Vec<source_db::AttributionUpdate> = Vec::new();
-            while let Some(row) = rows.next()? {
-                let incident_id: String = row.get(0)?;
-                let lng: f64 = row.get(1)?;
-                let lat: f64 = row.get(2)?;
-
-                let tract_geoid = geo_index.lookup_tract(lng, lat).map(str::to_owned);
-                let place_geoid = geo_index.lookup_place(lng, lat).map(str::to_owned);
-                let state_fips = tract_geoid
-                    .as_deref()
-                    .and_then(SpatialIndex::derive_state_fips)
-                    .map(str::to_owned);
-                let county_geoid = tract_geoid
-                    .as_deref()
-                    .and_then(SpatialIndex::derive_county_geoid)
-                    .map(str::to_owned);
-                let neighborhood_id = tract_geoid
-                    .as_deref()
-                    .and_then(|g| geo_index.lookup_neighborhood(g))
-                    .map(str::to_owned);
-
-                last_id.clone_from(&incident_id);
-
-                batch.push(source_db::AttributionUpdate {
-                    source_incident_id: incident_id,
-                    census_tract_geoid: tract_geoid,
-                    census_place_geoid: place_geoid,
-                    state_fips,
-                    county_geoid,
-                    neighborhood_id,
-                });
-            }
+                if batch.is_empty() {
+                    break;
+                }
 
-            if batch.is_empty() {
-                break;
-            }
+                #[allow(clippy::cast_possible_truncation)]
+                let batch_len = batch.len() as u64;
+                source_db::batch_update_attribution(&source_conn, &batch)?;
+                source_enriched += batch_len;
 
-            #[allow(clippy::cast_possible_truncation)]
-            let batch_len = batch.len() as u64;
-            source_db::batch_update_attribution(&source_conn, &batch)?;
-            source_enriched += batch_len;
+                if let Some(ref p) = progress {
+                    p.inc(batch_len);
+                }
 
-            if let Some(ref p) = progress {
-                p.inc(batch_len);
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+                if (batch_len as i64) < ENRICH_BATCH_SIZE {
+                    break;
+                }
             }
 
-            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-            if (batch_len as i64) < ENRICH_BATCH_SIZE {
-                break;
-            }
+            log::info!("{label}: enriched {source_enriched} record(s)");
+            total_enriched += source_enriched;
+            processed = true;
         }
 
-        log::info!("{sid}: enriched {source_enriched} record(s)");
-        total_enriched += source_enriched;
-        sources_processed += 1;
+        if processed {
+            sources_processed += 1;
+        }
     }
 
     if let Some(ref p) = progress {
@@ -453,6 +935,119 @@ pub fn run_enrich(
     })
 }
 
+/// Builds a coordinate-quality report for the given sources, including
+/// each source's last sync failure recorded by [`sync_source`].
+///
+/// Empty `source_ids` means every source with a local `DuckDB` file.
+/// Counts are summed across a sharded source's files (see
+/// [`source_db::shard_paths`]). Files without an `incidents` table (e.g.
+/// an interrupted first sync) are skipped with a warning, as are sources
+/// left with none.
+///
+/// # Errors
+///
+/// Returns an error if a source database can't be opened or queried.
+pub fn quality_report(
+    source_ids: &[String],
+) -> Result<Vec<SourceQuality>, crime_map_database::DbError> {
+    let target_ids = if source_ids.is_empty() {
+        source_db::discover_source_ids()
+    } else {
+        source_ids.to_vec()
+    };
+
+    let mut report = Vec::with_capacity(target_ids.len());
+    for sid in target_ids {
+        let paths = source_db::shard_paths(&sid);
+        if paths.is_empty() {
+            log::warn!("Skipping source '{sid}': no local DuckDB file");
+            continue;
+        }
+
+        let mut counts: Option<source_db::CoordinateQuality> = None;
+        let mut last_error = None;
+        for path in paths {
+            let Some(conn) = source_db::open_existing(&path)? else {
+                log::warn!("Skipping {}: no incidents table", path.display());
+                continue;
+            };
+            let shard = source_db::coordinate_quality(&conn)?;
+            let total = counts.get_or_insert_default();
+            total.total += shard.total;
+            total.with_coordinates += shard.with_coordinates;
+            total.geocoded += shard.geocoded;
+            total.enriched += shard.enriched;
+            total.outside_us += shard.outside_us;
+            if last_error.is_none() {
+                last_error = source_db::get_last_error(&conn)?;
+            }
+        }
+
+        let Some(counts) = counts else {
+            log::warn!("Skipping source '{sid}': no incidents table");
+            continue;
+        };
+        report.push(SourceQuality::new(sid, counts, last_error));
+    }
+
+    Ok(report)
+}
+
+/// Integrity of one shared database, as returned by
+/// [`verify_shared_databases`].
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedDbHealth {
+    /// Path of the database file.
+    pub path: std::path::PathBuf,
+    /// Total rows across its tables, if it passed.
+    pub rows: Option<u64>,
+    /// What's wrong with it, or `None` if it passed.
+    pub problem: Option<String>,
+}
+
+/// Checks the integrity of the shared `boundaries.duckdb` and
+/// `geocode_cache.duckdb` (see [`geocode_cache::verify`]), e.g. after
+/// pulling them from R2. Each is opened read-only, so a missing table is
+/// reported rather than created. Files that don't exist are skipped.
+#[must_use]
+pub fn verify_shared_databases() -> Vec<SharedDbHealth> {
+    type Verify = fn(&Connection) -> Result<u64, crime_map_database::DbError>;
+    let databases: [(std::path::PathBuf, Verify); 2] = [
+        (
+            crime_map_database::paths::boundaries_db_path(),
+            crime_map_database::boundaries_db::verify,
+        ),
+        (
+            crime_map_database::paths::geocode_cache_db_path(),
+            geocode_cache::verify,
+        ),
+    ];
+
+    let mut report = Vec::new();
+    for (path, verify) in databases {
+        if !path.exists() {
+            log::info!("Skipping {}: not present", path.display());
+            continue;
+        }
+        let result = duckdb::Config::default()
+            .access_mode(duckdb::AccessMode::ReadOnly)
+            .and_then(|config| Connection::open_with_flags(&path, config))
+            .map_err(|e| format!("failed to open: {e}"))
+            .and_then(|conn| verify(&conn).map_err(|e| e.to_string()));
+        match &result {
+            Ok(rows) => log::info!("{} verified ({rows} rows)", path.display()),
+            Err(problem) => log::error!("{} failed verification: {problem}", path.display()),
+        }
+        report.push(SharedDbHealth {
+            path,
+            rows: result.as_ref().ok().copied(),
+            problem: result.err(),
+        });
+    }
+    report
+}
+
 /// Ingests census boundaries (tracts, places, counties, states) and
 /// neighborhoods into the shared `boundaries.duckdb`.
 ///
@@ -540,9 +1135,7 @@ pub async fn run_ingest_boundaries(
     let mut neighborhoods = 0u64;
 
     if !all_nbhd_sources.is_empty() {
-        let client = reqwest::Client::builder()
-            .user_agent("crime-map/1.0")
-            .build()?;
+        let client = build_http_client()?;
 
         let mut new_ingested = false;
         for source in &all_nbhd_sources {
@@ -613,17 +1206,17 @@ pub fn boundary_tract_count() -> Result<u64, Box<dyn std::error::Error>> {
     Ok(count as u64)
 }
 
-/// Resolves source IDs to definitions. If `source_ids` is empty, returns
-/// all enabled sources (respecting `CRIME_MAP_SOURCES` env var).
+/// Resolves source IDs to definitions, in ID order, with
+/// [`reconcile_sources`]. If `source_ids` is empty, returns all enabled
+/// sources (respecting `CRIME_MAP_SOURCES` env var). IDs missing from the
+/// registry are skipped with a warning.
 fn resolve_source_defs(source_ids: &[String]) -> Vec<SourceDefinition> {
-    if source_ids.is_empty() {
-        enabled_sources(None)
-    } else {
-        let all = all_sources();
-        all.into_iter()
-            .filter(|s| source_ids.contains(&s.id().to_string()))
-            .collect()
+    let synced = source_db::discover_source_ids().into_iter().collect();
+    let reconciliation = reconcile_sources(source_ids, &synced);
+    for problem in reconciliation.problems(&[SourceStatus::Unknown]) {
+        log::warn!("Skipping {problem}");
     }
+    reconciliation.into_enabled_definitions()
 }
 
 /// Returns all configured data sources from the TOML registry.
@@ -633,22 +1226,15 @@ pub fn all_sources() -> Vec<SourceDefinition> {
 }
 
 /// Returns the sources to sync, filtered by the `--sources` CLI flag or the
-/// `CRIME_MAP_SOURCES` environment variable. If neither is set, all sources
-/// are returned.
+/// `CRIME_MAP_SOURCES` environment variable, in ID order. If neither is
+/// set, all sources are returned.
 #[must_use]
 pub fn enabled_sources(cli_filter: Option<String>) -> Vec<SourceDefinition> {
-    let filter = cli_filter.or_else(|| std::env::var("CRIME_MAP_SOURCES").ok());
-
-    let all = all_sources();
-
-    let Some(filter_str) = filter else {
-        return all;
-    };
-
-    let ids: Vec<&str> = filter_str.split(',').map(str::trim).collect();
+    let ids: Vec<String> = cli_filter
+        .map(|filter| filter.split(',').map(|id| id.trim().to_string()).collect())
+        .unwrap_or_default();
 
-    let filtered: Vec<SourceDefinition> =
-        all.into_iter().filter(|s| ids.contains(&s.id())).collect();
+    let filtered = resolve_source_defs(&ids);
 
     if filtered.is_empty() {
         log::warn!(
@@ -676,16 +1262,58 @@ pub fn enabled_sources(cli_filter: Option<String>) -> Vec<SourceDefinition> {
 /// `MAX(occurred_at) - 7 days` for the source. Pass `force = true` to
 /// ignore the previous sync point and fetch everything.
 ///
+/// With `keep_raw`, that fraction of records also has its raw source JSON
+/// stored in the source's `raw_records` table, so generation can copy it
+/// into the sidebar DB for debugging normalization. Records are picked by
+/// a hash of their incident ID, so re-syncs keep the same ones.
+///
+/// A failed sync records its error, [`SyncFailureKind`], and time in the
+/// source's `_meta` table (see [`source_db::set_last_error`]) so
+/// [`quality_report`] can show why a source last failed; a successful
+/// sync clears it.
+///
 /// # Errors
 ///
-/// Returns an error if database queries, source fetching, or page
-/// normalization/insertion fails.
-#[allow(clippy::too_many_lines, clippy::future_not_send)]
+/// Returns an error if `keep_raw` isn't in `(0, 1]`, or if database
+/// queries, source fetching, or page normalization/insertion fails.
+#[allow(clippy::future_not_send)]
 pub async fn sync_source(
     conn: &Connection,
     source: &SourceDefinition,
     limit: Option<u64>,
     force: bool,
+    keep_raw: Option<f64>,
+    progress: Option<Arc<dyn ProgressCallback>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(fraction) = keep_raw
+        && !(fraction > 0.0 && fraction <= 1.0)
+    {
+        return Err(format!("--keep-raw ({fraction}) must be more than 0 and at most 1").into());
+    }
+
+    let result = sync_source_pages(conn, source, limit, force, keep_raw, progress).await;
+    let recorded = match &result {
+        Ok(()) => source_db::clear_last_error(conn),
+        Err(e) => source_db::set_last_error(
+            conn,
+            SyncFailureKind::of(e.as_ref()).as_str(),
+            &e.to_string(),
+        ),
+    };
+    if let Err(e) = recorded {
+        log::warn!("{}: failed to record the sync outcome: {e}", source.name());
+    }
+    result
+}
+
+/// Runs a [`sync_source`] sync.
+#[allow(clippy::too_many_lines, clippy::future_not_send)]
+async fn sync_source_pages(
+    conn: &Connection,
+    source: &SourceDefinition,
+    limit: Option<u64>,
+    force: bool,
+    keep_raw: Option<f64>,
     progress: Option<Arc<dyn ProgressCallback>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let start = Instant::now();
@@ -778,6 +1406,17 @@ pub async fn sync_source(
         let inserted = source_db::insert_incidents(conn, &incidents)?;
         total_inserted += inserted;
 
+        if let Some(fraction) = keep_raw {
+            let raw: Vec<(String, String)> = page
+                .iter()
+                .filter_map(|record| {
+                    let id = source.incident_id(record)?;
+                    keeps_raw(&id, fraction).then(|| (id, record.to_string()))
+                })
+                .collect();
+            source_db::insert_raw_records(conn, &raw)?;
+        }
+
         log::info!(
             "{}: page {page_num} — normalized {norm_count}/{raw_count}, inserted {inserted}",
             source.name(),
@@ -792,7 +1431,11 @@ pub async fn sync_source(
         if let Err(meta_err) = source_db::update_sync_metadata(conn, source.name()) {
             log::warn!("Failed to save sync metadata after fetch error: {meta_err}");
         }
-        return Err(format!("Fetch error for {}: {e}", source.name()).into());
+        return Err(FetchError {
+            source_name: source.name().to_string(),
+            error: e,
+        }
+        .into());
     }
 
     // Update source metadata
@@ -805,6 +1448,12 @@ pub async fn sync_source(
 
     let elapsed = start.elapsed();
     log::info!(
+        event = "source_synced",
+        source_id = source.id(),
+        records = total_inserted,
+        normalized = total_normalized,
+        raw = total_raw,
+        elapsed_ms = elapsed.as_millis();
         "Sync complete for {}: {} inserted ({} normalized from {} raw), took {:.1}s",
         source.name(),
         total_inserted,
@@ -816,13 +1465,148 @@ pub async fn sync_source(
     Ok(())
 }
 
+/// Returns `true` if the raw record of incident `id` falls in the kept
+/// `fraction`. Uses FNV-1a rather than `std`'s hasher, whose output may
+/// change between Rust releases and reshuffle the sample.
+fn keeps_raw(id: &str, fraction: f64) -> bool {
+    let hash = id.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    #[allow(clippy::cast_precision_loss)]
+    let unit = hash as f64 / u64::MAX as f64;
+    unit < fraction
+}
+
+/// Fetches up to `sample_size` records from `source` and normalizes them
+/// without writing anything, reporting how the category mapping performed.
+///
+/// Uses the same fetch + [`SourceDefinition::normalize_page`] path as
+/// [`sync_source`] with `limit` capped to the sample, so a misconfigured
+/// type mapping (everything landing in [`CrimeSubcategory::Unknown`]) shows
+/// up before committing to a full sync.
+///
+/// # Errors
+///
+/// Returns an error if fetching fails.
+pub async fn dry_run_normalize(
+    source: &SourceDefinition,
+    sample_size: u64,
+) -> Result<NormalizeReport, Box<dyn std::error::Error>> {
+    let options = FetchOptions {
+        since: None,
+        limit: Some(sample_size),
+        resume_offset: 0,
+    };
+
+    let (mut rx, fetch_handle) =
+        source.fetch_pages(&options, crime_map_source::progress::null_progress());
+
+    let mut report = NormalizeReport::default();
+    while let Some(page) = rx.recv().await {
+        report.total_raw += page.len() as u64;
+
+        for incident in source.normalize_page(&page) {
+            report.normalized += 1;
+            if incident.subcategory == CrimeSubcategory::Unknown {
+                report.unknown += 1;
+            }
+            *report
+                .subcategories
+                .entry(incident.subcategory)
+                .or_default() += 1;
+        }
+    }
+
+    fetch_handle
+        .await?
+        .map_err(|e| format!("Fetch error for {}: {e}", source.name()))?;
+
+    Ok(report)
+}
+
+/// Number of Nominatim addresses between geocode cache flushes. Nominatim
+/// is rate-limited to about one address per second, so a crash would
+/// otherwise discard long runs of answers.
+const NOMINATIM_FLUSH_EVERY: usize = 50;
+
+/// Default [`GeocodeArgs::cache_flush_every`].
+pub const DEFAULT_CACHE_FLUSH_EVERY: usize = 10_000;
+
+/// Outcomes of the addresses resolved so far in one geocoding run.
+///
+/// The geocode cache already remembers outcomes across runs, but it is
+/// consulted once per batch and written only after each provider, so a
+/// memo carried across batches and sources lets [`resolve_addresses`]
+/// answer an address seen earlier in the run without a cache lookup or
+/// provider call.
+///
+/// Only definitive outcomes are remembered: a match, or a miss every
+/// provider answered. An address a provider couldn't answer (an error,
+/// timeout, or unreachable provider) is looked up again by later batches.
+#[derive(Debug, Default)]
+pub struct AddressMemo {
+    /// Address key to `(lng, lat)`, or `None` if no provider matched it.
+    outcomes: BTreeMap<String, Option<(f64, f64)>>,
+    /// Addresses answered from the memo.
+    hits: u64,
+}
+
+impl AddressMemo {
+    /// Number of distinct addresses remembered.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Whether no address has been remembered yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+
+    /// Number of addresses answered from the memo instead of the cache or
+    /// a provider.
+    #[must_use]
+    pub const fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the remembered outcome of `address_key` (`Some(None)` for a
+    /// miss), counting a hit, or `None` if it hasn't been resolved yet.
+    fn get(&mut self, address_key: &str) -> Option<&Option<(f64, f64)>> {
+        let outcome = self.outcomes.get(address_key)?;
+        self.hits += 1;
+        Some(outcome)
+    }
+
+    /// Remembers the outcome of `address_key`: its `(lng, lat)`, or `None`
+    /// for a miss. A miss is only remembered if it is `conclusive`, i.e.
+    /// every provider answered it.
+    fn record(&mut self, address_key: &str, outcome: Option<(f64, f64)>, conclusive: bool) {
+        if outcome.is_some() || conclusive {
+            self.outcomes.insert(address_key.to_string(), outcome);
+        }
+    }
+}
+
 /// Resolves addresses through the geocoding pipeline: cache → Census → Nominatim.
 ///
 /// For each unique address in `addr_groups`:
+/// 0. With a `memo`, reuse the outcome of an address already resolved
+///    earlier in the run
 /// 1. Check the geocode cache for existing results (hits or known misses)
-/// 2. Iterate enabled geocoding services (from the TOML registry) in
-///    priority order, sending unresolved addresses to each provider
-/// 3. Write all results (hits and misses) to cache
+/// 2. Iterate `services` (see [`ordered_services`]) in order, sending
+///    unresolved addresses to each provider
+/// 3. Write results (hits and misses) to cache as they arrive: after each
+///    Census batch, every [`NOMINATIM_FLUSH_EVERY`] Nominatim addresses,
+///    after each provider, and whenever `cache_flush_every` results are
+///    buffered, so a crash keeps what providers already answered and the
+///    buffer stays bounded
+///
+/// With `validation` enabled, a provider's match that disagrees with the
+/// address's city/state (see [`MatchValidation::accepts`]) is rejected:
+/// it is cached as a low-confidence outcome and the address stays
+/// unresolved for the remaining providers.
 ///
 /// Returns `(updates, all_incident_ids)` where `updates` are
 /// `(source_incident_id, lng, lat)` tuples for successfully geocoded
@@ -831,9 +1615,13 @@ pub async fn sync_source(
 ///
 /// # Errors
 ///
-/// Returns an error if cache lookups, geocoder requests, or cache writes fail.
+/// Returns an error if cache lookups or geocoder requests fail. A failed
+/// cache write isn't an error: its entries stay buffered for the next
+/// flush, and any still unwritten at the end are only logged, so the
+/// resolved updates are always returned.
 #[allow(
     clippy::too_many_lines,
+    clippy::too_many_arguments,
     clippy::type_complexity,
     clippy::future_not_send
 )]
@@ -841,15 +1629,17 @@ pub async fn resolve_addresses(
     cache_conn: &Connection,
     client: &reqwest::Client,
     addr_groups: &std::collections::BTreeMap<(String, String, String), Vec<String>>,
-    nominatim_only: bool,
+    services: &[GeocodingService],
+    validation: MatchValidation,
+    cache_flush_every: usize,
+    mut memo: Option<&mut AddressMemo>,
     progress: &Option<Arc<dyn ProgressCallback>>,
 ) -> Result<(Vec<(String, f64, f64)>, Vec<String>), Box<dyn std::error::Error>> {
     use crime_map_geocoder::address::build_one_line_address;
-    use crime_map_geocoder::service_registry::{ProviderConfig, enabled_services};
+    use crime_map_geocoder::service_registry::ProviderConfig;
 
     let mut pending_updates: Vec<(String, f64, f64)> = Vec::new();
     let mut all_ids: Vec<String> = Vec::new();
-    let cache_writes: Vec<CacheEntry> = Vec::new();
 
     // Collect all incident IDs
     for ids in addr_groups.values() {
@@ -865,12 +1655,45 @@ pub async fn resolve_addresses(
         })
         .collect();
 
-    let all_keys: Vec<String> = keys_and_groups.iter().map(|(k, _, _)| k.clone()).collect();
+    // --- Memo lookup: addresses already resolved earlier in this run ---
+    let mut memo_keys: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+    if let Some(memo) = memo.as_deref_mut() {
+        let mut memo_incidents = 0u64;
+        for (address_key, _, ids) in &keys_and_groups {
+            let Some(&outcome) = memo.get(address_key) else {
+                continue;
+            };
+            if let Some((lng, lat)) = outcome {
+                for id in *ids {
+                    pending_updates.push((id.clone(), lng, lat));
+                }
+            }
+            memo_incidents += ids.len() as u64;
+            memo_keys.insert(address_key.clone());
+        }
+
+        if let Some(p) = progress {
+            p.inc(memo_incidents);
+        }
+        if !memo_keys.is_empty() {
+            log::info!(
+                "Memo: {} addresses already resolved earlier in this run",
+                memo_keys.len()
+            );
+        }
+    }
+
+    let all_keys: Vec<String> = keys_and_groups
+        .iter()
+        .map(|(k, _, _)| k.clone())
+        .filter(|k| !memo_keys.contains(k))
+        .collect();
 
     // --- Phase 0: Cache lookup ---
     let (cache_hits, cache_tried) = geocode_cache::cache_lookup(cache_conn, &all_keys)?;
 
-    let mut resolved_keys: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
+    // Memo answers need no further resolution, like cache hits.
+    let mut resolved_keys = memo_keys.clone();
 
     // Apply cache hits
     let mut cache_resolved_incidents = 0u64;
@@ -905,24 +1728,22 @@ pub async fn resolve_addresses(
         );
     }
 
-    // --- Provider pipeline: iterate services in priority order ---
-    let services = enabled_services();
-    let filtered_services: Vec<_> = if nominatim_only {
-        services
-            .into_iter()
-            .filter(|s| s.id == "nominatim")
-            .collect()
-    } else {
-        services
-    };
+    // --- Provider pipeline: iterate services in the requested order ---
 
     let mut state = ResolveState {
+        cache_conn,
         resolved_keys,
         pending_updates,
-        cache_writes,
+        cache_writes: Vec::new(),
+        flush_every: cache_flush_every.max(1),
+        validation,
+        low_confidence_writes: Vec::new(),
+        inconclusive: std::collections::BTreeSet::new(),
+        cached: 0,
+        rejected: 0,
     };
 
-    for service in &filtered_services {
+    for service in services {
         // Collect unresolved addresses for this provider
         let unresolved: Vec<AddressGroup<'_>> = keys_and_groups
             .iter()
@@ -938,12 +1759,15 @@ pub async fn resolve_addresses(
             ProviderConfig::Census {
                 base_url,
                 benchmark,
+                vintage,
                 max_batch_size,
             } => {
+                crime_map_geocoder::census::validate_benchmark_vintage(benchmark, vintage)?;
                 resolve_via_census(
                     client,
                     base_url,
                     benchmark,
+                    vintage,
                     *max_batch_size,
                     &unresolved,
                     &mut state,
@@ -969,6 +1793,7 @@ pub async fn resolve_addresses(
                     .await
                 {
                     log::info!("Pelias at {base_url} is not reachable, skipping");
+                    state.mark_inconclusive(&unresolved);
                     continue;
                 }
                 resolve_via_pelias(
@@ -1000,50 +1825,178 @@ pub async fn resolve_addresses(
             ProviderConfig::TantivyIndex => {
                 if !crime_map_geocoder::tantivy_index::is_available() {
                     log::info!("Tantivy geocoder index not found, skipping");
+                    state.mark_inconclusive(&unresolved);
                     continue;
                 }
                 resolve_via_tantivy(&unresolved, &mut state, progress.as_ref()).await?;
             }
         }
+
+        state.flush_cache();
     }
 
-    // --- Flush cache writes ---
-    if !state.cache_writes.is_empty() {
+    let unwritten = state.cache_writes.len() + state.low_confidence_writes.len();
+    if unwritten > 0 {
+        log::warn!(
+            "{unwritten} geocode result(s) couldn't be written to the cache; \
+             they'll be looked up again next run"
+        );
+    }
+    if state.cached > 0 {
+        log::info!("Wrote {} entries to geocode cache", state.cached);
+    }
+    if state.rejected > 0 {
         log::info!(
-            "Writing {} entries to geocode cache...",
-            state.cache_writes.len()
+            "Rejected {} match(es) disagreeing with the expected city/state",
+            state.rejected
         );
-        geocode_cache::cache_insert(cache_conn, &state.cache_writes)?;
+    }
+
+    if let Some(memo) = memo {
+        let coords: BTreeMap<&str, (f64, f64)> = state
+            .pending_updates
+            .iter()
+            .map(|(id, lng, lat)| (id.as_str(), (*lng, *lat)))
+            .collect();
+        for (address_key, _, ids) in &keys_and_groups {
+            if memo_keys.contains(address_key) {
+                continue;
+            }
+            let outcome = ids.first().and_then(|id| coords.get(id.as_str()).copied());
+            memo.record(
+                address_key,
+                outcome,
+                !state.inconclusive.contains(address_key),
+            );
+        }
     }
 
     Ok((state.pending_updates, all_ids))
 }
 
 /// Shared mutable state threaded through provider-specific resolve functions.
-struct ResolveState {
+struct ResolveState<'a> {
+    cache_conn: &'a Connection,
     resolved_keys: std::collections::BTreeSet<String>,
     pending_updates: Vec<(String, f64, f64)>,
+    /// Results not yet written to the cache (see [`Self::flush_cache`]).
     cache_writes: Vec<CacheEntry>,
+    /// Buffered results that trigger a flush (see
+    /// [`Self::flush_cache_if_full`]).
+    flush_every: usize,
+    validation: MatchValidation,
+    /// Matches rejected by `validation`, cached as low confidence.
+    low_confidence_writes: Vec<CacheEntry>,
+    /// Addresses some provider couldn't answer (an error or an unreachable
+    /// provider), so a miss isn't final (see [`AddressMemo::record`]).
+    inconclusive: std::collections::BTreeSet<String>,
+    /// Entries written to the cache so far.
+    cached: usize,
+    /// Low-confidence entries written to the cache so far.
+    rejected: usize,
+}
+
+impl ResolveState<'_> {
+    /// Writes the buffered cache entries, so results survive a crash later
+    /// in the run. A failed write is logged and its entries are kept for
+    /// the next flush; the resolved updates are unaffected.
+    fn flush_cache(&mut self) {
+        if let Err(e) = self.try_flush_cache() {
+            log::warn!(
+                "Failed to write {} geocode result(s) to the cache, keeping them for \
+                 the next flush: {e}",
+                self.cache_writes.len() + self.low_confidence_writes.len()
+            );
+        }
+    }
+
+    /// Flushes the cache once [`Self::flush_every`] results are buffered.
+    fn flush_cache_if_full(&mut self) {
+        if self.cache_writes.len() + self.low_confidence_writes.len() >= self.flush_every {
+            self.flush_cache();
+        }
+    }
+
+    /// Writes the buffered cache entries, clearing each buffer once it's
+    /// written.
+    fn try_flush_cache(&mut self) -> Result<(), crime_map_database::DbError> {
+        if !self.cache_writes.is_empty() {
+            log::debug!(
+                "Writing {} entries to geocode cache...",
+                self.cache_writes.len()
+            );
+            geocode_cache::cache_insert(self.cache_conn, &self.cache_writes)?;
+            self.cached += self.cache_writes.len();
+            self.cache_writes.clear();
+        }
+        if !self.low_confidence_writes.is_empty() {
+            geocode_cache::cache_insert_low_confidence(
+                self.cache_conn,
+                &self.low_confidence_writes,
+            )?;
+            self.rejected += self.low_confidence_writes.len();
+            self.low_confidence_writes.clear();
+        }
+        Ok(())
+    }
+
+    /// Records that a provider couldn't answer any of `groups`.
+    fn mark_inconclusive(&mut self, groups: &[AddressGroup<'_>]) {
+        self.inconclusive
+            .extend(groups.iter().map(|(key, _, _)| key.clone()));
+    }
+
+    /// Checks a `provider` match for `address_key` against the expected
+    /// `(street, city, state)`. Returns `false`, recording a low-confidence
+    /// cache entry, if validation rejects it.
+    fn accept_match(
+        &mut self,
+        address_key: &str,
+        (_, city, state): &(String, String, String),
+        provider: &str,
+        geocoded: &crime_map_geocoder::GeocodedAddress,
+    ) -> bool {
+        if self
+            .validation
+            .accepts(geocoded.matched_address.as_deref(), city, state)
+        {
+            return true;
+        }
+        log::debug!(
+            "{provider}: rejected '{}' for '{address_key}' (expected {city}, {state})",
+            geocoded.matched_address.as_deref().unwrap_or_default()
+        );
+        self.low_confidence_writes.push((
+            address_key.to_string(),
+            provider.to_string(),
+            Some(geocoded.latitude),
+            Some(geocoded.longitude),
+            geocoded.matched_address.clone(),
+        ));
+        false
+    }
 }
 
 /// Resolves addresses via the US Census Bureau batch geocoder.
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 async fn resolve_via_census(
     client: &reqwest::Client,
     base_url: &str,
     benchmark: &str,
+    vintage: &str,
     max_batch_size: usize,
     unresolved: &[AddressGroup<'_>],
-    state: &mut ResolveState,
+    state: &mut ResolveState<'_>,
     progress: Option<&Arc<dyn ProgressCallback>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     use crime_map_geocoder::AddressInput;
     use std::collections::BTreeSet;
 
-    let inputs: Vec<(AddressInput, &str, &Vec<String>)> = unresolved
+    let inputs: Vec<(AddressInput, &str, &(String, String, String), &Vec<String>)> = unresolved
         .iter()
         .enumerate()
-        .map(|(i, (address_key, (street, city, addr_state), ids))| {
+        .map(|(i, (address_key, group, ids))| {
+            let (street, city, addr_state) = group;
             (
                 AddressInput {
                     id: i.to_string(),
@@ -1053,6 +2006,7 @@ async fn resolve_via_census(
                     zip: None,
                 },
                 address_key.as_str(),
+                *group,
                 *ids,
             )
         })
@@ -1060,7 +2014,7 @@ async fn resolve_via_census(
 
     for chunk in inputs.chunks(max_batch_size) {
         let batch_inputs: Vec<AddressInput> =
-            chunk.iter().map(|(input, _, _)| input.clone()).collect();
+            chunk.iter().map(|(input, _, _, _)| input.clone()).collect();
 
         log::info!(
             "Sending batch of {} addresses to Census geocoder...",
@@ -1069,8 +2023,14 @@ async fn resolve_via_census(
 
         let mut matched_keys: BTreeSet<String> = BTreeSet::new();
 
-        match crime_map_geocoder::census::geocode_batch(client, base_url, benchmark, &batch_inputs)
-            .await
+        match crime_map_geocoder::census::geocode_batch(
+            client,
+            base_url,
+            benchmark,
+            vintage,
+            &batch_inputs,
+        )
+        .await
         {
             Ok(result) => {
                 log::info!(
@@ -1081,8 +2041,11 @@ async fn resolve_via_census(
 
                 for (id_str, geocoded) in &result.matched {
                     let idx: usize = id_str.parse().unwrap_or(usize::MAX);
-                    if let Some(&(_, address_key, ids)) = chunk.get(idx) {
+                    if let Some(&(_, address_key, group, ids)) = chunk.get(idx) {
                         matched_keys.insert(address_key.to_string());
+                        if !state.accept_match(address_key, group, "census", geocoded) {
+                            continue;
+                        }
                         state.resolved_keys.insert(address_key.to_string());
 
                         state.cache_writes.push((
@@ -1107,13 +2070,25 @@ async fn resolve_via_census(
                     }
                 }
             }
+            Err(e) if e.is_transient() => {
+                // Don't cache misses for a batch that never got an answer —
+                // these addresses will be retried on the next run.
+                log::warn!("Census batch geocoding failed (transient, not cached): {e}");
+                state
+                    .inconclusive
+                    .extend(chunk.iter().map(|&(_, key, _, _)| key.to_string()));
+                continue;
+            }
             Err(e) => {
                 log::error!("Census batch geocoding failed: {e}");
+                state
+                    .inconclusive
+                    .extend(chunk.iter().map(|&(_, key, _, _)| key.to_string()));
             }
         }
 
         // Cache misses for Census
-        for &(_, address_key, _) in chunk {
+        for &(_, address_key, _, _) in chunk {
             if !matched_keys.contains(address_key) {
                 state.cache_writes.push((
                     address_key.to_string(),
@@ -1124,6 +2099,9 @@ async fn resolve_via_census(
                 ));
             }
         }
+
+        // Batches can take minutes; keep each one's answers durable.
+        state.flush_cache();
     }
 
     Ok(())
@@ -1137,7 +2115,7 @@ async fn resolve_via_pelias(
     country_code: &str,
     concurrent_requests: usize,
     unresolved: &[AddressGroup<'_>],
-    state: &mut ResolveState,
+    state: &mut ResolveState<'_>,
     progress: Option<&Arc<dyn ProgressCallback>>,
     cf_access: Option<&crime_map_geocoder::pelias::CfAccessCredentials>,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -1151,9 +2129,9 @@ async fn resolve_via_pelias(
     let cf_access_owned = cf_access.cloned();
 
     // Fire concurrent requests via buffered stream
-    let results: Vec<_> = stream::iter(unresolved.iter().map(|(address_key, _, ids)| {
+    let results: Vec<_> = stream::iter(unresolved.iter().map(|&(ref address_key, group, ids)| {
         let key = address_key.clone();
-        let ids_clone = (*ids).clone();
+        let ids_clone = ids.clone();
         let creds = cf_access_owned.clone();
         async move {
             let result = crime_map_geocoder::pelias::geocode_freeform(
@@ -1164,15 +2142,17 @@ async fn resolve_via_pelias(
                 creds.as_ref(),
             )
             .await;
-            (key, ids_clone, result)
+            (key, group, ids_clone, result)
         }
     }))
     .buffer_unordered(concurrent_requests)
     .collect()
     .await;
 
-    for (address_key, ids, result) in results {
+    for (address_key, group, ids, result) in results {
         match result {
+            Ok(Some(geocoded)) if !state.accept_match(&address_key, group, "pelias", &geocoded) => {
+            }
             Ok(Some(geocoded)) => {
                 state.resolved_keys.insert(address_key.clone());
                 state.cache_writes.push((
@@ -1196,8 +2176,10 @@ async fn resolve_via_pelias(
             }
             Err(e) => {
                 log::warn!("Pelias error for '{address_key}': {e}");
+                state.inconclusive.insert(address_key);
             }
         }
+        state.flush_cache_if_full();
 
         if let Some(p) = progress {
             p.inc(ids.len() as u64);
@@ -1210,7 +2192,7 @@ async fn resolve_via_pelias(
 /// Resolves addresses via the local Tantivy geocoder index.
 async fn resolve_via_tantivy(
     unresolved: &[AddressGroup<'_>],
-    state: &mut ResolveState,
+    state: &mut ResolveState<'_>,
     progress: Option<&Arc<dyn ProgressCallback>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     use futures::stream::{self, StreamExt as _};
@@ -1227,21 +2209,22 @@ async fn resolve_via_tantivy(
     // and CPU-bound, so we let tokio's blocking pool manage threads.
     let concurrent_requests = 50;
 
-    let results: Vec<_> = stream::iter(unresolved.iter().map(|(address_key, _, ids)| {
+    let results: Vec<_> = stream::iter(unresolved.iter().map(|&(ref address_key, group, ids)| {
         let key = address_key.clone();
-        let ids_clone = (*ids).clone();
+        let ids_clone = ids.clone();
         let geocoder = geocoder.clone();
         async move {
             let result = crime_map_geocoder::tantivy_index::geocode_freeform(&geocoder, &key).await;
-            (key, ids_clone, result)
+            (key, group, ids_clone, result)
         }
     }))
     .buffer_unordered(concurrent_requests)
     .collect()
     .await;
 
-    for (address_key, ids, result) in results {
+    for (address_key, group, ids, result) in results {
         match result {
+            Ok(Some(hit)) if !state.accept_match(&address_key, group, "tantivy", &hit) => {}
             Ok(Some(hit)) => {
                 state.resolved_keys.insert(address_key.clone());
                 state.cache_writes.push((
@@ -1265,8 +2248,10 @@ async fn resolve_via_tantivy(
             }
             Err(e) => {
                 log::warn!("Tantivy error for '{address_key}': {e}");
+                state.inconclusive.insert(address_key);
             }
         }
+        state.flush_cache_if_full();
 
         if let Some(p) = progress {
             p.inc(ids.len() as u64);
@@ -1282,7 +2267,7 @@ async fn resolve_via_nominatim(
     base_url: &str,
     rate_limit_ms: u64,
     unresolved: &[AddressGroup<'_>],
-    state: &mut ResolveState,
+    state: &mut ResolveState<'_>,
     progress: Option<&Arc<dyn ProgressCallback>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     log::info!(
@@ -1290,10 +2275,16 @@ async fn resolve_via_nominatim(
         unresolved.len()
     );
 
-    for (address_key, _, ids) in unresolved {
+    for (i, (address_key, group, ids)) in unresolved.iter().enumerate() {
+        if i > 0 && i % NOMINATIM_FLUSH_EVERY == 0 {
+            state.flush_cache();
+        }
+
         tokio::time::sleep(std::time::Duration::from_millis(rate_limit_ms)).await;
 
         match crime_map_geocoder::nominatim::geocode_freeform(client, base_url, address_key).await {
+            Ok(Some(geocoded))
+                if !state.accept_match(address_key, group, "nominatim", &geocoded) => {}
             Ok(Some(geocoded)) => {
                 state.resolved_keys.insert(address_key.clone());
                 state.cache_writes.push((
@@ -1322,6 +2313,7 @@ async fn resolve_via_nominatim(
             }
             Err(e) => {
                 log::warn!("Nominatim error for '{address_key}': {e}");
+                state.inconclusive.insert(address_key.clone());
                 if matches!(e, crime_map_geocoder::GeocodeError::RateLimited) {
                     log::warn!("Rate limited by Nominatim, waiting 60s...");
                     tokio::time::sleep(std::time::Duration::from_secs(60)).await;
@@ -1339,19 +2331,96 @@ async fn resolve_via_nominatim(
     Ok(())
 }
 
+/// Cleans a raw block address into the street string used for geocoding
+/// and cache keys, or `None` if the address is not geocodable.
+fn geocodable_street(block: &str) -> Option<String> {
+    use crime_map_geocoder::address::{CleanedAddress, clean_block_address};
+
+    match clean_block_address(block) {
+        CleanedAddress::Street(s) => Some(s),
+        CleanedAddress::Intersection { street1, street2 } => {
+            Some(format!("{street1} and {street2}"))
+        }
+        CleanedAddress::NotGeocodable => None,
+    }
+}
+
+/// Collects every geocode cache key still referenced by an incident in
+/// the given sources (or all sources with local `DuckDB` files if
+/// `source_ids` is empty).
+///
+/// Scans the distinct `(block_address, city, state)` tuples of each
+/// source, so this is expensive on large datasets. Used by
+/// [`geocode_cache::prune`] to drop entries for addresses that no longer
+/// appear anywhere.
+///
+/// # Errors
+///
+/// Returns an error if a source database cannot be opened or queried.
+pub fn live_address_keys(
+    source_ids: &[String],
+) -> Result<std::collections::BTreeSet<String>, Box<dyn std::error::Error>> {
+    use crime_map_geocoder::address::build_one_line_address;
+
+    let ids = if source_ids.is_empty() {
+        source_db::discover_source_ids()
+    } else {
+        source_ids.to_vec()
+    };
+
+    let mut keys = std::collections::BTreeSet::new();
+    for sid in &ids {
+        let before = keys.len();
+        for path in source_db::shard_paths(sid) {
+            let Some(conn) = source_db::open_existing(&path)? else {
+                log::warn!("Skipping {}: no incidents table", path.display());
+                continue;
+            };
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT block_address, COALESCE(city, ''), COALESCE(state, '')
+                 FROM incidents
+                 WHERE block_address IS NOT NULL AND block_address != ''",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let block: String = row.get(0)?;
+                let city: String = row.get(1)?;
+                let state: String = row.get(2)?;
+                if let Some(street) = geocodable_street(&block) {
+                    keys.insert(build_one_line_address(&street, &city, &state));
+                }
+            }
+        }
+        log::info!("{sid}: {} live address keys", keys.len() - before);
+    }
+
+    Ok(keys)
+}
+
+/// Returns the SQL condition (with a leading `AND`) selecting incidents
+/// that occurred on or after `since`, or an empty string for `None`.
+fn since_condition(since: Option<chrono::NaiveDate>) -> String {
+    since.map_or_else(String::new, |date| {
+        format!("AND occurred_at >= DATE '{date}'")
+    })
+}
+
 /// Geocodes incidents that have block addresses but no coordinates.
 ///
 /// Fetches un-geocoded incidents from the per-source `DuckDB` in batches,
-/// deduplicates by address, resolves through the geocoding pipeline
+/// deduplicates by address (and, with a `memo`, against addresses resolved
+/// in earlier batches), resolves through the geocoding pipeline
 /// (cache → Census → Nominatim), then updates the incidents with the
 /// resolved coordinates. Loops until all eligible incidents have been
-/// processed.
+/// processed. With `since`, only incidents that occurred on or after that
+/// date are eligible.
 ///
 /// # Errors
 ///
 /// Returns an error if database queries, geocoding, or batch updates fail.
 #[allow(
     clippy::too_many_lines,
+    clippy::too_many_arguments,
     clippy::needless_pass_by_value,
     clippy::future_not_send
 )]
@@ -1360,50 +2429,56 @@ pub async fn geocode_missing(
     cache_conn: &Connection,
     batch_size: u64,
     limit: Option<u64>,
-    nominatim_only: bool,
+    since: Option<chrono::NaiveDate>,
+    services: &[GeocodingService],
+    validation: MatchValidation,
+    cache_flush_every: usize,
+    mut memo: Option<&mut AddressMemo>,
     progress: Option<Arc<dyn ProgressCallback>>,
 ) -> Result<u64, Box<dyn std::error::Error>> {
-    use crime_map_geocoder::address::{CleanedAddress, clean_block_address};
     use std::collections::BTreeMap;
 
+    let since = since_condition(since);
+
     // Query total un-geocoded count for progress reporting
     if let Some(ref p) = progress {
-        let mut stmt = source_conn.prepare(
+        let mut stmt = source_conn.prepare(&format!(
             "SELECT COUNT(*) FROM incidents
              WHERE has_coordinates = FALSE
                AND block_address IS NOT NULL
                AND block_address != ''
-               AND geocoded = FALSE",
-        )?;
+               AND geocoded = FALSE
+               {since}"
+        ))?;
         let count: i64 = stmt.query_row([], |row| row.get(0))?;
         #[allow(clippy::cast_sign_loss)]
         p.set_total(count as u64);
     }
 
-    let client = reqwest::Client::builder()
-        .user_agent("crime-map/1.0 (https://github.com/BSteffaniak/crime-map)")
-        .build()?;
+    let client = build_http_client()?;
 
     let mut grand_total = 0u64;
     let mut batch_num = 0u64;
 
     loop {
         batch_num += 1;
+        let batch_start = Instant::now();
 
         let effective_size = limit.map_or(batch_size, |l| batch_size.min(l - grand_total));
         if effective_size == 0 {
             break;
         }
 
-        let mut stmt = source_conn.prepare(
+        let mut stmt = source_conn.prepare(&format!(
             "SELECT source_incident_id, block_address, city, state
              FROM incidents
              WHERE has_coordinates = FALSE
                AND block_address IS NOT NULL
                AND block_address != ''
                AND geocoded = FALSE
-             LIMIT ?",
-        )?;
+               {since}
+             LIMIT ?"
+        ))?;
 
         let rows: Vec<(String, String, String, String)> = {
             let effective_i64 = i64::try_from(effective_size).unwrap_or(i64::MAX);
@@ -1436,16 +2511,9 @@ pub async fn geocode_missing(
         let mut skipped_count = 0u64;
 
         for (incident_id, block, city, state) in &rows {
-            let cleaned = clean_block_address(block);
-            let street = match cleaned {
-                CleanedAddress::Street(s) => s,
-                CleanedAddress::Intersection { street1, street2 } => {
-                    format!("{street1} and {street2}")
-                }
-                CleanedAddress::NotGeocodable => {
-                    skipped_count += 1;
-                    continue;
-                }
+            let Some(street) = geocodable_street(block) else {
+                skipped_count += 1;
+                continue;
             };
 
             addr_groups
@@ -1467,8 +2535,17 @@ pub async fn geocode_missing(
             rows.len()
         );
 
-        let (pending_updates, all_ids) =
-            resolve_addresses(cache_conn, &client, &addr_groups, nominatim_only, &progress).await?;
+        let (pending_updates, all_ids) = resolve_addresses(
+            cache_conn,
+            &client,
+            &addr_groups,
+            services,
+            validation,
+            cache_flush_every,
+            memo.as_deref_mut(),
+            &progress,
+        )
+        .await?;
 
         let mut batch_geocoded = 0u64;
 
@@ -1499,6 +2576,12 @@ pub async fn geocode_missing(
 
         grand_total += batch_geocoded;
         log::info!(
+            event = "geocode_batch",
+            batch = batch_num,
+            incidents = rows.len(),
+            records = batch_geocoded,
+            cumulative = grand_total,
+            elapsed_ms = batch_start.elapsed().as_millis();
             "Batch {batch_num} complete: {batch_geocoded} geocoded this batch, {grand_total} cumulative"
         );
 
@@ -1519,13 +2602,15 @@ pub async fn geocode_missing(
 ///
 /// Only re-geocodes incidents that have `geocoded = FALSE` (i.e., coordinates
 /// came from the source, not from a previous geocoding run). Processes all
-/// eligible incidents in batches.
+/// eligible incidents in batches, limited to those that occurred on or
+/// after `since` if set.
 ///
 /// # Errors
 ///
 /// Returns an error if database queries, geocoding, or batch updates fail.
 #[allow(
     clippy::too_many_lines,
+    clippy::too_many_arguments,
     clippy::needless_pass_by_value,
     clippy::future_not_send
 )]
@@ -1534,50 +2619,56 @@ pub async fn re_geocode_source(
     cache_conn: &Connection,
     batch_size: u64,
     limit: Option<u64>,
-    nominatim_only: bool,
+    since: Option<chrono::NaiveDate>,
+    services: &[GeocodingService],
+    validation: MatchValidation,
+    cache_flush_every: usize,
+    mut memo: Option<&mut AddressMemo>,
     progress: Option<Arc<dyn ProgressCallback>>,
 ) -> Result<u64, Box<dyn std::error::Error>> {
-    use crime_map_geocoder::address::{CleanedAddress, clean_block_address};
     use std::collections::BTreeMap;
 
+    let since = since_condition(since);
+
     // Query total eligible count for progress reporting
     if let Some(ref p) = progress {
-        let mut stmt = source_conn.prepare(
+        let mut stmt = source_conn.prepare(&format!(
             "SELECT COUNT(*) FROM incidents
              WHERE has_coordinates = TRUE
                AND geocoded = FALSE
                AND block_address IS NOT NULL
-               AND block_address != ''",
-        )?;
+               AND block_address != ''
+               {since}"
+        ))?;
         let count: i64 = stmt.query_row([], |row| row.get(0))?;
         #[allow(clippy::cast_sign_loss)]
         p.set_total(count as u64);
     }
 
-    let client = reqwest::Client::builder()
-        .user_agent("crime-map/1.0 (https://github.com/BSteffaniak/crime-map)")
-        .build()?;
+    let client = build_http_client()?;
 
     let mut grand_total = 0u64;
     let mut batch_num = 0u64;
 
     loop {
         batch_num += 1;
+        let batch_start = Instant::now();
 
         let effective_size = limit.map_or(batch_size, |l| batch_size.min(l - grand_total));
         if effective_size == 0 {
             break;
         }
 
-        let mut stmt = source_conn.prepare(
+        let mut stmt = source_conn.prepare(&format!(
             "SELECT source_incident_id, block_address, city, state
              FROM incidents
              WHERE has_coordinates = TRUE
                AND geocoded = FALSE
                AND block_address IS NOT NULL
                AND block_address != ''
-             LIMIT ?",
-        )?;
+               {since}
+             LIMIT ?"
+        ))?;
 
         let rows: Vec<(String, String, String, String)> = {
             let effective_i64 = i64::try_from(effective_size).unwrap_or(i64::MAX);
@@ -1610,16 +2701,9 @@ pub async fn re_geocode_source(
         let mut skipped_count = 0u64;
 
         for (incident_id, block, city, state) in &rows {
-            let cleaned = clean_block_address(block);
-            let street = match cleaned {
-                CleanedAddress::Street(s) => s,
-                CleanedAddress::Intersection { street1, street2 } => {
-                    format!("{street1} and {street2}")
-                }
-                CleanedAddress::NotGeocodable => {
-                    skipped_count += 1;
-                    continue;
-                }
+            let Some(street) = geocodable_street(block) else {
+                skipped_count += 1;
+                continue;
             };
 
             addr_groups
@@ -1641,8 +2725,17 @@ pub async fn re_geocode_source(
             rows.len()
         );
 
-        let (pending_updates, all_ids) =
-            resolve_addresses(cache_conn, &client, &addr_groups, nominatim_only, &progress).await?;
+        let (pending_updates, all_ids) = resolve_addresses(
+            cache_conn,
+            &client,
+            &addr_groups,
+            services,
+            validation,
+            cache_flush_every,
+            memo.as_deref_mut(),
+            &progress,
+        )
+        .await?;
 
         let mut batch_geocoded = 0u64;
 
@@ -1672,6 +2765,12 @@ pub async fn re_geocode_source(
 
         grand_total += batch_geocoded;
         log::info!(
+            event = "geocode_batch",
+            batch = batch_num,
+            incidents = rows.len(),
+            records = batch_geocoded,
+            cumulative = grand_total,
+            elapsed_ms = batch_start.elapsed().as_millis();
             "Batch {batch_num} complete: {batch_geocoded} geocoded this batch, {grand_total} cumulative"
         );
 
@@ -1683,3 +2782,26 @@ pub async fn re_geocode_source(
 
     Ok(grand_total)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_memo_remembers_only_definitive_outcomes() {
+        let mut memo = AddressMemo::default();
+        memo.record("1 MAIN ST, SPRINGFIELD, IL", Some((-89.6, 39.8)), false);
+        memo.record("2 MAIN ST, SPRINGFIELD, IL", None, true);
+        memo.record("3 MAIN ST, SPRINGFIELD, IL", None, false);
+
+        assert_eq!(memo.len(), 2);
+        assert_eq!(
+            memo.get("1 MAIN ST, SPRINGFIELD, IL"),
+            Some(&Some((-89.6, 39.8)))
+        );
+        assert_eq!(memo.get("2 MAIN ST, SPRINGFIELD, IL"), Some(&None));
+        assert_eq!(memo.get("3 MAIN ST, SPRINGFIELD, IL"), None);
+        assert_eq!(memo.get("4 MAIN ST, SPRINGFIELD, IL"), None);
+        assert_eq!(memo.hits(), 2);
+    }
+}
@@ -7,10 +7,11 @@
 use std::time::Instant;
 
 use clap::{Parser, Subcommand};
-use crime_map_cli_utils::IndicatifProgress;
+use crime_map_cli_utils::{IndicatifProgress, throttled};
 use crime_map_database::source_db;
 use crime_map_ingest::{
-    EnrichArgs, GeocodeArgs, SyncArgs, all_sources, enabled_sources, sync_source,
+    DEFAULT_CACHE_FLUSH_EVERY, EnrichArgs, GeocodeArgs, MatchValidation, SyncArgs, all_sources,
+    dry_run_normalize, enabled_sources, sync_source,
 };
 use crime_map_source::source_def::SourceDefinition;
 
@@ -43,6 +44,28 @@ enum Commands {
         /// Force a full sync, ignoring any previously synced data
         #[arg(long)]
         force: bool,
+        /// Also store the raw source JSON of this fraction of records
+        /// (e.g. 0.01, or 1 for all) for debugging. Large; never use for
+        /// public builds
+        #[arg(long, value_name = "FRACTION")]
+        keep_raw: Option<f64>,
+    },
+    /// Re-sync only the sources that failed their last sync
+    SyncRetry {
+        /// Maximum number of records per source (for testing)
+        #[arg(long)]
+        limit: Option<u64>,
+        /// Comma-separated list of failed source IDs to retry (default: all of them)
+        #[arg(long)]
+        sources: Option<String>,
+        /// Force a full sync, ignoring any previously synced data
+        #[arg(long)]
+        force: bool,
+        /// Also store the raw source JSON of this fraction of records
+        /// (e.g. 0.01, or 1 for all) for debugging. Large; never use for
+        /// public builds
+        #[arg(long, value_name = "FRACTION")]
+        keep_raw: Option<f64>,
     },
     /// Sync data from a specific source
     Sync {
@@ -54,6 +77,20 @@ enum Commands {
         /// Force a full sync, ignoring any previously synced data
         #[arg(long)]
         force: bool,
+        /// Also store the raw source JSON of this fraction of records
+        /// (e.g. 0.01, or 1 for all) for debugging. Large; never use for
+        /// public builds
+        #[arg(long, value_name = "FRACTION")]
+        keep_raw: Option<f64>,
+    },
+    /// Fetch a sample from a source and report category mapping coverage
+    /// without writing anything
+    DryRun {
+        /// Source identifier (e.g., "`chicago_pd`")
+        source: String,
+        /// Number of records to sample
+        #[arg(long, default_value_t = 1000)]
+        sample: u64,
     },
     /// List all configured data sources
     Sources,
@@ -112,12 +149,23 @@ enum Commands {
         /// eligible incidents are processed.
         #[arg(long)]
         limit: Option<u64>,
+        /// Only geocode incidents that occurred on or after this date
+        /// (`YYYY-MM-DD`), so recently synced records are placed on the map
+        /// first. Later runs without it work through the older backlog.
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
         /// Number of incidents to fetch per batch (default: 50,000).
         #[arg(long, default_value = "50000")]
         batch_size: u64,
         /// Skip Census Bureau batch geocoder and only use Nominatim.
-        #[arg(long)]
+        /// Shorthand for `--providers nominatim`.
+        #[arg(long, conflicts_with = "providers")]
         nominatim_only: bool,
+        /// Comma-separated geocoding provider IDs to use, in order (e.g.,
+        /// `"pelias,census"`). Providers not listed are skipped. Defaults
+        /// to every enabled provider in registry priority order.
+        #[arg(long, value_delimiter = ',')]
+        providers: Vec<String>,
         /// Comma-separated source IDs to geocode (TOML ids, e.g.,
         /// `"pg_county_md,dc_mpd"`). If not specified, geocodes all
         /// eligible incidents.
@@ -128,6 +176,37 @@ enum Commands {
         /// current batch. Progress is preserved in the `DuckDB` files.
         #[arg(long)]
         max_time: Option<u64>,
+        /// Reject matches whose matched address disagrees with the
+        /// incident's `state` or `city` (`off`, `state`, or `city`).
+        /// Rejections are cached as low confidence and the address falls
+        /// through to the next provider.
+        #[arg(long, default_value = "off")]
+        match_validation: MatchValidation,
+        /// Remember addresses resolved earlier in the run so repeats in
+        /// later batches or sources skip the cache and providers. Uses
+        /// memory proportional to the number of distinct addresses.
+        #[arg(long)]
+        dedup_addresses: bool,
+        /// Write buffered geocode results to the cache every this many
+        /// results, bounding memory on large runs.
+        #[arg(long, default_value_t = DEFAULT_CACHE_FLUSH_EVERY)]
+        cache_flush_every: usize,
+    },
+    /// Prune stale or unreferenced entries from the shared geocode cache.
+    ///
+    /// Each policy flag is applied independently; at least one is required.
+    PruneCache {
+        /// Drop cached misses (no coordinates) older than this many days.
+        #[arg(long)]
+        misses_older_than_days: Option<u32>,
+        /// Drop all entries from this provider (repeatable, e.g.
+        /// `--provider pelias --provider nominatim`).
+        #[arg(long)]
+        provider: Vec<String>,
+        /// Drop entries whose address no longer appears in any local
+        /// source `DuckDB` (scans every source; slow).
+        #[arg(long)]
+        unreferenced: bool,
     },
     /// Compare geocoding results between Tantivy and other providers.
     ///
@@ -155,6 +234,20 @@ enum Commands {
         #[arg(long)]
         force: bool,
     },
+    /// Report coordinate quality per source: the fraction of incidents
+    /// with coordinates, geocoded vs source-provided, enriched, and the
+    /// number of coordinates outside the US bounding box, plus why each
+    /// source's last sync failed, if it did.
+    QualityReport {
+        /// Comma-separated source IDs to report on. If not specified,
+        /// reports on all sources with local `DuckDB` files.
+        #[arg(long)]
+        sources: Option<String>,
+        /// Also write the report as JSON to this path (for tracking
+        /// quality regressions in CI).
+        #[arg(long)]
+        json: Option<String>,
+    },
     /// Pull `DuckDB` files from Cloudflare R2 to the local `data/` directory
     Pull {
         /// Comma-separated source IDs to pull (if not specified, pulls all
@@ -168,7 +261,14 @@ enum Commands {
         /// Only pull per-source files, skip shared databases.
         #[arg(long)]
         sources_only: bool,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
     },
+    /// Check that the shared databases (boundaries, geocode cache) open
+    /// and read back intact, and fail if either is broken
+    VerifyShared,
     /// Push local `DuckDB` files to Cloudflare R2
     Push {
         /// Comma-separated source IDs to push (if not specified, pushes all
@@ -182,6 +282,10 @@ enum Commands {
         /// Only push per-source files, skip shared databases.
         #[arg(long)]
         sources_only: bool,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
     },
     /// Pull a cached boundary partition from R2 into the local
     /// `boundaries.duckdb` (used by CI boundary ingestion jobs to reuse
@@ -279,8 +383,8 @@ enum Commands {
     /// Push generated partition outputs to R2 under `generated/partitions/{name}/`.
     ///
     /// Uploads `incidents.pmtiles`, `incidents.db`, `counts.duckdb`, `h3.duckdb`,
-    /// `analytics.duckdb`, `metadata.json`, and `manifest.json` from the given
-    /// directory to R2.
+    /// `analytics.duckdb`, `metadata.json`, and `manifest.json` from the
+    /// given directory to R2, plus any opt-in outputs present.
     PushGeneratedPartition {
         /// Partition name (e.g. "`chicago_pd`").
         #[arg(long)]
@@ -288,6 +392,10 @@ enum Commands {
         /// Local directory containing the generated partition files.
         #[arg(long)]
         dir: String,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
     },
     /// Pull generated partition outputs from R2 `generated/partitions/{name}/`.
     PullGeneratedPartition {
@@ -297,33 +405,77 @@ enum Commands {
         /// Local directory to write files to.
         #[arg(long)]
         dir: String,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
+        /// After pulling, compare the local `manifest.json` with the one on
+        /// R2 and report any drift. With `--dry-run`, compares without
+        /// pulling.
+        #[arg(long)]
+        compare_remote: bool,
     },
     /// Push boundary outputs to R2 under `generated/boundaries/`.
     PushGeneratedBoundaries {
         /// Local directory containing `boundaries.pmtiles` and `boundaries.db`.
         #[arg(long)]
         dir: String,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
     },
     /// Pull boundary outputs from R2 `generated/boundaries/`.
     PullGeneratedBoundaries {
         /// Local directory to write files to.
         #[arg(long)]
         dir: String,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
     },
     /// Push final merged outputs to R2 under `generated/merged/`.
     PushGeneratedMerged {
         /// Local directory containing the merged generated files.
         #[arg(long)]
         dir: String,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
     },
     /// Pull final merged outputs from R2 `generated/merged/`.
     PullGeneratedMerged {
         /// Local directory to write files to.
         #[arg(long)]
         dir: String,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
+        /// After pulling, compare the local `manifest.json` with the one on
+        /// R2 and report any drift. With `--dry-run`, compares without
+        /// pulling.
+        #[arg(long)]
+        compare_remote: bool,
     },
-    /// List all partition names that have generated outputs on R2.
+    /// List all partitions that have generated outputs on R2, with their
+    /// object count and last-modified time.
     ListGeneratedPartitions,
+    /// Delete generated partitions from R2 (`generated/partitions/{name}/`).
+    DeleteGeneratedPartitions {
+        /// Partition name(s) to delete. Repeatable.
+        #[arg(long)]
+        name: Vec<String>,
+        /// Delete every partition whose newest object is older than this
+        /// many days.
+        #[arg(long)]
+        older_than_days: Option<u64>,
+        /// Print what would be deleted without deleting anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Push `incidents.pmtiles` to the `crime-map-tiles` CDN bucket on R2.
     ///
     /// Uses multipart upload for large files and smart sync to skip
@@ -332,12 +484,20 @@ enum Commands {
         /// Local directory containing `incidents.pmtiles`.
         #[arg(long)]
         dir: String,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
     },
     /// Pull `incidents.pmtiles` from the `crime-map-tiles` CDN bucket on R2.
     PullTiles {
         /// Local directory to write `incidents.pmtiles` to.
         #[arg(long)]
         dir: String,
+        /// Only report which files would be transferred, skipped, or not
+        /// found, without transferring anything.
+        #[arg(long, visible_alias = "plan")]
+        dry_run: bool,
     },
 }
 
@@ -397,6 +557,70 @@ fn parse_source_csv(csv: Option<&str>) -> Vec<String> {
     .unwrap_or_default()
 }
 
+/// Verifies the shared databases, failing with the broken ones so the
+/// operator re-pulls them instead of re-geocoding everything.
+fn verify_shared() -> Result<(), Box<dyn std::error::Error>> {
+    let broken: Vec<String> = crime_map_ingest::verify_shared_databases()
+        .into_iter()
+        .filter_map(|health| {
+            health
+                .problem
+                .map(|problem| format!("{}: {problem}", health.path.display()))
+        })
+        .collect();
+    if broken.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "{} shared database(s) failed verification; re-pull them with \
+         `cargo ingest pull --shared-only`:\n  {}",
+        broken.len(),
+        broken.join("\n  ")
+    )
+    .into())
+}
+
+/// Compares `dir`'s `manifest.json` with the one under the generated
+/// output `prefix` on R2, logging each drifting field. Missing manifests
+/// on either side are reported rather than treated as errors.
+async fn compare_remote_manifest(
+    r2: &crime_map_r2::R2Client,
+    prefix: &str,
+    dir: &std::path::Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(remote) = r2.fetch_manifest(prefix).await? else {
+        log::warn!("No manifest.json on R2 under {prefix}; nothing to compare");
+        return Ok(());
+    };
+
+    let local_path = dir.join(crime_map_database::paths::MANIFEST_FILE);
+    let local: serde_json::Value = match std::fs::read_to_string(&local_path) {
+        Ok(contents) => serde_json::from_str(&contents)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            log::warn!(
+                "No local {} to compare with R2 {prefix}",
+                local_path.display()
+            );
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let drift = crime_map_r2::diff_manifests(&local, &remote);
+    if drift.is_empty() {
+        log::info!("Local manifest.json matches R2 {prefix}");
+        return Ok(());
+    }
+    log::warn!(
+        "Local manifest.json differs from R2 {prefix} in {} field(s):",
+        drift.len()
+    );
+    for d in &drift {
+        log::warn!("  {d}");
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_lines)]
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -420,6 +644,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             source,
             limit,
             force,
+            keep_raw,
         } => {
             let sources = all_sources();
             let src = sources
@@ -428,16 +653,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .ok_or_else(|| format!("Unknown source: {source}"))?;
 
             let conn = source_db::open_by_id(src.id())?;
-            let fetch_bar = IndicatifProgress::records_bar(&multi, src.name());
-            let result = sync_source(&conn, src, limit, force, Some(fetch_bar.clone())).await;
+            let fetch_bar = throttled(IndicatifProgress::records_bar(&multi, src.name()));
+            let result =
+                sync_source(&conn, src, limit, force, keep_raw, Some(fetch_bar.clone())).await;
             fetch_bar.finish_and_clear();
             result?;
         }
+        Commands::DryRun { source, sample } => {
+            let sources = all_sources();
+            let src = sources
+                .iter()
+                .find(|s| s.id() == source)
+                .ok_or_else(|| format!("Unknown source: {source}"))?;
+
+            let report = dry_run_normalize(src, sample).await?;
+            println!(
+                "{}: normalized {}/{} raw records, {} unknown ({:.1}% mapped)",
+                src.name(),
+                report.normalized,
+                report.total_raw,
+                report.unknown,
+                report.mapped_ratio() * 100.0,
+            );
+            for (subcategory, count) in &report.subcategories {
+                println!("  {:<28} {count}", subcategory.as_ref());
+            }
+        }
         Commands::SyncAll {
             limit,
             sources,
             states,
             force,
+            keep_raw,
         } => {
             let source_ids: Vec<String> = if states.is_some() || sources.is_some() {
                 resolve_source_filter(sources.as_deref(), states.as_deref())
@@ -452,12 +699,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             };
 
             let num_sources = source_ids.len();
-            let source_bar = IndicatifProgress::steps_bar(&multi, "Sources", num_sources as u64);
+            let source_bar = throttled(IndicatifProgress::steps_bar(
+                &multi,
+                "Sources",
+                num_sources as u64,
+            ));
 
             let args = SyncArgs {
                 source_ids,
                 limit,
                 force,
+                keep_raw,
             };
 
             let result = crime_map_ingest::run_sync(&args, Some(&source_bar)).await;
@@ -472,6 +724,44 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .into());
             }
         }
+        Commands::SyncRetry {
+            limit,
+            sources,
+            force,
+            keep_raw,
+        } => {
+            let source_ids: Vec<String> = sources
+                .map(|s| s.split(',').map(|id| id.trim().to_string()).collect())
+                .unwrap_or_default();
+            let num_sources = crime_map_ingest::load_sync_failures()
+                .iter()
+                .filter(|f| source_ids.is_empty() || source_ids.contains(&f.source_id))
+                .count();
+            let source_bar = throttled(IndicatifProgress::steps_bar(
+                &multi,
+                "Sources",
+                num_sources as u64,
+            ));
+
+            let args = SyncArgs {
+                source_ids,
+                limit,
+                force,
+                keep_raw,
+            };
+
+            let result = crime_map_ingest::run_sync_retry(&args, Some(&source_bar)).await;
+            source_bar.finish(format!("Retried {num_sources} source(s)"));
+
+            if !result.failed.is_empty() {
+                return Err(format!(
+                    "{} source(s) failed to sync again: {}",
+                    result.failed.len(),
+                    result.failed.join(", ")
+                )
+                .into());
+            }
+        }
         Commands::Tracts { states, force } => {
             let boundaries_conn = crime_map_database::boundaries_db::open_default()?;
 
@@ -515,9 +805,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 sources_to_ingest.len()
             );
 
-            let client = reqwest::Client::builder()
-                .user_agent("crime-map/1.0")
-                .build()?;
+            let client = crime_map_ingest::build_http_client()?;
 
             let start = Instant::now();
             let mut total = 0u64;
@@ -633,19 +921,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         Commands::Geocode {
             limit,
+            since,
             batch_size,
             nominatim_only,
+            providers,
             sources,
             max_time,
+            match_validation,
+            dedup_addresses,
+            cache_flush_every,
         } => {
             let start = Instant::now();
-            let geocode_bar = IndicatifProgress::batch_bar(&multi, "Geocoding");
+            let geocode_bar = throttled(IndicatifProgress::batch_bar(&multi, "Geocoding"));
 
             let args = GeocodeArgs {
                 source_ids: parse_source_csv(sources.as_deref()),
                 batch_size,
                 limit,
-                nominatim_only,
+                since,
+                provider_order: if nominatim_only {
+                    vec!["nominatim".to_string()]
+                } else {
+                    providers
+                },
+                match_validation,
+                dedup_addresses,
+                cache_flush_every,
             };
 
             let geocode_future = crime_map_ingest::run_geocode(&args, Some(geocode_bar.clone()));
@@ -681,6 +982,47 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 elapsed.as_secs_f64()
             );
         }
+        Commands::PruneCache {
+            misses_older_than_days,
+            provider,
+            unreferenced,
+        } => {
+            if misses_older_than_days.is_none() && provider.is_empty() && !unreferenced {
+                return Err(
+                    "Specify at least one of --misses-older-than-days, --provider, \
+                            or --unreferenced"
+                        .into(),
+                );
+            }
+
+            let start = Instant::now();
+            let live_address_keys = if unreferenced {
+                log::info!("Scanning source databases for live address keys...");
+                Some(crime_map_ingest::live_address_keys(&[])?)
+            } else {
+                None
+            };
+
+            let policy = crime_map_database::geocode_cache::PrunePolicy {
+                misses_older_than_days,
+                providers: provider,
+                live_address_keys,
+            };
+
+            let cache_conn = crime_map_database::geocode_cache::open_default()?;
+            let stats = crime_map_database::geocode_cache::prune(&cache_conn, &policy)?;
+
+            let elapsed = start.elapsed();
+            log::info!(
+                "Geocode cache pruned: {} entries removed ({} stale misses, {} by provider, \
+                 {} unreferenced) in {:.1}s",
+                stats.total(),
+                stats.stale_misses,
+                stats.provider_entries,
+                stats.unreferenced,
+                elapsed.as_secs_f64()
+            );
+        }
         Commands::GeocoderCompare { limit } => {
             let start = Instant::now();
 
@@ -788,7 +1130,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         Commands::Enrich { sources, force } => {
             let start = Instant::now();
-            let enrich_bar = IndicatifProgress::batch_bar(&multi, "Enriching");
+            let enrich_bar = throttled(IndicatifProgress::batch_bar(&multi, "Enriching"));
 
             let args = EnrichArgs {
                 source_ids: parse_source_csv(sources.as_deref()),
@@ -806,12 +1148,50 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 elapsed.as_secs_f64()
             );
         }
+        Commands::QualityReport { sources, json } => {
+            let report = crime_map_ingest::quality_report(&parse_source_csv(sources.as_deref()))?;
+
+            println!(
+                "{:<30} {:>10} {:>8} {:>9} {:>9} {:>10}",
+                "SOURCE", "TOTAL", "COORDS", "GEOCODED", "ENRICHED", "OUTSIDE US"
+            );
+            println!("{}", "-".repeat(81));
+            for q in &report {
+                println!(
+                    "{:<30} {:>10} {:>7.1}% {:>8.1}% {:>8.1}% {:>10}",
+                    q.source_id,
+                    q.total,
+                    q.coordinates_ratio * 100.0,
+                    q.geocoded_ratio * 100.0,
+                    q.enriched_ratio * 100.0,
+                    q.outside_us
+                );
+            }
+            for q in &report {
+                if let (Some(kind), Some(error)) = (&q.last_error_kind, &q.last_error) {
+                    println!(
+                        "{}: last failed ({kind}) at {}: {error}",
+                        q.source_id,
+                        q.last_failed_at.as_deref().unwrap_or("unknown time")
+                    );
+                }
+            }
+
+            if let Some(path) = json {
+                std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+                log::info!(
+                    "Wrote quality report for {} source(s) to {path}",
+                    report.len()
+                );
+            }
+        }
         Commands::Pull {
             sources,
             shared_only,
             sources_only,
+            dry_run,
         } => {
-            let r2 = crime_map_r2::R2Client::from_env()?;
+            let r2 = crime_map_r2::R2Client::from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let mut stats = crime_map_r2::SyncStats::default();
 
@@ -825,14 +1205,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             let elapsed = start.elapsed();
-            log::info!("Pull complete: {stats} in {:.1}s", elapsed.as_secs_f64());
+            let outcome = if dry_run { "plan" } else { "complete" };
+            log::info!("Pull {outcome}: {stats} in {:.1}s", elapsed.as_secs_f64());
+
+            // A truncated download can still open, so check the shared
+            // databases before anything relies on them.
+            if !sources_only && !dry_run {
+                verify_shared()?;
+            }
         }
+        Commands::VerifyShared => verify_shared()?,
         Commands::Push {
             sources,
             shared_only,
             sources_only,
+            dry_run,
         } => {
-            let r2 = crime_map_r2::R2Client::from_env()?;
+            let r2 = crime_map_r2::R2Client::from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let mut stats = crime_map_r2::SyncStats::default();
 
@@ -846,7 +1235,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             let elapsed = start.elapsed();
-            log::info!("Push complete: {stats} in {:.1}s", elapsed.as_secs_f64());
+            let outcome = if dry_run { "plan" } else { "complete" };
+            log::info!("Push {outcome}: {stats} in {:.1}s", elapsed.as_secs_f64());
         }
         Commands::PullBoundaryPart { name } => {
             let r2 = crime_map_r2::R2Client::from_env()?;
@@ -1047,10 +1437,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let index_dir = crime_map_geocoder_index::default_index_dir();
             let archive_path = crime_map_geocoder_index::default_archive_path();
 
+            let unpacked_dir = index_dir.clone();
             tokio::task::spawn_blocking(move || {
-                crime_map_geocoder_index::archive::unpack(&archive_path, &index_dir)
+                crime_map_geocoder_index::archive::unpack(&archive_path, &unpacked_dir)
             })
             .await??;
+            crime_map_geocoder_index::version::check(&index_dir)?;
 
             let elapsed = start.elapsed();
             log::info!("Geocoder index unpacked in {:.1}s", elapsed.as_secs_f64());
@@ -1166,8 +1558,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
-        Commands::PushGeneratedPartition { name, dir } => {
-            let r2 = crime_map_r2::R2Client::from_env()?;
+        Commands::PushGeneratedPartition { name, dir, dry_run } => {
+            let r2 = crime_map_r2::R2Client::from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let dir = std::path::PathBuf::from(dir);
             let stats = r2.push_generated_partition(&name, &dir).await?;
@@ -1177,8 +1569,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 elapsed.as_secs_f64()
             );
         }
-        Commands::PullGeneratedPartition { name, dir } => {
-            let r2 = crime_map_r2::R2Client::from_env()?;
+        Commands::PullGeneratedPartition {
+            name,
+            dir,
+            dry_run,
+            compare_remote,
+        } => {
+            let r2 = crime_map_r2::R2Client::from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let dir = std::path::PathBuf::from(dir);
             let stats = r2.pull_generated_partition(&name, &dir).await?;
@@ -1187,9 +1584,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "Pull generated partition '{name}': {stats} in {:.1}s",
                 elapsed.as_secs_f64()
             );
+            if compare_remote {
+                let prefix = crime_map_r2::generated_partition_prefix(&name);
+                compare_remote_manifest(&r2, &prefix, &dir).await?;
+            }
         }
-        Commands::PushGeneratedBoundaries { dir } => {
-            let r2 = crime_map_r2::R2Client::from_env()?;
+        Commands::PushGeneratedBoundaries { dir, dry_run } => {
+            let r2 = crime_map_r2::R2Client::from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let dir = std::path::PathBuf::from(dir);
             let stats = r2.push_generated_boundaries(&dir).await?;
@@ -1199,8 +1600,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 elapsed.as_secs_f64()
             );
         }
-        Commands::PullGeneratedBoundaries { dir } => {
-            let r2 = crime_map_r2::R2Client::from_env()?;
+        Commands::PullGeneratedBoundaries { dir, dry_run } => {
+            let r2 = crime_map_r2::R2Client::from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let dir = std::path::PathBuf::from(dir);
             let stats = r2.pull_generated_boundaries(&dir).await?;
@@ -1210,8 +1611,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 elapsed.as_secs_f64()
             );
         }
-        Commands::PushGeneratedMerged { dir } => {
-            let r2 = crime_map_r2::R2Client::from_env()?;
+        Commands::PushGeneratedMerged { dir, dry_run } => {
+            let r2 = crime_map_r2::R2Client::from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let dir = std::path::PathBuf::from(dir);
             let stats = r2.push_generated_merged(&dir).await?;
@@ -1221,8 +1622,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 elapsed.as_secs_f64()
             );
         }
-        Commands::PullGeneratedMerged { dir } => {
-            let r2 = crime_map_r2::R2Client::from_env()?;
+        Commands::PullGeneratedMerged {
+            dir,
+            dry_run,
+            compare_remote,
+        } => {
+            let r2 = crime_map_r2::R2Client::from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let dir = std::path::PathBuf::from(dir);
             let stats = r2.pull_generated_merged(&dir).await?;
@@ -1231,29 +1636,82 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "Pull generated merged: {stats} in {:.1}s",
                 elapsed.as_secs_f64()
             );
+            if compare_remote {
+                let prefix = crime_map_r2::generated_merged_prefix();
+                compare_remote_manifest(&r2, prefix, &dir).await?;
+            }
         }
         Commands::ListGeneratedPartitions => {
             let r2 = crime_map_r2::R2Client::from_env()?;
-            let partitions = r2.list_generated_partitions().await?;
+            let partitions = r2.list_generated_partitions_detailed().await?;
             if partitions.is_empty() {
                 println!("No generated partitions found on R2.");
             } else {
                 println!("Generated partitions on R2 ({}):", partitions.len());
-                for name in &partitions {
-                    println!("  {name}");
+                for p in &partitions {
+                    let modified = p.last_modified.map_or_else(
+                        || "unknown".to_string(),
+                        |t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339(),
+                    );
+                    println!(
+                        "  {:<40} {:>3} objects  last modified {modified}",
+                        p.name, p.object_count
+                    );
+                }
+            }
+        }
+        Commands::DeleteGeneratedPartitions {
+            name,
+            older_than_days,
+            dry_run,
+        } => {
+            if name.is_empty() && older_than_days.is_none() {
+                return Err("Specify --name and/or --older-than-days".into());
+            }
+
+            let cutoff = older_than_days
+                .map(|days| {
+                    days.checked_mul(86_400)
+                        .and_then(|secs| {
+                            std::time::SystemTime::now()
+                                .checked_sub(std::time::Duration::from_secs(secs))
+                        })
+                        .ok_or_else(|| format!("--older-than-days {days} is out of range"))
+                })
+                .transpose()?;
+
+            let r2 = crime_map_r2::R2Client::from_env()?;
+            let mut targets: std::collections::BTreeSet<String> = name.into_iter().collect();
+            if let Some(cutoff) = cutoff {
+                for p in r2.list_generated_partitions_detailed().await? {
+                    if p.last_modified.is_some_and(|t| t < cutoff) {
+                        targets.insert(p.name);
+                    }
+                }
+            }
+
+            if targets.is_empty() {
+                println!("No partitions to delete.");
+            }
+            for target in &targets {
+                if dry_run {
+                    println!("Would delete partition '{target}'");
+                } else {
+                    let deleted = r2.delete_partition(target).await?;
+                    println!("Deleted partition '{target}' ({deleted} objects)");
                 }
             }
         }
-        Commands::PushTiles { dir } => {
-            let r2 = crime_map_r2::R2Client::tiles_from_env()?;
+        Commands::PushTiles { dir, dry_run } => {
+            let r2 = crime_map_r2::R2Client::tiles_from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let dir = std::path::PathBuf::from(dir);
             let stats = r2.push_tiles(&dir).await?;
             let elapsed = start.elapsed();
             log::info!("Push tiles: {stats} in {:.1}s", elapsed.as_secs_f64());
         }
-        Commands::PullTiles { dir } => {
-            let r2 = crime_map_r2::R2Client::tiles_from_env()?;
+        Commands::PullTiles { dir, dry_run } => {
+            let r2 = crime_map_r2::R2Client::tiles_from_env()?.with_dry_run(dry_run);
             let start = Instant::now();
             let dir = std::path::PathBuf::from(dir);
             let stats = r2.pull_tiles(&dir).await?;
@@ -14,6 +14,79 @@
 //! bandwidth on incremental pipeline runs where most files haven't changed.
 //! Pass `force = true` to bypass the check.
 //!
+//! `ETag`s are only MD5 digests for objects R2 stored without SSE using our
+//! own part size. Set `R2_VERIFY_SHA256=1` (or call
+//! [`R2Client::with_sha256_verification`]) for a stricter mode: uploads
+//! store the file's SHA-256 as `x-amz-meta-sha256`, and skip decisions
+//! compare against it whenever present, falling back to the `ETag` logic
+//! otherwise.
+//!
+//! ## Multipart uploads
+//!
+//! Files larger than the multipart threshold (default 500 MB) are uploaded
+//! in fixed-size parts (default 100 MB). Both are configurable through
+//! `R2_MULTIPART_THRESHOLD_MB` / `R2_MULTIPART_PART_SIZE_MB` or
+//! [`R2Client::with_multipart_threshold`] /
+//! [`R2Client::with_multipart_part_size`]: larger parts mean fewer requests
+//! on a fast link, smaller parts make retries cheaper on a flaky one.
+//!
+//! A multipart `ETag` depends on the part size, so smart sync recomputes it
+//! with the client's *current* part size. An object uploaded with a
+//! different part size either has a different part count (the check falls
+//! back to size-only comparison) or the same count but a different digest
+//! (the file is treated as changed and transferred again). Neither case
+//! skips a file that actually differs, but changing the part size can cause
+//! one round of redundant transfers for large files.
+//!
+//! ## Geocode cache deltas
+//!
+//! The geocode cache grows to many GB, and any new entry changes the
+//! whole file's hash. With `R2_GEOCODE_CACHE_DELTAS=1` (or
+//! [`R2Client::with_geocode_cache_deltas`]) pushes upload only the entries
+//! added since the last push, as a small timestamped Parquet file under
+//! `shared/geocode_cache_deltas/`, and pulls apply every delta newer than
+//! the last one the local cache has applied. The full DB is only
+//! downloaded when there is no local cache, and is re-uploaded as a
+//! compaction once [`GEOCODE_CACHE_COMPACT_AFTER`] deltas have accumulated
+//! since its last upload.
+//!
+//! ## Retries and circuit breaker
+//!
+//! Every request (heads, lists, deletes, single and multipart uploads, and
+//! downloads) is retried on S3 failures, up to 3 attempts with a delay
+//! starting at 2 s and doubling each time. Failures are also counted across
+//! operations: after 5 consecutive ones the client stops sending requests
+//! and fails fast with [`R2Error::CircuitOpen`] for a 60 s cooldown, after
+//! which a single request is let through to probe the endpoint. All of
+//! these are configurable with [`R2Client::with_max_attempts`],
+//! [`R2Client::with_retry_base_delay`], and
+//! [`R2Client::with_circuit_breaker`].
+//!
+//! ## Plans (dry runs)
+//!
+//! With [`R2Client::with_dry_run`], every push and pull runs the same
+//! smart-sync comparison but stops before transferring anything: each
+//! file is logged as `PLAN: would transfer` (with its size), `PLAN: skipped
+//! (unchanged)`, or `PLAN: not found`, and the returned [`SyncStats`] are marked
+//! [`SyncStats::planned`] so their summary reads as a plan rather than a
+//! completed sync. Geocode cache delta pushes export nothing in a plan;
+//! delta pulls list the deltas they would download without applying them.
+//!
+//! ## Build tagging
+//!
+//! Generated outputs are uploaded with `x-amz-meta-build` set to the
+//! `build.id` recorded in their directory's `metadata.json` (the crate
+//! version and git commit of the generator), so an object on R2 can be
+//! traced back to the code that produced it.
+//!
+//! ## Manifest drift
+//!
+//! [`R2Client::fetch_manifest`] reads the `manifest.json` of a generated
+//! output prefix without writing it to disk, and [`diff_manifests`]
+//! compares it with a local one, so a directory can be checked against the
+//! generation state the server was deployed from (e.g. outputs regenerated
+//! locally but never pushed, or artifacts pulled before a newer push).
+//!
 //! # Environment Variables
 //!
 //! | Variable | Required | Description |
@@ -21,16 +94,23 @@
 //! | `CLOUDFLARE_ACCOUNT_ID` | Yes | Cloudflare account ID (builds the R2 endpoint) |
 //! | `R2_ACCESS_KEY_ID` | Yes | S3-compatible access key for R2 |
 //! | `R2_SECRET_ACCESS_KEY` | Yes | S3-compatible secret key for R2 |
+//! | `R2_VERIFY_SHA256` | No | `1`/`true` to enable SHA-256 smart-sync checks |
+//! | `R2_MULTIPART_THRESHOLD_MB` | No | Upload files larger than this many MB in parts (default 500) |
+//! | `R2_MULTIPART_PART_SIZE_MB` | No | Multipart part size in MB (default 100, minimum 5) |
+//! | `R2_GEOCODE_CACHE_DELTAS` | No | `1`/`true` to sync the geocode cache as deltas |
 //!
 //! Alternatively, set `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` /
 //! `AWS_ENDPOINT_URL` directly (the AWS SDK reads these automatically).
 
 use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use aws_config::Region;
 use aws_sdk_s3::config::{Credentials, StalledStreamProtectionConfig};
 use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
-use crime_map_database::paths;
+use crime_map_database::paths::{self, BOUNDARY_FILES, GENERATED_FILES, OPTIONAL_GENERATED_FILES};
+use crime_map_database::{geocode_cache, source_db};
 use crime_map_source::registry;
 
 /// R2 bucket name for pipeline data.
@@ -39,19 +119,23 @@ const DATA_BUCKET: &str = "crime-map-data";
 /// R2 bucket name for public `PMTiles` served via CDN.
 const TILES_BUCKET: &str = "crime-map-tiles";
 
-/// Files produced per partition/merged output.
-const GENERATED_FILES: &[&str] = &[
-    "incidents.pmtiles",
-    "incidents.db",
-    "counts.duckdb",
-    "h3.duckdb",
-    "analytics.duckdb",
-    "metadata.json",
-    "manifest.json",
-];
+/// User metadata key holding the object's SHA-256 hex digest (sent as
+/// `x-amz-meta-sha256`).
+const SHA256_METADATA_KEY: &str = "sha256";
+
+/// User metadata key holding the generator build ID of a generated output
+/// (sent as `x-amz-meta-build`).
+const BUILD_METADATA_KEY: &str = "build";
+
+/// R2 key of the full geocode cache.
+const GEOCODE_CACHE_KEY: &str = "shared/geocode_cache.duckdb";
 
-/// Files produced by boundary generation.
-const BOUNDARY_FILES: &[&str] = &["boundaries.pmtiles", "boundaries.db"];
+/// R2 prefix of the geocode cache deltas (see the module docs).
+const GEOCODE_CACHE_DELTAS_PREFIX: &str = "shared/geocode_cache_deltas/";
+
+/// Number of deltas newer than the full geocode cache on R2 after which a
+/// delta push also re-uploads the full cache.
+pub const GEOCODE_CACHE_COMPACT_AFTER: usize = 50;
 
 /// Errors that can occur during R2 operations.
 #[derive(Debug, thiserror::Error)]
@@ -63,6 +147,17 @@ pub enum R2Error {
         name: String,
     },
 
+    /// An environment variable is set to an invalid value.
+    #[error("Invalid value for environment variable {name}={value:?}: {reason}")]
+    InvalidEnv {
+        /// Name of the environment variable.
+        name: String,
+        /// The rejected value.
+        value: String,
+        /// Why the value was rejected.
+        reason: String,
+    },
+
     /// S3 `GetObject` failed.
     #[error("Failed to download s3://{bucket}/{key}: {source}")]
     Download {
@@ -118,41 +213,122 @@ pub enum R2Error {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    /// Refused to push a directory of sampled (preview-only) outputs.
+    #[error(
+        "Refusing to push preview outputs from {}: generated with --sample \
+         (remove {} only if this is intentional)",
+        .dir.display(),
+        paths::PREVIEW_MARKER_FILE
+    )]
+    PreviewOutputs {
+        /// Directory containing the preview marker.
+        dir: std::path::PathBuf,
+    },
+
+    /// A source `DuckDB` still had a write-ahead log after being
+    /// checkpointed, so its main file may be missing synced data.
+    #[error(
+        "Refusing to upload {}: its write-ahead log {} survived a checkpoint \
+         (is another process still writing it?)",
+        .path.display(),
+        .wal.display()
+    )]
+    PendingWal {
+        /// The source database.
+        path: std::path::PathBuf,
+        /// Its write-ahead log.
+        wal: std::path::PathBuf,
+    },
+
+    /// Reading or writing the local geocode cache failed.
+    #[error("Database error: {0}")]
+    Database(#[from] crime_map_database::DbError),
+
     /// I/O error reading or writing local files.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
+
+    /// Too many consecutive S3 failures; requests fail fast until the
+    /// circuit breaker's cooldown has passed (see the module docs).
+    #[error(
+        "R2 unavailable: {failures} consecutive request failures, \
+         not retrying for another {retry_in:.0?}"
+    )]
+    CircuitOpen {
+        /// Consecutive failures that tripped the breaker.
+        failures: u32,
+        /// Time left until a request is let through again.
+        retry_in: Duration,
+    },
+}
+
+impl R2Error {
+    /// Whether this is a failed S3 request that may succeed when retried.
+    #[must_use]
+    pub const fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::Download { .. }
+                | Self::Upload { .. }
+                | Self::Head { .. }
+                | Self::Delete { .. }
+                | Self::List { .. }
+        )
+    }
 }
 
-/// Maximum number of download attempts (initial + retries).
-const MAX_DOWNLOAD_ATTEMPTS: u32 = 3;
+/// Default maximum number of attempts per request (initial + retries).
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
 
-/// Base delay between download retries (doubles each attempt).
-const RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+/// Default base delay between retries (doubles each attempt).
+const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
 
-/// Files larger than this use multipart upload (S3 `PutObject` limit is 5 GB).
-const MULTIPART_THRESHOLD: u64 = 500 * 1024 * 1024; // 500 MB
+/// Default number of consecutive failures, across all operations, after
+/// which the circuit breaker opens.
+const DEFAULT_CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
 
-/// Size of each part in a multipart upload.
-const MULTIPART_PART_SIZE: u64 = 100 * 1024 * 1024; // 100 MB
+/// Default time the circuit breaker stays open before probing again.
+const DEFAULT_CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Default size above which files use multipart upload (S3 `PutObject`
+/// limit is 5 GB).
+const DEFAULT_MULTIPART_THRESHOLD: u64 = 500 * 1024 * 1024; // 500 MB
+
+/// Default size of each part in a multipart upload.
+const DEFAULT_MULTIPART_PART_SIZE: u64 = 100 * 1024 * 1024; // 100 MB
+
+/// Smallest part size S3 accepts for every part but the last.
+const MIN_MULTIPART_PART_SIZE: u64 = 5 * 1024 * 1024; // 5 MB
+
+/// Largest part size S3 accepts.
+const MAX_MULTIPART_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024; // 5 GB
 
 /// Result of a sync batch: how many files were transferred vs skipped.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct SyncStats {
-    /// Number of files actually transferred (uploaded or downloaded).
+    /// Number of files actually transferred (uploaded or downloaded), or
+    /// that would be in a plan.
     pub transferred: u64,
+    /// Total size of the transferred files in bytes.
+    pub transferred_bytes: u64,
     /// Number of files skipped because they matched the remote.
     pub skipped: u64,
     /// Number of files not found (missing locally for upload, or missing
     /// remotely for download).
     pub not_found: u64,
+    /// Whether these stats are a plan from a dry run (see the module
+    /// docs): nothing was actually transferred.
+    pub planned: bool,
 }
 
 impl SyncStats {
     /// Merge another stats into this one.
     pub const fn merge(&mut self, other: Self) {
         self.transferred += other.transferred;
+        self.transferred_bytes += other.transferred_bytes;
         self.skipped += other.skipped;
         self.not_found += other.not_found;
+        self.planned |= other.planned;
     }
 
     /// Total number of files considered.
@@ -164,11 +340,22 @@ impl SyncStats {
 
 impl std::fmt::Display for SyncStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} transferred, {} skipped (unchanged), {} not found",
-            self.transferred, self.skipped, self.not_found
-        )
+        #[allow(clippy::cast_precision_loss)] // display-only MB value
+        let mb = self.transferred_bytes as f64 / 1_048_576.0;
+        if self.planned {
+            write!(
+                f,
+                "PLAN ONLY, nothing transferred: {} would transfer ({mb:.1} MB), \
+                 {} would skip (unchanged), {} not found",
+                self.transferred, self.skipped, self.not_found
+            )
+        } else {
+            write!(
+                f,
+                "{} transferred ({mb:.1} MB), {} skipped (unchanged), {} not found",
+                self.transferred, self.skipped, self.not_found
+            )
+        }
     }
 }
 
@@ -179,12 +366,175 @@ struct RemoteMeta {
     /// `ETag` (usually the MD5 hex digest surrounded by quotes for
     /// non-multipart uploads).
     etag: Option<String>,
+    /// SHA-256 hex digest from `x-amz-meta-sha256`, if the uploader set it.
+    sha256: Option<String>,
+}
+
+/// A generated partition on R2 with summary metadata from its objects.
+#[derive(Debug, Clone)]
+pub struct PartitionInfo {
+    /// Partition name (the `{name}` in `generated/partitions/{name}/`).
+    pub name: String,
+    /// Number of objects under the partition prefix.
+    pub object_count: usize,
+    /// Most recent `LastModified` across the partition's objects, i.e.
+    /// when it was last (partially) pushed.
+    pub last_modified: Option<SystemTime>,
+}
+
+/// Counts consecutive request failures across operations and, once
+/// `threshold` is reached, refuses requests until `cooldown` has passed.
+///
+/// After the cooldown one request is let through: a success closes the
+/// breaker, another failure re-opens it immediately. A `threshold` of 0
+/// disables the breaker.
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Returns [`R2Error::CircuitOpen`] while the breaker is open.
+    fn check(&self) -> Result<(), R2Error> {
+        let mut state = self.lock();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+
+        let elapsed = opened_at.elapsed();
+        if elapsed < self.cooldown {
+            return Err(R2Error::CircuitOpen {
+                failures: state.consecutive_failures,
+                retry_in: self.cooldown - elapsed,
+            });
+        }
+
+        // Half-open: let this request through; one more failure re-opens.
+        log::info!("R2 circuit breaker cooldown elapsed, probing endpoint");
+        state.opened_at = None;
+        state.consecutive_failures = self.threshold.saturating_sub(1);
+        Ok(())
+    }
+
+    fn record_success(&self) {
+        *self.lock() = BreakerState::default();
+    }
+
+    /// Records a failure and returns whether it opened the breaker.
+    fn record_failure(&self) -> bool {
+        let mut state = self.lock();
+        state.consecutive_failures += 1;
+        if self.threshold == 0
+            || state.consecutive_failures < self.threshold
+            || state.opened_at.is_some()
+        {
+            return false;
+        }
+
+        log::error!(
+            "R2 circuit breaker open after {} consecutive failures, \
+             failing fast for {:.0?}",
+            state.consecutive_failures,
+            self.cooldown
+        );
+        state.opened_at = Some(Instant::now());
+        true
+    }
+}
+
+/// Retry budget shared by every request an [`R2Client`] makes.
+struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    breaker: CircuitBreaker,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_RETRY_BASE_DELAY,
+            breaker: CircuitBreaker::new(
+                DEFAULT_CIRCUIT_BREAKER_THRESHOLD,
+                DEFAULT_CIRCUIT_BREAKER_COOLDOWN,
+            ),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs `request`, retrying transient failures with exponential
+    /// backoff until `max_attempts` is used up or the circuit breaker
+    /// opens. Non-transient errors are returned immediately.
+    async fn run<T, F, Fut>(&self, operation: &str, mut request: F) -> Result<T, R2Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, R2Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            self.breaker.check()?;
+            match request().await {
+                Ok(value) => {
+                    self.breaker.record_success();
+                    return Ok(value);
+                }
+                Err(e) if e.is_transient() => {
+                    if self.breaker.record_failure() || attempt >= self.max_attempts {
+                        return Err(e);
+                    }
+                    let delay = self.base_delay * 2u32.saturating_pow(attempt - 1);
+                    log::warn!(
+                        "  {operation} attempt {attempt}/{} failed, retrying in {delay:.1?}: {e}",
+                        self.max_attempts
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
 }
 
 /// Client for syncing `DuckDB` files with Cloudflare R2.
 pub struct R2Client {
     client: aws_sdk_s3::Client,
     bucket: String,
+    /// Store and compare SHA-256 object metadata (see module docs).
+    verify_sha256: bool,
+    /// Files larger than this many bytes use multipart upload.
+    multipart_threshold: u64,
+    /// Part size in bytes for multipart uploads and multipart `ETag`
+    /// recomputation (see module docs).
+    multipart_part_size: u64,
+    /// Sync the geocode cache as deltas (see module docs).
+    geocode_cache_deltas: bool,
+    /// Retry and circuit breaker settings (see module docs).
+    retry: RetryPolicy,
+    /// Only plan transfers, without performing them (see module docs).
+    dry_run: bool,
 }
 
 impl R2Client {
@@ -215,7 +565,8 @@ impl R2Client {
     ///
     /// # Errors
     ///
-    /// Returns [`R2Error::MissingEnv`] if any required variable is unset.
+    /// Returns [`R2Error::MissingEnv`] if any required variable is unset,
+    /// or [`R2Error::InvalidEnv`] if a multipart size variable is invalid.
     pub fn from_env_with_bucket(bucket: &str) -> Result<Self, R2Error> {
         let account_id = require_env("CLOUDFLARE_ACCOUNT_ID")?;
         let access_key = require_env("R2_ACCESS_KEY_ID")?;
@@ -232,33 +583,151 @@ impl R2Client {
             .stalled_stream_protection(StalledStreamProtectionConfig::disabled())
             .build();
 
+        let verify_sha256 = std::env::var("R2_VERIFY_SHA256")
+            .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true"));
+
+        let multipart_threshold = optional_env_mb("R2_MULTIPART_THRESHOLD_MB", 1, u64::MAX)?
+            .unwrap_or(DEFAULT_MULTIPART_THRESHOLD);
+        let multipart_part_size = optional_env_mb(
+            "R2_MULTIPART_PART_SIZE_MB",
+            MIN_MULTIPART_PART_SIZE,
+            MAX_MULTIPART_PART_SIZE,
+        )?
+        .unwrap_or(DEFAULT_MULTIPART_PART_SIZE);
+
+        let geocode_cache_deltas = std::env::var("R2_GEOCODE_CACHE_DELTAS")
+            .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true"));
+
         Ok(Self {
             client: aws_sdk_s3::Client::from_conf(config),
             bucket: bucket.to_string(),
+            verify_sha256,
+            multipart_threshold,
+            multipart_part_size,
+            geocode_cache_deltas,
+            retry: RetryPolicy::default(),
+            dry_run: false,
         })
     }
 
+    /// Sets the maximum number of attempts per request, including the
+    /// first. Values below 1 are treated as 1 (no retries).
+    #[must_use]
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.retry.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry; each further retry waits
+    /// twice as long as the previous one.
+    #[must_use]
+    pub const fn with_retry_base_delay(mut self, delay: Duration) -> Self {
+        self.retry.base_delay = delay;
+        self
+    }
+
+    /// Configures the circuit breaker: after `threshold` consecutive
+    /// failures across all operations, requests fail fast with
+    /// [`R2Error::CircuitOpen`] until `cooldown` has passed. A `threshold`
+    /// of 0 disables it.
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.retry.breaker = CircuitBreaker::new(threshold, cooldown);
+        self
+    }
+
+    /// Enables or disables delta sync of the geocode cache, overriding
+    /// `R2_GEOCODE_CACHE_DELTAS`.
+    #[must_use]
+    pub const fn with_geocode_cache_deltas(mut self, enabled: bool) -> Self {
+        self.geocode_cache_deltas = enabled;
+        self
+    }
+
+    /// Enables or disables plan mode: pushes and pulls classify each file
+    /// as would-transfer, would-skip, or not found without transferring
+    /// anything (see the module docs).
+    #[must_use]
+    pub const fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Prefix for per-file log lines: `"PLAN: "` in plan mode, so skipped
+    /// and missing files in a plan aren't mistaken for a real sync.
+    const fn plan_prefix(&self) -> &'static str {
+        if self.dry_run { "PLAN: " } else { "" }
+    }
+
+    /// Enables or disables SHA-256 smart-sync verification, overriding
+    /// `R2_VERIFY_SHA256`.
+    ///
+    /// When enabled, uploads store the file's SHA-256 as object metadata
+    /// and skip decisions compare it against the local file whenever the
+    /// remote object carries it.
+    #[must_use]
+    pub const fn with_sha256_verification(mut self, enabled: bool) -> Self {
+        self.verify_sha256 = enabled;
+        self
+    }
+
+    /// Sets the size in bytes above which files are uploaded in parts,
+    /// overriding `R2_MULTIPART_THRESHOLD_MB`.
+    #[must_use]
+    pub const fn with_multipart_threshold(mut self, bytes: u64) -> Self {
+        self.multipart_threshold = bytes;
+        self
+    }
+
+    /// Sets the multipart part size in bytes, overriding
+    /// `R2_MULTIPART_PART_SIZE_MB`. Clamped to the 5 MB – 5 GB range S3
+    /// accepts.
+    ///
+    /// This also changes how multipart `ETag`s are recomputed for smart
+    /// sync; see the module docs.
+    #[must_use]
+    pub const fn with_multipart_part_size(mut self, bytes: u64) -> Self {
+        self.multipart_part_size = if bytes < MIN_MULTIPART_PART_SIZE {
+            MIN_MULTIPART_PART_SIZE
+        } else if bytes > MAX_MULTIPART_PART_SIZE {
+            MAX_MULTIPART_PART_SIZE
+        } else {
+            bytes
+        };
+        self
+    }
+
     // ── Source DuckDB files ──────────────────────────────────────────
 
     /// Pull per-source `DuckDB` files from R2 to local `data/sources/`.
     ///
     /// If `source_ids` is empty, pulls all known sources from the TOML
-    /// registry. Missing R2 objects are skipped with a warning (expected on
-    /// first run).
+    /// registry. Every object backing a source is pulled: the unsharded
+    /// `sources/{id}.duckdb` and any `sources/{id}.{shard}.duckdb` shards
+    /// (see [`source_db::shard_paths`]). Sources with no objects on R2 are
+    /// skipped with a warning (expected on first run).
     ///
     /// # Errors
     ///
-    /// Returns [`R2Error::Download`] on S3 failures, [`R2Error::Io`] on
-    /// local filesystem errors.
+    /// Returns [`R2Error::List`] or [`R2Error::Download`] on S3 failures,
+    /// [`R2Error::Io`] on local filesystem errors.
     pub async fn pull_sources(&self, source_ids: &[String]) -> Result<SyncStats, R2Error> {
         let ids = resolve_source_ids(source_ids);
-        paths::ensure_dir(&paths::sources_dir())?;
+        let sources_dir = paths::sources_dir();
+        paths::ensure_dir(&sources_dir)?;
 
+        let listed = self.list_keys("sources/").await?;
         let mut stats = SyncStats::default();
         for id in &ids {
-            let key = format!("sources/{id}.duckdb");
-            let local = paths::source_db_path(id);
-            stats.merge(self.download(&key, &local).await?);
+            let mut keys = source_object_keys(id, &listed);
+            if keys.is_empty() {
+                // Let the download report the source as not found.
+                keys.push(format!("sources/{id}.duckdb"));
+            }
+            for key in keys {
+                let local = sources_dir.join(&key["sources/".len()..]);
+                stats.merge(self.download(&key, &local).await?);
+            }
         }
 
         Ok(stats)
@@ -267,31 +736,77 @@ impl R2Client {
     /// Push per-source `DuckDB` files from local `data/sources/` to R2.
     ///
     /// If `source_ids` is empty, pushes all known sources that have a local
-    /// file. Sources without a local `DuckDB` file are skipped.
+    /// file. Every file backing a source is pushed, including its shards
+    /// (see [`source_db::shard_paths`]). Sources without a local `DuckDB`
+    /// file are skipped. Each file is checkpointed first if it has a
+    /// pending write-ahead log, since only the main file is uploaded.
     ///
     /// # Errors
     ///
     /// Returns [`R2Error::Upload`] on S3 failures, [`R2Error::Io`] on
-    /// local filesystem errors.
+    /// local filesystem errors, and [`R2Error::PendingWal`] if a source's
+    /// write-ahead log can't be checkpointed away.
     pub async fn push_sources(&self, source_ids: &[String]) -> Result<SyncStats, R2Error> {
         let ids = resolve_source_ids(source_ids);
 
         let mut stats = SyncStats::default();
         for id in &ids {
-            let key = format!("sources/{id}.duckdb");
-            let local = paths::source_db_path(id);
-            stats.merge(self.upload(&key, &local).await?);
+            let mut locals = source_db::shard_paths(id);
+            if locals.is_empty() {
+                // Let the upload report the source as not found.
+                locals.push(paths::source_db_path(id));
+            }
+            for local in locals {
+                let Some(name) = local.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                let key = format!("sources/{name}");
+                self.checkpoint_source(&local)?;
+                stats.merge(self.upload(&key, &local).await?);
+            }
         }
 
         Ok(stats)
     }
 
+    /// Makes sure the source `DuckDB` at `path` holds all of its synced
+    /// data before it's uploaded (see [`source_db::checkpoint`]). A plan
+    /// only reports a pending write-ahead log.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`R2Error::PendingWal`] if the log survives the checkpoint,
+    /// or [`R2Error::Database`] if the checkpoint fails.
+    fn checkpoint_source(&self, path: &Path) -> Result<(), R2Error> {
+        if self.dry_run {
+            let wal = source_db::wal_path(path);
+            if wal.exists() {
+                log::info!(
+                    "{}{} has a pending write-ahead log, which a push checkpoints first",
+                    self.plan_prefix(),
+                    path.display()
+                );
+            }
+            return Ok(());
+        }
+
+        match source_db::checkpoint(path)? {
+            Some(wal) => Err(R2Error::PendingWal {
+                path: path.to_path_buf(),
+                wal,
+            }),
+            None => Ok(()),
+        }
+    }
+
     // ── Shared databases ────────────────────────────────────────────
 
     /// Pull shared databases (`boundaries.duckdb`, `geocode_cache.duckdb`)
     /// from R2.
     ///
-    /// Also pulls the geocoder index archive if it exists on R2.
+    /// Also pulls the geocoder index archive if it exists on R2. With
+    /// geocode cache deltas enabled, the cache is updated from its deltas
+    /// instead (see the module docs).
     ///
     /// # Errors
     ///
@@ -307,13 +822,14 @@ impl R2Client {
                 .await?,
         );
 
-        stats.merge(
-            self.download(
-                "shared/geocode_cache.duckdb",
-                &paths::geocode_cache_db_path(),
-            )
-            .await?,
-        );
+        if self.geocode_cache_deltas {
+            stats.merge(self.pull_geocode_cache_deltas().await?);
+        } else {
+            stats.merge(
+                self.download(GEOCODE_CACHE_KEY, &paths::geocode_cache_db_path())
+                    .await?,
+            );
+        }
 
         // Pull geocoder index archive (optional — may not exist yet)
         let archive_path = paths::shared_dir().join("geocoder_index.tar.zst");
@@ -327,7 +843,9 @@ impl R2Client {
 
     /// Push shared databases to R2.
     ///
-    /// Also pushes the geocoder index archive if it exists locally.
+    /// Also pushes the geocoder index archive if it exists locally. With
+    /// geocode cache deltas enabled, only new cache entries are pushed,
+    /// plus an occasional full compaction (see the module docs).
     ///
     /// # Errors
     ///
@@ -341,13 +859,14 @@ impl R2Client {
                 .await?,
         );
 
-        stats.merge(
-            self.upload(
-                "shared/geocode_cache.duckdb",
-                &paths::geocode_cache_db_path(),
-            )
-            .await?,
-        );
+        if self.geocode_cache_deltas {
+            stats.merge(self.push_geocode_cache_delta().await?);
+        } else {
+            stats.merge(
+                self.upload(GEOCODE_CACHE_KEY, &paths::geocode_cache_db_path())
+                    .await?,
+            );
+        }
 
         // Push geocoder index archive if it exists
         let archive_path = paths::shared_dir().join("geocoder_index.tar.zst");
@@ -359,23 +878,177 @@ impl R2Client {
         Ok(stats)
     }
 
+    /// Brings the local geocode cache up to date from R2 deltas.
+    ///
+    /// Downloads the full cache first if there is no local one, then
+    /// imports every delta newer than the last one applied, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`R2Error::Download`] or [`R2Error::List`] on S3 failures,
+    /// [`R2Error::Database`] if a delta can't be applied.
+    async fn pull_geocode_cache_deltas(&self) -> Result<SyncStats, R2Error> {
+        let cache_path = paths::geocode_cache_db_path();
+        let mut stats = SyncStats::default();
+
+        if !cache_path.exists() {
+            stats.merge(self.download(GEOCODE_CACHE_KEY, &cache_path).await?);
+        }
+
+        // A plan doesn't download the full cache, so it may not exist.
+        let conn = if self.dry_run && !cache_path.exists() {
+            None
+        } else {
+            Some(geocode_cache::open(&cache_path)?)
+        };
+        let last_applied = match &conn {
+            Some(conn) => geocode_cache::last_applied_delta(conn)?,
+            None => None,
+        };
+
+        let mut pending: Vec<String> = self
+            .list_keys(GEOCODE_CACHE_DELTAS_PREFIX)
+            .await?
+            .into_iter()
+            .filter(|key| last_applied.as_deref() < Some(delta_name(key)))
+            .collect();
+        pending.sort_unstable();
+
+        let delta_path = paths::shared_dir().join("geocode_cache_delta.parquet");
+        for key in &pending {
+            stats.merge(self.download(key, &delta_path).await?);
+            let Some(conn) = conn.as_ref().filter(|_| !self.dry_run) else {
+                continue;
+            };
+            let imported = geocode_cache::import(conn, &delta_path)?;
+            geocode_cache::set_last_applied_delta(conn, delta_name(key))?;
+            log::info!("  applied {key} ({imported} new entries)");
+        }
+        if delta_path.exists() && !self.dry_run {
+            std::fs::remove_file(&delta_path)?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Uploads the geocode cache entries added since the last push as a
+    /// new delta, then re-uploads the full cache if
+    /// [`GEOCODE_CACHE_COMPACT_AFTER`] deltas are newer than it on R2.
+    ///
+    /// Pending deltas are applied before a compaction so the uploaded
+    /// cache contains them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`R2Error::Upload`] or [`R2Error::List`] on S3 failures,
+    /// [`R2Error::Database`] if the cache can't be exported.
+    async fn push_geocode_cache_delta(&self) -> Result<SyncStats, R2Error> {
+        let cache_path = paths::geocode_cache_db_path();
+        let mut stats = SyncStats::default();
+
+        if !cache_path.exists() {
+            log::warn!(
+                "  {} does not exist locally, skipping",
+                cache_path.display()
+            );
+            stats.not_found += 1;
+            return Ok(stats);
+        }
+
+        if self.dry_run {
+            log::info!(
+                "  PLAN: geocode cache delta push not planned (new entries are only \
+                 exported by a real push)"
+            );
+            stats.planned = true;
+            return Ok(stats);
+        }
+
+        let conn = geocode_cache::open(&cache_path)?;
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let name = format!("{millis:016}.parquet");
+        let delta_path = paths::shared_dir().join(&name);
+
+        let exported = geocode_cache::export_unsynced(&conn, &delta_path)?;
+        if exported == 0 {
+            log::info!("  geocode cache: no new entries to push");
+        } else {
+            let key = format!("{GEOCODE_CACHE_DELTAS_PREFIX}{name}");
+            stats.merge(self.upload(&key, &delta_path).await?);
+            geocode_cache::mark_synced(&conn, &delta_path)?;
+            std::fs::remove_file(&delta_path)?;
+            log::info!("  pushed {exported} new geocode cache entries as {key}");
+        }
+        drop(conn);
+
+        let full_modified = self
+            .list_objects(GEOCODE_CACHE_KEY)
+            .await?
+            .into_iter()
+            .find(|(key, _)| key == GEOCODE_CACHE_KEY)
+            .and_then(|(_, modified)| modified);
+        let newer = self
+            .list_objects(GEOCODE_CACHE_DELTAS_PREFIX)
+            .await?
+            .into_iter()
+            .filter(|(_, modified)| full_modified.is_none() || *modified > full_modified)
+            .count();
+
+        if newer >= GEOCODE_CACHE_COMPACT_AFTER || full_modified.is_none() {
+            log::info!("  compacting geocode cache ({newer} deltas since the last full upload)");
+            stats.merge(self.pull_geocode_cache_deltas().await?);
+            stats.merge(self.upload(GEOCODE_CACHE_KEY, &cache_path).await?);
+        }
+
+        Ok(stats)
+    }
+
     // ── Generated outputs ───────────────────────────────────────────
 
     /// Push merged generated outputs to R2 under `generated/merged/`.
     ///
-    /// Uploads all standard generated files from `dir` (incidents, counts,
-    /// h3, analytics, boundaries, metadata, manifest).
+    /// Uploads all standard generated files from `dir` (see
+    /// [`paths::GENERATED_FILES`] and [`paths::BOUNDARY_FILES`]), plus any
+    /// [`paths::OPTIONAL_GENERATED_FILES`] present.
     ///
     /// # Errors
     ///
-    /// Returns [`R2Error::Upload`] on S3 failures, [`R2Error::Io`] on
-    /// local filesystem errors.
+    /// Returns [`R2Error::PreviewOutputs`] if `dir` holds sampled outputs,
+    /// [`R2Error::Upload`] on S3 failures, [`R2Error::Io`] on local
+    /// filesystem errors.
     pub async fn push_generated_merged(&self, dir: &Path) -> Result<SyncStats, R2Error> {
+        self.push_generated_merged_with(dir, &[]).await
+    }
+
+    /// Like [`Self::push_generated_merged`], additionally uploading
+    /// `extra_files` (names relative to `dir`) for outputs outside the
+    /// standard set.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::push_generated_merged`].
+    pub async fn push_generated_merged_with(
+        &self,
+        dir: &Path,
+        extra_files: &[&str],
+    ) -> Result<SyncStats, R2Error> {
+        ensure_not_preview(dir)?;
+        let build = generated_build_id(dir);
         let mut stats = SyncStats::default();
-        for &file in GENERATED_FILES.iter().chain(BOUNDARY_FILES.iter()) {
+        for &file in GENERATED_FILES
+            .iter()
+            .chain(BOUNDARY_FILES.iter())
+            .chain(present_optional_files(dir))
+            .chain(extra_files.iter())
+        {
             let key = format!("generated/merged/{file}");
             let local = dir.join(file);
-            stats.merge(self.upload(&key, &local).await?);
+            stats.merge(
+                self.upload_with_build(&key, &local, build.as_deref())
+                    .await?,
+            );
         }
         Ok(stats)
     }
@@ -389,7 +1062,11 @@ impl R2Client {
     pub async fn pull_generated_merged(&self, dir: &Path) -> Result<SyncStats, R2Error> {
         paths::ensure_dir(dir)?;
         let mut stats = SyncStats::default();
-        for &file in GENERATED_FILES.iter().chain(BOUNDARY_FILES.iter()) {
+        for &file in GENERATED_FILES
+            .iter()
+            .chain(BOUNDARY_FILES.iter())
+            .chain(OPTIONAL_GENERATED_FILES.iter())
+        {
             let key = format!("generated/merged/{file}");
             let local = dir.join(file);
             stats.merge(self.download(&key, &local).await?);
@@ -402,18 +1079,44 @@ impl R2Client {
     ///
     /// # Errors
     ///
-    /// Returns [`R2Error::Upload`] on S3 failures, [`R2Error::Io`] on
-    /// local filesystem errors.
+    /// Returns [`R2Error::PreviewOutputs`] if `dir` holds sampled outputs,
+    /// [`R2Error::Upload`] on S3 failures, [`R2Error::Io`] on local
+    /// filesystem errors.
     pub async fn push_generated_partition(
         &self,
         name: &str,
         dir: &Path,
     ) -> Result<SyncStats, R2Error> {
+        self.push_generated_partition_with(name, dir, &[]).await
+    }
+
+    /// Like [`Self::push_generated_partition`], additionally uploading
+    /// `extra_files` (names relative to `dir`) for outputs outside the
+    /// standard set.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::push_generated_partition`].
+    pub async fn push_generated_partition_with(
+        &self,
+        name: &str,
+        dir: &Path,
+        extra_files: &[&str],
+    ) -> Result<SyncStats, R2Error> {
+        ensure_not_preview(dir)?;
+        let build = generated_build_id(dir);
         let mut stats = SyncStats::default();
-        for &file in GENERATED_FILES {
+        for &file in GENERATED_FILES
+            .iter()
+            .chain(present_optional_files(dir))
+            .chain(extra_files.iter())
+        {
             let key = format!("generated/partitions/{name}/{file}");
             let local = dir.join(file);
-            stats.merge(self.upload(&key, &local).await?);
+            stats.merge(
+                self.upload_with_build(&key, &local, build.as_deref())
+                    .await?,
+            );
         }
         Ok(stats)
     }
@@ -432,7 +1135,10 @@ impl R2Client {
     ) -> Result<SyncStats, R2Error> {
         paths::ensure_dir(dir)?;
         let mut stats = SyncStats::default();
-        for &file in GENERATED_FILES {
+        for &file in GENERATED_FILES
+            .iter()
+            .chain(OPTIONAL_GENERATED_FILES.iter())
+        {
             let key = format!("generated/partitions/{name}/{file}");
             let local = dir.join(file);
             stats.merge(self.download(&key, &local).await?);
@@ -440,6 +1146,58 @@ impl R2Client {
         Ok(stats)
     }
 
+    /// Downloads and parses the `manifest.json` under the generated output
+    /// `prefix` (e.g. [`generated_merged_prefix`]) without writing it to
+    /// disk. Works the same in a plan, since nothing is transferred
+    /// locally.
+    ///
+    /// Returns `None` if the prefix has no manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`R2Error::Head`] or [`R2Error::Download`] on S3 failures
+    /// after all retries are exhausted, or [`R2Error::Download`] if the
+    /// manifest isn't valid JSON.
+    pub async fn fetch_manifest(&self, prefix: &str) -> Result<Option<serde_json::Value>, R2Error> {
+        let key = format!("{}/{}", prefix.trim_end_matches('/'), paths::MANIFEST_FILE);
+        if self.head(&key).await?.is_none() {
+            return Ok(None);
+        }
+
+        let bytes = self.retry.run("download", || self.fetch_once(&key)).await?;
+        serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| R2Error::Download {
+                bucket: self.bucket.clone(),
+                key,
+                source: Box::new(e),
+            })
+    }
+
+    /// Single `GetObject` attempt, buffering the body in memory. Only for
+    /// small objects; use [`Self::download`] for files.
+    async fn fetch_once(&self, key: &str) -> Result<Vec<u8>, R2Error> {
+        let download_err = |source: Box<dyn std::error::Error + Send + Sync>| R2Error::Download {
+            bucket: self.bucket.clone(),
+            key: key.to_string(),
+            source,
+        };
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| download_err(Box::new(e)))?;
+        let body = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| download_err(Box::new(e)))?;
+        Ok(body.into_bytes().to_vec())
+    }
+
     /// Push boundary outputs to R2 under `generated/boundaries/`.
     ///
     /// # Errors
@@ -482,18 +1240,73 @@ impl R2Client {
     ///
     /// Returns [`R2Error::List`] on S3 failures.
     pub async fn list_generated_partitions(&self) -> Result<Vec<String>, R2Error> {
-        let keys = self.list_keys("generated/partitions/").await?;
-        let mut names = std::collections::BTreeSet::new();
-        for key in &keys {
-            if let Some(name) = key
+        Ok(self
+            .list_generated_partitions_detailed()
+            .await?
+            .into_iter()
+            .map(|p| p.name)
+            .collect())
+    }
+
+    /// List all generated partitions on R2 with their object count and
+    /// most recent `LastModified`, sorted by name.
+    ///
+    /// Use this to find stale partitions to remove with
+    /// [`Self::delete_partition`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`R2Error::List`] on S3 failures.
+    pub async fn list_generated_partitions_detailed(&self) -> Result<Vec<PartitionInfo>, R2Error> {
+        let objects = self.list_objects("generated/partitions/").await?;
+        let mut partitions: std::collections::BTreeMap<String, PartitionInfo> =
+            std::collections::BTreeMap::new();
+        for (key, modified) in objects {
+            let Some(name) = key
                 .strip_prefix("generated/partitions/")
                 .and_then(|rest| rest.split('/').next())
                 .filter(|n| !n.is_empty())
-            {
-                names.insert(name.to_string());
-            }
+            else {
+                continue;
+            };
+            let info = partitions
+                .entry(name.to_string())
+                .or_insert_with(|| PartitionInfo {
+                    name: name.to_string(),
+                    object_count: 0,
+                    last_modified: None,
+                });
+            info.object_count += 1;
+            info.last_modified = info.last_modified.max(modified);
+        }
+        Ok(partitions.into_values().collect())
+    }
+
+    /// Deletes every object under `generated/partitions/{name}/`.
+    ///
+    /// Returns the number of objects deleted (`0` if the partition doesn't
+    /// exist).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`R2Error::List`] or [`R2Error::Delete`] on S3 failures, or
+    /// [`R2Error::Delete`] if `name` is empty or contains `/` (which would
+    /// widen the prefix beyond a single partition).
+    pub async fn delete_partition(&self, name: &str) -> Result<usize, R2Error> {
+        let prefix = generated_partition_prefix(name);
+        if name.is_empty() || name.contains('/') {
+            return Err(R2Error::Delete {
+                bucket: self.bucket.clone(),
+                key: prefix,
+                source: format!("invalid partition name '{name}'").into(),
+            });
         }
-        Ok(names.into_iter().collect())
+
+        let keys = self.list_keys(&prefix).await?;
+        for key in &keys {
+            self.delete(key).await?;
+        }
+        Ok(keys.len())
     }
 
     // ── Tiles (CDN bucket) ──────────────────────────────────────────
@@ -505,11 +1318,18 @@ impl R2Client {
     ///
     /// # Errors
     ///
-    /// Returns [`R2Error::Upload`] on S3 failures, [`R2Error::Io`] on
-    /// local filesystem errors.
+    /// Returns [`R2Error::PreviewOutputs`] if `dir` holds sampled outputs,
+    /// [`R2Error::Upload`] on S3 failures, [`R2Error::Io`] on local
+    /// filesystem errors.
     pub async fn push_tiles(&self, dir: &Path) -> Result<SyncStats, R2Error> {
-        let local = dir.join("incidents.pmtiles");
-        self.upload("incidents.pmtiles", &local).await
+        ensure_not_preview(dir)?;
+        let local = dir.join(paths::INCIDENTS_PMTILES_FILE);
+        self.upload_with_build(
+            paths::INCIDENTS_PMTILES_FILE,
+            &local,
+            generated_build_id(dir).as_deref(),
+        )
+        .await
     }
 
     /// Pull `incidents.pmtiles` from the current bucket to `dir`.
@@ -520,8 +1340,8 @@ impl R2Client {
     /// local filesystem errors.
     pub async fn pull_tiles(&self, dir: &Path) -> Result<SyncStats, R2Error> {
         paths::ensure_dir(dir)?;
-        let local = dir.join("incidents.pmtiles");
-        self.download("incidents.pmtiles", &local).await
+        let local = dir.join(paths::INCIDENTS_PMTILES_FILE);
+        self.download(paths::INCIDENTS_PMTILES_FILE, &local).await
     }
 
     // ── Low-level operations ────────────────────────────────────────
@@ -529,8 +1349,8 @@ impl R2Client {
     /// Downloads an object from R2 to a local file.
     ///
     /// Uses **size + MD5/ETag comparison** to skip the download when the
-    /// local file already matches the remote object. Retries up to
-    /// [`MAX_DOWNLOAD_ATTEMPTS`] times on transient errors.
+    /// local file already matches the remote object. Failed transfers are
+    /// retried (see the module docs).
     ///
     /// Returns stats indicating whether the file was transferred, skipped,
     /// or not found.
@@ -548,50 +1368,54 @@ impl R2Client {
 
         // Check if we can skip via smart sync
         if let Some(remote) = self.head(key).await? {
-            if is_local_match(local_path, &remote).await {
-                log::info!("  skipped (unchanged)");
+            if is_local_match(
+                local_path,
+                &remote,
+                self.verify_sha256,
+                self.multipart_part_size,
+            )
+            .await
+            {
+                log::info!("  {}skipped (unchanged)", self.plan_prefix());
                 return Ok(SyncStats {
                     skipped: 1,
+                    planned: self.dry_run,
+                    ..SyncStats::default()
+                });
+            }
+            if self.dry_run {
+                #[allow(clippy::cast_precision_loss)] // display-only MB value
+                let mb = remote.size as f64 / 1_048_576.0;
+                log::info!("  PLAN: would transfer ({mb:.1} MB)");
+                return Ok(SyncStats {
+                    transferred: 1,
+                    transferred_bytes: remote.size,
+                    planned: true,
                     ..SyncStats::default()
                 });
             }
         } else {
             // Object doesn't exist on R2
-            log::warn!("  not found in R2 (first run?), skipping");
+            log::warn!(
+                "  {}not found in R2 (first run?), skipping",
+                self.plan_prefix()
+            );
             return Ok(SyncStats {
                 not_found: 1,
+                planned: self.dry_run,
                 ..SyncStats::default()
             });
         }
 
-        let mut last_err: Option<R2Error> = None;
+        self.retry
+            .run("download", || self.download_once(key, local_path))
+            .await?;
 
-        for attempt in 1..=MAX_DOWNLOAD_ATTEMPTS {
-            match self.download_once(key, local_path).await {
-                Ok(()) => {
-                    return Ok(SyncStats {
-                        transferred: 1,
-                        ..SyncStats::default()
-                    });
-                }
-                Err(e @ R2Error::Download { .. }) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
-                    let delay = RETRY_BASE_DELAY * 2u32.saturating_pow(attempt - 1);
-                    log::warn!(
-                        "  download attempt {attempt}/{MAX_DOWNLOAD_ATTEMPTS} failed, \
-                         retrying in {delay:.1?}..."
-                    );
-                    last_err = Some(e);
-                    tokio::time::sleep(delay).await;
-                }
-                Err(e) => return Err(e),
-            }
-        }
-
-        Err(last_err.unwrap_or_else(|| R2Error::Download {
-            bucket: self.bucket.clone(),
-            key: key.to_string(),
-            source: "all download attempts exhausted".into(),
-        }))
+        Ok(SyncStats {
+            transferred: 1,
+            transferred_bytes: tokio::fs::metadata(local_path).await?.len(),
+            ..SyncStats::default()
+        })
     }
 
     /// Single download attempt (always transfers, no smart-sync check).
@@ -643,31 +1467,56 @@ impl R2Client {
     ///
     /// # Errors
     ///
-    /// Returns [`R2Error::Upload`] on S3 failures, [`R2Error::Io`] on
-    /// local filesystem errors.
+    /// Returns [`R2Error::Upload`] on S3 failures after all retries are
+    /// exhausted, [`R2Error::Io`] on local filesystem errors.
     pub async fn upload(&self, key: &str, local_path: &Path) -> Result<SyncStats, R2Error> {
+        self.upload_with_build(key, local_path, None).await
+    }
+
+    /// Like [`Self::upload`], additionally tagging the object with `build`
+    /// (see the module docs) when set.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::upload`].
+    pub async fn upload_with_build(
+        &self,
+        key: &str,
+        local_path: &Path,
+        build: Option<&str>,
+    ) -> Result<SyncStats, R2Error> {
         if !local_path.exists() {
             log::warn!(
-                "  {} does not exist locally, skipping",
+                "  {}{} does not exist locally, skipping",
+                self.plan_prefix(),
                 local_path.display()
             );
             return Ok(SyncStats {
                 not_found: 1,
+                planned: self.dry_run,
                 ..SyncStats::default()
             });
         }
 
         // Check if remote already matches
         if let Some(remote) = self.head(key).await?
-            && is_local_match(local_path, &remote).await
+            && is_local_match(
+                local_path,
+                &remote,
+                self.verify_sha256,
+                self.multipart_part_size,
+            )
+            .await
         {
             log::info!(
-                "  {} -> s3://{}/{key}: skipped (unchanged)",
+                "  {}{} -> s3://{}/{key}: skipped (unchanged)",
+                self.plan_prefix(),
                 local_path.display(),
                 self.bucket,
             );
             return Ok(SyncStats {
                 skipped: 1,
+                planned: self.dry_run,
                 ..SyncStats::default()
             });
         }
@@ -676,74 +1525,118 @@ impl R2Client {
         #[allow(clippy::cast_precision_loss)] // display-only MB value
         let mb = file_size as f64 / 1_048_576.0;
 
-        if file_size > MULTIPART_THRESHOLD {
+        if self.dry_run {
+            log::info!(
+                "  PLAN: {} -> s3://{}/{key}: would transfer ({mb:.1} MB)",
+                local_path.display(),
+                self.bucket,
+            );
+            return Ok(SyncStats {
+                transferred: 1,
+                transferred_bytes: file_size,
+                planned: true,
+                ..SyncStats::default()
+            });
+        }
+
+        let sha256 = if self.verify_sha256 {
+            Some(compute_sha256(local_path).await?)
+        } else {
+            None
+        };
+
+        if file_size > self.multipart_threshold {
             log::info!(
                 "Pushing {} -> s3://{}/{key} ({mb:.1} MB, multipart)",
                 local_path.display(),
                 self.bucket,
             );
-            self.upload_multipart(key, local_path, file_size).await?;
+            self.upload_multipart(key, local_path, file_size, sha256.as_deref(), build)
+                .await?;
         } else {
             log::info!(
                 "Pushing {} -> s3://{}/{key} ({mb:.1} MB)",
                 local_path.display(),
                 self.bucket,
             );
-            let body = aws_sdk_s3::primitives::ByteStream::from_path(local_path)
-                .await
-                .map_err(|e| R2Error::Upload {
-                    bucket: self.bucket.clone(),
-                    key: key.to_string(),
-                    source: Box::new(e),
-                })?;
-
-            self.client
-                .put_object()
-                .bucket(&self.bucket)
-                .key(key)
-                .body(body)
-                .content_type("application/octet-stream")
-                .send()
-                .await
-                .map_err(|e| R2Error::Upload {
-                    bucket: self.bucket.clone(),
-                    key: key.to_string(),
-                    source: Box::new(e),
-                })?;
+            let sha256 = sha256.as_deref();
+            self.retry
+                .run("upload", || async move {
+                    let body = aws_sdk_s3::primitives::ByteStream::from_path(local_path)
+                        .await
+                        .map_err(|e| R2Error::Upload {
+                            bucket: self.bucket.clone(),
+                            key: key.to_string(),
+                            source: Box::new(e),
+                        })?;
+
+                    let mut request = self
+                        .client
+                        .put_object()
+                        .bucket(&self.bucket)
+                        .key(key)
+                        .body(body)
+                        .content_type("application/octet-stream");
+                    if let Some(sha256) = sha256 {
+                        request = request.metadata(SHA256_METADATA_KEY, sha256);
+                    }
+                    if let Some(build) = build {
+                        request = request.metadata(BUILD_METADATA_KEY, build);
+                    }
+                    request.send().await.map_err(|e| R2Error::Upload {
+                        bucket: self.bucket.clone(),
+                        key: key.to_string(),
+                        source: Box::new(e),
+                    })
+                })
+                .await?;
         }
 
         log::info!("  uploaded {key}");
         Ok(SyncStats {
             transferred: 1,
+            transferred_bytes: file_size,
             ..SyncStats::default()
         })
     }
 
     /// Uploads a large file using S3 multipart upload.
     ///
-    /// Reads the file in [`MULTIPART_PART_SIZE`] chunks to avoid loading
+    /// Reads the file in part-size chunks to avoid loading
     /// the entire file into memory. Aborts the multipart upload on any
-    /// error to avoid leaving orphaned parts on R2.
+    /// error to avoid leaving orphaned parts on R2. If `sha256` or `build`
+    /// is set it is stored as object metadata.
     async fn upload_multipart(
         &self,
         key: &str,
         local_path: &Path,
         file_size: u64,
+        sha256: Option<&str>,
+        build: Option<&str>,
     ) -> Result<(), R2Error> {
         // Initiate multipart upload
         let create = self
-            .client
-            .create_multipart_upload()
-            .bucket(&self.bucket)
-            .key(key)
-            .content_type("application/octet-stream")
-            .send()
-            .await
-            .map_err(|e| R2Error::Upload {
-                bucket: self.bucket.clone(),
-                key: key.to_string(),
-                source: Box::new(e),
-            })?;
+            .retry
+            .run("create multipart upload", || async move {
+                let mut request = self
+                    .client
+                    .create_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .content_type("application/octet-stream");
+                if let Some(sha256) = sha256 {
+                    request = request.metadata(SHA256_METADATA_KEY, sha256);
+                }
+                if let Some(build) = build {
+                    request = request.metadata(BUILD_METADATA_KEY, build);
+                }
+                request.send().await.map_err(|e| R2Error::Upload {
+                    bucket: self.bucket.clone(),
+                    key: key.to_string(),
+                    source: Box::new(e),
+                })
+            })
+            .await?;
 
         let upload_id = create
             .upload_id()
@@ -764,20 +1657,26 @@ impl R2Client {
                 let completed = CompletedMultipartUpload::builder()
                     .set_parts(Some(parts))
                     .build();
-
-                self.client
-                    .complete_multipart_upload()
-                    .bucket(&self.bucket)
-                    .key(key)
-                    .upload_id(&upload_id)
-                    .multipart_upload(completed)
-                    .send()
-                    .await
-                    .map_err(|e| R2Error::Upload {
-                        bucket: self.bucket.clone(),
-                        key: key.to_string(),
-                        source: Box::new(e),
-                    })?;
+                let completed = &completed;
+                let upload_id = upload_id.as_str();
+
+                self.retry
+                    .run("complete multipart upload", || async move {
+                        self.client
+                            .complete_multipart_upload()
+                            .bucket(&self.bucket)
+                            .key(key)
+                            .upload_id(upload_id)
+                            .multipart_upload(completed.clone())
+                            .send()
+                            .await
+                            .map_err(|e| R2Error::Upload {
+                                bucket: self.bucket.clone(),
+                                key: key.to_string(),
+                                source: Box::new(e),
+                            })
+                    })
+                    .await?;
 
                 Ok(())
             }
@@ -798,7 +1697,8 @@ impl R2Client {
     }
 
     /// Uploads individual parts for a multipart upload, reading from disk
-    /// in chunks to keep memory usage bounded.
+    /// in chunks to keep memory usage bounded. Each part is retried on its
+    /// own.
     async fn upload_multipart_parts(
         &self,
         key: &str,
@@ -806,9 +1706,8 @@ impl R2Client {
         file_size: u64,
         upload_id: &str,
     ) -> Result<Vec<CompletedPart>, R2Error> {
-        use tokio::io::AsyncReadExt;
+        use aws_sdk_s3::primitives::{ByteStream, Length};
 
-        let mut file = tokio::fs::File::open(local_path).await?;
         let mut parts = Vec::new();
         let mut part_number: i32 = 1;
         let mut uploaded: u64 = 0;
@@ -819,30 +1718,42 @@ impl R2Client {
             if remaining == 0 {
                 break;
             }
-            #[allow(clippy::cast_possible_truncation)]
-            let chunk_size = remaining.min(MULTIPART_PART_SIZE) as usize;
-
-            // Read chunk from disk
-            let mut buf = vec![0u8; chunk_size];
-            file.read_exact(&mut buf).await?;
-
-            let body = aws_sdk_s3::primitives::ByteStream::from(buf);
+            let chunk_size = remaining.min(self.multipart_part_size);
+            let offset = uploaded;
 
+            // Stream the chunk from disk; re-opened on every attempt so a
+            // retry resends the whole part.
             let upload_part = self
-                .client
-                .upload_part()
-                .bucket(&self.bucket)
-                .key(key)
-                .upload_id(upload_id)
-                .part_number(part_number)
-                .body(body)
-                .send()
-                .await
-                .map_err(|e| R2Error::Upload {
-                    bucket: self.bucket.clone(),
-                    key: key.to_string(),
-                    source: Box::new(e),
-                })?;
+                .retry
+                .run("upload part", || async move {
+                    let body = ByteStream::read_from()
+                        .path(local_path)
+                        .offset(offset)
+                        .length(Length::Exact(chunk_size))
+                        .build()
+                        .await
+                        .map_err(|e| R2Error::Upload {
+                            bucket: self.bucket.clone(),
+                            key: key.to_string(),
+                            source: Box::new(e),
+                        })?;
+
+                    self.client
+                        .upload_part()
+                        .bucket(&self.bucket)
+                        .key(key)
+                        .upload_id(upload_id)
+                        .part_number(part_number)
+                        .body(body)
+                        .send()
+                        .await
+                        .map_err(|e| R2Error::Upload {
+                            bucket: self.bucket.clone(),
+                            key: key.to_string(),
+                            source: Box::new(e),
+                        })
+                })
+                .await?;
 
             let etag = upload_part.e_tag().unwrap_or_default().to_string();
             parts.push(
@@ -852,7 +1763,7 @@ impl R2Client {
                     .build(),
             );
 
-            uploaded += chunk_size as u64;
+            uploaded += chunk_size;
             #[allow(clippy::cast_precision_loss)] // display-only values
             let pct = (uploaded as f64 / file_size as f64) * 100.0;
             #[allow(clippy::cast_precision_loss)] // display-only MB value
@@ -872,21 +1783,26 @@ impl R2Client {
     ///
     /// # Errors
     ///
-    /// Returns [`R2Error::Delete`] on S3 failures.
+    /// Returns [`R2Error::Delete`] on S3 failures after all retries are
+    /// exhausted.
     pub async fn delete(&self, key: &str) -> Result<(), R2Error> {
         log::info!("Deleting s3://{}/{key}", self.bucket);
 
-        self.client
-            .delete_object()
-            .bucket(&self.bucket)
-            .key(key)
-            .send()
-            .await
-            .map_err(|e| R2Error::Delete {
-                bucket: self.bucket.clone(),
-                key: key.to_string(),
-                source: Box::new(e),
-            })?;
+        self.retry
+            .run("delete", || async move {
+                self.client
+                    .delete_object()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .send()
+                    .await
+                    .map_err(|e| R2Error::Delete {
+                        bucket: self.bucket.clone(),
+                        key: key.to_string(),
+                        source: Box::new(e),
+                    })
+            })
+            .await?;
 
         Ok(())
     }
@@ -897,33 +1813,56 @@ impl R2Client {
     ///
     /// # Errors
     ///
-    /// Returns [`R2Error::List`] on S3 failures.
+    /// Returns [`R2Error::List`] on S3 failures after all retries are
+    /// exhausted.
     pub async fn list_keys(&self, prefix: &str) -> Result<Vec<String>, R2Error> {
+        Ok(self
+            .list_objects(prefix)
+            .await?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect())
+    }
+
+    /// Lists all objects under a prefix as `(key, last_modified)` pairs.
+    async fn list_objects(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<(String, Option<SystemTime>)>, R2Error> {
         log::info!("Listing s3://{}/{prefix}*", self.bucket);
 
         let mut keys = Vec::new();
         let mut continuation_token: Option<String> = None;
 
         loop {
-            let mut request = self
-                .client
-                .list_objects_v2()
-                .bucket(&self.bucket)
-                .prefix(prefix);
-
-            if let Some(token) = &continuation_token {
-                request = request.continuation_token(token);
-            }
-
-            let output = request.send().await.map_err(|e| R2Error::List {
-                bucket: self.bucket.clone(),
-                prefix: prefix.to_string(),
-                source: Box::new(e),
-            })?;
+            let token = continuation_token.as_deref();
+            let output = self
+                .retry
+                .run("list", || async move {
+                    let mut request = self
+                        .client
+                        .list_objects_v2()
+                        .bucket(&self.bucket)
+                        .prefix(prefix);
+
+                    if let Some(token) = token {
+                        request = request.continuation_token(token);
+                    }
+
+                    request.send().await.map_err(|e| R2Error::List {
+                        bucket: self.bucket.clone(),
+                        prefix: prefix.to_string(),
+                        source: Box::new(e),
+                    })
+                })
+                .await?;
 
             for obj in output.contents() {
                 if let Some(key) = obj.key() {
-                    keys.push(key.to_string());
+                    let modified = obj
+                        .last_modified()
+                        .and_then(|t| SystemTime::try_from(*t).ok());
+                    keys.push((key.to_string(), modified));
                 }
             }
 
@@ -940,8 +1879,14 @@ impl R2Client {
 
     /// Fetch object metadata via `HeadObject`.
     ///
-    /// Returns `None` if the object doesn't exist (`NotFound`).
+    /// Returns `None` if the object doesn't exist (`NotFound`). Other
+    /// failures are retried.
     async fn head(&self, key: &str) -> Result<Option<RemoteMeta>, R2Error> {
+        self.retry.run("head", || self.head_once(key)).await
+    }
+
+    /// Single `HeadObject` attempt.
+    async fn head_once(&self, key: &str) -> Result<Option<RemoteMeta>, R2Error> {
         let result = self
             .client
             .head_object()
@@ -956,7 +1901,11 @@ impl R2Client {
                 #[allow(clippy::cast_sign_loss)] // S3 content-length is non-negative
                 let size = size as u64;
                 let etag = output.e_tag().map(str::to_string);
-                Ok(Some(RemoteMeta { size, etag }))
+                let sha256 = output
+                    .metadata()
+                    .and_then(|m| m.get(SHA256_METADATA_KEY))
+                    .map(|v| v.to_ascii_lowercase());
+                Ok(Some(RemoteMeta { size, etag, sha256 }))
             }
             Err(err) => {
                 // NotFound is not an error — it means the object doesn't exist
@@ -980,15 +1929,24 @@ impl R2Client {
 ///
 /// 1. If the local file doesn't exist, returns `false`.
 /// 2. Compares file sizes — if they differ, returns `false` immediately.
-/// 3. If sizes match AND the remote `ETag` looks like a single-part MD5
+/// 3. If `verify_sha256` is set and the remote object carries
+///    `x-amz-meta-sha256`, computes the local SHA-256 and returns whether
+///    it matches. This is independent of how the object was uploaded or
+///    encrypted, so no `ETag` fallback is needed.
+/// 4. If sizes match AND the remote `ETag` looks like a single-part MD5
 ///    hex digest (32 hex chars, no `-`), computes the local file's MD5
 ///    and compares.
-/// 4. If the `ETag` is a multipart `ETag` (format `{hex}-{part_count}`),
-///    recomputes the composite multipart MD5 using
-///    [`MULTIPART_PART_SIZE`] boundaries and compares. This only
-///    produces a match if the file was uploaded with the same part size
-///    we use, which is always the case for files we uploaded.
-async fn is_local_match(local_path: &Path, remote: &RemoteMeta) -> bool {
+/// 5. If the `ETag` is a multipart `ETag` (format `{hex}-{part_count}`),
+///    recomputes the composite multipart MD5 using `part_size`
+///    boundaries and compares. This only produces a match if the file was
+///    uploaded with the same part size; a different part count falls back
+///    to size-only (see the module docs).
+async fn is_local_match(
+    local_path: &Path,
+    remote: &RemoteMeta,
+    verify_sha256: bool,
+    part_size: u64,
+) -> bool {
     let Ok(meta) = tokio::fs::metadata(local_path).await else {
         return false;
     };
@@ -998,6 +1956,12 @@ async fn is_local_match(local_path: &Path, remote: &RemoteMeta) -> bool {
         return false;
     }
 
+    if verify_sha256 && let Some(expected) = &remote.sha256 {
+        return compute_sha256(local_path)
+            .await
+            .is_ok_and(|local| local == *expected);
+    }
+
     // Sizes match — try ETag comparison for stronger guarantee
     if let Some(etag) = &remote.etag {
         let clean = etag.trim_matches('"');
@@ -1007,7 +1971,7 @@ async fn is_local_match(local_path: &Path, remote: &RemoteMeta) -> bool {
             if let Ok(expected_parts) = count_str.parse::<u64>()
                 && hex.len() == 32
                 && let Ok(local_etag) =
-                    compute_multipart_etag(local_path, local_size, expected_parts).await
+                    compute_multipart_etag(local_path, local_size, expected_parts, part_size).await
             {
                 return local_etag == clean;
             }
@@ -1048,10 +2012,36 @@ fn compute_md5_sync(path: &Path) -> Result<String, std::io::Error> {
     Ok(format!("{:x}", context.finalize()))
 }
 
+/// Compute SHA-256 hex digest of a local file.
+async fn compute_sha256(path: &Path) -> Result<String, std::io::Error> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || compute_sha256_sync(&path))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// Synchronous SHA-256 computation (runs in blocking thread).
+fn compute_sha256_sync(path: &Path) -> Result<String, std::io::Error> {
+    use sha2::Digest as _;
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = sha2::Sha256::new();
+    let mut buffer = vec![0u8; 256 * 1024]; // 256 KB chunks
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 /// Compute the composite `ETag` that S3/R2 produces for multipart uploads.
 ///
 /// The algorithm:
-/// 1. Split the file into parts at [`MULTIPART_PART_SIZE`] boundaries.
+/// 1. Split the file into parts at `part_size` boundaries.
 /// 2. Compute the MD5 digest of each part (raw 16 bytes).
 /// 3. Concatenate all raw part digests.
 /// 4. Compute the MD5 of the concatenation.
@@ -1064,23 +2054,27 @@ async fn compute_multipart_etag(
     path: &Path,
     file_size: u64,
     expected_parts: u64,
+    part_size: u64,
 ) -> Result<String, std::io::Error> {
     // Verify the part count is consistent with our part size
-    let our_parts = file_size.div_ceil(MULTIPART_PART_SIZE);
-    if our_parts != expected_parts {
+    if file_size.div_ceil(part_size) != expected_parts {
         return Err(std::io::Error::other(
             "part count mismatch — file was uploaded with a different part size",
         ));
     }
 
     let path = path.to_path_buf();
-    tokio::task::spawn_blocking(move || compute_multipart_etag_sync(&path, file_size))
+    tokio::task::spawn_blocking(move || compute_multipart_etag_sync(&path, file_size, part_size))
         .await
         .map_err(std::io::Error::other)?
 }
 
 /// Synchronous multipart `ETag` computation (runs in blocking thread).
-fn compute_multipart_etag_sync(path: &Path, file_size: u64) -> Result<String, std::io::Error> {
+fn compute_multipart_etag_sync(
+    path: &Path,
+    file_size: u64,
+    part_size: u64,
+) -> Result<String, std::io::Error> {
     use std::io::Read;
 
     let mut file = std::fs::File::open(path)?;
@@ -1089,11 +2083,11 @@ fn compute_multipart_etag_sync(path: &Path, file_size: u64) -> Result<String, st
 
     while remaining > 0 {
         #[allow(clippy::cast_possible_truncation)]
-        let part_size = remaining.min(MULTIPART_PART_SIZE) as usize;
+        let this_part = remaining.min(part_size) as usize;
 
         // Compute MD5 for this part by reading in 256 KB chunks
         let mut context = md5::Context::new();
-        let mut part_remaining = part_size;
+        let mut part_remaining = this_part;
         let mut buffer = vec![0u8; 256 * 1024];
 
         while part_remaining > 0 {
@@ -1111,7 +2105,7 @@ fn compute_multipart_etag_sync(path: &Path, file_size: u64) -> Result<String, st
 
         let digest: [u8; 16] = *context.finalize();
         part_digests.push(digest);
-        remaining -= part_size as u64;
+        remaining -= this_part as u64;
     }
 
     // Concatenate all raw part digests and compute MD5 of the concatenation
@@ -1124,6 +2118,154 @@ fn compute_multipart_etag_sync(path: &Path, file_size: u64) -> Result<String, st
     Ok(format!("{final_hash}-{}", part_digests.len()))
 }
 
+/// Returns the file name of a geocode cache delta key. Delta names sort
+/// chronologically.
+fn delta_name(key: &str) -> &str {
+    key.rsplit('/').next().unwrap_or(key)
+}
+
+/// Rejects directories tagged with [`paths::PREVIEW_MARKER_FILE`], i.e.
+/// outputs generated from a `--sample` that must not reach production.
+fn ensure_not_preview(dir: &Path) -> Result<(), R2Error> {
+    if dir.join(paths::PREVIEW_MARKER_FILE).exists() {
+        return Err(R2Error::PreviewOutputs {
+            dir: dir.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the [`OPTIONAL_GENERATED_FILES`] that exist in `dir`.
+fn present_optional_files(dir: &Path) -> impl Iterator<Item = &'static &'static str> + '_ {
+    OPTIONAL_GENERATED_FILES
+        .iter()
+        .filter(move |file| dir.join(file).exists())
+}
+
+/// Reads the generator build ID (`build.id`) from `dir`'s
+/// `metadata.json`, or `None` if it is missing or predates build tagging.
+fn generated_build_id(dir: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(dir.join(paths::METADATA_FILE)).ok()?;
+    let metadata: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    metadata["build"]["id"].as_str().map(str::to_string)
+}
+
+/// One field that differs between two generation manifests (see
+/// [`diff_manifests`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestDrift {
+    /// Dotted path of the field, e.g. `outputs.incidents_pmtiles` for a
+    /// per-output entry or `source_fingerprints` for a whole field.
+    pub field: String,
+    /// The local value as compact JSON, or `None` if absent locally.
+    pub local: Option<String>,
+    /// The remote value as compact JSON, or `None` if absent remotely.
+    pub remote: Option<String>,
+}
+
+impl ManifestDrift {
+    fn new(
+        field: String,
+        local: Option<&serde_json::Value>,
+        remote: Option<&serde_json::Value>,
+    ) -> Self {
+        Self {
+            field,
+            local: local.map(ToString::to_string),
+            remote: remote.map(ToString::to_string),
+        }
+    }
+}
+
+impl std::fmt::Display for ManifestDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |value: Option<&str>| match value {
+            Some(value) if value.chars().count() > 80 => {
+                format!("{}...", value.chars().take(77).collect::<String>())
+            }
+            Some(value) => value.to_string(),
+            None => "(absent)".to_string(),
+        };
+        write!(
+            f,
+            "{}: local {}, remote {}",
+            self.field,
+            show(self.local.as_deref()),
+            show(self.remote.as_deref())
+        )
+    }
+}
+
+/// Compares a local and a remote generation manifest (`manifest.json`),
+/// returning every top-level field that differs, in field order. Fields
+/// holding an object keyed by output name (`outputs`, `builds`, ...) are
+/// compared entry by entry, so a single regenerated output is reported as
+/// such. Returns nothing when the manifests match.
+#[must_use]
+pub fn diff_manifests(local: &serde_json::Value, remote: &serde_json::Value) -> Vec<ManifestDrift> {
+    let mut drift = Vec::new();
+    for (field, local, remote) in zip_fields(local, remote) {
+        match (local, remote) {
+            (Some(l), Some(r)) if l == r => {}
+            (Some(l), Some(r)) if l.is_object() && r.is_object() => {
+                for (entry, l, r) in zip_fields(l, r) {
+                    if l != r {
+                        drift.push(ManifestDrift::new(format!("{field}.{entry}"), l, r));
+                    }
+                }
+            }
+            (l, r) => drift.push(ManifestDrift::new(field.to_string(), l, r)),
+        }
+    }
+    drift
+}
+
+/// Returns every field name of `local` and `remote` with its value in
+/// each, in name order. Values that aren't objects have no fields.
+fn zip_fields<'a>(
+    local: &'a serde_json::Value,
+    remote: &'a serde_json::Value,
+) -> Vec<(
+    &'a str,
+    Option<&'a serde_json::Value>,
+    Option<&'a serde_json::Value>,
+)> {
+    let names: std::collections::BTreeSet<&str> = [local, remote]
+        .into_iter()
+        .filter_map(serde_json::Value::as_object)
+        .flat_map(|fields| fields.keys().map(String::as_str))
+        .collect();
+    names
+        .into_iter()
+        .map(|name| (name, local.get(name), remote.get(name)))
+        .collect()
+}
+
+/// Filters `keys` down to the objects backing `source_id`: the unsharded
+/// `sources/{source_id}.duckdb` first, then its
+/// `sources/{source_id}.{shard}.duckdb` shards sorted by key, mirroring
+/// [`source_db::shard_paths`].
+fn source_object_keys(source_id: &str, keys: &[String]) -> Vec<String> {
+    let unsharded = format!("sources/{source_id}.duckdb");
+    let shard_prefix = format!("sources/{source_id}.");
+    let mut shards: Vec<String> = keys
+        .iter()
+        .filter(|key| {
+            key.strip_prefix(&shard_prefix)
+                .and_then(|rest| rest.strip_suffix(".duckdb"))
+                .is_some_and(|shard| !shard.is_empty() && !shard.contains(['.', '/']))
+        })
+        .cloned()
+        .collect();
+    shards.sort();
+
+    keys.contains(&unsharded)
+        .then_some(unsharded)
+        .into_iter()
+        .chain(shards)
+        .collect()
+}
+
 /// Resolves source IDs: if `source_ids` is empty, returns all known source
 /// IDs from the compile-time TOML registry.
 fn resolve_source_ids(source_ids: &[String]) -> Vec<String> {
@@ -1144,6 +2286,35 @@ fn require_env(name: &str) -> Result<String, R2Error> {
     })
 }
 
+/// Reads an optional environment variable holding a size in megabytes and
+/// returns it in bytes, rejecting values outside `min..=max` bytes.
+fn optional_env_mb(name: &str, min: u64, max: u64) -> Result<Option<u64>, R2Error> {
+    let Ok(value) = std::env::var(name) else {
+        return Ok(None);
+    };
+
+    let invalid = |reason: String| R2Error::InvalidEnv {
+        name: name.to_string(),
+        value: value.clone(),
+        reason,
+    };
+    let mb: u64 = value
+        .trim()
+        .parse()
+        .map_err(|e: std::num::ParseIntError| invalid(e.to_string()))?;
+    let bytes = mb
+        .checked_mul(1024 * 1024)
+        .filter(|b| (min..=max).contains(b))
+        .ok_or_else(|| {
+            invalid(format!(
+                "must be between {} and {} MB",
+                min.div_ceil(1024 * 1024),
+                max / (1024 * 1024)
+            ))
+        })?;
+    Ok(Some(bytes))
+}
+
 /// Returns the list of generated file names per partition/merged output.
 #[must_use]
 pub const fn generated_files() -> &'static [&'static str] {
@@ -1173,3 +2344,206 @@ pub const fn generated_boundaries_prefix() -> &'static str {
 pub const fn generated_merged_prefix() -> &'static str {
     "generated/merged/"
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipart_etag_depends_on_part_size() {
+        let dir = std::env::temp_dir().join(format!("crime_map_r2_etag_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("object.bin");
+        let data: Vec<u8> = (0..10_000u32)
+            .map(|i| u8::try_from(i % 251).unwrap())
+            .collect();
+        std::fs::write(&path, &data).unwrap();
+        let size = data.len() as u64;
+
+        let etag = compute_multipart_etag_sync(&path, size, 4_000).unwrap();
+        assert!(etag.ends_with("-3"));
+        assert_eq!(
+            compute_multipart_etag_sync(&path, size, 4_000).unwrap(),
+            etag
+        );
+
+        // Same part count, different boundaries: digest differs, so the
+        // file is re-uploaded rather than wrongly skipped.
+        let other = compute_multipart_etag_sync(&path, size, 4_500).unwrap();
+        assert!(other.ends_with("-3"));
+        assert_ne!(other, etag);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn part_count_mismatch_falls_back_to_size_only() {
+        let dir = std::env::temp_dir().join(format!("crime_map_r2_match_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("object.bin");
+        std::fs::write(&path, vec![7u8; 10_000]).unwrap();
+
+        // Uploaded in 5 parts; we'd split it into 3.
+        let remote = RemoteMeta {
+            size: 10_000,
+            etag: Some(format!("\"{}-5\"", "0".repeat(32))),
+            sha256: None,
+        };
+        assert!(
+            compute_multipart_etag(&path, 10_000, 5, 4_000)
+                .await
+                .is_err()
+        );
+        assert!(is_local_match(&path, &remote, false, 4_000).await);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn manifest_diff_reports_changed_outputs_and_fields() {
+        let local = serde_json::json!({
+            "version": 3,
+            "limit": null,
+            "outputs": {"incidents_pmtiles": "2026-01-02T00:00:00Z", "count_db": "2026-01-01T00:00:00Z"},
+            "builds": {"count_db": "0.1.0+abc"},
+        });
+        let remote = serde_json::json!({
+            "version": 3,
+            "limit": 1000,
+            "outputs": {"incidents_pmtiles": "2026-01-01T00:00:00Z", "count_db": "2026-01-01T00:00:00Z"},
+            "builds": {"count_db": "0.1.0+abc", "h3_db": "0.1.0+abc"},
+        });
+
+        let drift = diff_manifests(&local, &remote);
+        let fields: Vec<&str> = drift.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["builds.h3_db", "limit", "outputs.incidents_pmtiles"]
+        );
+        assert_eq!(drift[0].local, None);
+        assert_eq!(
+            drift[1].to_string(),
+            "limit: local null, remote 1000".to_string()
+        );
+        assert!(diff_manifests(&local, &local).is_empty());
+    }
+
+    #[test]
+    fn source_object_keys_include_shards_of_only_that_source() {
+        let keys: Vec<String> = [
+            "sources/chicago_pd.2024.duckdb",
+            "sources/chicago_pd.duckdb",
+            "sources/chicago_pd.2023.duckdb",
+            "sources/chicago_pd_arrests.duckdb",
+            "sources/chicago_pd.2023.duckdb.wal",
+            "sources/chicago_pd.a.b.duckdb",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        assert_eq!(
+            source_object_keys("chicago_pd", &keys),
+            [
+                "sources/chicago_pd.duckdb",
+                "sources/chicago_pd.2023.duckdb",
+                "sources/chicago_pd.2024.duckdb",
+            ]
+        );
+        assert_eq!(
+            source_object_keys("chicago_pd_arrests", &keys),
+            ["sources/chicago_pd_arrests.duckdb"]
+        );
+        assert!(source_object_keys("denver_pd", &keys).is_empty());
+    }
+
+    #[test]
+    fn planned_stats_are_labelled_as_a_plan() {
+        let mut stats = SyncStats {
+            transferred: 1,
+            transferred_bytes: 1_048_576,
+            ..SyncStats::default()
+        };
+        assert_eq!(
+            stats.to_string(),
+            "1 transferred (1.0 MB), 0 skipped (unchanged), 0 not found"
+        );
+
+        stats.merge(SyncStats {
+            skipped: 2,
+            planned: true,
+            ..SyncStats::default()
+        });
+        assert_eq!(
+            stats.to_string(),
+            "PLAN ONLY, nothing transferred: 1 would transfer (1.0 MB), \
+             2 would skip (unchanged), 0 not found"
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_and_trips_breaker() {
+        fn blip() -> R2Error {
+            R2Error::Head {
+                bucket: "bucket".to_string(),
+                key: "key".to_string(),
+                source: "connection reset".into(),
+            }
+        }
+
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::ZERO,
+            breaker: CircuitBreaker::new(4, Duration::from_secs(60)),
+        };
+        let calls = std::cell::Cell::new(0u32);
+        let always_fail = || {
+            calls.set(calls.get() + 1);
+            async { Err::<(), _>(blip()) }
+        };
+
+        // A blip followed by a success is retried, and the success resets
+        // the failure count.
+        let value = policy
+            .run("head", || {
+                calls.set(calls.get() + 1);
+                let ok = calls.get() == 2;
+                async move { if ok { Ok(7) } else { Err(blip()) } }
+            })
+            .await
+            .unwrap();
+        assert_eq!((value, calls.replace(0)), (7, 2));
+
+        // Non-transient errors aren't retried.
+        let err = policy
+            .run("head", || {
+                calls.set(calls.get() + 1);
+                async {
+                    Err::<(), _>(R2Error::MissingEnv {
+                        name: "X".to_string(),
+                    })
+                }
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, R2Error::MissingEnv { .. }));
+        assert_eq!(calls.replace(0), 1);
+
+        // Three failed attempts, then the fourth consecutive failure opens
+        // the breaker without using the rest of that request's attempts.
+        assert!(policy.run("head", always_fail).await.is_err());
+        assert_eq!(calls.replace(0), 3);
+        assert!(matches!(
+            policy.run("head", always_fail).await,
+            Err(R2Error::Head { .. })
+        ));
+        assert_eq!(calls.replace(0), 1);
+
+        // While open, requests fail fast without being sent.
+        assert!(matches!(
+            policy.run("head", always_fail).await,
+            Err(R2Error::CircuitOpen { failures: 4, .. })
+        ));
+        assert_eq!(calls.get(), 0);
+    }
+}
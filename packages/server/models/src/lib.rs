@@ -432,6 +432,24 @@ pub struct ApiSource {
     pub state: String,
     /// Human-readable portal URL for the dataset.
     pub portal_url: Option<String>,
+    /// License and required attribution, if the source is in the registry.
+    #[serde(default)]
+    pub license: Option<ApiSourceLicense>,
+}
+
+/// Licensing terms for an [`ApiSource`], from its source definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSourceLicense {
+    /// License type identifier (e.g., `"cc_zero"`, `"open_data"`).
+    #[serde(rename = "type")]
+    pub license_type: String,
+    /// URL to the terms of service or license page.
+    pub tos_url: Option<String>,
+    /// Whether clients must display [`Self::attribution_text`].
+    pub attribution_required: bool,
+    /// Verbatim credit line to display.
+    pub attribution_text: Option<String>,
 }
 
 /// Query parameters for the source-counts endpoint.
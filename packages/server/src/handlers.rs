@@ -1232,15 +1232,11 @@ fn build_count_conditions(
     bind_values: &mut Vec<DuckValue>,
 ) {
     if let Some(b) = bbox {
-        // Convert bbox to cell coordinates: floor(coord * 1000)
-        #[allow(clippy::cast_possible_truncation)]
-        let cell_west = (b.west * 1000.0).floor() as i32;
-        #[allow(clippy::cast_possible_truncation)]
-        let cell_east = (b.east * 1000.0).floor() as i32;
-        #[allow(clippy::cast_possible_truncation)]
-        let cell_south = (b.south * 1000.0).floor() as i32;
-        #[allow(clippy::cast_possible_truncation)]
-        let cell_north = (b.north * 1000.0).floor() as i32;
+        let ((cell_west, cell_east), (cell_south, cell_north)) =
+            crime_map_database::counts::bbox_to_cell_range(
+                [b.west, b.south, b.east, b.north],
+                crime_map_database::counts::DEFAULT_CELL_PRECISION,
+            );
 
         conditions.push("cell_lng >= ? AND cell_lng <= ?".to_string());
         bind_values.push(DuckValue::Int(cell_west));
@@ -1596,7 +1592,9 @@ fn execute_boundary_counts(
 /// `GET /api/boundaries/search`
 ///
 /// Searches boundary names in the pre-generated `boundaries.db` `SQLite`
-/// database. Supports filtering by boundary type and limiting results.
+/// database with [`crime_map_database::boundaries_db::search`], so names
+/// starting with the query rank above names containing it. Supports
+/// filtering by boundary type and limiting results.
 pub async fn boundary_search(
     state: web::Data<AppState>,
     params: web::Query<BoundarySearchParams>,
@@ -1607,49 +1605,27 @@ pub async fn boundary_search(
         }));
     };
 
-    let q = params.q.trim().to_string();
-    if q.is_empty() {
-        return HttpResponse::Ok().json(Vec::<BoundarySearchResult>::new());
-    }
-
     let limit = params.limit.unwrap_or(20).min(100);
-    let boundary_type = params.boundary_type.clone();
-
-    let mut conditions: Vec<String> = vec!["name LIKE '%' || $1 || '%'".to_string()];
-    let mut bind_params: Vec<DatabaseValue> = vec![DatabaseValue::String(q)];
-    let mut param_idx: usize = 2;
-
-    if let Some(ref bt) = boundary_type {
-        conditions.push(format!("type = ${param_idx}"));
-        bind_params.push(DatabaseValue::String(bt.clone()));
-        param_idx += 1;
-    }
-
-    let where_clause = conditions.join(" AND ");
-    let query = format!(
-        "SELECT type, geoid, name, full_name, state_abbr, population
-         FROM boundaries
-         WHERE {where_clause}
-         ORDER BY name
-         LIMIT ${param_idx}"
-    );
-    bind_params.push(DatabaseValue::UInt32(limit));
+    let types: Vec<&str> = params.boundary_type.as_deref().into_iter().collect();
 
-    match boundaries_db.query_raw_params(&query, &bind_params).await {
-        Ok(rows) => {
-            let results: Vec<BoundarySearchResult> = rows
-                .iter()
-                .map(|row| {
-                    let pop_i32: Option<i32> = row.to_value("population").unwrap_or(None);
-                    let pop_i64: Option<i64> = pop_i32.map(i64::from);
-                    BoundarySearchResult {
-                        boundary_type: row.to_value("type").unwrap_or_default(),
-                        geoid: row.to_value("geoid").unwrap_or_default(),
-                        name: row.to_value("name").unwrap_or_default(),
-                        full_name: row.to_value("full_name").unwrap_or(None),
-                        state_abbr: row.to_value("state_abbr").unwrap_or(None),
-                        population: pop_i64,
-                    }
+    match crime_map_database::boundaries_db::search(
+        boundaries_db.as_ref(),
+        &params.q,
+        &types,
+        limit,
+    )
+    .await
+    {
+        Ok(matches) => {
+            let results: Vec<BoundarySearchResult> = matches
+                .into_iter()
+                .map(|m| BoundarySearchResult {
+                    boundary_type: m.boundary_type,
+                    geoid: m.geoid,
+                    name: m.name,
+                    full_name: m.full_name,
+                    state_abbr: m.state_abbr,
+                    population: m.population,
                 })
                 .collect();
             HttpResponse::Ok().json(results)
@@ -36,6 +36,7 @@ pub mod interactive;
 use actix_cors::Cors;
 use actix_files::Files;
 use actix_web::{App, HttpServer, middleware, web};
+use crime_map_database::paths;
 use crime_map_server_models::ApiSource;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -122,10 +123,10 @@ pub struct AppState {
 /// Required data files that must all be present before the server can
 /// serve map data.
 const REQUIRED_DATA_FILES: &[&str] = &[
-    "incidents.db",
-    "counts.duckdb",
-    "h3.duckdb",
-    "analytics.duckdb",
+    paths::INCIDENTS_DB_FILE,
+    paths::COUNTS_DB_FILE,
+    paths::H3_DB_FILE,
+    paths::ANALYTICS_DB_FILE,
 ];
 
 /// Interval between file existence checks when data files are missing.
@@ -139,12 +140,12 @@ const DATA_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5
 /// Returns an error if any database file cannot be opened.
 fn init_data_state(dir: &Path) -> Result<DataState, Box<dyn std::error::Error>> {
     log::info!("Opening sidebar SQLite database...");
-    let sidebar_path = dir.join("incidents.db");
+    let sidebar_path = dir.join(paths::INCIDENTS_DB_FILE);
     let sidebar_db = init_sqlite_rusqlite(Some(&sidebar_path))
         .map_err(|e| format!("Failed to open sidebar SQLite: {e}"))?;
 
     log::info!("Opening DuckDB count database...");
-    let count_path = dir.join("counts.duckdb");
+    let count_path = dir.join(paths::COUNTS_DB_FILE);
     let count_db = duckdb::Connection::open_with_flags(
         &count_path,
         duckdb::Config::default()
@@ -154,12 +155,12 @@ fn init_data_state(dir: &Path) -> Result<DataState, Box<dyn std::error::Error>>
     .map_err(|e| format!("Failed to open DuckDB count database: {e}"))?;
 
     log::info!("Opening H3 hexbin DuckDB connection pool...");
-    let h3_path = dir.join("h3.duckdb");
+    let h3_path = dir.join(paths::H3_DB_FILE);
     let h3_pool =
         DuckDbPool::new(&h3_path, 4).map_err(|e| format!("Failed to open H3 DuckDB pool: {e}"))?;
 
     log::info!("Opening analytics DuckDB database...");
-    let analytics_path = dir.join("analytics.duckdb");
+    let analytics_path = dir.join(paths::ANALYTICS_DB_FILE);
     let analytics_db = duckdb::Connection::open_with_flags(
         &analytics_path,
         duckdb::Config::default()
@@ -233,10 +234,11 @@ struct LoadedMetadata {
 /// - `cities`: array of `[city, state]` pairs
 /// - `minDate` / `maxDate`: dataset date range
 /// - `sources`: array of source metadata objects
+/// - `build`: version and git commit of the generator that wrote it
 ///
 /// Falls back to empty defaults if the file is missing or malformed.
 fn load_metadata(dir: &Path) -> LoadedMetadata {
-    let path = dir.join("metadata.json");
+    let path = dir.join(paths::METADATA_FILE);
     let Ok(contents) = std::fs::read_to_string(&path) else {
         log::warn!(
             "No metadata.json found at {}; AI context will be empty",
@@ -381,7 +383,7 @@ pub async fn run_server() -> std::io::Result<()> {
             .await
             .expect("Failed to open conversations database");
 
-    let boundaries_db_path = data_dir.join("boundaries.db");
+    let boundaries_db_path = data_dir.join(paths::BOUNDARIES_DB_FILE);
     let boundaries_db: Option<Arc<dyn Database>> = if boundaries_db_path.exists() {
         match init_sqlite_rusqlite(Some(&boundaries_db_path)) {
             Ok(db) => {
@@ -26,6 +26,8 @@ pub mod parsing;
 pub mod pdf_extract;
 pub mod press_release;
 pub mod progress;
+pub mod projection;
+pub mod reconcile;
 pub mod registry;
 pub mod retry;
 pub mod socrata;
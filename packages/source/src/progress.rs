@@ -3,9 +3,11 @@
 //! Defines a [`ProgressCallback`] trait that decouples progress reporting
 //! from any specific rendering backend (e.g., `indicatif` progress bars,
 //! log-only reporting, or silence). Implementations are provided upstream
-//! in crates that choose a rendering strategy.
+//! in crates that choose a rendering strategy; [`ThrottledProgress`] can
+//! wrap any of them to limit how often they are updated.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Trait for reporting progress from long-running operations.
 ///
@@ -29,6 +31,14 @@ pub trait ProgressCallback: Send + Sync {
 
     /// Mark progress as complete and remove the progress indicator.
     fn finish_and_clear(&self);
+
+    /// Create a nested progress indicator labelled `label` beneath this one.
+    ///
+    /// Used for hierarchical progress (e.g. one child per output under a
+    /// parent tracking overall outputs). The parent keeps its own state;
+    /// callers should `finish_and_clear()` the child when the sub-task is
+    /// done.
+    fn child(&self, label: &str) -> Arc<dyn ProgressCallback>;
 }
 
 /// A no-op implementation of [`ProgressCallback`] that silently ignores
@@ -45,6 +55,9 @@ impl ProgressCallback for NullProgress {
     fn set_message(&self, _msg: String) {}
     fn finish(&self, _msg: String) {}
     fn finish_and_clear(&self) {}
+    fn child(&self, _label: &str) -> Arc<dyn ProgressCallback> {
+        null_progress()
+    }
 }
 
 /// Returns a shared [`NullProgress`] instance for convenient use.
@@ -52,3 +65,175 @@ impl ProgressCallback for NullProgress {
 pub fn null_progress() -> Arc<dyn ProgressCallback> {
     Arc::new(NullProgress)
 }
+
+/// A [`ProgressCallback`] adapter that coalesces `inc`/`set_position`
+/// updates and forwards them to the inner callback at most once per
+/// `interval`.
+///
+/// Hot loops can then report progress per batch without paying for a
+/// redraw each time. `set_total`, `set_message`, and the `finish` calls
+/// are always forwarded, after flushing any pending update, so the inner
+/// callback ends at the exact final position. Children are throttled with
+/// the same interval.
+pub struct ThrottledProgress {
+    inner: Arc<dyn ProgressCallback>,
+    interval: Duration,
+    pending: Mutex<PendingProgress>,
+}
+
+/// Updates received by a [`ThrottledProgress`] since its last flush.
+#[derive(Default)]
+struct PendingProgress {
+    /// Absolute position from the latest `set_position`, if any.
+    position: Option<u64>,
+    /// Sum of `inc` deltas received after `position`.
+    delta: u64,
+    /// When updates were last forwarded, or `None` if never.
+    flushed_at: Option<Instant>,
+}
+
+impl ThrottledProgress {
+    /// Wraps `inner` so position updates reach it at most once per
+    /// `interval`.
+    #[must_use]
+    pub fn wrap(inner: Arc<dyn ProgressCallback>, interval: Duration) -> Arc<dyn ProgressCallback> {
+        Arc::new(Self {
+            inner,
+            interval,
+            pending: Mutex::new(PendingProgress::default()),
+        })
+    }
+
+    /// Applies `update` to the pending state, then forwards it if the
+    /// interval has elapsed since the last flush (or `force` is set).
+    /// Only a flush that forwards something restarts the interval, so the
+    /// first update after an empty flush still goes through.
+    fn update(&self, force: bool, update: impl FnOnce(&mut PendingProgress)) {
+        let (position, delta) = {
+            let mut pending = self.pending.lock().unwrap();
+            update(&mut pending);
+            let due = pending
+                .flushed_at
+                .is_none_or(|at| at.elapsed() >= self.interval);
+            let has_update = pending.position.is_some() || pending.delta > 0;
+            if !has_update || (!force && !due) {
+                return;
+            }
+            pending.flushed_at = Some(Instant::now());
+            (pending.position.take(), std::mem::take(&mut pending.delta))
+        };
+
+        // Forward outside the lock so a slow renderer doesn't block
+        // other threads reporting progress.
+        if let Some(pos) = position {
+            self.inner.set_position(pos);
+        }
+        if delta > 0 {
+            self.inner.inc(delta);
+        }
+    }
+
+    /// Forwards any pending update immediately.
+    fn flush(&self) {
+        self.update(true, |_| {});
+    }
+}
+
+impl ProgressCallback for ThrottledProgress {
+    fn set_total(&self, total: u64) {
+        self.flush();
+        self.inner.set_total(total);
+    }
+
+    fn set_position(&self, pos: u64) {
+        self.update(false, |pending| {
+            pending.position = Some(pos);
+            pending.delta = 0;
+        });
+    }
+
+    fn inc(&self, delta: u64) {
+        self.update(false, |pending| pending.delta += delta);
+    }
+
+    fn set_message(&self, msg: String) {
+        self.flush();
+        self.inner.set_message(msg);
+    }
+
+    fn finish(&self, msg: String) {
+        self.flush();
+        self.inner.finish(msg);
+    }
+
+    fn finish_and_clear(&self) {
+        self.flush();
+        self.inner.finish_and_clear();
+    }
+
+    fn child(&self, label: &str) -> Arc<dyn ProgressCallback> {
+        Self::wrap(self.inner.child(label), self.interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records every call as a string.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl ProgressCallback for Recorder {
+        fn set_total(&self, total: u64) {
+            self.0.lock().unwrap().push(format!("total {total}"));
+        }
+        fn set_position(&self, pos: u64) {
+            self.0.lock().unwrap().push(format!("pos {pos}"));
+        }
+        fn inc(&self, delta: u64) {
+            self.0.lock().unwrap().push(format!("inc {delta}"));
+        }
+        fn set_message(&self, msg: String) {
+            self.0.lock().unwrap().push(format!("msg {msg}"));
+        }
+        fn finish(&self, msg: String) {
+            self.0.lock().unwrap().push(format!("finish {msg}"));
+        }
+        fn finish_and_clear(&self) {
+            self.0.lock().unwrap().push("clear".to_string());
+        }
+        fn child(&self, _label: &str) -> Arc<dyn ProgressCallback> {
+            null_progress()
+        }
+    }
+
+    #[test]
+    fn throttled_progress_coalesces_updates_until_flushed() {
+        let recorder = Arc::new(Recorder::default());
+        let progress = ThrottledProgress::wrap(recorder.clone(), Duration::from_secs(3600));
+
+        progress.set_total(100);
+        // The first update goes through; the rest wait for the interval.
+        progress.inc(1);
+        progress.inc(2);
+        progress.inc(3);
+        progress.set_message("halfway".to_string());
+        progress.set_position(50);
+        progress.inc(10);
+        progress.finish("done".to_string());
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "total 100",
+                "inc 1",
+                "inc 5",
+                "msg halfway",
+                "pos 50",
+                "inc 10",
+                "finish done",
+            ]
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! Reprojection of source coordinates to WGS84.
+//!
+//! Some sources publish coordinates in a projected CRS (Web Mercator or a
+//! US State Plane zone) rather than WGS84 decimal degrees. Sources declare
+//! their CRS via [`SourceDefinition::source_crs`](crate::source_def::SourceDefinition::source_crs)
+//! as an EPSG code, and [`to_wgs84`] converts each point during
+//! normalization.
+//!
+//! The transforms are pure Rust and cover only the projections used by
+//! configured sources. State Plane zones use the NAD83 datum, which is
+//! treated as identical to WGS84 (they differ by ~1-2 m, well below
+//! incident location precision).
+
+/// GRS80 semi-major axis in meters.
+const GRS80_A: f64 = 6_378_137.0;
+
+/// GRS80 inverse flattening.
+const GRS80_INV_F: f64 = 298.257_222_101;
+
+/// Web Mercator sphere radius in meters.
+const WEB_MERCATOR_R: f64 = 6_378_137.0;
+
+/// Meters per US survey foot.
+const US_SURVEY_FOOT: f64 = 1200.0 / 3937.0;
+
+/// A supported coordinate reference system.
+#[derive(Debug, Clone, Copy)]
+enum Projection {
+    /// WGS84 geographic (`x` = longitude, `y` = latitude).
+    Geographic,
+    /// Spherical Web Mercator in meters.
+    WebMercator,
+    /// Lambert Conformal Conic (2 standard parallels) on GRS80.
+    Lcc(LccParams),
+    /// Transverse Mercator on GRS80.
+    Tm(TmParams),
+}
+
+/// Lambert Conformal Conic 2SP parameters. Angles in degrees, false
+/// easting/northing in meters, `unit` in meters per input unit.
+#[derive(Debug, Clone, Copy)]
+struct LccParams {
+    lat1: f64,
+    lat2: f64,
+    lat0: f64,
+    lon0: f64,
+    false_easting: f64,
+    false_northing: f64,
+    unit: f64,
+}
+
+/// Transverse Mercator parameters. Angles in degrees, false
+/// easting/northing in meters, `unit` in meters per input unit.
+#[derive(Debug, Clone, Copy)]
+struct TmParams {
+    lat0: f64,
+    lon0: f64,
+    scale: f64,
+    false_easting: f64,
+    false_northing: f64,
+    unit: f64,
+}
+
+/// Degrees + minutes to decimal degrees.
+const fn dm(degrees: f64, minutes: f64) -> f64 {
+    degrees + minutes / 60.0
+}
+
+/// Returns the projection for an EPSG code, or `None` if unsupported.
+fn projection(epsg: u32) -> Option<Projection> {
+    let lcc = |lat1, lat2, lat0, lon0, false_easting, false_northing| {
+        Projection::Lcc(LccParams {
+            lat1,
+            lat2,
+            lat0,
+            lon0,
+            false_easting,
+            false_northing,
+            unit: US_SURVEY_FOOT,
+        })
+    };
+    let tm = |lat0, lon0, scale, false_easting| {
+        Projection::Tm(TmParams {
+            lat0,
+            lon0,
+            scale,
+            false_easting,
+            false_northing: 0.0,
+            unit: US_SURVEY_FOOT,
+        })
+    };
+
+    Some(match epsg {
+        4326 => Projection::Geographic,
+        3857 | 900_913 | 102_100 => Projection::WebMercator,
+        // NAD83 / California zone 3 (ftUS)
+        2227 => lcc(
+            dm(38.0, 26.0),
+            dm(37.0, 4.0),
+            36.5,
+            -120.5,
+            2_000_000.0,
+            500_000.0,
+        ),
+        // NAD83 / California zone 5 (ftUS)
+        2229 => lcc(
+            dm(35.0, 28.0),
+            dm(34.0, 2.0),
+            33.5,
+            -118.0,
+            2_000_000.0,
+            500_000.0,
+        ),
+        // NAD83 / Maryland (ftUS)
+        2248 => lcc(39.45, 38.3, dm(37.0, 40.0), -77.0, 400_000.0, 0.0),
+        // NAD83 / New York Long Island (ftUS)
+        2263 => lcc(
+            dm(41.0, 2.0),
+            dm(40.0, 40.0),
+            dm(40.0, 10.0),
+            -74.0,
+            300_000.0,
+            0.0,
+        ),
+        // NAD83 / Pennsylvania South (ftUS)
+        2272 => lcc(
+            dm(40.0, 58.0),
+            dm(39.0, 56.0),
+            dm(39.0, 20.0),
+            -77.75,
+            600_000.0,
+            0.0,
+        ),
+        // NAD83 / Texas North Central (ftUS)
+        2276 => lcc(
+            dm(33.0, 58.0),
+            dm(32.0, 8.0),
+            dm(31.0, 40.0),
+            -98.5,
+            600_000.0,
+            2_000_000.0,
+        ),
+        // NAD83 / Texas South Central (ftUS)
+        2278 => lcc(
+            dm(30.0, 17.0),
+            dm(28.0, 23.0),
+            dm(27.0, 50.0),
+            -99.0,
+            600_000.0,
+            4_000_000.0,
+        ),
+        // NAD83 / Virginia North (ftUS)
+        2283 => lcc(
+            39.2,
+            dm(38.0, 2.0),
+            dm(37.0, 40.0),
+            -78.5,
+            3_500_000.0,
+            2_000_000.0,
+        ),
+        // NAD83 / New Mexico Central (ftUS)
+        2903 => tm(31.0, -106.25, 0.9999, 500_000.0),
+        // NAD83 / Illinois East (ftUS)
+        3435 => tm(dm(36.0, 40.0), -dm(88.0, 20.0), 0.999_975, 300_000.0),
+        _ => return None,
+    })
+}
+
+/// Returns `true` if [`to_wgs84`] can reproject from this EPSG code.
+#[must_use]
+pub fn is_supported(epsg: u32) -> bool {
+    projection(epsg).is_some()
+}
+
+/// Reprojects a point from `epsg` to WGS84.
+///
+/// `x` is the easting (or longitude) and `y` the northing (or latitude) in
+/// the source CRS's units. Returns `(latitude, longitude)` in decimal
+/// degrees, or `None` if the EPSG code is unsupported or the input isn't
+/// finite.
+#[must_use]
+pub fn to_wgs84(epsg: u32, x: f64, y: f64) -> Option<(f64, f64)> {
+    if !x.is_finite() || !y.is_finite() {
+        return None;
+    }
+
+    let (lat, lng) = match projection(epsg)? {
+        Projection::Geographic => (y, x),
+        Projection::WebMercator => web_mercator_inverse(x, y),
+        Projection::Lcc(p) => lcc_inverse(&p, x, y),
+        Projection::Tm(p) => tm_inverse(&p, x, y),
+    };
+
+    (lat.is_finite() && lng.is_finite()).then_some((lat, lng))
+}
+
+fn web_mercator_inverse(x: f64, y: f64) -> (f64, f64) {
+    let lng = (x / WEB_MERCATOR_R).to_degrees();
+    let lat = 2.0f64
+        .mul_add(
+            (y / WEB_MERCATOR_R).exp().atan(),
+            -std::f64::consts::FRAC_PI_2,
+        )
+        .to_degrees();
+    (lat, lng)
+}
+
+/// GRS80 first eccentricity squared.
+fn grs80_e2() -> f64 {
+    let f = 1.0 / GRS80_INV_F;
+    f.mul_add(-f, 2.0 * f)
+}
+
+/// Inverse Lambert Conformal Conic (Snyder, _Map Projections: A Working
+/// Manual_, eqs. 15-9 to 15-11 and 7-9).
+#[allow(clippy::many_single_char_names)]
+fn lcc_inverse(p: &LccParams, x: f64, y: f64) -> (f64, f64) {
+    let e2 = grs80_e2();
+    let e = e2.sqrt();
+    let m = |phi: f64| phi.cos() / e2.mul_add(-phi.sin().powi(2), 1.0).sqrt();
+    let t = |phi: f64| {
+        let es = e * phi.sin();
+        (std::f64::consts::FRAC_PI_4 - phi / 2.0).tan() / ((1.0 - es) / (1.0 + es)).powf(e / 2.0)
+    };
+
+    let (phi1, phi2, phi0) = (
+        p.lat1.to_radians(),
+        p.lat2.to_radians(),
+        p.lat0.to_radians(),
+    );
+    let n = (m(phi1).ln() - m(phi2).ln()) / (t(phi1).ln() - t(phi2).ln());
+    let big_f = m(phi1) / (n * t(phi1).powf(n));
+    let rho0 = GRS80_A * big_f * t(phi0).powf(n);
+
+    let dx = x.mul_add(p.unit, -p.false_easting);
+    let dy = rho0 - y.mul_add(p.unit, -p.false_northing);
+    let rho = n.signum() * dx.hypot(dy);
+    let t_prime = (rho / (GRS80_A * big_f)).powf(1.0 / n);
+    let theta = if n < 0.0 {
+        (-dx).atan2(-dy)
+    } else {
+        dx.atan2(dy)
+    };
+
+    // Iterate for latitude; converges to sub-millimeter in a few steps.
+    let mut phi = 2.0f64.mul_add(-t_prime.atan(), std::f64::consts::FRAC_PI_2);
+    for _ in 0..10 {
+        let es = e * phi.sin();
+        let next = 2.0f64.mul_add(
+            -(t_prime * ((1.0 - es) / (1.0 + es)).powf(e / 2.0)).atan(),
+            std::f64::consts::FRAC_PI_2,
+        );
+        let done = (next - phi).abs() < 1e-12;
+        phi = next;
+        if done {
+            break;
+        }
+    }
+
+    (phi.to_degrees(), (theta / n).to_degrees() + p.lon0)
+}
+
+/// Meridional arc length from the equator to `phi` (Snyder eq. 3-21).
+#[allow(clippy::suboptimal_flops)]
+fn meridian_arc(e2: f64, phi: f64) -> f64 {
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    GRS80_A
+        * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+            - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+            + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+            - (35.0 * e6 / 3072.0) * (6.0 * phi).sin())
+}
+
+/// Inverse Transverse Mercator (Snyder eqs. 8-18 to 8-25).
+#[allow(clippy::suboptimal_flops)]
+fn tm_inverse(p: &TmParams, x: f64, y: f64) -> (f64, f64) {
+    let e2 = grs80_e2();
+    let e4 = e2 * e2;
+    let e6 = e4 * e2;
+    let ep2 = e2 / (1.0 - e2);
+
+    let dx = x.mul_add(p.unit, -p.false_easting);
+    let dy = y.mul_add(p.unit, -p.false_northing);
+
+    let arc = meridian_arc(e2, p.lat0.to_radians()) + dy / p.scale;
+    let mu = arc / (GRS80_A * (1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0));
+    let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+
+    let phi1 = mu
+        + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+        + (21.0 * e1.powi(2) / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+        + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+        + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+    let sin1 = phi1.sin();
+    let cos1 = phi1.cos();
+    let tan1 = phi1.tan();
+    let c1 = ep2 * cos1 * cos1;
+    let t1 = tan1 * tan1;
+    let n1 = GRS80_A / (1.0 - e2 * sin1 * sin1).sqrt();
+    let r1 = GRS80_A * (1.0 - e2) / (1.0 - e2 * sin1 * sin1).powf(1.5);
+    let d = dx / (n1 * p.scale);
+
+    let phi = phi1
+        - (n1 * tan1 / r1)
+            * (d.powi(2) / 2.0
+                - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1 - 252.0 * ep2 - 3.0 * c1 * c1)
+                    * d.powi(6)
+                    / 720.0);
+    let lambda = (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+        + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1) * d.powi(5)
+            / 120.0)
+        / cos1;
+
+    (phi.to_degrees(), lambda.to_degrees() + p.lon0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Max allowed error in degrees (~1 cm).
+    const TOLERANCE: f64 = 1e-7;
+
+    fn assert_reprojects(epsg: u32, x: f64, y: f64, expected_lat: f64, expected_lng: f64) {
+        let (lat, lng) = to_wgs84(epsg, x, y).unwrap();
+        assert!(
+            (lat - expected_lat).abs() < TOLERANCE && (lng - expected_lng).abs() < TOLERANCE,
+            "EPSG:{epsg} ({x}, {y}) -> ({lat}, {lng}), expected ({expected_lat}, {expected_lng})"
+        );
+    }
+
+    #[test]
+    fn geographic_is_identity() {
+        assert_reprojects(4326, -77.0353, 38.8895, 38.8895, -77.0353);
+    }
+
+    #[test]
+    fn web_mercator() {
+        assert_reprojects(3857, -8_575_530.369, 4_705_855.733, 38.8895, -77.0353);
+        assert_reprojects(102_100, -8_575_530.369, 4_705_855.733, 38.8895, -77.0353);
+    }
+
+    #[test]
+    fn california_zone_3() {
+        assert_reprojects(2227, 6_007_018.849, 2_111_910.385, 37.7793, -122.4193);
+    }
+
+    #[test]
+    fn california_zone_5() {
+        assert_reprojects(2229, 6_488_120.942, 1_842_013.469, 34.0537, -118.2428);
+    }
+
+    #[test]
+    fn maryland() {
+        assert_reprojects(2248, 1_422_489.748, 591_660.428, 39.2906, -76.6108);
+    }
+
+    #[test]
+    fn new_york_long_island() {
+        assert_reprojects(2263, 988_212.237, 211_939.279, 40.7484, -73.9857);
+    }
+
+    #[test]
+    fn pennsylvania_south() {
+        assert_reprojects(2272, 2_693_536.617, 236_208.869, 39.9526, -75.1635);
+    }
+
+    #[test]
+    fn texas_north_central() {
+        assert_reprojects(2276, 2_491_875.041, 6_969_751.329, 32.7767, -96.7970);
+    }
+
+    #[test]
+    fn texas_south_central() {
+        assert_reprojects(2278, 3_120_099.088, 13_841_900.858, 29.7604, -95.3698);
+    }
+
+    #[test]
+    fn virginia_north() {
+        assert_reprojects(2283, 11_883_998.369, 7_007_189.935, 38.8816, -77.0910);
+    }
+
+    #[test]
+    fn new_mexico_central() {
+        assert_reprojects(2903, 1_520_631.269, 1_486_250.497, 35.0844, -106.6504);
+    }
+
+    #[test]
+    fn illinois_east() {
+        assert_reprojects(3435, 1_175_844.397, 1_898_954.877, 41.8781, -87.6298);
+    }
+
+    #[test]
+    fn false_origin_maps_to_projection_origin() {
+        // Maryland: (400000 m, 0) is the false origin at 37°40'N, 77°W.
+        assert_reprojects(2248, 400_000.0 / US_SURVEY_FOOT, 0.0, dm(37.0, 40.0), -77.0);
+    }
+
+    #[test]
+    fn unsupported_or_non_finite_is_none() {
+        assert!(!is_supported(2000));
+        assert!(to_wgs84(2000, 1.0, 1.0).is_none());
+        assert!(to_wgs84(2248, f64::NAN, 1.0).is_none());
+    }
+}
@@ -0,0 +1,274 @@
+//! Reconciles a source selection against the registry, the
+//! [`SOURCES_ENV`] filter, and the sources with local databases.
+//!
+//! Ingest and generate both turn a `--sources` list (or none) into the
+//! sources to work on. [`reconcile_sources`] classifies every requested
+//! source once, so both report the same problem the same way:
+//!
+//! | Status | In registry | Enabled | Local DB |
+//! |--------|-------------|---------|----------|
+//! | [`SourceStatus::Ready`] | yes | yes | yes |
+//! | [`SourceStatus::NotSynced`] | yes | yes | no |
+//! | [`SourceStatus::Disabled`] | yes | no | either |
+//! | [`SourceStatus::Unknown`] | no | either | either |
+//!
+//! A source is enabled when it was requested explicitly, or when nothing
+//! was and [`SOURCES_ENV`] is unset or lists it, so an explicit list
+//! overrides the environment.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::registry::all_sources;
+use crate::source_def::SourceDefinition;
+
+/// Environment variable with the comma-separated source IDs enabled when
+/// none are requested explicitly.
+pub const SOURCES_ENV: &str = "CRIME_MAP_SOURCES";
+
+/// Classification of one source by [`reconcile_sources`] (see the
+/// [module docs](self)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SourceStatus {
+    /// Registered, enabled, and synced.
+    Ready,
+    /// Registered and enabled, but without a local database yet.
+    NotSynced,
+    /// Registered, but left out by [`SOURCES_ENV`].
+    Disabled,
+    /// Not in the registry: a typo, or the database of a removed source.
+    Unknown,
+}
+
+/// What [`reconcile_sources`] found for one source.
+#[derive(Debug, Clone)]
+pub struct SourceCheck {
+    /// Source ID.
+    pub id: String,
+    /// Index of the registry entry (see
+    /// [`SourceReconciliation::definition`]), or `None` if the ID isn't
+    /// registered.
+    pub registry_index: Option<usize>,
+    /// Whether the source is enabled (see the [module docs](self)).
+    pub enabled: bool,
+    /// Whether the source has a local database.
+    pub has_file: bool,
+    /// Whether the source was requested explicitly.
+    pub requested: bool,
+}
+
+impl SourceCheck {
+    /// Returns the source's classification.
+    #[must_use]
+    pub const fn status(&self) -> SourceStatus {
+        match (self.registry_index, self.enabled, self.has_file) {
+            (None, _, _) => SourceStatus::Unknown,
+            (Some(_), false, _) => SourceStatus::Disabled,
+            (Some(_), true, false) => SourceStatus::NotSynced,
+            (Some(_), true, true) => SourceStatus::Ready,
+        }
+    }
+
+    /// Describes what's wrong with the source, or `None` if it's ready.
+    #[must_use]
+    pub fn problem(&self) -> Option<String> {
+        let id = &self.id;
+        match self.status() {
+            SourceStatus::Ready => None,
+            SourceStatus::NotSynced => Some(format!(
+                "source '{id}' has no local DuckDB file; run `cargo ingest sync --sources {id}`"
+            )),
+            SourceStatus::Disabled => Some(format!("source '{id}' is left out by {SOURCES_ENV}")),
+            SourceStatus::Unknown if self.has_file => Some(format!(
+                "source '{id}' has a local DuckDB file but isn't in the registry"
+            )),
+            SourceStatus::Unknown => Some(format!("unknown source ID '{id}'")),
+        }
+    }
+}
+
+/// Every source considered by [`reconcile_sources`], by ID.
+#[derive(Debug, Default)]
+pub struct SourceReconciliation {
+    /// One check per source, in ID order.
+    pub sources: Vec<SourceCheck>,
+    /// The registry the checks' `registry_index` points into.
+    registry: Vec<SourceDefinition>,
+}
+
+impl SourceReconciliation {
+    /// Returns the registry entry of `check`, or `None` if it isn't
+    /// registered.
+    #[must_use]
+    pub fn definition(&self, check: &SourceCheck) -> Option<&SourceDefinition> {
+        check.registry_index.and_then(|i| self.registry.get(i))
+    }
+
+    /// Returns the sources with `status`, in ID order.
+    pub fn with_status(&self, status: SourceStatus) -> impl Iterator<Item = &SourceCheck> {
+        self.sources.iter().filter(move |s| s.status() == status)
+    }
+
+    /// Returns the registry entries of the enabled sources, synced or
+    /// not, in ID order.
+    #[must_use]
+    pub fn into_enabled_definitions(self) -> Vec<SourceDefinition> {
+        let mut registry: Vec<Option<SourceDefinition>> =
+            self.registry.into_iter().map(Some).collect();
+        self.sources
+            .iter()
+            .filter(|s| s.enabled)
+            .filter_map(|s| s.registry_index.and_then(|i| registry[i].take()))
+            .collect()
+    }
+
+    /// Returns the IDs of the [`SourceStatus::Ready`] sources.
+    #[must_use]
+    pub fn ready_ids(&self) -> Vec<String> {
+        self.with_status(SourceStatus::Ready)
+            .map(|s| s.id.clone())
+            .collect()
+    }
+
+    /// Returns the problem of every source in `statuses` (see
+    /// [`SourceCheck::problem`]), in ID order.
+    #[must_use]
+    pub fn problems(&self, statuses: &[SourceStatus]) -> Vec<String> {
+        self.sources
+            .iter()
+            .filter(|s| statuses.contains(&s.status()))
+            .filter_map(SourceCheck::problem)
+            .collect()
+    }
+}
+
+/// Classifies the `requested` sources (see the [module docs](self)).
+/// `synced` holds the IDs of the sources with a local database, e.g.
+/// from `crime_map_database::source_db::discover_source_ids`.
+///
+/// With nothing requested, every registered source and every synced one
+/// is considered, and [`SOURCES_ENV`] decides which are enabled.
+#[must_use]
+pub fn reconcile_sources(requested: &[String], synced: &BTreeSet<String>) -> SourceReconciliation {
+    let env = std::env::var(SOURCES_ENV).ok();
+    reconcile(requested, synced, all_sources(), env.as_deref())
+}
+
+fn reconcile(
+    requested: &[String],
+    synced: &BTreeSet<String>,
+    registry: Vec<SourceDefinition>,
+    env: Option<&str>,
+) -> SourceReconciliation {
+    let indices: BTreeMap<&str, usize> = registry
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.id(), i))
+        .collect();
+    let explicit: BTreeSet<&str> = requested
+        .iter()
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty())
+        .collect();
+    let env_ids: Option<BTreeSet<&str>> = env.map(|ids| {
+        ids.split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .collect()
+    });
+
+    let candidates: BTreeSet<&str> = if explicit.is_empty() {
+        indices
+            .keys()
+            .copied()
+            .chain(synced.iter().map(String::as_str))
+            .collect()
+    } else {
+        explicit.clone()
+    };
+
+    let sources = candidates
+        .into_iter()
+        .map(|id| SourceCheck {
+            id: id.to_string(),
+            registry_index: indices.get(id).copied(),
+            enabled: !explicit.is_empty() || env_ids.as_ref().is_none_or(|ids| ids.contains(id)),
+            has_file: synced.contains(id),
+            requested: explicit.contains(id),
+        })
+        .collect();
+    drop(indices);
+    SourceReconciliation { sources, registry }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn classifies_each_source_and_lets_explicit_requests_override_the_env() {
+        let registered = all_sources().len();
+        let synced: BTreeSet<String> = ids(&["chicago_pd", "removed_pd"]).into_iter().collect();
+
+        let explicit = reconcile(
+            &ids(&["chicago_pd", "dc_mpd", "typo_pd"]),
+            &synced,
+            all_sources(),
+            Some("dc_mpd"),
+        );
+        let statuses: Vec<(&str, SourceStatus)> = explicit
+            .sources
+            .iter()
+            .map(|s| (s.id.as_str(), s.status()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("chicago_pd", SourceStatus::Ready),
+                ("dc_mpd", SourceStatus::NotSynced),
+                ("typo_pd", SourceStatus::Unknown),
+            ]
+        );
+        assert_eq!(explicit.ready_ids(), ids(&["chicago_pd"]));
+        assert_eq!(
+            explicit.problems(&[SourceStatus::Unknown]),
+            vec!["unknown source ID 'typo_pd'".to_string()]
+        );
+
+        let from_env = reconcile(&[], &synced, all_sources(), Some("dc_mpd"));
+        assert_eq!(from_env.sources.len(), registered + 1);
+        assert_eq!(
+            from_env
+                .definition(&from_env.sources[0])
+                .map(SourceDefinition::id),
+            Some(from_env.sources[0].id.as_str())
+        );
+        assert_eq!(
+            from_env
+                .with_status(SourceStatus::Disabled)
+                .find(|s| s.id == "chicago_pd")
+                .map(|s| s.has_file),
+            Some(true)
+        );
+        assert_eq!(
+            from_env.problems(&[SourceStatus::Unknown]),
+            vec![
+                "source 'removed_pd' has a local DuckDB file but isn't in the registry".to_string()
+            ]
+        );
+        assert_eq!(
+            from_env
+                .into_enabled_definitions()
+                .iter()
+                .map(SourceDefinition::id)
+                .collect::<Vec<_>>(),
+            vec!["dc_mpd"]
+        );
+
+        let everything = reconcile(&[], &synced, all_sources(), None);
+        assert_eq!(everything.into_enabled_definitions().len(), registered);
+    }
+}
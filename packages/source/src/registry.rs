@@ -252,4 +252,30 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn source_crs_codes_are_supported() {
+        for source in &all_sources() {
+            if let Some(epsg) = source.source_crs {
+                assert!(
+                    crate::projection::is_supported(epsg),
+                    "{}: unsupported source_crs EPSG:{epsg}",
+                    source.id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn timezones_are_valid_iana_names() {
+        for source in &all_sources() {
+            if let Some(tz) = &source.timezone {
+                assert!(
+                    source.timezone().is_some(),
+                    "{}: invalid timezone {tz:?}",
+                    source.id
+                );
+            }
+        }
+    }
 }
@@ -62,11 +62,227 @@ pub struct SourceDefinition {
     /// coordinates. Defaults to `false`.
     #[serde(default)]
     pub re_geocode: bool,
+    /// Which coordinate enrichment and export use for incidents that have
+    /// both a source-provided and a geocoded one. Both are kept either way.
+    /// Defaults to the geocoded coordinate.
+    #[serde(default)]
+    pub coordinate_precedence: CoordinatePrecedence,
+    /// Incidents of this source left out of every generated output, on
+    /// top of the shared coordinate-validity filter (e.g. a "redacted
+    /// location" sentinel address). Validated when the TOML is parsed.
+    #[serde(default)]
+    pub export_exclude: Vec<ExportExclusion>,
     /// Optional URL to the human-readable data portal page for this source.
     /// If not set, one may be derived from the fetcher config (e.g., Socrata
     /// dataset pages from the API URL).
     #[serde(default)]
     pub portal_url: Option<String>,
+    /// EPSG code of the coordinate reference system the `lat`/`lng` fields
+    /// are published in (e.g. `2248` for Maryland State Plane, `3857` for
+    /// Web Mercator). Coordinates are reprojected to WGS84 during
+    /// normalization. Defaults to WGS84 when unset. See
+    /// [`crate::projection`] for supported codes.
+    #[serde(default)]
+    pub source_crs: Option<u32>,
+    /// IANA timezone the source's incidents happen in (e.g.
+    /// `"America/Chicago"`). Set it for sources that publish absolute
+    /// instants (e.g. `ArcGIS` epoch-ms dates): generation converts
+    /// `occurred_at` to this zone before bucketing days. Sources that
+    /// publish floating local times are already stored as local wall-clock
+    /// and should leave it unset.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Display priority among sources that cover the same area (e.g. a
+    /// city police department inside a county sheriff's jurisdiction).
+    /// With `--dedup-sources`, generation drops incidents that duplicate
+    /// an incident of a higher-priority source. Defaults to `0`.
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Which of an incident's coordinates is used when it has both a
+/// source-provided and a geocoded one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinatePrecedence {
+    /// Use the geocoded coordinate when there is one.
+    #[default]
+    Geocoded,
+    /// Use the source-provided coordinate when there is one, falling back
+    /// to the geocoded one.
+    Source,
+}
+
+impl CoordinatePrecedence {
+    /// Returns the value stored in the incidents' `coord_source` column
+    /// for coordinates of the preferred kind.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Geocoded => "geocoded",
+            Self::Source => "source",
+        }
+    }
+}
+
+// ── Export exclusions ────────────────────────────────────────────────────
+
+/// A column of the source `incidents` table that an [`ExportExclusion`]
+/// can test. Only these identifiers ever reach the generated SQL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFilterColumn {
+    /// Normalized subcategory.
+    Category,
+    /// Normalized top-level category.
+    ParentCategory,
+    /// Severity (1–5).
+    Severity,
+    /// Longitude.
+    Longitude,
+    /// Latitude.
+    Latitude,
+    /// Free-text description.
+    Description,
+    /// Block-level address.
+    BlockAddress,
+    /// City name.
+    City,
+    /// State abbreviation.
+    State,
+    /// Whether an arrest was made.
+    ArrestMade,
+    /// Whether the incident was domestic.
+    Domestic,
+    /// Location type (e.g. `"STREET"`).
+    LocationType,
+}
+
+impl ExportFilterColumn {
+    /// Returns the column name.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Category => "category",
+            Self::ParentCategory => "parent_category",
+            Self::Severity => "severity",
+            Self::Longitude => "longitude",
+            Self::Latitude => "latitude",
+            Self::Description => "description",
+            Self::BlockAddress => "block_address",
+            Self::City => "city",
+            Self::State => "state",
+            Self::ArrestMade => "arrest_made",
+            Self::Domestic => "domestic",
+            Self::LocationType => "location_type",
+        }
+    }
+
+    /// Returns whether `value` has this column's type.
+    const fn accepts(self, value: &ExportFilterValue) -> bool {
+        match self {
+            Self::Severity | Self::Longitude | Self::Latitude => {
+                matches!(value, ExportFilterValue::Number(n) if n.is_finite())
+            }
+            Self::ArrestMade | Self::Domestic => matches!(value, ExportFilterValue::Bool(_)),
+            _ => matches!(value, ExportFilterValue::Text(_)),
+        }
+    }
+}
+
+/// A literal compared against an [`ExportFilterColumn`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ExportFilterValue {
+    /// A boolean, for `arrest_made` and `domestic`.
+    Bool(bool),
+    /// A number, for `severity`, `longitude`, and `latitude`.
+    Number(f64),
+    /// A string, for the text columns.
+    Text(String),
+}
+
+impl ExportFilterValue {
+    /// Returns the value as a SQL literal, with quotes in strings escaped.
+    fn to_sql(&self) -> String {
+        match self {
+            Self::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+            Self::Number(n) => n.to_string(),
+            Self::Text(s) => format!("'{}'", s.replace('\'', "''")),
+        }
+    }
+}
+
+/// Excludes a source's incidents whose `column` equals one of `equals`, or
+/// (with `is_null = true`) is NULL:
+///
+/// ```toml
+/// [[export_exclude]]
+/// column = "block_address"
+/// equals = ["LOCATION REDACTED", "UNKNOWN"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "RawExportExclusion")]
+pub struct ExportExclusion {
+    /// Column to test.
+    pub column: ExportFilterColumn,
+    /// Values to exclude. Empty when `is_null` is set.
+    pub equals: Vec<ExportFilterValue>,
+    /// Exclude incidents where the column is NULL.
+    pub is_null: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawExportExclusion {
+    column: ExportFilterColumn,
+    #[serde(default)]
+    equals: Vec<ExportFilterValue>,
+    #[serde(default)]
+    is_null: bool,
+}
+
+impl TryFrom<RawExportExclusion> for ExportExclusion {
+    type Error = String;
+
+    fn try_from(raw: RawExportExclusion) -> Result<Self, Self::Error> {
+        let column = raw.column.as_str();
+        if raw.is_null != raw.equals.is_empty() {
+            return Err(format!(
+                "export_exclude on {column} needs exactly one of `equals` or `is_null = true`"
+            ));
+        }
+        if let Some(value) = raw.equals.iter().find(|v| !raw.column.accepts(v)) {
+            return Err(format!(
+                "export_exclude value {value:?} doesn't match the type of {column}"
+            ));
+        }
+
+        Ok(Self {
+            column: raw.column,
+            equals: raw.equals,
+            is_null: raw.is_null,
+        })
+    }
+}
+
+impl ExportExclusion {
+    /// Returns the SQL predicate selecting the incidents to *keep*.
+    /// Incidents with a NULL `column` are kept unless `is_null` is set.
+    #[must_use]
+    pub fn keep_predicate(&self) -> String {
+        let column = self.column.as_str();
+        if self.is_null {
+            return format!("{column} IS NOT NULL");
+        }
+        let values = self
+            .equals
+            .iter()
+            .map(ExportFilterValue::to_sql)
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("({column} IS NULL OR {column} NOT IN ({values}))")
+    }
 }
 
 // ── License metadata ─────────────────────────────────────────────────────
@@ -722,6 +938,43 @@ impl SourceDefinition {
         self.re_geocode
     }
 
+    /// Returns which coordinate to use for incidents that have both a
+    /// source-provided and a geocoded one.
+    #[must_use]
+    pub const fn coordinate_precedence(&self) -> CoordinatePrecedence {
+        self.coordinate_precedence
+    }
+
+    /// Returns the SQL predicate keeping only incidents not matched by any
+    /// of this source's `export_exclude` entries, or `None` if it has none.
+    #[must_use]
+    pub fn export_predicate(&self) -> Option<String> {
+        if self.export_exclude.is_empty() {
+            return None;
+        }
+        Some(
+            self.export_exclude
+                .iter()
+                .map(ExportExclusion::keep_predicate)
+                .collect::<Vec<_>>()
+                .join(" AND "),
+        )
+    }
+
+    /// Returns the parsed `timezone` field, or `None` if it is unset or not
+    /// a valid IANA name.
+    #[must_use]
+    pub fn timezone(&self) -> Option<chrono_tz::Tz> {
+        self.timezone.as_deref().and_then(|tz| tz.parse().ok())
+    }
+
+    /// Returns the display priority used to pick which overlapping source
+    /// keeps a duplicated incident (higher wins).
+    #[must_use]
+    pub const fn priority(&self) -> i32 {
+        self.priority
+    }
+
     /// Returns the portal URL for this source.
     ///
     /// If a `portal_url` is explicitly set in the TOML, that value is
@@ -1080,10 +1333,22 @@ impl SourceDefinition {
             let latitude = fields.lat.as_ref().and_then(|f| f.extract(record));
             let longitude = fields.lng.as_ref().and_then(|f| f.extract(record));
 
+            // Reproject from the source CRS (`lng` is the easting, `lat`
+            // the northing). Points that can't be reprojected become
+            // missing and are left for the geocoder.
+            let (latitude, longitude) = match (self.source_crs, latitude, longitude) {
+                (Some(epsg), Some(y), Some(x)) if x != 0.0 && y != 0.0 => {
+                    crate::projection::to_wgs84(epsg, x, y)
+                        .map_or((None, None), |(lat, lng)| (Some(lat), Some(lng)))
+                }
+                (Some(_), _, _) => (None, None),
+                (None, lat, lng) => (lat, lng),
+            };
+
             // Reject zero or out-of-range coordinates (treat as missing).
-            // Some sources return projected coordinates (e.g. State Plane)
-            // instead of WGS84 decimal degrees — filter those out so they
-            // can be geocoded from block addresses instead.
+            // Sources that return projected coordinates (e.g. State Plane)
+            // without a `source_crs` are filtered out here so they can be
+            // geocoded from block addresses instead.
             let (latitude, longitude) = match (latitude, longitude) {
                 (Some(lat), Some(lng))
                     if lat != 0.0
@@ -1163,6 +1428,14 @@ impl SourceDefinition {
 
         incidents
     }
+
+    /// Returns the source incident ID of a raw record, the same one
+    /// [`Self::normalize_page`] gives its incident, or `None` if it has
+    /// none.
+    #[must_use]
+    pub fn incident_id(&self, record: &serde_json::Value) -> Option<String> {
+        extract_incident_id(record, &self.fields.incident_id)
+    }
 }
 
 /// Tries each field name in order and returns the first non-empty string
@@ -1364,5 +1637,92 @@ mod tests {
             !def.re_geocode,
             "sources without re_geocode should default to false"
         );
+        assert_eq!(def.coordinate_precedence, CoordinatePrecedence::Geocoded);
+    }
+
+    #[test]
+    fn parses_and_validates_export_exclusions() {
+        let with = |exclusions: &str| {
+            parse_source_toml(&format!(
+                "{}\n{exclusions}",
+                include_str!("../sources/chicago.toml")
+            ))
+        };
+
+        let def = with(
+            "[[export_exclude]]
+             column = \"block_address\"
+             equals = [\"REDACTED\", \"O'HARE AIRPORT\"]
+
+             [[export_exclude]]
+             column = \"longitude\"
+             equals = [-87, -87.5]
+
+             [[export_exclude]]
+             column = \"location_type\"
+             is_null = true",
+        )
+        .unwrap();
+        assert_eq!(
+            def.export_predicate().unwrap(),
+            "(block_address IS NULL OR block_address NOT IN ('REDACTED', 'O''HARE AIRPORT')) \
+             AND (longitude IS NULL OR longitude NOT IN (-87, -87.5)) \
+             AND location_type IS NOT NULL"
+        );
+
+        assert!(with("").unwrap().export_predicate().is_none());
+        for invalid in [
+            "[[export_exclude]]\ncolumn = \"source_incident_id; DROP TABLE incidents\"\nis_null = true",
+            "[[export_exclude]]\ncolumn = \"severity\"\nequals = [\"1\"]",
+            "[[export_exclude]]\ncolumn = \"city\"",
+            "[[export_exclude]]\ncolumn = \"city\"\nequals = [\"X\"]\nis_null = true",
+            "[[export_exclude]]\ncolumn = \"city\"\nsql = \"1 = 1\"",
+        ] {
+            assert!(with(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn parses_coordinate_precedence() {
+        let toml_str = format!(
+            "coordinate_precedence = \"source\"\n{}",
+            include_str!("../sources/chicago.toml")
+        );
+        let def = parse_source_toml(&toml_str).unwrap();
+        assert_eq!(def.coordinate_precedence(), CoordinatePrecedence::Source);
+    }
+
+    #[test]
+    fn reprojects_source_crs_coordinates() {
+        let toml_str = format!(
+            "source_crs = 2903\n{}",
+            include_str!("../sources/albuquerque.toml")
+        );
+        let def = parse_source_toml(&toml_str).unwrap();
+        assert_eq!(def.source_crs, Some(2903));
+
+        let records = [
+            serde_json::json!({
+                "OBJECTID": 1,
+                "CMLegend": "BURGLARY",
+                "ReportDateTime": 1_704_067_200_000_i64,
+                "_geometry_x": 1_520_631.269,
+                "_geometry_y": 1_486_250.497,
+            }),
+            serde_json::json!({
+                "OBJECTID": 2,
+                "CMLegend": "BURGLARY",
+                "ReportDateTime": 1_704_067_200_000_i64,
+            }),
+        ];
+        let incidents = def.normalize_page(&records);
+
+        let lat = incidents[0].latitude.unwrap();
+        let lng = incidents[0].longitude.unwrap();
+        assert!((lat - 35.0844).abs() < 1e-6, "lat = {lat}");
+        assert!((lng - -106.6504).abs() < 1e-6, "lng = {lng}");
+
+        assert!(incidents[1].latitude.is_none());
+        assert!(incidents[1].longitude.is_none());
     }
 }
@@ -10,6 +10,7 @@
 
 use std::collections::BTreeMap;
 
+use crime_map_geography_models::neighborhood_geoid;
 use geo::{Contains, MultiPolygon};
 use geojson::GeoJson;
 use rstar::{AABB, RTree, RTreeObject};
@@ -121,7 +122,7 @@ impl SpatialIndex {
             let geoid: String = row.get(0)?;
             let nbhd_id: i32 = row.get(1)?;
             if !geoid.is_empty() && nbhd_id > 0 {
-                map.insert(geoid, format!("nbhd-{nbhd_id}"));
+                map.insert(geoid, neighborhood_geoid(nbhd_id));
             }
         }
 